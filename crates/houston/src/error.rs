@@ -26,6 +26,10 @@ pub enum HoustonProblem {
     #[error("There is no profile named \"{0}\".")]
     ProfileNotFound(String),
 
+    /// ProfileAlreadyExists occurs when a profile would be overwritten by copying or renaming another one.
+    #[error("There is already a profile named \"{0}\".")]
+    ProfileAlreadyExists(String),
+
     /// NoProfilesFound occurs when there are no profiles at all, often for new users
     #[error("No configuration profiles were found, and this command requires one.")]
     NoConfigProfiles,
@@ -38,6 +42,20 @@ pub enum HoustonProblem {
     #[error("The API key associated with profile \"{0}\" is corrupt.")]
     CorruptedProfile(String),
 
+    /// KeychainUnavailable occurs when a profile keeps its API key in the OS keychain, but Houston
+    /// was built without the `keychain` feature.
+    #[error("The API key of profile \"{0}\" is kept in the OS keychain, which this build of Rover can't access.")]
+    KeychainUnavailable(String),
+
+    /// KeychainError occurs when the OS keychain can't be read from or written to.
+    #[error("Could not access the OS keychain: {0}.")]
+    KeychainError(String),
+
+    /// SsoRefreshFailed occurs when the short-lived token of a profile authenticated with SSO
+    /// can't be refreshed, usually because its session has expired.
+    #[error("Could not refresh the SSO token of profile \"{0}\": {1}.")]
+    SsoRefreshFailed(String, String),
+
     /// PathNotUtf8 occurs when Houston encounters a file path that is not valid UTF-8
     #[error(transparent)]
     PathNotUtf8(#[from] camino::FromPathBufError),
@@ -11,4 +11,6 @@ pub use error::HoustonProblem;
 
 pub use profile::mask_key;
 /// Utilites for saving, loading, and deleting configuration profiles.
-pub use profile::{Credential, CredentialOrigin, LoadOpts, Profile};
+pub use profile::{
+    Credential, CredentialOrigin, CredentialStore, LoadOpts, Profile, ProfileDefaults, SsoSession,
+};
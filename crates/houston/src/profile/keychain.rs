@@ -0,0 +1,46 @@
+use keyring::Entry;
+
+use crate::{profile::Profile, Config, HoustonProblem};
+
+/// The service the API keys of profiles are filed under in the OS keychain
+const SERVICE: &str = "rover";
+
+/// The keychain entry of a profile. Entries are named after the profile's directory, so profiles
+/// with the same name under different config homes don't share a key.
+fn entry(profile_name: &str, config: &Config) -> Result<Entry, HoustonProblem> {
+    Entry::new(SERVICE, Profile::dir(profile_name, config).as_str())
+        .map_err(|e| HoustonProblem::KeychainError(e.to_string()))
+}
+
+/// Saves the API key of a profile to the OS keychain, replacing any it had.
+pub(crate) fn save(
+    profile_name: &str,
+    config: &Config,
+    api_key: &str,
+) -> Result<(), HoustonProblem> {
+    entry(profile_name, config)?
+        .set_password(api_key)
+        .map_err(|e| HoustonProblem::KeychainError(e.to_string()))?;
+    tracing::debug!(profile_name, "saved API key to the OS keychain");
+    Ok(())
+}
+
+/// Loads the API key of a profile from the OS keychain.
+pub(crate) fn load(profile_name: &str, config: &Config) -> Result<String, HoustonProblem> {
+    entry(profile_name, config)?
+        .get_password()
+        .map_err(|e| match e {
+            keyring::Error::NoEntry => HoustonProblem::KeychainError(format!(
+                "it has no API key for profile \"{profile_name}\""
+            )),
+            e => HoustonProblem::KeychainError(e.to_string()),
+        })
+}
+
+/// Removes the API key of a profile from the OS keychain, if it's there.
+pub(crate) fn delete(profile_name: &str, config: &Config) -> Result<(), HoustonProblem> {
+    match entry(profile_name, config)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(HoustonProblem::KeychainError(e.to_string())),
+    }
+}
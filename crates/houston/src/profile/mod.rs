@@ -1,7 +1,10 @@
+#[cfg(feature = "keychain")]
+mod keychain;
 mod sensitive;
 
 use crate::{Config, HoustonProblem};
 use sensitive::Sensitive;
+pub use sensitive::{CredentialStore, SsoSession};
 use serde::{Deserialize, Serialize};
 
 use camino::Utf8PathBuf as PathBuf;
@@ -24,6 +27,40 @@ pub struct LoadOpts {
 pub struct ProfileData {
     /// Apollo API Key
     pub api_key: Option<String>,
+
+    /// Where to keep the API key
+    pub store: CredentialStore,
+
+    /// How to refresh the API key, when it's a short-lived token from an SSO login
+    pub sso: Option<SsoSession>,
+}
+
+/// Defaults that commands run with a profile use for the options they aren't passed. They're
+/// saved to `$APOLLO_CONFIG_HOME/profiles/<profile_name>/defaults.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileDefaults {
+    /// The graph ref for commands that take one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graph_ref: Option<String>,
+
+    /// The federation version to compose with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub federation_version: Option<String>,
+
+    /// The format to print output in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<String>,
+
+    /// What to log, in the format `--log` takes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log: Option<String>,
+}
+
+impl ProfileDefaults {
+    /// Whether no defaults are set
+    pub fn is_empty(&self) -> bool {
+        self == &ProfileDefaults::default()
+    }
 }
 
 /// Struct containing info about an API Key
@@ -36,6 +73,15 @@ pub struct Credential {
     pub origin: CredentialOrigin,
 }
 
+impl fmt::Debug for Credential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credential")
+            .field("api_key", &mask_key(&self.api_key))
+            .field("origin", &self.origin)
+            .finish()
+    }
+}
+
 /// Info about where the API key was retrieved
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CredentialOrigin {
@@ -55,19 +101,69 @@ impl Profile {
         Profile::base_dir(config).join(name)
     }
 
+    fn defaults_path(name: &str, config: &Config) -> PathBuf {
+        Profile::dir(name, config).join("defaults.toml")
+    }
+
     /// Writes an api_key to the filesystem (`$APOLLO_CONFIG_HOME/profiles/<profile_name>/.sensitive`).
     pub fn set_api_key(name: &str, config: &Config, api_key: &str) -> Result<(), HoustonProblem> {
+        Profile::set_api_key_in(name, config, api_key, CredentialStore::File)
+    }
+
+    /// Writes an api_key to `store`. The profile's `.sensitive` file records where the key is
+    /// kept, so [`Profile::get_credential`] reads it from the right place.
+    pub fn set_api_key_in(
+        name: &str,
+        config: &Config,
+        api_key: &str,
+        store: CredentialStore,
+    ) -> Result<(), HoustonProblem> {
         let data = ProfileData {
             api_key: Some(api_key.to_string()),
+            store,
+            sso: None,
         };
         Profile::save(name, config, data)?;
         Ok(())
     }
 
+    /// Writes a short-lived token from an SSO login to the filesystem as the profile's API key,
+    /// along with the session to refresh it with once it expires.
+    pub fn set_sso_token(
+        name: &str,
+        config: &Config,
+        token: &str,
+        session: SsoSession,
+    ) -> Result<(), HoustonProblem> {
+        let data = ProfileData {
+            api_key: Some(token.to_string()),
+            store: CredentialStore::File,
+            sso: Some(session),
+        };
+        Profile::save(name, config, data)?;
+        Ok(())
+    }
+
+    /// Returns the SSO session of a profile whose API key is a short-lived token, unless an
+    /// `APOLLO_KEY` env var overrides the profile. Profiles that can't be read don't have one, and
+    /// [`Profile::get_credential`] reports why.
+    pub fn get_sso_session(name: &str, config: &Config) -> Option<SsoSession> {
+        if config.override_api_key.is_some() {
+            return None;
+        }
+        Sensitive::read(name, config).ok()?.sso
+    }
+
+    /// Returns where the API key of a profile is kept, without reading it from the OS keychain.
+    pub fn get_store(name: &str, config: &Config) -> Result<CredentialStore, HoustonProblem> {
+        Ok(Sensitive::read(name, config)?.store)
+    }
+
     /// Returns an API key for interacting with Apollo services.
     ///
     /// Checks for the presence of an `APOLLO_KEY` env var, and returns its value
-    /// if it finds it. Otherwise looks for credentials on the file system.
+    /// if it finds it. Otherwise looks for credentials on the file system, or in the
+    /// OS keychain for profiles that keep them there.
     ///
     /// Takes an optional `profile` argument. Defaults to `"default"`.
     pub fn get_credential(name: &str, config: &Config) -> Result<Credential, HoustonProblem> {
@@ -86,7 +182,7 @@ impl Profile {
             }
         };
 
-        tracing::debug!("using API key {}", mask_key(&credential.api_key));
+        tracing::debug!(origin = ?credential.origin, "using API key {}", mask_key(&credential.api_key));
 
         Ok(credential)
     }
@@ -95,7 +191,12 @@ impl Profile {
     /// splitting sensitive information into a separate file.
     pub fn save(name: &str, config: &Config, data: ProfileData) -> Result<(), HoustonProblem> {
         if let Some(api_key) = data.api_key {
-            Sensitive { api_key }.save(name, config)?;
+            Sensitive {
+                api_key,
+                store: data.store,
+                sso: data.sso,
+            }
+            .save(name, config)?;
         }
         Ok(())
     }
@@ -126,10 +227,115 @@ impl Profile {
         }
     }
 
-    /// Deletes profile data from file system.
+    /// Loads the defaults of a profile. A profile that doesn't exist, or doesn't set any defaults,
+    /// has empty ones.
+    pub fn get_defaults(name: &str, config: &Config) -> Result<ProfileDefaults, HoustonProblem> {
+        let path = Profile::defaults_path(name, config);
+        if !path.exists() {
+            return Ok(ProfileDefaults::default());
+        }
+        let data = Fs::read_file(&path)?;
+        tracing::debug!(path = ?path, data_len = ?data.len());
+        Ok(toml::from_str(&data)?)
+    }
+
+    /// Replaces the defaults of an existing profile. Empty defaults remove the profile's
+    /// `defaults.toml`.
+    pub fn set_defaults(
+        name: &str,
+        config: &Config,
+        defaults: &ProfileDefaults,
+    ) -> Result<(), HoustonProblem> {
+        if !Profile::dir(name, config).exists() {
+            return Err(HoustonProblem::ProfileNotFound(name.to_string()));
+        }
+        let path = Profile::defaults_path(name, config);
+        if defaults.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(&path)?;
+            }
+            return Ok(());
+        }
+        let data = toml::to_string(defaults)?;
+        Fs::write_file(&path, &data)?;
+        tracing::debug!(path = ?path, data_len = ?data.len());
+        Ok(())
+    }
+
+    /// Copies a profile, along with its API key and defaults, to a new profile.
+    pub fn copy(from: &str, to: &str, config: &Config) -> Result<(), HoustonProblem> {
+        let (from_dir, to_dir) = Profile::check_move(from, to, config)?;
+        let keychain_key = Profile::keychain_key(from, config)?;
+        Fs::copy_dir_all(from_dir, to_dir)?;
+        if let Some(api_key) = keychain_key {
+            Sensitive {
+                api_key,
+                store: CredentialStore::Keychain,
+                sso: None,
+            }
+            .save(to, config)?;
+        }
+        Ok(())
+    }
+
+    /// Renames a profile, keeping its API key and defaults.
+    pub fn rename(from: &str, to: &str, config: &Config) -> Result<(), HoustonProblem> {
+        let (from_dir, to_dir) = Profile::check_move(from, to, config)?;
+        let keychain_key = Profile::keychain_key(from, config)?;
+        std::fs::rename(from_dir, to_dir)?;
+        if let Some(api_key) = keychain_key {
+            Sensitive {
+                api_key,
+                store: CredentialStore::Keychain,
+                sso: None,
+            }
+            .save(to, config)?;
+            Sensitive::forget_keychain_entry(from, config)?;
+        }
+        Ok(())
+    }
+
+    /// The API key of a profile that keeps it in the OS keychain, which is filed under the
+    /// profile's name and so has to move along with it
+    fn keychain_key(name: &str, config: &Config) -> Result<Option<String>, HoustonProblem> {
+        match Sensitive::read(name, config) {
+            Ok(sensitive) if sensitive.store == CredentialStore::Keychain => {
+                Ok(Some(Sensitive::load(name, config)?.api_key))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// The directories of the profiles to copy or rename between, as long as the first exists and
+    /// the second doesn't
+    fn check_move(
+        from: &str,
+        to: &str,
+        config: &Config,
+    ) -> Result<(PathBuf, PathBuf), HoustonProblem> {
+        let from_dir = Profile::dir(from, config);
+        let to_dir = Profile::dir(to, config);
+        if !from_dir.is_dir() {
+            return Err(HoustonProblem::ProfileNotFound(from.to_string()));
+        }
+        if to_dir.exists() {
+            return Err(HoustonProblem::ProfileAlreadyExists(to.to_string()));
+        }
+        Ok((from_dir, to_dir))
+    }
+
+    /// Deletes profile data from file system, along with its API key if it's kept in the OS
+    /// keychain.
     pub fn delete(name: &str, config: &Config) -> Result<(), HoustonProblem> {
         let dir = Profile::dir(name, config);
         tracing::debug!(dir = ?dir);
+        // a key left in a keychain this build can't access can't be removed, but shouldn't keep
+        // the profile around either
+        if let Err(e) = Sensitive::forget(name, config) {
+            tracing::warn!(
+                "could not remove the API key of profile {name} from the OS keychain: {e}"
+            );
+        }
         Fs::remove_dir_all(dir)?;
         Ok(())
     }
@@ -181,7 +387,161 @@ pub fn mask_key(key: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::mask_key;
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+
+    use super::{mask_key, Profile, ProfileDefaults};
+    use crate::{Config, HoustonProblem};
+
+    fn config(home: &TempDir) -> Config {
+        let home = Utf8PathBuf::try_from(home.path().to_path_buf()).unwrap();
+        Config::new(Some(&home), None).unwrap()
+    }
+
+    #[test]
+    fn it_can_copy_and_rename_profiles() {
+        let home = TempDir::new().unwrap();
+        let config = config(&home);
+        let defaults = ProfileDefaults {
+            graph_ref: Some("my-graph@current".to_string()),
+            ..Default::default()
+        };
+        Profile::set_api_key("work", &config, "user:gh.foo:djru4788dhsg3657fhLOLO").unwrap();
+        Profile::set_defaults("work", &config, &defaults).unwrap();
+
+        Profile::copy("work", "staging", &config).unwrap();
+        Profile::rename("work", "prod", &config).unwrap();
+
+        let mut profiles = Profile::list(&config).unwrap();
+        profiles.sort();
+        assert_eq!(profiles, vec!["prod".to_string(), "staging".to_string()]);
+        for profile in ["prod", "staging"] {
+            assert_eq!(
+                Profile::get_credential(profile, &config).unwrap().api_key,
+                "user:gh.foo:djru4788dhsg3657fhLOLO"
+            );
+            assert_eq!(Profile::get_defaults(profile, &config).unwrap(), defaults);
+        }
+    }
+
+    #[test]
+    fn it_refuses_to_overwrite_profiles() {
+        let home = TempDir::new().unwrap();
+        let config = config(&home);
+        Profile::set_api_key("work", &config, "key").unwrap();
+        Profile::set_api_key("prod", &config, "other key").unwrap();
+
+        assert!(matches!(
+            Profile::rename("work", "prod", &config),
+            Err(HoustonProblem::ProfileAlreadyExists(name)) if name == "prod"
+        ));
+        assert!(matches!(
+            Profile::copy("missing", "new", &config),
+            Err(HoustonProblem::ProfileNotFound(name)) if name == "missing"
+        ));
+        assert_eq!(
+            Profile::get_credential("prod", &config).unwrap().api_key,
+            "other key"
+        );
+    }
+
+    #[test]
+    fn it_clears_defaults() {
+        let home = TempDir::new().unwrap();
+        let config = config(&home);
+        Profile::set_api_key("work", &config, "key").unwrap();
+        let defaults = ProfileDefaults {
+            output_format: Some("json".to_string()),
+            ..Default::default()
+        };
+        Profile::set_defaults("work", &config, &defaults).unwrap();
+        Profile::set_defaults("work", &config, &ProfileDefaults::default()).unwrap();
+
+        assert!(Profile::get_defaults("work", &config).unwrap().is_empty());
+        assert!(matches!(
+            Profile::set_defaults("missing", &config, &defaults),
+            Err(HoustonProblem::ProfileNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn it_keeps_the_sso_session_with_the_token() {
+        use super::SsoSession;
+
+        let home = TempDir::new().unwrap();
+        let config = config(&home);
+        let session = SsoSession {
+            refresh_token: "refresh".to_string(),
+            token_url: "https://sso.example.com/token".to_string(),
+            expires_at: 1_800_000_000,
+        };
+        Profile::set_sso_token("work", &config, "short-lived", session.clone()).unwrap();
+        assert_eq!(
+            Profile::get_credential("work", &config).unwrap().api_key,
+            "short-lived"
+        );
+        assert_eq!(Profile::get_sso_session("work", &config), Some(session));
+
+        // an API key replaces the session, and APOLLO_KEY overrides it
+        Profile::set_api_key("work", &config, "key").unwrap();
+        assert_eq!(Profile::get_sso_session("work", &config), None);
+        assert_eq!(Profile::get_sso_session("missing", &config), None);
+    }
+
+    #[test]
+    fn it_knows_when_sso_tokens_expire() {
+        use std::time::Duration;
+
+        use super::SsoSession;
+
+        let session = SsoSession {
+            refresh_token: "refresh".to_string(),
+            token_url: "https://sso.example.com/token".to_string(),
+            expires_at: 1_000,
+        };
+        assert!(session.expires_within(Duration::ZERO));
+        let session = SsoSession {
+            expires_at: u64::MAX / 2,
+            ..session
+        };
+        assert!(!session.expires_within(Duration::from_secs(60)));
+    }
+
+    #[test]
+    #[cfg(not(feature = "keychain"))]
+    fn it_reports_keychain_profiles_it_cannot_read() {
+        use super::CredentialStore;
+
+        let home = TempDir::new().unwrap();
+        let config = config(&home);
+        assert!(matches!(
+            Profile::set_api_key_in("work", &config, "key", CredentialStore::Keychain),
+            Err(HoustonProblem::KeychainUnavailable(name)) if name == "work"
+        ));
+
+        // a profile saved by a build that can access the keychain
+        let sensitive = Profile::dir("work", &config).join(".sensitive");
+        rover_std::Fs::write_file(&sensitive, "store = \"keychain\"\n").unwrap();
+        assert!(matches!(
+            Profile::get_credential("work", &config),
+            Err(HoustonProblem::KeychainUnavailable(name)) if name == "work"
+        ));
+        Profile::delete("work", &config).unwrap();
+        assert!(Profile::list(&config).unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_masks_keys_in_debug_output() {
+        use super::{Credential, CredentialOrigin};
+
+        let credential = Credential {
+            api_key: "user:gh.foo:djru4788dhsg3657fhLOLO".to_string(),
+            origin: CredentialOrigin::EnvVar,
+        };
+        let debug = format!("{credential:?}");
+        assert!(!debug.contains("djru4788dhsg3657fh"));
+        assert!(debug.contains("user**************************LOLO"));
+    }
 
     #[test]
     fn it_can_mask_user_key() {
@@ -1,15 +1,83 @@
+#[cfg(feature = "keychain")]
+use super::keychain::{delete as keychain_delete, load as keychain_load, save as keychain_save};
 use crate::{profile::Profile, Config, HoustonProblem};
 use rover_std::Fs;
 
-use std::fmt;
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use camino::Utf8PathBuf;
 use serde::{Deserialize, Serialize};
 
+/// Where the API key of a profile is kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialStore {
+    /// The profile's `.sensitive` file
+    #[default]
+    File,
+
+    /// The OS keychain: the macOS Keychain, Windows Credential Manager, or Secret Service.
+    /// Rover needs to be built with the `keychain` feature to use it.
+    Keychain,
+}
+
+impl CredentialStore {
+    fn is_file(&self) -> bool {
+        self == &CredentialStore::File
+    }
+}
+
+/// What's needed to replace the short-lived token of a profile authenticated by `rover config auth
+/// --sso` once it expires. The token itself is kept as the profile's API key.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SsoSession {
+    /// The token to exchange for a new short-lived token
+    pub refresh_token: String,
+
+    /// The endpoint to exchange the refresh token at
+    pub token_url: String,
+
+    /// When the short-lived token expires, in seconds since the Unix epoch
+    pub expires_at: u64,
+}
+
+impl SsoSession {
+    /// Whether the short-lived token has expired, or will within `margin`
+    pub fn expires_within(&self, margin: Duration) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        now + margin >= Duration::from_secs(self.expires_at)
+    }
+}
+
+impl fmt::Debug for SsoSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SsoSession")
+            .field("refresh_token", &super::mask_key(&self.refresh_token))
+            .field("token_url", &self.token_url)
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
 /// Holds sensitive information regarding authentication.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct Sensitive {
+    /// The API key, which isn't written to the `.sensitive` file when it's kept in the keychain
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub api_key: String,
+
+    /// Where the API key is kept
+    #[serde(default, skip_serializing_if = "CredentialStore::is_file")]
+    pub store: CredentialStore,
+
+    /// How to refresh the API key, when it's a short-lived token from an SSO login
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sso: Option<SsoSession>,
 }
 
 impl Sensitive {
@@ -17,10 +85,24 @@ impl Sensitive {
         Profile::dir(profile_name, config).join(".sensitive")
     }
 
-    /// Serializes to toml and saves to file system at `$APOLLO_CONFIG_HOME/<profile_name>/.sensitive`.
+    /// Serializes to toml and saves to file system at `$APOLLO_CONFIG_HOME/<profile_name>/.sensitive`,
+    /// saving the API key to the OS keychain instead when that's where it's kept.
     pub fn save(&self, profile_name: &str, config: &Config) -> Result<(), HoustonProblem> {
         let path = Sensitive::path(profile_name, config);
-        let data = toml::to_string(self)?;
+        let previous_store = Sensitive::read(profile_name, config)
+            .map(|sensitive| sensitive.store)
+            .unwrap_or_default();
+        let data = match self.store {
+            CredentialStore::File => toml::to_string(self)?,
+            CredentialStore::Keychain => {
+                keychain_save(profile_name, config, &self.api_key)?;
+                toml::to_string(&Sensitive {
+                    api_key: String::new(),
+                    store: self.store,
+                    sso: self.sso.clone(),
+                })?
+            }
+        };
 
         if let Some(dirs) = &path.parent() {
             Fs::create_dir_all(dirs)?;
@@ -28,22 +110,86 @@ impl Sensitive {
 
         Fs::write_file(&path, &data)?;
         tracing::debug!(path = ?path, data_len = ?data.len());
+
+        // don't leave a key that's no longer used behind in the keychain
+        if previous_store == CredentialStore::Keychain && self.store == CredentialStore::File {
+            Sensitive::forget(profile_name, config)?;
+        }
         Ok(())
     }
 
-    /// Opens and deserializes `$APOLLO_CONFIG_HOME/<profile_name>/.sensitive`.
+    /// Opens and deserializes `$APOLLO_CONFIG_HOME/<profile_name>/.sensitive`, reading the API key
+    /// from the OS keychain when that's where it's kept.
     pub fn load(profile_name: &str, config: &Config) -> Result<Sensitive, HoustonProblem> {
+        let mut sensitive = Sensitive::read(profile_name, config)?;
+        match sensitive.store {
+            // old versions of rover used to allow profiles to be created
+            // with these contents in certain PowerShell environments
+            CredentialStore::File if sensitive.api_key.as_bytes() == [22] => {
+                Err(HoustonProblem::CorruptedProfile(profile_name.to_string()))
+            }
+            CredentialStore::File => Ok(sensitive),
+            CredentialStore::Keychain => {
+                sensitive.api_key = keychain_load(profile_name, config)?;
+                Ok(sensitive)
+            }
+        }
+    }
+
+    /// Removes the API key of a profile from the OS keychain, if that's where it's kept.
+    pub fn forget(profile_name: &str, config: &Config) -> Result<(), HoustonProblem> {
+        match Sensitive::read(profile_name, config).map(|sensitive| sensitive.store) {
+            Ok(CredentialStore::Keychain) => Sensitive::forget_keychain_entry(profile_name, config),
+            _ => Ok(()),
+        }
+    }
+
+    /// Removes the OS keychain entry a profile's API key would be kept in, for when the profile
+    /// has moved out from under it.
+    pub fn forget_keychain_entry(
+        profile_name: &str,
+        config: &Config,
+    ) -> Result<(), HoustonProblem> {
+        keychain_delete(profile_name, config)
+    }
+
+    /// Deserializes `.sensitive` without reading the API key from the OS keychain.
+    pub fn read(profile_name: &str, config: &Config) -> Result<Sensitive, HoustonProblem> {
         let path = Sensitive::path(profile_name, config);
         let data = Fs::read_file(&path)?;
         tracing::debug!(path = ?path, data_len = ?data.len());
-        let sensitive: Self = toml::from_str(&data)?;
-        // old versions of rover used to allow profiles to be created
-        // with these contents in certain PowerShell environments
-        if sensitive.api_key.as_bytes() == [22] {
-            Err(HoustonProblem::CorruptedProfile(profile_name.to_string()))
-        } else {
-            Ok(sensitive)
-        }
+        Ok(toml::from_str(&data)?)
+    }
+}
+
+#[cfg(not(feature = "keychain"))]
+fn keychain_save(profile_name: &str, _: &Config, _: &str) -> Result<(), HoustonProblem> {
+    Err(HoustonProblem::KeychainUnavailable(
+        profile_name.to_string(),
+    ))
+}
+
+#[cfg(not(feature = "keychain"))]
+fn keychain_load(profile_name: &str, _: &Config) -> Result<String, HoustonProblem> {
+    Err(HoustonProblem::KeychainUnavailable(
+        profile_name.to_string(),
+    ))
+}
+
+#[cfg(not(feature = "keychain"))]
+fn keychain_delete(profile_name: &str, _: &Config) -> Result<(), HoustonProblem> {
+    Err(HoustonProblem::KeychainUnavailable(
+        profile_name.to_string(),
+    ))
+}
+
+impl fmt::Debug for Sensitive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sensitive")
+            .field("api_key", &super::mask_key(&self.api_key))
+            .field("store", &self.store)
+            .field("sso", &self.sso.is_some())
+            .finish()
     }
 }
 
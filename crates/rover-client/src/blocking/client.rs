@@ -5,11 +5,36 @@ use reqwest::{
     header::{HeaderMap, HeaderValue},
     Client as ReqwestClient, Response, StatusCode,
 };
+use sha2::{Digest, Sha256};
 
 use crate::error::{EndpointKind, RoverClientError};
 
 pub(crate) const JSON_CONTENT_TYPE: &str = "application/json";
 
+/// The message GraphQL servers send back when they don't recognize the hash of an
+/// automatic persisted query, which asks the client to send the full query
+const PERSISTED_QUERY_NOT_FOUND: &str = "PersistedQueryNotFound";
+
+/// How a [`GraphQLClient`] sends an operation to a GraphQL server
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GraphQLTransport {
+    /// A POST request with the operation in a JSON body
+    #[default]
+    Post,
+    /// A GET request with the operation encoded in the query string
+    Get,
+    /// An automatic persisted query: a POST request with only the hash of the operation,
+    /// followed by one with the full operation if the server doesn't recognize the hash
+    PersistedQuery,
+}
+
+/// The HTTP request that carries a GraphQL operation
+#[derive(Debug)]
+enum GraphQLRequest {
+    Json(String),
+    QueryString(Vec<(&'static str, String)>),
+}
+
 /// Represents a generic GraphQL client for making http requests.
 pub struct GraphQLClient {
     graphql_endpoint: String,
@@ -45,6 +70,7 @@ impl GraphQLClient {
     where
         Q: GraphQLQuery,
     {
+        let _timing = rover_std::timing::time_phase("network");
         let request_body = self.get_request_body::<Q>(variables)?;
         header_map.append("Content-Type", HeaderValue::from_str(JSON_CONTENT_TYPE)?);
         let response = self
@@ -66,6 +92,7 @@ impl GraphQLClient {
     where
         Q: GraphQLQuery,
     {
+        let _timing = rover_std::timing::time_phase("network");
         let request_body = self.get_request_body::<Q>(variables)?;
         header_map.append("Content-Type", HeaderValue::from_str(JSON_CONTENT_TYPE)?);
         let response = self
@@ -74,17 +101,101 @@ impl GraphQLClient {
         GraphQLClient::handle_response::<Q>(response?, endpoint_kind).await
     }
 
+    /// Client method for making a GraphQL request with the given [`GraphQLTransport`].
+    ///
+    /// Retries requests if `should_retry` is set.
+    pub async fn send<Q>(
+        &self,
+        variables: Q::Variables,
+        header_map: &mut HeaderMap,
+        endpoint_kind: EndpointKind,
+        transport: GraphQLTransport,
+        should_retry: bool,
+    ) -> Result<Q::ResponseData, RoverClientError>
+    where
+        Q: GraphQLQuery,
+    {
+        match transport {
+            GraphQLTransport::Post if should_retry => {
+                self.post::<Q>(variables, header_map, endpoint_kind).await
+            }
+            GraphQLTransport::Post => {
+                self.post_no_retry::<Q>(variables, header_map, endpoint_kind)
+                    .await
+            }
+            GraphQLTransport::Get => {
+                let _timing = rover_std::timing::time_phase("network");
+                let request = self.get_query_string::<Q>(variables)?;
+                header_map.append("Content-Type", HeaderValue::from_str(JSON_CONTENT_TYPE)?);
+                let response = self
+                    .execute(request, header_map, should_retry, endpoint_kind)
+                    .await;
+                GraphQLClient::handle_response::<Q>(response?, endpoint_kind).await
+            }
+            GraphQLTransport::PersistedQuery => {
+                let _timing = rover_std::timing::time_phase("network");
+                header_map.append("Content-Type", HeaderValue::from_str(JSON_CONTENT_TYPE)?);
+                let body = Q::build_query(variables);
+                let mut request = serde_json::to_value(&body)?;
+                request["extensions"] = serde_json::json!({
+                    "persistedQuery": {
+                        "version": 1,
+                        "sha256Hash": format!("{:x}", Sha256::digest(body.query)),
+                    }
+                });
+                let full_request = GraphQLRequest::Json(serde_json::to_string(&request)?);
+                if let Some(request) = request.as_object_mut() {
+                    request.remove("query");
+                }
+                let hashed_request = GraphQLRequest::Json(serde_json::to_string(&request)?);
+
+                let response = self
+                    .execute(hashed_request, header_map, should_retry, endpoint_kind)
+                    .await;
+                match GraphQLClient::handle_response::<Q>(response?, endpoint_kind).await {
+                    Err(RoverClientError::GraphQl { msg }) if msg == PERSISTED_QUERY_NOT_FOUND => {
+                        tracing::debug!("registering persisted query with the server");
+                        let response = self
+                            .execute(full_request, header_map, should_retry, endpoint_kind)
+                            .await;
+                        GraphQLClient::handle_response::<Q>(response?, endpoint_kind).await
+                    }
+                    result => result,
+                }
+            }
+        }
+    }
+
     fn get_request_body<Q: GraphQLQuery>(
         &self,
         variables: Q::Variables,
-    ) -> Result<String, RoverClientError> {
+    ) -> Result<GraphQLRequest, RoverClientError> {
         let body = Q::build_query(variables);
-        Ok(serde_json::to_string(&body)?)
+        Ok(GraphQLRequest::Json(serde_json::to_string(&body)?))
+    }
+
+    fn get_query_string<Q: GraphQLQuery>(
+        &self,
+        variables: Q::Variables,
+    ) -> Result<GraphQLRequest, RoverClientError> {
+        let body = Q::build_query(variables);
+        let mut params = vec![
+            ("query", body.query.to_string()),
+            ("operationName", body.operation_name.to_string()),
+        ];
+        let variables = serde_json::to_value(&body.variables)?;
+        if variables
+            .as_object()
+            .map_or(!variables.is_null(), |v| !v.is_empty())
+        {
+            params.push(("variables", serde_json::to_string(&variables)?));
+        }
+        Ok(GraphQLRequest::QueryString(params))
     }
 
     async fn execute(
         &self,
-        request_body: String,
+        request: GraphQLRequest,
         header_map: &HeaderMap,
         should_retry: bool,
         endpoint_kind: EndpointKind,
@@ -92,15 +203,17 @@ impl GraphQLClient {
         use backoff::{future::retry, Error as BackoffError, ExponentialBackoff};
 
         tracing::trace!(request_headers = ?header_map);
-        tracing::debug!("Request Body: {}", request_body);
+        tracing::debug!("Request: {:?}", request);
         let graphql_operation = || async {
-            let response = self
-                .client
-                .post(&self.graphql_endpoint)
-                .headers(header_map.clone())
-                .body(request_body.clone())
-                .send()
-                .await;
+            let request_builder = match &request {
+                GraphQLRequest::Json(body) => {
+                    self.client.post(&self.graphql_endpoint).body(body.clone())
+                }
+                GraphQLRequest::QueryString(params) => {
+                    self.client.get(&self.graphql_endpoint).query(params)
+                }
+            };
+            let response = request_builder.headers(header_map.clone()).send().await;
 
             match response {
                 Err(client_error) => {
@@ -209,9 +322,10 @@ impl GraphQLClient {
                                 null_field: "data".to_string(),
                             })
                     }
-                    status_code => Err(RoverClientError::ClientError {
-                        msg: status_code.to_string(),
-                    }),
+                    status_code => Err(RoverClientError::from_status(
+                        status_code.as_u16(),
+                        status_code,
+                    )),
                 }
             }
             Err(e) => {
@@ -221,9 +335,10 @@ impl GraphQLClient {
                         endpoint_kind,
                     })
                 } else {
-                    Err(RoverClientError::ClientError {
-                        msg: response_status.to_string(),
-                    })
+                    Err(RoverClientError::from_status(
+                        response_status.as_u16(),
+                        response_status,
+                    ))
                 }
             }
         }
@@ -237,6 +352,8 @@ fn handle_graphql_body_errors(errors: Vec<GraphQLError>) -> Result<(), RoverClie
         tracing::debug!("GraphQL response errors: {:?}", errors);
         if errors[0].message == "406: Not Acceptable" {
             Err(RoverClientError::MalformedKey)
+        } else if let Some(err) = errors.iter().find_map(RoverClientError::from_graphql_error) {
+            Err(err)
         } else {
             Err(RoverClientError::GraphQl {
                 msg: errors
@@ -316,6 +433,59 @@ mod tests {
         assert_eq!(actual_error, expected_error);
     }
 
+    #[test]
+    fn it_returns_typed_errors_from_extension_codes() {
+        let error = |code: &str| GraphQLError {
+            message: "Nope.".to_string(),
+            locations: None,
+            extensions: Some(
+                [("code".to_string(), serde_json::Value::from(code))]
+                    .into_iter()
+                    .collect(),
+            ),
+            path: None,
+        };
+        assert!(matches!(
+            handle_graphql_body_errors(vec![error("UNAUTHENTICATED")]),
+            Err(RoverClientError::InvalidKey)
+        ));
+        assert!(matches!(
+            handle_graphql_body_errors(vec![error("FORBIDDEN")]),
+            Err(RoverClientError::PermissionError { msg }) if msg == "Nope"
+        ));
+        assert!(matches!(
+            handle_graphql_body_errors(vec![error("NOT_FOUND")]),
+            Err(RoverClientError::NotFound { .. })
+        ));
+        assert!(matches!(
+            handle_graphql_body_errors(vec![error("RATE_LIMITED")]),
+            Err(RoverClientError::RateLimitExceeded)
+        ));
+        assert!(matches!(
+            handle_graphql_body_errors(vec![error("SOMETHING_ELSE")]),
+            Err(RoverClientError::GraphQl { .. })
+        ));
+    }
+
+    #[test]
+    fn it_returns_typed_errors_from_statuses() {
+        assert!(matches!(
+            RoverClientError::from_status(401, StatusCode::UNAUTHORIZED),
+            RoverClientError::InvalidKey
+        ));
+        assert!(matches!(
+            RoverClientError::from_status(429, StatusCode::TOO_MANY_REQUESTS),
+            RoverClientError::RateLimitExceeded
+        ));
+        assert_eq!(
+            RoverClientError::from_status(502, StatusCode::BAD_GATEWAY).to_string(),
+            RoverClientError::ClientError {
+                msg: "502 Bad Gateway".to_string()
+            }
+            .to_string()
+        );
+    }
+
     #[tokio::test]
     async fn test_successful_response() {
         let server = MockServer::start();
@@ -334,7 +504,7 @@ mod tests {
 
         let response = graphql_client
             .execute(
-                "{}".to_string(),
+                GraphQLRequest::Json("{}".to_string()),
                 &HeaderMap::new(),
                 true,
                 EndpointKind::ApolloStudio,
@@ -365,7 +535,7 @@ mod tests {
 
         let response = graphql_client
             .execute(
-                "{}".to_string(),
+                GraphQLRequest::Json("{}".to_string()),
                 &HeaderMap::new(),
                 true,
                 EndpointKind::ApolloStudio,
@@ -396,7 +566,7 @@ mod tests {
 
         let response = graphql_client
             .execute(
-                "{}".to_string(),
+                GraphQLRequest::Json("{}".to_string()),
                 &HeaderMap::new(),
                 true,
                 EndpointKind::ApolloStudio,
@@ -434,7 +604,7 @@ mod tests {
 
         let response = graphql_client
             .execute(
-                "{}".to_string(),
+                GraphQLRequest::Json("{}".to_string()),
                 &HeaderMap::new(),
                 true,
                 EndpointKind::ApolloStudio,
@@ -450,4 +620,95 @@ mod tests {
         let reqwest_error = error.source().unwrap().source().unwrap();
         assert!(reqwest_error.to_string().contains("operation timed out"));
     }
+
+    struct NameQuery;
+
+    impl GraphQLQuery for NameQuery {
+        type Variables = serde_json::Value;
+        type ResponseData = serde_json::Value;
+
+        fn build_query(variables: Self::Variables) -> graphql_client::QueryBody<Self::Variables> {
+            graphql_client::QueryBody {
+                variables,
+                query: "query NameQuery { name }",
+                operation_name: "NameQuery",
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_transport_sends_query_string() {
+        let server = MockServer::start();
+        let get_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/graphql")
+                .query_param("query", "query NameQuery { name }")
+                .query_param("operationName", "NameQuery")
+                .matches(|req| {
+                    !req.query_params
+                        .iter()
+                        .flatten()
+                        .any(|(name, _)| name == "variables")
+                });
+            then.status(200)
+                .json_body(serde_json::json!({ "data": { "name": "rover" } }));
+        });
+
+        let graphql_client =
+            GraphQLClient::new(&server.url("/graphql"), ReqwestClient::new(), None);
+        let response = graphql_client
+            .send::<NameQuery>(
+                serde_json::json!({}),
+                &mut HeaderMap::new(),
+                EndpointKind::Customer,
+                GraphQLTransport::Get,
+                false,
+            )
+            .await;
+
+        get_mock.assert();
+        assert_eq!(response.unwrap(), serde_json::json!({ "name": "rover" }));
+    }
+
+    #[tokio::test]
+    async fn test_persisted_query_transport_registers_unknown_queries() {
+        let server = MockServer::start();
+        let hash = format!("{:x}", Sha256::digest("query NameQuery { name }"));
+        let hashed_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/graphql")
+                .body_contains(&hash)
+                .matches(|req| {
+                    !String::from_utf8_lossy(req.body.as_deref().unwrap_or_default())
+                        .contains("\"query\"")
+                });
+            then.status(200).json_body(serde_json::json!({
+                "errors": [{ "message": "PersistedQueryNotFound" }]
+            }));
+        });
+        let full_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/graphql")
+                .body_contains(&hash)
+                .body_contains("\"query\":\"query NameQuery { name }\"");
+            then.status(200)
+                .json_body(serde_json::json!({ "data": { "name": "rover" } }));
+        });
+
+        let graphql_client =
+            GraphQLClient::new(&server.url("/graphql"), ReqwestClient::new(), None);
+        let response = graphql_client
+            .send::<NameQuery>(
+                serde_json::json!({}),
+                &mut HeaderMap::new(),
+                EndpointKind::Customer,
+                GraphQLTransport::PersistedQuery,
+                false,
+            )
+            .await;
+
+        hashed_mock.assert();
+        full_mock.assert();
+        assert_eq!(response.unwrap(), serde_json::json!({ "name": "rover" }));
+    }
 }
@@ -1,7 +1,7 @@
 mod client;
 mod studio_client;
 
-pub use client::GraphQLClient;
+pub use client::{GraphQLClient, GraphQLTransport};
 pub use studio_client::StudioClient;
 
 pub(crate) const CLIENT_NAME: &str = "rover-client";
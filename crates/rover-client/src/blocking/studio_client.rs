@@ -1,6 +1,7 @@
 use crate::{
     blocking::{GraphQLClient, CLIENT_NAME},
     error::EndpointKind,
+    shared::DryRunRequest,
     RoverClientError,
 };
 
@@ -35,6 +36,7 @@ pub struct StudioClient {
     version: String,
     is_sudo: bool,
     retry_period: Option<Duration>,
+    dry_run: bool,
 }
 
 impl StudioClient {
@@ -56,9 +58,17 @@ impl StudioClient {
             version: version.to_string(),
             is_sudo,
             retry_period,
+            dry_run: false,
         }
     }
 
+    /// Stops mutations from being sent, returning [`RoverClientError::DryRun`]
+    /// describing each one instead. Queries are still sent.
+    pub fn with_dry_run(mut self, dry_run: bool) -> StudioClient {
+        self.dry_run = dry_run;
+        self
+    }
+
     /// Client method for making a GraphQL request to Apollo Studio.
     ///
     /// Takes one argument, `variables`. Returns a Response or a RoverClientError.
@@ -67,6 +77,7 @@ impl StudioClient {
         &self,
         variables: Q::Variables,
     ) -> Result<Q::ResponseData, RoverClientError> {
+        let variables = self.check_dry_run::<Q>(variables)?;
         let mut header_map = self.build_studio_headers()?;
         self.client
             .post::<Q>(variables, &mut header_map, EndpointKind::ApolloStudio)
@@ -81,12 +92,30 @@ impl StudioClient {
         &self,
         variables: Q::Variables,
     ) -> Result<Q::ResponseData, RoverClientError> {
+        let variables = self.check_dry_run::<Q>(variables)?;
         let mut header_map = self.build_studio_headers()?;
         self.client
             .post_no_retry::<Q>(variables, &mut header_map, EndpointKind::ApolloStudio)
             .await
     }
 
+    fn check_dry_run<Q: GraphQLQuery>(
+        &self,
+        variables: Q::Variables,
+    ) -> Result<Q::Variables, RoverClientError> {
+        let body = Q::build_query(variables);
+        if self.dry_run {
+            if let Some(request) =
+                DryRunRequest::intercept(body.operation_name, body.query, &body.variables)
+            {
+                return Err(RoverClientError::DryRun {
+                    request: Box::new(request),
+                });
+            }
+        }
+        Ok(body.variables)
+    }
+
     /// Function for building a [HeaderMap] for making http requests. Use for making
     /// requests to Apollo Studio. We're leaving this separate from `build` since we
     /// need to be able to mark the api_key as sensitive (at the bottom)
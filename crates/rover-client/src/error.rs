@@ -5,7 +5,9 @@ use itertools::Itertools;
 use rover_graphql::GraphQLServiceError;
 use thiserror::Error;
 
-use crate::shared::{CheckTaskStatus, CheckWorkflowResponse, GraphRef, LintResponse};
+use crate::shared::{
+    CheckTaskStatus, CheckWorkflowResponse, DryRunRequest, GraphRef, LintResponse,
+};
 
 /// RoverClientError represents all possible failures that can occur during a client request.
 #[derive(Error, Debug)]
@@ -234,12 +236,26 @@ pub enum RoverClientError {
         frontend_url_root: String,
     },
 
+    #[error("Could not find proposal \"{proposal_id}\"")]
+    ProposalNotFound { proposal_id: String },
+
+    #[error("Could not update the proposal: {msg}")]
+    ProposalError { msg: String },
+
     #[error("Offline licences are not enabled for your organization.")]
     OfflineLicenseNotEnabled,
 
     #[error("You've encountered a rate limit.")]
     RateLimitExceeded,
 
+    /// The registry couldn't find something the request referred to, like a graph or variant
+    #[error("{msg}")]
+    NotFound { msg: String },
+
+    /// The response parsed as JSON, but not into the shape the operation expects
+    #[error("The response from Apollo Studio didn't have the shape Rover expected: {msg}")]
+    UnexpectedResponse { msg: String },
+
     #[error("Invalid router config: {msg}")]
     InvalidRouterConfig { msg: String },
 
@@ -248,6 +264,10 @@ pub enum RoverClientError {
 
     #[error("Could not instantiate the service.")]
     ServiceError(Box<dyn std::error::Error + Send + Sync>),
+
+    /// `--dry-run` stopped a mutation from being sent to Studio.
+    #[error("Did not send {} because `--dry-run` was passed.", .request.operation_name)]
+    DryRun { request: Box<DryRunRequest> },
 }
 
 fn contract_publish_errors_msg(msgs: &[String], no_launch: &bool) -> String {
@@ -296,7 +316,9 @@ fn check_workflow_error_msg(check_response: &CheckWorkflowResponse) -> String {
             None
         },
         if let Some(downstream_response) = &check_response.maybe_downstream_response {
-            if downstream_response.task_status == CheckTaskStatus::FAILED {
+            if downstream_response.task_status == CheckTaskStatus::FAILED
+                || !downstream_response.failed_variants().is_empty()
+            {
                 Some("downstream")
             } else {
                 None
@@ -305,7 +327,9 @@ fn check_workflow_error_msg(check_response: &CheckWorkflowResponse) -> String {
             None
         },
         if let Some(proposals_response) = &check_response.maybe_proposals_response {
-            if proposals_response.task_status == CheckTaskStatus::FAILED {
+            if proposals_response.task_status == CheckTaskStatus::FAILED
+                || proposals_response.has_unapproved_changes()
+            {
                 Some("proposal")
             } else {
                 None
@@ -345,18 +369,73 @@ fn check_workflow_error_msg(check_response: &CheckWorkflowResponse) -> String {
     }
 }
 
+impl RoverClientError {
+    /// The typed error for a GraphQL error whose `extensions.code` is one of the platform's
+    /// common failures, like an invalid API key or a rate limit
+    pub fn from_graphql_error(error: &graphql_client::Error) -> Option<RoverClientError> {
+        let code = error
+            .extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("code"))
+            .and_then(|code| code.as_str())?;
+        match code {
+            "UNAUTHENTICATED" | "INVALID_API_KEY" => Some(RoverClientError::InvalidKey),
+            "FORBIDDEN" | "PERMISSION_DENIED" => Some(RoverClientError::PermissionError {
+                msg: error.message.trim_end_matches('.').to_string(),
+            }),
+            "NOT_FOUND" | "GRAPH_NOT_FOUND" => Some(RoverClientError::NotFound {
+                msg: error.message.clone(),
+            }),
+            "RATE_LIMITED" | "TOO_MANY_REQUESTS" => Some(RoverClientError::RateLimitExceeded),
+            _ => None,
+        }
+    }
+
+    /// The typed error for an HTTP status that says why a request was refused, falling back on a
+    /// [`RoverClientError::ClientError`] naming the status
+    pub fn from_status(status: u16, reason: impl ToString) -> RoverClientError {
+        match status {
+            401 => RoverClientError::InvalidKey,
+            403 => RoverClientError::PermissionError {
+                msg: "the registry refused the request".to_string(),
+            },
+            429 => RoverClientError::RateLimitExceeded,
+            _ => RoverClientError::ClientError {
+                msg: reason.to_string(),
+            },
+        }
+    }
+}
+
 impl<T: Debug + Send + Sync> From<GraphQLServiceError<T>> for RoverClientError {
     fn from(value: GraphQLServiceError<T>) -> Self {
         match value {
-            GraphQLServiceError::NoData(_) => RoverClientError::GraphQl {
-                msg: value.to_string(),
-            },
+            GraphQLServiceError::NoData(ref errors) => errors
+                .iter()
+                .find_map(RoverClientError::from_graphql_error)
+                .unwrap_or_else(|| RoverClientError::GraphQl {
+                    msg: value.to_string(),
+                }),
             GraphQLServiceError::PartialError { errors, .. } => {
+                if let Some(err) = errors.iter().find_map(RoverClientError::from_graphql_error) {
+                    return err;
+                }
                 let errors = errors.iter().map(|err| err.to_string()).join("\n");
                 RoverClientError::GraphQl {
                     msg: format!("Response returned with errors:\n{}", errors),
                 }
             }
+            GraphQLServiceError::Deserialization {
+                error, status_code, ..
+            } => {
+                if status_code.is_success() {
+                    RoverClientError::UnexpectedResponse {
+                        msg: error.to_string(),
+                    }
+                } else {
+                    RoverClientError::from_status(status_code.as_u16(), status_code)
+                }
+            }
             _ => RoverClientError::ClientError {
                 msg: value.to_string(),
             },
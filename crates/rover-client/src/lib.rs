@@ -20,3 +20,7 @@ pub mod releases;
 
 /// Module for shared functionality
 pub mod shared;
+
+/// The operations that follow semver, for use outside of Rover
+#[cfg(feature = "stable-api")]
+pub mod stable;
@@ -0,0 +1,121 @@
+use graphql_client::*;
+
+use crate::blocking::StudioClient;
+use crate::operations::config::graphs::StudioGraph;
+use crate::RoverClientError;
+
+#[derive(GraphQLQuery)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/config/graphs/graphs_query.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. config_graphs_query
+pub(crate) struct ConfigGraphsQuery;
+
+/// Lists the graphs the API key can access: every graph in the user's organizations for a user
+/// key, or only the key's graph for a graph key
+pub async fn run(client: &StudioClient) -> Result<Vec<StudioGraph>, RoverClientError> {
+    let data = client
+        .post::<ConfigGraphsQuery>(config_graphs_query::Variables {})
+        .await?;
+    build_response(data)
+}
+
+fn build_response(
+    data: config_graphs_query::ResponseData,
+) -> Result<Vec<StudioGraph>, RoverClientError> {
+    use config_graphs_query::ConfigGraphsQueryMe;
+
+    let me = data.me.ok_or(RoverClientError::InvalidKey)?;
+    let mut graphs: Vec<StudioGraph> = match me {
+        ConfigGraphsQueryMe::User(user) => user
+            .memberships
+            .into_iter()
+            .flat_map(|membership| {
+                let organization = membership.account.name;
+                membership
+                    .account
+                    .graphs
+                    .into_iter()
+                    .map(move |graph| StudioGraph {
+                        id: graph.id,
+                        title: graph.title,
+                        organization: Some(organization.clone()),
+                    })
+            })
+            .collect(),
+        ConfigGraphsQueryMe::Graph(graph) => vec![StudioGraph {
+            id: graph.id,
+            title: graph.title,
+            organization: graph.account.map(|account| account.name),
+        }],
+        _ => Vec::new(),
+    };
+    graphs.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(graphs)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn build_response_lists_graphs_across_organizations() {
+        let json_response = json!({
+            "me": {
+                "__typename": "User",
+                "memberships": [
+                    {
+                        "account": {
+                            "name": "Acme",
+                            "graphs": [
+                                { "id": "storefront", "title": "Storefront" },
+                                { "id": "inventory", "title": "Inventory" }
+                            ]
+                        }
+                    },
+                    {
+                        "account": {
+                            "name": "Side project",
+                            "graphs": [{ "id": "blog", "title": "Blog" }]
+                        }
+                    }
+                ]
+            }
+        });
+        let data: config_graphs_query::ResponseData =
+            serde_json::from_value(json_response).unwrap();
+        let graphs = build_response(data).unwrap();
+        let ids: Vec<_> = graphs.iter().map(|graph| graph.id.as_str()).collect();
+        assert_that!(ids).is_equal_to(vec!["blog", "inventory", "storefront"]);
+        assert_that!(graphs[0].organization).is_equal_to(Some("Side project".to_string()));
+    }
+
+    #[test]
+    fn build_response_lists_the_graph_of_a_graph_key() {
+        let json_response = json!({
+            "me": {
+                "__typename": "Graph",
+                "id": "storefront",
+                "title": "Storefront",
+                "account": { "name": "Acme" }
+            }
+        });
+        let data: config_graphs_query::ResponseData =
+            serde_json::from_value(json_response).unwrap();
+        assert_that!(build_response(data).unwrap()).is_equal_to(vec![StudioGraph {
+            id: "storefront".to_string(),
+            title: "Storefront".to_string(),
+            organization: Some("Acme".to_string()),
+        }]);
+    }
+}
@@ -0,0 +1,8 @@
+/// A graph that the authenticated API key can access
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StudioGraph {
+    pub id: String,
+    pub title: String,
+    /// The name of the organization the graph belongs to
+    pub organization: Option<String>,
+}
@@ -1,5 +1,8 @@
 /// runner for rover config whoami
 pub mod who_am_i;
 
+/// runner for listing the graphs an API key can access
+pub mod graphs;
+
 /// runner is_federated check
 pub mod is_federated;
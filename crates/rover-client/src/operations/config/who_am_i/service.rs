@@ -39,6 +39,8 @@ impl PartialEq for config_who_am_i_query::Variables {
 pub enum WhoAmIError {
     #[error("Invalid key")]
     InvalidKey,
+    #[error("{0}")]
+    UnexpectedResponse(String),
     #[error(transparent)]
     GraphQL(#[from] GraphQLServiceError<<ConfigWhoAmIQuery as GraphQLQuery>::ResponseData>),
 }
@@ -47,6 +49,7 @@ impl From<WhoAmIError> for RoverClientError {
     fn from(value: WhoAmIError) -> Self {
         match value {
             WhoAmIError::InvalidKey => RoverClientError::InvalidKey,
+            WhoAmIError::UnexpectedResponse(msg) => RoverClientError::UnexpectedResponse { msg },
             WhoAmIError::GraphQL(err) => err.into(),
         }
     }
@@ -128,6 +131,18 @@ where
                             _ => None,
                         };
 
+                        if me.id.is_empty() {
+                            return Err(WhoAmIError::UnexpectedResponse(
+                                "the identity of the API key has no ID".to_string(),
+                            ));
+                        }
+                        if key_actor_type == Actor::GRAPH && graph_title.is_none() {
+                            return Err(WhoAmIError::UnexpectedResponse(format!(
+                                "the graph API key for '{}' didn't resolve to a graph",
+                                me.id
+                            )));
+                        }
+
                         Ok(RegistryIdentity {
                             id: me.id,
                             graph_title,
@@ -245,4 +260,41 @@ mod tests {
         assert_eq!(output.unwrap(), expected_identity);
         resp_task.await.unwrap()
     }
+
+    #[tokio::test]
+    async fn get_identity_rejects_graph_keys_without_a_graph() {
+        let (service, mut handle) =
+            mock::spawn::<GraphQLRequest<ConfigWhoAmIQuery>, config_who_am_i_query::ResponseData>();
+
+        let inner = ServiceBuilder::new()
+            .map_err(GraphQLServiceError::UpstreamService)
+            .service(service.into_inner());
+        let mut who_am_i = WhoAmI::new(inner);
+        let who_am_i = who_am_i.ready().await.unwrap();
+
+        let response = who_am_i.call(WhoAmIRequest::new(CredentialOrigin::EnvVar));
+
+        let json_response = json!({
+            "me": {
+              "__typename": "User",
+              "id": "big-ol-graph-key-lolol",
+              "asActor": {
+                "type": "GRAPH"
+              },
+            }
+        });
+
+        let response_data: config_who_am_i_query::ResponseData =
+            serde_json::from_value(json_response).unwrap();
+
+        let resp_task = task::spawn(async move {
+            let (_, send_response) = handle.next_request().await.unwrap();
+            send_response.send_response(response_data);
+        });
+
+        let output = response.await;
+
+        assert!(matches!(output, Err(WhoAmIError::UnexpectedResponse(_))));
+        resp_task.await.unwrap()
+    }
 }
@@ -0,0 +1,210 @@
+use graphql_client::*;
+
+use super::types::*;
+use crate::blocking::StudioClient;
+use crate::RoverClientError;
+
+type GraphQLDocument = String;
+type Timestamp = String;
+
+#[derive(GraphQLQuery)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/graph/changelog/changelog_query.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. graph_changelog_query
+pub(crate) struct GraphChangelogQuery;
+
+type ChangelogLaunch = graph_changelog_query::ChangelogLaunch;
+
+/// Walks back through the launches of a variant, from the latest, until it reaches the schema
+/// published at `input.since`
+pub async fn run(
+    input: GraphChangelogInput,
+    client: &StudioClient,
+) -> Result<GraphChangelogResponse, RoverClientError> {
+    let mut publications = Vec::new();
+    let mut next_launch_id: Option<String> = None;
+    for _ in 0..input.limit {
+        let data = client
+            .post::<GraphChangelogQuery>(input.variables(next_launch_id.as_deref()))
+            .await?;
+        let Some(launch) = get_launch_from_response_data(data, &input)? else {
+            break;
+        };
+        next_launch_id = launch
+            .previous_launch
+            .as_ref()
+            .map(|previous| previous.id.clone());
+        let launch_id = launch.id.clone();
+        if let Some(publication) = publication(launch) {
+            let reached_since = match &input.since {
+                ChangelogSince::Launch(id) => launch_id.starts_with(id.as_str()),
+                ChangelogSince::Date(date) => publication.published_at <= *date,
+            };
+            publications.push(publication);
+            if reached_since {
+                publications.reverse();
+                return Ok(GraphChangelogResponse {
+                    graph_ref: input.graph_ref,
+                    publications,
+                });
+            }
+        }
+        if next_launch_id.is_none() {
+            break;
+        }
+    }
+
+    match input.since {
+        ChangelogSince::Launch(id) => Err(RoverClientError::NotFound {
+            msg: format!(
+                "Could not find launch \"{}\" in the last {} launches of {}",
+                id, input.limit, input.graph_ref
+            ),
+        }),
+        // the variant's history began after `since`, so every publication is part of the changelog
+        ChangelogSince::Date(_) if next_launch_id.is_none() => {
+            publications.reverse();
+            Ok(GraphChangelogResponse {
+                graph_ref: input.graph_ref,
+                publications,
+            })
+        }
+        ChangelogSince::Date(date) => Err(RoverClientError::NotFound {
+            msg: format!(
+                "The last {} launches of {} were all published after {}",
+                input.limit, input.graph_ref, date
+            ),
+        }),
+    }
+}
+
+fn get_launch_from_response_data(
+    data: graph_changelog_query::ResponseData,
+    input: &GraphChangelogInput,
+) -> Result<Option<ChangelogLaunch>, RoverClientError> {
+    let graph = data.graph.ok_or(RoverClientError::GraphNotFound {
+        graph_ref: input.graph_ref.clone(),
+    })?;
+    let valid_variants = graph.variants.iter().map(|it| it.name.clone()).collect();
+    let variant = graph.variant.ok_or(RoverClientError::NoSchemaForVariant {
+        graph_ref: input.graph_ref.clone(),
+        valid_variants,
+        frontend_url_root: data.frontend_url_root,
+    })?;
+    Ok(variant.latest_launch.or(variant.launch))
+}
+
+/// The schema a launch published, if it published one
+fn publication(launch: ChangelogLaunch) -> Option<LaunchPublication> {
+    let publication = launch.publication?;
+    Some(LaunchPublication {
+        launch_id: launch.id,
+        published_at: publication.published_at.parse().ok()?,
+        schema: publication.schema.document,
+        changed_subgraphs: launch
+            .subgraph_changes
+            .unwrap_or_default()
+            .into_iter()
+            .map(|change| change.name)
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::shared::GraphRef;
+
+    fn mock_input() -> GraphChangelogInput {
+        GraphChangelogInput {
+            graph_ref: GraphRef {
+                name: "mygraph".to_string(),
+                variant: "current".to_string(),
+            },
+            since: ChangelogSince::Launch("abc".to_string()),
+            limit: 10,
+        }
+    }
+
+    #[test]
+    fn get_launch_from_response_data_works() {
+        let json_response = json!({
+            "frontendUrlRoot": "https://studio.apollographql.com",
+            "graph": {
+                "variant": {
+                    "latestLaunch": {
+                        "id": "def456",
+                        "subgraphChanges": [{ "name": "products" }],
+                        "publication": {
+                            "publishedAt": "2024-05-01T10:00:00Z",
+                            "schema": { "document": "type Query { a: ID }" }
+                        },
+                        "previousLaunch": { "id": "abc123" }
+                    }
+                },
+                "variants": []
+            }
+        });
+        let data = serde_json::from_value(json_response).unwrap();
+        let launch = get_launch_from_response_data(data, &mock_input())
+            .unwrap()
+            .unwrap();
+        assert_eq!(launch.previous_launch.as_ref().unwrap().id, "abc123");
+
+        let publication = publication(launch).unwrap();
+        assert_eq!(publication.launch_id, "def456");
+        assert_eq!(publication.changed_subgraphs, vec!["products".to_string()]);
+        assert_eq!(
+            publication.published_at,
+            "2024-05-01T10:00:00Z"
+                .parse::<chrono::DateTime<chrono::Utc>>()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn unpublished_launches_have_no_publication() {
+        let json_response = json!({
+            "frontendUrlRoot": "https://studio.apollographql.com",
+            "graph": {
+                "variant": {
+                    "launch": {
+                        "id": "def456",
+                        "subgraphChanges": null,
+                        "publication": null,
+                        "previousLaunch": null
+                    }
+                },
+                "variants": []
+            }
+        });
+        let data = serde_json::from_value(json_response).unwrap();
+        let launch = get_launch_from_response_data(data, &mock_input())
+            .unwrap()
+            .unwrap();
+        assert!(publication(launch).is_none());
+    }
+
+    #[test]
+    fn get_launch_from_response_data_errs_with_no_variant() {
+        let json_response = json!({
+            "frontendUrlRoot": "https://studio.apollographql.com",
+            "graph": { "variant": null, "variants": [] }
+        });
+        let data = serde_json::from_value(json_response).unwrap();
+        assert!(matches!(
+            get_launch_from_response_data(data, &mock_input()),
+            Err(RoverClientError::NoSchemaForVariant { .. })
+        ));
+    }
+}
@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+
+use crate::operations::graph::changelog::runner::graph_changelog_query;
+use crate::shared::GraphRef;
+
+pub(crate) type QueryVariables = graph_changelog_query::Variables;
+
+/// Where a changelog starts
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ChangelogSince {
+    /// The launch that published the schema the changelog starts from, by its full or shortened ID
+    Launch(String),
+    /// The last schema published before this time
+    Date(DateTime<Utc>),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GraphChangelogInput {
+    pub graph_ref: GraphRef,
+    pub since: ChangelogSince,
+    /// The most launches to walk back through
+    pub limit: usize,
+}
+
+impl GraphChangelogInput {
+    pub(crate) fn variables(&self, launch_id: Option<&str>) -> QueryVariables {
+        QueryVariables {
+            graph_id: self.graph_ref.name.clone(),
+            variant: self.graph_ref.variant.clone(),
+            launch_id: launch_id.unwrap_or_default().to_string(),
+            from_latest: launch_id.is_none(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GraphChangelogResponse {
+    pub graph_ref: GraphRef,
+    /// The schemas that were published, oldest first. The first is the one the changelog starts
+    /// from: the one published at `since`, or the variant's first schema if it's newer
+    pub publications: Vec<LaunchPublication>,
+}
+
+/// A schema that a launch published to the variant
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LaunchPublication {
+    pub launch_id: String,
+    pub published_at: DateTime<Utc>,
+    pub schema: String,
+    /// The subgraphs whose publishes started the launch
+    pub changed_subgraphs: Vec<String>,
+}
@@ -1,21 +1,25 @@
 use std::time::{Duration, Instant};
 
 use graphql_client::*;
+use rover_std::progress::ProgressBar;
+use rover_std::warnln;
 
 use crate::blocking::StudioClient;
 use crate::operations::graph::check_workflow::types::{CheckWorkflowInput, QueryResponseData};
 use crate::shared::{
-    CheckWorkflowResponse, CustomCheckResponse, Diagnostic, GraphRef, LintCheckResponse,
-    OperationCheckResponse, SchemaChange, Violation,
+    CheckWorkflowResponse, CustomCheckResponse, Diagnostic, DownstreamCheckResponse,
+    DownstreamVariantResult, GraphRef, LintCheckResponse, OperationCheckResponse, SchemaChange,
+    Violation,
 };
 use crate::RoverClientError;
 
 use self::graph_check_workflow_query::GraphCheckWorkflowQueryGraphCheckWorkflowTasksOn::{
-    CustomCheckTask, LintCheckTask, OperationsCheckTask,
+    CustomCheckTask, DownstreamCheckTask, LintCheckTask, OperationsCheckTask,
 };
 use self::graph_check_workflow_query::{
     CheckWorkflowStatus, CheckWorkflowTaskStatus,
     GraphCheckWorkflowQueryGraphCheckWorkflowTasksOnCustomCheckTaskResult,
+    GraphCheckWorkflowQueryGraphCheckWorkflowTasksOnDownstreamCheckTaskResults,
     GraphCheckWorkflowQueryGraphCheckWorkflowTasksOnLintCheckTaskResult,
     GraphCheckWorkflowQueryGraphCheckWorkflowTasksOnOperationsCheckTaskResult,
 };
@@ -44,6 +48,7 @@ pub async fn run(
     let graph_ref = input.graph_ref.clone();
     let mut url: Option<String> = None;
     let now = Instant::now();
+    let _progress = ProgressBar::new("waiting for the check to finish");
     loop {
         let result = client
             .post::<GraphCheckWorkflowQuery>(input.clone().into())
@@ -61,7 +66,7 @@ pub async fn run(
                 url = get_target_url_from_data(data);
             }
             Err(e) => {
-                eprintln!("error while checking status of check: {e}\nthis error may be transient... retrying");
+                warnln!("error while checking status of check: {e}\nthis error may be transient... retrying");
             }
         }
         if now.elapsed() > Duration::from_secs(input.checks_timeout_seconds) {
@@ -103,6 +108,12 @@ fn get_check_response_from_data(
         GraphCheckWorkflowQueryGraphCheckWorkflowTasksOnCustomCheckTaskResult,
     > = None;
 
+    let mut downstream_status = None;
+    let mut downstream_target_url = None;
+    let mut downstream_result: Option<
+        Vec<GraphCheckWorkflowQueryGraphCheckWorkflowTasksOnDownstreamCheckTaskResults>,
+    > = None;
+
     for task in check_workflow.tasks {
         match task.on {
             OperationsCheckTask(typed_task) => {
@@ -128,6 +139,13 @@ fn get_check_response_from_data(
                     custom_result = Some(result)
                 }
             }
+            DownstreamCheckTask(typed_task) => {
+                downstream_status = Some(task.status);
+                downstream_target_url = task.target_url;
+                if let Some(results) = typed_task.results {
+                    downstream_result = Some(results)
+                }
+            }
             _ => (),
         }
     }
@@ -158,7 +176,11 @@ fn get_check_response_from_data(
             custom_result,
         ),
         maybe_proposals_response: None,
-        maybe_downstream_response: None,
+        maybe_downstream_response: get_downstream_response_from_result(
+            downstream_status,
+            downstream_target_url,
+            downstream_result,
+        ),
     };
 
     match check_workflow.status {
@@ -294,3 +316,39 @@ fn get_custom_response_from_result(
         None => None,
     }
 }
+
+fn get_downstream_response_from_result(
+    task_status: Option<CheckWorkflowTaskStatus>,
+    target_url: Option<String>,
+    results: Option<
+        Vec<GraphCheckWorkflowQueryGraphCheckWorkflowTasksOnDownstreamCheckTaskResults>,
+    >,
+) -> Option<DownstreamCheckResponse> {
+    match results {
+        Some(results) => {
+            let blocking_variants = results
+                .iter()
+                .filter(|result| result.fails_upstream_workflow.unwrap_or(false))
+                .map(|result| result.downstream_variant_name.clone())
+                .collect();
+            let variants = results
+                .into_iter()
+                .map(|result| DownstreamVariantResult {
+                    variant_name: result.downstream_variant_name,
+                    blocking: result.blocking,
+                    status: result
+                        .downstream_workflow
+                        .map(|workflow| workflow.status)
+                        .into(),
+                })
+                .collect();
+            Some(DownstreamCheckResponse {
+                task_status: task_status.into(),
+                target_url,
+                blocking_variants,
+                variants,
+            })
+        }
+        None => None,
+    }
+}
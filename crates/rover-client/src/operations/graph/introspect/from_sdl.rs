@@ -0,0 +1,821 @@
+//! Schema decoding module, the reverse of [`Schema::encode`](super::Schema::encode).
+//!
+//! Many codegen tools read a schema as the result of an introspection query rather than as SDL,
+//! so this turns SDL back into the introspection result its server would return. It also
+//! minifies SDL onto a single line, for tools that want it that way.
+use std::collections::{HashMap, HashSet};
+
+use apollo_parser::{cst, cst::CstNode, Parser, SyntaxKind};
+use serde_json::{json, Value};
+
+use crate::RoverClientError;
+
+const DEFAULT_DEPRECATION_REASON: &str = "No longer supported";
+
+/// The types and directives every schema has, whether or not its SDL defines them
+const BUILT_INS: &str = r#"
+"The `String` scalar type represents textual data, represented as UTF-8 character sequences."
+scalar String
+"The `Int` scalar type represents non-fractional signed whole numeric values."
+scalar Int
+"The `Float` scalar type represents signed double-precision fractional values."
+scalar Float
+"The `Boolean` scalar type represents `true` or `false`."
+scalar Boolean
+"The `ID` scalar type represents a unique identifier."
+scalar ID
+
+"Directs the executor to include this field or fragment only when the `if` argument is true."
+directive @include("Included when true." if: Boolean!) on FIELD | FRAGMENT_SPREAD | INLINE_FRAGMENT
+"Directs the executor to skip this field or fragment when the `if` argument is true."
+directive @skip("Skipped when true." if: Boolean!) on FIELD | FRAGMENT_SPREAD | INLINE_FRAGMENT
+"Marks an element of a GraphQL schema as no longer supported."
+directive @deprecated(reason: String = "No longer supported") on FIELD_DEFINITION | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION | ENUM_VALUE
+"Exposes a URL that specifies the behaviour of this scalar."
+directive @specifiedBy("The URL that specifies the behaviour of this scalar." url: String!) on SCALAR
+
+type __Schema {
+  description: String
+  types: [__Type!]!
+  queryType: __Type!
+  mutationType: __Type
+  subscriptionType: __Type
+  directives: [__Directive!]!
+}
+
+type __Type {
+  kind: __TypeKind!
+  name: String
+  description: String
+  specifiedByURL: String
+  fields(includeDeprecated: Boolean = false): [__Field!]
+  interfaces: [__Type!]
+  possibleTypes: [__Type!]
+  enumValues(includeDeprecated: Boolean = false): [__EnumValue!]
+  inputFields(includeDeprecated: Boolean = false): [__InputValue!]
+  ofType: __Type
+}
+
+enum __TypeKind {
+  SCALAR
+  OBJECT
+  INTERFACE
+  UNION
+  ENUM
+  INPUT_OBJECT
+  LIST
+  NON_NULL
+}
+
+type __Field {
+  name: String!
+  description: String
+  args(includeDeprecated: Boolean = false): [__InputValue!]!
+  type: __Type!
+  isDeprecated: Boolean!
+  deprecationReason: String
+}
+
+type __InputValue {
+  name: String!
+  description: String
+  type: __Type!
+  defaultValue: String
+  isDeprecated: Boolean!
+  deprecationReason: String
+}
+
+type __EnumValue {
+  name: String!
+  description: String
+  isDeprecated: Boolean!
+  deprecationReason: String
+}
+
+type __Directive {
+  name: String!
+  description: String
+  locations: [__DirectiveLocation!]!
+  args(includeDeprecated: Boolean = false): [__InputValue!]!
+  isRepeatable: Boolean!
+}
+
+enum __DirectiveLocation {
+  QUERY
+  MUTATION
+  SUBSCRIPTION
+  FIELD
+  FRAGMENT_DEFINITION
+  FRAGMENT_SPREAD
+  INLINE_FRAGMENT
+  VARIABLE_DEFINITION
+  SCHEMA
+  SCALAR
+  OBJECT
+  FIELD_DEFINITION
+  ARGUMENT_DEFINITION
+  INTERFACE
+  UNION
+  ENUM
+  ENUM_VALUE
+  INPUT_OBJECT
+  INPUT_FIELD_DEFINITION
+}
+"#;
+
+/// Builds the response to the full introspection query, with the same shape as a server's
+/// response, from the schema `sdl` describes.
+pub fn introspection_json(sdl: &str) -> Result<Value, RoverClientError> {
+    let documents = [parse(sdl)?, parse(BUILT_INS)?];
+
+    let mut kinds = HashMap::new();
+    for document in &documents {
+        for parts in document.definitions().filter_map(type_parts) {
+            kinds.entry(parts.name).or_insert(parts.kind);
+        }
+    }
+    let mut introspection = Introspection {
+        kinds,
+        ..Default::default()
+    };
+    let mut defined_types = HashSet::new();
+    let mut defined_directives = HashSet::new();
+    for (index, document) in documents.iter().enumerate() {
+        let built_in = index > 0;
+        for definition in document.definitions() {
+            if let Some(parts) = type_parts(definition.clone()) {
+                // the schema's own definitions replace the built-in ones
+                if built_in && defined_types.contains(&parts.name) {
+                    continue;
+                }
+                if !built_in {
+                    defined_types.insert(parts.name.clone());
+                }
+                introspection.add_type(parts)?;
+            } else if let cst::Definition::DirectiveDefinition(def) = definition {
+                let name = def.name().map(|name| name.text().to_string());
+                if built_in
+                    && name
+                        .as_ref()
+                        .is_some_and(|n| defined_directives.contains(n))
+                {
+                    continue;
+                }
+                defined_directives.extend(name);
+                introspection.add_directive(def)?;
+            } else if !built_in {
+                let operation_types = match definition {
+                    cst::Definition::SchemaDefinition(def) => {
+                        def.root_operation_type_definitions().collect()
+                    }
+                    cst::Definition::SchemaExtension(def) => {
+                        def.root_operation_type_definitions().collect()
+                    }
+                    _ => Vec::new(),
+                };
+                introspection.add_operation_types(operation_types);
+            }
+        }
+    }
+    Ok(introspection.into_json())
+}
+
+/// Prints the schema `sdl` describes on a single line, without comments or any whitespace that
+/// doesn't separate two names or values
+pub fn minify_sdl(sdl: &str) -> Result<String, RoverClientError> {
+    let document = parse(sdl)?;
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut minified = String::new();
+    for token in document
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+    {
+        if matches!(
+            token.kind(),
+            SyntaxKind::WHITESPACE | SyntaxKind::COMMENT | SyntaxKind::COMMA | SyntaxKind::EOF
+        ) {
+            continue;
+        }
+        let text = match token.parent().and_then(cst::StringValue::cast) {
+            // block strings are the only tokens that can span lines, so they're printed as the
+            // single-line string with the same value
+            Some(string) if token.text().starts_with("\"\"\"") => {
+                Value::String(String::from(&string)).to_string()
+            }
+            _ => token.text().to_string(),
+        };
+        if minified.ends_with(is_word) && text.starts_with(is_word) {
+            minified.push(' ');
+        }
+        minified.push_str(&text);
+    }
+    Ok(minified)
+}
+
+fn parse(sdl: &str) -> Result<cst::Document, RoverClientError> {
+    let tree = Parser::new(sdl).parse();
+    if let Some(err) = tree.errors().next() {
+        return Err(RoverClientError::IntrospectionError {
+            msg: format!("Could not parse the schema: {}", err.message()),
+        });
+    }
+    Ok(tree.document())
+}
+
+/// The parts of a type definition or extension that introspection describes
+#[derive(Default)]
+struct TypeParts {
+    kind: &'static str,
+    name: String,
+    description: Option<cst::Description>,
+    directives: Option<cst::Directives>,
+    fields: Option<cst::FieldsDefinition>,
+    interfaces: Option<cst::ImplementsInterfaces>,
+    members: Option<cst::UnionMemberTypes>,
+    values: Option<cst::EnumValuesDefinition>,
+    input_fields: Option<cst::InputFieldsDefinition>,
+}
+
+fn type_parts(definition: cst::Definition) -> Option<TypeParts> {
+    let name = |name: Option<cst::Name>| name.map(|name| name.text().to_string());
+    let parts = match definition {
+        cst::Definition::ScalarTypeDefinition(def) => TypeParts {
+            kind: "SCALAR",
+            name: name(def.name())?,
+            description: def.description(),
+            directives: def.directives(),
+            ..Default::default()
+        },
+        cst::Definition::ScalarTypeExtension(def) => TypeParts {
+            kind: "SCALAR",
+            name: name(def.name())?,
+            directives: def.directives(),
+            ..Default::default()
+        },
+        cst::Definition::ObjectTypeDefinition(def) => TypeParts {
+            kind: "OBJECT",
+            name: name(def.name())?,
+            description: def.description(),
+            fields: def.fields_definition(),
+            interfaces: def.implements_interfaces(),
+            ..Default::default()
+        },
+        cst::Definition::ObjectTypeExtension(def) => TypeParts {
+            kind: "OBJECT",
+            name: name(def.name())?,
+            fields: def.fields_definition(),
+            interfaces: def.implements_interfaces(),
+            ..Default::default()
+        },
+        cst::Definition::InterfaceTypeDefinition(def) => TypeParts {
+            kind: "INTERFACE",
+            name: name(def.name())?,
+            description: def.description(),
+            fields: def.fields_definition(),
+            interfaces: def.implements_interfaces(),
+            ..Default::default()
+        },
+        cst::Definition::InterfaceTypeExtension(def) => TypeParts {
+            kind: "INTERFACE",
+            name: name(def.name())?,
+            fields: def.fields_definition(),
+            interfaces: def.implements_interfaces(),
+            ..Default::default()
+        },
+        cst::Definition::UnionTypeDefinition(def) => TypeParts {
+            kind: "UNION",
+            name: name(def.name())?,
+            description: def.description(),
+            members: def.union_member_types(),
+            ..Default::default()
+        },
+        cst::Definition::UnionTypeExtension(def) => TypeParts {
+            kind: "UNION",
+            name: name(def.name())?,
+            members: def.union_member_types(),
+            ..Default::default()
+        },
+        cst::Definition::EnumTypeDefinition(def) => TypeParts {
+            kind: "ENUM",
+            name: name(def.name())?,
+            description: def.description(),
+            values: def.enum_values_definition(),
+            ..Default::default()
+        },
+        cst::Definition::EnumTypeExtension(def) => TypeParts {
+            kind: "ENUM",
+            name: name(def.name())?,
+            values: def.enum_values_definition(),
+            ..Default::default()
+        },
+        cst::Definition::InputObjectTypeDefinition(def) => TypeParts {
+            kind: "INPUT_OBJECT",
+            name: name(def.name())?,
+            description: def.description(),
+            input_fields: def.input_fields_definition(),
+            ..Default::default()
+        },
+        cst::Definition::InputObjectTypeExtension(def) => TypeParts {
+            kind: "INPUT_OBJECT",
+            name: name(def.name())?,
+            input_fields: def.input_fields_definition(),
+            ..Default::default()
+        },
+        _ => return None,
+    };
+    Some(parts)
+}
+
+/// A type as introspection describes it, merged from its definition and extensions
+struct IntrospectedType {
+    kind: &'static str,
+    name: String,
+    description: Option<String>,
+    specified_by_url: Option<String>,
+    fields: Vec<Value>,
+    interfaces: Vec<String>,
+    members: Vec<String>,
+    values: Vec<Value>,
+    input_fields: Vec<Value>,
+}
+
+#[derive(Default)]
+struct Introspection {
+    kinds: HashMap<String, &'static str>,
+    types: Vec<IntrospectedType>,
+    directives: Vec<Value>,
+    query_type: Option<String>,
+    mutation_type: Option<String>,
+    subscription_type: Option<String>,
+}
+
+impl Introspection {
+    fn add_type(&mut self, parts: TypeParts) -> Result<(), RoverClientError> {
+        let index = match self.types.iter().position(|ty| ty.name == parts.name) {
+            Some(index) => index,
+            None => {
+                self.types.push(IntrospectedType {
+                    kind: parts.kind,
+                    name: parts.name.clone(),
+                    description: None,
+                    specified_by_url: None,
+                    fields: Vec::new(),
+                    interfaces: Vec::new(),
+                    members: Vec::new(),
+                    values: Vec::new(),
+                    input_fields: Vec::new(),
+                });
+                self.types.len() - 1
+            }
+        };
+
+        let fields = parts
+            .fields
+            .iter()
+            .flat_map(|fields| fields.field_definitions())
+            .map(|field| self.field(field))
+            .collect::<Result<Vec<_>, _>>()?;
+        let input_fields = parts
+            .input_fields
+            .iter()
+            .flat_map(|fields| fields.input_value_definitions())
+            .map(|field| self.input_value(field))
+            .collect::<Result<Vec<_>, _>>()?;
+        let specified_by_url = directive(&parts.directives, "specifiedBy")
+            .and_then(|specified_by| string_argument(&specified_by, "url"));
+
+        let ty = &mut self.types[index];
+        if let Some(description) = parts.description {
+            ty.description = description_text(Some(description));
+        }
+        if specified_by_url.is_some() {
+            ty.specified_by_url = specified_by_url;
+        }
+        ty.fields.extend(fields);
+        ty.input_fields.extend(input_fields);
+        ty.interfaces.extend(
+            parts
+                .interfaces
+                .iter()
+                .flat_map(|interfaces| interfaces.named_types())
+                .filter_map(|named| named_type_name(&named)),
+        );
+        ty.members.extend(
+            parts
+                .members
+                .iter()
+                .flat_map(|members| members.named_types())
+                .filter_map(|named| named_type_name(&named)),
+        );
+        ty.values.extend(
+            parts
+                .values
+                .iter()
+                .flat_map(|values| values.enum_value_definitions())
+                .filter_map(|value| {
+                    let name = value.enum_value()?.name()?.text().to_string();
+                    let (is_deprecated, deprecation_reason) = deprecation(&value.directives());
+                    Some(json!({
+                        "name": name,
+                        "description": description_text(value.description()),
+                        "isDeprecated": is_deprecated,
+                        "deprecationReason": deprecation_reason,
+                    }))
+                }),
+        );
+        Ok(())
+    }
+
+    fn add_directive(&mut self, def: cst::DirectiveDefinition) -> Result<(), RoverClientError> {
+        let args = def
+            .arguments_definition()
+            .iter()
+            .flat_map(|args| args.input_value_definitions())
+            .map(|arg| self.input_value(arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        let locations: Vec<String> = def
+            .directive_locations()
+            .iter()
+            .flat_map(|locations| locations.directive_locations())
+            .map(|location| location.syntax().text().to_string().trim().to_string())
+            .collect();
+        self.directives.push(json!({
+            "name": def.name().map(|name| name.text().to_string()),
+            "description": description_text(def.description()),
+            "locations": locations,
+            "args": args,
+            "isRepeatable": def.repeatable_token().is_some(),
+        }));
+        Ok(())
+    }
+
+    fn add_operation_types(&mut self, operation_types: Vec<cst::RootOperationTypeDefinition>) {
+        for operation_type in operation_types {
+            let Some(name) = operation_type
+                .named_type()
+                .and_then(|named| named_type_name(&named))
+            else {
+                continue;
+            };
+            match operation_type.operation_type() {
+                Some(op) if op.query_token().is_some() => self.query_type = Some(name),
+                Some(op) if op.mutation_token().is_some() => self.mutation_type = Some(name),
+                Some(op) if op.subscription_token().is_some() => {
+                    self.subscription_type = Some(name)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn field(&self, field: cst::FieldDefinition) -> Result<Value, RoverClientError> {
+        let args = field
+            .arguments_definition()
+            .iter()
+            .flat_map(|args| args.input_value_definitions())
+            .map(|arg| self.input_value(arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (is_deprecated, deprecation_reason) = deprecation(&field.directives());
+        Ok(json!({
+            "name": field.name().map(|name| name.text().to_string()),
+            "description": description_text(field.description()),
+            "args": args,
+            "type": self.type_ref(field.ty())?,
+            "isDeprecated": is_deprecated,
+            "deprecationReason": deprecation_reason,
+        }))
+    }
+
+    fn input_value(&self, value: cst::InputValueDefinition) -> Result<Value, RoverClientError> {
+        let (is_deprecated, deprecation_reason) = deprecation(&value.directives());
+        Ok(json!({
+            "name": value.name().map(|name| name.text().to_string()),
+            "description": description_text(value.description()),
+            "type": self.type_ref(value.ty())?,
+            "defaultValue": value
+                .default_value()
+                .and_then(|default| default.value())
+                .map(|default| default.syntax().text().to_string().trim().to_string()),
+            "isDeprecated": is_deprecated,
+            "deprecationReason": deprecation_reason,
+        }))
+    }
+
+    fn type_ref(&self, ty: Option<cst::Type>) -> Result<Value, RoverClientError> {
+        match ty {
+            Some(cst::Type::NamedType(named)) => self.named_type_ref(named),
+            Some(cst::Type::ListType(list)) => Ok(json!({
+                "kind": "LIST",
+                "name": null,
+                "ofType": self.type_ref(list.ty())?,
+            })),
+            Some(cst::Type::NonNullType(non_null)) => {
+                let of_type = match (non_null.named_type(), non_null.list_type()) {
+                    (Some(named), _) => self.named_type_ref(named)?,
+                    (None, Some(list)) => self.type_ref(Some(cst::Type::ListType(list)))?,
+                    (None, None) => return self.type_ref(None),
+                };
+                Ok(json!({ "kind": "NON_NULL", "name": null, "ofType": of_type }))
+            }
+            None => Err(RoverClientError::IntrospectionError {
+                msg: "Could not parse the schema: a field or argument is missing its type."
+                    .to_string(),
+            }),
+        }
+    }
+
+    fn named_type_ref(&self, named: cst::NamedType) -> Result<Value, RoverClientError> {
+        let name = named_type_name(&named).unwrap_or_default();
+        let kind = self
+            .kinds
+            .get(&name)
+            .ok_or_else(|| RoverClientError::IntrospectionError {
+                msg: format!("The schema uses the type `{}` without defining it.", name),
+            })?;
+        Ok(json!({ "kind": kind, "name": name, "ofType": null }))
+    }
+
+    fn into_json(self) -> Value {
+        // without a schema definition, the root types are the ones with the default names
+        let root = |explicit: Option<String>, default: &str| {
+            explicit
+                .or_else(|| {
+                    self.kinds
+                        .contains_key(default)
+                        .then(|| default.to_string())
+                })
+                .map(|name| json!({ "name": name }))
+        };
+        let query_type = root(self.query_type.clone(), "Query");
+        let mutation_type = root(self.mutation_type.clone(), "Mutation");
+        let subscription_type = root(self.subscription_type.clone(), "Subscription");
+        let type_ref =
+            |kind: &str, name: &str| json!({ "kind": kind, "name": name, "ofType": null });
+
+        let types: Vec<Value> = self
+            .types
+            .iter()
+            .map(|ty| {
+                let has_fields = ty.kind == "OBJECT" || ty.kind == "INTERFACE";
+                let possible_types: Option<Vec<Value>> = match ty.kind {
+                    "UNION" => Some(
+                        ty.members
+                            .iter()
+                            .map(|member| type_ref("OBJECT", member))
+                            .collect(),
+                    ),
+                    "INTERFACE" => Some(
+                        self.types
+                            .iter()
+                            .filter(|other| {
+                                other.kind == "OBJECT" && other.interfaces.contains(&ty.name)
+                            })
+                            .map(|other| type_ref("OBJECT", &other.name))
+                            .collect(),
+                    ),
+                    _ => None,
+                };
+                json!({
+                    "kind": ty.kind,
+                    "name": ty.name,
+                    "description": ty.description,
+                    "specifiedByURL": ty.specified_by_url,
+                    "fields": has_fields.then_some(&ty.fields),
+                    "inputFields": (ty.kind == "INPUT_OBJECT").then_some(&ty.input_fields),
+                    "interfaces": has_fields.then(|| {
+                        ty.interfaces
+                            .iter()
+                            .map(|interface| type_ref("INTERFACE", interface))
+                            .collect::<Vec<_>>()
+                    }),
+                    "enumValues": (ty.kind == "ENUM").then_some(&ty.values),
+                    "possibleTypes": possible_types,
+                })
+            })
+            .collect();
+
+        json!({
+            "data": {
+                "__schema": {
+                    "queryType": query_type,
+                    "mutationType": mutation_type,
+                    "subscriptionType": subscription_type,
+                    "types": types,
+                    "directives": self.directives,
+                }
+            }
+        })
+    }
+}
+
+fn named_type_name(named: &cst::NamedType) -> Option<String> {
+    named.name().map(|name| name.text().to_string())
+}
+
+fn description_text(description: Option<cst::Description>) -> Option<String> {
+    description?.string_value().map(String::from)
+}
+
+fn directive(directives: &Option<cst::Directives>, name: &str) -> Option<cst::Directive> {
+    directives
+        .iter()
+        .flat_map(|directives| directives.directives())
+        .find(|directive| {
+            directive
+                .name()
+                .is_some_and(|directive_name| directive_name.text() == name)
+        })
+}
+
+fn string_argument(directive: &cst::Directive, name: &str) -> Option<String> {
+    directive
+        .arguments()?
+        .arguments()
+        .find(|argument| {
+            argument
+                .name()
+                .is_some_and(|argument_name| argument_name.text() == name)
+        })
+        .and_then(|argument| match argument.value()? {
+            cst::Value::StringValue(value) => Some(String::from(value)),
+            _ => None,
+        })
+}
+
+/// Whether `@deprecated` is applied, and the reason it gives
+fn deprecation(directives: &Option<cst::Directives>) -> (bool, Option<String>) {
+    match directive(directives, "deprecated") {
+        Some(deprecated) => (
+            true,
+            Some(
+                string_argument(&deprecated, "reason")
+                    .unwrap_or_else(|| DEFAULT_DEPRECATION_REASON.to_string()),
+            ),
+        ),
+        None => (false, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use graphql_client::Response;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::operations::graph::introspect::types::QueryResponseData;
+    use crate::operations::graph::introspect::Schema;
+
+    const SDL: &str = indoc! { r#"
+        schema {
+          query: Root
+        }
+        "A thing that has an ID"
+        interface Node {
+          id: ID!
+        }
+        type Root {
+          "Finds a node"
+          node(id: ID!, first: Int = 10): Node
+          things: [Thing!]! @deprecated(reason: "Use node")
+        }
+        type Thing implements Node {
+          id: ID!
+          color: Color
+        }
+        extend type Thing {
+          size: Float
+        }
+        enum Color {
+          RED
+          BLUE @deprecated
+        }
+        union Result = Thing
+        input Filter {
+          color: Color = RED
+        }
+        scalar Url @specifiedBy(url: "https://example.com")
+    "#};
+
+    #[test]
+    fn it_builds_an_introspection_result() {
+        let introspection = introspection_json(SDL).unwrap();
+        let schema = &introspection["data"]["__schema"];
+        assert_eq!(schema["queryType"], json!({ "name": "Root" }));
+        assert_eq!(schema["mutationType"], Value::Null);
+
+        let find = |name: &str| {
+            schema["types"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|ty| ty["name"] == name)
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(
+            find("Node")["possibleTypes"],
+            json!([{ "kind": "OBJECT", "name": "Thing", "ofType": null }])
+        );
+        assert_eq!(find("Thing")["fields"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            find("Root")["fields"][1],
+            json!({
+                "name": "things",
+                "description": null,
+                "args": [],
+                "type": {
+                    "kind": "NON_NULL",
+                    "name": null,
+                    "ofType": {
+                        "kind": "LIST",
+                        "name": null,
+                        "ofType": {
+                            "kind": "NON_NULL",
+                            "name": null,
+                            "ofType": { "kind": "OBJECT", "name": "Thing", "ofType": null }
+                        }
+                    }
+                },
+                "isDeprecated": true,
+                "deprecationReason": "Use node",
+            })
+        );
+        assert_eq!(
+            find("Root")["fields"][0]["args"][1]["defaultValue"],
+            json!("10")
+        );
+        assert_eq!(
+            find("Color")["enumValues"][1]["deprecationReason"],
+            json!(DEFAULT_DEPRECATION_REASON)
+        );
+        assert_eq!(find("Url")["specifiedByURL"], json!("https://example.com"));
+        assert_eq!(find("String")["kind"], json!("SCALAR"));
+        assert_eq!(find("__Schema")["kind"], json!("OBJECT"));
+    }
+
+    #[test]
+    fn it_round_trips_through_the_schema_encoder() {
+        let sdl = indoc! { r#"
+            type Query {
+              "A simple type for getting started!"
+              hello: String
+              cats(cat: [String]! = ["Nori"]): [String]!
+            }
+            enum CacheControlScope {
+              PUBLIC
+              PRIVATE
+            }
+            directive @cacheControl(maxAge: Int, scope: CacheControlScope) on FIELD_DEFINITION | OBJECT | INTERFACE
+        "#};
+        let response: Response<QueryResponseData> =
+            serde_json::from_value(introspection_json(sdl).unwrap()).unwrap();
+        let schema = Schema::try_from(response.data.unwrap()).unwrap();
+        assert_eq!(
+            schema.encode(),
+            indoc! { r#"
+            type Query {
+              "A simple type for getting started!"
+              hello: String
+              cats(cat: [String]! = ["Nori"]): [String]!
+            }
+            enum CacheControlScope {
+              PUBLIC
+              PRIVATE
+            }
+            directive @cacheControl(maxAge: Int, scope: CacheControlScope) on FIELD_DEFINITION | OBJECT | INTERFACE
+            "Exposes a URL that specifies the behaviour of this scalar."
+            directive @specifiedBy(
+                "The URL that specifies the behaviour of this scalar."
+                url: String!
+              ) on SCALAR
+        "#}
+        );
+    }
+
+    #[test]
+    fn it_fails_on_undefined_types() {
+        let err = introspection_json("type Query { me: User }").unwrap_err();
+        assert!(err.to_string().contains("`User`"));
+    }
+
+    #[test]
+    fn it_minifies_sdl() {
+        let sdl = indoc! { r#"
+            # the root
+            type Query {
+              """
+              Says hello
+              """
+              hello(name: String = "you", times: Int): [String!]! @deprecated
+            }
+        "#};
+        assert_eq!(
+            minify_sdl(sdl).unwrap(),
+            r#"type Query{"Says hello"hello(name:String="you"times:Int):[String!]!@deprecated}"#
+        );
+    }
+}
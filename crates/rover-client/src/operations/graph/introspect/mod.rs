@@ -1,7 +1,9 @@
+mod from_sdl;
 mod runner;
 mod schema;
 mod types;
 
+pub use from_sdl::{introspection_json, minify_sdl};
 pub use runner::run;
 pub use schema::Schema;
 pub use types::{GraphIntrospectInput, GraphIntrospectResponse};
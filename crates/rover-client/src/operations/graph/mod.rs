@@ -16,6 +16,9 @@ pub mod introspect;
 /// "graph delete" command execution
 pub mod delete;
 
+/// "graph changelog" command execution
+pub mod changelog;
+
 /// "graph lint" command execution
 pub mod lint;
 
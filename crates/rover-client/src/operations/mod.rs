@@ -7,6 +7,9 @@ pub mod cloud;
 /// all rover-client functionality for the "graph" commands in rover
 pub mod graph;
 
+/// all rover-client functionality for the "proposal" commands in rover
+pub mod proposal;
+
 /// all rover-client functionality for the "readme" commands in rover
 pub mod readme;
 
@@ -16,6 +19,9 @@ pub mod subgraph;
 /// all rover-client functionality for the "supergraph" commands in rover
 pub mod supergraph;
 
+/// all rover-client functionality for the operation collections rover reads operations from
+pub mod operation_collection;
+
 /// all rover-client functionality for the "persisted-queries" commands in rover
 pub mod persisted_queries;
 
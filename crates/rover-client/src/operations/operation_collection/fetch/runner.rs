@@ -0,0 +1,101 @@
+use graphql_client::*;
+
+use super::types::*;
+use crate::blocking::StudioClient;
+use crate::RoverClientError;
+
+#[derive(GraphQLQuery)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/operation_collection/fetch/fetch_query.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. operation_collection_fetch_query
+pub(crate) struct OperationCollectionFetchQuery;
+
+/// Fetches the latest revision of every operation saved in an operation collection
+pub async fn run(
+    input: OperationCollectionFetchInput,
+    client: &StudioClient,
+) -> Result<OperationCollectionFetchResponse, RoverClientError> {
+    let data = client
+        .post::<OperationCollectionFetchQuery>(input.into())
+        .await?;
+    build_response(data)
+}
+
+fn build_response(
+    data: operation_collection_fetch_query::ResponseData,
+) -> Result<OperationCollectionFetchResponse, RoverClientError> {
+    use operation_collection_fetch_query::OperationCollectionFetchQueryOperationCollection::*;
+    match data.operation_collection {
+        OperationCollection(collection) => Ok(OperationCollectionFetchResponse {
+            name: collection.name,
+            operations: collection
+                .operations
+                .into_iter()
+                .map(|entry| OperationCollectionEntry {
+                    name: entry.name,
+                    body: entry.current_operation_revision.body,
+                })
+                .collect(),
+        }),
+        NotFoundError(err) => Err(RoverClientError::NotFound { msg: err.message }),
+        PermissionError(err) => Err(RoverClientError::PermissionError {
+            msg: err.message.trim_end_matches('.').to_string(),
+        }),
+        ValidationError(err) => Err(RoverClientError::AdhocError { msg: err.message }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn get_operations_from_response_data_works() {
+        let json_response = json!({
+            "operationCollection": {
+                "__typename": "OperationCollection",
+                "name": "Checkout",
+                "operations": [
+                    {
+                        "name": "GetCart",
+                        "currentOperationRevision": { "body": "query GetCart { cart { id } }" }
+                    }
+                ]
+            }
+        });
+        let data = serde_json::from_value(json_response).unwrap();
+        let output = build_response(data);
+
+        let expected_response = OperationCollectionFetchResponse {
+            name: "Checkout".to_string(),
+            operations: vec![OperationCollectionEntry {
+                name: "GetCart".to_string(),
+                body: "query GetCart { cart { id } }".to_string(),
+            }],
+        };
+        assert_eq!(output.unwrap(), expected_response);
+    }
+
+    #[test]
+    fn get_operations_from_response_data_errs_when_not_found() {
+        let json_response = json!({
+            "operationCollection": {
+                "__typename": "NotFoundError",
+                "message": "No collection with ID abc"
+            }
+        });
+        let data = serde_json::from_value(json_response).unwrap();
+        let output = build_response(data);
+        assert!(matches!(output, Err(RoverClientError::NotFound { .. })));
+    }
+}
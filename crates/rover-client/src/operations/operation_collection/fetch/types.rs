@@ -0,0 +1,29 @@
+use crate::operations::operation_collection::fetch::runner::operation_collection_fetch_query;
+
+type QueryVariables = operation_collection_fetch_query::Variables;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OperationCollectionFetchInput {
+    pub collection_id: String,
+}
+
+impl From<OperationCollectionFetchInput> for QueryVariables {
+    fn from(input: OperationCollectionFetchInput) -> Self {
+        Self {
+            collection_id: input.collection_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OperationCollectionFetchResponse {
+    pub name: String,
+    pub operations: Vec<OperationCollectionEntry>,
+}
+
+/// A saved operation in a collection, as of its latest revision
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OperationCollectionEntry {
+    pub name: String,
+    pub body: String,
+}
@@ -0,0 +1,2 @@
+/// "supergraph validate-operations --collection"
+pub mod fetch;
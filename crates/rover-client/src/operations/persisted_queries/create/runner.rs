@@ -0,0 +1,120 @@
+use crate::blocking::StudioClient;
+use crate::operations::persisted_queries::create::{
+    PersistedQueryListCreateInput, PersistedQueryListCreateResponse,
+};
+use crate::operations::persisted_queries::list::PersistedQueryListInfo;
+use crate::RoverClientError;
+use graphql_client::*;
+
+use create_persisted_query_list_mutation::CreatePersistedQueryListMutationGraphCreatePersistedQueryList as CreateResult;
+
+#[derive(GraphQLQuery, Debug)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/persisted_queries/create/create_mutation.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+pub struct CreatePersistedQueryListMutation;
+
+/// Creates a persisted query list, linking it to any variants in the input
+pub async fn run(
+    input: PersistedQueryListCreateInput,
+    client: &StudioClient,
+) -> Result<PersistedQueryListCreateResponse, RoverClientError> {
+    let graph_id = input.graph_id.clone();
+    let data = client
+        .post::<CreatePersistedQueryListMutation>(input.into())
+        .await?;
+    build_response(data, graph_id)
+}
+
+fn build_response(
+    data: create_persisted_query_list_mutation::ResponseData,
+    graph_id: String,
+) -> Result<PersistedQueryListCreateResponse, RoverClientError> {
+    let graph = data.graph.ok_or(RoverClientError::GraphIdNotFound {
+        graph_id: graph_id.clone(),
+    })?;
+
+    match graph.create_persisted_query_list {
+        CreateResult::CreatePersistedQueryListResult(result) => {
+            let list = result.persisted_query_list;
+            Ok(PersistedQueryListCreateResponse {
+                graph_id,
+                list: PersistedQueryListInfo {
+                    id: list.id,
+                    name: list.name,
+                    linked_variants: list
+                        .linked_variants
+                        .into_iter()
+                        .map(|variant| variant.name)
+                        .collect(),
+                },
+            })
+        }
+        CreateResult::PermissionError(error) => {
+            Err(RoverClientError::PermissionError { msg: error.message })
+        }
+        CreateResult::ValidationError(error) => {
+            Err(RoverClientError::AdhocError { msg: error.message })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn build_response_works() {
+        let json_response = json!({
+            "graph": {
+                "createPersistedQueryList": {
+                    "__typename": "CreatePersistedQueryListResult",
+                    "persistedQueryList": {
+                        "id": "list-id",
+                        "name": "web",
+                        "linkedVariants": [{ "name": "current" }]
+                    }
+                }
+            }
+        });
+        let data: create_persisted_query_list_mutation::ResponseData =
+            serde_json::from_value(json_response).unwrap();
+        let output = build_response(data, "mygraph".to_string()).unwrap();
+
+        assert_eq!(
+            output,
+            PersistedQueryListCreateResponse {
+                graph_id: "mygraph".to_string(),
+                list: PersistedQueryListInfo {
+                    id: "list-id".to_string(),
+                    name: "web".to_string(),
+                    linked_variants: vec!["current".to_string()],
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn build_response_errs_with_validation_error() {
+        let json_response = json!({
+            "graph": {
+                "createPersistedQueryList": {
+                    "__typename": "ValidationError",
+                    "message": "variant staging is already linked to a list"
+                }
+            }
+        });
+        let data: create_persisted_query_list_mutation::ResponseData =
+            serde_json::from_value(json_response).unwrap();
+        let output = build_response(data, "mygraph".to_string());
+
+        assert!(matches!(output, Err(RoverClientError::AdhocError { .. })));
+    }
+}
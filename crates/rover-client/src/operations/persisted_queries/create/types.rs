@@ -0,0 +1,29 @@
+use serde::Serialize;
+
+use crate::operations::persisted_queries::create::runner::create_persisted_query_list_mutation;
+use crate::operations::persisted_queries::list::PersistedQueryListInfo;
+
+type MutationVariables = create_persisted_query_list_mutation::Variables;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PersistedQueryListCreateInput {
+    pub graph_id: String,
+    pub name: String,
+    pub linked_variants: Vec<String>,
+}
+
+impl From<PersistedQueryListCreateInput> for MutationVariables {
+    fn from(input: PersistedQueryListCreateInput) -> Self {
+        Self {
+            graph_id: input.graph_id,
+            name: input.name,
+            linked_variant_names: Some(input.linked_variants),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PersistedQueryListCreateResponse {
+    pub graph_id: String,
+    pub list: PersistedQueryListInfo,
+}
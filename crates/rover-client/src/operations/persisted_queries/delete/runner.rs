@@ -0,0 +1,102 @@
+use crate::blocking::StudioClient;
+use crate::operations::persisted_queries::delete::{
+    PersistedQueryListDeleteInput, PersistedQueryListDeleteResponse,
+};
+use crate::RoverClientError;
+use graphql_client::*;
+
+use delete_persisted_query_list_mutation::DeletePersistedQueryListMutationGraphPersistedQueryListDelete as DeleteResult;
+
+#[derive(GraphQLQuery, Debug)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/persisted_queries/delete/delete_mutation.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+pub struct DeletePersistedQueryListMutation;
+
+/// Deletes a persisted query list that isn't linked to any variants
+pub async fn run(
+    input: PersistedQueryListDeleteInput,
+    client: &StudioClient,
+) -> Result<PersistedQueryListDeleteResponse, RoverClientError> {
+    let graph_id = input.graph_id.clone();
+    let list_id = input.list_id.clone();
+    let data = client
+        .post::<DeletePersistedQueryListMutation>(input.into())
+        .await?;
+    build_response(data, graph_id, list_id)
+}
+
+fn build_response(
+    data: delete_persisted_query_list_mutation::ResponseData,
+    graph_id: String,
+    list_id: String,
+) -> Result<PersistedQueryListDeleteResponse, RoverClientError> {
+    let graph = data.graph.ok_or(RoverClientError::GraphIdNotFound {
+        graph_id: graph_id.clone(),
+    })?;
+
+    match graph.persisted_query_list.delete {
+        DeleteResult::DeletePersistedQueryListResult => {
+            Ok(PersistedQueryListDeleteResponse { graph_id, list_id })
+        }
+        DeleteResult::PermissionError(error) => {
+            Err(RoverClientError::PermissionError { msg: error.message })
+        }
+        DeleteResult::CannotDeletePersistedQueryListLinkedToVariantsError(error) => {
+            Err(RoverClientError::AdhocError { msg: error.message })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn build_response_works() {
+        let json_response = json!({
+            "graph": {
+                "persistedQueryList": {
+                    "delete": { "__typename": "DeletePersistedQueryListResult" }
+                }
+            }
+        });
+        let data: delete_persisted_query_list_mutation::ResponseData =
+            serde_json::from_value(json_response).unwrap();
+        let output = build_response(data, "mygraph".to_string(), "list-id".to_string());
+
+        assert_eq!(
+            output.unwrap(),
+            PersistedQueryListDeleteResponse {
+                graph_id: "mygraph".to_string(),
+                list_id: "list-id".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn build_response_errs_when_list_is_linked() {
+        let json_response = json!({
+            "graph": {
+                "persistedQueryList": {
+                    "delete": {
+                        "__typename": "CannotDeletePersistedQueryListLinkedToVariantsError",
+                        "message": "unlink the list from current before deleting it"
+                    }
+                }
+            }
+        });
+        let data: delete_persisted_query_list_mutation::ResponseData =
+            serde_json::from_value(json_response).unwrap();
+        let output = build_response(data, "mygraph".to_string(), "list-id".to_string());
+
+        assert!(matches!(output, Err(RoverClientError::AdhocError { .. })));
+    }
+}
@@ -0,0 +1,26 @@
+use serde::Serialize;
+
+use crate::operations::persisted_queries::delete::runner::delete_persisted_query_list_mutation;
+
+type MutationVariables = delete_persisted_query_list_mutation::Variables;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PersistedQueryListDeleteInput {
+    pub graph_id: String,
+    pub list_id: String,
+}
+
+impl From<PersistedQueryListDeleteInput> for MutationVariables {
+    fn from(input: PersistedQueryListDeleteInput) -> Self {
+        Self {
+            graph_id: input.graph_id,
+            list_id: input.list_id,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PersistedQueryListDeleteResponse {
+    pub graph_id: String,
+    pub list_id: String,
+}
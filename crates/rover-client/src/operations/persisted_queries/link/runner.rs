@@ -0,0 +1,115 @@
+use crate::blocking::StudioClient;
+use crate::operations::persisted_queries::link::{
+    PersistedQueryListLinkInput, PersistedQueryListLinkResponse,
+};
+use crate::shared::GraphRef;
+use crate::RoverClientError;
+use graphql_client::*;
+
+use link_persisted_query_list_mutation::LinkPersistedQueryListMutationGraphVariantLinkPersistedQueryList as LinkResult;
+
+#[derive(GraphQLQuery, Debug)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/persisted_queries/link/link_mutation.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+pub struct LinkPersistedQueryListMutation;
+
+/// Links a persisted query list to a graph variant
+pub async fn run(
+    input: PersistedQueryListLinkInput,
+    client: &StudioClient,
+) -> Result<PersistedQueryListLinkResponse, RoverClientError> {
+    let graph_ref = input.graph_ref.clone();
+    let list_id = input.list_id.clone();
+    let data = client
+        .post::<LinkPersistedQueryListMutation>(input.into())
+        .await?;
+    build_response(data, graph_ref, list_id)
+}
+
+fn build_response(
+    data: link_persisted_query_list_mutation::ResponseData,
+    graph_ref: GraphRef,
+    list_id: String,
+) -> Result<PersistedQueryListLinkResponse, RoverClientError> {
+    let graph = data.graph.ok_or(RoverClientError::GraphIdNotFound {
+        graph_id: graph_ref.name.clone(),
+    })?;
+    let variant = graph.variant.ok_or(RoverClientError::GraphNotFound {
+        graph_ref: graph_ref.clone(),
+    })?;
+
+    match variant.link_persisted_query_list {
+        LinkResult::LinkPersistedQueryListResult(result) => Ok(PersistedQueryListLinkResponse {
+            graph_ref,
+            list_id,
+            list_name: result.persisted_query_list.name,
+        }),
+        LinkResult::PermissionError(error) => {
+            Err(RoverClientError::PermissionError { msg: error.message })
+        }
+        LinkResult::ListNotFoundError(error) => {
+            Err(RoverClientError::AdhocError { msg: error.message })
+        }
+        LinkResult::VariantAlreadyLinkedError(error) => {
+            Err(RoverClientError::AdhocError { msg: error.message })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn build_response_works() {
+        let json_response = json!({
+            "graph": {
+                "variant": {
+                    "linkPersistedQueryList": {
+                        "__typename": "LinkPersistedQueryListResult",
+                        "persistedQueryList": { "name": "web" }
+                    }
+                }
+            }
+        });
+        let data: link_persisted_query_list_mutation::ResponseData =
+            serde_json::from_value(json_response).unwrap();
+        let output = build_response(data, mock_graph_ref(), "list-id".to_string()).unwrap();
+
+        assert_eq!(output.list_name, "web");
+    }
+
+    #[test]
+    fn build_response_errs_when_variant_is_already_linked() {
+        let json_response = json!({
+            "graph": {
+                "variant": {
+                    "linkPersistedQueryList": {
+                        "__typename": "VariantAlreadyLinkedError",
+                        "message": "current is already linked to a persisted query list"
+                    }
+                }
+            }
+        });
+        let data: link_persisted_query_list_mutation::ResponseData =
+            serde_json::from_value(json_response).unwrap();
+        let output = build_response(data, mock_graph_ref(), "list-id".to_string());
+
+        assert!(matches!(output, Err(RoverClientError::AdhocError { .. })));
+    }
+
+    fn mock_graph_ref() -> GraphRef {
+        GraphRef {
+            name: "mygraph".to_string(),
+            variant: "current".to_string(),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use serde::Serialize;
+
+use crate::operations::persisted_queries::link::runner::link_persisted_query_list_mutation;
+use crate::shared::GraphRef;
+
+type MutationVariables = link_persisted_query_list_mutation::Variables;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PersistedQueryListLinkInput {
+    pub graph_ref: GraphRef,
+    pub list_id: String,
+}
+
+impl From<PersistedQueryListLinkInput> for MutationVariables {
+    fn from(input: PersistedQueryListLinkInput) -> Self {
+        Self {
+            graph_id: input.graph_ref.name,
+            variant: input.graph_ref.variant,
+            list_id: input.list_id,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PersistedQueryListLinkResponse {
+    #[serde(skip_serializing)]
+    pub graph_ref: GraphRef,
+    pub list_id: String,
+    pub list_name: String,
+}
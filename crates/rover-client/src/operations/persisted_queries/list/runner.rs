@@ -0,0 +1,119 @@
+use crate::blocking::StudioClient;
+use crate::operations::persisted_queries::list::{
+    PersistedQueryListInfo, PersistedQueryListsInput, PersistedQueryListsResponse,
+};
+use crate::RoverClientError;
+use graphql_client::*;
+
+#[derive(GraphQLQuery, Debug)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/persisted_queries/list/list_query.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+pub struct PersistedQueryListsQuery;
+
+/// Fetches every persisted query list that belongs to a graph
+pub async fn run(
+    input: PersistedQueryListsInput,
+    client: &StudioClient,
+) -> Result<PersistedQueryListsResponse, RoverClientError> {
+    let graph_id = input.graph_id.clone();
+    let data = client
+        .post::<PersistedQueryListsQuery>(input.into())
+        .await?;
+    build_response(data, graph_id)
+}
+
+fn build_response(
+    data: persisted_query_lists_query::ResponseData,
+    graph_id: String,
+) -> Result<PersistedQueryListsResponse, RoverClientError> {
+    let graph = data.graph.ok_or(RoverClientError::GraphIdNotFound {
+        graph_id: graph_id.clone(),
+    })?;
+
+    let lists = graph
+        .persisted_query_lists
+        .unwrap_or_default()
+        .into_iter()
+        .map(|list| PersistedQueryListInfo {
+            id: list.id,
+            name: list.name,
+            linked_variants: list
+                .linked_variants
+                .into_iter()
+                .map(|variant| variant.name)
+                .collect(),
+        })
+        .collect();
+
+    Ok(PersistedQueryListsResponse {
+        graph_id,
+        lists,
+        root_url: data.frontend_url_root,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn build_response_works() {
+        let json_response = json!({
+            "frontendUrlRoot": "https://studio.apollographql.com/",
+            "graph": {
+                "persistedQueryLists": [
+                    {
+                        "id": "list-id",
+                        "name": "web",
+                        "linkedVariants": [{ "name": "current" }, { "name": "staging" }]
+                    },
+                    {
+                        "id": "other-list-id",
+                        "name": "ios",
+                        "linkedVariants": []
+                    }
+                ]
+            }
+        });
+        let data: persisted_query_lists_query::ResponseData =
+            serde_json::from_value(json_response).unwrap();
+        let output = build_response(data, "mygraph".to_string()).unwrap();
+
+        assert_eq!(
+            output.lists,
+            vec![
+                PersistedQueryListInfo {
+                    id: "list-id".to_string(),
+                    name: "web".to_string(),
+                    linked_variants: vec!["current".to_string(), "staging".to_string()],
+                },
+                PersistedQueryListInfo {
+                    id: "other-list-id".to_string(),
+                    name: "ios".to_string(),
+                    linked_variants: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn build_response_errs_with_no_graph() {
+        let json_response = json!({
+            "frontendUrlRoot": "https://studio.apollographql.com/",
+            "graph": null
+        });
+        let data: persisted_query_lists_query::ResponseData =
+            serde_json::from_value(json_response).unwrap();
+        let output = build_response(data, "mygraph".to_string());
+
+        assert!(output.is_err());
+    }
+}
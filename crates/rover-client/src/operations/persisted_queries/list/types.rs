@@ -0,0 +1,34 @@
+use serde::Serialize;
+
+use crate::operations::persisted_queries::list::runner::persisted_query_lists_query;
+
+type QueryVariables = persisted_query_lists_query::Variables;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PersistedQueryListsInput {
+    pub graph_id: String,
+}
+
+impl From<PersistedQueryListsInput> for QueryVariables {
+    fn from(input: PersistedQueryListsInput) -> Self {
+        Self {
+            graph_id: input.graph_id,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PersistedQueryListInfo {
+    pub id: String,
+    pub name: String,
+    pub linked_variants: Vec<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PersistedQueryListsResponse {
+    #[serde(skip_serializing)]
+    pub graph_id: String,
+    pub lists: Vec<PersistedQueryListInfo>,
+    #[serde(skip_serializing)]
+    pub root_url: String,
+}
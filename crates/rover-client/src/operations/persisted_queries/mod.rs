@@ -1,3 +1,7 @@
+pub mod create;
+pub mod delete;
+pub mod link;
+pub mod list;
 pub mod name;
 pub mod publish;
 pub mod resolve;
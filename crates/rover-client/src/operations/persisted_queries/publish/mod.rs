@@ -4,6 +4,6 @@ mod types;
 pub use runner::run;
 pub use types::{
     ApolloPersistedQueryManifest, PersistedQueriesOperationCounts, PersistedQueriesPublishInput,
-    PersistedQueriesPublishResponse, PersistedQueryPublishOperationResult,
-    RelayPersistedQueryManifest,
+    PersistedQueriesPublishResponse, PersistedQueryOperation, PersistedQueryOperationType,
+    PersistedQueryPublishOperationResult, RelayPersistedQueryManifest,
 };
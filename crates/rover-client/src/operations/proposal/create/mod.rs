@@ -0,0 +1,5 @@
+mod runner;
+mod types;
+
+pub use runner::run;
+pub use types::{ProposalCreateInput, ProposalCreateResponse};
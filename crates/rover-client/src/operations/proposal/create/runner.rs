@@ -0,0 +1,146 @@
+use graphql_client::*;
+
+use crate::blocking::StudioClient;
+use crate::operations::proposal::create::types::*;
+use crate::shared::{GraphRef, ProposalInfo, ProposalStatus};
+use crate::RoverClientError;
+
+#[derive(GraphQLQuery)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/proposal/create/create_mutation.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. proposal_create_mutation
+pub(crate) struct ProposalCreateMutation;
+
+/// Opens a new, empty schema proposal against a graph variant
+pub async fn run(
+    input: ProposalCreateInput,
+    client: &StudioClient,
+) -> Result<ProposalCreateResponse, RoverClientError> {
+    let graph_ref = input.graph_ref.clone();
+    let response_data = client.post::<ProposalCreateMutation>(input.into()).await?;
+    get_create_response_from_response_data(response_data, graph_ref)
+}
+
+fn get_create_response_from_response_data(
+    response_data: MutationResponseData,
+    graph_ref: GraphRef,
+) -> Result<ProposalCreateResponse, RoverClientError> {
+    let graph = response_data.graph.ok_or(RoverClientError::GraphNotFound {
+        graph_ref: graph_ref.clone(),
+    })?;
+
+    let variant = match graph.create_proposal {
+        MutationCreateProposalResult::GraphVariant(variant) => Ok(variant),
+        MutationCreateProposalResult::PermissionError(error) => {
+            Err(RoverClientError::PermissionError { msg: error.message })
+        }
+        MutationCreateProposalResult::CreateProposalError(error) => {
+            Err(RoverClientError::ProposalError { msg: error.message })
+        }
+        MutationCreateProposalResult::ValidationError(error) => {
+            Err(RoverClientError::ProposalError { msg: error.message })
+        }
+    }?;
+
+    let proposal = variant
+        .proposal
+        .ok_or(RoverClientError::MalformedResponse {
+            null_field: "proposal".to_string(),
+        })?;
+
+    Ok(ProposalCreateResponse {
+        proposal: ProposalInfo {
+            id: proposal.id,
+            display_name: proposal.display_name,
+            status: proposal_status(proposal.status)?,
+            source_variant: graph_ref.variant,
+        },
+    })
+}
+
+fn proposal_status(status: MutationProposalStatus) -> Result<ProposalStatus, RoverClientError> {
+    match status {
+        MutationProposalStatus::APPROVED => Ok(ProposalStatus::APPROVED),
+        MutationProposalStatus::CLOSED => Ok(ProposalStatus::CLOSED),
+        MutationProposalStatus::DRAFT => Ok(ProposalStatus::DRAFT),
+        MutationProposalStatus::IMPLEMENTED => Ok(ProposalStatus::IMPLEMENTED),
+        MutationProposalStatus::OPEN => Ok(ProposalStatus::OPEN),
+        MutationProposalStatus::Other(status) => Err(RoverClientError::AdhocError {
+            msg: format!(
+                "Apollo Studio reported an unknown proposal status \"{}\"",
+                status
+            ),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn get_create_response_from_response_data_works() {
+        let json_response = json!({
+            "graph": {
+                "createProposal": {
+                    "__typename": "GraphVariant",
+                    "proposal": {
+                        "id": "proposal-id",
+                        "displayName": "Add reviews",
+                        "status": "DRAFT"
+                    }
+                }
+            }
+        });
+        let data: MutationResponseData = serde_json::from_value(json_response).unwrap();
+        let output = get_create_response_from_response_data(data, mock_graph_ref());
+
+        assert_eq!(
+            output.unwrap(),
+            ProposalCreateResponse {
+                proposal: ProposalInfo {
+                    id: "proposal-id".to_string(),
+                    display_name: "Add reviews".to_string(),
+                    status: ProposalStatus::DRAFT,
+                    source_variant: "current".to_string(),
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn get_create_response_from_response_data_errs_with_create_error() {
+        let json_response = json!({
+            "graph": {
+                "createProposal": {
+                    "__typename": "CreateProposalError",
+                    "message": "proposals are not enabled for this graph"
+                }
+            }
+        });
+        let data: MutationResponseData = serde_json::from_value(json_response).unwrap();
+        let output = get_create_response_from_response_data(data, mock_graph_ref());
+
+        assert!(matches!(
+            output,
+            Err(RoverClientError::ProposalError { msg }) if msg == "proposals are not enabled for this graph"
+        ));
+    }
+
+    fn mock_graph_ref() -> GraphRef {
+        GraphRef {
+            name: "mygraph".to_string(),
+            variant: "current".to_string(),
+        }
+    }
+}
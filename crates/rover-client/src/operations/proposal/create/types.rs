@@ -0,0 +1,34 @@
+use crate::operations::proposal::create::runner::proposal_create_mutation;
+use crate::shared::{GraphRef, ProposalInfo};
+
+pub(crate) type MutationCreateProposalResult =
+    proposal_create_mutation::ProposalCreateMutationGraphCreateProposal;
+pub(crate) type MutationResponseData = proposal_create_mutation::ResponseData;
+pub(crate) type MutationProposalStatus = proposal_create_mutation::ProposalStatus;
+
+type MutationVariables = proposal_create_mutation::Variables;
+
+use serde::Serialize;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ProposalCreateInput {
+    pub graph_ref: GraphRef,
+    pub display_name: String,
+    pub description: Option<String>,
+}
+
+impl From<ProposalCreateInput> for MutationVariables {
+    fn from(input: ProposalCreateInput) -> Self {
+        Self {
+            graph_id: input.graph_ref.name,
+            source_variant: input.graph_ref.variant,
+            display_name: input.display_name,
+            description: input.description,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Eq, PartialEq, Debug)]
+pub struct ProposalCreateResponse {
+    pub proposal: ProposalInfo,
+}
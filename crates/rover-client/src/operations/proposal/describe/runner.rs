@@ -0,0 +1,121 @@
+use graphql_client::*;
+
+use crate::blocking::StudioClient;
+use crate::operations::proposal::describe::types::*;
+use crate::shared::{ProposalInfo, ProposalStatus};
+use crate::RoverClientError;
+
+#[derive(GraphQLQuery)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/proposal/describe/describe_query.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. proposal_describe_query
+pub(crate) struct ProposalDescribeQuery;
+
+/// Fetches the status of a proposal and the launch a new revision of it has to build on
+pub async fn run(
+    input: ProposalDescribeInput,
+    client: &StudioClient,
+) -> Result<ProposalDescribeResponse, RoverClientError> {
+    let proposal_id = input.proposal_id.clone();
+    let response_data = client.post::<ProposalDescribeQuery>(input.into()).await?;
+    get_describe_response_from_response_data(response_data, proposal_id)
+}
+
+fn get_describe_response_from_response_data(
+    response_data: QueryResponseData,
+    proposal_id: String,
+) -> Result<ProposalDescribeResponse, RoverClientError> {
+    let proposal = response_data
+        .proposal
+        .ok_or(RoverClientError::ProposalNotFound { proposal_id })?;
+
+    Ok(ProposalDescribeResponse {
+        graph_id: proposal.source_variant.graph.id,
+        latest_launch_id: proposal
+            .backing_variant
+            .latest_launch
+            .map(|launch| launch.id),
+        proposal: ProposalInfo {
+            id: proposal.id,
+            display_name: proposal.display_name,
+            status: proposal_status(proposal.status)?,
+            source_variant: proposal.source_variant.name,
+        },
+        root_url: response_data.frontend_url_root,
+    })
+}
+
+fn proposal_status(status: QueryProposalStatus) -> Result<ProposalStatus, RoverClientError> {
+    match status {
+        QueryProposalStatus::APPROVED => Ok(ProposalStatus::APPROVED),
+        QueryProposalStatus::CLOSED => Ok(ProposalStatus::CLOSED),
+        QueryProposalStatus::DRAFT => Ok(ProposalStatus::DRAFT),
+        QueryProposalStatus::IMPLEMENTED => Ok(ProposalStatus::IMPLEMENTED),
+        QueryProposalStatus::OPEN => Ok(ProposalStatus::OPEN),
+        QueryProposalStatus::Other(status) => Err(RoverClientError::AdhocError {
+            msg: format!(
+                "Apollo Studio reported an unknown proposal status \"{}\"",
+                status
+            ),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn get_describe_response_from_response_data_works() {
+        let json_response = json!({
+            "frontendUrlRoot": "https://studio.apollographql.com/",
+            "proposal": {
+                "id": "proposal-id",
+                "displayName": "Add reviews",
+                "status": "OPEN",
+                "sourceVariant": {
+                    "name": "current",
+                    "graph": { "id": "mygraph" }
+                },
+                "backingVariant": {
+                    "latestLaunch": { "id": "launch-id" }
+                }
+            }
+        });
+        let data: QueryResponseData = serde_json::from_value(json_response).unwrap();
+        let output =
+            get_describe_response_from_response_data(data, "proposal-id".to_string()).unwrap();
+
+        assert_eq!(output.proposal.status, ProposalStatus::OPEN);
+        assert_eq!(output.latest_launch_id, Some("launch-id".to_string()));
+        assert_eq!(
+            output.url(),
+            "https://studio.apollographql.com/graph/mygraph/proposals/proposal-id"
+        );
+    }
+
+    #[test]
+    fn get_describe_response_from_response_data_errs_with_no_proposal() {
+        let json_response = json!({
+            "frontendUrlRoot": "https://studio.apollographql.com/",
+            "proposal": null
+        });
+        let data: QueryResponseData = serde_json::from_value(json_response).unwrap();
+        let output = get_describe_response_from_response_data(data, "proposal-id".to_string());
+
+        assert!(matches!(
+            output,
+            Err(RoverClientError::ProposalNotFound { proposal_id }) if proposal_id == "proposal-id"
+        ));
+    }
+}
@@ -0,0 +1,48 @@
+use crate::operations::proposal::describe::runner::proposal_describe_query;
+use crate::shared::ProposalInfo;
+
+pub(crate) type QueryResponseData = proposal_describe_query::ResponseData;
+pub(crate) type QueryProposalStatus = proposal_describe_query::ProposalStatus;
+
+type QueryVariables = proposal_describe_query::Variables;
+
+use serde::Serialize;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ProposalDescribeInput {
+    pub proposal_id: String,
+}
+
+impl From<ProposalDescribeInput> for QueryVariables {
+    fn from(input: ProposalDescribeInput) -> Self {
+        Self {
+            proposal_id: input.proposal_id,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Eq, PartialEq, Debug)]
+pub struct ProposalDescribeResponse {
+    pub proposal: ProposalInfo,
+
+    pub graph_id: String,
+
+    /// The most recent launch of the variant that backs the proposal, which a
+    /// new revision of the proposal has to build on
+    pub latest_launch_id: Option<String>,
+
+    #[serde(skip_serializing)]
+    pub root_url: String,
+}
+
+impl ProposalDescribeResponse {
+    /// The page for this proposal in Apollo Studio
+    pub fn url(&self) -> String {
+        format!(
+            "{}/graph/{}/proposals/{}",
+            self.root_url.trim_end_matches('/'),
+            self.graph_id,
+            self.proposal.id
+        )
+    }
+}
@@ -0,0 +1,142 @@
+use graphql_client::*;
+
+use crate::blocking::StudioClient;
+use crate::operations::proposal::list::types::*;
+use crate::shared::{GraphRef, ProposalInfo, ProposalStatus};
+use crate::RoverClientError;
+
+#[derive(GraphQLQuery)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/proposal/list/list_query.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. proposal_list_query
+pub(crate) struct ProposalListQuery;
+
+/// Fetches the proposals opened against a graph variant
+pub async fn run(
+    input: ProposalListInput,
+    client: &StudioClient,
+) -> Result<ProposalListResponse, RoverClientError> {
+    let graph_ref = input.graph_ref.clone();
+    let response_data = client.post::<ProposalListQuery>(input.into()).await?;
+    get_list_response_from_response_data(response_data, graph_ref)
+}
+
+fn get_list_response_from_response_data(
+    response_data: QueryResponseData,
+    graph_ref: GraphRef,
+) -> Result<ProposalListResponse, RoverClientError> {
+    let graph = response_data.graph.ok_or(RoverClientError::GraphNotFound {
+        graph_ref: graph_ref.clone(),
+    })?;
+
+    let proposals = graph
+        .proposals
+        .proposals
+        .into_iter()
+        .map(format_proposal)
+        .collect::<Result<_, _>>()?;
+
+    Ok(ProposalListResponse {
+        proposals,
+        total_count: graph.proposals.total_count,
+        root_url: response_data.frontend_url_root,
+        graph_ref,
+    })
+}
+
+fn format_proposal(proposal: QueryProposalInfo) -> Result<ProposalInfo, RoverClientError> {
+    let status = match proposal.status {
+        QueryProposalStatus::APPROVED => ProposalStatus::APPROVED,
+        QueryProposalStatus::CLOSED => ProposalStatus::CLOSED,
+        QueryProposalStatus::DRAFT => ProposalStatus::DRAFT,
+        QueryProposalStatus::IMPLEMENTED => ProposalStatus::IMPLEMENTED,
+        QueryProposalStatus::OPEN => ProposalStatus::OPEN,
+        QueryProposalStatus::Other(status) => {
+            return Err(RoverClientError::AdhocError {
+                msg: format!(
+                    "Apollo Studio reported an unknown proposal status \"{}\"",
+                    status
+                ),
+            })
+        }
+    };
+    Ok(ProposalInfo {
+        id: proposal.id,
+        display_name: proposal.display_name,
+        status,
+        source_variant: proposal.source_variant.name,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn get_list_response_from_response_data_works() {
+        let json_response = json!({
+            "frontendUrlRoot": "https://studio.apollographql.com/",
+            "graph": {
+                "proposals": {
+                    "totalCount": 2,
+                    "proposals": [
+                        {
+                            "id": "first",
+                            "displayName": "Add reviews",
+                            "status": "APPROVED",
+                            "sourceVariant": { "name": "current" }
+                        },
+                        {
+                            "id": "second",
+                            "displayName": "Remove legacy fields",
+                            "status": "OPEN",
+                            "sourceVariant": { "name": "current" }
+                        }
+                    ]
+                }
+            }
+        });
+        let data: QueryResponseData = serde_json::from_value(json_response).unwrap();
+        let output = get_list_response_from_response_data(data, mock_graph_ref()).unwrap();
+
+        assert_eq!(output.total_count, 2);
+        assert_eq!(
+            output.proposals[1],
+            ProposalInfo {
+                id: "second".to_string(),
+                display_name: "Remove legacy fields".to_string(),
+                status: ProposalStatus::OPEN,
+                source_variant: "current".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn get_list_response_from_response_data_errs_with_no_graph() {
+        let json_response = json!({
+            "frontendUrlRoot": "https://studio.apollographql.com/",
+            "graph": null
+        });
+        let data: QueryResponseData = serde_json::from_value(json_response).unwrap();
+        let output = get_list_response_from_response_data(data, mock_graph_ref());
+
+        assert!(output.is_err());
+    }
+
+    fn mock_graph_ref() -> GraphRef {
+        GraphRef {
+            name: "mygraph".to_string(),
+            variant: "current".to_string(),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::operations::proposal::list::runner::proposal_list_query;
+use crate::shared::{GraphRef, ProposalInfo, ProposalStatus};
+
+pub(crate) type QueryProposalInfo = proposal_list_query::ProposalListQueryGraphProposalsProposals;
+pub(crate) type QueryResponseData = proposal_list_query::ResponseData;
+pub(crate) type QueryProposalStatus = proposal_list_query::ProposalStatus;
+
+type QueryVariables = proposal_list_query::Variables;
+
+use serde::Serialize;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ProposalListInput {
+    pub graph_ref: GraphRef,
+    pub statuses: Vec<ProposalStatus>,
+    pub limit: Option<i64>,
+}
+
+impl From<ProposalListInput> for QueryVariables {
+    fn from(input: ProposalListInput) -> Self {
+        let status = if input.statuses.is_empty() {
+            None
+        } else {
+            Some(
+                input
+                    .statuses
+                    .into_iter()
+                    .map(|status| match status {
+                        ProposalStatus::APPROVED => QueryProposalStatus::APPROVED,
+                        ProposalStatus::CLOSED => QueryProposalStatus::CLOSED,
+                        ProposalStatus::DRAFT => QueryProposalStatus::DRAFT,
+                        ProposalStatus::IMPLEMENTED => QueryProposalStatus::IMPLEMENTED,
+                        ProposalStatus::OPEN => QueryProposalStatus::OPEN,
+                    })
+                    .collect(),
+            )
+        };
+        Self {
+            graph_id: input.graph_ref.name,
+            source_variant: input.graph_ref.variant,
+            status,
+            limit: input.limit,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Eq, PartialEq, Debug)]
+pub struct ProposalListResponse {
+    pub proposals: Vec<ProposalInfo>,
+
+    pub total_count: i64,
+
+    #[serde(skip_serializing)]
+    pub root_url: String,
+
+    #[serde(skip_serializing)]
+    pub graph_ref: GraphRef,
+}
@@ -0,0 +1,11 @@
+/// "proposal create" command execution
+pub mod create;
+
+/// fetches the details of a single proposal
+pub mod describe;
+
+/// "proposal list" command execution
+pub mod list;
+
+/// publishes a revision of subgraph schemas to a proposal
+pub mod publish;
@@ -0,0 +1,159 @@
+use graphql_client::*;
+
+use crate::blocking::StudioClient;
+use crate::operations::proposal::describe::{self, ProposalDescribeInput};
+use crate::operations::proposal::publish::types::*;
+use crate::shared::ProposalStatus;
+use crate::RoverClientError;
+
+#[derive(GraphQLQuery)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/proposal/publish/publish_mutation.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. proposal_publish_mutation
+pub(crate) struct ProposalPublishMutation;
+
+/// Publishes a subgraph schema as a new revision of a proposal
+pub async fn run(
+    input: ProposalPublishInput,
+    client: &StudioClient,
+) -> Result<ProposalPublishResponse, RoverClientError> {
+    // every revision of a proposal has to name the launch it builds on, so
+    // look up the latest launch of the variant that backs the proposal first
+    let mut describe_response = describe::run(
+        ProposalDescribeInput {
+            proposal_id: input.proposal_id.clone(),
+        },
+        client,
+    )
+    .await?;
+    let previous_launch_id =
+        describe_response
+            .latest_launch_id
+            .clone()
+            .ok_or(RoverClientError::MalformedResponse {
+                null_field: "latestLaunch".to_string(),
+            })?;
+
+    let proposal_id = input.proposal_id.clone();
+    let response_data = client
+        .post::<ProposalPublishMutation>(input.into_variables(previous_launch_id))
+        .await?;
+    describe_response.proposal.status = get_status_from_response_data(response_data, proposal_id)?;
+
+    Ok(ProposalPublishResponse {
+        proposal_url: describe_response.url(),
+        proposal: describe_response.proposal,
+    })
+}
+
+fn get_status_from_response_data(
+    response_data: MutationResponseData,
+    proposal_id: String,
+) -> Result<ProposalStatus, RoverClientError> {
+    let proposal = match response_data.proposal {
+        MutationProposalResult::ProposalMutation(proposal) => Ok(proposal),
+        MutationProposalResult::NotFoundError(_) => {
+            Err(RoverClientError::ProposalNotFound { proposal_id })
+        }
+        MutationProposalResult::PermissionError(error) => {
+            Err(RoverClientError::PermissionError { msg: error.message })
+        }
+        MutationProposalResult::ValidationError(error) => {
+            Err(RoverClientError::ProposalError { msg: error.message })
+        }
+    }?;
+
+    match proposal.publish_subgraphs {
+        MutationPublishResult::Proposal(proposal) => match proposal.status {
+            MutationProposalStatus::APPROVED => Ok(ProposalStatus::APPROVED),
+            MutationProposalStatus::CLOSED => Ok(ProposalStatus::CLOSED),
+            MutationProposalStatus::DRAFT => Ok(ProposalStatus::DRAFT),
+            MutationProposalStatus::IMPLEMENTED => Ok(ProposalStatus::IMPLEMENTED),
+            MutationProposalStatus::OPEN => Ok(ProposalStatus::OPEN),
+            MutationProposalStatus::Other(status) => Err(RoverClientError::AdhocError {
+                msg: format!(
+                    "Apollo Studio reported an unknown proposal status \"{}\"",
+                    status
+                ),
+            }),
+        },
+        MutationPublishResult::NotFoundError(error) => {
+            Err(RoverClientError::ProposalError { msg: error.message })
+        }
+        MutationPublishResult::PermissionError(error) => {
+            Err(RoverClientError::PermissionError { msg: error.message })
+        }
+        MutationPublishResult::ValidationError(error) => {
+            Err(RoverClientError::ProposalError { msg: error.message })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn get_status_from_response_data_works() {
+        let json_response = json!({
+            "proposal": {
+                "__typename": "ProposalMutation",
+                "publishSubgraphs": {
+                    "__typename": "Proposal",
+                    "status": "OPEN"
+                }
+            }
+        });
+        let data: MutationResponseData = serde_json::from_value(json_response).unwrap();
+        let output = get_status_from_response_data(data, "proposal-id".to_string());
+
+        assert_eq!(output.unwrap(), ProposalStatus::OPEN);
+    }
+
+    #[test]
+    fn get_status_from_response_data_errs_with_unknown_proposal() {
+        let json_response = json!({
+            "proposal": {
+                "__typename": "NotFoundError",
+                "message": "not found"
+            }
+        });
+        let data: MutationResponseData = serde_json::from_value(json_response).unwrap();
+        let output = get_status_from_response_data(data, "proposal-id".to_string());
+
+        assert!(matches!(
+            output,
+            Err(RoverClientError::ProposalNotFound { proposal_id }) if proposal_id == "proposal-id"
+        ));
+    }
+
+    #[test]
+    fn get_status_from_response_data_errs_with_validation_error() {
+        let json_response = json!({
+            "proposal": {
+                "__typename": "ProposalMutation",
+                "publishSubgraphs": {
+                    "__typename": "ValidationError",
+                    "message": "the schema is invalid"
+                }
+            }
+        });
+        let data: MutationResponseData = serde_json::from_value(json_response).unwrap();
+        let output = get_status_from_response_data(data, "proposal-id".to_string());
+
+        assert!(matches!(
+            output,
+            Err(RoverClientError::ProposalError { msg }) if msg == "the schema is invalid"
+        ));
+    }
+}
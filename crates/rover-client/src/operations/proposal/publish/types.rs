@@ -0,0 +1,60 @@
+use crate::operations::proposal::publish::runner::proposal_publish_mutation;
+use crate::shared::{GitContext, ProposalInfo};
+
+pub(crate) type MutationProposalResult = proposal_publish_mutation::ProposalPublishMutationProposal;
+pub(crate) type MutationPublishResult =
+    proposal_publish_mutation::ProposalPublishMutationProposalOnProposalMutationPublishSubgraphs;
+pub(crate) type MutationResponseData = proposal_publish_mutation::ResponseData;
+pub(crate) type MutationProposalStatus = proposal_publish_mutation::ProposalStatus;
+pub(crate) type MutationVariables = proposal_publish_mutation::Variables;
+
+type SchemaInput = proposal_publish_mutation::PartialSchemaInput;
+type GitContextInput = proposal_publish_mutation::GitContextInput;
+
+use serde::Serialize;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ProposalPublishInput {
+    pub proposal_id: String,
+    pub subgraph: String,
+    pub url: Option<String>,
+    pub schema: String,
+    pub summary: String,
+    pub git_context: GitContext,
+}
+
+impl ProposalPublishInput {
+    pub(crate) fn into_variables(self, previous_launch_id: String) -> MutationVariables {
+        MutationVariables {
+            proposal_id: self.proposal_id,
+            previous_launch_id,
+            revision: self.git_context.commit.clone().unwrap_or_default(),
+            summary: self.summary,
+            git_context: self.git_context.into(),
+            subgraph: self.subgraph,
+            url: self.url,
+            schema: SchemaInput {
+                sdl: Some(self.schema),
+                hash: None,
+            },
+        }
+    }
+}
+
+impl From<GitContext> for GitContextInput {
+    fn from(git_context: GitContext) -> GitContextInput {
+        GitContextInput {
+            branch: git_context.branch,
+            commit: git_context.commit,
+            committer: git_context.author,
+            remote_url: git_context.remote_url,
+            message: None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Eq, PartialEq, Debug)]
+pub struct ProposalPublishResponse {
+    pub proposal: ProposalInfo,
+    pub proposal_url: String,
+}
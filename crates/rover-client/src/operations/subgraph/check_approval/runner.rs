@@ -0,0 +1,138 @@
+use super::types::*;
+use crate::blocking::StudioClient;
+use crate::shared::GraphRef;
+use crate::RoverClientError;
+
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/subgraph/check_approval/check_approval_query.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. subgraph_check_approval_query
+pub(crate) struct SubgraphCheckApprovalQuery;
+
+/// Fetches a check, and whether it or one of its reruns passed
+pub async fn run(
+    input: SubgraphCheckApprovalInput,
+    client: &StudioClient,
+) -> Result<SubgraphCheckApproval, RoverClientError> {
+    let graph_ref = input.graph_ref.clone();
+    let check_id = input.check_id.clone();
+    let data = client
+        .post::<SubgraphCheckApprovalQuery>(input.into())
+        .await?;
+    build_response(data, graph_ref, check_id)
+}
+
+fn build_response(
+    data: ResponseData,
+    graph_ref: GraphRef,
+    check_id: String,
+) -> Result<SubgraphCheckApproval, RoverClientError> {
+    let workflow = data
+        .graph
+        .ok_or(RoverClientError::GraphNotFound { graph_ref })?
+        .check_workflow
+        .ok_or_else(|| RoverClientError::AdhocError {
+            msg: format!("Could not find check {check_id}."),
+        })?;
+    let approved_by = if workflow.status == CheckWorkflowStatus::PASSED {
+        Some(workflow.id)
+    } else {
+        workflow
+            .reruns
+            .into_iter()
+            .flatten()
+            .find(|rerun| rerun.status == CheckWorkflowStatus::PASSED)
+            .map(|rerun| rerun.id)
+    };
+    Ok(SubgraphCheckApproval {
+        check_id,
+        subgraph: workflow.implementing_service_name,
+        variant: workflow.base_variant.map(|variant| variant.name),
+        approved_by,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn graph_ref() -> GraphRef {
+        GraphRef {
+            name: "mygraph".to_string(),
+            variant: "current".to_string(),
+        }
+    }
+
+    #[test]
+    fn build_response_approves_passed_reruns() {
+        let json_response = json!({
+            "graph": {
+                "checkWorkflow": {
+                    "id": "check-1",
+                    "status": "FAILED",
+                    "implementingServiceName": "products",
+                    "baseVariant": { "name": "current" },
+                    "reruns": [
+                        { "id": "check-3", "status": "PASSED" },
+                        { "id": "check-2", "status": "FAILED" }
+                    ]
+                }
+            }
+        });
+        let data: ResponseData = serde_json::from_value(json_response).unwrap();
+        let output = build_response(data, graph_ref(), "check-1".to_string());
+
+        assert_eq!(
+            output.unwrap(),
+            SubgraphCheckApproval {
+                check_id: "check-1".to_string(),
+                subgraph: Some("products".to_string()),
+                variant: Some("current".to_string()),
+                approved_by: Some("check-3".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn build_response_does_not_approve_failed_checks() {
+        let json_response = json!({
+            "graph": {
+                "checkWorkflow": {
+                    "id": "check-1",
+                    "status": "FAILED",
+                    "implementingServiceName": "products",
+                    "baseVariant": { "name": "current" },
+                    "reruns": []
+                }
+            }
+        });
+        let data: ResponseData = serde_json::from_value(json_response).unwrap();
+        let output = build_response(data, graph_ref(), "check-1".to_string());
+
+        assert_eq!(output.unwrap().approved_by, None);
+    }
+
+    #[test]
+    fn build_response_errs_with_unknown_check() {
+        let json_response = json!({ "graph": { "checkWorkflow": null } });
+        let data: ResponseData = serde_json::from_value(json_response).unwrap();
+        let output = build_response(data, graph_ref(), "check-1".to_string());
+
+        assert!(output
+            .unwrap_err()
+            .to_string()
+            .contains("Could not find check check-1."));
+    }
+}
@@ -0,0 +1,35 @@
+use crate::shared::GraphRef;
+
+use super::runner::subgraph_check_approval_query;
+
+pub(crate) type ResponseData = subgraph_check_approval_query::ResponseData;
+pub(crate) type QueryVariables = subgraph_check_approval_query::Variables;
+pub(crate) type CheckWorkflowStatus = subgraph_check_approval_query::CheckWorkflowStatus;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SubgraphCheckApprovalInput {
+    pub graph_ref: GraphRef,
+    pub check_id: String,
+}
+
+impl From<SubgraphCheckApprovalInput> for QueryVariables {
+    fn from(input: SubgraphCheckApprovalInput) -> Self {
+        Self {
+            graph_id: input.graph_ref.name,
+            check_id: input.check_id,
+        }
+    }
+}
+
+/// Whether a check was approved in Studio, along with what it checked
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SubgraphCheckApproval {
+    pub check_id: String,
+    /// The subgraph the check was run for
+    pub subgraph: Option<String>,
+    /// The variant the check was run against
+    pub variant: Option<String>,
+    /// The check that approves it: either the check itself when it passed, or the most recent
+    /// rerun of it that passed once its failures were approved in Studio
+    pub approved_by: Option<String>,
+}
@@ -2,13 +2,16 @@ use std::time::{Duration, Instant};
 
 use apollo_federation_types::rover::BuildError;
 use graphql_client::*;
+use rover_std::progress::ProgressBar;
+use rover_std::warnln;
 
 use crate::blocking::StudioClient;
 use crate::operations::subgraph::check_workflow::types::QueryResponseData;
 use crate::shared::{
-    CheckWorkflowResponse, CustomCheckResponse, Diagnostic, DownstreamCheckResponse, GraphRef,
-    LintCheckResponse, OperationCheckResponse, ProposalsCheckResponse, ProposalsCheckSeverityLevel,
-    ProposalsCoverage, RelatedProposal, SchemaChange, Violation,
+    CheckWorkflowResponse, CustomCheckResponse, Diagnostic, DownstreamCheckResponse,
+    DownstreamVariantResult, GraphRef, LintCheckResponse, OperationCheckResponse,
+    ProposalsCheckResponse, ProposalsCheckSeverityLevel, ProposalsCoverage, RelatedProposal,
+    SchemaChange, Violation,
 };
 use crate::RoverClientError;
 
@@ -51,6 +54,7 @@ pub async fn run(
     let graph_ref = input.graph_ref.clone();
     let mut url: Option<String> = None;
     let now = Instant::now();
+    let _progress = ProgressBar::new("waiting for the check to finish");
     loop {
         let result = client
             .post::<SubgraphCheckWorkflowQuery>(input.clone().into())
@@ -68,7 +72,7 @@ pub async fn run(
                 url = get_target_url_from_data(data);
             }
             Err(e) => {
-                eprintln!("error while checking status of check: {e}\nthis error may be transient... retrying");
+                warnln!("error while checking status of check: {e}\nthis error may be transient... retrying");
             }
         }
         if now.elapsed() > Duration::from_secs(input.checks_timeout_seconds) {
@@ -456,10 +460,22 @@ fn get_downstream_response_from_result(
                 .filter(|result| result.fails_upstream_workflow.unwrap_or(false))
                 .map(|result| result.downstream_variant_name.clone())
                 .collect();
+            let variants = results
+                .into_iter()
+                .map(|result| DownstreamVariantResult {
+                    variant_name: result.downstream_variant_name,
+                    blocking: result.blocking,
+                    status: result
+                        .downstream_workflow
+                        .map(|workflow| workflow.status)
+                        .into(),
+                })
+                .collect();
             Some(DownstreamCheckResponse {
                 task_status: task_status.into(),
                 target_url,
                 blocking_variants,
+                variants,
             })
         }
         None => None,
@@ -53,6 +53,17 @@ impl From<WorkflowStatus> for ChangeSeverity {
     }
 }
 
+impl From<Option<WorkflowStatus>> for CheckTaskStatus {
+    fn from(status: Option<WorkflowStatus>) -> Self {
+        // a missing workflow hasn't been initialized yet
+        match status {
+            Some(WorkflowStatus::PASSED) => CheckTaskStatus::PASSED,
+            Some(WorkflowStatus::PENDING) | None => CheckTaskStatus::PENDING,
+            _ => CheckTaskStatus::FAILED,
+        }
+    }
+}
+
 impl From<Option<CheckWorkflowTaskStatus>> for CheckTaskStatus {
     fn from(status: Option<CheckWorkflowTaskStatus>) -> Self {
         match status {
@@ -29,19 +29,16 @@ pub async fn run(
             HeaderValue::from_str(&header_value)?,
         );
     }
-    let response_data = if should_retry {
-        client
-            .post::<SubgraphIntrospectQuery>(input.into(), &mut header_map, EndpointKind::Customer)
-            .await
-    } else {
-        client
-            .post_no_retry::<SubgraphIntrospectQuery>(
-                input.into(),
-                &mut header_map,
-                EndpointKind::Customer,
-            )
-            .await
-    };
+    let transport = input.transport;
+    let response_data = client
+        .send::<SubgraphIntrospectQuery>(
+            input.into(),
+            &mut header_map,
+            EndpointKind::Customer,
+            transport,
+            should_retry,
+        )
+        .await;
 
     match response_data {
         Ok(data) => build_response(data),
@@ -5,9 +5,12 @@ pub(crate) type QueryResponseData = subgraph_introspect_query::ResponseData;
 
 use std::collections::HashMap;
 
+use crate::blocking::GraphQLTransport;
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SubgraphIntrospectInput {
     pub headers: HashMap<String, String>,
+    pub transport: GraphQLTransport,
 }
 
 impl From<SubgraphIntrospectInput> for QueryVariables {
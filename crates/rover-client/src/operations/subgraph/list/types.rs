@@ -6,7 +6,7 @@ pub(crate) type QueryResponseData = subgraph_list_query::ResponseData;
 type QueryVariables = subgraph_list_query::Variables;
 
 use chrono::{DateTime, Local, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct SubgraphListInput {
@@ -33,14 +33,14 @@ pub struct SubgraphListResponse {
     pub graph_ref: GraphRef,
 }
 
-#[derive(Clone, Serialize, Eq, PartialEq, Debug)]
+#[derive(Clone, Serialize, Deserialize, Eq, PartialEq, Debug)]
 pub struct SubgraphInfo {
     pub name: String,
     pub url: Option<String>, // optional, and may not be a real url
     pub updated_at: SubgraphUpdatedAt,
 }
 
-#[derive(Clone, Serialize, Eq, PartialEq, Debug)]
+#[derive(Clone, Serialize, Deserialize, Eq, PartialEq, Debug)]
 pub struct SubgraphUpdatedAt {
     pub local: Option<DateTime<Local>>,
     pub utc: Option<DateTime<Utc>>,
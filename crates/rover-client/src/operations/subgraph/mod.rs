@@ -7,6 +7,9 @@ pub mod check_workflow;
 /// "subgraph check --async" command execution
 pub mod check;
 
+/// query for whether a failed check was approved, for "subgraph publish --allow-breaking-with-approval"
+pub mod check_approval;
+
 /// "subgraph fetch" command execution
 pub mod fetch;
 
@@ -22,6 +22,8 @@ pub struct SubgraphPublishInput {
     pub schema: String,
     pub git_context: GitContext,
     pub convert_to_federated_graph: bool,
+    /// Free-form metadata recorded with the publish, like a check override it was approved by
+    pub revision: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Eq, PartialEq)]
@@ -54,7 +56,7 @@ impl From<SubgraphPublishInput> for MutationVariables {
                 hash: None,
             },
             git_context: publish_input.git_context.into(),
-            revision: "".to_string(),
+            revision: publish_input.revision.unwrap_or_default(),
         }
     }
 }
@@ -79,7 +79,9 @@ impl CheckWorkflowResponse {
         }
 
         if let Some(downstream_response) = &self.maybe_downstream_response {
-            if !downstream_response.blocking_variants.is_empty() {
+            if !downstream_response.blocking_variants.is_empty()
+                || !downstream_response.variants.is_empty()
+            {
                 msg.push('\n');
                 msg.push_str(&Self::task_title(
                     "Downstream Check",
@@ -125,6 +127,22 @@ impl CheckWorkflowResponse {
         json_result
     }
 
+    /// Whether the check of any downstream variant failed, including
+    /// non-blocking variants that don't fail the check on their own.
+    pub fn has_failed_downstream_variants(&self) -> bool {
+        self.maybe_downstream_response
+            .as_ref()
+            .is_some_and(|response| !response.failed_variants().is_empty())
+    }
+
+    /// Whether some of the changes in this check are not part of an approved
+    /// proposal, even if the proposals task didn't fail the check on its own.
+    pub fn has_unapproved_proposal_changes(&self) -> bool {
+        self.maybe_proposals_response
+            .as_ref()
+            .is_some_and(ProposalsCheckResponse::has_unapproved_changes)
+    }
+
     fn task_title(title: &str, status: CheckTaskStatus) -> String {
         format!(
             "\n{} [{}]:\n",
@@ -172,6 +190,10 @@ impl OperationCheckResponse {
         }
     }
 
+    pub fn changes(&self) -> &[SchemaChange] {
+        &self.changes
+    }
+
     pub fn get_table(&self) -> String {
         let mut table = Table::new();
 
@@ -320,6 +342,14 @@ pub struct ProposalsCheckResponse {
 }
 
 impl ProposalsCheckResponse {
+    /// Whether some of the changes in this check are not part of an approved proposal
+    pub fn has_unapproved_changes(&self) -> bool {
+        matches!(
+            self.proposal_coverage,
+            ProposalsCoverage::NONE | ProposalsCoverage::PARTIAL
+        )
+    }
+
     pub fn get_table(&self) -> String {
         let mut table = Table::new();
 
@@ -449,6 +479,7 @@ pub struct DownstreamCheckResponse {
     pub task_status: CheckTaskStatus,
     pub target_url: Option<String>,
     pub blocking_variants: Vec<String>,
+    pub variants: Vec<DownstreamVariantResult>,
 }
 
 impl DownstreamCheckResponse {
@@ -470,6 +501,34 @@ impl DownstreamCheckResponse {
         )
     }
 
+    /// Returns the downstream variants whose own check workflow failed,
+    /// whether or not they block the upstream check.
+    pub fn failed_variants(&self) -> Vec<&DownstreamVariantResult> {
+        self.variants
+            .iter()
+            .filter(|variant| variant.status == CheckTaskStatus::FAILED)
+            .collect()
+    }
+
+    pub fn get_table(&self) -> String {
+        let mut table = Table::new();
+
+        table.set_format(*FORMAT_BOX_CHARS);
+
+        // bc => sets top row to be bold and center
+        table.add_row(row![bc => "Status", "Downstream Variant", "Blocking"]);
+
+        for variant in &self.variants {
+            table.add_row(row![
+                variant.status.as_ref(),
+                variant.variant_name,
+                if variant.blocking { "yes" } else { "no" }
+            ]);
+        }
+
+        table.to_string()
+    }
+
     pub fn get_output(&self) -> String {
         let mut msg = String::new();
 
@@ -478,6 +537,10 @@ impl DownstreamCheckResponse {
             msg.push('\n');
         }
 
+        if !self.variants.is_empty() {
+            msg.push_str(&self.get_table());
+        }
+
         if let Some(url) = &self.target_url {
             msg.push_str("View downstream check details at: ");
             msg.push_str(&Style::Link.paint(url));
@@ -491,6 +554,16 @@ impl DownstreamCheckResponse {
     }
 }
 
+/// The result of the check workflow that ran for a single downstream
+/// variant (such as a contract) of the variant being checked.
+#[derive(Debug, Serialize, Clone, Eq, PartialEq)]
+pub struct DownstreamVariantResult {
+    pub variant_name: String,
+    /// Whether a failure of this variant's checks fails the upstream check
+    pub blocking: bool,
+    pub status: CheckTaskStatus,
+}
+
 #[derive(Debug, Serialize, Clone, Eq, PartialEq)]
 pub enum CheckTaskStatus {
     BLOCKED,
@@ -0,0 +1,252 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Mutations that only read from Studio, so they are still sent during a dry run
+const READ_ONLY_MUTATIONS: &[&str] = &[
+    "GraphCheckMutation",
+    "SubgraphCheckMutation",
+    "LintGraphMutation",
+    "LintSubgraphMutation",
+];
+
+/// Variables whose names contain any of these are never printed
+const SENSITIVE_VARIABLES: &[&str] = &["key", "token", "secret", "password"];
+
+/// Strings longer than this (schemas, configs, READMEs) are summarized by their size
+const MAX_VARIABLE_LENGTH: usize = 120;
+
+/// A mutation that would have been sent to Apollo Studio if `--dry-run` weren't set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DryRunRequest {
+    pub operation_name: String,
+    pub query: String,
+    /// The request variables, with credentials and large documents redacted
+    pub variables: Value,
+    /// A short description of what the mutation would change
+    pub effect: String,
+}
+
+impl DryRunRequest {
+    /// Returns the request that would be sent, or `None` if the operation doesn't
+    /// change anything in Studio and is safe to send during a dry run.
+    pub fn intercept<V: Serialize>(
+        operation_name: &str,
+        query: &str,
+        variables: &V,
+    ) -> Option<DryRunRequest> {
+        if !is_mutation(query) || READ_ONLY_MUTATIONS.contains(&operation_name) {
+            return None;
+        }
+        let variables = serde_json::to_value(variables).unwrap_or(Value::Null);
+        // `subgraph delete` asks Studio for its own dry run before deleting anything
+        if variables.get("dry_run") == Some(&Value::Bool(true)) {
+            return None;
+        }
+        Some(DryRunRequest {
+            operation_name: operation_name.to_string(),
+            query: query.trim().to_string(),
+            effect: describe_effect(operation_name, &variables),
+            variables: redact(variables),
+        })
+    }
+}
+
+fn is_mutation(query: &str) -> bool {
+    query
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .is_some_and(|line| line.starts_with("mutation"))
+}
+
+fn describe_effect(operation_name: &str, variables: &Value) -> String {
+    let var = |name: &str| match variables.get(name) {
+        Some(Value::String(value)) => value.clone(),
+        Some(Value::Null) | None => "<unknown>".to_string(),
+        Some(value) => value.to_string(),
+    };
+    match operation_name {
+        "SubgraphPublishMutation" => format!(
+            "publish subgraph '{}' to '{}@{}' and trigger composition",
+            var("subgraph"),
+            var("graph_id"),
+            var("variant")
+        ),
+        "SubgraphDeleteMutation" => format!(
+            "delete subgraph '{}' from '{}@{}' and trigger composition",
+            var("subgraph"),
+            var("graph_id"),
+            var("variant")
+        ),
+        "GraphPublishMutation" => format!(
+            "replace the schema of '{}@{}'",
+            var("graph_id"),
+            var("variant")
+        ),
+        "GraphDeleteMutation" => format!(
+            "delete the variant '{}@{}' and all of its subgraphs",
+            var("graph_id"),
+            var("variant")
+        ),
+        "ContractPublishMutation" => {
+            let launch = if variables.get("initiate_launch") == Some(&Value::Bool(true)) {
+                " and start a launch"
+            } else {
+                ""
+            };
+            format!(
+                "update the contract configuration of '{}@{}'{}",
+                var("graph_id"),
+                var("variant"),
+                launch
+            )
+        }
+        "CloudConfigUpdateQuery" => format!(
+            "replace the cloud router config of '{}@{}'",
+            var("graph_id"),
+            var("variant")
+        ),
+        "ReadmePublishMutation" => format!(
+            "replace the README of '{}@{}'",
+            var("graphId"),
+            var("variant")
+        ),
+        "CreatePersistedQueryListMutation" => format!(
+            "create the persisted query list '{}' in '{}'",
+            var("name"),
+            var("graphId")
+        ),
+        "DeletePersistedQueryListMutation" => format!(
+            "delete the persisted query list '{}' from '{}'",
+            var("listId"),
+            var("graphId")
+        ),
+        "LinkPersistedQueryListMutation" => format!(
+            "link the persisted query list '{}' to '{}@{}'",
+            var("listId"),
+            var("graphId"),
+            var("variant")
+        ),
+        "PublishOperationsMutation" => {
+            let count = variables
+                .get("operationManifest")
+                .and_then(Value::as_array)
+                .map_or(0, Vec::len);
+            format!(
+                "publish {} operations to the persisted query list '{}' in '{}'",
+                count,
+                var("listId"),
+                var("graphId")
+            )
+        }
+        "ProposalCreateMutation" => format!(
+            "create the proposal '{}' against '{}@{}'",
+            var("display_name"),
+            var("graph_id"),
+            var("source_variant")
+        ),
+        "ProposalPublishMutation" => format!(
+            "publish subgraph '{}' to proposal '{}'",
+            var("subgraph"),
+            var("proposal_id")
+        ),
+        other => format!("run the '{}' mutation", other),
+    }
+}
+
+fn redact(value: Value) -> Value {
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| {
+                    let lowercase = name.to_lowercase();
+                    let value = if SENSITIVE_VARIABLES
+                        .iter()
+                        .any(|sensitive| lowercase.contains(sensitive))
+                    {
+                        Value::String("<redacted>".to_string())
+                    } else {
+                        redact(value)
+                    };
+                    (name, value)
+                })
+                .collect::<Map<String, Value>>(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact).collect()),
+        Value::String(text) if text.chars().count() > MAX_VARIABLE_LENGTH => {
+            Value::String(format!("<{} characters>", text.chars().count()))
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use serde_json::json;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    const PUBLISH: &str =
+        "mutation SubgraphPublishMutation($graph_id: ID!) { graph(id: $graph_id) { id } }";
+
+    #[test]
+    fn intercepts_mutations_and_describes_them() {
+        let variables = json!({
+            "graph_id": "my-graph",
+            "variant": "current",
+            "subgraph": "products",
+            "schema": { "sdl": "type Query { a: String }".repeat(10) },
+        });
+        let request =
+            DryRunRequest::intercept("SubgraphPublishMutation", PUBLISH, &variables).unwrap();
+        assert_that!(request.effect).is_equal_to(
+            "publish subgraph 'products' to 'my-graph@current' and trigger composition".to_string(),
+        );
+        assert_that!(request.variables).is_equal_to(json!({
+            "graph_id": "my-graph",
+            "variant": "current",
+            "subgraph": "products",
+            "schema": { "sdl": "<240 characters>" },
+        }));
+    }
+
+    #[rstest]
+    #[case::query("FetchQuery", "query FetchQuery { me { id } }", json!({}))]
+    #[case::check("SubgraphCheckMutation", "mutation SubgraphCheckMutation { a }", json!({}))]
+    #[case::server_dry_run(
+        "SubgraphDeleteMutation",
+        "mutation SubgraphDeleteMutation { a }",
+        json!({ "dry_run": true })
+    )]
+    fn sends_operations_that_change_nothing(
+        #[case] operation_name: &str,
+        #[case] query: &str,
+        #[case] variables: Value,
+    ) {
+        assert_that!(DryRunRequest::intercept(operation_name, query, &variables)).is_none();
+    }
+
+    #[test]
+    fn redacts_credentials() {
+        let variables = json!({
+            "graphId": "my-graph",
+            "apiKey": "service:my-graph:abc",
+            "headers": [{ "name": "x", "authToken": "secret" }],
+        });
+        let request = DryRunRequest::intercept(
+            "SomeMutation",
+            "# comment\nmutation SomeMutation { a }",
+            &variables,
+        )
+        .unwrap();
+        assert_that!(request.effect).is_equal_to("run the 'SomeMutation' mutation".to_string());
+        assert_that!(request.variables).is_equal_to(json!({
+            "graphId": "my-graph",
+            "apiKey": "<redacted>",
+            "headers": [{ "name": "x", "authToken": "<redacted>" }],
+        }));
+    }
+}
@@ -1,18 +1,22 @@
 mod async_check_response;
 mod check_response;
+mod dry_run;
 mod fetch_response;
 mod git_context;
 mod graph_ref;
 mod lint_response;
+mod proposal;
 
 pub use async_check_response::CheckRequestSuccessResult;
 pub use check_response::{
     ChangeSeverity, CheckConfig, CheckTaskStatus, CheckWorkflowResponse, CustomCheckResponse,
-    DownstreamCheckResponse, LintCheckResponse, OperationCheckResponse, ProposalsCheckResponse,
-    ProposalsCheckSeverityLevel, ProposalsCoverage, RelatedProposal, SchemaChange,
-    ValidationPeriod, Violation,
+    DownstreamCheckResponse, DownstreamVariantResult, LintCheckResponse, OperationCheckResponse,
+    ProposalsCheckResponse, ProposalsCheckSeverityLevel, ProposalsCoverage, RelatedProposal,
+    SchemaChange, ValidationPeriod, Violation,
 };
+pub use dry_run::DryRunRequest;
 pub use fetch_response::{FetchResponse, Sdl, SdlType};
 pub use git_context::GitContext;
 pub use graph_ref::GraphRef;
 pub use lint_response::{Diagnostic, LintResponse};
+pub use proposal::{ProposalInfo, ProposalStatus};
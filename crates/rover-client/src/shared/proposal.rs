@@ -0,0 +1,82 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::RoverClientError;
+
+/// The review status of a schema proposal
+#[derive(Debug, Serialize, Clone, Copy, Eq, PartialEq)]
+pub enum ProposalStatus {
+    APPROVED,
+    CLOSED,
+    DRAFT,
+    IMPLEMENTED,
+    OPEN,
+}
+
+impl Display for ProposalStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = match self {
+            ProposalStatus::APPROVED => "APPROVED",
+            ProposalStatus::CLOSED => "CLOSED",
+            ProposalStatus::DRAFT => "DRAFT",
+            ProposalStatus::IMPLEMENTED => "IMPLEMENTED",
+            ProposalStatus::OPEN => "OPEN",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+impl FromStr for ProposalStatus {
+    type Err = RoverClientError;
+
+    fn from_str(status: &str) -> Result<Self, Self::Err> {
+        match status.to_uppercase().as_str() {
+            "APPROVED" => Ok(ProposalStatus::APPROVED),
+            "CLOSED" => Ok(ProposalStatus::CLOSED),
+            "DRAFT" => Ok(ProposalStatus::DRAFT),
+            "IMPLEMENTED" => Ok(ProposalStatus::IMPLEMENTED),
+            "OPEN" => Ok(ProposalStatus::OPEN),
+            _ => Err(RoverClientError::AdhocError {
+                msg: format!(
+                    "\"{}\" is not a proposal status. Expected one of APPROVED, CLOSED, DRAFT, IMPLEMENTED or OPEN",
+                    status
+                ),
+            }),
+        }
+    }
+}
+
+/// A schema proposal as reported by Apollo Studio
+#[derive(Debug, Serialize, Clone, Eq, PartialEq)]
+pub struct ProposalInfo {
+    pub id: String,
+    pub display_name: String,
+    pub status: ProposalStatus,
+    pub source_variant: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("approved", ProposalStatus::APPROVED)]
+    #[case("OPEN", ProposalStatus::OPEN)]
+    #[case("Draft", ProposalStatus::DRAFT)]
+    fn it_parses_proposal_statuses(#[case] input: &str, #[case] expected: ProposalStatus) {
+        assert_eq!(input.parse::<ProposalStatus>().unwrap(), expected);
+        assert_eq!(
+            expected.to_string().parse::<ProposalStatus>().unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn it_rejects_unknown_proposal_statuses() {
+        assert!("merged".parse::<ProposalStatus>().is_err());
+    }
+}
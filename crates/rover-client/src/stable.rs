@@ -0,0 +1,101 @@
+//! The operations Rover runs against Apollo Studio, for use outside of the Rover CLI.
+//!
+//! Everything in this module follows semver: an item is only removed, or has its signature
+//! changed, in a release that bumps the major version of `rover-client`. New fields on the input
+//! and response types count as breaking too, so tools can build them with struct literals.
+//! The rest of the crate is Rover's internals and changes with Rover, so depend on this module
+//! rather than on `rover_client::operations`.
+//!
+//! The module is only compiled with the `stable-api` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! rover-client = { git = "https://github.com/apollographql/rover", features = ["stable-api"] }
+//! ```
+//!
+//! Each operation is an async function taking its input and a [`StudioClient`]:
+//!
+//! ```no_run
+//! use rover_client::stable::{
+//!     subgraph, Credential, CredentialOrigin, ReqwestClient, StudioClient,
+//! };
+//!
+//! # async fn fetch() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = StudioClient::new(
+//!     Credential {
+//!         api_key: std::env::var("APOLLO_KEY")?,
+//!         origin: CredentialOrigin::EnvVar,
+//!     },
+//!     "https://api.apollographql.com/api/graphql",
+//!     env!("CARGO_PKG_VERSION"),
+//!     false,
+//!     ReqwestClient::new(),
+//!     None,
+//! );
+//! let response = subgraph::fetch(
+//!     subgraph::SubgraphFetchInput {
+//!         graph_ref: "my-graph@current".parse()?,
+//!         subgraph_name: "products".to_string(),
+//!     },
+//!     &client,
+//! )
+//! .await?;
+//! println!("{}", response.sdl.contents);
+//! # Ok(())
+//! # }
+//! ```
+
+pub use houston::{Credential, CredentialOrigin};
+/// The HTTP client a [`StudioClient`] sends its requests with
+pub use reqwest::Client as ReqwestClient;
+
+pub use crate::blocking::StudioClient;
+pub use crate::shared::{
+    CheckConfig, CheckRequestSuccessResult, CheckWorkflowResponse, FetchResponse, GitContext,
+    GraphRef, Sdl, SdlType,
+};
+pub use crate::{EndpointKind, RoverClientError};
+
+/// Publishing, fetching and checking the subgraphs of a federated graph
+pub mod subgraph {
+    pub use crate::operations::subgraph::check::{run as check, SubgraphCheckAsyncInput};
+    pub use crate::operations::subgraph::check_workflow::{
+        run as check_workflow, CheckWorkflowInput,
+    };
+    pub use crate::operations::subgraph::fetch::{run as fetch, SubgraphFetchInput};
+    pub use crate::operations::subgraph::publish::{
+        run as publish, SubgraphPublishInput, SubgraphPublishResponse,
+    };
+}
+
+/// Publishing, fetching and checking the schema of a non-federated graph
+pub mod graph {
+    pub use crate::operations::graph::check::{run as check, CheckSchemaAsyncInput};
+    pub use crate::operations::graph::check_workflow::{run as check_workflow, CheckWorkflowInput};
+    pub use crate::operations::graph::fetch::{run as fetch, GraphFetchInput};
+    pub use crate::operations::graph::publish::{
+        run as publish, ChangeSummary, FieldChanges, GraphPublishInput, GraphPublishResponse,
+        TypeChanges,
+    };
+}
+
+/// Fetching the composed supergraph schema of a federated graph
+pub mod supergraph {
+    pub use crate::operations::supergraph::fetch::{run as fetch, SupergraphFetchInput};
+}
+
+/// Publishing operations to the persisted query list of a graph
+pub mod persisted_queries {
+    pub use crate::operations::persisted_queries::name::{
+        run as name, PersistedQueryListNameInput, PersistedQueryListNameResponse,
+    };
+    pub use crate::operations::persisted_queries::publish::{
+        run as publish, ApolloPersistedQueryManifest, PersistedQueriesOperationCounts,
+        PersistedQueriesPublishInput, PersistedQueriesPublishResponse, PersistedQueryOperation,
+        PersistedQueryOperationType, PersistedQueryPublishOperationResult,
+        RelayPersistedQueryManifest,
+    };
+    pub use crate::operations::persisted_queries::resolve::{
+        run as resolve, PersistedQueryList, ResolvePersistedQueryListInput,
+    };
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "stable-api")]
+
+use httpmock::{Method::POST, MockServer};
+use rover_client::stable::{
+    subgraph, Credential, CredentialOrigin, ReqwestClient, RoverClientError, StudioClient,
+};
+use serde_json::json;
+use speculoos::prelude::*;
+
+fn studio_client(server: &MockServer) -> StudioClient {
+    StudioClient::new(
+        Credential {
+            api_key: "service:my-graph:key".to_string(),
+            origin: CredentialOrigin::EnvVar,
+        },
+        &server.url("/graphql"),
+        "1.0.0",
+        false,
+        ReqwestClient::new(),
+        None,
+    )
+}
+
+#[tokio::test]
+async fn subgraph_fetch_returns_the_subgraph_schema() {
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/graphql")
+            .header("x-api-key", "service:my-graph:key")
+            .body_contains("SubgraphFetchQuery");
+        then.json_body(json!({
+            "data": {
+                "variant": {
+                    "__typename": "GraphVariant",
+                    "subgraph": {
+                        "url": "http://products.example.com/graphql",
+                        "activePartialSchema": { "sdl": "type Query { products: [String] }" }
+                    },
+                    "subgraphs": [{ "name": "products" }]
+                }
+            }
+        }));
+    });
+
+    let response = subgraph::fetch(
+        subgraph::SubgraphFetchInput {
+            graph_ref: "my-graph@current".parse().unwrap(),
+            subgraph_name: "products".to_string(),
+        },
+        &studio_client(&server),
+    )
+    .await
+    .unwrap();
+
+    mock.assert();
+    assert_that!(response.sdl.contents)
+        .is_equal_to("type Query { products: [String] }".to_string());
+}
+
+#[tokio::test]
+async fn subgraph_fetch_reports_missing_subgraphs() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(POST).path("/graphql");
+        then.json_body(json!({
+            "data": {
+                "variant": {
+                    "__typename": "GraphVariant",
+                    "subgraph": null,
+                    "subgraphs": [{ "name": "products" }]
+                }
+            }
+        }));
+    });
+
+    let err = subgraph::fetch(
+        subgraph::SubgraphFetchInput {
+            graph_ref: "my-graph@current".parse().unwrap(),
+            subgraph_name: "reviews".to_string(),
+        },
+        &studio_client(&server),
+    )
+    .await
+    .unwrap_err();
+
+    assert_that!(matches!(err, RoverClientError::NoSubgraphInGraph { .. })).is_true();
+}
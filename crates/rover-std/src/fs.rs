@@ -94,6 +94,34 @@ impl Fs {
         Ok(())
     }
 
+    /// writes a file to disk by writing to a temporary file next to it and renaming that into
+    /// place, so anything reading the file sees either the old contents or the new ones, never a
+    /// partial write
+    pub fn write_file_atomically<P, C>(path: P, contents: C) -> Result<(), RoverStdError>
+    where
+        P: AsRef<Utf8Path>,
+        C: AsRef<[u8]>,
+    {
+        let path = path.as_ref();
+        let file_name = path.file_name().ok_or(anyhow!(
+            "cannot write to a path without a final element {path}"
+        ))?;
+        // a bare file name has an empty parent, which can't be written to as-is
+        let parent = path
+            .parent()
+            .filter(|parent| !parent.as_str().is_empty())
+            .unwrap_or(Utf8Path::new("."));
+        let temp_path = parent.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+        Self::write_file(&temp_path, contents)?;
+        tracing::info!("moving {} into place at {}", &temp_path, &path);
+        fs::rename(&temp_path, path)
+            .tap_err(|_| {
+                let _ = fs::remove_file(&temp_path);
+            })
+            .with_context(|| format!("could not move {} to {}", &temp_path, &path))?;
+        Ok(())
+    }
+
     /// Given a path, where some elements may not exist, it will return the canonical
     /// representation of the path, AND create any missing interim directories.
     fn upsert_path_exists(path: &Utf8Path) -> Result<Utf8PathBuf, anyhow::Error> {
@@ -165,6 +193,25 @@ impl Fs {
             .with_context(|| format!("could not find a file at the path '{}'", file))?)
     }
 
+    /// the bytes the current user can write to the filesystem holding `path`, or holding its
+    /// closest existing ancestor when `path` hasn't been created yet. `None` when that can't be
+    /// told, like on Windows
+    pub fn available_space<P>(path: P) -> Option<u64>
+    where
+        P: AsRef<Utf8Path>,
+    {
+        let existing = path.as_ref().ancestors().find(|ancestor| {
+            // an empty ancestor stands for the current directory in relative paths
+            ancestor.as_str().is_empty() || ancestor.exists()
+        })?;
+        let existing = if existing.as_str().is_empty() {
+            Utf8Path::new(".")
+        } else {
+            existing
+        };
+        available_space(existing)
+    }
+
     /// copies one file to another
     pub fn copy<I, O>(in_path: I, out_path: O) -> Result<(), RoverStdError>
     where
@@ -367,6 +414,26 @@ impl Fs {
     }
 }
 
+#[cfg(unix)]
+fn available_space(path: &Utf8Path) -> Option<u64> {
+    use std::ffi::CString;
+
+    let path = CString::new(path.as_str()).ok()?;
+    let mut stats = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: `path` is a valid C string and `stats` is only read when `statvfs` succeeds
+    if unsafe { libc::statvfs(path.as_ptr(), stats.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    let stats = unsafe { stats.assume_init() };
+    #[allow(clippy::unnecessary_cast)]
+    Some(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn available_space(_path: &Utf8Path) -> Option<u64> {
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -382,6 +449,32 @@ mod tests {
 
     use super::*;
 
+    #[cfg(unix)]
+    #[test]
+    fn test_available_space_looks_at_the_closest_existing_directory() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dir = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf()).unwrap();
+        let available = Fs::available_space(&dir);
+        assert_that!(available).is_some();
+        assert_that!(Fs::available_space(dir.join("not/created/yet"))).is_some();
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_file_atomically_replaces_contents() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dir = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf()).unwrap();
+        let path = dir.join("supergraph.graphql");
+        Fs::write_file(&path, "type Query { old: String }")?;
+
+        Fs::write_file_atomically(&path, "type Query { new: String }")?;
+
+        assert_that!(Fs::read_file(&path)?).is_equal_to("type Query { new: String }".to_string());
+        let entries = Fs::get_dir_entries(&dir)?.collect::<Result<Vec<_>, _>>()?;
+        assert_that!(entries).has_length(1);
+        Ok(())
+    }
+
     #[rstest]
     #[case("a/b/c", "a/b/c/supergraph.yaml", vec!(), false)]
     #[case("a/b", "a/b/c/supergraph.yaml", vec!(), false)]
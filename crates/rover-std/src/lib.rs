@@ -4,7 +4,9 @@ mod style;
 mod url;
 
 pub mod print;
+pub mod progress;
 pub mod prompt;
+pub mod timing;
 pub use error::RoverStdError;
 pub use fs::Fs;
 pub use style::is_no_color_set;
@@ -1,24 +1,45 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Stops [`infoln!`], [`warnln!`], and [`progressln!`] from printing anything, for `--quiet`.
+/// Errors are still printed.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether `--quiet` was passed
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
 /// Prints to the standard error, with a newline.
 ///
 /// Equivalent to the [`eprintln!`] macro except that an info prefix is
-/// printed before the message.
+/// printed before the message, and nothing is printed with `--quiet`.
 #[macro_export]
 macro_rules! infoln {
     ($($t:tt)*) => {{
-        eprint!("{} ", $crate::Style::InfoPrefix.paint("==>"));
-        eprintln!($($t)*);
+        if !$crate::print::is_quiet() {
+            let _suspended = $crate::progress::suspend();
+            eprint!("{} ", $crate::Style::InfoPrefix.paint("==>"));
+            eprintln!($($t)*);
+        }
     }};
 }
 
 /// Prints to the standard error, with a newline.
 ///
 /// Equivalent to the [`eprintln!`] macro except that a warning prefix is
-/// printed before the message.
+/// printed before the message, and nothing is printed with `--quiet`.
 #[macro_export]
 macro_rules! warnln {
     ($($t:tt)*) => {{
-        eprint!("{} ", $crate::Style::WarningPrefix.paint("warning:"));
-        eprintln!($($t)*);
+        if !$crate::print::is_quiet() {
+            let _suspended = $crate::progress::suspend();
+            eprint!("{} ", $crate::Style::WarningPrefix.paint("warning:"));
+            eprintln!($($t)*);
+        }
     }};
 }
 
@@ -29,7 +50,22 @@ macro_rules! warnln {
 #[macro_export]
 macro_rules! errln {
     ($($t:tt)*) => {{
+        let _suspended = $crate::progress::suspend();
         eprint!("{} ", $crate::Style::ErrorPrefix.paint("error:"));
         eprintln!($($t)*);
     }};
 }
+
+/// Prints to the standard error, with a newline.
+///
+/// Equivalent to the [`eprintln!`] macro except that nothing is printed with `--quiet`. For
+/// messages about what a command is doing, like which graph it's fetching from.
+#[macro_export]
+macro_rules! progressln {
+    ($($t:tt)*) => {{
+        if !$crate::print::is_quiet() {
+            let _suspended = $crate::progress::suspend();
+            eprintln!($($t)*);
+        }
+    }};
+}
@@ -0,0 +1,247 @@
+//! Spinners and percent bars for operations that take long enough that Rover would otherwise sit
+//! silent, like downloading plugins or waiting on checks.
+//!
+//! Progress is drawn on a single line of stderr, and only when stderr is a terminal, `--quiet`
+//! wasn't passed, and the output isn't meant for machines, like `--format json`. The line is
+//! cleared before anything else is printed to stderr with Rover's print macros, and redrawn after.
+
+use std::fmt::Write as _;
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::print::is_quiet;
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Held while anything is drawn on or printed to stderr, and whether a progress line is drawn
+static STDERR: Mutex<bool> = Mutex::new(false);
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const TICK: Duration = Duration::from_millis(100);
+/// Operations that finish quicker than this never draw anything, so they don't flicker
+const DELAY: Duration = Duration::from_millis(300);
+const BAR_WIDTH: usize = 24;
+
+/// Stops progress from being drawn, for output that's meant for machines
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+fn should_draw() -> bool {
+    ENABLED.load(Ordering::Relaxed) && !is_quiet() && io::stderr().is_terminal()
+}
+
+fn lock_stderr() -> MutexGuard<'static, bool> {
+    STDERR
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Keeps progress from being drawn until it's dropped
+pub struct Suspended(#[allow(dead_code)] MutexGuard<'static, bool>);
+
+/// Clears the progress line, if one is drawn, so a message can be printed in its place. Nothing
+/// is drawn until the returned guard is dropped
+pub fn suspend() -> Suspended {
+    let mut drawn = lock_stderr();
+    if *drawn {
+        eprint!("\r\x1b[2K");
+        *drawn = false;
+    }
+    Suspended(drawn)
+}
+
+/// Reports how far along a long-running operation is
+pub trait Progress {
+    /// Changes what the operation is described as
+    fn set_message(&self, message: &str);
+
+    /// Sets how much work there is in total, which turns a spinner into a percent bar
+    fn set_length(&self, length: u64);
+
+    /// Records that `delta` more of the work is done
+    fn inc(&self, delta: u64);
+
+    /// Stops reporting progress, and clears what was drawn
+    fn finish(&self);
+}
+
+#[derive(Debug)]
+struct State {
+    message: Mutex<String>,
+    position: AtomicU64,
+    /// 0 until the length is known
+    length: AtomicU64,
+    done: AtomicBool,
+    started: Instant,
+}
+
+impl State {
+    fn render(&self, tick: usize) -> String {
+        let message = self
+            .message
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        let length = self.length.load(Ordering::Relaxed);
+        let mut line = String::new();
+        if length == 0 {
+            let _ = write!(
+                line,
+                "{} {} ({}s)",
+                SPINNER[tick % SPINNER.len()],
+                message,
+                self.started.elapsed().as_secs()
+            );
+        } else {
+            let position = self.position.load(Ordering::Relaxed).min(length);
+            let filled = (position as f64 / length as f64 * BAR_WIDTH as f64) as usize;
+            let _ = write!(
+                line,
+                "{} [{}{}] {:>3}%",
+                message,
+                "#".repeat(filled),
+                "-".repeat(BAR_WIDTH - filled),
+                position * 100 / length
+            );
+        }
+        let width = console::Term::stderr().size().1 as usize;
+        if width > 1 && line.chars().count() >= width {
+            line = line.chars().take(width - 1).collect();
+        }
+        line
+    }
+}
+
+/// A spinner, or a percent bar once the amount of work is known, drawn on stderr. When progress
+/// shouldn't be drawn, it's hidden, and reporting progress to it does nothing
+#[derive(Debug)]
+pub struct ProgressBar {
+    state: Arc<State>,
+    ticker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ProgressBar {
+    /// Starts a spinner with `message`, like `downloading the 'router' plugin`
+    pub fn new(message: impl Into<String>) -> Self {
+        let state = Arc::new(State {
+            message: Mutex::new(message.into()),
+            position: AtomicU64::new(0),
+            length: AtomicU64::new(0),
+            done: AtomicBool::new(false),
+            started: Instant::now(),
+        });
+        let ticker = should_draw().then(|| {
+            let state = state.clone();
+            thread::spawn(move || {
+                let mut tick = 0;
+                while !state.done.load(Ordering::Relaxed) {
+                    thread::sleep(TICK);
+                    if state.started.elapsed() < DELAY || state.done.load(Ordering::Relaxed) {
+                        continue;
+                    }
+                    let line = state.render(tick);
+                    let mut drawn = lock_stderr();
+                    let mut stderr = io::stderr().lock();
+                    let _ = write!(stderr, "\r\x1b[2K{}", line);
+                    let _ = stderr.flush();
+                    *drawn = true;
+                    tick += 1;
+                }
+            })
+        });
+        Self {
+            state,
+            ticker: Mutex::new(ticker),
+        }
+    }
+
+    /// Whether anything is being drawn
+    pub fn is_hidden(&self) -> bool {
+        self.ticker
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .is_none()
+    }
+}
+
+impl Progress for ProgressBar {
+    fn set_message(&self, message: &str) {
+        *self
+            .state
+            .message
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = message.to_string();
+    }
+
+    fn set_length(&self, length: u64) {
+        self.state.length.store(length, Ordering::Relaxed);
+    }
+
+    fn inc(&self, delta: u64) {
+        self.state.position.fetch_add(delta, Ordering::Relaxed);
+    }
+
+    fn finish(&self) {
+        self.state.done.store(true, Ordering::Relaxed);
+        let ticker = self
+            .ticker
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+        if let Some(ticker) = ticker {
+            let _ = ticker.join();
+            drop(suspend());
+        }
+    }
+}
+
+impl Drop for ProgressBar {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    fn state(length: u64, position: u64) -> State {
+        State {
+            message: Mutex::new("downloading".to_string()),
+            position: AtomicU64::new(position),
+            length: AtomicU64::new(length),
+            done: AtomicBool::new(false),
+            started: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn test_renders_a_spinner_until_the_length_is_known() {
+        assert_that!(state(0, 10).render(1)).is_equal_to("⠙ downloading (0s)".to_string());
+    }
+
+    #[test]
+    fn test_renders_a_percent_bar() {
+        assert_that!(state(200, 50).render(0))
+            .is_equal_to("downloading [######------------------]  25%".to_string());
+        // never past 100%, even if more is reported than the length
+        assert_that!(state(100, 150).render(0)).ends_with("100%");
+    }
+
+    #[test]
+    fn test_hidden_bars_do_nothing() {
+        // tests don't run with stderr attached to a terminal that's drawn on
+        set_enabled(false);
+        let bar = ProgressBar::new("composing");
+        bar.set_length(10);
+        bar.inc(5);
+        assert_that!(bar.is_hidden()).is_true();
+        bar.finish();
+    }
+}
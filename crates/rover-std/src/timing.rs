@@ -0,0 +1,104 @@
+//! Records how long each phase of a command takes, such as authenticating or composing, so that
+//! `--verbose` can show where the time went.
+//!
+//! Phases are recorded for the whole process and can nest, so a `network` phase that's part of
+//! `resolution` counts towards both of them.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+static TIMINGS: Mutex<Vec<(Instant, PhaseTiming)>> = Mutex::new(Vec::new());
+
+/// The time spent in one phase, across every time it ran
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhaseTiming {
+    pub phase: &'static str,
+    pub duration: Duration,
+    pub count: u32,
+}
+
+/// Times a phase until it's dropped
+#[must_use = "the phase is timed until this is dropped"]
+pub struct PhaseScope {
+    phase: &'static str,
+    started_at: Instant,
+}
+
+/// Starts timing `phase`, which is recorded once the returned scope is dropped
+pub fn time_phase(phase: &'static str) -> PhaseScope {
+    PhaseScope {
+        phase,
+        started_at: Instant::now(),
+    }
+}
+
+impl Drop for PhaseScope {
+    fn drop(&mut self) {
+        record(self.phase, self.started_at);
+    }
+}
+
+/// Adds the time since `started_at` to the time spent in `phase`
+pub fn record(phase: &'static str, started_at: Instant) {
+    let duration = started_at.elapsed();
+    tracing::debug!("{} took {:?}", phase, duration);
+    let Ok(mut timings) = TIMINGS.lock() else {
+        return;
+    };
+    match timings.iter_mut().find(|(_, timing)| timing.phase == phase) {
+        Some((first_started_at, timing)) => {
+            *first_started_at = (*first_started_at).min(started_at);
+            timing.duration += duration;
+            timing.count += 1;
+        }
+        None => timings.push((
+            started_at,
+            PhaseTiming {
+                phase,
+                duration,
+                count: 1,
+            },
+        )),
+    }
+}
+
+/// Every phase recorded so far, in the order they first started
+pub fn phase_timings() -> Vec<PhaseTiming> {
+    let Ok(timings) = TIMINGS.lock() else {
+        return Vec::new();
+    };
+    let mut timings = timings.clone();
+    timings.sort_by_key(|(first_started_at, _)| *first_started_at);
+    timings.into_iter().map(|(_, timing)| timing).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use speculoos::prelude::*;
+
+    use super::{phase_timings, record, time_phase};
+
+    #[test]
+    fn test_phases_are_summed_and_ordered_by_start() {
+        let started_at = Instant::now() - Duration::from_secs(1);
+        {
+            let _outer = time_phase("test outer");
+            record("test inner", Instant::now());
+            record("test inner", Instant::now());
+        }
+        record("test earliest", started_at);
+
+        let timings: Vec<_> = phase_timings()
+            .into_iter()
+            .filter(|timing| timing.phase.starts_with("test "))
+            .collect();
+        let phases: Vec<_> = timings.iter().map(|timing| timing.phase).collect();
+        assert_that!(phases).is_equal_to(vec!["test earliest", "test outer", "test inner"]);
+        assert_that!(timings[0].duration).is_greater_than_or_equal_to(Duration::from_secs(1));
+        assert_that!(timings[2].count).is_equal_to(2);
+    }
+}
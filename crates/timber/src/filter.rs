@@ -0,0 +1,223 @@
+//! Parses `--log` into the levels each part of Rover logs at, like `warn,composition=trace`
+
+use std::{fmt, str::FromStr};
+
+use tracing_core::LevelFilter;
+use tracing_subscriber::filter::Targets;
+
+/// The parts of Rover that can be given their own level, and the targets (module paths) each
+/// covers. When a target falls under several, the most specific one's level is used, so
+/// `composition=trace,watcher=warn` quiets the watchers that composition uses.
+pub const SUBSYSTEMS: &[(&str, &[&str])] = &[
+    (
+        "composition",
+        &["rover::composition", "rover::command::supergraph"],
+    ),
+    (
+        "watcher",
+        &[
+            "rover::composition::watchers",
+            "rover::command::dev::legacy::watcher",
+            "rover::command::dev::next::router::watchers",
+        ],
+    ),
+    (
+        "router",
+        &[
+            "rover::command::dev::legacy::router",
+            "rover::command::dev::next::router",
+        ],
+    ),
+    ("dev", &["rover::command::dev"]),
+    (
+        "studio",
+        &[
+            "rover_client",
+            "rover_studio",
+            "rover_graphql",
+            "rover_http",
+        ],
+    ),
+    (
+        "plugins",
+        &[
+            "binstall",
+            "rover::command::install",
+            "rover::command::plugin",
+        ],
+    ),
+    ("config", &["houston", "rover::command::config"]),
+    ("telemetry", &["sputnik"]),
+];
+
+/// Which logs are written: a level for everything, and levels for the subsystems or targets that
+/// should log more or less than that. Written like `RUST_LOG`, as a comma-separated list of
+/// `level` and `name=level`, where `name` is one of the [`SUBSYSTEMS`] or a module path like
+/// `rover_client::blocking`. Anything that isn't named logs at the bare level, or not at all
+/// when there isn't one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogFilter {
+    level: Option<LevelFilter>,
+    directives: Vec<(String, LevelFilter)>,
+}
+
+impl LogFilter {
+    /// A filter that logs everything at `level` and above
+    pub fn new(level: LevelFilter) -> Self {
+        Self {
+            level: Some(level),
+            directives: Vec::new(),
+        }
+    }
+
+    /// Whether this filter lets anything through at all
+    pub fn is_enabled(&self) -> bool {
+        self.max_level() > LevelFilter::OFF
+    }
+
+    /// The most verbose level anything is logged at
+    pub fn max_level(&self) -> LevelFilter {
+        self.directives
+            .iter()
+            .map(|(_, level)| *level)
+            .chain(self.level)
+            .max()
+            .unwrap_or(LevelFilter::OFF)
+    }
+
+    /// The tracing filter that applies the levels, with subsystems expanded to their targets
+    pub fn targets(&self) -> Targets {
+        let mut targets = Targets::new().with_default(self.level.unwrap_or(LevelFilter::OFF));
+        for (name, level) in &self.directives {
+            match SUBSYSTEMS.iter().find(|(subsystem, _)| subsystem == name) {
+                Some((_, subsystem_targets)) => {
+                    for target in subsystem_targets.iter() {
+                        targets = targets.with_target(*target, *level);
+                    }
+                }
+                None => targets = targets.with_target(name.clone(), *level),
+            }
+        }
+        targets
+    }
+}
+
+impl FromStr for LogFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = LogFilter::default();
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((name, level)) => {
+                    let name = name.trim();
+                    if name.is_empty() || !name.chars().all(is_target_char) {
+                        return Err(format!(
+                            "'{}' is not a subsystem or module path. Subsystems are: {}",
+                            name,
+                            subsystem_names()
+                        ));
+                    }
+                    let level = parse_level(level.trim())?;
+                    // a later directive for the same name replaces an earlier one
+                    filter.directives.retain(|(existing, _)| existing != name);
+                    filter.directives.push((name.to_string(), level));
+                }
+                None => filter.level = Some(parse_level(directive)?),
+            }
+        }
+        if filter.level.is_none() && filter.directives.is_empty() {
+            return Err("expected a level, or a list of `subsystem=level`".to_string());
+        }
+        Ok(filter)
+    }
+}
+
+impl fmt::Display for LogFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let directives = self
+            .level
+            .iter()
+            .map(|level| level_name(*level))
+            .chain(
+                self.directives
+                    .iter()
+                    .map(|(name, level)| format!("{}={}", name, level_name(*level))),
+            )
+            .collect::<Vec<_>>();
+        write!(f, "{}", directives.join(","))
+    }
+}
+
+fn is_target_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == ':'
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    LevelFilter::from_str(level).map_err(|_| {
+        format!(
+            "'{}' is not a log level. Levels are: off, error, warn, info, debug, trace",
+            level
+        )
+    })
+}
+
+fn level_name(level: LevelFilter) -> String {
+    level.to_string().to_lowercase()
+}
+
+fn subsystem_names() -> String {
+    SUBSYSTEMS
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_core::{Level, LevelFilter};
+
+    use super::LogFilter;
+
+    #[test]
+    fn it_parses_a_bare_level() {
+        let filter: LogFilter = "DEBUG".parse().unwrap();
+        assert_eq!(filter, LogFilter::new(LevelFilter::DEBUG));
+        assert_eq!(filter.to_string(), "debug");
+    }
+
+    #[test]
+    fn it_parses_subsystem_directives() {
+        let filter: LogFilter = "composition=trace, router=warn,info,router=error"
+            .parse()
+            .unwrap();
+        assert_eq!(filter.to_string(), "info,composition=trace,router=error");
+        assert_eq!(filter.max_level(), LevelFilter::TRACE);
+
+        let targets = filter.targets();
+        assert!(targets.would_enable("rover::composition::runner", &Level::TRACE));
+        assert!(!targets.would_enable("rover::command::dev::next::router::run", &Level::WARN));
+        assert!(targets.would_enable("rover_client::blocking", &Level::INFO));
+        assert!(!targets.would_enable("rover_client::blocking", &Level::DEBUG));
+    }
+
+    #[test]
+    fn the_most_specific_subsystem_wins() {
+        let filter: LogFilter = "composition=trace,watcher=warn".parse().unwrap();
+        let targets = filter.targets();
+        assert!(!targets.would_enable("rover::composition::watchers::subgraphs", &Level::INFO));
+        assert!(targets.would_enable("rover::composition::supergraph", &Level::TRACE));
+        // without a bare level, nothing else is logged
+        assert!(!targets.would_enable("rover_client", &Level::ERROR));
+    }
+
+    #[test]
+    fn it_rejects_invalid_directives() {
+        assert!("".parse::<LogFilter>().is_err());
+        assert!("composition=loud".parse::<LogFilter>().is_err());
+        assert!("=trace".parse::<LogFilter>().is_err());
+        assert!("not a target=trace".parse::<LogFilter>().is_err());
+        assert!("verbose".parse::<LogFilter>().is_err());
+    }
+}
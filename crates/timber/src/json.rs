@@ -0,0 +1,207 @@
+//! Formats each event as a single line of JSON, for log aggregators that can't parse the
+//! human-readable format
+
+use std::fmt;
+
+use serde_json::{Map, Number, Value};
+use tracing_core::{
+    field::{Field, Visit},
+    span::Record,
+    Event, Subscriber,
+};
+use tracing_subscriber::{
+    field::RecordFields,
+    fmt::{
+        format::Writer,
+        time::{FormatTime, SystemTime},
+        FmtContext, FormatEvent, FormatFields, FormattedFields,
+    },
+    registry::LookupSpan,
+};
+
+/// Collects the fields of an event or span into a JSON object
+#[derive(Default)]
+struct JsonVisitor(Map<String, Value>);
+
+impl Visit for JsonVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        let value = Number::from_f64(value).map_or(Value::Null, Value::Number);
+        self.0.insert(field.name().to_string(), value);
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.0
+            .insert(field.name().to_string(), value.to_string().into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}").into());
+    }
+}
+
+/// Stores the fields of each span as a JSON object, so [`JsonFormat`] can include them in the
+/// lines of the events that happen inside it
+pub(crate) struct JsonFields;
+
+impl<'writer> FormatFields<'writer> for JsonFields {
+    fn format_fields<R: RecordFields>(
+        &self,
+        mut writer: Writer<'writer>,
+        fields: R,
+    ) -> fmt::Result {
+        let mut visitor = JsonVisitor::default();
+        fields.record(&mut visitor);
+        write!(writer, "{}", Value::Object(visitor.0))
+    }
+
+    fn add_fields(
+        &self,
+        current: &'writer mut FormattedFields<Self>,
+        fields: &Record<'_>,
+    ) -> fmt::Result {
+        let mut visitor = JsonVisitor(
+            serde_json::from_str(&current.fields)
+                .ok()
+                .and_then(|fields: Value| fields.as_object().cloned())
+                .unwrap_or_default(),
+        );
+        fields.record(&mut visitor);
+        current.fields = Value::Object(visitor.0).to_string();
+        Ok(())
+    }
+}
+
+/// Writes each event as a JSON object on a line of its own, like
+///
+/// ```json
+/// {"timestamp":"2024-05-01T12:00:00.000000Z","level":"INFO","target":"rover::composition","fields":{"message":"composing"},"spans":[{"name":"composition","composition_id":"1b4e..."}]}
+/// ```
+pub(crate) struct JsonFormat;
+
+impl<S> FormatEvent<S, JsonFields> for JsonFormat
+where
+    S: Subscriber + for<'lookup> LookupSpan<'lookup>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, JsonFields>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mut timestamp = String::new();
+        SystemTime.format_time(&mut Writer::new(&mut timestamp))?;
+        let metadata = event.metadata();
+        let mut fields = JsonVisitor::default();
+        event.record(&mut fields);
+
+        // from the outermost span in, so the innermost one is last
+        let spans = ctx
+            .event_scope()
+            .into_iter()
+            .flat_map(|scope| scope.from_root())
+            .map(|span| {
+                let mut object = Map::new();
+                object.insert("name".to_string(), span.name().into());
+                if let Some(Value::Object(fields)) = span
+                    .extensions()
+                    .get::<FormattedFields<JsonFields>>()
+                    .and_then(|fields| serde_json::from_str(&fields.fields).ok())
+                {
+                    object.extend(fields);
+                }
+                Value::Object(object)
+            })
+            .collect::<Vec<_>>();
+
+        let mut line = Map::new();
+        line.insert("timestamp".to_string(), timestamp.into());
+        line.insert("level".to_string(), metadata.level().as_str().into());
+        line.insert("target".to_string(), metadata.target().into());
+        line.insert("fields".to_string(), Value::Object(fields.0));
+        if !spans.is_empty() {
+            line.insert("spans".to_string(), Value::Array(spans));
+        }
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use serde_json::{json, Value};
+    use tracing_subscriber::fmt;
+
+    use super::{JsonFields, JsonFormat};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn it_writes_events_as_json_lines() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = fmt()
+            .fmt_fields(JsonFields)
+            .event_format(JsonFormat)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let composition = tracing::info_span!("composition", composition_id = "abc");
+            let _composition = composition.enter();
+            let subgraph = tracing::info_span!("subgraph", subgraph = "products");
+            let _subgraph = subgraph.enter();
+            tracing::info!(attempt = 2, "introspecting");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1);
+        let mut line: Value = serde_json::from_str(lines[0]).unwrap();
+        assert!(line["timestamp"].is_string());
+        line.as_object_mut().unwrap().remove("timestamp");
+        assert_eq!(
+            line,
+            json!({
+                "level": "INFO",
+                "target": "timber::json::tests",
+                "fields": { "message": "introspecting", "attempt": 2 },
+                "spans": [
+                    { "name": "composition", "composition_id": "abc" },
+                    { "name": "subgraph", "subgraph": "products" },
+                ],
+            })
+        );
+    }
+}
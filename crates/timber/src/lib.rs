@@ -5,9 +5,13 @@
 
 use clap::ValueEnum;
 use std::io;
-use tracing_subscriber::fmt;
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt};
 
-pub use tracing_core::Level;
+mod filter;
+mod json;
+
+pub use filter::{LogFilter, SUBSYSTEMS};
+pub use tracing_core::{Level, LevelFilter};
 
 #[derive(Clone, ValueEnum)]
 pub(crate) enum RoverLogLevel {
@@ -28,17 +32,51 @@ impl std::fmt::Display for RoverLogLevel {
     }
 }
 
+/// How each log line is written
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable text, spread over several lines
+    #[default]
+    Text,
+    /// One JSON object per line, with a timestamp, level, target, the event's fields,
+    /// and the fields of the spans it happened in, for log aggregators
+    Json,
+}
+
+impl std::fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self.to_possible_value() {
+            Some(possible_value) => possible_value.get_name().to_string(),
+            None => "unknown".to_string(),
+        };
+        write!(f, "{}", msg)
+    }
+}
+
 /// Initializes a global tracing subscriber that formats
 /// all logs produced by an application that calls init,
 /// and all logs produced by libraries consumed by that application.
-pub fn init(level: Option<Level>) {
+pub fn init(filter: Option<&LogFilter>, format: LogFormat) {
     // by default, no logs are printed.
-    if let Some(level) = level {
-        let format = fmt::format().without_time().pretty();
-        fmt()
-            .with_max_level(level)
-            .event_format(format)
-            .with_writer(io::stderr)
-            .init();
+    let Some(filter) = filter.filter(|filter| filter.is_enabled()) else {
+        return;
+    };
+    let subscriber = tracing_subscriber::registry().with(filter.targets());
+    match format {
+        LogFormat::Text => subscriber
+            .with(
+                fmt::layer()
+                    .event_format(fmt::format().without_time().pretty())
+                    .with_writer(io::stderr),
+            )
+            .init(),
+        LogFormat::Json => subscriber
+            .with(
+                fmt::layer()
+                    .fmt_fields(json::JsonFields)
+                    .event_format(json::JsonFormat)
+                    .with_writer(io::stderr),
+            )
+            .init(),
     }
 }
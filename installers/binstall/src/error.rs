@@ -1,8 +1,11 @@
+use camino::Utf8PathBuf;
 use rover_std::RoverStdError;
 use thiserror::Error;
 
 use std::io;
 
+const MIB: u64 = 1024 * 1024;
+
 /// InstallerError is the type of Error that occurred while installing.
 #[derive(Error, Debug)]
 pub enum InstallerError {
@@ -42,4 +45,16 @@ pub enum InstallerError {
 
     #[error(transparent)]
     RoverStdError(#[from] RoverStdError),
+
+    /// There isn't enough free space to download or install a binary
+    #[error(
+        "{path} only has {} MiB free, but about {} MiB are needed",
+        .available_bytes / MIB,
+        .required_bytes.div_ceil(MIB)
+    )]
+    InsufficientDiskSpace {
+        path: Utf8PathBuf,
+        required_bytes: u64,
+        available_bytes: u64,
+    },
 }
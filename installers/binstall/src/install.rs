@@ -1,9 +1,10 @@
 use std::env;
 use std::io::{self, IsTerminal, Write};
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use url::Url;
 
+use rover_std::progress::{Progress, ProgressBar};
 use rover_std::Fs;
 
 use crate::InstallerError;
@@ -42,15 +43,19 @@ impl Installer {
     /// Checks if a binary already exists, and if it does not,
     /// downloads a plugin tarball from a URL, extracts the binary,
     /// and puts it in the `bin` directory for the main tool
+    ///
+    /// `no_redirect_client` is used to look up the latest version,
+    /// see [`Installer::get_plugin_version`]
     pub async fn install_plugin(
         &self,
         plugin_name: &str,
         plugin_tarball_url: &str,
         client: &reqwest::Client,
+        no_redirect_client: &reqwest::Client,
         is_latest: bool,
     ) -> Result<Option<Utf8PathBuf>, InstallerError> {
         let version = self
-            .get_plugin_version(plugin_tarball_url, is_latest)
+            .get_plugin_version(plugin_tarball_url, is_latest, no_redirect_client)
             .await?;
 
         let bin_dir_path = self.get_bin_dir_path()?;
@@ -79,15 +84,16 @@ impl Installer {
         Ok(Some(plugin_bin_destination))
     }
 
+    /// Gets the version a plugin tarball URL resolves to. For `latest` URLs,
+    /// this reads the `X-Version` header of the redirect to the tarball, so
+    /// `no_redirect_client` must be built not to follow redirects
     pub async fn get_plugin_version(
         &self,
         plugin_tarball_url: &str,
         is_latest: bool,
+        no_redirect_client: &reqwest::Client,
     ) -> Result<String, InstallerError> {
         if is_latest {
-            let no_redirect_client = reqwest::Client::builder()
-                .redirect(reqwest::redirect::Policy::none())
-                .build()?;
             let response = no_redirect_client
                 .head(plugin_tarball_url)
                 .send()
@@ -189,6 +195,10 @@ impl Installer {
         plugin_version: &str,
     ) -> Result<(), InstallerError> {
         let plugin_destination = self.get_plugin_bin_path(plugin_name, plugin_version)?;
+        require_disk_space(
+            &self.get_bin_dir_path()?,
+            Fs::metadata(plugin_bin_path)?.len(),
+        )?;
         Fs::copy(plugin_bin_path, plugin_destination)?;
         // clean up temp dir
         if let Some(dist) = plugin_bin_path.parent() {
@@ -248,17 +258,25 @@ impl Installer {
         let download_dir = tempfile::Builder::new().prefix(plugin_name).tempdir()?;
         let download_dir_path = Utf8PathBuf::try_from(download_dir.into_path())?;
         let tarball_path = download_dir_path.join(format!("{}.tar.gz", plugin_name));
-        let mut f = std::fs::File::create(&tarball_path)?;
-        let response_bytes = client
+        let response = client
             .get(plugin_tarball_url)
             .header(reqwest::header::USER_AGENT, "rover-client")
             .header(reqwest::header::ACCEPT, "application/octet-stream")
             .send()
             .await?
-            .error_for_status()?
-            .bytes()
-            .await?;
-        f.write_all(&response_bytes[..])?;
+            .error_for_status()?;
+        // fail before downloading anything rather than partway through writing it out
+        if let Some(tarball_bytes) = response.content_length() {
+            require_disk_space(
+                &download_dir_path,
+                tarball_bytes + tarball_bytes * UNPACKED_RATIO,
+            )?;
+            require_disk_space(&self.get_bin_dir_path()?, tarball_bytes * UNPACKED_RATIO)?;
+        }
+        let mut f = std::fs::File::create(&tarball_path)?;
+        let progress = ProgressBar::new(format!("downloading the '{}' plugin", plugin_name));
+        download(response, &mut f, &progress).await?;
+        progress.finish();
         f.sync_all()?;
         let f = std::fs::File::open(&tarball_path)?;
         let tar = flate2::read::GzDecoder::new(f);
@@ -285,3 +303,39 @@ impl Installer {
         crate::unix::add_binary_to_path(self)
     }
 }
+
+/// Writes the body of `response` to `writer` as it's received, reporting how much of it has
+/// been written to `progress` when the response says how long it is
+async fn download(
+    mut response: reqwest::Response,
+    writer: &mut impl Write,
+    progress: &impl Progress,
+) -> Result<(), InstallerError> {
+    if let Some(length) = response.content_length() {
+        progress.set_length(length);
+    }
+    while let Some(chunk) = response.chunk().await? {
+        writer.write_all(&chunk)?;
+        progress.inc(chunk.len() as u64);
+    }
+    Ok(())
+}
+
+/// Roughly how many times larger a plugin binary is than its tarball, to estimate the space an
+/// install needs before downloading it
+const UNPACKED_RATIO: u64 = 4;
+
+/// Fails when the filesystem holding `path` has less than `required_bytes` free. Passes when
+/// that can't be told, so installs work the same as before on those platforms
+pub fn require_disk_space(path: &Utf8Path, required_bytes: u64) -> Result<(), InstallerError> {
+    match Fs::available_space(path) {
+        Some(available_bytes) if available_bytes < required_bytes => {
+            Err(InstallerError::InsufficientDiskSpace {
+                path: path.to_path_buf(),
+                required_bytes,
+                available_bytes,
+            })
+        }
+        _ => Ok(()),
+    }
+}
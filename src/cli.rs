@@ -1,5 +1,7 @@
 use camino::Utf8PathBuf;
-use clap::{Parser, ValueEnum};
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, ValueEnum};
 use lazycell::{AtomicLazyCell, LazyCell};
 use reqwest::Client;
 use serde::Serialize;
@@ -7,9 +9,13 @@ use serde::Serialize;
 use crate::command::{self, RoverOutput};
 use crate::options::OutputOpts;
 use crate::utils::{
-    client::{ClientBuilder, ClientTimeout, StudioClientConfig},
+    client::{ClientBuilder, ClientIdentity, ClientTimeout, StudioClientConfig},
+    dns::{CachingResolver, DnsServer},
     env::{RoverEnv, RoverEnvKey},
-    stringify::option_from_display,
+    migrations,
+    project::ProjectDefaults,
+    runtime_mode::RuntimeMode,
+    stringify::{from_display, option_from_display},
     version,
 };
 use crate::RoverResult;
@@ -17,8 +23,9 @@ use crate::RoverResult;
 use config::Config;
 use houston as config;
 use rover_client::shared::GitContext;
+use rover_std::{timing, warnln};
 use sputnik::Session;
-use timber::Level;
+use timber::{LogFilter, LogFormat};
 
 use std::fmt::Display;
 use std::{io, process};
@@ -57,10 +64,19 @@ pub struct Rover {
     #[clap(subcommand)]
     command: Command,
 
-    /// Specify Rover's log level
+    /// Specify Rover's log level, like `debug`, or the level of each subsystem, like
+    /// `composition=trace,router=warn`. Subsystems are composition, watcher, router, dev,
+    /// studio, plugins, config, and telemetry; anything not named logs at the bare level, if
+    /// one is given
     #[arg(long = "log", short = 'l', global = true)]
     #[serde(serialize_with = "option_from_display")]
-    log_level: Option<Level>,
+    log_level: Option<LogFilter>,
+
+    /// How to write logs: `text` for people, or `json` for one JSON object per line, with the
+    /// fields of the spans each event happened in, like the subgraph being resolved
+    #[arg(long = "log-format", global = true, default_value_t = LogFormat::default())]
+    #[serde(serialize_with = "from_display")]
+    log_format: LogFormat,
 
     #[clap(flatten)]
     output_opts: OutputOpts,
@@ -92,10 +108,52 @@ pub struct Rover {
     )]
     client_timeout: ClientTimeout,
 
+    /// A PEM-encoded client certificate to present when performing HTTPS requests to servers that
+    /// require mutual TLS. Must be used with `--client-key`.
+    #[arg(long = "client-cert", global = true, requires = "client_key")]
+    #[serde(skip_serializing)]
+    client_cert: Option<Utf8PathBuf>,
+
+    /// The PEM-encoded PKCS #8 private key for the certificate passed to `--client-cert`.
+    #[arg(long = "client-key", global = true, requires = "client_cert")]
+    #[serde(skip_serializing)]
+    client_key: Option<Utf8PathBuf>,
+
+    /// A DNS server to resolve hostnames with instead of the system resolver, as `IP[:PORT]`.
+    /// Resolved addresses are cached either way, so that requests polling the same hosts don't
+    /// wait on DNS every time.
+    #[arg(long = "dns-server", global = true, value_name = "IP[:PORT]")]
+    #[serde(skip_serializing)]
+    dns_server: Option<DnsServer>,
+
     /// Skip checking for newer versions of rover.
     #[arg(long = "skip-update-check", global = true)]
     skip_update_check: bool,
 
+    /// Run without the network: don't talk to Apollo Studio, report telemetry, check for updates,
+    /// or download plugins. Commands that can't work without the network fail right away.
+    ///
+    /// Can also be turned on by setting `APOLLO_OFFLINE=true`.
+    #[arg(long = "offline", global = true)]
+    offline: bool,
+
+    /// Don't report anonymous usage data. Same as setting `APOLLO_TELEMETRY_DISABLED=true`.
+    #[arg(long = "no-telemetry", global = true)]
+    no_telemetry: bool,
+
+    /// Don't send anything that would change Apollo Studio. Publish, delete, link, and config
+    /// commands print the GraphQL mutation they would have sent, with credentials and large
+    /// documents redacted, along with what it would have changed. `plugin prune` lists the
+    /// versions it would remove.
+    #[arg(long = "dry-run", global = true)]
+    dry_run: bool,
+
+    /// When Apollo Studio can't be reached, have `graph fetch`, `subgraph list`, and
+    /// `config whoami` print the response they last got instead, with a warning saying how old
+    /// it is.
+    #[arg(long = "allow-stale", global = true)]
+    allow_stale: bool,
+
     #[arg(skip)]
     #[serde(skip_serializing)]
     env_store: LazyCell<RoverEnv>,
@@ -111,13 +169,65 @@ pub struct Rover {
 
 impl Rover {
     pub async fn run_from_args() -> RoverResult<()> {
-        Rover::parse().run().await
+        // warn before parsing, since usage that a change made obsolete often doesn't parse anymore
+        let args: Vec<String> = std::env::args_os()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        migrations::warn_about_obsolete_usage(&args, |key| std::env::var(key).ok());
+        // options the project sets in its `.apollo/config.yaml` become the defaults for any that
+        // aren't passed
+        let project_defaults = ProjectDefaults::for_current_dir().unwrap_or_else(|err| {
+            warnln!("Ignoring the project's defaults: {}", err);
+            ProjectDefaults::default()
+        });
+        // so do those of the profile the command runs with, for any the project leaves out
+        let defaults = Self::profile_config()
+            .and_then(|config| {
+                project_defaults
+                    .clone()
+                    .with_profile_defaults(&args, &config)
+            })
+            .unwrap_or_else(|err| {
+                warnln!("Ignoring the profile's defaults: {}", err);
+                project_defaults
+            });
+        let mut command = defaults.apply(Rover::command());
+        let mut matches = command.get_matches_mut();
+        // a profile's default applies to every command, so only the flag itself is rejected below
+        let format_passed = matches.value_source("format_kind") == Some(ValueSource::CommandLine);
+        let mut rover = Rover::from_arg_matches_mut(&mut matches)
+            .unwrap_or_else(|err| err.format(&mut command).exit());
+        // only check results have test cases to report, so any other command would print
+        // something that isn't JUnit XML to a file the CI system parses as such
+        if rover.output_opts.format_kind == RoverOutputFormatKind::Junit
+            && !rover.command.reports_checks()
+        {
+            if format_passed {
+                command
+                    .error(
+                        ErrorKind::ArgumentConflict,
+                        "`--format junit` is only supported by `graph check`, `subgraph check`, and `supergraph check`",
+                    )
+                    .exit()
+            }
+            rover.output_opts.format_kind = RoverOutputFormatKind::Plain;
+        }
+        rover.run().await
+    }
+
+    /// The global config that profiles are read from, before the command line has been parsed
+    fn profile_config() -> RoverResult<Config> {
+        let override_home = RoverEnv::new()?
+            .get(RoverEnvKey::ConfigHome)
+            .map(Utf8PathBuf::from);
+        Ok(Config::new(override_home.as_ref(), None)?)
     }
 
     pub async fn run(&self) -> RoverResult<()> {
-        timber::init(self.log_level);
+        timber::init(self.log_level.as_ref(), self.log_format);
         tracing::trace!(command_structure = ?self);
         self.output_opts.set_no_color();
+        self.output_opts.set_quiet();
 
         // attempt to create a new `Session` to capture anonymous usage data
         let rover_output = match Session::new(self) {
@@ -151,16 +261,37 @@ impl Rover {
             Err(_) => self.execute_command().await,
         };
 
+        // a mutation stopped by `--dry-run` is the result the user asked for, not a failure
+        let rover_output = rover_output.or_else(|error| match error.dry_run_request() {
+            Some(request) => Ok(RoverOutput::DryRun(request.clone())),
+            None => Err(error),
+        });
+
         match rover_output {
             Ok(output) => {
-                self.output_opts.handle_output(output)?;
-
-                process::exit(0);
+                let output_timing = timing::time_phase("output");
+                // printing fails on its own when `--json-path` points at nothing, which a CI
+                // script gating on the value needs to see as a failed command
+                let exit_code = match self.output_opts.handle_output(output) {
+                    Ok(()) => 0,
+                    Err(error) => {
+                        self.output_opts.handle_output(error)?;
+                        1
+                    }
+                };
+                drop(output_timing);
+                self.output_opts.print_phase_timings()?;
+
+                process::exit(exit_code);
             }
             Err(error) => {
+                let output_timing = timing::time_phase("output");
+                let exit_code = error.exit_code();
                 self.output_opts.handle_output(error)?;
+                drop(output_timing);
+                self.output_opts.print_phase_timings()?;
 
-                process::exit(1);
+                process::exit(exit_code);
             }
         }
     }
@@ -170,30 +301,45 @@ impl Rover {
         // this only happens once a day automatically
         // we skip this check for the `rover update` commands, since they
         // do their own checks.
-        // the check is also skipped if the `--skip-update-check` flag is passed.
+        // the check is also skipped if the `--skip-update-check` flag is passed,
+        // or when running offline.
         if let Command::Update(_) = &self.command { /* skip check */
-        } else if !self.skip_update_check {
+        } else if !self.skip_update_check && self.get_runtime_mode()?.is_update_check_enabled() {
             let config = self.get_rover_config();
             if let Ok(config) = config {
+                let _timing = timing::time_phase("update check");
                 let _ = version::check_for_update(config, false, self.get_reqwest_client()?).await;
             }
         }
 
+        let _timing = timing::time_phase("command");
+
         match &self.command {
-            Command::Cloud(command) => command.run(self.get_client_config()?).await,
-            Command::Config(command) => command.run(self.get_client_config()?).await,
-            Command::Contract(command) => command.run(self.get_client_config()?).await,
-            Command::Dev(command) => {
+            #[cfg(feature = "composition-js")]
+            Command::Bench(command) => {
                 command
                     .run(self.get_install_override_path()?, self.get_client_config()?)
                     .await
             }
+            Command::Cloud(command) => command.run(self.get_client_config()?).await,
+            Command::Config(command) => command.run(self.get_client_config()?).await,
+            Command::Contract(command) => command.run(self.get_client_config()?).await,
+            Command::Dev(command) => match &command.command {
+                Some(dev_command) => dev_command.run(),
+                None => {
+                    command
+                        .run(self.get_install_override_path()?, self.get_client_config()?)
+                        .await
+                }
+            },
             Command::Supergraph(command) => {
                 command
                     .run(
                         self.get_install_override_path()?,
                         self.get_client_config()?,
                         self.output_opts.output_file.clone(),
+                        self.get_git_context()?,
+                        self.get_checks_timeout_seconds()?,
                     )
                     .await
             }
@@ -208,8 +354,14 @@ impl Rover {
                     )
                     .await
             }
+            Command::Proposal(command) => {
+                command
+                    .run(self.get_client_config()?, self.get_git_context()?)
+                    .await
+            }
             Command::Template(command) => command.run(self.get_client_config()?).await,
             Command::Readme(command) => command.run(self.get_client_config()?).await,
+            Command::Schema(command) => command.run(),
             Command::Subgraph(command) => {
                 command
                     .run(
@@ -217,10 +369,13 @@ impl Rover {
                         self.get_git_context()?,
                         self.get_checks_timeout_seconds()?,
                         &self.output_opts,
+                        self.get_install_override_path()?,
                     )
                     .await
             }
             Command::Update(command) => {
+                self.get_runtime_mode()?
+                    .require_network("Checking for updates to Rover")?;
                 command
                     .run(self.get_rover_config()?, self.get_reqwest_client()?)
                     .await
@@ -233,7 +388,17 @@ impl Rover {
             Command::Info(command) => command.run(),
             Command::Explain(command) => command.run(),
             Command::PersistedQueries(command) => command.run(self.get_client_config()?).await,
+            Command::Plugin(command) => {
+                command
+                    .run(
+                        self.get_install_override_path()?,
+                        self.get_client_config()?,
+                        self.dry_run,
+                    )
+                    .await
+            }
             Command::License(command) => command.run(self.get_client_config()?).await,
+            Command::Meta(command) => command.run(),
         }
     }
 
@@ -260,6 +425,19 @@ impl Rover {
             is_sudo,
             self.get_reqwest_client_builder(),
             Some(self.client_timeout.get_duration()),
+        )
+        .with_runtime_mode(self.get_runtime_mode()?)
+        .with_dry_run(self.dry_run)
+        .with_allow_stale(self.allow_stale))
+    }
+
+    /// Whether Rover can reach the network and report telemetry, from the global flags and the
+    /// env vars that stand in for them
+    pub(crate) fn get_runtime_mode(&self) -> io::Result<RuntimeMode> {
+        Ok(RuntimeMode::new(
+            self.offline,
+            self.no_telemetry,
+            self.get_env_store()?,
         ))
     }
 
@@ -298,7 +476,7 @@ impl Rover {
     pub(crate) fn get_reqwest_client_builder(&self) -> ClientBuilder {
         // return a copy of the underlying client builder if it's already been populated
         if let Some(client_builder) = self.client_builder.borrow() {
-            *client_builder
+            client_builder.clone()
         } else {
             // if a request hasn't been made yet, this cell won't be populated yet
             self.client_builder
@@ -306,7 +484,14 @@ impl Rover {
                     ClientBuilder::new()
                         .accept_invalid_certs(self.accept_invalid_certs)
                         .accept_invalid_hostnames(self.accept_invalid_hostnames)
-                        .with_timeout(self.client_timeout.get_duration()),
+                        .with_timeout(self.client_timeout.get_duration())
+                        .with_client_identity(
+                            self.client_cert
+                                .clone()
+                                .zip(self.client_key.clone())
+                                .map(|(cert, key)| ClientIdentity::new(cert, key)),
+                        )
+                        .with_dns_resolver(CachingResolver::new(self.dns_server)),
                 )
                 .ok();
             self.get_reqwest_client_builder()
@@ -323,16 +508,19 @@ impl Rover {
     }
 
     pub(crate) fn get_env_var(&self, key: RoverEnvKey) -> io::Result<Option<String>> {
-        Ok(if let Some(env_store) = self.env_store.borrow() {
-            env_store.get(key)
-        } else {
-            let env_store = RoverEnv::new()?;
-            let val = env_store.get(key);
+        Ok(self.get_env_store()?.get(key))
+    }
+
+    fn get_env_store(&self) -> io::Result<&RoverEnv> {
+        if self.env_store.borrow().is_none() {
             self.env_store
-                .fill(env_store)
+                .fill(RoverEnv::new()?)
                 .expect("Could not overwrite the existing environment variable store");
-            val
-        })
+        }
+        Ok(self
+            .env_store
+            .borrow()
+            .expect("The environment variable store was just filled"))
     }
 
     #[cfg(test)]
@@ -352,6 +540,10 @@ impl Rover {
 
 #[derive(Debug, Serialize, Parser)]
 pub enum Command {
+    /// Benchmarking commands
+    #[cfg(feature = "composition-js")]
+    Bench(command::Bench),
+
     /// Cloud configuration commands
     Cloud(command::Cloud),
 
@@ -392,7 +584,7 @@ pub enum Command {
     /// or third subgraph) will decompose that subgraph from your supergraph.
     ///
     /// Think plug-n-play USB devices but with your GraphQL APIs!
-    Dev(command::Dev),
+    Dev(Box<command::Dev>),
 
     /// Supergraph schema commands
     Supergraph(command::Supergraph),
@@ -400,6 +592,9 @@ pub enum Command {
     /// Graph API schema commands
     Graph(command::Graph),
 
+    /// Schema proposal commands
+    Proposal(command::Proposal),
+
     /// Commands for working with templates
     Template(command::Template),
 
@@ -409,6 +604,9 @@ pub enum Command {
     /// Subgraph schema commands
     Subgraph(command::Subgraph),
 
+    /// Commands for working with GraphQL schemas locally
+    Schema(command::Schema),
+
     /// Interact with Rover's documentation
     Docs(command::Docs),
 
@@ -419,6 +617,9 @@ pub enum Command {
     #[command(visible_alias = "pq")]
     PersistedQueries(command::PersistedQueries),
 
+    /// Commands for managing the plugin binaries Rover installs, like `supergraph` and `router`
+    Plugin(command::Plugin),
+
     /// Installs Rover
     #[command(hide = true)]
     Install(command::Install),
@@ -432,6 +633,22 @@ pub enum Command {
 
     /// Commands for fetching offline licenses
     License(command::License),
+
+    /// Commands about Rover itself
+    Meta(command::Meta),
+}
+
+impl Command {
+    /// Whether the command's output is the result of a check workflow, which `--format junit`
+    /// reports as test cases
+    fn reports_checks(&self) -> bool {
+        match self {
+            Command::Graph(command) => command.is_check(),
+            Command::Subgraph(command) => command.is_check(),
+            Command::Supergraph(command) => command.is_check(),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Default, ValueEnum, Debug, Serialize, Clone, Copy, Eq, PartialEq)]
@@ -439,6 +656,12 @@ pub enum RoverOutputFormatKind {
     #[default]
     Plain,
     Json,
+    /// Plain output, plus GitHub Actions workflow commands on stderr that annotate check
+    /// failures, lint violations, and composition errors
+    GithubAnnotations,
+    /// JUnit XML with a test case per check task, for CI systems that show them as test results.
+    /// Only supported by the check commands
+    Junit,
 }
 
 impl Display for RoverOutputFormatKind {
@@ -446,6 +669,8 @@ impl Display for RoverOutputFormatKind {
         match self {
             RoverOutputFormatKind::Plain => write!(f, "plain"),
             RoverOutputFormatKind::Json => write!(f, "json"),
+            RoverOutputFormatKind::GithubAnnotations => write!(f, "github-annotations"),
+            RoverOutputFormatKind::Junit => write!(f, "junit"),
         }
     }
 }
@@ -0,0 +1,322 @@
+use std::{
+    io::{self, Read},
+    process::{Child, Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context};
+use apollo_federation_types::{
+    config::{FederationVersion, SupergraphConfig},
+    rover::BuildResult,
+};
+use camino::Utf8PathBuf;
+use clap::Parser;
+use derive_getters::Getters;
+use rover_client::RoverClientError;
+use serde::Serialize;
+
+use crate::{
+    command::{
+        install::{Install, Plugin},
+        supergraph::compose::do_compose::SupergraphConfigSource,
+    },
+    options::PluginOpts,
+    utils::{client::StudioClientConfig, supergraph_config::get_supergraph_config},
+    RoverError, RoverOutput, RoverResult,
+};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Compose {
+    #[clap(flatten)]
+    plugin_opts: PluginOpts,
+
+    #[clap(flatten)]
+    supergraph_config_source: SupergraphConfigSource,
+
+    /// The versions of Apollo Federation to benchmark composition with. Pass this more than once
+    /// (or as a comma-separated list) to compare versions. If no version is supplied, Rover will
+    /// automatically determine the version from the supergraph config
+    #[arg(long = "federation-version", value_delimiter = ',')]
+    federation_versions: Vec<FederationVersion>,
+
+    /// The number of times to run composition with each version of Apollo Federation
+    #[arg(long = "runs", default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+}
+
+/// The results of repeatedly composing a supergraph with a single version of Apollo Federation
+#[derive(Clone, Debug, Eq, PartialEq, Getters)]
+pub struct ComposeBenchmark {
+    federation_version: String,
+    runs: usize,
+    min: Duration,
+    median: Duration,
+    p95: Duration,
+    /// The largest resident set size of any composition run, when the platform reports it
+    peak_memory_bytes: Option<u64>,
+}
+
+impl ComposeBenchmark {
+    fn new(federation_version: String, samples: &[CompositionSample]) -> ComposeBenchmark {
+        let mut durations: Vec<Duration> = samples.iter().map(|sample| sample.duration).collect();
+        durations.sort();
+        let middle = durations.len() / 2;
+        let median = if durations.len() % 2 == 0 {
+            (durations[middle - 1] + durations[middle]) / 2
+        } else {
+            durations[middle]
+        };
+        ComposeBenchmark {
+            federation_version,
+            runs: durations.len(),
+            min: durations[0],
+            median,
+            p95: nearest_rank(&durations, 95),
+            peak_memory_bytes: samples
+                .iter()
+                .filter_map(|sample| sample.peak_memory_bytes)
+                .max(),
+        }
+    }
+}
+
+/// A single timed run of the `supergraph` binary
+struct CompositionSample {
+    duration: Duration,
+    peak_memory_bytes: Option<u64>,
+}
+
+impl Compose {
+    pub async fn run(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+    ) -> RoverResult<RoverOutput> {
+        let supergraph_config = get_supergraph_config(
+            self.supergraph_config_source.graph_ref(),
+            self.supergraph_config_source.supergraph_yaml(),
+            None,
+            client_config.clone(),
+            &self.plugin_opts.profile,
+            true,
+            self.plugin_opts.env.as_deref(),
+            self.plugin_opts.introspection_policy_opts.policy(),
+        )
+        .await?
+        .ok_or_else(|| anyhow!("error getting supergraph config"))?;
+
+        let federation_versions = if self.federation_versions.is_empty() {
+            // this will always be `Some` once the config has been resolved
+            vec![supergraph_config
+                .get_federation_version()
+                .ok_or_else(|| anyhow!("failed to determine the Federation version"))?]
+        } else {
+            self.federation_versions.clone()
+        };
+
+        let dir = tempfile::Builder::new()
+            .prefix("supergraph-bench")
+            .tempdir()?;
+        let mut benchmarks = Vec::with_capacity(federation_versions.len());
+        for federation_version in federation_versions {
+            let exe = self
+                .install_supergraph(
+                    override_install_path.clone(),
+                    client_config.clone(),
+                    federation_version.clone(),
+                )
+                .await?;
+            let exact_version = Self::extract_exact_version(&exe)?;
+
+            // the supergraph binaries only check the major version, so pin the config to it
+            let mut supergraph_config = supergraph_config.clone();
+            supergraph_config.set_federation_version(match exact_version.major {
+                0 | 1 => FederationVersion::LatestFedOne,
+                _ => FederationVersion::LatestFedTwo,
+            });
+            let yaml_path =
+                Utf8PathBuf::try_from(dir.path().join(format!("{exact_version}.yaml")))?;
+            std::fs::write(&yaml_path, serde_yaml::to_string(&supergraph_config)?)?;
+
+            eprintln!(
+                "benchmarking composition with Federation v{} ({} runs)",
+                exact_version, self.runs
+            );
+            let mut samples = Vec::with_capacity(self.runs as usize);
+            for run in 0..self.runs {
+                let (sample, output) = Self::compose_once(&exe, &yaml_path)?;
+                // a failing composition is much cheaper than a successful one, so make sure the
+                // timings actually describe a composed supergraph
+                if run == 0 {
+                    Self::check_composition(&exe, &output, &supergraph_config)?;
+                }
+                samples.push(sample);
+            }
+            benchmarks.push(ComposeBenchmark::new(format!("v{exact_version}"), &samples));
+        }
+
+        Ok(RoverOutput::ComposeBenchmark(benchmarks))
+    }
+
+    async fn install_supergraph(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+        federation_version: FederationVersion,
+    ) -> RoverResult<Utf8PathBuf> {
+        if federation_version.is_fed_two() {
+            self.plugin_opts
+                .elv2_license_accepter
+                .require_elv2_license(&client_config)?;
+        }
+        let install_command = Install {
+            force: false,
+            plugin: Some(Plugin::Supergraph(federation_version)),
+            elv2_license_accepter: self.plugin_opts.elv2_license_accepter,
+        };
+        install_command
+            .get_versioned_plugin(
+                override_install_path,
+                client_config,
+                self.plugin_opts.skip_update,
+            )
+            .await
+    }
+
+    /// Extracts the exact version of Apollo Federation from the name of a `supergraph` binary
+    fn extract_exact_version(exe: &Utf8PathBuf) -> RoverResult<semver::Version> {
+        let file_name = exe
+            .file_name()
+            .ok_or_else(|| anyhow!("{} is not a supergraph binary", exe))?;
+        let without_exe = file_name.strip_suffix(".exe").unwrap_or(file_name);
+        let version = without_exe
+            .strip_prefix("supergraph-v")
+            .unwrap_or(without_exe);
+        Ok(semver::Version::parse(version)?)
+    }
+
+    fn compose_once(
+        exe: &Utf8PathBuf,
+        yaml_path: &Utf8PathBuf,
+    ) -> RoverResult<(CompositionSample, String)> {
+        let start = Instant::now();
+        let mut child = Command::new(exe)
+            .args(["compose", yaml_path.as_ref()])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to execute `{} compose`", exe))?;
+        let mut output = String::new();
+        if let Some(mut stdout) = child.stdout.take() {
+            stdout
+                .read_to_string(&mut output)
+                .with_context(|| format!("Could not read output of `{} compose`", exe))?;
+        }
+        let peak_memory_bytes = wait_for_peak_memory(child)
+            .with_context(|| format!("Failed to wait for `{} compose`", exe))?;
+        let sample = CompositionSample {
+            duration: start.elapsed(),
+            peak_memory_bytes,
+        };
+        Ok((sample, output))
+    }
+
+    fn check_composition(
+        exe: &Utf8PathBuf,
+        output: &str,
+        supergraph_config: &SupergraphConfig,
+    ) -> RoverResult<()> {
+        match serde_json::from_str::<BuildResult>(output)
+            .with_context(|| format!("Output from `{} compose` was malformed.", exe))?
+        {
+            Ok(_) => Ok(()),
+            Err(build_errors) => Err(RoverError::from(RoverClientError::BuildErrors {
+                source: build_errors,
+                num_subgraphs: supergraph_config.get_subgraph_definitions()?.len(),
+            })),
+        }
+    }
+}
+
+/// Waits for a child process to exit, reporting the peak resident set size it reached
+#[cfg(unix)]
+fn wait_for_peak_memory(child: Child) -> io::Result<Option<u64>> {
+    let pid = child.id() as libc::pid_t;
+    let mut status = 0;
+    // SAFETY: `rusage` is plain old data, so an all-zero value is valid for `wait4` to fill in
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        // SAFETY: `pid` belongs to a child we spawned and haven't waited on yet, and `child` is
+        // consumed here so nothing else will try to reap it
+        let result = unsafe { libc::wait4(pid, &mut status, 0, &mut usage) };
+        if result != -1 {
+            break;
+        }
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+    // `ru_maxrss` is reported in bytes on macOS, but in kilobytes everywhere else
+    let max_rss = usage.ru_maxrss as u64;
+    Ok(Some(if cfg!(target_os = "macos") {
+        max_rss
+    } else {
+        max_rss * 1024
+    }))
+}
+
+/// Waits for a child process to exit; peak memory isn't available on this platform
+#[cfg(not(unix))]
+fn wait_for_peak_memory(mut child: Child) -> io::Result<Option<u64>> {
+    child.wait()?;
+    Ok(None)
+}
+
+/// Finds the nearest-rank percentile of a sorted, non-empty set of durations
+fn nearest_rank(sorted: &[Duration], percentile: usize) -> Duration {
+    let rank = (sorted.len() * percentile).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::{ComposeBenchmark, CompositionSample};
+
+    fn samples(millis: &[u64]) -> Vec<CompositionSample> {
+        millis
+            .iter()
+            .enumerate()
+            .map(|(idx, millis)| CompositionSample {
+                duration: Duration::from_millis(*millis),
+                peak_memory_bytes: Some(idx as u64 * 1024),
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case::single_run(&[40], (40, 40, 40))]
+    #[case::odd_runs(&[30, 10, 20], (10, 20, 30))]
+    #[case::even_runs(&[40, 10, 30, 20], (10, 25, 40))]
+    #[case::p95_excludes_outlier(
+        &[10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 11, 500],
+        (10, 10, 11)
+    )]
+    fn test_compose_benchmark_statistics(
+        #[case] millis: &[u64],
+        #[case] expected: (u64, u64, u64),
+    ) {
+        let benchmark = ComposeBenchmark::new("v2.9.0".to_string(), &samples(millis));
+        assert_that!(benchmark.runs()).is_equal_to(&millis.len());
+        assert_that!(benchmark.min()).is_equal_to(&Duration::from_millis(expected.0));
+        assert_that!(benchmark.median()).is_equal_to(&Duration::from_millis(expected.1));
+        assert_that!(benchmark.p95()).is_equal_to(&Duration::from_millis(expected.2));
+        assert_that!(benchmark.peak_memory_bytes())
+            .is_equal_to(&Some((millis.len() as u64 - 1) * 1024));
+    }
+}
@@ -0,0 +1,33 @@
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult};
+
+pub(crate) mod compose;
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Bench {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub enum Command {
+    /// Repeatedly compose supergraph SDL from a set of subgraph schemas, reporting how long
+    /// composition takes and how much memory it uses with each version of Apollo Federation
+    Compose(compose::Compose),
+}
+
+impl Bench {
+    pub async fn run(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+    ) -> RoverResult<RoverOutput> {
+        match &self.command {
+            Command::Compose(command) => command.run(override_install_path, client_config).await,
+        }
+    }
+}
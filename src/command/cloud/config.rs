@@ -1,4 +1,5 @@
 use clap::Parser;
+use rover_std::progressln;
 use serde::Serialize;
 
 use crate::options::{FileOpt, GraphRefOpt, ProfileOpt};
@@ -75,7 +76,7 @@ impl Config {
         client: StudioClient,
         graph: &GraphRefOpt,
     ) -> RoverResult<RoverOutput> {
-        eprintln!("Fetching cloud router config for: {}", graph.graph_ref);
+        progressln!("Fetching cloud router config for: {}", graph.graph_ref);
 
         let cloud_config = fetch::run(
             CloudConfigFetchInput {
@@ -3,10 +3,14 @@ use clap::Parser;
 use rover_std::Style;
 use serde::Serialize;
 
-use config::Profile;
+use config::{CredentialStore, Profile};
 use houston as config;
 
-use crate::{options::ProfileOpt, RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+use crate::{
+    options::ProfileOpt,
+    utils::{client::StudioClientConfig, sso},
+    RoverError, RoverErrorSuggestion, RoverOutput, RoverResult,
+};
 
 #[derive(Debug, Serialize, Parser)]
 /// Authenticate a configuration profile with an API key
@@ -22,20 +26,71 @@ use crate::{options::ProfileOpt, RoverError, RoverErrorSuggestion, RoverOutput,
 pub struct Auth {
     #[clap(flatten)]
     profile: ProfileOpt,
+
+    /// Keep the API key in the OS keychain (the macOS Keychain, Windows Credential Manager, or
+    /// Secret Service) instead of a plaintext file in Rover's config directory
+    #[cfg(feature = "keychain")]
+    #[arg(long = "keychain", conflicts_with = "sso")]
+    keychain: bool,
+
+    /// Log in through your organization's SSO instead of pasting an API key. Rover saves a
+    /// short-lived token, and refreshes it whenever it's about to expire
+    #[arg(long = "sso")]
+    sso: bool,
+
+    /// The URL of your organization's SSO service, which serves the OAuth 2.0 device authorization
+    /// endpoints at `/device/code` and `/token`. Required with --sso
+    #[arg(
+        long = "sso-url",
+        value_name = "URL",
+        env = "APOLLO_SSO_URL",
+        required_if_eq("sso", "true")
+    )]
+    sso_url: Option<String>,
 }
 
 impl Auth {
-    pub fn run(&self, config: config::Config) -> RoverResult<RoverOutput> {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let config = &client_config.config;
+        // clap requires `--sso-url` whenever `--sso` is passed
+        if let (true, Some(sso_url)) = (self.sso, &self.sso_url) {
+            let (token, session) = sso::log_in(sso_url, client_config.client_builder())?;
+            Profile::set_sso_token(&self.profile.profile_name, config, &token, session)?;
+            eprintln!("Successfully logged in. Consider running `rover config whoami` to verify your API authentication.");
+            return Ok(RoverOutput::EmptySuccess);
+        }
+
         let api_key = api_key_prompt()?;
-        Profile::set_api_key(&self.profile.profile_name, &config, &api_key)?;
-        Profile::get_credential(&self.profile.profile_name, &config).map(|_| {
+        Profile::set_api_key_in(
+            &self.profile.profile_name,
+            config,
+            &api_key,
+            self.credential_store(),
+        )?;
+        Profile::get_credential(&self.profile.profile_name, config).map(|_| {
             eprintln!("Successfully saved API key. Consider running `rover config whoami` to verify your API authentication.");
         })?;
         Ok(RoverOutput::EmptySuccess)
     }
 }
 
-fn api_key_prompt() -> RoverResult<String> {
+impl Auth {
+    #[cfg(feature = "keychain")]
+    fn credential_store(&self) -> CredentialStore {
+        if self.keychain {
+            CredentialStore::Keychain
+        } else {
+            CredentialStore::File
+        }
+    }
+
+    #[cfg(not(feature = "keychain"))]
+    fn credential_store(&self) -> CredentialStore {
+        CredentialStore::File
+    }
+}
+
+pub(super) fn api_key_prompt() -> RoverResult<String> {
     let term = console::Term::stderr();
     eprintln!(
         "Go to {} and create a new Personal API Key.",
@@ -66,14 +121,25 @@ mod tests {
     use camino::Utf8Path;
     use serial_test::serial;
 
+    use clap::Parser;
     use houston::{Config, Profile};
 
+    use super::Auth;
+
     const DEFAULT_PROFILE: &str = "default";
     const DEFAULT_KEY: &str = "default-key";
 
     const CUSTOM_PROFILE: &str = "custom";
     const CUSTOM_KEY: &str = "custom-key";
 
+    #[test]
+    fn it_requires_an_sso_url_with_sso() {
+        assert!(Auth::try_parse_from(["auth", "--sso"]).is_err());
+        assert!(
+            Auth::try_parse_from(["auth", "--sso", "--sso-url", "https://sso.example.com"]).is_ok()
+        );
+    }
+
     #[test]
     #[serial]
     fn it_can_set_default_api_key() {
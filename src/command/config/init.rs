@@ -0,0 +1,206 @@
+use std::io::{self, IsTerminal};
+
+use anyhow::anyhow;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use dialoguer::{Input, Select};
+use houston::Profile;
+use rover_client::{
+    operations::config::{
+        graphs::{self, StudioGraph},
+        who_am_i,
+    },
+    shared::GraphRef,
+    RoverClientError,
+};
+use rover_std::{Fs, Style};
+use serde::Serialize;
+
+use crate::command::install::PROJECT_CONFIG_PATH;
+use crate::command::supergraph::init::{render_supergraph_config, SubgraphDiscoveryOpts};
+use crate::options::ProfileOpt;
+use crate::utils::{client::StudioClientConfig, project::ProjectDefaults};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+use super::auth::api_key_prompt;
+
+/// The supergraph config the wizard writes, relative to the project's root
+const SUPERGRAPH_CONFIG_PATH: &str = "supergraph.yaml";
+
+#[derive(Debug, Serialize, Parser)]
+/// Set up the project in the current directory
+///
+/// Authenticates a configuration profile if it isn't already, picks the graph in Apollo Studio
+/// the project belongs to, finds the subgraphs running locally, and writes the project's
+/// `.apollo/config.yaml` along with a `supergraph.yaml` that composes the subgraphs.
+pub struct Init {
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    #[clap(flatten)]
+    discovery: SubgraphDiscoveryOpts,
+}
+
+impl Init {
+    pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        if !io::stderr().is_terminal() {
+            let mut err = RoverError::new(anyhow!(
+                "`rover config init` asks questions, so it needs to be attached to a terminal"
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Authenticate with `{}` and create a supergraph config with `{}` instead.",
+                Style::Command.paint("rover config auth"),
+                Style::Command.paint("rover supergraph init")
+            )));
+            return Err(err);
+        }
+        let project_root = Utf8PathBuf::try_from(std::env::current_dir()?)?;
+
+        self.authenticate(&client_config).await?;
+        let graph_ref = self.pick_graph(&client_config).await?;
+
+        let supergraph_config_path = project_root.join(SUPERGRAPH_CONFIG_PATH);
+        let supergraph_config = if supergraph_config_path.exists() {
+            eprintln!(
+                "Keeping the existing {}. Add subgraphs to it with `{}`.",
+                SUPERGRAPH_CONFIG_PATH,
+                Style::Command.paint("rover supergraph config add-subgraph")
+            );
+            Some(SUPERGRAPH_CONFIG_PATH)
+        } else {
+            let discovered = self.discovery.discover(&client_config).await?;
+            if discovered.is_empty() {
+                eprintln!(
+                    "Could not find any subgraphs running locally. Once they're running, create a supergraph config with `{}`.",
+                    Style::Command.paint("rover supergraph init")
+                );
+                None
+            } else {
+                let (contents, _) = render_supergraph_config(&discovered, false)?;
+                Fs::write_file(&supergraph_config_path, contents)?;
+                eprintln!(
+                    "Wrote {} with {} subgraphs.",
+                    SUPERGRAPH_CONFIG_PATH,
+                    discovered.len()
+                );
+                Some(SUPERGRAPH_CONFIG_PATH)
+            }
+        };
+
+        let config_path = project_root.join(PROJECT_CONFIG_PATH);
+        ProjectDefaults::builder()
+            .graph_ref(graph_ref.to_string())
+            .profile(self.profile.profile_name.clone())
+            .and_supergraph_config(supergraph_config.map(Utf8PathBuf::from))
+            .build()
+            .write(&config_path)?;
+        eprintln!(
+            "Wrote {}. Commands run in this project now use {} and the {} profile by default.",
+            PROJECT_CONFIG_PATH,
+            Style::Link.paint(graph_ref.to_string()),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+        if supergraph_config.is_some() {
+            eprintln!(
+                "Run `{}` to start developing against your subgraphs.",
+                Style::Command.paint("rover dev")
+            );
+        }
+        Ok(RoverOutput::EmptySuccess)
+    }
+
+    /// Makes sure the profile has an API key that Studio accepts, asking for one if it doesn't
+    async fn authenticate(&self, client_config: &StudioClientConfig) -> RoverResult<()> {
+        let profile_name = &self.profile.profile_name;
+        if Profile::get_credential(profile_name, &client_config.config).is_ok() {
+            let client = client_config.get_authenticated_client(&self.profile)?;
+            match who_am_i::run(&client).await {
+                Ok(_) => {
+                    eprintln!(
+                        "Using the API key of the {} profile.",
+                        Style::Command.paint(profile_name)
+                    );
+                    return Ok(());
+                }
+                Err(RoverClientError::InvalidKey) => {
+                    eprintln!(
+                        "The API key of the {} profile is no longer valid.",
+                        Style::Command.paint(profile_name)
+                    );
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        let api_key = api_key_prompt()?;
+        Profile::set_api_key(profile_name, &client_config.config, &api_key)?;
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        who_am_i::run(&client).await?;
+        eprintln!(
+            "Saved the API key to the {} profile.",
+            Style::Command.paint(profile_name)
+        );
+        Ok(())
+    }
+
+    /// Asks which of the graphs the API key can access the project belongs to, or for the ID of
+    /// a graph to create
+    async fn pick_graph(&self, client_config: &StudioClientConfig) -> RoverResult<GraphRef> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        let graphs = graphs::run(&client).await?;
+        let mut items: Vec<String> = graphs.iter().map(describe_graph).collect();
+        items.push("Create a new graph".to_string());
+        let picked = Select::new()
+            .with_prompt("which graph does this project belong to?")
+            .items(&items)
+            .default(0)
+            .interact()?;
+
+        let graph_id = match graphs.get(picked) {
+            Some(graph) => graph.id.clone(),
+            None => {
+                let graph_id: String = Input::new()
+                    .with_prompt("what ID should the new graph have?")
+                    .interact_text()?;
+                eprintln!(
+                    "Create a graph with the ID {} at {} before publishing subgraphs to it.",
+                    Style::Command.paint(&graph_id),
+                    Style::Link.paint("https://studio.apollographql.com")
+                );
+                graph_id
+            }
+        };
+        let variant: String = Input::new()
+            .with_prompt("which variant should the project use?")
+            .default("current".to_string())
+            .interact_text()?;
+        Ok(format!("{graph_id}@{variant}").parse()?)
+    }
+}
+
+fn describe_graph(graph: &StudioGraph) -> String {
+    match &graph.organization {
+        Some(organization) => format!("{} ({}, in {})", graph.id, graph.title, organization),
+        None => format!("{} ({})", graph.id, graph.title),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rover_client::operations::config::graphs::StudioGraph;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::describe_graph;
+
+    #[rstest]
+    #[case::organization(Some("Acme"), "storefront (Storefront, in Acme)")]
+    #[case::no_organization(None, "storefront (Storefront)")]
+    fn test_describe_graph(#[case] organization: Option<&str>, #[case] expected: &str) {
+        let graph = StudioGraph {
+            id: "storefront".to_string(),
+            title: "Storefront".to_string(),
+            organization: organization.map(ToString::to_string),
+        };
+        assert_that!(describe_graph(&graph)).is_equal_to(expected.to_string());
+    }
+}
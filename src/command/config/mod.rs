@@ -1,7 +1,10 @@
 mod auth;
 mod clear;
 mod delete;
+#[cfg(feature = "composition-js")]
+mod init;
 mod list;
+mod profile;
 mod whoami;
 
 use clap::Parser;
@@ -27,9 +30,16 @@ pub enum Command {
     /// Delete a configuration profile
     Delete(delete::Delete),
 
+    /// Set up the project in the current directory
+    #[cfg(feature = "composition-js")]
+    Init(init::Init),
+
     /// List all configuration profiles
     List(list::List),
 
+    /// Clone, rename, or set the defaults of configuration profiles
+    Profile(profile::Profile),
+
     /// View the identity of a user/api key
     Whoami(whoami::WhoAmI),
 }
@@ -37,10 +47,13 @@ pub enum Command {
 impl Config {
     pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
         match &self.command {
-            Command::Auth(command) => command.run(client_config.config),
+            Command::Auth(command) => command.run(client_config),
             Command::List(command) => command.run(client_config.config),
             Command::Delete(command) => command.run(client_config.config),
             Command::Clear(command) => command.run(client_config.config),
+            Command::Profile(command) => command.run(client_config.config),
+            #[cfg(feature = "composition-js")]
+            Command::Init(command) => command.run(client_config).await,
             Command::Whoami(command) => command.run(client_config).await,
         }
     }
@@ -0,0 +1,32 @@
+use clap::Parser;
+use serde::Serialize;
+
+use houston as config;
+
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// Copy a configuration profile, with its API key and defaults, to a new profile
+///
+/// This is useful for starting a profile that only differs from an existing one in its defaults,
+/// such as one per organization. The new profile must not exist yet.
+pub struct CloneProfile {
+    /// The profile to copy
+    #[serde(skip_serializing)]
+    from: String,
+
+    /// The name of the new profile
+    #[serde(skip_serializing)]
+    to: String,
+}
+
+impl CloneProfile {
+    pub fn run(&self, config: config::Config) -> RoverResult<RoverOutput> {
+        config::Profile::copy(&self.from, &self.to, &config)?;
+        eprintln!(
+            "Successfully cloned profile \"{}\" to \"{}\"",
+            &self.from, &self.to
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
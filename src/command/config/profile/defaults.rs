@@ -0,0 +1,96 @@
+use apollo_federation_types::config::FederationVersion;
+use clap::{Parser, ValueEnum};
+use rover_client::shared::GraphRef;
+use serde::Serialize;
+use timber::LogFilter;
+
+use houston as config;
+
+use crate::cli::RoverOutputFormatKind;
+use crate::options::ProfileOpt;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// View or change the defaults of a configuration profile
+///
+/// Commands run with the profile use its defaults for the options they aren't passed, and
+/// defaults a project sets in its `.apollo/config.yaml` take precedence over the profile's.
+/// Without any options, this prints the profile's defaults.
+pub struct Defaults {
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// The graph ref to use for commands that take one
+    #[arg(long = "graph-ref")]
+    #[serde(skip_serializing)]
+    default_graph_ref: Option<GraphRef>,
+
+    /// The federation version to compose with
+    #[arg(long = "federation-version")]
+    #[serde(skip_serializing)]
+    default_federation_version: Option<FederationVersion>,
+
+    /// The format to print output in
+    #[arg(long = "output-format", value_enum)]
+    #[serde(skip_serializing)]
+    default_output_format: Option<RoverOutputFormatKind>,
+
+    /// What to log, in the format `--log` takes, like `composition=trace,router=warn`
+    #[arg(long = "log-level")]
+    #[serde(skip_serializing)]
+    default_log: Option<LogFilter>,
+
+    /// Remove defaults from the profile, as a comma-separated list
+    #[arg(long = "unset", value_enum, value_delimiter = ',')]
+    #[serde(skip_serializing)]
+    unset: Vec<ProfileDefault>,
+}
+
+/// A default that a profile can set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ProfileDefault {
+    GraphRef,
+    FederationVersion,
+    OutputFormat,
+    LogLevel,
+}
+
+impl Defaults {
+    pub fn run(&self, config: config::Config) -> RoverResult<RoverOutput> {
+        let profile_name = &self.profile.profile_name;
+        let mut defaults = config::Profile::get_defaults(profile_name, &config)?;
+        let changed = self.default_graph_ref.is_some()
+            || self.default_federation_version.is_some()
+            || self.default_output_format.is_some()
+            || self.default_log.is_some()
+            || !self.unset.is_empty();
+        if changed {
+            for unset in &self.unset {
+                match unset {
+                    ProfileDefault::GraphRef => defaults.graph_ref = None,
+                    ProfileDefault::FederationVersion => defaults.federation_version = None,
+                    ProfileDefault::OutputFormat => defaults.output_format = None,
+                    ProfileDefault::LogLevel => defaults.log = None,
+                }
+            }
+            if let Some(graph_ref) = &self.default_graph_ref {
+                defaults.graph_ref = Some(graph_ref.to_string());
+            }
+            if let Some(federation_version) = &self.default_federation_version {
+                defaults.federation_version = Some(federation_version.to_string());
+            }
+            if let Some(output_format) = &self.default_output_format {
+                defaults.output_format = Some(output_format.to_string());
+            }
+            if let Some(log) = &self.default_log {
+                defaults.log = Some(log.to_string());
+            }
+            config::Profile::set_defaults(profile_name, &config, &defaults)?;
+            eprintln!("Successfully updated the defaults of profile \"{profile_name}\"");
+        }
+        Ok(RoverOutput::ProfileDefaults {
+            profile: profile_name.to_string(),
+            defaults,
+        })
+    }
+}
@@ -0,0 +1,38 @@
+mod clone;
+mod defaults;
+mod rename;
+
+use clap::Parser;
+use serde::Serialize;
+
+use houston as config;
+
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Profile {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub enum Command {
+    /// Copy a configuration profile, with its API key and defaults, to a new profile
+    Clone(clone::CloneProfile),
+
+    /// View or change the defaults of a configuration profile
+    Defaults(defaults::Defaults),
+
+    /// Rename a configuration profile
+    Rename(rename::Rename),
+}
+
+impl Profile {
+    pub fn run(&self, config: config::Config) -> RoverResult<RoverOutput> {
+        match &self.command {
+            Command::Clone(command) => command.run(config),
+            Command::Defaults(command) => command.run(config),
+            Command::Rename(command) => command.run(config),
+        }
+    }
+}
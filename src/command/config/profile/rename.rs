@@ -0,0 +1,34 @@
+use clap::Parser;
+use serde::Serialize;
+
+use houston as config;
+
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// Rename a configuration profile
+///
+/// The profile keeps its API key and defaults. A profile with the new name must not exist yet.
+///
+/// WARNING: Renaming the `default` profile will result in
+/// errors when running commands without specifying a `--profile`.
+pub struct Rename {
+    /// The profile to rename
+    #[serde(skip_serializing)]
+    from: String,
+
+    /// The new name of the profile
+    #[serde(skip_serializing)]
+    to: String,
+}
+
+impl Rename {
+    pub fn run(&self, config: config::Config) -> RoverResult<RoverOutput> {
+        config::Profile::rename(&self.from, &self.to, &config)?;
+        eprintln!(
+            "Successfully renamed profile \"{}\" to \"{}\"",
+            &self.from, &self.to
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
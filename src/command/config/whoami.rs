@@ -1,8 +1,9 @@
 use anyhow::anyhow;
+use chrono::Duration;
 use clap::Parser;
-use rover_client::blocking::StudioClient;
 use rover_client::operations::config::who_am_i::{self, Actor, RegistryIdentity};
-use serde::Serialize;
+use rover_std::progressln;
+use serde::{Deserialize, Serialize};
 
 use houston::{mask_key, CredentialOrigin};
 
@@ -13,6 +14,15 @@ use crate::{RoverError, RoverOutput, RoverResult};
 
 use houston as config;
 
+/// What Studio reported about the API key, as it's cached for `--allow-stale`
+#[derive(Debug, Serialize, Deserialize)]
+struct Identity {
+    graph_id: Option<String>,
+    graph_title: Option<String>,
+    key_type: String,
+    user_id: Option<String>,
+}
+
 #[derive(Debug, Serialize, Parser)]
 pub struct WhoAmI {
     #[clap(flatten)]
@@ -25,12 +35,41 @@ pub struct WhoAmI {
     /// If you are sharing your screen your API key could be compromised
     #[arg(long)]
     insecure_unmask_key: bool,
+
+    /// Also report where the API key was found, where it's kept, what it can access, and the
+    /// order the places it could come from are checked in
+    #[arg(long = "show-credential-source")]
+    show_credential_source: bool,
 }
 
 impl WhoAmI {
     pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let (credential, report) = client_config.resolve_credential(&self.profile)?;
+        // keyed by the API key too, so a stale identity is never reported for a different key
+        let identity = client_config
+            .response_cache()
+            .fetch(
+                "whoami",
+                &[&self.profile.profile_name, &credential.api_key],
+                Duration::days(1),
+                self.fetch_identity(&client_config),
+            )
+            .await?;
+
+        Ok(RoverOutput::ConfigWhoAmIOutput {
+            api_key: self.get_maybe_masked_api_key(&credential),
+            graph_id: identity.graph_id,
+            graph_title: identity.graph_title,
+            key_type: identity.key_type,
+            origin: self.get_origin(&credential.origin),
+            user_id: identity.user_id,
+            credential: self.show_credential_source.then_some(report),
+        })
+    }
+
+    async fn fetch_identity(&self, client_config: &StudioClientConfig) -> RoverResult<Identity> {
         let client = client_config.get_authenticated_client(&self.profile)?;
-        eprintln!("Checking identity of your API key against the registry.");
+        progressln!("Checking identity of your API key against the registry.");
 
         let identity = who_am_i::run(&client).await?;
 
@@ -40,15 +79,10 @@ impl WhoAmI {
             )));
         }
 
-        let credential =
-            config::Profile::get_credential(&self.profile.profile_name, &client_config.config)?;
-
-        Ok(RoverOutput::ConfigWhoAmIOutput {
-            api_key: self.get_maybe_masked_api_key(&credential),
+        Ok(Identity {
             graph_id: self.get_graph_id(&identity),
             graph_title: self.get_graph_title(&identity),
             key_type: identity.key_actor_type.to_string(),
-            origin: self.get_origin(&client),
             user_id: self.get_user_id(&identity),
         })
     }
@@ -57,8 +91,8 @@ impl WhoAmI {
         matches!(identity.key_actor_type, Actor::USER | Actor::GRAPH)
     }
 
-    fn get_origin(&self, client: &StudioClient) -> String {
-        match client.get_credential_origin() {
+    fn get_origin(&self, origin: &CredentialOrigin) -> String {
+        match origin {
             CredentialOrigin::ConfigFile(path) => format!("--profile {}", &path),
             CredentialOrigin::EnvVar => format!("${}", &RoverEnvKey::Key),
         }
@@ -104,6 +138,7 @@ mod tests {
                 profile_name: "default".to_string(),
             },
             insecure_unmask_key: unmasked_key,
+            show_credential_source: false,
         }
     }
 
@@ -123,6 +158,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn it_leaves_verbose_to_the_global_flag() {
+        assert!(WhoAmI::try_parse_from(["whoami", "--verbose"]).is_err());
+        assert!(
+            WhoAmI::try_parse_from(["whoami", "--show-credential-source"])
+                .unwrap()
+                .show_credential_source
+        );
+    }
+
     #[test]
     fn it_can_validate_actor_type() {
         let woi = get_who_am_i(false);
@@ -6,7 +6,7 @@ use crate::utils::client::StudioClientConfig;
 use crate::{RoverOutput, RoverResult};
 
 use rover_client::operations::contract::describe::{self, ContractDescribeInput};
-use rover_std::Style;
+use rover_std::{progressln, Style};
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Describe {
@@ -20,7 +20,7 @@ pub struct Describe {
 impl Describe {
     pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
-        eprintln!(
+        progressln!(
             "Fetching description for configuration of {} using credentials from the {} profile.\n",
             Style::Link.paint(self.graph.graph_ref.to_string()),
             Style::Command.paint(&self.profile.profile_name)
@@ -0,0 +1,33 @@
+//! Finds the crash bundles `rover dev` writes to a session's workspace when the router exits
+//! unexpectedly, so a crash can be looked into after the session has ended
+
+use std::env;
+
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::{command::dev::legacy::router::CrashBundle, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct DevCrashes {
+    #[clap(subcommand)]
+    command: DevCrashesCommand,
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub enum DevCrashesCommand {
+    /// List the crash bundles of every `rover dev` session on this machine, newest first
+    List,
+}
+
+impl DevCrashes {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        match self.command {
+            DevCrashesCommand::List => {
+                let tmp_dir = Utf8PathBuf::try_from(env::temp_dir())?;
+                Ok(RoverOutput::DevCrashList(CrashBundle::find_all(&tmp_dir)))
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::io::prelude::*;
 
 use anyhow::{Context, Error};
 use apollo_federation_types::config::{FederationVersion, SupergraphConfig};
+use apollo_federation_types::rover::BuildErrors;
 use camino::Utf8PathBuf;
 use rover_std::{errln, Fs};
 
@@ -155,4 +156,12 @@ impl ComposeRunner {
             Err(err) => Err(err.to_string()),
         })
     }
+
+    /// The build errors of the last composition, if it failed to compose
+    pub fn build_errors(&self) -> Option<BuildErrors> {
+        match &self.composition_state {
+            Some(Err(err)) => err.build_errors().cloned(),
+            _ => None,
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use anyhow::{anyhow, Context};
 use camino::Utf8PathBuf;
 use futures::channel::mpsc::channel;
@@ -9,10 +11,11 @@ use rover_std::warnln;
 use crate::command::dev::{legacy::protocol::FollowerMessage, Dev};
 use crate::utils::client::StudioClientConfig;
 use crate::utils::supergraph_config::get_supergraph_config;
-use crate::{RoverError, RoverOutput, RoverResult};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
 
+use super::docker_compose::{url_map_rules, ComposeServices, ComposeTeardown, ServiceHealth};
 use super::protocol::{FollowerChannel, FollowerMessenger, LeaderChannel, LeaderSession};
-use super::router::RouterConfigHandler;
+use super::router::{PrewarmOperations, RouterConfigHandler};
 
 pub fn log_err_and_continue(err: RoverError) -> RoverError {
     let _ = err.print();
@@ -20,7 +23,7 @@ pub fn log_err_and_continue(err: RoverError) -> RoverError {
 }
 
 impl Dev {
-    pub async fn run(
+    pub(crate) async fn run_legacy(
         &self,
         override_install_path: Option<Utf8PathBuf>,
         client_config: StudioClientConfig,
@@ -30,6 +33,13 @@ impl Dev {
             .prompt_for_license_accept(&client_config)?;
 
         let router_config_handler = RouterConfigHandler::try_from(&self.opts.supergraph_opts)?;
+        let prewarm_operations = self
+            .opts
+            .supergraph_opts
+            .prewarm_operations_path
+            .as_deref()
+            .map(PrewarmOperations::from_file)
+            .transpose()?;
         let router_address = router_config_handler.get_router_address();
         let raw_socket_name = router_config_handler.get_raw_socket_name();
         let leader_channel = LeaderChannel::new();
@@ -42,9 +52,40 @@ impl Dev {
             client_config.clone(),
             &self.opts.plugin_opts.profile,
             false,
+            self.opts.plugin_opts.env.as_deref(),
+            self.opts.plugin_opts.introspection_policy_opts.policy(),
         )
         .await?;
 
+        if supergraph_config.is_none()
+            && client_config.is_offline()
+            && self.opts.subgraph_opts.introspects_schema()
+        {
+            let mut err = RoverError::new(anyhow!("Can't introspect the subgraph while offline"));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Pass the subgraph's schema with `--schema`, or run without `--offline`."
+                    .to_string(),
+            ));
+            return Err(err);
+        }
+
+        let compose_services = self.opts.supergraph_opts.compose_file.clone().map(|file| {
+            ComposeServices::new(file, self.opts.supergraph_opts.compose_profiles.clone())
+        });
+        let mut url_map = self.opts.supergraph_opts.url_map.clone();
+        if let Some(compose_services) = &compose_services {
+            compose_services.up()?;
+            let services = compose_services
+                .ps()
+                .inspect_err(|_| compose_services.down())?;
+            // rules passed with `--url-map` take precedence over the published ports
+            url_map.extend(url_map_rules(&services).inspect_err(|_| compose_services.down())?);
+            compose_services.watch_health(ServiceHealth::new(&services));
+        }
+        // every way this session can end from here on goes through the teardown, so the services
+        // don't outlive it
+        let compose_teardown = ComposeTeardown::new(compose_services);
+
         if let Some(mut leader_session) = LeaderSession::new(
             override_install_path,
             &client_config,
@@ -54,23 +95,43 @@ impl Dev {
             &supergraph_config,
             router_config_handler,
             self.opts.supergraph_opts.license.clone(),
+            prewarm_operations,
+            url_map,
+            self.opts
+                .supergraph_opts
+                .subgraph_url_health_interval
+                .map(Duration::from_secs),
+            self.opts.process_limit_opts,
+            compose_teardown.clone(),
         )
-        .await?
+        .await
+        .inspect_err(|_| compose_teardown.run())?
         {
             warnln!(
                 "Do not run this command in production! It is intended for local development only."
             );
+            if router_address.ip().is_unspecified() && !self.opts.supergraph_opts.bind_public {
+                warnln!(
+                    "the router accepts requests from every device on your network without authentication. Pass `--bind-public` instead of `--supergraph-address` to require a bearer token."
+                );
+            }
             let (ready_sender, mut ready_receiver) = channel(1);
             let follower_messenger = FollowerMessenger::from_main_session(
                 follower_channel.clone().sender,
                 leader_channel.receiver,
             );
 
+            let shutdown_client_config = client_config.clone();
+            let shutdown_compose_teardown = compose_teardown.clone();
             tokio::task::spawn_blocking(move || {
                 ctrlc::set_handler(move || {
                     eprintln!(
                         "\nshutting down the `rover dev` session and all attached processes..."
                     );
+                    shutdown_compose_teardown.run();
+                    if let Some(dns_cache_stats) = shutdown_client_config.dns_cache_stats() {
+                        tracing::info!("DNS cache: {dns_cache_stats}");
+                    }
                     let _ = follower_channel
                         .sender
                         .send(FollowerMessage::shutdown(true))
@@ -115,7 +176,8 @@ impl Dev {
                             follower_messenger.clone(),
                         )
                         .map(|watcher| vec![watcher])
-                })?;
+                })
+                .inspect_err(|_| compose_teardown.run())?;
 
             let futs = subgraph_watchers.into_iter().map(|mut watcher| async move {
                 let _ = watcher
@@ -126,40 +188,46 @@ impl Dev {
             tokio::join!(join_all(futs), subgraph_watcher_handle.map(|_| ()));
         } else {
             let follower_messenger = FollowerMessenger::from_attached_session(&raw_socket_name);
-            let mut subgraph_refresher = self.opts.subgraph_opts.get_subgraph_watcher(
-                router_address,
-                &client_config,
-                follower_messenger.clone(),
-            )?;
+            let mut subgraph_refresher = self
+                .opts
+                .subgraph_opts
+                .get_subgraph_watcher(router_address, &client_config, follower_messenger.clone())
+                .inspect_err(|_| compose_teardown.run())?;
             tracing::info!(
                 "connecting to existing `rover dev` process by communicating via the interprocess socket located at {raw_socket_name}",
             );
 
             // start the interprocess socket health check in the background
             let health_messenger = follower_messenger.clone();
+            let health_compose_teardown = compose_teardown.clone();
             tokio::task::spawn_blocking(move || {
                 let _ = health_messenger.health_check().map_err(|_| {
                     eprintln!("shutting down...");
+                    health_compose_teardown.run();
                     std::process::exit(1);
                 });
             });
 
             // set up the ctrl+c handler to notify the main session to remove the killed subgraph
             let kill_name = subgraph_refresher.get_name();
+            let kill_compose_teardown = compose_teardown.clone();
             ctrlc::set_handler(move || {
                 eprintln!("\nshutting down...");
+                kill_compose_teardown.run();
                 let _ = follower_messenger
                     .remove_subgraph(&kill_name)
                     .map_err(log_err_and_continue);
                 std::process::exit(1);
             })
-            .context("could not set ctrl-c handler")?;
+            .context("could not set ctrl-c handler")
+            .inspect_err(|_| compose_teardown.run())?;
 
             // watch for subgraph changes on the main thread
             // it will take care of updating the main `rover dev` session
             subgraph_refresher
                 .watch_subgraph_for_changes(client_config.retry_period)
-                .await?;
+                .await
+                .inspect_err(|_| compose_teardown.run())?;
         }
 
         unreachable!("watch_subgraph_for_changes never returns")
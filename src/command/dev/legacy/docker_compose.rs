@@ -0,0 +1,381 @@
+//! Brings up the docker-compose services a `rover dev` session depends on, for teams whose
+//! subgraphs already run with docker-compose. Their published ports are mapped into the routing
+//! URLs of the subgraphs, their health is reported for as long as the session runs, and they're
+//! torn down when it ends
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    process::Command,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context};
+use camino::Utf8PathBuf;
+use rover_std::{infoln, warnln};
+use serde::Deserialize;
+
+use crate::{command::dev::url_map::UrlMapRule, RoverError, RoverErrorSuggestion, RoverResult};
+
+/// How often the state of the services is checked once they're up
+const HEALTH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The services in a docker-compose file, limited to the ones in `profiles` when there are any
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct ComposeServices {
+    file: Utf8PathBuf,
+    profiles: Vec<String>,
+}
+
+impl ComposeServices {
+    pub(crate) fn new(file: Utf8PathBuf, profiles: Vec<String>) -> Self {
+        Self { file, profiles }
+    }
+
+    /// `docker compose` with the file and profiles of these services
+    fn command(&self) -> Command {
+        let mut command = Command::new("docker");
+        command.arg("compose").arg("--file").arg(&self.file);
+        for profile in &self.profiles {
+            command.arg("--profile").arg(profile);
+        }
+        command
+    }
+
+    /// Starts the services in the background and waits for them to be running, and healthy if
+    /// they have a health check
+    pub(crate) fn up(&self) -> RoverResult<()> {
+        eprintln!("starting the services in {}...", self.file);
+        let status = self
+            .command()
+            .args(["up", "--detach", "--wait"])
+            .status()
+            .map_err(|e| {
+                let mut err = RoverError::new(anyhow!("could not run `docker compose`: {e}"));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Install Docker with the Compose plugin (`docker compose version` should succeed), or run without `--compose-file`."
+                        .to_string(),
+                ));
+                err
+            })?;
+        if !status.success() {
+            self.down();
+            let mut err = RoverError::new(anyhow!(
+                "the services in {} did not start, `docker compose up` exited with {status}",
+                self.file
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Check the output of `docker compose --file {} up` above.",
+                self.file
+            )));
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Stops and removes the services, reporting rather than returning failures since this runs
+    /// as the session shuts down
+    pub(crate) fn down(&self) {
+        eprintln!("stopping the services in {}...", self.file);
+        match self.command().arg("down").status() {
+            Ok(status) if status.success() => {}
+            Ok(status) => warnln!("`docker compose down` exited with {status}"),
+            Err(e) => warnln!("could not run `docker compose down`: {e}"),
+        }
+    }
+
+    /// The state of each running service
+    pub(crate) fn ps(&self) -> RoverResult<Vec<ComposeService>> {
+        let output = self
+            .command()
+            .args(["ps", "--format", "json"])
+            .output()
+            .context("could not run `docker compose ps`")?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "`docker compose ps` exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        parse_ps(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Reports each service that becomes unhealthy or stops, and again when it recovers, for as
+    /// long as the session runs
+    pub(crate) fn watch_health(&self, mut health: ServiceHealth) {
+        let services = self.clone();
+        tokio::task::spawn_blocking(move || loop {
+            std::thread::sleep(HEALTH_INTERVAL);
+            match services.ps() {
+                Ok(ps) => {
+                    for change in health.record(&ps) {
+                        match change {
+                            ServiceChange::Degraded { .. } => warnln!("{}", change),
+                            ServiceChange::Recovered { .. } => infoln!("{}", change),
+                        }
+                    }
+                }
+                Err(e) => tracing::debug!("could not check the state of the services: {e}"),
+            }
+        });
+    }
+}
+
+/// Tears down the [`ComposeServices`] of a session, if it has any, from whichever of the
+/// session's exit paths gets there first. Clones share the teardown, so the services are only
+/// taken down once
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ComposeTeardown {
+    services: Option<ComposeServices>,
+    torn_down: Arc<AtomicBool>,
+}
+
+impl ComposeTeardown {
+    pub(crate) fn new(services: Option<ComposeServices>) -> Self {
+        Self {
+            services,
+            torn_down: Arc::default(),
+        }
+    }
+
+    /// Stops and removes the services, unless another exit path already did
+    pub(crate) fn run(&self) {
+        if let Some(services) = &self.services {
+            if !self.torn_down.swap(true, Ordering::SeqCst) {
+                services.down();
+            }
+        }
+    }
+}
+
+/// A service as listed by `docker compose ps --format json`
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ComposeService {
+    pub(crate) service: String,
+    pub(crate) state: String,
+    #[serde(default)]
+    pub(crate) health: String,
+    #[serde(default)]
+    pub(crate) publishers: Option<Vec<Publisher>>,
+}
+
+/// A port of a service published on the host
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct Publisher {
+    pub(crate) target_port: u16,
+    pub(crate) published_port: u16,
+}
+
+impl ComposeService {
+    /// Whether the service is running, and healthy if it has a health check
+    fn is_healthy(&self) -> bool {
+        self.state == "running" && matches!(self.health.as_str(), "" | "healthy")
+    }
+
+    /// Describes the state of the service, like `running (unhealthy)`
+    fn status(&self) -> String {
+        if self.health.is_empty() {
+            self.state.clone()
+        } else {
+            format!("{} ({})", self.state, self.health)
+        }
+    }
+}
+
+/// Parses the output of `docker compose ps --format json`, which is one JSON object per line in
+/// recent versions of Compose, and a single JSON array in older ones
+pub(crate) fn parse_ps(output: &str) -> RoverResult<Vec<ComposeService>> {
+    let output = output.trim();
+    let services = if output.starts_with('[') {
+        serde_json::from_str(output)?
+    } else {
+        output
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?
+    };
+    Ok(services)
+}
+
+/// Rules that route the URLs of services within the compose network, like
+/// `http://products:4001/graphql`, to the ports they're published on, like
+/// `http://localhost:14001/graphql`
+pub(crate) fn url_map_rules(services: &[ComposeService]) -> RoverResult<Vec<UrlMapRule>> {
+    let mut ports = BTreeMap::new();
+    for service in services {
+        for publisher in service.publishers.iter().flatten() {
+            // a port published on both IPv4 and IPv6 is listed once for each
+            if publisher.published_port != 0 {
+                ports
+                    .entry((service.service.as_str(), publisher.target_port))
+                    .or_insert(publisher.published_port);
+            }
+        }
+    }
+    ports
+        .into_iter()
+        .map(|((service, target_port), published_port)| {
+            format!("*://{service}:{target_port}=>{{1}}://localhost:{published_port}")
+                .parse::<UrlMapRule>()
+                .map_err(RoverError::from)
+        })
+        .collect()
+}
+
+/// A service becoming unhealthy or stopping, or recovering
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum ServiceChange {
+    Degraded { service: String, status: String },
+    Recovered { service: String, status: String },
+}
+
+impl fmt::Display for ServiceChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceChange::Degraded { service, status } => {
+                write!(f, "compose service '{service}' is {status}")
+            }
+            ServiceChange::Recovered { service, status } => {
+                write!(f, "compose service '{service}' is {status} again")
+            }
+        }
+    }
+}
+
+/// Which services were healthy when they were last checked
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct ServiceHealth {
+    healthy: BTreeMap<String, bool>,
+}
+
+impl ServiceHealth {
+    /// Starts from the state of the services once they're up
+    pub(crate) fn new(services: &[ComposeService]) -> Self {
+        let mut health = Self::default();
+        health.record(services);
+        health
+    }
+
+    /// Records the state of the services, returning the ones whose health changed. Services that
+    /// are no longer listed have stopped
+    pub(crate) fn record(&mut self, services: &[ComposeService]) -> Vec<ServiceChange> {
+        let mut changes = Vec::new();
+        let mut healthy = BTreeMap::new();
+        for service in services {
+            let was_healthy = self.healthy.get(&service.service).copied().unwrap_or(true);
+            let is_healthy = service.is_healthy();
+            if was_healthy && !is_healthy {
+                changes.push(ServiceChange::Degraded {
+                    service: service.service.clone(),
+                    status: service.status(),
+                });
+            } else if !was_healthy && is_healthy {
+                changes.push(ServiceChange::Recovered {
+                    service: service.service.clone(),
+                    status: service.status(),
+                });
+            }
+            healthy.insert(service.service.clone(), is_healthy);
+        }
+        for (service, was_healthy) in &self.healthy {
+            if !healthy.contains_key(service) {
+                if *was_healthy {
+                    changes.push(ServiceChange::Degraded {
+                        service: service.clone(),
+                        status: "stopped".to_string(),
+                    });
+                }
+                healthy.insert(service.clone(), false);
+            }
+        }
+        self.healthy = healthy;
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::{parse_ps, url_map_rules, ComposeService, ServiceChange, ServiceHealth};
+    use crate::command::dev::url_map::rewrite_url;
+
+    fn service(name: &str, state: &str, health: &str) -> ComposeService {
+        ComposeService {
+            service: name.to_string(),
+            state: state.to_string(),
+            health: health.to_string(),
+            publishers: None,
+        }
+    }
+
+    #[rstest]
+    #[case::lines(indoc! {r#"
+        {"Service":"products","State":"running","Health":"healthy","Publishers":[{"URL":"0.0.0.0","TargetPort":4001,"PublishedPort":14001,"Protocol":"tcp"},{"URL":"::","TargetPort":4001,"PublishedPort":14001,"Protocol":"tcp"}]}
+        {"Service":"reviews","State":"running","Health":"","Publishers":[{"URL":"","TargetPort":4002,"PublishedPort":0,"Protocol":"tcp"}]}
+    "#})]
+    #[case::array(indoc! {r#"
+        [{"Service":"products","State":"running","Health":"healthy","Publishers":[{"URL":"0.0.0.0","TargetPort":4001,"PublishedPort":14001,"Protocol":"tcp"}]},
+         {"Service":"reviews","State":"running","Health":"","Publishers":null}]
+    "#})]
+    fn test_maps_published_ports_into_routing_urls(#[case] output: &str) {
+        let services = parse_ps(output).unwrap();
+        assert_that!(services).has_length(2);
+
+        let rules = url_map_rules(&services).unwrap();
+        assert_that!(rules).has_length(1);
+        assert_that!(rewrite_url(&rules, "http://products:4001/graphql"))
+            .is_equal_to("http://localhost:14001/graphql".to_string());
+        assert_that!(rewrite_url(&rules, "http://products:4002/graphql"))
+            .is_equal_to("http://products:4002/graphql".to_string());
+        assert_that!(rewrite_url(&rules, "http://reviews:4002/graphql"))
+            .is_equal_to("http://reviews:4002/graphql".to_string());
+    }
+
+    #[test]
+    fn test_records_health_changes() {
+        let mut health = ServiceHealth::new(&[
+            service("products", "running", "healthy"),
+            service("reviews", "running", ""),
+        ]);
+
+        assert_that!(health.record(&[
+            service("products", "running", "unhealthy"),
+            service("reviews", "running", ""),
+        ]))
+        .is_equal_to(vec![ServiceChange::Degraded {
+            service: "products".to_string(),
+            status: "running (unhealthy)".to_string(),
+        }]);
+
+        // staying unhealthy isn't a change, but a service that's no longer listed has stopped
+        assert_that!(health.record(&[service("products", "running", "unhealthy")])).is_equal_to(
+            vec![ServiceChange::Degraded {
+                service: "reviews".to_string(),
+                status: "stopped".to_string(),
+            }],
+        );
+
+        let changes = health.record(&[
+            service("products", "running", "healthy"),
+            service("reviews", "running", ""),
+        ]);
+        assert_that!(changes.iter().map(ToString::to_string).collect::<Vec<_>>()).is_equal_to(
+            vec![
+                "compose service 'products' is running (healthy) again".to_string(),
+                "compose service 'reviews' is running again".to_string(),
+            ],
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Watches that the routing URLs of the subgraphs in a `rover dev` session stay reachable, so a
+//! subgraph that stops accepting connections is reported as degraded rather than only showing up
+//! as errors from the router
+
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+use futures::future::join_all;
+use rover_std::{infoln, warnln};
+use tokio::{net::TcpStream, sync::watch};
+use url::Url;
+
+/// The longest to wait for a subgraph to accept a connection before it counts as unreachable
+const MAX_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Probes the routing URL of each subgraph in the session on an interval, in the background
+#[derive(Debug)]
+pub(crate) struct SubgraphHealthMonitor {
+    routing_urls: watch::Sender<BTreeMap<String, String>>,
+}
+
+impl SubgraphHealthMonitor {
+    /// Starts probing every `interval`. There's nothing to probe until subgraphs are set with
+    /// [`SubgraphHealthMonitor::set_routing_urls`]
+    pub(crate) fn start(interval: Duration) -> Self {
+        let (routing_urls, receiver) = watch::channel(BTreeMap::new());
+        tokio::spawn(monitor(receiver, interval));
+        Self { routing_urls }
+    }
+
+    /// Replaces the subgraphs being probed with `routing_urls`, keyed by subgraph name
+    pub(crate) fn set_routing_urls(&self, routing_urls: BTreeMap<String, String>) {
+        self.routing_urls.send_replace(routing_urls);
+    }
+}
+
+async fn monitor(routing_urls: watch::Receiver<BTreeMap<String, String>>, interval: Duration) {
+    let timeout = interval.min(MAX_PROBE_TIMEOUT);
+    let mut health = SubgraphHealth::default();
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        if routing_urls.has_changed().is_err() {
+            // the session is gone
+            return;
+        }
+        let current = routing_urls.borrow().clone();
+        let probes = join_all(current.iter().map(|(name, url)| async move {
+            (name.clone(), url.clone(), probe(url, timeout).await)
+        }))
+        .await;
+        let changes = health.record(probes);
+        for change in &changes {
+            match change {
+                HealthChange::Degraded { .. } => warnln!("{}", change),
+                HealthChange::Recovered { .. } => infoln!("{}", change),
+            }
+        }
+        if !changes.is_empty() {
+            eprintln!("{}", health);
+        }
+    }
+}
+
+/// Checks that the host of a routing URL accepts connections on its port
+async fn probe(url: &str, timeout: Duration) -> Result<(), String> {
+    let url = Url::parse(url).map_err(|e| format!("invalid routing URL: {e}"))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| "the routing URL has no host".to_string())?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| "the routing URL has no port".to_string())?;
+    match tokio::time::timeout(timeout, TcpStream::connect((host, port))).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(err)) => Err(err.to_string()),
+        Err(_) => Err(format!("no connection after {}ms", timeout.as_millis())),
+    }
+}
+
+/// A subgraph becoming unreachable, or reachable again
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum HealthChange {
+    Degraded {
+        subgraph: String,
+        url: String,
+        reason: String,
+    },
+    Recovered {
+        subgraph: String,
+        url: String,
+    },
+}
+
+impl fmt::Display for HealthChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthChange::Degraded {
+                subgraph,
+                url,
+                reason,
+            } => write!(
+                f,
+                "subgraph '{subgraph}' is unreachable at {url} ({reason}), so the router can't resolve its fields until it recovers"
+            ),
+            HealthChange::Recovered { subgraph, url } => {
+                write!(f, "subgraph '{subgraph}' is reachable at {url} again")
+            }
+        }
+    }
+}
+
+/// Which subgraphs answered their last probe
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct SubgraphHealth {
+    reachable: BTreeMap<String, bool>,
+}
+
+impl SubgraphHealth {
+    /// Records a round of probes, returning the subgraphs whose reachability changed. Subgraphs
+    /// that are probed for the first time only count as a change when they're unreachable, and
+    /// subgraphs that weren't probed are forgotten
+    pub(crate) fn record(
+        &mut self,
+        probes: Vec<(String, String, Result<(), String>)>,
+    ) -> Vec<HealthChange> {
+        let mut reachable = BTreeMap::new();
+        let mut changes = Vec::new();
+        for (subgraph, url, result) in probes {
+            let was_reachable = self.reachable.get(&subgraph).copied().unwrap_or(true);
+            let is_reachable = result.is_ok();
+            match (was_reachable, result) {
+                (false, Ok(())) => changes.push(HealthChange::Recovered {
+                    subgraph: subgraph.clone(),
+                    url,
+                }),
+                (true, Err(reason)) => changes.push(HealthChange::Degraded {
+                    subgraph: subgraph.clone(),
+                    url,
+                    reason,
+                }),
+                _ => {}
+            }
+            reachable.insert(subgraph, is_reachable);
+        }
+        self.reachable = reachable;
+        changes
+    }
+
+    pub(crate) fn degraded(&self) -> Vec<&str> {
+        self.reachable
+            .iter()
+            .filter(|(_, reachable)| !**reachable)
+            .map(|(subgraph, _)| subgraph.as_str())
+            .collect()
+    }
+}
+
+impl fmt::Display for SubgraphHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let degraded = self.degraded();
+        if degraded.is_empty() {
+            write!(f, "all {} subgraphs are reachable", self.reachable.len())
+        } else {
+            write!(
+                f,
+                "{} of {} subgraphs degraded: {}",
+                degraded.len(),
+                self.reachable.len(),
+                degraded.join(", ")
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::TcpListener, time::Duration};
+
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::{probe, HealthChange, SubgraphHealth};
+
+    fn probes(results: &[(&str, bool)]) -> Vec<(String, String, Result<(), String>)> {
+        results
+            .iter()
+            .map(|(subgraph, reachable)| {
+                (
+                    subgraph.to_string(),
+                    format!("http://{subgraph}"),
+                    reachable
+                        .then_some(())
+                        .ok_or_else(|| "connection refused".to_string()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_record_reports_transitions() {
+        let mut health = SubgraphHealth::default();
+        assert_that!(health.record(probes(&[("products", true), ("reviews", false)]))).is_equal_to(
+            vec![HealthChange::Degraded {
+                subgraph: "reviews".to_string(),
+                url: "http://reviews".to_string(),
+                reason: "connection refused".to_string(),
+            }],
+        );
+        assert_that!(health.to_string())
+            .is_equal_to("1 of 2 subgraphs degraded: reviews".to_string());
+
+        // staying degraded isn't a change
+        assert_that!(health.record(probes(&[("products", true), ("reviews", false)]))).is_empty();
+
+        assert_that!(health.record(probes(&[("products", true), ("reviews", true)]))).is_equal_to(
+            vec![HealthChange::Recovered {
+                subgraph: "reviews".to_string(),
+                url: "http://reviews".to_string(),
+            }],
+        );
+        assert_that!(health.to_string()).is_equal_to("all 2 subgraphs are reachable".to_string());
+    }
+
+    #[test]
+    fn test_record_forgets_removed_subgraphs() {
+        let mut health = SubgraphHealth::default();
+        health.record(probes(&[("products", true), ("reviews", false)]));
+        health.record(probes(&[("products", true)]));
+        assert_that!(health.degraded()).is_empty();
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_probe(#[values(true, false)] listening: bool) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        if !listening {
+            drop(listener);
+        }
+        let result = probe(
+            &format!("http://127.0.0.1:{port}/graphql"),
+            Duration::from_secs(1),
+        )
+        .await;
+        assert_that!(result.is_ok()).is_equal_to(listening);
+    }
+
+    #[tokio::test]
+    async fn test_probe_rejects_invalid_urls() {
+        assert_that!(probe("not a url", Duration::from_secs(1)).await).is_err();
+    }
+}
@@ -122,10 +122,13 @@ impl SubgraphIntrospectRunner {
                 endpoint: self.endpoint.clone(),
                 headers: self.headers.clone(),
                 watch: false,
+                diff: false,
                 // TODO: remove after the composition rewrite; this is the de facto default of the
                 // polling interval option, here to make compilation work
                 polling_interval: Duration::from_secs(1),
             },
+            subscriptions: false,
+            auth: None,
         }
         .exec(&self.client, true, self.retry_period)
         .await
@@ -151,10 +154,12 @@ impl GraphIntrospectRunner {
                 endpoint: self.endpoint.clone(),
                 headers: self.headers.clone(),
                 watch: false,
+                diff: false,
                 // TODO: remove after the composition rewrite; this is the de facto default of the
                 // polling interval option, here to make compilation work
                 polling_interval: Duration::from_secs(1),
             },
+            compare_endpoint: None,
         }
         .exec(&self.client, true, self.retry_period)
         .await
@@ -4,6 +4,12 @@ mod compose;
 #[cfg(feature = "composition-js")]
 mod do_dev;
 
+#[cfg(feature = "composition-js")]
+mod docker_compose;
+
+#[cfg(feature = "composition-js")]
+mod health;
+
 #[cfg(feature = "composition-js")]
 mod introspect;
 
@@ -11,13 +17,16 @@ mod introspect;
 mod protocol;
 
 #[cfg(feature = "composition-js")]
-mod router;
+pub(crate) mod router;
+
+#[cfg(feature = "composition-js")]
+mod triage;
 
 #[cfg(feature = "composition-js")]
 mod schema;
 
 #[cfg(feature = "composition-js")]
-mod netstat;
+pub(crate) mod netstat;
 
 #[cfg(not(feature = "composition-js"))]
 mod no_dev;
@@ -1,8 +1,14 @@
+use futures::{stream, StreamExt};
 use reqwest::Url;
 use std::{
     collections::HashSet,
-    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    ops::RangeInclusive,
+    str::FromStr,
+    time::Duration,
 };
+use tokio::net::TcpStream;
 use url::Host;
 
 use crate::command::dev::legacy::protocol::SubgraphUrl;
@@ -60,3 +66,116 @@ pub fn normalize_loopback_urls(url: &SubgraphUrl) -> Vec<SubgraphUrl> {
         )
     }
 }
+
+/// How many ports are probed at once by [`listening_loopback_ports`]
+const CONCURRENT_PROBES: usize = 64;
+
+/// A range of ports written as `PORT` or `FIRST-LAST`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortRange(RangeInclusive<u16>);
+
+impl PortRange {
+    pub fn ports(&self) -> RangeInclusive<u16> {
+        self.0.clone()
+    }
+}
+
+impl FromStr for PortRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_port = |port: &str| {
+            port.trim()
+                .parse::<u16>()
+                .map_err(|_| format!("'{port}' is not a port"))
+        };
+        let (first, last) = match s.split_once('-') {
+            Some((first, last)) => (parse_port(first)?, parse_port(last)?),
+            None => (parse_port(s)?, parse_port(s)?),
+        };
+        if first > last {
+            return Err(format!("'{s}' ends before it starts"));
+        }
+        Ok(PortRange(first..=last))
+    }
+}
+
+impl fmt::Display for PortRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.start() == self.0.end() {
+            write!(f, "{}", self.0.start())
+        } else {
+            write!(f, "{}-{}", self.0.start(), self.0.end())
+        }
+    }
+}
+
+/// Finds the ports in `ports` that something is listening on at the IPv4 loopback address, in
+/// ascending order
+pub async fn listening_loopback_ports(
+    ports: impl IntoIterator<Item = u16>,
+    timeout: Duration,
+) -> Vec<u16> {
+    let mut listening: Vec<u16> = stream::iter(ports.into_iter().collect::<HashSet<_>>())
+        .map(|port| async move {
+            let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
+            match tokio::time::timeout(timeout, TcpStream::connect(addr)).await {
+                Ok(Ok(_)) => Some(port),
+                _ => None,
+            }
+        })
+        .buffer_unordered(CONCURRENT_PROBES)
+        .filter_map(|port| async move { port })
+        .collect()
+        .await;
+    listening.sort_unstable();
+    listening
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, time::Duration};
+
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::{listening_loopback_ports, PortRange};
+
+    #[rstest]
+    #[case::single_port("4001", 4001..=4001)]
+    #[case::range("4000-4010", 4000..=4010)]
+    fn test_parse_port_range(
+        #[case] port_range: &str,
+        #[case] expected: std::ops::RangeInclusive<u16>,
+    ) {
+        let port_range = PortRange::from_str(port_range).unwrap();
+        assert_that!(port_range.ports()).is_equal_to(expected);
+    }
+
+    #[rstest]
+    #[case::not_a_port("graphql")]
+    #[case::too_large("70000")]
+    #[case::backwards("4010-4000")]
+    fn test_parse_port_range_rejects_invalid_ranges(#[case] port_range: &str) {
+        assert_that!(PortRange::from_str(port_range)).is_err();
+    }
+
+    #[tokio::test]
+    async fn test_listening_loopback_ports_finds_listeners() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let listening_port = listener.local_addr().unwrap().port();
+        // bind and drop a listener to find a port that nothing listens on
+        let closed_port = {
+            let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            closed.local_addr().unwrap().port()
+        };
+
+        let listening = listening_loopback_ports(
+            [closed_port, listening_port, listening_port],
+            Duration::from_secs(1),
+        )
+        .await;
+
+        assert_that!(listening).is_equal_to(vec![listening_port]);
+    }
+}
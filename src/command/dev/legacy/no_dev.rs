@@ -6,7 +6,7 @@ use crate::{
 };
 
 impl Dev {
-    pub async fn run(
+    pub(crate) async fn run_legacy(
         &self,
         _override_install_path: Option<Utf8PathBuf>,
         _client_config: StudioClientConfig,
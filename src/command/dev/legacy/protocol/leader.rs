@@ -4,11 +4,12 @@ use std::{
     io::BufReader,
     net::TcpListener,
     str::FromStr,
+    time::Duration,
 };
 
 use anyhow::{anyhow, Context};
 use apollo_federation_types::{
-    config::{FederationVersion, SupergraphConfig},
+    config::{FederationVersion, SchemaSource, SupergraphConfig},
     javascript::SubgraphDefinition,
 };
 use camino::Utf8PathBuf;
@@ -16,6 +17,7 @@ use crossbeam_channel::{bounded, Receiver, Sender};
 use futures::TryFutureExt;
 use interprocess::local_socket::traits::{ListenerExt, Stream};
 use interprocess::local_socket::ListenerOptions;
+use rover_std::Fs;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
@@ -24,11 +26,18 @@ use crate::{
         legacy::{
             compose::ComposeRunner,
             do_dev::log_err_and_continue,
-            router::{RouterConfigHandler, RouterRunner},
+            docker_compose::ComposeTeardown,
+            health::SubgraphHealthMonitor,
+            router::{PrewarmOperations, RouterConfigHandler, RouterRunner},
+            triage::{
+                open_in_editor, skip_target, TriageCommand, TriageError, TriageInput, TriagePrompt,
+                TRIAGE_HELP,
+            },
         },
+        url_map::{rewrite_url, UrlMapRule},
         OVERRIDE_DEV_COMPOSITION_VERSION,
     },
-    options::PluginOpts,
+    options::{PluginOpts, ProcessLimitOpts},
     utils::client::StudioClientConfig,
     RoverError, RoverErrorSuggestion, RoverResult, PKG_VERSION,
 };
@@ -52,6 +61,11 @@ pub struct LeaderSession {
     leader_channel: LeaderChannel,
     federation_version: FederationVersion,
     supergraph_config: Option<SupergraphConfig>,
+    url_map: Vec<UrlMapRule>,
+    health_monitor: Option<SubgraphHealthMonitor>,
+    triage: Option<TriagePrompt>,
+    pending_message: Option<FollowerMessage>,
+    compose_teardown: ComposeTeardown,
 }
 
 impl LeaderSession {
@@ -72,6 +86,11 @@ impl LeaderSession {
         supergraph_config: &Option<SupergraphConfig>,
         router_config_handler: RouterConfigHandler,
         license: Option<Utf8PathBuf>,
+        prewarm_operations: Option<PrewarmOperations>,
+        url_map: Vec<UrlMapRule>,
+        subgraph_url_health_interval: Option<Duration>,
+        process_limit_opts: ProcessLimitOpts,
+        compose_teardown: ComposeTeardown,
     ) -> RoverResult<Option<Self>> {
         let raw_socket_name = router_config_handler.get_raw_socket_name();
         let router_socket_addr = router_config_handler.get_router_address();
@@ -121,7 +140,10 @@ impl LeaderSession {
             override_install_path,
             client_config.clone(),
             license,
-        );
+            prewarm_operations,
+            process_limit_opts,
+        )
+        .with_public_access(router_config_handler.get_public_access());
 
         let config_fed_version = supergraph_config
             .clone()
@@ -138,7 +160,7 @@ impl LeaderSession {
             .maybe_install_supergraph(federation_version.clone())
             .await?;
 
-        router_config_handler.start()?;
+        router_config_handler.start(client_config.get_reqwest_client()?)?;
 
         Ok(Some(Self {
             subgraphs: HashMap::new(),
@@ -149,6 +171,11 @@ impl LeaderSession {
             leader_channel,
             federation_version,
             supergraph_config: supergraph_config.clone(),
+            url_map,
+            health_monitor: subgraph_url_health_interval.map(SubgraphHealthMonitor::start),
+            triage: TriagePrompt::for_terminal(),
+            pending_message: None,
+            compose_teardown,
         }))
     }
 
@@ -202,7 +229,10 @@ impl LeaderSession {
         ready_sender.try_send(()).unwrap();
         loop {
             tracing::trace!("main session waiting for follower message");
-            let follower_message = self.follower_channel.receiver.recv().unwrap();
+            let follower_message = match self.pending_message.take() {
+                Some(follower_message) => follower_message,
+                None => self.follower_channel.receiver.recv().unwrap(),
+            };
             let leader_message = self
                 .handle_follower_message_kind(follower_message.kind())
                 .await;
@@ -359,8 +389,150 @@ impl LeaderSession {
         }
     }
 
-    /// Reruns composition, which triggers the router to reload.
+    /// Reruns composition, which triggers the router to reload. When composition fails while
+    /// `rover dev` is attached to a terminal, its errors can be triaged before moving on
     async fn compose(&mut self) -> CompositionResult {
+        let composition_result = self.compose_once().await;
+        if composition_result.is_err() && self.triage.is_some() {
+            self.triage_composition_errors(composition_result).await
+        } else {
+            composition_result
+        }
+    }
+
+    /// Prompts for what to do about a failed composition until it composes, the user moves on,
+    /// or a message comes in that needs handling (which is kept for the next loop)
+    async fn triage_composition_errors(
+        &mut self,
+        mut composition_result: CompositionResult,
+    ) -> CompositionResult {
+        let mut errors = Vec::new();
+        let mut failed_again = true;
+        while composition_result.is_err() {
+            if failed_again {
+                let Some(build_errors) = self.compose_runner.build_errors() else {
+                    break;
+                };
+                errors = TriageError::from_build_errors(&build_errors, &self.schema_files());
+                eprintln!(
+                    "composition failed with {}, {}",
+                    build_errors.length_string(),
+                    TRIAGE_HELP
+                );
+                failed_again = false;
+            }
+            let Some(prompt) = self.triage.as_mut() else {
+                break;
+            };
+            let command = match prompt.next_input(&self.follower_channel.receiver) {
+                TriageInput::Command(command) => command,
+                TriageInput::Message(follower_message) => {
+                    self.pending_message = Some(follower_message);
+                    break;
+                }
+                TriageInput::Closed => {
+                    self.triage = None;
+                    break;
+                }
+            };
+            match command {
+                TriageCommand::List => {
+                    for (index, error) in errors.iter().enumerate() {
+                        eprintln!("  [{}] {}", index + 1, error);
+                    }
+                }
+                TriageCommand::Open(number) => {
+                    let location = errors.get(number - 1).and_then(|error| {
+                        error
+                            .locations
+                            .iter()
+                            .find(|location| location.file.is_some())
+                            .or(error.locations.first())
+                    });
+                    match location {
+                        Some(location) => {
+                            let _ = open_in_editor(location).map_err(log_err_and_continue);
+                        }
+                        None => eprintln!("there's no location to open for error {}", number),
+                    }
+                }
+                TriageCommand::Retry => {
+                    self.reload_schema_files();
+                    composition_result = self.compose_once().await;
+                    failed_again = true;
+                }
+                TriageCommand::Skip(argument) => {
+                    match skip_target(argument.as_deref(), &errors)
+                        .and_then(|subgraph_name| self.skip_subgraph(&subgraph_name))
+                    {
+                        Ok(subgraph_name) => {
+                            eprintln!(
+                                "skipping the '{}' subgraph until its schema changes",
+                                subgraph_name
+                            );
+                            composition_result = self.compose_once().await;
+                            failed_again = true;
+                        }
+                        Err(message) => eprintln!("{}", message),
+                    }
+                }
+                TriageCommand::Continue => break,
+                TriageCommand::Help => eprintln!("{}", TRIAGE_HELP),
+            }
+        }
+        composition_result
+    }
+
+    /// The schema files that subgraphs in the supergraph config are read from, by subgraph name
+    fn schema_files(&self) -> HashMap<String, Utf8PathBuf> {
+        self.supergraph_config
+            .clone()
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, subgraph)| match subgraph.schema {
+                SchemaSource::File { file } => Some((name, file)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Re-reads the schemas of subgraphs that come from files, so that a retry doesn't have to
+    /// wait on the file watchers to pick up any fixes
+    fn reload_schema_files(&mut self) {
+        let schema_files = self.schema_files();
+        for ((name, _), sdl) in self.subgraphs.iter_mut() {
+            if let Some(file) = schema_files.get(name) {
+                match Fs::read_file(file) {
+                    Ok(contents) => *sdl = contents,
+                    Err(err) => {
+                        let _ = log_err_and_continue(err.into());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes a subgraph until its next update adds it back
+    fn skip_subgraph(&mut self, subgraph_name: &str) -> Result<String, String> {
+        let len = self.subgraphs.len();
+        self.subgraphs.retain(|(name, _), _| name != subgraph_name);
+        if self.subgraphs.len() < len {
+            Ok(subgraph_name.to_string())
+        } else {
+            Err(format!("there's no '{}' subgraph to skip", subgraph_name))
+        }
+    }
+
+    /// Composes the current subgraphs, spawning the router when there's a new supergraph schema
+    async fn compose_once(&mut self) -> CompositionResult {
+        if let Some(health_monitor) = &self.health_monitor {
+            health_monitor.set_routing_urls(
+                self.subgraphs
+                    .keys()
+                    .map(|(name, url)| (name.clone(), rewrite_url(&self.url_map, url.as_str())))
+                    .collect(),
+            );
+        }
         match self
             .compose_runner
             .run(&mut self.supergraph_config_internal_representation())
@@ -418,7 +590,7 @@ impl LeaderSession {
             .iter()
             .map(|((name, url), sdl)| SubgraphDefinition {
                 name: name.clone(),
-                url: url.to_string(),
+                url: rewrite_url(&self.url_map, url.as_str()),
                 sdl: sdl.clone(),
             })
             .collect::<Vec<SubgraphDefinition>>()
@@ -441,6 +613,7 @@ impl LeaderSession {
             let _ = runner.kill().await.map_err(log_err_and_continue);
         }
         let _ = std::fs::remove_file(&raw_socket_name);
+        self.compose_teardown.run();
         std::process::exit(1)
     }
 
@@ -476,11 +649,13 @@ impl Drop for LeaderSession {
     fn drop(&mut self) {
         let router_runner = self.router_runner.take();
         let socket_addr = self.raw_socket_name.clone();
+        let compose_teardown = self.compose_teardown.clone();
         tokio::task::spawn(async move {
             if let Some(mut runner) = router_runner {
                 let _ = runner.kill().await.map_err(log_err_and_continue);
             }
             let _ = std::fs::remove_file(&socket_addr);
+            compose_teardown.run();
             std::process::exit(1)
         });
     }
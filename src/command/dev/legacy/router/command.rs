@@ -1,7 +1,13 @@
 use std::env::var;
 use std::{
     io::{BufRead, BufReader},
-    process::{Child, Command, Stdio},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
 };
 
 use anyhow::{anyhow, Context};
@@ -9,14 +15,21 @@ use crossbeam_channel::Sender;
 use rover_client::operations::config::who_am_i::{self, Actor};
 use rover_std::warnln;
 
-use crate::options::ProfileOpt;
+use crate::command::dev::legacy::router::limits::{apply_limits, watch_memory};
+use crate::options::{ProcessLimitOpts, ProfileOpt};
 use crate::utils::client::StudioClientConfig;
 use crate::{command::dev::legacy::do_dev::log_err_and_continue, RoverError, RoverResult};
 
+/// How often a background task is checked for having exited
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 #[derive(Debug)]
 pub struct BackgroundTask {
-    child: Child,
+    child: Arc<Mutex<Child>>,
+    pid: u32,
     descriptor: String,
+    started_at: Instant,
+    stopping: Arc<AtomicBool>,
 }
 
 pub enum BackgroundTaskLog {
@@ -30,6 +43,7 @@ impl BackgroundTask {
         log_sender: Sender<BackgroundTaskLog>,
         client_config: &StudioClientConfig,
         profile_opt: &ProfileOpt,
+        process_limit_opts: &ProcessLimitOpts,
     ) -> RoverResult<Self> {
         let descriptor = command.clone();
         let args: Vec<&str> = command.split(' ').collect();
@@ -82,9 +96,12 @@ impl BackgroundTask {
             }
         }
 
+        apply_limits(&mut command, process_limit_opts);
+
         let mut child = command
             .spawn()
             .with_context(|| "could not spawn child process")?;
+        watch_memory(child.id(), descriptor.clone(), process_limit_opts);
 
         match child.stdout.take() {
             Some(stdout) => {
@@ -123,13 +140,54 @@ impl BackgroundTask {
             }
         }
 
-        Ok(Self { child, descriptor })
+        Ok(Self {
+            pid: child.id(),
+            child: Arc::new(Mutex::new(child)),
+            descriptor,
+            started_at: Instant::now(),
+            stopping: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Watches for the task exiting in the background, calling `on_exit` with its exit status
+    /// and how long it ran for if it exits without being killed. Watching stops once the task
+    /// has exited or been killed
+    pub fn on_unexpected_exit(&self, on_exit: impl FnOnce(ExitStatus, Duration) + Send + 'static) {
+        let child = self.child.clone();
+        let stopping = self.stopping.clone();
+        let started_at = self.started_at;
+        thread::spawn(move || loop {
+            if stopping.load(Ordering::SeqCst) {
+                return;
+            }
+            let status = match child.lock() {
+                Ok(mut child) => child.try_wait(),
+                Err(_) => return,
+            };
+            match status {
+                Ok(Some(status)) => {
+                    if !stopping.load(Ordering::SeqCst) {
+                        on_exit(status, started_at.elapsed());
+                    }
+                    return;
+                }
+                Ok(None) => thread::sleep(EXIT_POLL_INTERVAL),
+                Err(err) => {
+                    tracing::debug!("stopped watching for the child exiting: {}", err);
+                    return;
+                }
+            }
+        });
     }
 
     pub fn kill(&mut self) {
         let pid = self.id();
         tracing::info!("killing child with pid {}", &pid);
-        let _ = self.child.kill().map_err(|_| {
+        self.stopping.store(true, Ordering::SeqCst);
+        let Ok(mut child) = self.child.lock() else {
+            return;
+        };
+        let _ = child.kill().map_err(|_| {
             log_err_and_continue(RoverError::new(anyhow!(
                 "could not kill child with pid {}",
                 &pid
@@ -138,7 +196,7 @@ impl BackgroundTask {
     }
 
     pub fn id(&self) -> u32 {
-        self.child.id()
+        self.pid
     }
 
     pub fn descriptor(&self) -> &str {
@@ -7,6 +7,7 @@ use std::{
 use anyhow::{anyhow, Context};
 use camino::Utf8PathBuf;
 use crossbeam_channel::{unbounded, Receiver};
+use reqwest::Client;
 use serde_json::json;
 
 use rover_std::{warnln, Fs};
@@ -14,12 +15,19 @@ use rover_std::{warnln, Fs};
 use crate::utils::expansion::expand;
 use crate::{
     command::dev::{legacy::do_dev::log_err_and_continue, SupergraphOpts},
-    RoverError, RoverResult,
+    RoverError, RoverErrorSuggestion, RoverResult,
 };
 
+use super::public::{PublicAccess, PUBLIC_IP};
+use super::versioned::{router_is_ready, wait_for_reload, VersionedRouterConfig};
+
 const DEFAULT_ROUTER_SOCKET_ADDR: SocketAddr =
     SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 4000);
 
+/// The prefix of the temporary directory each session keeps its router config, supergraph
+/// schema, and crash bundles in
+pub(crate) const SESSION_DIR_PREFIX: &str = "supergraph";
+
 /// [`RouterConfigHandler`] is reponsible for orchestrating the YAML configuration file
 /// passed to the router plugin, optionally watching a user's router configuration file for changes
 #[derive(Debug, Clone)]
@@ -27,7 +35,10 @@ pub struct RouterConfigHandler {
     /// the router configuration reader
     config_reader: RouterConfigReader,
 
-    /// the temp path to write the patched router config out to
+    /// the versions of the patched router config, and the pointer the router reads
+    versions: Arc<Mutex<VersionedRouterConfig>>,
+
+    /// the temp path the router reads the patched router config from
     tmp_router_config_path: Utf8PathBuf,
 
     /// the temp path to write the composed schema out to
@@ -44,49 +55,92 @@ impl TryFrom<&SupergraphOpts> for RouterConfigHandler {
             value.router_config_path.clone(),
             value.supergraph_address,
             value.supergraph_port,
+            value.bind_public,
         )
     }
 }
 
 impl RouterConfigHandler {
-    /// Create a [`RouterConfigHandler`]
+    /// Create a [`RouterConfigHandler`]. With `bind_public`, the router listens on every
+    /// interface, and only serves requests with the session's bearer token
     pub fn new(
         input_config_path: Option<Utf8PathBuf>,
         ip_override: Option<IpAddr>,
         port_override: Option<u16>,
+        bind_public: bool,
     ) -> RoverResult<Self> {
-        let tmp_dir = tempfile::Builder::new().prefix("supergraph").tempdir()?;
+        let tmp_dir = tempfile::Builder::new()
+            .prefix(SESSION_DIR_PREFIX)
+            .tempdir()?;
         let tmp_config_dir_path = Utf8PathBuf::try_from(tmp_dir.into_path())?;
 
-        let tmp_router_config_path = tmp_config_dir_path.join("router.yaml");
         let tmp_supergraph_schema_path = tmp_config_dir_path.join("supergraph.graphql");
-
-        let config_reader = RouterConfigReader::new(input_config_path, ip_override, port_override);
+        let public_access = bind_public
+            .then(|| PublicAccess::new(tmp_config_dir_path.join("rhai")))
+            .transpose()?;
+        let mut versions = VersionedRouterConfig::new(tmp_config_dir_path);
+        let tmp_router_config_path = versions.pointer_path();
+
+        let ip_override = if bind_public {
+            Some(PUBLIC_IP)
+        } else {
+            ip_override
+        };
+        let config_reader = RouterConfigReader::new(input_config_path, ip_override, port_override)
+            .with_public_access(public_access);
 
         let config_state = config_reader.read()?;
 
-        Fs::write_file(&tmp_router_config_path, &config_state.config)?;
+        versions.publish(&config_state.config)?;
 
         Ok(Self {
             config_reader,
             config_state: Arc::new(Mutex::new(config_state)),
+            versions: Arc::new(Mutex::new(versions)),
             tmp_router_config_path,
             tmp_supergraph_schema_path,
         })
     }
 
-    /// Start up the router config handler
-    pub fn start(self) -> RoverResult<()> {
+    /// Start up the router config handler. `client` checks that the router is still ready after
+    /// each change to its config
+    pub fn start(self, client: Client) -> RoverResult<()> {
         // if a router config was passed, start watching it in the background for changes
 
-        if let Some(state_receiver) = self.config_reader.watch() {
-            // Build a Rayon Thread pool
+        if let Some(state_receiver) = self.config_reader.clone().watch() {
+            let runtime = tokio::runtime::Handle::current();
             tokio::task::spawn_blocking(move || loop {
                 let config_state = state_receiver
                     .recv()
                     .expect("could not watch router config");
-                let _ = Fs::write_file(&self.tmp_router_config_path, &config_state.config)
-                    .map_err(|e| log_err_and_continue(e.into()));
+                // a router that wasn't ready before the change can't show whether the change
+                // broke it, so only a ready router is checked afterwards
+                let was_ready =
+                    runtime.block_on(router_is_ready(&client, &self.get_health_endpoint()));
+                if let Err(e) = self
+                    .versions
+                    .lock()
+                    .expect("could not acquire lock on router config versions")
+                    .publish(&config_state.config)
+                {
+                    log_err_and_continue(e);
+                    continue;
+                }
+                if was_ready {
+                    let endpoint = config_state.health_endpoint();
+                    if let Err(reason) = runtime.block_on(wait_for_reload(&client, &endpoint)) {
+                        warnln!(
+                            "the router did not reload the updated router config ({reason}), rolling back to the previous config"
+                        );
+                        let _ = self
+                            .versions
+                            .lock()
+                            .expect("could not acquire lock on router config versions")
+                            .rollback()
+                            .map_err(log_err_and_continue);
+                        continue;
+                    }
+                }
                 eprintln!("successfully updated router config");
                 *self
                     .config_state
@@ -107,6 +161,11 @@ impl RouterConfigHandler {
             .unwrap_or(DEFAULT_ROUTER_SOCKET_ADDR)
     }
 
+    /// The bearer token and script that guard a router started with `--bind-public`
+    pub fn get_public_access(&self) -> Option<PublicAccess> {
+        self.config_reader.public_access.clone()
+    }
+
     /// The path the router should listen on
     pub fn get_router_listen_path(&self) -> String {
         self.config_state
@@ -116,6 +175,14 @@ impl RouterConfigHandler {
             .clone()
     }
 
+    /// The router's health endpoint, which reports whether it's ready
+    fn get_health_endpoint(&self) -> String {
+        self.config_state
+            .lock()
+            .expect("could not acquire lock on router config state")
+            .health_endpoint()
+    }
+
     /// Get the name of the interprocess socket address to communicate with other rover dev sessions
     pub fn get_raw_socket_name(&self) -> String {
         let socket_name = format!("supergraph-{}.sock", self.get_router_address());
@@ -152,11 +219,23 @@ pub struct RouterConfigState {
     pub listen_path: String,
 }
 
+impl RouterConfigState {
+    /// The router's health endpoint while it runs with this config
+    fn health_endpoint(&self) -> String {
+        format!(
+            "http://{}{}/health?ready",
+            self.socket_addr.unwrap_or(DEFAULT_ROUTER_SOCKET_ADDR),
+            self.listen_path
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 struct RouterConfigReader {
     input_config_path: Option<Utf8PathBuf>,
     ip_override: Option<IpAddr>,
     port_override: Option<u16>,
+    public_access: Option<PublicAccess>,
 }
 
 impl RouterConfigReader {
@@ -169,6 +248,14 @@ impl RouterConfigReader {
             input_config_path,
             ip_override,
             port_override,
+            public_access: None,
+        }
+    }
+
+    fn with_public_access(self, public_access: Option<PublicAccess>) -> Self {
+        Self {
+            public_access,
+            ..self
         }
     }
 
@@ -192,9 +279,13 @@ impl RouterConfigReader {
             .transpose()?
             .unwrap_or_default();
 
-        let yaml_socket_addr = yaml
+        let yaml_listen = yaml
             .get("supergraph")
             .and_then(|s| s.get("listen"))
+            .filter(|l| !l.is_null())
+            .cloned();
+        let yaml_socket_addr = yaml_listen
+            .as_ref()
             .and_then(|l| l.as_str())
             .and_then(|s| s.parse::<SocketAddr>().ok());
 
@@ -219,6 +310,20 @@ impl RouterConfigReader {
             (None, None, None) => None,
         };
 
+        // the CLI options win, but only when they agree with the router config; otherwise the
+        // router would quietly listen somewhere the user didn't ask for
+        if let (Some(socket_addr), Some(yaml_listen)) = (socket_addr, &yaml_listen) {
+            if yaml_socket_addr != Some(socket_addr) {
+                return Err(self.conflict(
+                    "supergraph.listen",
+                    yaml_listen,
+                    &socket_addr.to_string(),
+                    &format!("`{}`", self.override_flags()),
+                    "Remove `supergraph.listen` from the router config, or stop passing the CLI options, so the router's address is only set in one place.",
+                ));
+            }
+        }
+
         if let Some(socket_addr) = socket_addr {
             // update YAML with the ip and port CLI options
             yaml.entry("supergraph".into())
@@ -229,17 +334,39 @@ impl RouterConfigReader {
         }
 
         // disable the health check unless they have their own config
-        if yaml
+        let health_check = yaml
             .get("health_check")
             .or_else(|| yaml.get("health-check"))
-            .and_then(|h| h.as_mapping())
-            .is_none()
-        {
+            .filter(|h| !h.is_null());
+        if let Some(health_check) = health_check.filter(|h| !h.is_mapping()) {
+            return Err(self.conflict(
+                "health_check",
+                health_check,
+                "{ enabled: false }",
+                "`rover dev`",
+                "Write `health_check` as a mapping, such as `health_check: { enabled: true }`, so `rover dev` leaves it alone.",
+            ));
+        }
+        if health_check.is_none() {
             yaml.insert(
                 serde_yaml::to_value("health_check")?,
                 serde_yaml::to_value(json!({"enabled": false}))?,
             );
         }
+        // with `--bind-public`, a script turns away requests without the session's token
+        if let Some(public_access) = &self.public_access {
+            if let Some(rhai) = yaml.get("rhai").filter(|r| !r.is_null()) {
+                return Err(self.conflict(
+                    "rhai",
+                    rhai,
+                    "a script that checks the session's bearer token",
+                    "`--bind-public`",
+                    "Remove `rhai` from the router config while using `--bind-public`, or stop passing `--bind-public` and restrict access to the router some other way.",
+                ));
+            }
+            yaml.insert("rhai".into(), public_access.rhai_config());
+        }
+
         let listen_path = yaml
             .get("supergraph")
             .and_then(|s| s.as_mapping())
@@ -264,6 +391,45 @@ impl RouterConfigReader {
         })
     }
 
+    /// Describes a key that the user's router config sets one way and `rover dev` would set another
+    fn conflict(
+        &self,
+        key: &str,
+        user_value: &serde_yaml::Value,
+        rover_value: &str,
+        rover_source: &str,
+        suggestion: &str,
+    ) -> RoverError {
+        let user_value = serde_yaml::to_string(user_value)
+            .map(|value| value.trim_end().to_string())
+            .unwrap_or_else(|_| format!("{:?}", user_value));
+        let config_path = self
+            .input_config_path
+            .as_ref()
+            .map(|path| path.to_string())
+            .unwrap_or_else(|| "the router config".to_string());
+        let mut err = RoverError::new(anyhow!(
+            "`{key}` is set to `{user_value}` in {config_path}, but {rover_source} sets it to `{rover_value}`."
+        ));
+        err.set_suggestion(RoverErrorSuggestion::Adhoc(suggestion.to_string()));
+        err
+    }
+
+    /// The CLI options that override where the router listens, as they'd be written on the
+    /// command line
+    fn override_flags(&self) -> String {
+        let mut flags = Vec::new();
+        if self.public_access.is_some() {
+            flags.push("--bind-public".to_string());
+        } else if let Some(ip) = self.ip_override {
+            flags.push(format!("--supergraph-address {ip}"));
+        }
+        if let Some(port) = self.port_override {
+            flags.push(format!("--supergraph-port {port}"));
+        }
+        flags.join(" ")
+    }
+
     pub fn watch(self) -> Option<Receiver<RouterConfigState>> {
         if let Some(input_config_path) = &self.input_config_path {
             let (raw_tx, mut raw_rx) = tokio::sync::mpsc::unbounded_channel();
@@ -293,21 +459,115 @@ impl RouterConfigReader {
 mod tests {
     use std::net::{IpAddr, Ipv4Addr};
 
+    use assert_fs::{prelude::*, TempDir};
+    use camino::Utf8PathBuf;
     use rstest::rstest;
+    use speculoos::prelude::*;
 
     use crate::command::dev::legacy::router::RouterConfigHandler;
 
+    use super::RouterConfigReader;
+
+    fn reader_for(
+        temp_dir: &TempDir,
+        contents: &str,
+        port_override: Option<u16>,
+    ) -> RouterConfigReader {
+        let config_file = temp_dir.child("router.yaml");
+        config_file.write_str(contents).unwrap();
+        RouterConfigReader::new(
+            Some(Utf8PathBuf::from_path_buf(config_file.to_path_buf()).unwrap()),
+            None,
+            port_override,
+        )
+    }
+
     #[rstest]
     #[cfg_attr(windows, case("\\\\.\\pipe\\supergraph-127.0.0.1:4000.sock"))]
     #[cfg_attr(unix, case("/tmp/supergraph-127.0.0.1:4000.sock"))]
     fn test_socket_types_correctly_detected(#[case] expected_ipc_address: String) {
         let ip_addr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
         let port_number = 4000;
-        let r_config = RouterConfigHandler::new(None, Some(ip_addr), Some(port_number))
+        let r_config = RouterConfigHandler::new(None, Some(ip_addr), Some(port_number), false)
             .expect("failed to create config handler");
         assert_eq!(
             r_config.get_raw_socket_name(),
             format!("{}", expected_ipc_address)
         );
     }
+
+    #[rstest]
+    #[case::no_override("supergraph:\n  listen: 127.0.0.1:4001\n", None)]
+    #[case::same_address("supergraph:\n  listen: 127.0.0.1:4001\n", Some(4001))]
+    #[case::no_listen("supergraph:\n  path: /graphql\n", Some(4001))]
+    fn test_read_without_conflicts(#[case] contents: &str, #[case] port_override: Option<u16>) {
+        let temp_dir = TempDir::new().unwrap();
+        let state = reader_for(&temp_dir, contents, port_override).read();
+        assert_that!(state)
+            .is_ok()
+            .map(|state| &state.socket_addr)
+            .is_equal_to(Some("127.0.0.1:4001".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_read_reports_listen_conflict() {
+        let temp_dir = TempDir::new().unwrap();
+        let err = reader_for(
+            &temp_dir,
+            "supergraph:\n  listen: 127.0.0.1:4001\n",
+            Some(4002),
+        )
+        .read()
+        .unwrap_err();
+        assert_that!(err.to_string())
+            .contains("`supergraph.listen` is set to `127.0.0.1:4001` in ");
+        assert_that!(err.to_string())
+            .contains("but `--supergraph-port 4002` sets it to `127.0.0.1:4002`.");
+    }
+
+    #[test]
+    fn test_bind_public_listens_everywhere_and_checks_the_token() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_file = temp_dir.child("router.yaml");
+        config_file
+            .write_str("supergraph:\n  path: /graphql\n")
+            .unwrap();
+        let handler = RouterConfigHandler::new(
+            Some(Utf8PathBuf::from_path_buf(config_file.to_path_buf()).unwrap()),
+            None,
+            Some(4001),
+            true,
+        )
+        .unwrap();
+
+        assert_that!(handler.get_router_address())
+            .is_equal_to("0.0.0.0:4001".parse::<std::net::SocketAddr>().unwrap());
+        assert_that!(handler.get_public_access()).is_some();
+        let config = handler.config_state.lock().unwrap().config.clone();
+        assert_that!(config).contains("main: bind_public.rhai");
+    }
+
+    #[test]
+    fn test_bind_public_reports_rhai_conflict() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_file = temp_dir.child("router.yaml");
+        config_file.write_str("rhai:\n  main: mine.rhai\n").unwrap();
+        let err = RouterConfigHandler::new(
+            Some(Utf8PathBuf::from_path_buf(config_file.to_path_buf()).unwrap()),
+            None,
+            None,
+            true,
+        )
+        .unwrap_err();
+        assert_that!(err.to_string()).contains("but `--bind-public` sets it to");
+    }
+
+    #[test]
+    fn test_read_reports_health_check_conflict() {
+        let temp_dir = TempDir::new().unwrap();
+        let err = reader_for(&temp_dir, "health_check: true\n", None)
+            .read()
+            .unwrap_err();
+        assert_that!(err.to_string()).contains("but `rover dev` sets it to `{ enabled: false }`.");
+    }
 }
@@ -0,0 +1,281 @@
+//! Keeps what's needed to work out why the router crashed after the session that ran it is gone:
+//! its last config, a hash of the supergraph it served, its last log lines, and how it exited
+
+use std::{
+    collections::VecDeque,
+    fs,
+    process::ExitStatus,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Context;
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::config::SESSION_DIR_PREFIX;
+use crate::RoverResult;
+
+/// How many of the router's most recent log lines a crash bundle keeps
+pub const LOG_TAIL_LINES: usize = 500;
+
+/// The directory within a session's workspace that its crash bundles are written to
+const CRASHES_DIR: &str = "crashes";
+
+const REPORT_FILE: &str = "crash.json";
+const CONFIG_FILE: &str = "router.yaml";
+const LOGS_FILE: &str = "router.log";
+
+/// The most recent lines the router logged, shared between the thread that prints them and the
+/// one that watches for the router exiting
+#[derive(Clone, Debug, Default)]
+pub struct LogTail {
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl LogTail {
+    /// Records `line`, dropping the oldest line once there are [`LOG_TAIL_LINES`]
+    pub fn push(&self, line: String) {
+        if let Ok(mut lines) = self.lines.lock() {
+            if lines.len() == LOG_TAIL_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    }
+
+    /// The recorded lines, oldest first
+    pub fn lines(&self) -> Vec<String> {
+        self.lines
+            .lock()
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// How the router exited, written to `crash.json` in each bundle
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CrashReport {
+    pub crashed_at: DateTime<Utc>,
+    pub pid: u32,
+    pub exit_code: Option<i32>,
+    /// The signal that stopped the router, on platforms that have them
+    pub signal: Option<i32>,
+    pub uptime_secs: u64,
+    /// The SHA-256 of the supergraph schema the router was serving, if there was one
+    pub supergraph_sha256: Option<String>,
+    pub command: String,
+}
+
+impl CrashReport {
+    pub fn new(
+        pid: u32,
+        status: ExitStatus,
+        uptime: Duration,
+        supergraph_schema_path: &Utf8Path,
+        command: &str,
+    ) -> Self {
+        #[cfg(unix)]
+        let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+        #[cfg(not(unix))]
+        let signal = None;
+        Self {
+            crashed_at: Utc::now(),
+            pid,
+            exit_code: status.code(),
+            signal,
+            uptime_secs: uptime.as_secs(),
+            supergraph_sha256: fs::read(supergraph_schema_path)
+                .ok()
+                .map(|sdl| format!("{:x}", Sha256::digest(sdl))),
+            command: command.to_string(),
+        }
+    }
+
+    /// How the router exited, like `exit code 101` or `signal 11`
+    pub fn exit(&self) -> String {
+        match (self.exit_code, self.signal) {
+            (Some(code), _) => format!("exit code {code}"),
+            (None, Some(signal)) => format!("signal {signal}"),
+            (None, None) => "unknown".to_string(),
+        }
+    }
+}
+
+/// A directory holding a [`CrashReport`] and the router's last config and log lines
+#[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+pub struct CrashBundle {
+    pub path: Utf8PathBuf,
+    #[serde(flatten)]
+    pub report: CrashReport,
+}
+
+impl CrashBundle {
+    /// Writes a bundle to the `crashes` directory of the session workspace at `session_dir`.
+    /// `router_config` is the config the router was last running with, if it could be read
+    pub fn write(
+        session_dir: &Utf8Path,
+        report: CrashReport,
+        router_config: Option<&str>,
+        logs: &[String],
+    ) -> RoverResult<Self> {
+        let path = session_dir.join(CRASHES_DIR).join(format!(
+            "{}-{}",
+            report.crashed_at.format("%Y%m%dT%H%M%SZ"),
+            report.pid
+        ));
+        fs::create_dir_all(&path).with_context(|| format!("could not create {path}"))?;
+        let write = |file: &str, contents: &str| {
+            let file = path.join(file);
+            fs::write(&file, contents).with_context(|| format!("could not write {file}"))
+        };
+        write(REPORT_FILE, &serde_json::to_string_pretty(&report)?)?;
+        if let Some(router_config) = router_config {
+            write(CONFIG_FILE, router_config)?;
+        }
+        let mut logs = logs.join("\n");
+        logs.push('\n');
+        write(LOGS_FILE, &logs)?;
+        Ok(Self { path, report })
+    }
+
+    /// Every bundle in the session workspaces under `tmp_dir`, newest first. Bundles that can't
+    /// be read are skipped
+    pub fn find_all(tmp_dir: &Utf8Path) -> Vec<Self> {
+        let mut bundles: Vec<Self> = read_dirs(tmp_dir)
+            .filter(|session_dir| {
+                session_dir
+                    .file_name()
+                    .is_some_and(|name| name.starts_with(SESSION_DIR_PREFIX))
+            })
+            .flat_map(|session_dir| read_dirs(&session_dir.join(CRASHES_DIR)).collect::<Vec<_>>())
+            .filter_map(|path| {
+                let report = fs::read_to_string(path.join(REPORT_FILE)).ok()?;
+                let report = serde_json::from_str(&report).ok()?;
+                Some(Self { path, report })
+            })
+            .collect();
+        bundles.sort_by(|a, b| b.report.crashed_at.cmp(&a.report.crashed_at));
+        bundles
+    }
+}
+
+/// The directories directly within `dir`, or none if it can't be read
+fn read_dirs(dir: &Utf8Path) -> impl Iterator<Item = Utf8PathBuf> {
+    dir.read_dir_utf8()
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .map(|entry| entry.into_path())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    fn report(crashed_at: &str, pid: u32) -> CrashReport {
+        CrashReport {
+            crashed_at: crashed_at.parse().unwrap(),
+            pid,
+            exit_code: Some(101),
+            signal: None,
+            uptime_secs: 42,
+            supergraph_sha256: None,
+            command: "router --dev".to_string(),
+        }
+    }
+
+    #[rstest]
+    fn test_log_tail_keeps_the_most_recent_lines() {
+        let tail = LogTail::default();
+        for line in 0..LOG_TAIL_LINES + 10 {
+            tail.push(line.to_string());
+        }
+        let lines = tail.lines();
+        assert_that!(lines).has_length(LOG_TAIL_LINES);
+        assert_that!(lines.first()).is_equal_to(Some(&"10".to_string()));
+        assert_that!(lines.last()).is_equal_to(Some(&(LOG_TAIL_LINES + 9).to_string()));
+    }
+
+    #[rstest]
+    #[case::exit_code(Some(101), None, "exit code 101")]
+    #[case::signal(None, Some(11), "signal 11")]
+    #[case::unknown(None, None, "unknown")]
+    fn test_exit_describes_how_the_router_stopped(
+        #[case] exit_code: Option<i32>,
+        #[case] signal: Option<i32>,
+        #[case] expected: &str,
+    ) {
+        let report = CrashReport {
+            exit_code,
+            signal,
+            ..report("2026-10-17T12:00:00Z", 1)
+        };
+        assert_that!(report.exit()).is_equal_to(expected.to_string());
+    }
+
+    #[cfg(unix)]
+    #[rstest]
+    fn test_report_records_the_signal_and_supergraph_hash() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let dir = TempDir::new().unwrap();
+        let dir = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+        let supergraph_schema_path = dir.join("supergraph.graphql");
+        fs::write(&supergraph_schema_path, "type Query { me: String }").unwrap();
+
+        // a raw wait status of 11 is a process stopped by SIGSEGV
+        let report = CrashReport::new(
+            7,
+            ExitStatus::from_raw(11),
+            Duration::from_secs(90),
+            &supergraph_schema_path,
+            "router --dev",
+        );
+        assert_that!(report.signal).is_equal_to(Some(11));
+        assert_that!(report.exit_code).is_none();
+        assert_that!(report.uptime_secs).is_equal_to(90);
+        assert_that!(report.supergraph_sha256).is_equal_to(Some(format!(
+            "{:x}",
+            Sha256::digest("type Query { me: String }")
+        )));
+    }
+
+    #[rstest]
+    fn test_bundles_are_found_newest_first() {
+        let tmp_dir = TempDir::new().unwrap();
+        let tmp_dir = Utf8PathBuf::from_path_buf(tmp_dir.path().to_path_buf()).unwrap();
+        let first_session = tmp_dir.join(format!("{SESSION_DIR_PREFIX}abc"));
+        let second_session = tmp_dir.join(format!("{SESSION_DIR_PREFIX}def"));
+        let unrelated = tmp_dir.join("unrelated");
+
+        let older = CrashBundle::write(
+            &first_session,
+            report("2026-10-17T12:00:00Z", 1),
+            Some("supergraph:\n  listen: 127.0.0.1:4000\n"),
+            &["starting".to_string(), "panicked".to_string()],
+        )
+        .unwrap();
+        let newer = CrashBundle::write(
+            &second_session,
+            report("2026-10-17T13:00:00Z", 2),
+            None,
+            &[],
+        )
+        .unwrap();
+        CrashBundle::write(&unrelated, report("2026-10-17T14:00:00Z", 3), None, &[]).unwrap();
+
+        assert_that!(older.path.join(CONFIG_FILE).as_std_path()).exists();
+        assert_that!(newer.path.join(CONFIG_FILE).as_std_path()).does_not_exist();
+        assert_that!(fs::read_to_string(older.path.join(LOGS_FILE)).unwrap())
+            .is_equal_to("starting\npanicked\n".to_string());
+        assert_that!(CrashBundle::find_all(&tmp_dir)).is_equal_to(vec![newer, older]);
+    }
+}
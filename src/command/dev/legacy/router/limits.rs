@@ -0,0 +1,177 @@
+use std::{process::Command, thread, time::Duration};
+
+use rover_std::errln;
+
+use crate::options::ProcessLimitOpts;
+
+/// How often a limited process has its memory checked
+const MEMORY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Sets up `command` so the process it spawns can't use more CPU time or open more files than
+/// `limits` allow. The kernel enforces these, so there's nothing to watch afterwards
+#[cfg(unix)]
+pub fn apply_limits(command: &mut Command, limits: &ProcessLimitOpts) {
+    use std::os::unix::process::CommandExt;
+
+    let max_process_cpu = limits.max_process_cpu;
+    let max_open_files = limits.max_open_files;
+    if max_process_cpu.is_none() && max_open_files.is_none() {
+        return;
+    }
+    // SAFETY: `setrlimit` is async-signal-safe, and the closure doesn't allocate or take locks
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(seconds) = max_process_cpu {
+                set_limit(libc::RLIMIT_CPU, seconds)?;
+            }
+            if let Some(count) = max_open_files {
+                set_limit(libc::RLIMIT_NOFILE, count)?;
+            }
+            Ok(())
+        });
+    }
+}
+
+/// CPU time and file limits aren't supported on this platform, so this only says as much
+#[cfg(not(unix))]
+pub fn apply_limits(_command: &mut Command, limits: &ProcessLimitOpts) {
+    use rover_std::warnln;
+
+    if limits.max_process_cpu.is_some() || limits.max_open_files.is_some() {
+        warnln!(
+            "`--max-process-cpu` and `--max-open-files` are not enforced on this platform, and will be ignored"
+        );
+    }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type Resource = libc::__rlimit_resource_t;
+#[cfg(all(unix, not(all(target_os = "linux", target_env = "gnu"))))]
+type Resource = libc::c_int;
+
+#[cfg(unix)]
+fn set_limit(resource: Resource, value: u64) -> std::io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: value as libc::rlim_t,
+        rlim_max: value as libc::rlim_t,
+    };
+    // SAFETY: `limit` is a valid `rlimit` that outlives the call
+    if unsafe { libc::setrlimit(resource, &limit) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+/// Watches the memory of the process with the given `pid` in the background, killing it and
+/// reporting it as `descriptor` if it goes over `--max-process-memory`. Watching stops once the
+/// process has exited
+pub fn watch_memory(pid: u32, descriptor: String, limits: &ProcessLimitOpts) {
+    let Some(max_process_memory) = limits.max_process_memory else {
+        return;
+    };
+    let max_bytes = max_process_memory * 1024 * 1024;
+    thread::spawn(move || {
+        while let Some(resident_bytes) = resident_memory(pid) {
+            if resident_bytes > max_bytes {
+                tracing::info!("killing child with pid {} for using too much memory", pid);
+                kill(pid);
+                errln!(
+                    "`{}` was stopped after using {}MB of memory, over the {}MB allowed by `--max-process-memory`",
+                    descriptor,
+                    resident_bytes / 1024 / 1024,
+                    max_process_memory
+                );
+                return;
+            }
+            thread::sleep(MEMORY_POLL_INTERVAL);
+        }
+        tracing::debug!("stopped watching the memory of child with pid {}", pid);
+    });
+}
+
+/// The resident memory of a running process in bytes, or `None` once it's gone
+#[cfg(unix)]
+fn resident_memory(pid: u32) -> Option<u64> {
+    // `ps` reports in kilobytes, and prints nothing when there's no such process. A zombie
+    // process we haven't reaped yet has a resident size of 0, so it's reported as gone too
+    let output = Command::new("ps")
+        .args(["-o", "rss=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let kilobytes = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|kilobytes| *kilobytes > 0)?;
+    Some(kilobytes * 1024)
+}
+
+/// The working set of a running process in bytes, or `None` once it's gone
+#[cfg(windows)]
+fn resident_memory(pid: u32) -> Option<u64> {
+    // prints a line like `"router.exe","1234","Console","1","25,124 K"`
+    let output = Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {pid}"), "/FO", "CSV", "/NH"])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let kilobytes = stdout
+        .trim()
+        .rsplit("\",\"")
+        .next()?
+        .trim_end_matches(['"', 'K', ' '])
+        .replace([',', '.', '\u{a0}'], "")
+        .parse::<u64>()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
+#[cfg(unix)]
+fn kill(pid: u32) {
+    // SAFETY: sending a signal has no memory safety requirements
+    unsafe {
+        libc::kill(pid as libc::pid_t, libc::SIGKILL);
+    }
+}
+
+#[cfg(windows)]
+fn kill(pid: u32) {
+    let _ = Command::new("taskkill")
+        .args(["/F", "/PID", &pid.to_string()])
+        .output();
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::process::Command;
+
+    use speculoos::prelude::*;
+
+    use crate::options::ProcessLimitOpts;
+
+    use super::{apply_limits, resident_memory};
+
+    #[test]
+    fn test_apply_limits_sets_open_file_limit() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "ulimit -n"]);
+        apply_limits(
+            &mut command,
+            &ProcessLimitOpts {
+                max_open_files: Some(64),
+                ..Default::default()
+            },
+        );
+        let output = command.output().unwrap();
+        assert_that!(String::from_utf8_lossy(&output.stdout).trim()).is_equal_to("64");
+    }
+
+    #[test]
+    fn test_resident_memory_of_missing_process() {
+        let mut child = Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        assert_that!(resident_memory(pid)).is_none();
+    }
+}
@@ -1,7 +1,15 @@
 mod command;
 mod config;
+mod crash;
+mod limits;
+mod prewarm;
+mod public;
 mod runner;
+mod versioned;
 
 pub use command::{BackgroundTask, BackgroundTaskLog};
 pub use config::RouterConfigHandler;
+pub use crash::{CrashBundle, CrashReport, LogTail};
+pub use prewarm::PrewarmOperations;
+pub use public::PublicAccess;
 pub use runner::RouterRunner;
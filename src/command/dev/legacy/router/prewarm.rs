@@ -0,0 +1,217 @@
+use anyhow::anyhow;
+use apollo_parser::{cst, Parser};
+use camino::Utf8Path;
+use reqwest::Client;
+use rover_std::{infoln, warnln, Fs};
+use serde_json::{json, Value};
+
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+/// Operations that are run against the router once it's healthy, so that its query plans are
+/// warm and any operation the composed supergraph can't serve is reported right away.
+///
+/// Only queries are run. Mutations could change data in the subgraphs behind the router, and
+/// subscriptions never finish, so they're skipped.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PrewarmOperations {
+    document: String,
+    operation_names: Vec<Option<String>>,
+    skipped_operation_names: Vec<String>,
+}
+
+impl PrewarmOperations {
+    pub fn from_file(path: &Utf8Path) -> RoverResult<Self> {
+        let document = Fs::read_file(path)?;
+        Self::new(document).map_err(|err| {
+            RoverError::new(anyhow!("{} in the prewarm operations at {}", err, path))
+        })
+    }
+
+    fn new(document: String) -> RoverResult<Self> {
+        let parsed = Parser::new(&document).parse();
+        if let Some(err) = parsed.errors().next() {
+            return Err(RoverError::new(anyhow!(
+                "Could not parse operation: {}",
+                err.message()
+            )));
+        }
+
+        let operations: Vec<(Option<String>, bool)> = parsed
+            .document()
+            .definitions()
+            .filter_map(|definition| match definition {
+                cst::Definition::OperationDefinition(operation) => {
+                    // an operation without a type is shorthand for a query
+                    let is_query = operation.operation_type().map_or(true, |operation_type| {
+                        operation_type.query_token().is_some()
+                    });
+                    Some((
+                        operation.name().map(|name| name.text().to_string()),
+                        is_query,
+                    ))
+                }
+                _ => None,
+            })
+            .collect();
+
+        if operations.is_empty() {
+            return Err(RoverError::new(anyhow!("No operations were found")));
+        }
+        if operations.len() > 1 && operations.iter().any(|(name, _)| name.is_none()) {
+            let mut err = RoverError::new(anyhow!("Found an anonymous operation"));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Give every operation a name when there is more than one in the file.".to_string(),
+            ));
+            return Err(err);
+        }
+
+        let (queries, skipped): (Vec<_>, Vec<_>) =
+            operations.into_iter().partition(|(_, is_query)| *is_query);
+        if queries.is_empty() {
+            let mut err = RoverError::new(anyhow!("No queries were found"));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Only queries are run to prewarm the router, so add at least one query to the file."
+                    .to_string(),
+            ));
+            return Err(err);
+        }
+
+        Ok(Self {
+            document,
+            operation_names: queries.into_iter().map(|(name, _)| name).collect(),
+            // with more than one operation every one of them has a name, and a lone operation
+            // that's skipped leaves no queries
+            skipped_operation_names: skipped.into_iter().filter_map(|(name, _)| name).collect(),
+        })
+    }
+
+    /// Runs every query against `endpoint`, with `bearer_token` if the router requires one,
+    /// warning about each one that fails. Failures never stop the `rover dev` session.
+    pub async fn run(&self, client: &Client, endpoint: &str, bearer_token: Option<&str>) -> usize {
+        for operation_name in &self.skipped_operation_names {
+            infoln!(
+                "skipped prewarm operation `{}`, since only queries are run",
+                operation_name
+            );
+        }
+
+        let mut failures = 0;
+        for operation_name in &self.operation_names {
+            if let Err(reason) = self
+                .run_operation(client, endpoint, bearer_token, operation_name)
+                .await
+            {
+                failures += 1;
+                warnln!(
+                    "prewarm operation `{}` failed: {}",
+                    operation_name.as_deref().unwrap_or("<anonymous>"),
+                    reason
+                );
+            }
+        }
+
+        let succeeded = self.operation_names.len() - failures;
+        infoln!(
+            "prewarmed your supergraph with {} of {} operations",
+            succeeded,
+            self.operation_names.len()
+        );
+        failures
+    }
+
+    async fn run_operation(
+        &self,
+        client: &Client,
+        endpoint: &str,
+        bearer_token: Option<&str>,
+        operation_name: &Option<String>,
+    ) -> Result<(), String> {
+        let mut request = client.post(endpoint);
+        if let Some(token) = bearer_token {
+            request = request.bearer_auth(token);
+        }
+        let response = request
+            .json(&json!({
+                "query": self.document,
+                "operationName": operation_name,
+            }))
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+        let status = response.status();
+        let body: Value = response.json().await.map_err(|err| err.to_string())?;
+
+        match body["errors"].as_array() {
+            Some(errors) if !errors.is_empty() => Err(errors
+                .iter()
+                .map(|error| error["message"].as_str().unwrap_or("unknown error"))
+                .collect::<Vec<_>>()
+                .join("; ")),
+            _ if !status.is_success() => Err(format!("the router responded with {}", status)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::MockServer;
+    use rstest::rstest;
+    use serde_json::json;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case::single_anonymous("{ products { id } }", vec![None])]
+    #[case::named(
+        "query A { a } query B { ...F } fragment F on Query { b }",
+        vec![Some("A".to_string()), Some("B".to_string())]
+    )]
+    #[case::skips_mutations_and_subscriptions(
+        "query A { a } mutation B { b } subscription C { c }",
+        vec![Some("A".to_string())]
+    )]
+    fn test_new_finds_queries(#[case] document: &str, #[case] expected: Vec<Option<String>>) {
+        let operations = PrewarmOperations::new(document.to_string());
+        assert_that!(operations)
+            .is_ok()
+            .map(|operations| &operations.operation_names)
+            .is_equal_to(&expected);
+    }
+
+    #[rstest]
+    #[case::empty("fragment F on Query { a }")]
+    #[case::mixed_anonymous("query A { a } { b }")]
+    #[case::only_mutations("mutation A { a }")]
+    #[case::invalid("query A {")]
+    fn test_new_rejects_invalid_documents(#[case] document: &str) {
+        assert_that!(PrewarmOperations::new(document.to_string())).is_err();
+    }
+
+    #[tokio::test]
+    async fn test_run_counts_failed_operations() {
+        let server = MockServer::start();
+        let ok_mock = server.mock(|when, then| {
+            when.method("POST")
+                .path("/")
+                .json_body_partial(r#"{"operationName": "Ok"}"#);
+            then.status(200).json_body(json!({ "data": { "a": 1 } }));
+        });
+        let failing_mock = server.mock(|when, then| {
+            when.method("POST")
+                .path("/")
+                .json_body_partial(r#"{"operationName": "Failing"}"#);
+            then.status(200)
+                .json_body(json!({ "errors": [{ "message": "Cannot query field" }] }));
+        });
+
+        let operations =
+            PrewarmOperations::new("query Ok { a } query Failing { b }".to_string()).unwrap();
+        let failures = operations.run(&Client::new(), &server.url("/"), None).await;
+
+        assert_that!(failures).is_equal_to(1);
+        ok_mock.assert();
+        failing_mock.assert();
+    }
+}
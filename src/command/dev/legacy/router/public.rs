@@ -0,0 +1,114 @@
+//! `rover dev --bind-public` lets other devices on the network, like a phone, query the router.
+//! The router then listens on every interface, so each session gets a random bearer token, and a
+//! Rhai script injected into the router config turns away requests that don't carry it.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+
+use camino::Utf8PathBuf;
+use rover_std::Fs;
+use uuid::Uuid;
+
+use crate::RoverResult;
+
+/// The address the router listens on with `--bind-public`
+pub const PUBLIC_IP: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+
+const SCRIPT_NAME: &str = "bind_public.rhai";
+
+/// The bearer token a `--bind-public` session requires, and the Rhai script that enforces it
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PublicAccess {
+    token: String,
+    scripts_dir: Utf8PathBuf,
+}
+
+impl PublicAccess {
+    /// Generates a token, and writes the script that enforces it to `scripts_dir`
+    pub fn new(scripts_dir: Utf8PathBuf) -> RoverResult<Self> {
+        let access = Self {
+            token: Uuid::new_v4().as_simple().to_string(),
+            scripts_dir,
+        };
+        Fs::create_dir_all(&access.scripts_dir)?;
+        Fs::write_file(access.scripts_dir.join(SCRIPT_NAME), access.script())?;
+        Ok(access)
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// The `rhai` section of the router config that runs the script
+    pub fn rhai_config(&self) -> serde_yaml::Value {
+        let mut rhai = serde_yaml::Mapping::new();
+        rhai.insert("scripts".into(), self.scripts_dir.as_str().into());
+        rhai.insert("main".into(), SCRIPT_NAME.into());
+        rhai.into()
+    }
+
+    fn script(&self) -> String {
+        format!(
+            r#"// Written by `rover dev --bind-public`: only requests with the session's bearer token are served
+
+fn supergraph_service(service) {{
+    service.map_request(Fn("check_token"));
+}}
+
+fn check_token(request) {{
+    if !("authorization" in request.headers) || request.headers["authorization"] != "Bearer {}" {{
+        throw #{{
+            status: 401,
+            message: "This `rover dev` session needs the bearer token it printed when it started."
+        }};
+    }}
+}}
+"#,
+            self.token
+        )
+    }
+
+    /// The URL other devices can query the router at, for a router listening on `socket_addr`
+    pub fn join_url(&self, socket_addr: SocketAddr, listen_path: &str) -> String {
+        let host = lan_ip()
+            .map(|ip| ip.to_string())
+            .unwrap_or_else(|| "<this machine's address>".to_string());
+        format!("http://{}:{}{}", host, socket_addr.port(), listen_path)
+    }
+}
+
+/// The address this machine is reached at on its local network, found by asking the OS which
+/// interface it would route outside traffic through. Nothing is sent
+fn lan_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind(SocketAddr::new(PUBLIC_IP, 0)).ok()?;
+    socket.connect("192.0.2.1:80").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+}
+
+#[cfg(test)]
+mod tests {
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_writes_a_script_that_checks_the_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let scripts_dir = Utf8PathBuf::try_from(dir.path().join("rhai")).unwrap();
+        let access = PublicAccess::new(scripts_dir.clone()).unwrap();
+
+        assert_that!(access.token().len()).is_equal_to(32);
+        let script = Fs::read_file(scripts_dir.join(SCRIPT_NAME)).unwrap();
+        assert_that!(script).contains(format!("\"Bearer {}\"", access.token()).as_str());
+        assert_that!(access.rhai_config()["main"].as_str()).is_equal_to(Some(SCRIPT_NAME));
+    }
+
+    #[test]
+    fn test_tokens_differ_between_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let scripts_dir = Utf8PathBuf::try_from(dir.path().to_path_buf()).unwrap();
+        let first = PublicAccess::new(scripts_dir.clone()).unwrap();
+        let second = PublicAccess::new(scripts_dir).unwrap();
+        assert_that!(first.token()).is_not_equal_to(second.token());
+    }
+}
@@ -4,7 +4,7 @@ use camino::Utf8PathBuf;
 use crossbeam_channel::bounded;
 use reqwest::Client;
 use reqwest::Url;
-use rover_std::{infoln, Style};
+use rover_std::{errln, infoln, warnln, Style};
 use semver::Version;
 
 use std::net::SocketAddr;
@@ -13,13 +13,16 @@ use std::time::{Duration, Instant};
 use crate::command::dev::{
     legacy::{
         do_dev::log_err_and_continue,
-        router::{BackgroundTask, BackgroundTaskLog},
+        router::{
+            BackgroundTask, BackgroundTaskLog, CrashBundle, CrashReport, LogTail,
+            PrewarmOperations, PublicAccess,
+        },
     },
     OVERRIDE_DEV_ROUTER_VERSION,
 };
 use crate::command::install::Plugin;
 use crate::command::Install;
-use crate::options::PluginOpts;
+use crate::options::{PluginOpts, ProcessLimitOpts};
 use crate::utils::client::StudioClientConfig;
 use crate::{RoverError, RoverResult};
 
@@ -35,6 +38,9 @@ pub struct RouterRunner {
     plugin_exe: Option<Utf8PathBuf>,
     router_handle: Option<BackgroundTask>,
     license: Option<Utf8PathBuf>,
+    prewarm_operations: Option<PrewarmOperations>,
+    process_limit_opts: ProcessLimitOpts,
+    public_access: Option<PublicAccess>,
 }
 
 impl RouterRunner {
@@ -48,6 +54,8 @@ impl RouterRunner {
         override_install_path: Option<Utf8PathBuf>,
         client_config: StudioClientConfig,
         license: Option<Utf8PathBuf>,
+        prewarm_operations: Option<PrewarmOperations>,
+        process_limit_opts: ProcessLimitOpts,
     ) -> Self {
         Self {
             supergraph_schema_path,
@@ -60,9 +68,18 @@ impl RouterRunner {
             router_handle: None,
             plugin_exe: None,
             license,
+            prewarm_operations,
+            process_limit_opts,
+            public_access: None,
         }
     }
 
+    /// Guards the router with the bearer token of a `--bind-public` session
+    pub fn with_public_access(mut self, public_access: Option<PublicAccess>) -> Self {
+        self.public_access = public_access;
+        self
+    }
+
     fn install_command(&self) -> RoverResult<Install> {
         let plugin = match &*OVERRIDE_DEV_ROUTER_VERSION {
             Some(version) => Plugin::Router(RouterVersion::Exact(Version::parse(version)?)),
@@ -142,6 +159,16 @@ impl RouterRunner {
                     .replace("[::1]", "localhost"),
                 &self.router_listen_path
             );
+            if let Some(public_access) = &self.public_access {
+                warnln!(
+                    "`--bind-public` lets every device on your network reach this router. Only share the token below with devices you trust."
+                );
+                infoln!(
+                    "query your supergraph from another device at {} with the header `Authorization: Bearer {}`",
+                    public_access.join_url(self.router_socket_addr, &self.router_listen_path),
+                    public_access.token()
+                );
+            }
             Ok(())
         } else {
             Err(RoverError::new(anyhow!(
@@ -185,15 +212,23 @@ impl RouterRunner {
                 router_log_sender,
                 &self.client_config,
                 &self.plugin_opts.profile,
+                &self.process_limit_opts,
             )
             .await?;
             tracing::info!("spawning router with `{}`", router_handle.descriptor());
+            let log_tail = LogTail::default();
+            self.collect_crash_bundles(&router_handle, log_tail.clone());
 
             let warn_prefix = Style::WarningPrefix.paint("WARN:");
             let error_prefix = Style::ErrorPrefix.paint("ERROR:");
             let unknown_prefix = Style::ErrorPrefix.paint("UNKNOWN:");
             tokio::task::spawn_blocking(move || loop {
                 while let Ok(log) = router_log_receiver.recv() {
+                    match &log {
+                        BackgroundTaskLog::Stdout(line) | BackgroundTaskLog::Stderr(line) => {
+                            log_tail.push(line.clone())
+                        }
+                    }
                     match log {
                         BackgroundTaskLog::Stdout(stdout) => {
                             if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&stdout) {
@@ -232,15 +267,59 @@ impl RouterRunner {
                 }
             });
 
-            self.wait_for_startup(client).await?;
+            self.wait_for_startup(client.clone()).await?;
             self.router_handle = Some(router_handle);
 
+            if let Some(prewarm_operations) = &self.prewarm_operations {
+                let endpoint = format!(
+                    "http://{}{}",
+                    &self.router_socket_addr, &self.router_listen_path
+                );
+                let token = self.public_access.as_ref().map(PublicAccess::token);
+                prewarm_operations.run(&client, &endpoint, token).await;
+            }
+
             Ok(())
         } else {
             Ok(())
         }
     }
 
+    /// Writes a [`CrashBundle`] to the session workspace if the router exits without being
+    /// killed, and prints where it is
+    fn collect_crash_bundles(&self, router_handle: &BackgroundTask, log_tail: LogTail) {
+        let pid = router_handle.id();
+        let command = router_handle.descriptor().to_string();
+        let supergraph_schema_path = self.supergraph_schema_path.clone();
+        let router_config_path = self.router_config_path.clone();
+        router_handle.on_unexpected_exit(move |status, uptime| {
+            let report = CrashReport::new(pid, status, uptime, &supergraph_schema_path, &command);
+            errln!(
+                "the router exited unexpectedly with {} after running for {}s",
+                report.exit(),
+                report.uptime_secs
+            );
+            let Some(session_dir) = supergraph_schema_path.parent() else {
+                return;
+            };
+            let router_config = std::fs::read_to_string(&router_config_path).ok();
+            match CrashBundle::write(
+                session_dir,
+                report,
+                router_config.as_deref(),
+                &log_tail.lines(),
+            ) {
+                Ok(bundle) => infoln!(
+                    "the router's last config, log lines, and exit status were saved to {}. Run `rover dev crashes list` to find it again later",
+                    bundle.path
+                ),
+                Err(err) => {
+                    log_err_and_continue(err);
+                }
+            }
+        });
+    }
+
     pub async fn kill(&mut self) -> RoverResult<()> {
         if self.router_handle.is_some() {
             tracing::info!("killing the router");
@@ -334,6 +413,10 @@ mod tests {
                     elv2_license_accepted: Some(true),
                 },
                 skip_update: true,
+                env: None,
+                schema_size_opts: Default::default(),
+                composition_limit_opts: Default::default(),
+                introspection_policy_opts: Default::default(),
             },
             *server.address(),
             "".to_string(),
@@ -346,6 +429,8 @@ mod tests {
                 Some(Duration::from_secs(3)),
             ),
             None,
+            None,
+            Default::default(),
         );
 
         // WHEN waiting for router startup
@@ -0,0 +1,227 @@
+//! Hands the router its config through a pointer to versioned files rather than rewriting one
+//! file in place, so the router's hot reload never reads a half-written config and a config the
+//! router can't run with can be backed out
+
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context};
+use camino::{Utf8Path, Utf8PathBuf};
+use reqwest::{Client, StatusCode};
+use rover_std::Fs;
+
+use crate::RoverResult;
+
+/// How long the router gets to notice a new config before its readiness counts
+const RELOAD_SETTLE: Duration = Duration::from_millis(500);
+
+/// How long the router gets to become ready again after a new config
+const RELOAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The router config files `rover dev` writes, one per version, and the `router.yaml` pointer the
+/// router is started with
+#[derive(Debug)]
+pub(crate) struct VersionedRouterConfig {
+    dir: Utf8PathBuf,
+    version: u64,
+    current: Option<Utf8PathBuf>,
+    previous: Option<Utf8PathBuf>,
+}
+
+impl VersionedRouterConfig {
+    pub(crate) fn new(dir: Utf8PathBuf) -> Self {
+        Self {
+            dir,
+            version: 0,
+            current: None,
+            previous: None,
+        }
+    }
+
+    /// The path the router reads its config from, which always resolves to the live version
+    pub(crate) fn pointer_path(&self) -> Utf8PathBuf {
+        self.dir.join("router.yaml")
+    }
+
+    /// Writes `contents` as the next version and points the router at it. Only the live version
+    /// and the one before it are kept, so there's always one to roll back to
+    pub(crate) fn publish(&mut self, contents: &str) -> RoverResult<Utf8PathBuf> {
+        self.version += 1;
+        let path = self.dir.join(format!("router.v{}.yaml", self.version));
+        Fs::write_file(&path, contents)?;
+        self.point_to(&path)?;
+        let previous = self.current.replace(path.clone());
+        if let Some(stale) = std::mem::replace(&mut self.previous, previous) {
+            let _ = std::fs::remove_file(stale);
+        }
+        Ok(path)
+    }
+
+    /// Points the router back at the version before the last one published, discarding the last
+    pub(crate) fn rollback(&mut self) -> RoverResult<Utf8PathBuf> {
+        let previous = self
+            .previous
+            .take()
+            .ok_or_else(|| anyhow!("there is no earlier router config to roll back to"))?;
+        self.point_to(&previous)?;
+        if let Some(failed) = self.current.replace(previous.clone()) {
+            let _ = std::fs::remove_file(failed);
+        }
+        Ok(previous)
+    }
+
+    /// Swaps the pointer to `version` in a single rename, so the router sees either the old
+    /// config or the new one
+    fn point_to(&self, version: &Utf8Path) -> RoverResult<()> {
+        let pointer_path = self.pointer_path();
+        #[cfg(unix)]
+        {
+            let file_name = version
+                .file_name()
+                .ok_or_else(|| anyhow!("{version} is not a file"))?;
+            let next = self.dir.join(".router.yaml.next");
+            let _ = std::fs::remove_file(&next);
+            std::os::unix::fs::symlink(file_name, &next)
+                .with_context(|| format!("could not link {next} to {version}"))?;
+            std::fs::rename(&next, &pointer_path)
+                .with_context(|| format!("could not move {next} to {pointer_path}"))?;
+        }
+        #[cfg(not(unix))]
+        {
+            // creating symlinks needs extra privileges on Windows, so the version is copied into
+            // place instead, which is still a single rename
+            Fs::write_file_atomically(&pointer_path, Fs::read_file(version)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether the router is ready to serve requests, per its health endpoint
+pub(crate) async fn router_is_ready(client: &Client, endpoint: &str) -> bool {
+    match client.get(endpoint).send().await {
+        Ok(response) => {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            is_ready(status, &body)
+        }
+        Err(_) => false,
+    }
+}
+
+/// Reads a response from the router's health endpoint. With the health check enabled, the
+/// payload's `status` says whether the router is ready; with it disabled, `rover dev`'s default,
+/// the router answering at all is the best there is
+fn is_ready(status: StatusCode, body: &str) -> bool {
+    match serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|payload| payload["status"].as_str().map(ToString::to_string))
+    {
+        Some(health) => health == "UP",
+        None => !status.is_server_error(),
+    }
+}
+
+/// Waits for the router to be ready again after its config pointer was swapped
+pub(crate) async fn wait_for_reload(client: &Client, endpoint: &str) -> Result<(), String> {
+    wait_for_ready(client, endpoint, RELOAD_SETTLE, RELOAD_TIMEOUT).await
+}
+
+async fn wait_for_ready(
+    client: &Client,
+    endpoint: &str,
+    settle: Duration,
+    timeout: Duration,
+) -> Result<(), String> {
+    tokio::time::sleep(settle).await;
+    let now = Instant::now();
+    loop {
+        if router_is_ready(client, endpoint).await {
+            return Ok(());
+        }
+        if now.elapsed() >= timeout {
+            return Err(format!(
+                "{endpoint} did not report the router as ready within {}s",
+                timeout.as_secs()
+            ));
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use httpmock::MockServer;
+    use reqwest::{Client, StatusCode};
+    use rover_std::Fs;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::{is_ready, wait_for_ready, VersionedRouterConfig};
+
+    fn versions(temp_dir: &TempDir) -> VersionedRouterConfig {
+        VersionedRouterConfig::new(Utf8PathBuf::from_path_buf(temp_dir.to_path_buf()).unwrap())
+    }
+
+    #[test]
+    fn test_publish_points_at_latest_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut versions = versions(&temp_dir);
+        let first = versions.publish("first: 1\n").unwrap();
+        assert_that!(Fs::read_file(versions.pointer_path()).unwrap())
+            .is_equal_to("first: 1\n".to_string());
+
+        versions.publish("second: 2\n").unwrap();
+        versions.publish("third: 3\n").unwrap();
+        assert_that!(Fs::read_file(versions.pointer_path()).unwrap())
+            .is_equal_to("third: 3\n".to_string());
+        // only the live version and the one before it are kept
+        assert_that!(first.exists()).is_false();
+    }
+
+    #[test]
+    fn test_rollback_restores_previous_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut versions = versions(&temp_dir);
+        let first = versions.publish("first: 1\n").unwrap();
+        let second = versions.publish("second: 2\n").unwrap();
+
+        assert_that!(versions.rollback()).is_ok().is_equal_to(first);
+        assert_that!(Fs::read_file(versions.pointer_path()).unwrap())
+            .is_equal_to("first: 1\n".to_string());
+        assert_that!(second.exists()).is_false();
+
+        // there's nothing left to roll back to
+        assert_that!(versions.rollback()).is_err();
+    }
+
+    #[rstest]
+    #[case::up(StatusCode::OK, r#"{"status":"UP"}"#, true)]
+    #[case::down(StatusCode::SERVICE_UNAVAILABLE, r#"{"status":"DOWN"}"#, false)]
+    #[case::health_check_disabled(StatusCode::NOT_FOUND, "", true)]
+    #[case::server_error(StatusCode::INTERNAL_SERVER_ERROR, "oops", false)]
+    fn test_is_ready(#[case] status: StatusCode, #[case] body: &str, #[case] expected: bool) {
+        assert_that!(is_ready(status, body)).is_equal_to(expected);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_wait_for_ready(#[values("UP", "DOWN")] health: &str) {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method("GET").path("/health");
+            then.status(200).body(format!(r#"{{"status":"{health}"}}"#));
+        });
+
+        let result = wait_for_ready(
+            &Client::new(),
+            &server.url("/health?ready"),
+            Duration::ZERO,
+            Duration::from_millis(500),
+        )
+        .await;
+        assert_that!(result.is_ok()).is_equal_to(health == "UP");
+    }
+}
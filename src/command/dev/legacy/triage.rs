@@ -0,0 +1,485 @@
+//! An interactive prompt for working through composition errors in `rover dev`, offered when
+//! composition fails while `rover dev` is attached to a terminal
+
+use std::{
+    collections::HashMap,
+    env, fmt,
+    io::{self, BufRead, IsTerminal},
+    process::Command,
+    thread,
+};
+
+use anyhow::anyhow;
+use apollo_federation_types::rover::BuildErrors;
+use camino::{Utf8Path, Utf8PathBuf};
+use crossbeam_channel::{bounded, select, unbounded, Receiver, Sender};
+
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+/// Editors that take the position to open as `--goto <file>:<line>:<column>`, rather than the
+/// `+<line> <file>` that vi, emacs, nano and most other terminal editors understand
+const GOTO_EDITORS: &[&str] = &["code", "code-insiders", "codium", "cursor"];
+
+/// Where in a subgraph's schema a composition error comes from
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TriageLocation {
+    pub subgraph: Option<String>,
+    pub file: Option<Utf8PathBuf>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl fmt::Display for TriageLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.file, &self.subgraph) {
+            (Some(file), _) => write!(f, "{}", file)?,
+            (None, Some(subgraph)) => write!(f, "subgraph '{}'", subgraph)?,
+            (None, None) => write!(f, "<unknown>")?,
+        }
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+            if let Some(column) = self.column {
+                write!(f, ":{}", column)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A single composition error, along with the places it was reported at
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TriageError {
+    pub code: Option<String>,
+    pub message: String,
+    pub locations: Vec<TriageLocation>,
+}
+
+impl TriageError {
+    /// Pairs each error's locations with the schema files their subgraphs are read from
+    pub fn from_build_errors(
+        build_errors: &BuildErrors,
+        schema_files: &HashMap<String, Utf8PathBuf>,
+    ) -> Vec<TriageError> {
+        build_errors
+            .iter()
+            .map(|build_error| TriageError {
+                code: build_error.get_code(),
+                message: build_error
+                    .get_message()
+                    .unwrap_or_else(|| "an unknown error occurred".to_string()),
+                locations: build_error
+                    .get_nodes()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|node| TriageLocation {
+                        file: node
+                            .subgraph
+                            .as_ref()
+                            .and_then(|subgraph| schema_files.get(subgraph).cloned()),
+                        subgraph: node.subgraph,
+                        line: node.start.as_ref().and_then(|start| start.line),
+                        column: node.start.as_ref().and_then(|start| start.column),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// The subgraphs this error was reported in, in the order they were first reported
+    pub fn subgraphs(&self) -> Vec<&str> {
+        let mut subgraphs = Vec::new();
+        for subgraph in self
+            .locations
+            .iter()
+            .filter_map(|location| location.subgraph.as_deref())
+        {
+            if !subgraphs.contains(&subgraph) {
+                subgraphs.push(subgraph);
+            }
+        }
+        subgraphs
+    }
+}
+
+impl fmt::Display for TriageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.code {
+            Some(code) => write!(f, "{}: {}", code, self.message)?,
+            None => write!(f, "{}", self.message)?,
+        }
+        for location in &self.locations {
+            write!(f, "\n      at {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+/// What the user asked for at the triage prompt
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TriageCommand {
+    /// `e`: list the errors from the last composition
+    List,
+    /// `<number>`: open the location of the error with that number in `$EDITOR`
+    Open(usize),
+    /// `r`: re-read schema files and compose again
+    Retry,
+    /// `s [<number> | <subgraph>]`: compose without a subgraph until its schema changes
+    Skip(Option<String>),
+    /// `c`: stop triaging and wait for subgraphs to change
+    Continue,
+    /// `h`, or anything that isn't understood
+    Help,
+}
+
+impl TriageCommand {
+    pub fn parse(input: &str) -> TriageCommand {
+        let input = input.trim();
+        let (command, argument) = match input.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, Some(argument.trim().to_string())),
+            None => (input, None),
+        };
+        match (command, argument) {
+            ("e", None) => TriageCommand::List,
+            ("r", None) => TriageCommand::Retry,
+            ("s", argument) => TriageCommand::Skip(argument),
+            ("c", None) => TriageCommand::Continue,
+            (number, None) => number
+                .parse()
+                .ok()
+                .filter(|number| *number > 0)
+                .map(TriageCommand::Open)
+                .unwrap_or(TriageCommand::Help),
+            _ => TriageCommand::Help,
+        }
+    }
+}
+
+/// Printed whenever a composition fails while the prompt is open
+pub const TRIAGE_HELP: &str = "enter `e` to list the errors, an error's number to open it in $EDITOR, `r` to retry, `s [<number> | <subgraph>]` to skip a subgraph or `c` to wait for changes";
+
+/// Works out which subgraph `s` should skip: the one named, the one an error number blames, or,
+/// when no argument is given, the only subgraph any of the errors blame
+pub fn skip_target(argument: Option<&str>, errors: &[TriageError]) -> Result<String, String> {
+    let only_subgraph = |subgraphs: Vec<&str>, description: &str| match subgraphs.as_slice() {
+        [subgraph] => Ok(subgraph.to_string()),
+        [] => Err(format!("{} doesn't point at a subgraph", description)),
+        subgraphs => Err(format!(
+            "{} points at more than one subgraph, pick one of: {}",
+            description,
+            subgraphs.join(", ")
+        )),
+    };
+    match argument {
+        None => {
+            let mut subgraphs = Vec::new();
+            for subgraph in errors.iter().flat_map(TriageError::subgraphs) {
+                if !subgraphs.contains(&subgraph) {
+                    subgraphs.push(subgraph);
+                }
+            }
+            only_subgraph(subgraphs, "composition")
+        }
+        Some(argument) => match argument.parse::<usize>() {
+            Ok(number) => match number.checked_sub(1).and_then(|index| errors.get(index)) {
+                Some(error) => only_subgraph(error.subgraphs(), &format!("error {}", number)),
+                None => Err(format!("there's no error number {}", number)),
+            },
+            Err(_) => Ok(argument.to_string()),
+        },
+    }
+}
+
+/// Either a line typed at the prompt, or something that arrived while waiting for one
+#[derive(Debug)]
+pub enum TriageInput<T> {
+    Command(TriageCommand),
+    Message(T),
+    Closed,
+}
+
+/// Reads triage commands from stdin without getting in the way of anything else `rover dev`
+/// needs to react to while the prompt is open
+#[derive(Debug)]
+pub struct TriagePrompt {
+    requests: Sender<()>,
+    lines: Receiver<Option<String>>,
+    awaiting_line: bool,
+}
+
+impl TriagePrompt {
+    /// A prompt reading from stdin, when both stdin and stderr are attached to a terminal
+    pub fn for_terminal() -> Option<TriagePrompt> {
+        (io::stdin().is_terminal() && io::stderr().is_terminal())
+            .then(|| TriagePrompt::new(io::BufReader::new(io::stdin())))
+    }
+
+    /// Lines are read from `reader` one at a time, and only when asked for, so that nothing
+    /// competes with an editor opened from the prompt for the terminal's input
+    pub fn new(reader: impl BufRead + Send + 'static) -> TriagePrompt {
+        let (requests, pending_requests) = unbounded::<()>();
+        let (line_sender, lines) = bounded(1);
+        thread::spawn(move || {
+            let mut reader = reader;
+            for () in pending_requests {
+                let mut line = String::new();
+                let line = match reader.read_line(&mut line) {
+                    Ok(0) | Err(_) => None,
+                    Ok(_) => Some(line),
+                };
+                let closed = line.is_none();
+                if line_sender.send(line).is_err() || closed {
+                    break;
+                }
+            }
+        });
+        TriagePrompt {
+            requests,
+            lines,
+            awaiting_line: false,
+        }
+    }
+
+    /// Waits for the next command, or for a message on `messages`, whichever comes first. A line
+    /// that's still being typed when a message arrives is picked up by the next call
+    pub fn next_input<T>(&mut self, messages: &Receiver<T>) -> TriageInput<T> {
+        if !self.awaiting_line {
+            if self.requests.send(()).is_err() {
+                return TriageInput::Closed;
+            }
+            self.awaiting_line = true;
+        }
+        select! {
+            recv(self.lines) -> line => {
+                self.awaiting_line = false;
+                match line {
+                    Ok(Some(line)) => TriageInput::Command(TriageCommand::parse(&line)),
+                    _ => TriageInput::Closed,
+                }
+            }
+            recv(messages) -> message => match message {
+                Ok(message) => TriageInput::Message(message),
+                Err(_) => TriageInput::Closed,
+            }
+        }
+    }
+}
+
+/// Opens `location` in the editor named by `$VISUAL` or `$EDITOR`, waiting for it to exit
+pub fn open_in_editor(location: &TriageLocation) -> RoverResult<()> {
+    let Some(file) = &location.file else {
+        return Err(
+            RoverError::new(anyhow!("there's no schema file to open for {}", location))
+                .with_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Only subgraphs read from a `file` in your supergraph config can be opened."
+                        .to_string(),
+                )),
+        );
+    };
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .ok_or_else(|| {
+            RoverError::new(anyhow!("no editor is set to open {} with", location)).with_suggestion(
+                RoverErrorSuggestion::Adhoc(
+                    "Set the `EDITOR` environment variable to the command of your editor."
+                        .to_string(),
+                ),
+            )
+        })?;
+    let (program, args) = editor_command(&editor, file, location.line, location.column);
+    let status = Command::new(&program)
+        .args(&args)
+        .status()
+        .map_err(|e| RoverError::new(anyhow!("could not start `{}`: {}", program, e)))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(RoverError::new(anyhow!(
+            "`{}` exited with {}",
+            program,
+            status
+        )))
+    }
+}
+
+/// The program and arguments that open `file` at the given position with `editor`, which may
+/// include arguments of its own (eg, `code --wait`)
+fn editor_command(
+    editor: &str,
+    file: &Utf8Path,
+    line: Option<usize>,
+    column: Option<usize>,
+) -> (String, Vec<String>) {
+    let mut words = editor.split_whitespace().map(str::to_string);
+    let program = words.next().unwrap_or_default();
+    let mut args: Vec<String> = words.collect();
+    let name = Utf8Path::new(&program).file_name().unwrap_or(&program);
+    match line {
+        Some(line) if GOTO_EDITORS.contains(&name) => {
+            args.push("--goto".to_string());
+            args.push(format!("{}:{}:{}", file, line, column.unwrap_or(1)));
+        }
+        Some(line) => {
+            args.push(format!("+{}", line));
+            args.push(file.to_string());
+        }
+        None => args.push(file.to_string()),
+    }
+    (program, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, io::BufReader};
+
+    use apollo_federation_types::{
+        build_plugin::{BuildMessageLocation, BuildMessagePoint},
+        rover::{BuildError, BuildErrors},
+    };
+    use camino::{Utf8Path, Utf8PathBuf};
+    use crossbeam_channel::unbounded;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case::list("e\n", TriageCommand::List)]
+    #[case::open(" 2 ", TriageCommand::Open(2))]
+    #[case::open_zero("0", TriageCommand::Help)]
+    #[case::retry("r", TriageCommand::Retry)]
+    #[case::skip("s", TriageCommand::Skip(None))]
+    #[case::skip_subgraph("s products", TriageCommand::Skip(Some("products".to_string())))]
+    #[case::continue_("c", TriageCommand::Continue)]
+    #[case::unknown("retry please", TriageCommand::Help)]
+    fn test_parse_command(#[case] input: &str, #[case] expected: TriageCommand) {
+        assert_that!(TriageCommand::parse(input)).is_equal_to(expected);
+    }
+
+    #[rstest]
+    #[case::vi("vi", Some(12), vec!["+12", "/schemas/products.graphql"])]
+    #[case::code_wait("code --wait", Some(12), vec!["--wait", "--goto", "/schemas/products.graphql:12:5"])]
+    #[case::no_line("nano", None, vec!["/schemas/products.graphql"])]
+    fn test_editor_command(
+        #[case] editor: &str,
+        #[case] line: Option<usize>,
+        #[case] expected_args: Vec<&str>,
+    ) {
+        let (program, args) = editor_command(
+            editor,
+            Utf8Path::new("/schemas/products.graphql"),
+            line,
+            Some(5),
+        );
+        assert_that!(program.as_str()).is_equal_to(editor.split(' ').next().unwrap());
+        assert_that!(args).is_equal_to(
+            expected_args
+                .into_iter()
+                .map(str::to_string)
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn test_from_build_errors_maps_subgraphs_to_schema_files() {
+        let location = |subgraph: &str, line: usize| BuildMessageLocation {
+            subgraph: Some(subgraph.to_string()),
+            source: None,
+            start: Some(BuildMessagePoint {
+                start: None,
+                end: None,
+                column: Some(3),
+                line: Some(line),
+            }),
+            end: None,
+            other: Default::default(),
+        };
+        let build_errors = BuildErrors::from(vec![BuildError::composition_error(
+            Some("INVALID_FIELD_SHARING".to_string()),
+            Some("Non-shareable field \"Product.name\"".to_string()),
+            Some(vec![location("products", 7), location("inventory", 2)]),
+            None,
+        )]);
+        let schema_files = HashMap::from_iter([(
+            "products".to_string(),
+            Utf8PathBuf::from("/schemas/products.graphql"),
+        )]);
+
+        let errors = TriageError::from_build_errors(&build_errors, &schema_files);
+
+        assert_that!(errors).has_length(1);
+        assert_that!(errors[0].subgraphs()).is_equal_to(vec!["products", "inventory"]);
+        assert_that!(errors[0].to_string()).is_equal_to(
+            "INVALID_FIELD_SHARING: Non-shareable field \"Product.name\"\n      at /schemas/products.graphql:7:3\n      at subgraph 'inventory':2:3"
+                .to_string(),
+        );
+    }
+
+    #[rstest]
+    #[case::only_subgraph(None, Ok("products"))]
+    #[case::named(Some("reviews"), Ok("reviews"))]
+    #[case::numbered(Some("2"), Ok("products"))]
+    #[case::ambiguous(
+        Some("1"),
+        Err("error 1 points at more than one subgraph, pick one of: products, inventory")
+    )]
+    #[case::out_of_range(Some("3"), Err("there's no error number 3"))]
+    fn test_skip_target(#[case] argument: Option<&str>, #[case] expected: Result<&str, &str>) {
+        let error = |subgraphs: &[&str]| TriageError {
+            code: None,
+            message: "invalid".to_string(),
+            locations: subgraphs
+                .iter()
+                .map(|subgraph| TriageLocation {
+                    subgraph: Some(subgraph.to_string()),
+                    file: None,
+                    line: None,
+                    column: None,
+                })
+                .collect(),
+        };
+        let errors = [error(&["products", "inventory"]), error(&["products"])];
+        let errors = match argument {
+            None => &errors[1..],
+            Some(_) => &errors[..],
+        };
+        assert_that!(skip_target(argument, errors))
+            .is_equal_to(expected.map(str::to_string).map_err(str::to_string));
+    }
+
+    /// Blocks reads until the test sends the input to return
+    struct ChannelReader(crossbeam_channel::Receiver<String>);
+
+    impl std::io::Read for ChannelReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let input = self.0.recv().unwrap_or_default();
+            buf[..input.len()].copy_from_slice(input.as_bytes());
+            Ok(input.len())
+        }
+    }
+
+    #[test]
+    fn test_next_input_keeps_a_pending_line_for_the_next_call() {
+        let (input, reader) = unbounded();
+        let mut prompt = TriagePrompt::new(BufReader::new(ChannelReader(reader)));
+        let (sender, messages) = unbounded();
+
+        sender.send("products changed").unwrap();
+        assert_that!(matches!(
+            prompt.next_input(&messages),
+            TriageInput::Message("products changed")
+        ))
+        .is_true();
+
+        input.send("s products\n".to_string()).unwrap();
+        assert_that!(matches!(
+            prompt.next_input(&messages),
+            TriageInput::Command(TriageCommand::Skip(Some(subgraph))) if subgraph == "products"
+        ))
+        .is_true();
+
+        drop(input);
+        assert_that!(matches!(prompt.next_input(&messages), TriageInput::Closed)).is_true();
+    }
+}
@@ -8,21 +8,56 @@ use rover_client::shared::GraphRef;
 use serde::Serialize;
 
 use crate::{
-    options::{OptionalSubgraphOpts, PluginOpts},
-    utils::parsers::FileDescriptorType,
+    options::{OptionalSubgraphOpts, PluginOpts, ProcessLimitOpts},
+    utils::{client::StudioClientConfig, parsers::FileDescriptorType},
+    RoverOutput, RoverResult,
 };
 
-#[cfg(not(feature = "dev-next"))]
+use self::url_map::UrlMapRule;
+
+#[cfg(feature = "composition-js")]
+pub mod crashes;
+// builds with `dev-next` run `rover dev` on `next`, and only use the router and port helpers here
+#[cfg_attr(feature = "dev-next", allow(dead_code))]
 pub mod legacy;
 #[cfg(feature = "dev-next")]
 pub mod next;
+pub mod service;
+pub mod url_map;
 
 #[derive(Debug, Serialize, Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct Dev {
+    #[clap(subcommand)]
+    pub(crate) command: Option<DevCommand>,
+
     #[clap(flatten)]
     pub(crate) opts: DevOpts,
 }
 
+#[derive(Debug, Serialize, Parser)]
+pub enum DevCommand {
+    #[command(flatten)]
+    Service(service::DevServiceCommand),
+
+    /// Inspect the crash bundles collected when the router exits unexpectedly
+    ///
+    /// Each bundle holds the router's last config, a hash of the supergraph schema it served,
+    /// its last 500 log lines, and its exit code or signal and uptime.
+    #[cfg(feature = "composition-js")]
+    Crashes(crashes::DevCrashes),
+}
+
+impl DevCommand {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        match self {
+            DevCommand::Service(service) => service.run(),
+            #[cfg(feature = "composition-js")]
+            DevCommand::Crashes(crashes) => crashes.run(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Parser)]
 pub struct DevOpts {
     #[clap(flatten)]
@@ -33,6 +68,28 @@ pub struct DevOpts {
 
     #[clap(flatten)]
     pub supergraph_opts: SupergraphOpts,
+
+    #[clap(flatten)]
+    pub process_limit_opts: ProcessLimitOpts,
+}
+
+impl Dev {
+    /// Runs `rover dev`. Builds with the `dev-next` feature run the implementation built on the
+    /// composition runner, and every other build runs the original one
+    pub async fn run(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+    ) -> RoverResult<RoverOutput> {
+        #[cfg(feature = "dev-next")]
+        {
+            self.run_next(override_install_path, client_config).await
+        }
+        #[cfg(not(feature = "dev-next"))]
+        {
+            self.run_legacy(override_install_path, client_config).await
+        }
+    }
 }
 
 #[derive(Debug, Parser, Serialize, Clone, Getters)]
@@ -53,6 +110,14 @@ pub struct SupergraphOpts {
     #[arg(long)]
     supergraph_address: Option<IpAddr>,
 
+    /// Let other devices on your network, like a phone, query the router, by listening on every
+    /// network interface (`0.0.0.0`).
+    ///
+    /// The router then only serves requests with the bearer token that's generated for, and
+    /// printed by, each session.
+    #[arg(long = "bind-public", conflicts_with = "supergraph_address")]
+    bind_public: bool,
+
     /// The path to a router configuration file. If the file path is empty, a default configuration will be written to that file. This file is then watched for changes and propagated to the router.
     ///
     /// For information on the format of this file, please see https://www.apollographql.com/docs/router/configuration/overview/#yaml-config-file.
@@ -90,6 +155,56 @@ pub struct SupergraphOpts {
     /// For more information, please see https://www.apollographql.com/docs/router/enterprise-features/#offline-enterprise-license
     #[arg(long)]
     license: Option<Utf8PathBuf>,
+
+    /// The path to a file of GraphQL operations to run against the router once it starts.
+    ///
+    /// This warms the router's query planner and reports any query that the composed supergraph fails to serve. Mutations and subscriptions in the file are skipped.
+    #[arg(long = "prewarm", value_name = "OPERATIONS_PATH")]
+    #[serde(skip_serializing)]
+    prewarm_operations_path: Option<Utf8PathBuf>,
+
+    /// A rule that rewrites the routing URLs of subgraphs, formatted as `PATTERN=>REPLACEMENT`.
+    ///
+    /// For example, `--url-map 'https://*.internal.example.com=>http://localhost:{PRODUCTS_PORT}'` routes requests for any subgraph under `internal.example.com` to the port in the `PRODUCTS_PORT` environment variable, keeping the rest of the URL. Each `*` matches part of the URL up to the next `/`, and `{1}`, `{2}`, and so on in the replacement stand for what each `*` matched.
+    ///
+    /// Pass this option more than once to add more rules; the first rule that matches a URL is used. Rules can also be set with the `APOLLO_ROVER_DEV_URL_MAP` environment variable, separated by commas.
+    #[arg(
+        long = "url-map",
+        value_name = "RULE",
+        env = "APOLLO_ROVER_DEV_URL_MAP",
+        value_delimiter = ','
+    )]
+    #[serde(skip_serializing)]
+    url_map: Vec<UrlMapRule>,
+
+    /// Check that each subgraph's routing URL accepts connections every SECONDS seconds for as
+    /// long as the session runs.
+    ///
+    /// Subgraphs that stop accepting connections are reported as degraded, and again when they
+    /// recover, so a flapping service is easy to tell apart from a problem with the supergraph.
+    #[arg(long = "subgraph-url-health-interval", value_name = "SECONDS")]
+    #[serde(skip_serializing)]
+    subgraph_url_health_interval: Option<u64>,
+
+    /// The path to a docker-compose file with services the session depends on, like subgraphs
+    /// that already run with docker-compose.
+    ///
+    /// The services are started before the subgraphs are watched, and stopped when the session
+    /// ends. Routing URLs that point at a service's port within the compose network, like
+    /// `http://products:4001/graphql`, are routed to the port it's published on instead.
+    #[arg(long = "compose-file", value_name = "PATH")]
+    #[serde(skip_serializing)]
+    compose_file: Option<Utf8PathBuf>,
+
+    /// The docker-compose profiles to start services from, separated by commas
+    #[arg(
+        long = "compose-profiles",
+        value_name = "PROFILES",
+        value_delimiter = ',',
+        requires = "compose_file"
+    )]
+    #[serde(skip_serializing)]
+    compose_profiles: Vec<String>,
 }
 
 lazy_static::lazy_static! {
@@ -101,3 +216,119 @@ lazy_static::lazy_static! {
     pub(crate) static ref OVERRIDE_DEV_COMPOSITION_VERSION: Option<String> =
         std::env::var("APOLLO_ROVER_DEV_COMPOSITION_VERSION").ok();
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use speculoos::prelude::*;
+
+    use super::DevOpts;
+
+    #[test]
+    fn test_compose_profiles_require_a_compose_file() {
+        assert_that!(DevOpts::try_parse_from([
+            "dev",
+            "--compose-profiles",
+            "graphql"
+        ]))
+        .is_err();
+        let opts = DevOpts::try_parse_from([
+            "dev",
+            "--compose-file",
+            "docker-compose.yml",
+            "--compose-profiles",
+            "graphql,db",
+        ])
+        .unwrap();
+        assert_that!(opts.supergraph_opts.compose_profiles)
+            .is_equal_to(vec!["graphql".to_string(), "db".to_string()]);
+    }
+
+    /// Both implementations have to agree on the subgraphs a supergraph config describes
+    #[cfg(feature = "composition-js")]
+    #[tokio::test]
+    async fn test_engines_load_the_same_supergraph_config() {
+        use std::time::Duration;
+
+        use apollo_federation_types::config::SupergraphConfig;
+        use assert_fs::{prelude::*, TempDir};
+        use camino::Utf8PathBuf;
+        use houston::Config;
+        use indoc::indoc;
+
+        use crate::{
+            composition::supergraph::config::resolver::SupergraphConfigResolver,
+            options::ProfileOpt,
+            utils::{
+                client::{ClientBuilder, StudioClientConfig},
+                effect::{
+                    fetch_remote_subgraphs::MockFetchRemoteSubgraphs,
+                    introspect::IntrospectionPolicy, read_stdin::MockReadStdin,
+                },
+                parsers::FileDescriptorType,
+                supergraph_config::get_supergraph_config,
+            },
+        };
+
+        let dir = TempDir::new().unwrap();
+        dir.child("products.graphql")
+            .write_str("type Query { products: [String] }")
+            .unwrap();
+        dir.child("supergraph.yaml")
+            .write_str(indoc! {r#"
+                federation_version: =2.9.0
+                subgraphs:
+                  products:
+                    routing_url: http://localhost:4001
+                    schema:
+                      file: ./products.graphql
+                  reviews:
+                    schema:
+                      subgraph_url: http://localhost:4002
+                  inline:
+                    routing_url: http://localhost:4003
+                    schema:
+                      sdl: "type Query { inline: String }"
+            "#})
+            .unwrap();
+        let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+        let supergraph_config_path = FileDescriptorType::File(root.join("supergraph.yaml"));
+
+        let home = TempDir::new().unwrap();
+        let home_dir = Utf8PathBuf::from_path_buf(home.path().to_path_buf()).unwrap();
+        let client_config = StudioClientConfig::new(
+            None,
+            Config::new(Some(&home_dir), None).unwrap(),
+            false,
+            ClientBuilder::default(),
+            Some(Duration::from_secs(3)),
+        );
+        let legacy = get_supergraph_config(
+            &None,
+            &Some(supergraph_config_path.clone()),
+            None,
+            client_config,
+            &ProfileOpt {
+                profile_name: "default".to_string(),
+            },
+            false,
+            None,
+            IntrospectionPolicy::default(),
+        )
+        .await
+        .unwrap();
+
+        let next: SupergraphConfig = SupergraphConfigResolver::default()
+            .load_remote_subgraphs(&MockFetchRemoteSubgraphs::new(), None)
+            .await
+            .unwrap()
+            .load_from_file_descriptor(&mut MockReadStdin::new(), Some(&supergraph_config_path))
+            .unwrap()
+            .lazily_resolve_subgraphs(&root)
+            .await
+            .unwrap()
+            .into();
+
+        assert_that!(legacy).is_equal_to(Some(next));
+    }
+}
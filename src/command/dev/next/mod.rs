@@ -8,7 +8,10 @@ use tap::TapFallible;
 
 use crate::{
     command::Dev,
-    composition::runner::OneShotComposition,
+    composition::{
+        runner::OneShotComposition,
+        supergraph::config::resolver::{SubgraphResolutionPolicy, SubgraphRetryPolicy},
+    },
     subtask::{Subtask, SubtaskHandleUnit, SubtaskRunUnit},
     utils::{client::StudioClientConfig, effect::read_file::FsReadFile},
     RoverError, RoverOutput, RoverResult,
@@ -22,8 +25,8 @@ use self::router::{
 mod router;
 
 impl Dev {
-    /// Runs rover dev
-    pub async fn run(
+    /// Runs rover dev on the composition runner
+    pub(crate) async fn run_next(
         &self,
         override_install_path: Option<Utf8PathBuf>,
         client_config: StudioClientConfig,
@@ -68,7 +71,16 @@ impl Dev {
             .profile(profile)
             .elv2_license_accepter(self.opts.plugin_opts.elv2_license_accepter)
             .skip_update(self.opts.plugin_opts.skip_update)
+            .and_environment(self.opts.plugin_opts.env.clone())
+            .schema_size_opts(self.opts.plugin_opts.schema_size_opts)
+            .composition_limit_opts(self.opts.plugin_opts.composition_limit_opts)
+            .introspection_policy(self.opts.plugin_opts.introspection_policy_opts.policy())
             .output_file(composition_output)
+            // one unreachable subgraph shouldn't take down the whole local supergraph
+            .subgraph_resolution_policy(SubgraphResolutionPolicy::AllowPartial)
+            .subgraph_retry_policy(SubgraphRetryPolicy::new(
+                u32::try_from(self.opts.subgraph_opts.subgraph_retries).unwrap_or(u32::MAX),
+            ))
             .and_federation_version(federation_version)
             .and_graph_ref(graph_ref)
             .and_supergraph_yaml(supergraph_yaml)
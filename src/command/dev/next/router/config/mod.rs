@@ -16,6 +16,7 @@ use self::{
 mod parser;
 pub mod remote;
 mod state;
+mod subscriptions;
 
 const DEFAULT_ROUTER_IP_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
 const DEFAULT_ROUTER_PORT: u16 = 4000;
@@ -0,0 +1,167 @@
+use serde_yaml::{Mapping, Value};
+
+use crate::composition::supergraph::config::full::FullyResolvedSubgraph;
+
+use super::RouterConfig;
+
+/// The router's name for the `graphql-transport-ws` subprotocol
+const ROUTER_GRAPHQL_WS: &str = "graphql_ws";
+
+impl RouterConfig {
+    /// Enables subscription routing in passthrough mode for every subgraph that was found to serve
+    /// subscriptions over a websocket. Anything the config already says about subscriptions, or
+    /// about a particular subgraph's subscriptions, is left as it is
+    #[allow(unused)]
+    pub fn with_subscriptions<'a>(
+        &self,
+        subgraphs: impl IntoIterator<Item = (&'a String, &'a FullyResolvedSubgraph)>,
+    ) -> Result<RouterConfig, serde_yaml::Error> {
+        let subscription_subgraphs = subgraphs
+            .into_iter()
+            .filter_map(|(name, subgraph)| {
+                subgraph
+                    .subscriptions()
+                    .as_ref()
+                    .map(|subscriptions| (name, subscriptions.url.path().to_string()))
+            })
+            .collect::<Vec<_>>();
+        if subscription_subgraphs.is_empty() {
+            return Ok(RouterConfig::new(self.inner()));
+        }
+
+        let mut config = match serde_yaml::from_str(self.inner())? {
+            Value::Mapping(config) => config,
+            Value::Null => Mapping::new(),
+            _ => return Ok(RouterConfig::new(self.inner())),
+        };
+        let Some(subscription) = mapping_entry(&mut config, "subscription") else {
+            return Ok(RouterConfig::new(self.inner()));
+        };
+        subscription
+            .entry(Value::from("enabled"))
+            .or_insert(Value::Bool(true));
+        let Some(passthrough_subgraphs) = mapping_entry(subscription, "mode")
+            .and_then(|mode| mapping_entry(mode, "passthrough"))
+            .and_then(|passthrough| mapping_entry(passthrough, "subgraphs"))
+        else {
+            return Ok(RouterConfig::new(self.inner()));
+        };
+        for (name, path) in subscription_subgraphs {
+            let mut subgraph = Mapping::new();
+            subgraph.insert(Value::from("path"), Value::from(path));
+            subgraph.insert(Value::from("protocol"), Value::from(ROUTER_GRAPHQL_WS));
+            passthrough_subgraphs
+                .entry(Value::from(name.as_str()))
+                .or_insert(Value::Mapping(subgraph));
+        }
+        Ok(RouterConfig::new(serde_yaml::to_string(&config)?))
+    }
+}
+
+/// The mapping at `key`, creating it when it's missing or empty. `None` when something other than
+/// a mapping is already there
+fn mapping_entry<'a>(mapping: &'a mut Mapping, key: &str) -> Option<&'a mut Mapping> {
+    let value = mapping
+        .entry(Value::from(key))
+        .or_insert_with(|| Value::Mapping(Mapping::new()));
+    if value.is_null() {
+        *value = Value::Mapping(Mapping::new());
+    }
+    value.as_mapping_mut()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use indoc::indoc;
+    use speculoos::prelude::*;
+
+    use crate::{
+        composition::supergraph::config::full::FullyResolvedSubgraph,
+        utils::graphql_ws::{SubscriptionSupport, GRAPHQL_TRANSPORT_WS},
+    };
+
+    use super::RouterConfig;
+
+    #[test]
+    fn test_with_subscriptions_keeps_existing_settings() {
+        let subgraph = |subscriptions: Option<&str>| {
+            FullyResolvedSubgraph::builder()
+                .schema("type Query { a: Int }".to_string())
+                .and_subscriptions(subscriptions.map(|url| SubscriptionSupport {
+                    url: url.parse().unwrap(),
+                    protocol: GRAPHQL_TRANSPORT_WS.to_string(),
+                }))
+                .build()
+        };
+        let subgraphs = BTreeMap::from_iter([
+            (
+                "products".to_string(),
+                subgraph(Some("http://localhost:4001/graphql")),
+            ),
+            (
+                "reviews".to_string(),
+                subgraph(Some("http://localhost:4002/graphql")),
+            ),
+            ("users".to_string(), subgraph(None)),
+        ]);
+        let config = RouterConfig::new(indoc! {"
+            supergraph:
+              listen: 127.0.0.1:4000
+            subscription:
+              enabled: false
+              mode:
+                passthrough:
+                  subgraphs:
+                    reviews:
+                      path: /ws
+        "});
+
+        let config = config.with_subscriptions(&subgraphs).unwrap();
+
+        assert_that!(config.inner()).is_equal_to(indoc! {"
+            supergraph:
+              listen: 127.0.0.1:4000
+            subscription:
+              enabled: false
+              mode:
+                passthrough:
+                  subgraphs:
+                    reviews:
+                      path: /ws
+                    products:
+                      path: /graphql
+                      protocol: graphql_ws
+        "});
+    }
+
+    #[test]
+    fn test_with_subscriptions_from_an_empty_config() {
+        let subgraphs = BTreeMap::from_iter([(
+            "products".to_string(),
+            FullyResolvedSubgraph::builder()
+                .schema("type Subscription { a: Int }".to_string())
+                .subscriptions(SubscriptionSupport {
+                    url: "http://localhost:4001/".parse().unwrap(),
+                    protocol: GRAPHQL_TRANSPORT_WS.to_string(),
+                })
+                .build(),
+        )]);
+
+        let config = RouterConfig::new("")
+            .with_subscriptions(&subgraphs)
+            .unwrap();
+
+        assert_that!(config.inner()).is_equal_to(indoc! {"
+            subscription:
+              enabled: true
+              mode:
+                passthrough:
+                  subgraphs:
+                    products:
+                      path: /
+                      protocol: graphql_ws
+        "});
+    }
+}
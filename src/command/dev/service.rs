@@ -0,0 +1,549 @@
+//! Registers `rover dev` sessions with the operating system's user-level service manager, so
+//! that an always-on local supergraph comes back up after a reboot
+
+use std::{env, fmt::Write as _, fs, process::Command};
+
+use anyhow::{anyhow, Context};
+use camino::Utf8PathBuf;
+use clap::{Args, Parser};
+use serde::Serialize;
+
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+const SERVICE_PREFIX: &str = "rover-dev-";
+const LAUNCHD_LABEL_PREFIX: &str = "com.apollographql.rover.dev.";
+
+#[derive(Debug, Serialize, Parser)]
+pub enum DevServiceCommand {
+    /// Register a `rover dev` session for a project with your service manager, so it starts in
+    /// the background now and every time you log in
+    ///
+    /// Uses a systemd user unit on Linux, a launchd agent on macOS, and a scheduled task on
+    /// Windows.
+    InstallService(InstallService),
+
+    /// Stop a background `rover dev` session and remove it from your service manager
+    UninstallService(ServiceNameOpt),
+
+    /// Report whether a background `rover dev` session is installed and running
+    ServiceStatus(ServiceNameOpt),
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub struct InstallService {
+    #[clap(flatten)]
+    service_name: ServiceNameOpt,
+
+    /// The project directory that the `rover dev` session runs in. Defaults to the current
+    /// directory
+    #[arg(long = "project-dir")]
+    #[serde(skip_serializing)]
+    project_dir: Option<Utf8PathBuf>,
+
+    /// Replace an existing service with the same name
+    #[arg(long = "force", short = 'f')]
+    force: bool,
+
+    /// The arguments to run `rover dev` with, passed after `--`
+    /// (eg, `-- --supergraph-config supergraph.yaml --elv2-license accept`)
+    #[arg(last = true)]
+    #[serde(skip_serializing)]
+    dev_args: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Args)]
+pub struct ServiceNameOpt {
+    /// The name of the background `rover dev` session. Defaults to the name of the current
+    /// directory
+    #[arg(long = "service-name")]
+    service_name: Option<String>,
+}
+
+impl ServiceNameOpt {
+    fn resolve(&self, project_dir: &Utf8PathBuf) -> RoverResult<String> {
+        let name = match &self.service_name {
+            Some(name) => name.clone(),
+            None => project_dir
+                .file_name()
+                .ok_or_else(|| {
+                    anyhow!(
+                        "could not determine a service name from {}, pass `--service-name` instead",
+                        project_dir
+                    )
+                })?
+                .to_string(),
+        };
+        let name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        if name.trim_matches('-').is_empty() {
+            return Err(RoverError::new(anyhow!(
+                "'{}' is not a valid service name, it must contain at least one letter or number",
+                name
+            )));
+        }
+        Ok(name)
+    }
+}
+
+/// Everything a service manager needs to know to run a `rover dev` session
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct ServiceDefinition {
+    name: String,
+    rover_exe: Utf8PathBuf,
+    project_dir: Utf8PathBuf,
+    dev_args: Vec<String>,
+}
+
+impl ServiceDefinition {
+    fn command(&self) -> Vec<String> {
+        let mut command = vec![self.rover_exe.to_string(), "dev".to_string()];
+        command.extend(self.dev_args.iter().cloned());
+        command
+    }
+}
+
+/// The user-level service managers that background sessions can be registered with
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ServiceManager {
+    Systemd,
+    Launchd,
+    TaskScheduler,
+}
+
+impl ServiceManager {
+    fn current() -> RoverResult<ServiceManager> {
+        if cfg!(target_os = "linux") {
+            Ok(ServiceManager::Systemd)
+        } else if cfg!(target_os = "macos") {
+            Ok(ServiceManager::Launchd)
+        } else if cfg!(windows) {
+            Ok(ServiceManager::TaskScheduler)
+        } else {
+            Err(RoverError::new(anyhow!(
+                "background `rover dev` sessions are not supported on this platform"
+            )))
+        }
+    }
+
+    /// The identifier the service manager knows the session by
+    fn service_id(&self, name: &str) -> String {
+        match self {
+            ServiceManager::Systemd => format!("{SERVICE_PREFIX}{name}.service"),
+            ServiceManager::Launchd => format!("{LAUNCHD_LABEL_PREFIX}{name}"),
+            ServiceManager::TaskScheduler => format!("{SERVICE_PREFIX}{name}"),
+        }
+    }
+
+    /// Where the service definition lives on disk, for service managers that read one
+    fn definition_path(&self, name: &str) -> RoverResult<Option<Utf8PathBuf>> {
+        let home = || -> RoverResult<Utf8PathBuf> {
+            let home = directories_next::BaseDirs::new()
+                .ok_or_else(|| anyhow!("could not find your home directory"))?
+                .home_dir()
+                .to_path_buf();
+            Ok(Utf8PathBuf::try_from(home)?)
+        };
+        Ok(match self {
+            ServiceManager::Systemd => Some(
+                home()?
+                    .join(".config/systemd/user")
+                    .join(self.service_id(name)),
+            ),
+            ServiceManager::Launchd => Some(
+                home()?
+                    .join("Library/LaunchAgents")
+                    .join(format!("{}.plist", self.service_id(name))),
+            ),
+            ServiceManager::TaskScheduler => None,
+        })
+    }
+
+    fn is_installed(&self, name: &str) -> RoverResult<bool> {
+        match self.definition_path(name)? {
+            Some(path) => Ok(path.exists()),
+            None => Ok(Command::new("schtasks")
+                .args(["/Query", "/TN", &self.service_id(name)])
+                .output()
+                .map(|output| output.status.success())
+                .unwrap_or(false)),
+        }
+    }
+
+    fn install(&self, service: &ServiceDefinition) -> RoverResult<()> {
+        let service_id = self.service_id(&service.name);
+        match self {
+            ServiceManager::Systemd => {
+                self.write_definition(&service.name, &systemd_unit(service))?;
+                run(Command::new("systemctl").args(["--user", "daemon-reload"]))?;
+                run(Command::new("systemctl").args(["--user", "enable", "--now", &service_id]))
+            }
+            ServiceManager::Launchd => {
+                let path = self.write_definition(&service.name, &launchd_plist(service))?;
+                run(Command::new("launchctl").args(["load", "-w", path.as_str()]))
+            }
+            ServiceManager::TaskScheduler => {
+                run(Command::new("schtasks").args(schtasks_create_args(service)))?;
+                run(Command::new("schtasks").args(["/Run", "/TN", &service_id]))
+            }
+        }
+    }
+
+    fn uninstall(&self, name: &str) -> RoverResult<()> {
+        let service_id = self.service_id(name);
+        match self {
+            ServiceManager::Systemd => {
+                run(Command::new("systemctl").args(["--user", "disable", "--now", &service_id]))?;
+                self.remove_definition(name)?;
+                run(Command::new("systemctl").args(["--user", "daemon-reload"]))
+            }
+            ServiceManager::Launchd => {
+                if let Some(path) = self.definition_path(name)? {
+                    run(Command::new("launchctl").args(["unload", "-w", path.as_str()]))?;
+                }
+                self.remove_definition(name)
+            }
+            ServiceManager::TaskScheduler => {
+                // ending a task that isn't running fails, which is fine
+                let _ = Command::new("schtasks")
+                    .args(["/End", "/TN", &service_id])
+                    .output();
+                run(Command::new("schtasks").args(["/Delete", "/TN", &service_id, "/F"]))
+            }
+        }
+    }
+
+    fn status(&self, name: &str) -> RoverResult<String> {
+        let service_id = self.service_id(name);
+        let output = match self {
+            ServiceManager::Systemd => Command::new("systemctl")
+                .args(["--user", "is-active", &service_id])
+                .output(),
+            ServiceManager::Launchd => Command::new("launchctl")
+                .args(["list", &service_id])
+                .output(),
+            ServiceManager::TaskScheduler => Command::new("schtasks")
+                .args(["/Query", "/TN", &service_id, "/FO", "LIST"])
+                .output(),
+        }
+        .with_context(|| format!("could not query the status of {service_id}"))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(match self {
+            // `is-active` prints the state and exits non-zero for anything but "active"
+            ServiceManager::Systemd => stdout.trim().to_string(),
+            // `list <label>` only succeeds for loaded agents, and includes a PID while running
+            ServiceManager::Launchd if !output.status.success() => "not loaded".to_string(),
+            ServiceManager::Launchd if stdout.contains("\"PID\"") => "running".to_string(),
+            ServiceManager::Launchd => "loaded, not running".to_string(),
+            ServiceManager::TaskScheduler => stdout
+                .lines()
+                .find_map(|line| line.strip_prefix("Status:"))
+                .map(|status| status.trim().to_lowercase())
+                .unwrap_or_else(|| "unknown".to_string()),
+        })
+    }
+
+    fn write_definition(&self, name: &str, contents: &str) -> RoverResult<Utf8PathBuf> {
+        let path = self
+            .definition_path(name)?
+            .ok_or_else(|| anyhow!("this service manager does not use definition files"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("could not create {parent}"))?;
+        }
+        fs::write(&path, contents).with_context(|| format!("could not write {path}"))?;
+        Ok(path)
+    }
+
+    fn remove_definition(&self, name: &str) -> RoverResult<()> {
+        if let Some(path) = self.definition_path(name)? {
+            if path.exists() {
+                fs::remove_file(&path).with_context(|| format!("could not remove {path}"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DevServiceCommand {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let manager = ServiceManager::current()?;
+        let current_dir = Utf8PathBuf::try_from(env::current_dir()?)?;
+        let msg = match self {
+            DevServiceCommand::InstallService(install) => {
+                let project_dir = match &install.project_dir {
+                    Some(project_dir) => current_dir.join(project_dir),
+                    None => current_dir,
+                };
+                let project_dir = Utf8PathBuf::try_from(
+                    project_dir
+                        .canonicalize()
+                        .with_context(|| format!("could not find {project_dir}"))?,
+                )?;
+                let name = install.service_name.resolve(&project_dir)?;
+                if manager.is_installed(&name)? {
+                    if !install.force {
+                        let mut err = RoverError::new(anyhow!(
+                            "a background `rover dev` session named '{}' is already installed",
+                            name
+                        ));
+                        err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                            "Pass `--force` to replace it, or choose another name with `--service-name`.".to_string(),
+                        ));
+                        return Err(err);
+                    }
+                    manager.uninstall(&name)?;
+                }
+                let service = ServiceDefinition {
+                    name: name.clone(),
+                    rover_exe: Utf8PathBuf::try_from(env::current_exe()?)?,
+                    project_dir,
+                    dev_args: install.dev_args.clone(),
+                };
+                manager.install(&service)?;
+                format!(
+                    "Installed {} to run `{}` in {}",
+                    manager.service_id(&name),
+                    service.command().join(" "),
+                    service.project_dir
+                )
+            }
+            DevServiceCommand::UninstallService(service_name) => {
+                let name = service_name.resolve(&current_dir)?;
+                if !manager.is_installed(&name)? {
+                    return Err(RoverError::new(anyhow!(
+                        "there is no background `rover dev` session named '{}'",
+                        name
+                    )));
+                }
+                manager.uninstall(&name)?;
+                format!("Uninstalled {}", manager.service_id(&name))
+            }
+            DevServiceCommand::ServiceStatus(service_name) => {
+                let name = service_name.resolve(&current_dir)?;
+                let status = if manager.is_installed(&name)? {
+                    manager.status(&name)?
+                } else {
+                    "not installed".to_string()
+                };
+                format!("{}: {}", manager.service_id(&name), status)
+            }
+        };
+        Ok(RoverOutput::MessageResponse { msg })
+    }
+}
+
+/// Runs a service manager command, failing when it exits unsuccessfully
+fn run(command: &mut Command) -> RoverResult<()> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let output = command
+        .output()
+        .with_context(|| format!("could not run `{program}`"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(RoverError::new(anyhow!(
+            "`{}` failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Renders a systemd user unit that runs the session, restarting it if it fails
+fn systemd_unit(service: &ServiceDefinition) -> String {
+    // systemd splits `ExecStart` on whitespace, honours double quotes, and expands `%` specifiers
+    let exec_start = service
+        .command()
+        .iter()
+        .map(|arg| {
+            format!(
+                "\"{}\"",
+                arg.replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('%', "%%")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "[Unit]\n\
+         Description=rover dev session for {name}\n\
+         \n\
+         [Service]\n\
+         WorkingDirectory={project_dir}\n\
+         ExecStart={exec_start}\n\
+         Restart=on-failure\n\
+         RestartSec=5\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n",
+        name = service.name,
+        project_dir = service.project_dir,
+    )
+}
+
+/// Renders a launchd agent that runs the session at login, restarting it if it fails
+fn launchd_plist(service: &ServiceDefinition) -> String {
+    let escape = |value: &str| {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let arguments = service
+        .command()
+        .iter()
+        .fold(String::new(), |mut arguments, arg| {
+            let _ = writeln!(arguments, "        <string>{}</string>", escape(arg));
+            arguments
+        });
+    let log_path = service.project_dir.join(".rover-dev.log");
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+{arguments}    </array>
+    <key>WorkingDirectory</key>
+    <string>{project_dir}</string>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
+    <key>StandardOutPath</key>
+    <string>{log_path}</string>
+    <key>StandardErrorPath</key>
+    <string>{log_path}</string>
+</dict>
+</plist>
+"#,
+        label = escape(&ServiceManager::Launchd.service_id(&service.name)),
+        project_dir = escape(service.project_dir.as_str()),
+        log_path = escape(log_path.as_str()),
+    )
+}
+
+/// Builds the `schtasks` arguments that register the session to start at logon
+fn schtasks_create_args(service: &ServiceDefinition) -> Vec<String> {
+    let quote = |value: &str| {
+        if value.contains([' ', '"']) {
+            format!("\"{}\"", value.replace('"', "\\\""))
+        } else {
+            value.to_string()
+        }
+    };
+    let command = service
+        .command()
+        .iter()
+        .map(|arg| quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    // scheduled tasks have no working directory of their own, so change into the project first
+    let task = format!(
+        "cmd /c cd /d {} && {}",
+        quote(service.project_dir.as_str()),
+        command
+    );
+    vec![
+        "/Create".to_string(),
+        "/TN".to_string(),
+        ServiceManager::TaskScheduler.service_id(&service.name),
+        "/TR".to_string(),
+        task,
+        "/SC".to_string(),
+        "ONLOGON".to_string(),
+        "/RL".to_string(),
+        "LIMITED".to_string(),
+        "/F".to_string(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8PathBuf;
+    use rstest::{fixture, rstest};
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    #[fixture]
+    fn service() -> ServiceDefinition {
+        ServiceDefinition {
+            name: "my-project".to_string(),
+            rover_exe: Utf8PathBuf::from("/usr/local/bin/rover"),
+            project_dir: Utf8PathBuf::from("/home/me/my project"),
+            dev_args: vec![
+                "--supergraph-config".to_string(),
+                "supergraph.yaml".to_string(),
+                "--router-config".to_string(),
+                "100%.yaml".to_string(),
+            ],
+        }
+    }
+
+    #[rstest]
+    #[case::from_directory(None, "/home/me/My Project", "my-project")]
+    #[case::explicit(Some("products_v2"), "/home/me/project", "products_v2")]
+    fn test_resolve_service_name(
+        #[case] service_name: Option<&str>,
+        #[case] project_dir: &str,
+        #[case] expected: &str,
+    ) {
+        let opt = ServiceNameOpt {
+            service_name: service_name.map(|name| name.to_string()),
+        };
+        let name = opt.resolve(&Utf8PathBuf::from(project_dir));
+        assert_that!(name).is_ok().is_equal_to(expected.to_string());
+    }
+
+    #[rstest]
+    fn test_resolve_service_name_rejects_empty_names() {
+        let opt = ServiceNameOpt {
+            service_name: Some("../".to_string()),
+        };
+        assert_that!(opt.resolve(&Utf8PathBuf::from("/home/me"))).is_err();
+    }
+
+    #[rstest]
+    fn test_systemd_unit(service: ServiceDefinition) {
+        let unit = systemd_unit(&service);
+        assert_that!(unit).contains("WorkingDirectory=/home/me/my project\n");
+        assert_that!(unit).contains(
+            "ExecStart=\"/usr/local/bin/rover\" \"dev\" \"--supergraph-config\" \"supergraph.yaml\" \"--router-config\" \"100%%.yaml\"\n",
+        );
+        assert_that!(unit).contains("WantedBy=default.target\n");
+    }
+
+    #[rstest]
+    fn test_launchd_plist(service: ServiceDefinition) {
+        let plist = launchd_plist(&service);
+        assert_that!(plist).contains("<string>com.apollographql.rover.dev.my-project</string>");
+        assert_that!(plist).contains(
+            "        <string>/usr/local/bin/rover</string>\n        <string>dev</string>\n        <string>--supergraph-config</string>\n",
+        );
+        assert_that!(plist).contains("<string>/home/me/my project/.rover-dev.log</string>");
+    }
+
+    #[rstest]
+    fn test_schtasks_create_args(service: ServiceDefinition) {
+        let args = schtasks_create_args(&service);
+        assert_that!(args).contains("rover-dev-my-project".to_string());
+        assert_that!(args).contains(
+            "cmd /c cd /d \"/home/me/my project\" && /usr/local/bin/rover dev --supergraph-config supergraph.yaml --router-config 100%.yaml".to_string(),
+        );
+    }
+}
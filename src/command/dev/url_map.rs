@@ -0,0 +1,197 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{anyhow, bail};
+
+/// A rule that rewrites the routing URLs of subgraphs, written as `PATTERN=>REPLACEMENT`.
+///
+/// Every `*` in the pattern matches any part of the URL up to the next `/`, and the pattern only
+/// needs to match the start of the URL, as long as it ends where the path or query begins. The
+/// rest of the URL is appended to the replacement. In the replacement, `{1}`, `{2}`, and so on
+/// stand for what each `*` matched, and any other `{NAME}` stands for the value of the environment
+/// variable `NAME` when `rover dev` starts.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UrlMapRule {
+    pattern: String,
+    replacement: String,
+}
+
+impl UrlMapRule {
+    fn parse(rule: &str, env_var: impl Fn(&str) -> Option<String>) -> anyhow::Result<Self> {
+        let (pattern, replacement) = rule
+            .split_once("=>")
+            .ok_or_else(|| anyhow!("expected a rule formatted as `PATTERN=>REPLACEMENT`"))?;
+        let (pattern, replacement) = (pattern.trim(), replacement.trim());
+        if pattern.is_empty() || replacement.is_empty() {
+            bail!("neither the pattern nor the replacement of a rule can be empty");
+        }
+
+        let wildcards = pattern.matches('*').count();
+        let mut resolved = String::with_capacity(replacement.len());
+        let mut rest = replacement;
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..]
+                .find('}')
+                .map(|end| start + end)
+                .ok_or_else(|| anyhow!("`{{` in `{}` is never closed", replacement))?;
+            let placeholder = &rest[start + 1..end];
+            resolved.push_str(&rest[..start]);
+            match placeholder.parse::<usize>() {
+                Ok(index) if (1..=wildcards).contains(&index) => {
+                    resolved.push_str(&rest[start..=end])
+                }
+                Ok(index) => bail!(
+                    "`{{{}}}` doesn't refer to a `*` in `{}`, which has {}",
+                    index,
+                    pattern,
+                    wildcards
+                ),
+                Err(_) => resolved.push_str(&env_var(placeholder).ok_or_else(|| {
+                    anyhow!("the environment variable `{}` is not set", placeholder)
+                })?),
+            }
+            rest = &rest[end + 1..];
+        }
+        resolved.push_str(rest);
+
+        Ok(UrlMapRule {
+            pattern: pattern.to_string(),
+            replacement: resolved,
+        })
+    }
+
+    /// Rewrites `url` if this rule's pattern matches it
+    pub fn apply(&self, url: &str) -> Option<String> {
+        let segments: Vec<&str> = self.pattern.split('*').collect();
+        let mut captures = Vec::new();
+        let rest = match_segments(&segments, url, &mut captures)?;
+
+        let mut rewritten = self.replacement.clone();
+        for (index, capture) in captures.iter().enumerate() {
+            rewritten = rewritten.replace(&format!("{{{}}}", index + 1), capture);
+        }
+        rewritten.push_str(rest);
+        Some(rewritten)
+    }
+}
+
+/// Rewrites `url` with the first of `rules` that matches it, leaving it as-is if none do
+pub fn rewrite_url(rules: &[UrlMapRule], url: &str) -> String {
+    match rules.iter().find_map(|rule| rule.apply(url)) {
+        Some(rewritten) => {
+            tracing::debug!("rewrote routing URL {} to {}", url, rewritten);
+            rewritten
+        }
+        None => url.to_string(),
+    }
+}
+
+/// Matches `url` against the literal `segments` of a pattern, with a wildcard between each of
+/// them, returning the part of `url` after the match
+fn match_segments<'a>(
+    segments: &[&str],
+    url: &'a str,
+    captures: &mut Vec<&'a str>,
+) -> Option<&'a str> {
+    let (literal, segments) = segments.split_first()?;
+    let url = url.strip_prefix(literal)?;
+    if segments.is_empty() {
+        return (url.is_empty() || url.starts_with(['/', '?'])).then_some(url);
+    }
+
+    let wildcard_end = url.find('/').unwrap_or(url.len());
+    let candidates = url[..wildcard_end]
+        .char_indices()
+        .map(|(index, _)| index)
+        .chain(std::iter::once(wildcard_end));
+    for end in candidates {
+        captures.push(&url[..end]);
+        if let Some(rest) = match_segments(segments, &url[end..], captures) {
+            return Some(rest);
+        }
+        captures.pop();
+    }
+    None
+}
+
+impl FromStr for UrlMapRule {
+    type Err = anyhow::Error;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        Self::parse(rule, |key| std::env::var(key).ok())
+    }
+}
+
+impl fmt::Display for UrlMapRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}=>{}", self.pattern, self.replacement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    fn rule(rule: &str) -> UrlMapRule {
+        UrlMapRule::parse(rule, |key| {
+            (key == "PRODUCTS_PORT").then(|| "4001".to_string())
+        })
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case::keeps_path(
+        "https://*.internal.example.com=>http://localhost:{PRODUCTS_PORT}",
+        "https://products.internal.example.com/graphql",
+        Some("http://localhost:4001/graphql")
+    )]
+    #[case::captures(
+        "https://*.internal.example.com=>http://{1}.localhost",
+        "https://products.internal.example.com",
+        Some("http://products.localhost")
+    )]
+    #[case::requires_boundary(
+        "https://*.internal.example.com=>http://localhost:4001",
+        "https://products.internal.example.com.evil.com/graphql",
+        None
+    )]
+    #[case::wildcard_stops_at_path(
+        "https://*/graphql=>http://localhost:4001/graphql",
+        "https://products.example.com/v2/graphql",
+        None
+    )]
+    #[case::no_match(
+        "https://*.internal.example.com=>http://localhost:4001",
+        "http://localhost:4002/graphql",
+        None
+    )]
+    fn test_apply(#[case] rule_str: &str, #[case] url: &str, #[case] expected: Option<&str>) {
+        assert_that!(rule(rule_str).apply(url)).is_equal_to(expected.map(String::from));
+    }
+
+    #[rstest]
+    #[case::missing_arrow("https://example.com")]
+    #[case::empty_replacement("https://example.com=>")]
+    #[case::unset_env_var("https://*.example.com=>http://localhost:{MISSING_PORT}")]
+    #[case::unknown_capture("https://*.example.com=>http://{2}.localhost")]
+    #[case::unclosed_placeholder("https://*.example.com=>http://{1.localhost")]
+    fn test_parse_rejects_invalid_rules(#[case] rule_str: &str) {
+        assert_that!(UrlMapRule::parse(rule_str, |_| None)).is_err();
+    }
+
+    #[test]
+    fn test_rewrite_url_uses_first_matching_rule() {
+        let rules = vec![
+            rule("https://products.example.com=>http://localhost:4001"),
+            rule("https://*.example.com=>http://localhost:4000"),
+        ];
+        assert_that!(rewrite_url(&rules, "https://products.example.com/graphql"))
+            .is_equal_to("http://localhost:4001/graphql".to_string());
+        assert_that!(rewrite_url(&rules, "https://reviews.example.com/graphql"))
+            .is_equal_to("http://localhost:4000/graphql".to_string());
+        assert_that!(rewrite_url(&rules, "http://localhost:4002/graphql"))
+            .is_equal_to("http://localhost:4002/graphql".to_string());
+    }
+}
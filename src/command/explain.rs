@@ -5,14 +5,17 @@ use serde::Serialize;
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Explain {
-    /// The full error code. For example, E020
+    /// The full error code, or its name. For example, E020 or E020_NO_CONFIG_PROFILES.
+    /// Leave it out to list every error code
     #[arg(value_name = "CODE")]
-    code: RoverErrorCode,
+    code: Option<RoverErrorCode>,
 }
 
 impl Explain {
     pub fn run(&self) -> RoverResult<RoverOutput> {
-        let explanation = &self.code.explain();
-        Ok(RoverOutput::ErrorExplanation(explanation.clone()))
+        match &self.code {
+            Some(code) => Ok(RoverOutput::ErrorExplanation(code.explain())),
+            None => Ok(RoverOutput::ErrorCatalog(RoverErrorCode::all())),
+        }
     }
 }
@@ -0,0 +1,338 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::Parser;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use rover_client::operations::graph::changelog::{
+    self, ChangelogSince, GraphChangelogInput, LaunchPublication,
+};
+use rover_client::shared::GraphRef;
+use rover_std::{progressln, Style};
+
+use crate::options::{GraphRefOpt, ProfileOpt};
+use crate::schema_diff::{ChangeSeverity, SchemaChange, SchemaDiff};
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+/// The heading for changes published to a graph without subgraphs
+const GRAPH_HEADING: &str = "Schema";
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Changelog {
+    #[clap(flatten)]
+    graph: GraphRefOpt,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// Where the changelog starts: the ID of the launch that published the schema to compare
+    /// against (the shortened ID works too), or a date (`2024-05-01`) or RFC 3339 timestamp to
+    /// compare against the schema that was current at that time
+    #[arg(long = "since", value_name = "LAUNCH_ID|DATE", value_parser = parse_since)]
+    #[serde(skip_serializing)]
+    since: ChangelogSince,
+
+    /// The most launches to walk back through looking for `--since`
+    #[arg(long = "limit", default_value_t = 100)]
+    limit: usize,
+}
+
+impl Changelog {
+    pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        progressln!(
+            "Building the changelog of {} using credentials from the {} profile.",
+            Style::Link.paint(self.graph.graph_ref.to_string()),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        let history = changelog::run(
+            GraphChangelogInput {
+                graph_ref: self.graph.graph_ref.clone(),
+                since: self.since.clone(),
+                limit: self.limit,
+            },
+            &client,
+        )
+        .await?;
+        Ok(RoverOutput::GraphChangelog(GraphChangelog::new(
+            history.graph_ref,
+            &history.publications,
+        )?))
+    }
+}
+
+fn parse_since(since: &str) -> Result<ChangelogSince, String> {
+    if let Ok(timestamp) = since.parse::<DateTime<Utc>>() {
+        return Ok(ChangelogSince::Date(timestamp));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+        return Ok(ChangelogSince::Date(
+            date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc(),
+        ));
+    }
+    if since.is_empty() {
+        return Err("expected a launch ID or a date".to_string());
+    }
+    Ok(ChangelogSince::Launch(since.to_string()))
+}
+
+/// A change to the schema of a variant, and the launch that published it
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct ChangelogChange {
+    pub severity: ChangeSeverity,
+    #[serde(flatten)]
+    pub change: SchemaChange,
+    pub launch_id: String,
+    pub published_at: DateTime<Utc>,
+}
+
+/// The changes between consecutive publishes of a variant, worked out by `rover graph changelog`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GraphChangelog {
+    pub graph_ref: GraphRef,
+    /// The launch whose schema the changelog starts from
+    pub base_launch_id: Option<String>,
+    /// Changes grouped by the subgraphs whose publishes launched them, then by severity. Changes
+    /// from launches of several subgraphs at once are grouped under all of their names
+    pub changes: BTreeMap<String, BTreeMap<ChangeSeverity, Vec<ChangelogChange>>>,
+}
+
+impl GraphChangelog {
+    /// Diffs each publication against the one before it. `publications` are oldest first, and the
+    /// first one is only compared against
+    pub fn new(graph_ref: GraphRef, publications: &[LaunchPublication]) -> RoverResult<Self> {
+        let mut changes: BTreeMap<String, BTreeMap<ChangeSeverity, Vec<ChangelogChange>>> =
+            BTreeMap::new();
+        for pair in publications.windows(2) {
+            let (previous, current) = (&pair[0], &pair[1]);
+            let diff = SchemaDiff::new(&previous.schema, &current.schema).map_err(|err| {
+                let mut err = RoverError::new(anyhow!(
+                    "Could not compare the schemas published by launches {} and {}: {}",
+                    previous.launch_id,
+                    current.launch_id,
+                    err
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Pass a later `--since` to skip this publish.".to_string(),
+                ));
+                err
+            })?;
+            let heading = if current.changed_subgraphs.is_empty() {
+                GRAPH_HEADING.to_string()
+            } else {
+                current.changed_subgraphs.join(", ")
+            };
+            for change in diff.changes {
+                changes
+                    .entry(heading.clone())
+                    .or_default()
+                    .entry(change.kind.severity())
+                    .or_default()
+                    .push(ChangelogChange {
+                        severity: change.kind.severity(),
+                        change,
+                        launch_id: current.launch_id.clone(),
+                        published_at: current.published_at,
+                    });
+            }
+        }
+        // newest first, the way release notes read
+        for by_launch in changes
+            .values_mut()
+            .flat_map(|by_severity| by_severity.values_mut())
+        {
+            by_launch.sort_by(|a, b| b.published_at.cmp(&a.published_at));
+        }
+        Ok(Self {
+            graph_ref,
+            base_launch_id: publications
+                .first()
+                .map(|publication| publication.launch_id.clone()),
+            changes,
+        })
+    }
+
+    /// Renders the changelog as Markdown release notes
+    pub fn get_output(&self) -> String {
+        let mut output = format!("# Schema changelog for {}\n", self.graph_ref);
+        if let Some(base_launch_id) = &self.base_launch_id {
+            output.push_str(&format!("\nChanges since launch `{}`.\n", base_launch_id));
+        }
+        if self.changes.is_empty() {
+            output.push_str("\nNo changes were published to the API schema.\n");
+            return output;
+        }
+        for (heading, by_severity) in &self.changes {
+            output.push_str(&format!("\n## {}\n", heading));
+            for (severity, changes) in by_severity {
+                output.push_str(&format!("\n### {}\n\n", severity));
+                for ChangelogChange {
+                    change,
+                    launch_id,
+                    published_at,
+                    ..
+                } in changes
+                {
+                    output.push_str(&format!("- `{}` {}", change.coordinate, change.kind));
+                    if let Some(detail) = &change.detail {
+                        output.push_str(&format!(" ({})", detail));
+                    }
+                    output.push_str(&format!(
+                        " — launch `{}`, {}\n",
+                        launch_id,
+                        published_at.format("%Y-%m-%d")
+                    ));
+                }
+            }
+        }
+        output
+    }
+
+    pub fn get_json(&self) -> Value {
+        json!({
+            "graph_ref": self.graph_ref.to_string(),
+            "base_launch_id": self.base_launch_id,
+            "subgraphs": self.changes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::*;
+    use crate::schema_diff::ChangeKind;
+
+    fn graph_ref() -> GraphRef {
+        GraphRef {
+            name: "mygraph".to_string(),
+            variant: "prod".to_string(),
+        }
+    }
+
+    fn publication(
+        launch_id: &str,
+        day: u32,
+        subgraphs: &[&str],
+        schema: &str,
+    ) -> LaunchPublication {
+        LaunchPublication {
+            launch_id: launch_id.to_string(),
+            published_at: NaiveDate::from_ymd_opt(2024, 5, day)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc(),
+            schema: schema.to_string(),
+            changed_subgraphs: subgraphs.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    #[rstest]
+    #[case::launch_id("a1b2c3", ChangelogSince::Launch("a1b2c3".to_string()))]
+    #[case::date("2024-05-01", ChangelogSince::Date("2024-05-01T00:00:00Z".parse().unwrap()))]
+    #[case::timestamp(
+        "2024-05-01T12:30:00+02:00",
+        ChangelogSince::Date("2024-05-01T10:30:00Z".parse().unwrap())
+    )]
+    fn test_parse_since(#[case] input: &str, #[case] expected: ChangelogSince) {
+        assert_that!(parse_since(input)).is_ok_containing(expected);
+    }
+
+    #[test]
+    fn test_changes_are_grouped_by_subgraph_and_severity() {
+        let publications = vec![
+            publication("base", 1, &["products"], "type Query { a: ID b: ID }"),
+            publication("second", 2, &["products"], "type Query { a: ID c: ID }"),
+            publication(
+                "third",
+                3,
+                &["reviews", "users"],
+                "type Query { a: String c: ID d: ID }",
+            ),
+        ];
+        let changelog = GraphChangelog::new(graph_ref(), &publications).unwrap();
+
+        assert_that!(changelog.base_launch_id).is_equal_to(Some("base".to_string()));
+        let summary: Vec<(&str, ChangeSeverity, ChangeKind, &str, &str)> = changelog
+            .changes
+            .iter()
+            .flat_map(|(heading, by_severity)| {
+                by_severity.iter().flat_map(move |(severity, changes)| {
+                    changes.iter().map(move |change| {
+                        (
+                            heading.as_str(),
+                            *severity,
+                            change.change.kind,
+                            change.change.coordinate.as_str(),
+                            change.launch_id.as_str(),
+                        )
+                    })
+                })
+            })
+            .collect();
+        assert_that!(summary).is_equal_to(vec![
+            (
+                "products",
+                ChangeSeverity::Breaking,
+                ChangeKind::FieldRemoved,
+                "Query.b",
+                "second",
+            ),
+            (
+                "products",
+                ChangeSeverity::Safe,
+                ChangeKind::FieldAdded,
+                "Query.c",
+                "second",
+            ),
+            (
+                "reviews, users",
+                ChangeSeverity::Breaking,
+                ChangeKind::FieldTypeChanged,
+                "Query.a",
+                "third",
+            ),
+            (
+                "reviews, users",
+                ChangeSeverity::Safe,
+                ChangeKind::FieldAdded,
+                "Query.d",
+                "third",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_markdown_output() {
+        let publications = vec![
+            publication("base", 1, &[], "type Query { a: ID }"),
+            publication("next", 2, &[], "type Query { a: ID b: ID }"),
+        ];
+        let output = GraphChangelog::new(graph_ref(), &publications)
+            .unwrap()
+            .get_output();
+        assert_that!(output).contains("## Schema\n\n### Safe\n\n");
+        assert_that!(output).contains("- `Query.b` FIELD_ADDED — launch `next`, 2024-05-02\n");
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let publications = vec![publication(
+            "base",
+            1,
+            &["products"],
+            "type Query { a: ID }",
+        )];
+        let output = GraphChangelog::new(graph_ref(), &publications)
+            .unwrap()
+            .get_output();
+        assert_that!(output).contains("No changes were published to the API schema.");
+    }
+}
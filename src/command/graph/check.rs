@@ -1,5 +1,5 @@
 use clap::Parser;
-use rover_std::Style;
+use rover_std::{progressln, Style};
 use serde::Serialize;
 
 use rover_client::operations::graph::{
@@ -7,8 +7,9 @@ use rover_client::operations::graph::{
     check_workflow::{self, CheckWorkflowInput},
 };
 use rover_client::shared::{CheckConfig, GitContext};
+use rover_client::RoverClientError;
 
-use crate::options::{CheckConfigOpts, GraphRefOpt, ProfileOpt, SchemaOpt};
+use crate::options::{CheckConfigOpts, GraphRefOpt, PolicyOpts, ProfileOpt, SchemaOpt};
 use crate::utils::client::StudioClientConfig;
 use crate::{RoverOutput, RoverResult};
 
@@ -26,6 +27,9 @@ pub struct Check {
 
     #[clap(flatten)]
     config: CheckConfigOpts,
+
+    #[clap(flatten)]
+    policies: PolicyOpts,
 }
 
 impl Check {
@@ -36,11 +40,15 @@ impl Check {
         checks_timeout_seconds: u64,
     ) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
-        let proposed_schema = self
+        let file_with_metadata = self
             .schema
-            .read_file_descriptor("SDL", &mut std::io::stdin())?;
+            .read_file_descriptor_with_metadata("SDL", &mut std::io::stdin())?;
+        if let Some(policies) = self.policies.load()? {
+            policies.check(&file_with_metadata.file_path, &file_with_metadata.schema)?;
+        }
+        let proposed_schema = file_with_metadata.schema;
 
-        eprintln!(
+        progressln!(
             "Checking the proposed schema against {}",
             Style::Link.paint(self.graph.graph_ref.to_string())
         );
@@ -70,6 +78,15 @@ impl Check {
                 &client,
             )
             .await?;
+
+            if self.config.fail_on_downstream && check_res.has_failed_downstream_variants() {
+                return Err(RoverClientError::CheckWorkflowFailure {
+                    graph_ref: self.graph.graph_ref.clone(),
+                    check_response: Box::new(check_res),
+                }
+                .into());
+            }
+
             Ok(RoverOutput::CheckWorkflowResponse(check_res))
         }
     }
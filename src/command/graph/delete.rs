@@ -2,7 +2,7 @@ use clap::Parser;
 use serde::Serialize;
 
 use rover_client::operations::graph::delete::{self, GraphDeleteInput};
-use rover_std::{prompt, Style};
+use rover_std::{progressln, prompt, Style};
 
 use crate::options::{GraphRefOpt, ProfileOpt};
 use crate::utils::client::StudioClientConfig;
@@ -27,7 +27,7 @@ impl Delete {
         let client = client_config.get_authenticated_client(&self.profile)?;
         let graph_ref = self.graph.graph_ref.to_string();
 
-        eprintln!(
+        progressln!(
             "Deleting {} using credentials from the {} profile.",
             Style::Link.paint(&graph_ref),
             Style::Command.paint(&self.profile.profile_name)
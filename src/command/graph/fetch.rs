@@ -1,10 +1,13 @@
+use chrono::Duration;
 use clap::Parser;
 use serde::Serialize;
 
 use rover_client::operations::graph::fetch::{self, GraphFetchInput};
-use rover_std::Style;
+use rover_client::operations::supergraph::fetch::{self as supergraph_fetch, SupergraphFetchInput};
+use rover_client::shared::{FetchResponse, Sdl, SdlType};
+use rover_std::{progressln, Style};
 
-use crate::options::{GraphRefOpt, ProfileOpt};
+use crate::options::{GraphRefOpt, ProfileOpt, SchemaFormat, SchemaKind};
 use crate::utils::client::StudioClientConfig;
 use crate::{RoverOutput, RoverResult};
 
@@ -15,26 +18,80 @@ pub struct Fetch {
 
     #[clap(flatten)]
     profile: ProfileOpt,
+
+    /// Which schema to fetch. `api` fetches the schema clients query, while `supergraph` fetches
+    /// the full composed document, which is what the router runs
+    #[arg(long = "schema-kind", value_enum, default_value_t = SchemaKind::Api)]
+    schema_kind: SchemaKind,
+
+    /// Which format to print the schema in
+    #[arg(long = "schema-format", value_enum, default_value_t = SchemaFormat::Sdl)]
+    schema_format: SchemaFormat,
 }
 
 impl Fetch {
     pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let schema_kind = match self.schema_kind {
+            SchemaKind::Api => "api",
+            SchemaKind::Supergraph => "supergraph",
+        };
+        let contents = client_config
+            .response_cache()
+            .fetch(
+                "graph-fetch",
+                &[
+                    &self.profile.profile_name,
+                    &self.graph.graph_ref.to_string(),
+                    schema_kind,
+                ],
+                Duration::days(7),
+                self.fetch_sdl(&client_config),
+            )
+            .await?;
+        let r#type = match self.schema_kind {
+            SchemaKind::Api => SdlType::Graph,
+            SchemaKind::Supergraph => SdlType::Supergraph,
+        };
+
+        self.schema_format.output(FetchResponse {
+            sdl: Sdl { contents, r#type },
+        })
+    }
+
+    async fn fetch_sdl(&self, client_config: &StudioClientConfig) -> RoverResult<String> {
         let client = client_config.get_authenticated_client(&self.profile)?;
         let graph_ref = self.graph.graph_ref.to_string();
-        eprintln!(
-            "Fetching SDL from {} using credentials from the {} profile.",
-            Style::Link.paint(graph_ref),
-            Style::Command.paint(&self.profile.profile_name)
-        );
-
-        let fetch_response = fetch::run(
-            GraphFetchInput {
-                graph_ref: self.graph.graph_ref.clone(),
-            },
-            &client,
-        )
-        .await?;
-
-        Ok(RoverOutput::FetchResponse(fetch_response))
+        let fetch_response = match self.schema_kind {
+            SchemaKind::Api => {
+                progressln!(
+                    "Fetching SDL from {} using credentials from the {} profile.",
+                    Style::Link.paint(graph_ref),
+                    Style::Command.paint(&self.profile.profile_name)
+                );
+                fetch::run(
+                    GraphFetchInput {
+                        graph_ref: self.graph.graph_ref.clone(),
+                    },
+                    &client,
+                )
+                .await?
+            }
+            SchemaKind::Supergraph => {
+                progressln!(
+                    "Fetching supergraph SDL from {} using credentials from the {} profile.",
+                    Style::Link.paint(graph_ref),
+                    Style::Command.paint(&self.profile.profile_name)
+                );
+                supergraph_fetch::run(
+                    SupergraphFetchInput {
+                        graph_ref: self.graph.graph_ref.clone(),
+                    },
+                    &client,
+                )
+                .await?
+            }
+        };
+
+        Ok(fetch_response.sdl.contents)
     }
 }
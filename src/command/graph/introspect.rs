@@ -1,7 +1,9 @@
 use clap::Parser;
 use reqwest::Client;
 use serde::Serialize;
+use serde_json::Value;
 use std::{collections::HashMap, time::Duration};
+use url::Url;
 
 use rover_client::{
     blocking::GraphQLClient,
@@ -10,13 +12,27 @@ use rover_client::{
 
 use crate::{
     options::{IntrospectOpts, OutputOpts},
-    RoverOutput, RoverResult,
+    schema_diff::SchemaDiff,
+    RoverError, RoverOutput, RoverResult, StructuredFailure,
 };
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Introspect {
     #[clap(flatten)]
     pub opts: IntrospectOpts,
+
+    /// Another endpoint serving the same graph, such as the other half of a blue/green
+    /// deployment, to introspect with the same headers and compare against the first.
+    ///
+    /// Rover prints the differences between the two schemas and exits with an error if there are
+    /// any, so this can be used to gate a deploy on every replica serving the same schema.
+    #[arg(
+        long = "compare-endpoint",
+        value_name = "URL",
+        conflicts_with = "watch"
+    )]
+    #[serde(skip_serializing)]
+    pub compare_endpoint: Option<Url>,
 }
 
 impl Introspect {
@@ -29,6 +45,12 @@ impl Introspect {
         if self.opts.watch {
             self.exec_and_watch(&client, output_opts, retry_period)
                 .await
+        } else if let Some(compare_endpoint) = &self.compare_endpoint {
+            let (sdl, compare_sdl) = tokio::try_join!(
+                self.introspect(&self.opts.endpoint, &client, true, retry_period),
+                self.introspect(compare_endpoint, &client, true, retry_period),
+            )?;
+            compare_schemas(&self.opts.endpoint, compare_endpoint, &sdl, &compare_sdl)
         } else {
             let sdl = self.exec(&client, true, retry_period).await?;
             Ok(RoverOutput::Introspection(sdl))
@@ -41,7 +63,18 @@ impl Introspect {
         should_retry: bool,
         retry_period: Option<Duration>,
     ) -> RoverResult<String> {
-        let client = GraphQLClient::new(self.opts.endpoint.as_ref(), client.clone(), retry_period);
+        self.introspect(&self.opts.endpoint, client, should_retry, retry_period)
+            .await
+    }
+
+    async fn introspect(
+        &self,
+        endpoint: &Url,
+        client: &Client,
+        should_retry: bool,
+        retry_period: Option<Duration>,
+    ) -> RoverResult<String> {
+        let client = GraphQLClient::new(endpoint.as_ref(), client.clone(), retry_period);
 
         // add the flag headers to a hashmap to pass along to rover-client
         let mut headers = HashMap::new();
@@ -70,3 +103,86 @@ impl Introspect {
             .await
     }
 }
+
+/// Diffs the schemas introspected from two endpoints, failing if they aren't the same
+fn compare_schemas(
+    endpoint: &Url,
+    compare_endpoint: &Url,
+    sdl: &str,
+    compare_sdl: &str,
+) -> RoverResult<RoverOutput> {
+    let diff = SchemaDiff::new(sdl, compare_sdl)?;
+    if diff.is_empty() {
+        Ok(RoverOutput::SupergraphDiff(diff))
+    } else {
+        Err(RoverError::from_failure(IntrospectionMismatch {
+            endpoint: endpoint.clone(),
+            compare_endpoint: compare_endpoint.clone(),
+            diff,
+        }))
+    }
+}
+
+/// Returned by `rover graph introspect --compare-endpoint` when the two endpoints serve different
+/// schemas
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "The schemas served by {endpoint} and {compare_endpoint} differ by {}.",
+    match .diff.changes.len() {
+        1 => "1 change".to_string(),
+        n => format!("{} changes", n),
+    }
+)]
+pub struct IntrospectionMismatch {
+    pub endpoint: Url,
+    pub compare_endpoint: Url,
+    pub diff: SchemaDiff,
+}
+
+impl StructuredFailure for IntrospectionMismatch {
+    fn get_output(&self) -> String {
+        self.diff.get_output()
+    }
+
+    fn get_json(&self) -> Value {
+        self.diff.get_json()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use speculoos::prelude::*;
+    use url::Url;
+
+    use super::compare_schemas;
+    use crate::RoverOutput;
+
+    const BLUE: &str = "type Query { products: [Product] }\ntype Product { id: ID! name: String }";
+
+    fn endpoints() -> (Url, Url) {
+        (
+            Url::parse("http://blue.internal/graphql").unwrap(),
+            Url::parse("http://green.internal/graphql").unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_compare_schemas_ignores_ordering() {
+        let (blue, green) = endpoints();
+        let green_sdl = "type Product { name: String id: ID! }\ntype Query { products: [Product] }";
+        let result = compare_schemas(&blue, &green, BLUE, green_sdl);
+        assert!(matches!(result, Ok(RoverOutput::SupergraphDiff(diff)) if diff.is_empty()));
+    }
+
+    #[test]
+    fn test_compare_schemas_reports_skew() {
+        let (blue, green) = endpoints();
+        let green_sdl = "type Query { products: [Product] }\ntype Product { id: ID! }";
+        let err = compare_schemas(&blue, &green, BLUE, green_sdl).unwrap_err();
+        assert_that!(err.to_string()).contains(
+            "The schemas served by http://blue.internal/graphql and http://green.internal/graphql differ by 1 change.",
+        );
+        assert_that!(err.get_internal_data_json()["changes"][0]["coordinate"])
+            .is_equal_to(serde_json::json!("Product.name"));
+    }
+}
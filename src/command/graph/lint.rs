@@ -25,13 +25,14 @@ pub struct Lint {
 
 impl Lint {
     pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let policies = self.lint.policies.load()?;
         let client = client_config.get_authenticated_client(&self.profile)?;
 
         let file_with_metadata = self
             .schema
             .read_file_descriptor_with_metadata("SDL", &mut std::io::stdin())?;
 
-        let lint_result: rover_client::shared::LintResponse = lint::run(
+        let lint_result = lint::run(
             LintGraphInput {
                 graph_ref: self.graph.graph_ref.clone(),
                 file_name: file_with_metadata.file_path,
@@ -40,7 +41,11 @@ impl Lint {
             },
             &client,
         )
-        .await?;
+        .await;
+        let lint_result = match policies {
+            Some(policies) => policies.lint(lint_result)?,
+            None => lint_result?,
+        };
 
         Ok(RoverOutput::LintResponse(lint_result))
     }
@@ -1,3 +1,4 @@
+mod changelog;
 mod check;
 mod delete;
 mod fetch;
@@ -5,8 +6,8 @@ mod introspect;
 mod lint;
 mod publish;
 
+pub use changelog::GraphChangelog;
 use clap::Parser;
-#[cfg(not(feature = "dev-next"))]
 pub use introspect::Introspect;
 use serde::Serialize;
 
@@ -24,6 +25,9 @@ pub struct Graph {
 
 #[derive(Debug, Serialize, Parser)]
 pub enum Command {
+    /// Generate release notes from the schema changes published to a graph variant
+    Changelog(changelog::Changelog),
+
     /// Check for breaking changes in a local graph schema
     /// against a graph schema in the Apollo graph registry
     Check(check::Check),
@@ -45,6 +49,11 @@ pub enum Command {
 }
 
 impl Graph {
+    /// Whether this is `graph check`, the output of which `--format junit` reports
+    pub(crate) fn is_check(&self) -> bool {
+        matches!(self.command, Command::Check(_))
+    }
+
     pub async fn run(
         &self,
         client_config: StudioClientConfig,
@@ -53,6 +62,7 @@ impl Graph {
         output_opts: &OutputOpts,
     ) -> RoverResult<RoverOutput> {
         match &self.command {
+            Command::Changelog(command) => command.run(client_config).await,
             Command::Check(command) => {
                 command
                     .run(client_config, git_context, checks_timeout_seconds)
@@ -3,7 +3,7 @@ use serde::Serialize;
 
 use rover_client::operations::graph::publish::{self, GraphPublishInput};
 use rover_client::shared::GitContext;
-use rover_std::Style;
+use rover_std::{progressln, Style};
 
 use crate::options::{GraphRefOpt, ProfileOpt, SchemaOpt};
 use crate::utils::client::StudioClientConfig;
@@ -30,7 +30,7 @@ impl Publish {
     ) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
         let graph_ref = self.graph.graph_ref.to_string();
-        eprintln!(
+        progressln!(
             "Publishing SDL to {} using credentials from the {} profile.",
             Style::Link.paint(graph_ref),
             Style::Command.paint(&self.profile.profile_name)
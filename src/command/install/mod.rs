@@ -14,8 +14,10 @@ use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult, PKG_NAME
 use std::convert::TryFrom;
 use std::env;
 
+mod pin;
 mod plugin;
-pub(crate) use plugin::{Plugin, PluginInstaller};
+pub(crate) use pin::{find_project_config, write_pin, PluginPins, PROJECT_CONFIG_PATH};
+pub(crate) use plugin::{list_installed_plugins, InstalledPlugin, Plugin, PluginInstaller};
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Install {
@@ -124,16 +126,24 @@ impl Install {
         binary_name: String,
         override_install_path: Option<Utf8PathBuf>,
     ) -> RoverResult<Installer> {
-        if let Ok(executable_location) = env::current_exe() {
-            let executable_location = Utf8PathBuf::try_from(executable_location)?;
-            Ok(Installer {
-                binary_name,
-                force_install: self.force,
-                override_install_path,
-                executable_location,
-            })
-        } else {
-            Err(anyhow!("Failed to get the current executable's path.").into())
-        }
+        get_installer(binary_name, self.force, override_install_path)
+    }
+}
+
+pub(crate) fn get_installer(
+    binary_name: String,
+    force_install: bool,
+    override_install_path: Option<Utf8PathBuf>,
+) -> RoverResult<Installer> {
+    if let Ok(executable_location) = env::current_exe() {
+        let executable_location = Utf8PathBuf::try_from(executable_location)?;
+        Ok(Installer {
+            binary_name,
+            force_install,
+            override_install_path,
+            executable_location,
+        })
+    } else {
+        Err(anyhow!("Failed to get the current executable's path.").into())
     }
 }
@@ -0,0 +1,244 @@
+use std::env;
+
+use anyhow::anyhow;
+use apollo_federation_types::config::{FederationVersion, RouterVersion};
+use camino::{Utf8Path, Utf8PathBuf};
+use semver::Version;
+use serde_yaml::{Mapping, Value};
+
+use rover_std::Fs;
+
+use super::Plugin;
+use crate::utils::project::update_project_config;
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+/// Where a project keeps its Rover configuration, relative to the project's root
+pub(crate) const PROJECT_CONFIG_PATH: &str = ".apollo/config.yaml";
+
+/// The plugin versions a project has pinned under the `plugins` key of its
+/// `.apollo/config.yaml`, which look like this:
+///
+/// ```yaml
+/// plugins:
+///   supergraph: 2.9.3
+///   router: 1.57.1
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct PluginPins {
+    config_path: Option<Utf8PathBuf>,
+    supergraph: Option<Version>,
+    router: Option<Version>,
+}
+
+impl PluginPins {
+    /// Loads the pins of the project the current directory is in, if any
+    pub(crate) fn for_current_dir() -> RoverResult<Self> {
+        let current_dir = Utf8PathBuf::try_from(env::current_dir()?)?;
+        match find_project_config(&current_dir) {
+            Some(config_path) => Self::load(&config_path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub(crate) fn load(config_path: &Utf8Path) -> RoverResult<Self> {
+        let contents = Fs::read_file(config_path)?;
+        let config: Value = serde_yaml::from_str(&contents)
+            .map_err(|e| RoverError::new(anyhow!("Could not parse {}: {}", config_path, e)))?;
+        let pinned = |plugin_name: &str| -> RoverResult<Option<Version>> {
+            match config
+                .get("plugins")
+                .and_then(|plugins| plugins.get(plugin_name))
+            {
+                None | Some(Value::Null) => Ok(None),
+                Some(Value::String(version)) => parse_pinned_version(version)
+                    .map(Some)
+                    .ok_or_else(|| invalid_pin(config_path, plugin_name, version)),
+                Some(other) => Err(invalid_pin(
+                    config_path,
+                    plugin_name,
+                    &serde_yaml::to_string(other)?,
+                )),
+            }
+        };
+        Ok(Self {
+            config_path: Some(config_path.to_path_buf()),
+            supergraph: pinned("supergraph")?,
+            router: pinned("router")?,
+        })
+    }
+
+    /// The version pinned for the plugin with the given name
+    pub(crate) fn get(&self, plugin_name: &str) -> Option<&Version> {
+        match plugin_name {
+            "supergraph" => self.supergraph.as_ref(),
+            "router" => self.router.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn config_path(&self) -> Option<&Utf8Path> {
+        self.config_path.as_deref()
+    }
+
+    /// Swaps a `latest` plugin for the exact version this project has pinned. Exact versions are
+    /// left alone, as are `latest` versions whose major version doesn't match the pin, so a
+    /// project pinned to a Federation 2 composition plugin can still compose Federation 1
+    /// supergraphs
+    pub(crate) fn apply(&self, plugin: &Plugin) -> Option<Plugin> {
+        match plugin {
+            Plugin::Supergraph(FederationVersion::LatestFedOne) => self
+                .supergraph
+                .as_ref()
+                .filter(|version| version.major == 0)
+                .map(|version| Plugin::Supergraph(FederationVersion::ExactFedOne(version.clone()))),
+            Plugin::Supergraph(FederationVersion::LatestFedTwo) => self
+                .supergraph
+                .as_ref()
+                .filter(|version| version.major == 2)
+                .map(|version| Plugin::Supergraph(FederationVersion::ExactFedTwo(version.clone()))),
+            Plugin::Router(RouterVersion::Latest) => self
+                .router
+                .as_ref()
+                .filter(|version| version.major == 1)
+                .map(|version| Plugin::Router(RouterVersion::Exact(version.clone()))),
+            _ => None,
+        }
+    }
+}
+
+/// Pins `plugin` to `version` in the project config at `config_path`, creating the file if it
+/// doesn't exist yet. Anything else in the file is kept, as described in
+/// [`update_project_config`]
+pub(crate) fn write_pin(
+    config_path: &Utf8Path,
+    plugin_name: &str,
+    version: &Version,
+) -> RoverResult<()> {
+    update_project_config(config_path, |config| {
+        let plugins = config
+            .entry(Value::from("plugins"))
+            .or_insert_with(|| Value::Mapping(Mapping::new()));
+        if plugins.is_null() {
+            *plugins = Value::Mapping(Mapping::new());
+        }
+        let Some(plugins) = plugins.as_mapping_mut() else {
+            return Err(RoverError::new(anyhow!(
+                "Could not add a plugin pin to {} because its `plugins` key is not a mapping.",
+                config_path
+            )));
+        };
+        plugins.insert(Value::from(plugin_name), Value::from(version.to_string()));
+        Ok(())
+    })
+}
+
+/// Finds the `.apollo/config.yaml` that applies to `dir`, looking in `dir` and then each of its
+/// parents
+pub(crate) fn find_project_config(dir: &Utf8Path) -> Option<Utf8PathBuf> {
+    dir.ancestors()
+        .map(|ancestor| ancestor.join(PROJECT_CONFIG_PATH))
+        .find(|config_path| config_path.is_file())
+}
+
+/// Pins are written as bare versions, but the `=` and `v` prefixes that `--plugin` takes are
+/// accepted too
+fn parse_pinned_version(version: &str) -> Option<Version> {
+    let version = version.trim();
+    let version = version
+        .strip_prefix('=')
+        .or_else(|| version.strip_prefix('v'))
+        .unwrap_or(version);
+    Version::parse(version).ok()
+}
+
+fn invalid_pin(config_path: &Utf8Path, plugin_name: &str, version: &str) -> RoverError {
+    let mut err = RoverError::new(anyhow!(
+        "The '{}' plugin pin in {} is not an exact version: '{}'",
+        plugin_name,
+        config_path,
+        version.trim()
+    ));
+    err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+        "Pin an exact version with `rover plugin pin {plugin_name}@<version>`, or remove `plugins.{plugin_name}` from {config_path}."
+    )));
+    err
+}
+
+#[cfg(test)]
+mod tests {
+    use apollo_federation_types::config::{FederationVersion, RouterVersion};
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use indoc::indoc;
+    use rstest::rstest;
+    use semver::Version;
+    use speculoos::prelude::*;
+
+    use super::{find_project_config, write_pin, PluginPins, PROJECT_CONFIG_PATH};
+    use crate::command::install::Plugin;
+
+    #[rstest]
+    #[case::latest_fed_two(
+        Plugin::Supergraph(FederationVersion::LatestFedTwo),
+        Some(Plugin::Supergraph(FederationVersion::ExactFedTwo(Version::new(2, 9, 3))))
+    )]
+    #[case::latest_fed_one(Plugin::Supergraph(FederationVersion::LatestFedOne), None)]
+    #[case::exact(
+        Plugin::Supergraph(FederationVersion::ExactFedTwo(Version::new(2, 8, 0))),
+        None
+    )]
+    #[case::latest_router(
+        Plugin::Router(RouterVersion::Latest),
+        Some(Plugin::Router(RouterVersion::Exact(Version::new(1, 57, 1))))
+    )]
+    fn test_apply(#[case] plugin: Plugin, #[case] expected: Option<Plugin>) {
+        let pins = PluginPins {
+            config_path: None,
+            supergraph: Some(Version::new(2, 9, 3)),
+            router: Some(Version::new(1, 57, 1)),
+        };
+        assert_that!(pins
+            .apply(&plugin)
+            .map(|plugin| plugin.get_tarball_version()))
+        .is_equal_to(expected.map(|plugin| plugin.get_tarball_version()));
+    }
+
+    #[test]
+    fn test_write_pin_keeps_other_keys_and_loads_from_subdirectory() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf()).unwrap();
+        let config_path = root.join(PROJECT_CONFIG_PATH);
+        std::fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &config_path,
+            indoc! {r#"
+                graph_ref: my-graph@current
+                plugins:
+                  router: v1.57.1
+            "#},
+        )
+        .unwrap();
+
+        write_pin(&config_path, "supergraph", &Version::new(2, 9, 3)).unwrap();
+
+        let nested = root.join("services/products");
+        std::fs::create_dir_all(&nested).unwrap();
+        let found = find_project_config(&nested).unwrap();
+        assert_that!(found).is_equal_to(&config_path);
+
+        let pins = PluginPins::load(&found).unwrap();
+        assert_that!(pins.get("supergraph")).is_equal_to(Some(&Version::new(2, 9, 3)));
+        assert_that!(pins.get("router")).is_equal_to(Some(&Version::new(1, 57, 1)));
+        assert_that!(std::fs::read_to_string(&config_path).unwrap())
+            .contains("graph_ref: my-graph@current");
+    }
+
+    #[test]
+    fn test_load_rejects_inexact_pins() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = Utf8PathBuf::from_path_buf(temp_dir.path().join("config.yaml")).unwrap();
+        std::fs::write(&config_path, "plugins:\n  supergraph: latest-2\n").unwrap();
+        let err = PluginPins::load(&config_path).unwrap_err();
+        assert_that!(err.to_string()).contains("is not an exact version: 'latest-2'");
+    }
+}
@@ -6,9 +6,12 @@ use camino::Utf8PathBuf;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 
-use binstall::Installer;
-use rover_std::{sanitize_url, Fs};
+use binstall::{Installer, InstallerError};
+use rover_std::{progressln, sanitize_url, timing, Fs};
 
+use super::pin::PluginPins;
+use crate::command::plugin::reclaimable_bytes;
+use crate::utils::env::RoverEnvKey;
 use crate::{utils::client::StudioClientConfig, RoverError, RoverErrorSuggestion, RoverResult};
 
 // These OSX versions of the router were compiled for aarch64 only
@@ -209,6 +212,8 @@ impl PluginInstaller {
     }
 
     pub async fn install(&self, plugin: &Plugin, skip_update: bool) -> RoverResult<Utf8PathBuf> {
+        let _timing = timing::time_phase("plugin install");
+        let offline = self.client_config.is_offline();
         let skip_update_err = |plugin_name: &str, version: &str| {
             let mut err = RoverError::new(anyhow!(
                 "You do not have the '{}-v{}' plugin installed.",
@@ -220,9 +225,10 @@ impl PluginInstaller {
                     "Try runnning `npm install` to reinstall the plugin.".to_string(),
                 ));
             } else {
-                err.set_suggestion(RoverErrorSuggestion::Adhoc(
-                    "Try re-running this command without the `--skip-update` flag.".to_string(),
-                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(rerun_suggestion(
+                    skip_update,
+                    offline,
+                )));
             }
             err
         };
@@ -235,6 +241,20 @@ impl PluginInstaller {
             err
         };
 
+        let pinned = PluginPins::for_current_dir()?;
+        let pinned_plugin = pinned.apply(plugin);
+        if let Some(pinned_plugin) = &pinned_plugin {
+            tracing::info!(
+                "using the '{}' plugin v{} pinned in {}",
+                pinned_plugin.get_name(),
+                pinned_plugin.get_tarball_version(),
+                pinned.config_path().map(|p| p.as_str()).unwrap_or_default()
+            );
+        }
+        let plugin = pinned_plugin.as_ref().unwrap_or(plugin);
+        // the latest versions can't be looked up offline, so the latest installed ones are used
+        let skip_latest_update = skip_update || offline;
+
         let install_location = match plugin {
             Plugin::Router(version) => match version {
                 RouterVersion::Exact(version) => {
@@ -250,8 +270,8 @@ impl PluginInstaller {
                 }
                 RouterVersion::Latest => {
                     let major_version = 1;
-                    if skip_update {
-                        self.find_existing_latest_major(plugin, major_version)?
+                    if skip_latest_update {
+                        self.find_existing_latest_major(plugin, major_version, skip_update)?
                             .ok_or_else(|| {
                                 skip_update_err(
                                     &plugin.get_name(),
@@ -283,8 +303,8 @@ impl PluginInstaller {
                 }
                 FederationVersion::LatestFedOne => {
                     let major_version = 0;
-                    if skip_update {
-                        self.find_existing_latest_major(plugin, major_version)?
+                    if skip_latest_update {
+                        self.find_existing_latest_major(plugin, major_version, skip_update)?
                             .ok_or_else(|| {
                                 skip_update_err(&plugin.get_name(), version.to_string().as_str())
                             })
@@ -299,9 +319,9 @@ impl PluginInstaller {
                 }
                 FederationVersion::LatestFedTwo => {
                     let major_version = 2;
-                    if skip_update {
+                    if skip_latest_update {
                         Ok(self
-                            .find_existing_latest_major(plugin, major_version)?
+                            .find_existing_latest_major(plugin, major_version, skip_update)?
                             .ok_or_else(|| {
                                 skip_update_err(
                                     &plugin.get_name(),
@@ -327,6 +347,7 @@ impl PluginInstaller {
         &self,
         plugin: &Plugin,
         major_version: u64,
+        skip_update: bool,
     ) -> RoverResult<Option<Utf8PathBuf>> {
         let plugin_dir = self.rover_installer.get_bin_dir_path()?;
         let plugin_name = plugin.get_name();
@@ -338,10 +359,10 @@ impl PluginInstaller {
                 &plugin_name,
                 &plugin_dir
             ));
-            err.set_suggestion(RoverErrorSuggestion::Adhoc(
-                "Re-run this command without the `--skip-update` flag to install the proper plugin."
-                    .to_string(),
-            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(rerun_suggestion(
+                skip_update,
+                self.client_config.is_offline(),
+            )));
             Err(err)
         } else {
             // installed_plugins are sorted by semver
@@ -353,7 +374,11 @@ impl PluginInstaller {
     async fn install_latest_major(&self, plugin: &Plugin) -> RoverResult<Option<Utf8PathBuf>> {
         let latest_version = self
             .rover_installer
-            .get_plugin_version(&plugin.get_tarball_url()?, true)
+            .get_plugin_version(
+                &plugin.get_tarball_url()?,
+                true,
+                &self.client_config.get_reqwest_client_without_redirects()?,
+            )
             .await?;
 
         if let Ok(Some(exe)) = self.find_existing_exact(plugin, &latest_version) {
@@ -374,7 +399,7 @@ impl PluginInstaller {
     ) -> RoverResult<Option<Utf8PathBuf>> {
         let plugin_dir = self.rover_installer.get_bin_dir_path()?;
         let plugin_name = plugin.get_name();
-        Ok(find_installed_plugin(&plugin_dir, &plugin_name, version).ok())
+        Ok(find_installed_plugin(&plugin_dir, &plugin_name, version))
     }
 
     async fn install_exact(
@@ -397,25 +422,90 @@ impl PluginInstaller {
         is_latest: bool,
     ) -> RoverResult<Option<Utf8PathBuf>> {
         let plugin_name = plugin.get_name();
+        self.client_config
+            .require_network(&format!("Downloading the '{plugin_name}' plugin"))?;
         let plugin_tarball_url = plugin.get_tarball_url()?;
         // only print the download message if the username and password have been stripped from the URL
         if let Some(sanitized_url) = sanitize_url(&plugin_tarball_url) {
-            eprintln!("downloading the '{plugin_name}' plugin from {sanitized_url}");
+            progressln!("downloading the '{plugin_name}' plugin from {sanitized_url}");
         } else {
-            eprintln!("downloading the '{plugin_name}' plugin");
+            progressln!("downloading the '{plugin_name}' plugin");
         }
-        Ok(self
-            .rover_installer
+        self.rover_installer
             .install_plugin(
                 &plugin_name,
                 &plugin_tarball_url,
                 &self.client_config.get_reqwest_client()?,
+                &self.client_config.get_reqwest_client_without_redirects()?,
                 is_latest,
             )
-            .await?)
+            .await
+            .map_err(|err| {
+                let insufficient_space =
+                    matches!(err, InstallerError::InsufficientDiskSpace { .. });
+                let mut err = RoverError::from(err);
+                if insufficient_space {
+                    let reclaimable_bytes = self
+                        .rover_installer
+                        .get_bin_dir_path()
+                        .ok()
+                        .and_then(|plugin_dir| reclaimable_bytes(&plugin_dir));
+                    err.set_suggestion(RoverErrorSuggestion::FreeDiskSpace { reclaimable_bytes });
+                }
+                err
+            })
     }
 }
 
+/// A plugin binary in Rover's plugin directory
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct InstalledPlugin {
+    pub name: String,
+    pub version: Version,
+    pub path: Utf8PathBuf,
+    pub size_bytes: u64,
+    /// Whether the project in the current directory has pinned this version
+    pub pinned: bool,
+}
+
+/// Lists every plugin binary in `plugin_dir`, sorted by name and then by version
+pub(crate) fn list_installed_plugins(
+    plugin_dir: &Utf8PathBuf,
+    pins: &PluginPins,
+) -> RoverResult<Vec<InstalledPlugin>> {
+    let mut installed_plugins = Vec::new();
+    for entry in Fs::get_dir_entries(plugin_dir)?.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        let file_name = entry.file_name();
+        let file_name = file_name
+            .strip_suffix(std::env::consts::EXE_SUFFIX)
+            .unwrap_or(file_name);
+        let Some((name, version)) = file_name.split_once("-v") else {
+            continue;
+        };
+        if name != "supergraph" && name != "router" {
+            continue;
+        }
+        let Ok(version) = Version::parse(version) else {
+            continue;
+        };
+        installed_plugins.push(InstalledPlugin {
+            pinned: pins.get(name) == Some(&version),
+            name: name.to_string(),
+            version,
+            path: entry.path().to_path_buf(),
+            size_bytes: metadata.len(),
+        });
+    }
+    installed_plugins.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+    Ok(installed_plugins)
+}
+
 fn find_installed_plugins(
     plugin_dir: &Utf8PathBuf,
     plugin_name: &str,
@@ -460,32 +550,28 @@ fn find_installed_plugin(
     plugin_dir: &Utf8PathBuf,
     plugin_name: &str,
     version: &str,
-) -> RoverResult<Utf8PathBuf> {
-    let version = if let Some(version) = version.strip_prefix('v') {
-        version.to_string()
-    } else {
-        version.to_string()
-    };
+) -> Option<Utf8PathBuf> {
+    let version = version.strip_prefix('v').unwrap_or(version);
     let maybe_plugin = plugin_dir.join(format!(
         "{}-v{}{}",
         plugin_name,
         version,
         std::env::consts::EXE_SUFFIX
     ));
-    if Fs::assert_path_exists(&maybe_plugin).is_ok() {
-        Ok(maybe_plugin)
-    } else {
-        let mut err = RoverError::new(anyhow!("Could not find plugin at {}", &maybe_plugin));
-        if std::env::var("APOLLO_NODE_MODULES_BIN_DIR").is_ok() {
-            err.set_suggestion(RoverErrorSuggestion::Adhoc(
-                "Try runnning `npm install` to reinstall the plugin.".to_string(),
-            ));
-        } else {
-            err.set_suggestion(RoverErrorSuggestion::Adhoc(
-                "Try re-running this command without the `--skip-update` flag.".to_string(),
-            ));
-        }
-        Err(err)
+    Fs::assert_path_exists(&maybe_plugin)
+        .is_ok()
+        .then_some(maybe_plugin)
+}
+
+/// How to get a plugin installed, by naming whichever of `--skip-update` and `--offline` kept it
+/// from being downloaded
+fn rerun_suggestion(skip_update: bool, offline: bool) -> String {
+    let offline_env = format!("`${}`", RoverEnvKey::Offline);
+    match (skip_update, offline) {
+        (true, true) => format!("Try re-running this command without `--skip-update` and `--offline`, and with {offline_env} unset, to install the plugin."),
+        (false, true) => format!("Try re-running this command without `--offline`, and with {offline_env} unset, to install the plugin."),
+        (true, false) => "Try re-running this command without the `--skip-update` flag to install the plugin.".to_string(),
+        (false, false) => "Try re-running this command to install the plugin.".to_string(),
     }
 }
 
@@ -493,7 +579,7 @@ fn find_installed_plugin(
 mod tests {
     use rstest::rstest;
     use speculoos::assert_that;
-    use speculoos::prelude::ResultAssertions;
+    use speculoos::prelude::{ResultAssertions, StrAssertions};
 
     use super::*;
 
@@ -754,6 +840,18 @@ mod tests {
         };
     }
 
+    #[rstest]
+    #[case::skip_update(true, false, "without the `--skip-update` flag")]
+    #[case::offline(false, true, "without `--offline`, and with `$APOLLO_OFFLINE` unset")]
+    #[case::both(true, true, "without `--skip-update` and `--offline`")]
+    fn test_rerun_suggestion_names_the_flags_passed(
+        #[case] skip_update: bool,
+        #[case] offline: bool,
+        #[case] expected: &str,
+    ) {
+        assert_that!(rerun_suggestion(skip_update, offline)).contains(expected);
+    }
+
     #[test]
     #[cfg(target_env = "musl")]
     fn test_plugin_version_should_fail() {
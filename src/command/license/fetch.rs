@@ -3,7 +3,7 @@ use crate::utils::client::StudioClientConfig;
 use crate::{RoverOutput, RoverResult};
 use clap::Parser;
 use rover_client::operations::license::fetch::LicenseFetchInput;
-use rover_std::Style;
+use rover_std::{progressln, Style};
 use serde::Serialize;
 
 #[derive(Debug, Serialize, Parser)]
@@ -20,7 +20,7 @@ pub struct Fetch {
 impl Fetch {
     pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
-        eprintln!(
+        progressln!(
             "Fetching license for {} using credentials from the {} profile.",
             Style::Link.paint(&self.graph_id),
             Style::Command.paint(&self.profile.profile_name)
@@ -0,0 +1,25 @@
+use clap::Parser;
+use semver::Version;
+use serde::Serialize;
+
+use crate::utils::migrations::migrations_between;
+use crate::{RoverOutput, RoverResult, PKG_VERSION};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Migrations {
+    /// Only list the changes made after this version of Rover, such as the version you're
+    /// upgrading from
+    #[arg(long = "from", value_name = "VERSION")]
+    #[serde(skip_serializing)]
+    from: Option<Version>,
+}
+
+impl Migrations {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let current_version = Version::parse(PKG_VERSION)?;
+        Ok(RoverOutput::MetaMigrations(migrations_between(
+            self.from.as_ref(),
+            &current_version,
+        )))
+    }
+}
@@ -0,0 +1,26 @@
+mod migrations;
+
+use clap::Parser;
+use serde::Serialize;
+
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Meta {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub enum Command {
+    /// List the changes between Rover versions that may require updating existing usage
+    Migrations(migrations::Migrations),
+}
+
+impl Meta {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        match &self.command {
+            Command::Migrations(command) => command.run(),
+        }
+    }
+}
@@ -1,21 +1,29 @@
+#[cfg(feature = "composition-js")]
+pub(crate) mod bench;
 mod cloud;
 mod config;
 mod contract;
 mod dev;
 mod docs;
 mod explain;
-mod graph;
+pub(crate) mod graph;
 mod info;
 pub(crate) mod install;
 mod license;
+mod meta;
 pub(crate) mod output;
 mod persisted_queries;
+mod plugin;
+mod proposal;
 mod readme;
+mod schema;
 pub(crate) mod subgraph;
 pub(crate) mod supergraph;
 pub(crate) mod template;
 mod update;
 
+#[cfg(feature = "composition-js")]
+pub use bench::Bench;
 pub use cloud::Cloud;
 pub use config::Config;
 pub use contract::Contract;
@@ -26,9 +34,13 @@ pub use graph::Graph;
 pub use info::Info;
 pub use install::Install;
 pub use license::License;
+pub use meta::Meta;
 pub use output::RoverOutput;
 pub use persisted_queries::PersistedQueries;
+pub use plugin::Plugin;
+pub use proposal::Proposal;
 pub use readme::Readme;
+pub use schema::Schema;
 pub use subgraph::Subgraph;
 pub use supergraph::Supergraph;
 pub use template::Template;
@@ -6,29 +6,59 @@ use std::{
 
 use calm_io::{stderr, stderrln};
 use camino::Utf8PathBuf;
+use houston::{CredentialStore, ProfileDefaults};
 use serde_json::{json, Value};
 use termimad::{crossterm::style::Attribute::Underlined, MadSkin};
 
 use rover_client::operations::contract::describe::ContractDescribeResponse;
 use rover_client::operations::contract::publish::ContractPublishResponse;
 use rover_client::operations::graph::publish::GraphPublishResponse;
+use rover_client::operations::persisted_queries::create::PersistedQueryListCreateResponse;
+use rover_client::operations::persisted_queries::delete::PersistedQueryListDeleteResponse;
+use rover_client::operations::persisted_queries::link::PersistedQueryListLinkResponse;
+use rover_client::operations::persisted_queries::list::PersistedQueryListsResponse;
 use rover_client::operations::persisted_queries::publish::PersistedQueriesPublishResponse;
+use rover_client::operations::proposal::list::ProposalListResponse;
+use rover_client::operations::proposal::publish::ProposalPublishResponse;
 use rover_client::operations::subgraph::delete::SubgraphDeleteResponse;
 use rover_client::operations::subgraph::list::SubgraphListResponse;
 use rover_client::operations::subgraph::publish::SubgraphPublishResponse;
 use rover_client::shared::{
-    CheckRequestSuccessResult, CheckWorkflowResponse, FetchResponse, GraphRef, LintResponse,
-    SdlType,
+    CheckRequestSuccessResult, CheckWorkflowResponse, DryRunRequest, FetchResponse, GraphRef,
+    LintResponse, SdlType,
 };
 use rover_client::RoverClientError;
 use rover_std::Style;
 
+#[cfg(feature = "composition-js")]
+use crate::command::bench::compose::ComposeBenchmark;
+#[cfg(feature = "composition-js")]
+use crate::command::dev::legacy::router::CrashBundle;
+use crate::command::graph::GraphChangelog;
+use crate::command::install::InstalledPlugin;
+#[cfg(feature = "composition-js")]
+use crate::command::subgraph::delete::SubgraphDeleteImpact;
+#[cfg(feature = "composition-js")]
+use crate::command::subgraph::migrate_field::FieldMigrationPlan;
+#[cfg(feature = "composition-js")]
+use crate::command::supergraph::check::SupergraphCheckResponse;
 use crate::command::supergraph::compose::CompositionOutput;
+use crate::command::supergraph::provenance::Provenance;
 use crate::command::template::queries::list_templates_for_language::ListTemplatesForLanguageTemplates;
+#[cfg(feature = "composition-js")]
+use crate::operation_validation::OperationValidation;
 use crate::options::JsonVersion;
 use crate::options::ProjectLanguage;
+use crate::options::SchemaFormat;
+use crate::schema_diff::SchemaDiff;
+use crate::schema_mask::MaskedSchema;
+use crate::utils::credentials::{CredentialReport, ResolutionOutcome};
+use crate::utils::github_annotations::{self, GithubAnnotation};
+use crate::utils::graphql_ws::SubscriptionSupport;
+use crate::utils::junit::{self, JunitReport};
+use crate::utils::migrations::Migration;
 use crate::utils::table::{self, row};
-use crate::RoverError;
+use crate::{RoverError, RoverErrorCode};
 
 /// RoverOutput defines all of the different types of data that are printed
 /// to `stdout`. Every one of Rover's commands should return `saucer::Result<RoverOutput>`
@@ -47,17 +77,41 @@ pub enum RoverOutput {
         key_type: String,
         origin: String,
         user_id: Option<String>,
+        credential: Option<CredentialReport>,
     },
     ContractDescribe(ContractDescribeResponse),
     ContractPublish(ContractPublishResponse),
     DocsList(BTreeMap<&'static str, &'static str>),
     FetchResponse(FetchResponse),
+    /// A fetched schema converted to another `--schema-format` than SDL
+    ConvertedSchema {
+        schema: String,
+        format: SchemaFormat,
+    },
     SupergraphSchema(String),
     CompositionResult(CompositionOutput),
+    SupergraphProvenance(Provenance),
+    #[cfg(feature = "composition-js")]
+    ComposeBenchmark(Vec<ComposeBenchmark>),
     SubgraphList(SubgraphListResponse),
+    #[cfg(feature = "composition-js")]
+    SubgraphFetchAllResponse {
+        graph_ref: GraphRef,
+        supergraph_config: Utf8PathBuf,
+        subgraphs: Vec<String>,
+    },
+    ProposalList(ProposalListResponse),
+    ProposalPublishResponse(ProposalPublishResponse),
     CheckWorkflowResponse(CheckWorkflowResponse),
+    #[cfg(feature = "composition-js")]
+    SupergraphCheckResponse(SupergraphCheckResponse),
+    SupergraphDiff(SchemaDiff),
+    #[cfg(feature = "composition-js")]
+    OperationValidation(OperationValidation),
+    MaskedSchema(MaskedSchema),
     AsyncCheckResponse(CheckRequestSuccessResult),
     LintResponse(LintResponse),
+    GraphChangelog(GraphChangelog),
     GraphPublishResponse {
         graph_ref: GraphRef,
         publish_response: GraphPublishResponse,
@@ -73,14 +127,32 @@ pub enum RoverOutput {
         dry_run: bool,
         delete_response: SubgraphDeleteResponse,
     },
+    #[cfg(feature = "composition-js")]
+    SubgraphDeleteImpact(SubgraphDeleteImpact),
+    #[cfg(feature = "composition-js")]
+    FieldMigrationPlan(FieldMigrationPlan),
     TemplateList(Vec<ListTemplatesForLanguageTemplates>),
     TemplateUseSuccess {
         template_id: String,
         path: Utf8PathBuf,
     },
     Profiles(Vec<String>),
+    ProfileDefaults {
+        profile: String,
+        defaults: ProfileDefaults,
+    },
     Introspection(String),
+    SubgraphIntrospection {
+        sdl: String,
+        subscriptions: Option<SubscriptionSupport>,
+    },
     ErrorExplanation(String),
+    DryRun(DryRunRequest),
+    ErrorCatalog(Vec<RoverErrorCode>),
+    MetaMigrations(Vec<&'static Migration>),
+    PluginList(Vec<InstalledPlugin>),
+    #[cfg(feature = "composition-js")]
+    DevCrashList(Vec<CrashBundle>),
     ReadmeFetchResponse {
         graph_ref: GraphRef,
         content: String,
@@ -92,6 +164,10 @@ pub enum RoverOutput {
         last_updated_time: Option<String>,
     },
     PersistedQueriesPublishResponse(PersistedQueriesPublishResponse),
+    PersistedQueryListCreateResponse(PersistedQueryListCreateResponse),
+    PersistedQueryLists(PersistedQueryListsResponse),
+    PersistedQueryListDeleteResponse(PersistedQueryListDeleteResponse),
+    PersistedQueryListLinkResponse(PersistedQueryListLinkResponse),
     LicenseResponse {
         graph_id: String,
         jwt: String,
@@ -106,6 +182,96 @@ pub enum RoverOutput {
 }
 
 impl RoverOutput {
+    /// Annotations for `--format github-annotations`, for the outputs of checks, lints, and
+    /// compositions
+    pub fn get_github_annotations(&self) -> Vec<GithubAnnotation> {
+        match self {
+            RoverOutput::CompositionResult(composition_output) => {
+                github_annotations::from_build_hints(&composition_output.hints)
+            }
+            RoverOutput::CheckWorkflowResponse(check_response) => {
+                github_annotations::from_check_response(check_response, None)
+            }
+            #[cfg(feature = "composition-js")]
+            RoverOutput::SupergraphCheckResponse(check_response) => {
+                check_response.get_github_annotations()
+            }
+            RoverOutput::LintResponse(lint_response) => {
+                github_annotations::from_lint_response(lint_response)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// The output for `--porcelain`: only the fields a script needs, in a format that doesn't
+    /// change. Outputs without a porcelain format fall back to their plain output
+    pub fn get_porcelain(&self) -> io::Result<Option<String>> {
+        Ok(match self {
+            RoverOutput::FetchResponse(fetch_response) => Some(fetch_response.sdl.contents.clone()),
+            RoverOutput::ConvertedSchema { schema, .. } => Some(schema.clone()),
+            RoverOutput::SupergraphSchema(sdl)
+            | RoverOutput::Introspection(sdl)
+            | RoverOutput::SubgraphIntrospection { sdl, .. } => Some(sdl.clone()),
+            RoverOutput::CompositionResult(composition_output) => {
+                Some(composition_output.supergraph_sdl.clone())
+            }
+            RoverOutput::MaskedSchema(masked) => Some(masked.sdl.clone()),
+            RoverOutput::CheckWorkflowResponse(_) | RoverOutput::LintResponse(_) => {
+                Some("passed".to_string())
+            }
+            #[cfg(feature = "composition-js")]
+            RoverOutput::SupergraphCheckResponse(check_response) => {
+                Some(check_response.get_porcelain())
+            }
+            RoverOutput::AsyncCheckResponse(check_response) => {
+                Some(check_response.workflow_id.clone())
+            }
+            RoverOutput::GraphPublishResponse {
+                publish_response, ..
+            } => Some(publish_response.api_schema_hash.clone()),
+            RoverOutput::SubgraphPublishResponse {
+                publish_response, ..
+            } => publish_response.api_schema_hash.clone(),
+            RoverOutput::SubgraphList(list) => Some(
+                list.subgraphs
+                    .iter()
+                    .map(|subgraph| subgraph.name.clone())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            RoverOutput::Profiles(profiles) => Some(profiles.join("\n")),
+            RoverOutput::PluginList(plugins) => Some(
+                plugins
+                    .iter()
+                    .map(|plugin| format!("{} {}", plugin.name, plugin.version))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            RoverOutput::ReadmeFetchResponse { content, .. } => Some(content.clone()),
+            RoverOutput::CloudConfigFetchResponse { config } => Some(config.clone()),
+            RoverOutput::LicenseResponse { jwt, .. } => Some(jwt.clone()),
+            RoverOutput::EmptySuccess => None,
+            _ => self.get_stdout()?,
+        })
+    }
+
+    /// The JUnit report for `--format junit`, for the outputs of checks
+    pub fn get_junit(&self) -> Option<JunitReport> {
+        match self {
+            RoverOutput::CheckWorkflowResponse(check_response) => {
+                Some(JunitReport::new(vec![junit::from_check_response(
+                    check_response,
+                    "checks",
+                )]))
+            }
+            #[cfg(feature = "composition-js")]
+            RoverOutput::SupergraphCheckResponse(check_response) => {
+                Some(JunitReport::new(check_response.get_junit_suites()))
+            }
+            _ => None,
+        }
+    }
+
     pub fn get_stdout(&self) -> io::Result<Option<String>> {
         Ok(match self {
             RoverOutput::ConfigWhoAmIOutput {
@@ -115,6 +281,7 @@ impl RoverOutput {
                 key_type,
                 origin,
                 user_id,
+                credential,
             } => {
                 let mut table = table::get_table();
 
@@ -135,6 +302,38 @@ impl RoverOutput {
                 table.add_row(row![Style::WhoAmIKey.paint("Origin"), origin]);
                 table.add_row(row![Style::WhoAmIKey.paint("API Key"), api_key]);
 
+                if let Some(credential) = credential {
+                    table.add_row(row![Style::WhoAmIKey.paint("Key Scope"), credential.key]);
+                    if let Some(store) = credential.store {
+                        let store = match store {
+                            CredentialStore::File => "profile file",
+                            CredentialStore::Keychain => "OS keychain",
+                        };
+                        table.add_row(row![Style::WhoAmIKey.paint("Stored In"), store]);
+                    }
+                    if credential.sso {
+                        table.add_row(row![
+                            Style::WhoAmIKey.paint("SSO"),
+                            "short-lived token, refreshed automatically"
+                        ]);
+                    }
+                    let resolution = credential
+                        .resolution
+                        .iter()
+                        .enumerate()
+                        .map(|(i, step)| {
+                            let outcome = match step.outcome {
+                                ResolutionOutcome::Used => "used",
+                                ResolutionOutcome::NotSet => "not set",
+                                ResolutionOutcome::Skipped => "skipped",
+                            };
+                            format!("{}. {} ({outcome})", i + 1, step.source)
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    table.add_row(row![Style::WhoAmIKey.paint("Resolution"), resolution]);
+                }
+
                 Some(format!("{}", table))
             }
             RoverOutput::ContractDescribe(describe_response) => Some(format!(
@@ -174,6 +373,7 @@ impl RoverOutput {
             RoverOutput::FetchResponse(fetch_response) => {
                 Some((fetch_response.sdl.contents).to_string())
             }
+            RoverOutput::ConvertedSchema { schema, .. } => Some(schema.clone()),
             RoverOutput::GraphPublishResponse {
                 graph_ref,
                 publish_response,
@@ -278,6 +478,21 @@ impl RoverOutput {
                 }
             }
             RoverOutput::SupergraphSchema(csdl) => Some((csdl).to_string()),
+            RoverOutput::SupergraphProvenance(provenance) => {
+                stderrln!("The supergraph schema matches its provenance header.")?;
+                let mut lines = vec![format!("rover_version: {}", provenance.rover_version)];
+                if let Some(supergraph_version) = &provenance.supergraph_version {
+                    lines.push(format!("supergraph_version: {supergraph_version}"));
+                }
+                lines.push(format!("composed_at: {}", provenance.composed_at));
+                lines.extend(
+                    provenance
+                        .subgraphs
+                        .keys()
+                        .map(|name| format!("subgraph: {name}")),
+                );
+                Some(lines.join("\n"))
+            }
             RoverOutput::CompositionResult(composition_output) => {
                 let warn_prefix = Style::HintPrefix.paint("HINT:");
 
@@ -292,8 +507,55 @@ impl RoverOutput {
 
                 stderrln!("{}", hints_string)?;
 
+                #[cfg(feature = "composition-js")]
+                for suggestion in composition_output.suggestions.iter().flatten() {
+                    stderrln!("{} {}", Style::HintPrefix.paint("SUGGESTION:"), suggestion)?;
+                }
+
+                // stdout is the supergraph, which is often redirected to a file
+                #[cfg(feature = "composition-js")]
+                for query_plan in composition_output.query_plans.iter().flatten() {
+                    stderrln!(
+                        "{}",
+                        Style::Heading.paint(format!("Query plan for {}:", query_plan.operation))
+                    )?;
+                    match (&query_plan.query_plan, &query_plan.error) {
+                        (Some(plan), _) => stderrln!("{}\n", plan)?,
+                        (None, error) => stderrln!(
+                            "{} {}\n",
+                            Style::WarningPrefix.paint("WARN:"),
+                            error.as_deref().unwrap_or("no query plan")
+                        )?,
+                    }
+                }
+
                 Some((composition_output.supergraph_sdl).to_string())
             }
+            #[cfg(feature = "composition-js")]
+            RoverOutput::ComposeBenchmark(benchmarks) => {
+                let mut table = table::get_table();
+
+                // bc => sets top row to be bold and center
+                table.add_row(
+                    row![bc => "Federation Version", "Runs", "Min", "Median", "p95", "Peak Memory"],
+                );
+                for benchmark in benchmarks {
+                    let peak_memory = benchmark
+                        .peak_memory_bytes()
+                        .map(|bytes| format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0)))
+                        .unwrap_or_else(|| "N/A".to_string());
+                    table.add_row(row![
+                        benchmark.federation_version(),
+                        benchmark.runs(),
+                        format!("{:.1}ms", benchmark.min().as_secs_f64() * 1000.0),
+                        format!("{:.1}ms", benchmark.median().as_secs_f64() * 1000.0),
+                        format!("{:.1}ms", benchmark.p95().as_secs_f64() * 1000.0),
+                        peak_memory,
+                    ]);
+                }
+
+                Some(format!("{}", table))
+            }
             RoverOutput::SubgraphList(details) => {
                 let mut table = table::get_table();
 
@@ -324,6 +586,54 @@ impl RoverOutput {
                     table, details.root_url, details.graph_ref.name
                 ))
             }
+            #[cfg(feature = "composition-js")]
+            RoverOutput::SubgraphFetchAllResponse {
+                graph_ref,
+                supergraph_config,
+                subgraphs,
+            } => Some(format!(
+                "Fetched {} subgraphs from {}. Compose them locally with '{}'",
+                subgraphs.len(),
+                Style::Link.paint(graph_ref.to_string()),
+                Style::Command.paint(format!(
+                    "rover supergraph compose --config {supergraph_config}"
+                ))
+            )),
+            RoverOutput::ProposalList(details) => {
+                let mut table = table::get_table();
+
+                // bc => sets top row to be bold and center
+                table.add_row(row![bc => "ID", "Name", "Status", "Source Variant"]);
+
+                for proposal in &details.proposals {
+                    table.add_row(row![
+                        proposal.id,
+                        proposal.display_name,
+                        proposal.status,
+                        proposal.source_variant
+                    ]);
+                }
+                Some(format!(
+                    "{}\n Showing {} of {} proposals. View all of them at {}/graph/{}/proposals",
+                    table,
+                    details.proposals.len(),
+                    details.total_count,
+                    details.root_url.trim_end_matches('/'),
+                    details.graph_ref.name
+                ))
+            }
+            RoverOutput::ProposalPublishResponse(publish_response) => {
+                stderrln!(
+                    "Published a revision of proposal \"{}\", which is now {}.",
+                    publish_response.proposal.display_name,
+                    Style::Command.paint(publish_response.proposal.status.to_string())
+                )?;
+                Some(format!(
+                    "{}\nView the proposal at {}",
+                    publish_response.proposal.id,
+                    Style::Link.paint(&publish_response.proposal_url)
+                ))
+            }
             RoverOutput::TemplateList(templates) => {
                 let mut table = table::get_table();
 
@@ -357,6 +667,19 @@ impl RoverOutput {
                 forum_call_to_action))
             }
             RoverOutput::CheckWorkflowResponse(check_response) => Some(check_response.get_output()),
+            #[cfg(feature = "composition-js")]
+            RoverOutput::SupergraphCheckResponse(check_response) => {
+                Some(check_response.get_output())
+            }
+            RoverOutput::SupergraphDiff(diff) => Some(diff.get_output()),
+            RoverOutput::GraphChangelog(changelog) => Some(changelog.get_output()),
+            #[cfg(feature = "composition-js")]
+            RoverOutput::OperationValidation(validation) => Some(validation.get_output()),
+            #[cfg(feature = "composition-js")]
+            RoverOutput::SubgraphDeleteImpact(impact) => Some(impact.get_output()),
+            #[cfg(feature = "composition-js")]
+            RoverOutput::FieldMigrationPlan(plan) => Some(plan.get_output()),
+            RoverOutput::MaskedSchema(masked) => Some(masked.sdl.clone()),
             RoverOutput::AsyncCheckResponse(check_response) => Some(format!(
                 "Check successfully started with workflow ID: {}\nView full details at {}",
                 check_response.workflow_id, check_response.target_url
@@ -368,9 +691,37 @@ impl RoverOutput {
                 }
                 Some(profiles.join("\n"))
             }
+            RoverOutput::ProfileDefaults { profile, defaults } => {
+                let defaults = [
+                    ("graph_ref", &defaults.graph_ref),
+                    ("federation_version", &defaults.federation_version),
+                    ("output_format", &defaults.output_format),
+                    ("log", &defaults.log),
+                ]
+                .into_iter()
+                .filter_map(|(key, value)| value.as_ref().map(|value| format!("{key}: {value}")))
+                .collect::<Vec<_>>();
+                if defaults.is_empty() {
+                    stderrln!("Profile \"{}\" has no defaults.", profile)?;
+                    None
+                } else {
+                    Some(defaults.join("\n"))
+                }
+            }
             RoverOutput::Introspection(introspection_response) => {
                 Some((introspection_response).to_string())
             }
+            RoverOutput::SubgraphIntrospection { sdl, subscriptions } => {
+                match subscriptions {
+                    Some(subscriptions) => stderrln!(
+                        "Subscriptions are served over {} at {}",
+                        subscriptions.protocol,
+                        subscriptions.url
+                    )?,
+                    None => stderrln!("Subscriptions are not served over websockets")?,
+                }
+                Some(sdl.to_string())
+            }
             RoverOutput::ErrorExplanation(explanation) => {
                 // underline bolded md
                 let mut skin = MadSkin::default();
@@ -378,6 +729,93 @@ impl RoverOutput {
 
                 Some(format!("{}", skin.inline(explanation)))
             }
+            RoverOutput::DryRun(request) => {
+                stderrln!(
+                    "Dry run: did not send {}, which would {}.",
+                    Style::Command.paint(&request.operation_name),
+                    request.effect
+                )?;
+                Some(format!(
+                    "{}\n\nVariables:\n{}",
+                    request.query,
+                    serde_json::to_string_pretty(&request.variables)?
+                ))
+            }
+            RoverOutput::ErrorCatalog(codes) => {
+                let mut table = table::get_table();
+
+                // bc => sets top row to be bold and center
+                table.add_row(row![bc => "Code", "Name", "Summary"]);
+                for code in codes {
+                    table.add_row(row![code, code.name(), code.summary()]);
+                }
+                stderrln!("Run `rover explain <CODE>` to learn how to resolve any of these.")?;
+                Some(format!("{}", table))
+            }
+            RoverOutput::MetaMigrations(migrations) => {
+                if migrations.is_empty() {
+                    stderrln!("There are no migrations to make.")?;
+                    None
+                } else {
+                    let mut table = table::get_table();
+
+                    // bc => sets top row to be bold and center
+                    table.add_row(row![bc => "Version", "Change", "Migration"]);
+                    for migration in migrations {
+                        table.add_row(row![
+                            migration.version,
+                            migration.summary,
+                            migration.migration
+                        ]);
+                    }
+                    Some(format!("{}", table))
+                }
+            }
+            RoverOutput::PluginList(plugins) => {
+                if plugins.is_empty() {
+                    stderrln!("There are no plugins installed.")?;
+                    None
+                } else {
+                    let mut table = table::get_table();
+
+                    // bc => sets top row to be bold and center
+                    table.add_row(row![bc => "Plugin", "Version", "Size", "Pinned"]);
+                    for plugin in plugins {
+                        table.add_row(row![
+                            plugin.name,
+                            plugin.version,
+                            format!("{:.1} MiB", plugin.size_bytes as f64 / (1024.0 * 1024.0)),
+                            if plugin.pinned { "yes" } else { "" }
+                        ]);
+                    }
+                    Some(format!("{}", table))
+                }
+            }
+            #[cfg(feature = "composition-js")]
+            RoverOutput::DevCrashList(bundles) => {
+                if bundles.is_empty() {
+                    stderrln!("There are no crash bundles from `rover dev` sessions.")?;
+                    None
+                } else {
+                    let mut table = table::get_table();
+
+                    // bc => sets top row to be bold and center
+                    table.add_row(row![bc => "Crashed At", "Exit", "Uptime", "Bundle"]);
+                    for bundle in bundles {
+                        table.add_row(row![
+                            bundle
+                                .report
+                                .crashed_at
+                                .with_timezone(&chrono::Local)
+                                .format("%Y-%m-%d %H:%M:%S %Z"),
+                            bundle.report.exit(),
+                            format!("{}s", bundle.report.uptime_secs),
+                            bundle.path
+                        ]);
+                    }
+                    Some(format!("{}", table))
+                }
+            }
             RoverOutput::ReadmeFetchResponse {
                 graph_ref: _,
                 content,
@@ -444,6 +882,49 @@ impl RoverOutput {
 
                 Some(result)
             }
+            RoverOutput::PersistedQueryListCreateResponse(response) => {
+                stderrln!(
+                    "Created persisted query list {} for {}.",
+                    Style::PersistedQueryList.paint(&response.list.name),
+                    Style::Link.paint(&response.graph_id)
+                )?;
+                if !response.list.linked_variants.is_empty() {
+                    stderrln!("Linked it to {}.", response.list.linked_variants.join(", "))?;
+                }
+                Some(response.list.id.clone())
+            }
+            RoverOutput::PersistedQueryLists(details) => {
+                let mut table = table::get_table();
+
+                // bc => sets top row to be bold and center
+                table.add_row(row![bc => "ID", "Name", "Linked Variants"]);
+
+                for list in &details.lists {
+                    table.add_row(row![list.id, list.name, list.linked_variants.join(", ")]);
+                }
+                Some(format!(
+                    "{}\n Showing {} persisted query lists for {}",
+                    table,
+                    details.lists.len(),
+                    details.graph_id
+                ))
+            }
+            RoverOutput::PersistedQueryListDeleteResponse(response) => {
+                stderrln!(
+                    "Deleted persisted query list {} from {}.",
+                    Style::PersistedQueryList.paint(&response.list_id),
+                    Style::Link.paint(&response.graph_id)
+                )?;
+                None
+            }
+            RoverOutput::PersistedQueryListLinkResponse(response) => {
+                stderrln!(
+                    "Linked {} to persisted query list {}.",
+                    Style::Link.paint(response.graph_ref.to_string()),
+                    Style::PersistedQueryList.paint(&response.list_name)
+                )?;
+                None
+            }
             RoverOutput::LicenseResponse { jwt, .. } => {
                 stderrln!("Success!")?;
                 Some(jwt.to_string())
@@ -463,15 +944,20 @@ impl RoverOutput {
                 graph_title,
                 graph_id,
                 user_id,
+                credential,
             } => {
-                json!({
+                let mut json = json!({
                   "key_type": key_type,
                   "graph_id": graph_id,
                   "graph_title": graph_title,
                   "user_id": user_id,
                   "origin": origin,
                   "api_key": api_key,
-                })
+                });
+                if let Some(credential) = credential {
+                    json["credential"] = json!(credential);
+                }
+                json
             }
             RoverOutput::ContractDescribe(describe_response) => json!(describe_response),
             RoverOutput::ContractPublish(publish_response) => json!(publish_response),
@@ -485,20 +971,54 @@ impl RoverOutput {
                 json!({ "shortlinks": shortlink_vec })
             }
             RoverOutput::FetchResponse(fetch_response) => json!(fetch_response),
+            RoverOutput::ConvertedSchema { schema, format } => {
+                json!({ "schema": schema, "format": format })
+            }
             RoverOutput::SupergraphSchema(csdl) => json!({ "core_schema": csdl }),
+            RoverOutput::SupergraphProvenance(provenance) => json!({ "provenance": provenance }),
             RoverOutput::CompositionResult(composition_output) => {
-                if let Some(federation_version) = &composition_output.federation_version {
-                    json!({
-                      "core_schema": composition_output.supergraph_sdl,
-                      "hints": composition_output.hints,
-                      "federation_version": federation_version
-                    })
-                } else {
-                    json!({
-                        "core_schema": composition_output.supergraph_sdl,
-                        "hints": composition_output.hints
-                    })
+                let mut json =
+                    if let Some(federation_version) = &composition_output.federation_version {
+                        json!({
+                          "core_schema": composition_output.supergraph_sdl,
+                          "hints": composition_output.hints,
+                          "federation_version": federation_version
+                        })
+                    } else {
+                        json!({
+                            "core_schema": composition_output.supergraph_sdl,
+                            "hints": composition_output.hints
+                        })
+                    };
+                if let Some(profile) = &composition_output.profile {
+                    json["profile"] = json!(profile);
+                }
+                #[cfg(feature = "composition-js")]
+                if let Some(suggestions) = &composition_output.suggestions {
+                    json["suggestions"] = json!(suggestions);
                 }
+                #[cfg(feature = "composition-js")]
+                if let Some(query_plans) = &composition_output.query_plans {
+                    json["query_plans"] = json!(query_plans);
+                }
+                json
+            }
+            #[cfg(feature = "composition-js")]
+            RoverOutput::ComposeBenchmark(benchmarks) => {
+                let benchmarks: Vec<Value> = benchmarks
+                    .iter()
+                    .map(|benchmark| {
+                        json!({
+                          "federation_version": benchmark.federation_version(),
+                          "runs": benchmark.runs(),
+                          "min_ms": benchmark.min().as_secs_f64() * 1000.0,
+                          "median_ms": benchmark.median().as_secs_f64() * 1000.0,
+                          "p95_ms": benchmark.p95().as_secs_f64() * 1000.0,
+                          "peak_memory_bytes": benchmark.peak_memory_bytes(),
+                        })
+                    })
+                    .collect();
+                json!({ "benchmarks": benchmarks })
             }
             RoverOutput::GraphPublishResponse {
                 graph_ref: _,
@@ -518,20 +1038,64 @@ impl RoverOutput {
                 json!(delete_response)
             }
             RoverOutput::SubgraphList(list_response) => json!(list_response),
+            #[cfg(feature = "composition-js")]
+            RoverOutput::SubgraphFetchAllResponse {
+                graph_ref,
+                supergraph_config,
+                subgraphs,
+            } => json!({
+                "graph_ref": graph_ref.to_string(),
+                "supergraph_config": supergraph_config,
+                "subgraphs": subgraphs,
+            }),
+            RoverOutput::ProposalList(list_response) => json!(list_response),
+            RoverOutput::ProposalPublishResponse(publish_response) => json!(publish_response),
             RoverOutput::TemplateList(templates) => json!({ "templates": templates }),
             RoverOutput::TemplateUseSuccess { template_id, path } => {
                 json!({ "template_id": template_id, "path": path })
             }
             RoverOutput::CheckWorkflowResponse(check_response) => check_response.get_json(),
+            #[cfg(feature = "composition-js")]
+            RoverOutput::SupergraphCheckResponse(check_response) => check_response.get_json(),
+            RoverOutput::SupergraphDiff(diff) => diff.get_json(),
+            RoverOutput::GraphChangelog(changelog) => changelog.get_json(),
+            #[cfg(feature = "composition-js")]
+            RoverOutput::OperationValidation(validation) => validation.get_json(),
+            #[cfg(feature = "composition-js")]
+            RoverOutput::SubgraphDeleteImpact(impact) => impact.get_json(),
+            #[cfg(feature = "composition-js")]
+            RoverOutput::FieldMigrationPlan(plan) => plan.get_json(),
+            RoverOutput::MaskedSchema(masked) => json!(masked),
             RoverOutput::AsyncCheckResponse(check_response) => check_response.get_json(),
             RoverOutput::LintResponse(lint_response) => lint_response.get_json(),
             RoverOutput::Profiles(profiles) => json!({ "profiles": profiles }),
+            RoverOutput::ProfileDefaults { profile, defaults } => {
+                json!({ "profile": profile, "defaults": defaults })
+            }
             RoverOutput::Introspection(introspection_response) => {
                 json!({ "introspection_response": introspection_response })
             }
+            RoverOutput::SubgraphIntrospection { sdl, subscriptions } => {
+                json!({ "introspection_response": sdl, "subscriptions": subscriptions })
+            }
             RoverOutput::ErrorExplanation(explanation_markdown) => {
                 json!({ "explanation_markdown": explanation_markdown })
             }
+            RoverOutput::DryRun(request) => json!({ "dry_run": request }),
+            RoverOutput::ErrorCatalog(codes) => json!({
+                "codes": codes
+                    .iter()
+                    .map(|code| json!({
+                        "code": code,
+                        "name": code.name(),
+                        "summary": code.summary(),
+                    }))
+                    .collect::<Vec<_>>()
+            }),
+            RoverOutput::MetaMigrations(migrations) => json!({ "migrations": migrations }),
+            RoverOutput::PluginList(plugins) => json!({ "plugins": plugins }),
+            #[cfg(feature = "composition-js")]
+            RoverOutput::DevCrashList(bundles) => json!({ "crashes": bundles }),
             RoverOutput::ReadmeFetchResponse {
                 graph_ref: _,
                 content,
@@ -547,6 +1111,10 @@ impl RoverOutput {
                 json!({ "readme": new_content, "last_updated_time": last_updated_time })
             }
             RoverOutput::EmptySuccess => json!(null),
+            RoverOutput::PersistedQueryListCreateResponse(response) => json!(response),
+            RoverOutput::PersistedQueryLists(response) => json!(response),
+            RoverOutput::PersistedQueryListDeleteResponse(response) => json!(response),
+            RoverOutput::PersistedQueryListLinkResponse(response) => json!(response),
             RoverOutput::PersistedQueriesPublishResponse(response) => {
                 json!({
                   "revision": response.revision,
@@ -619,12 +1187,14 @@ impl RoverOutput {
     pub(crate) fn get_json_version(&self) -> JsonVersion {
         match &self {
             Self::CheckWorkflowResponse(_) => JsonVersion::Two,
+            #[cfg(feature = "composition-js")]
+            Self::SupergraphCheckResponse(_) => JsonVersion::Two,
             _ => JsonVersion::default(),
         }
     }
 
     pub(crate) fn print_descriptor(&self) -> io::Result<()> {
-        if std::io::stdout().is_terminal() {
+        if std::io::stdout().is_terminal() && !rover_std::print::is_quiet() {
             if let Some(descriptor) = self.descriptor() {
                 stderrln!("{}: \n", Style::Heading.paint(descriptor))?;
             }
@@ -632,7 +1202,7 @@ impl RoverOutput {
         Ok(())
     }
     pub(crate) fn print_one_line_descriptor(&self) -> io::Result<()> {
-        if std::io::stdout().is_terminal() {
+        if std::io::stdout().is_terminal() && !rover_std::print::is_quiet() {
             if let Some(descriptor) = self.descriptor() {
                 stderr!("{}: ", Style::Heading.paint(descriptor))?;
             }
@@ -647,15 +1217,23 @@ impl RoverOutput {
                 SdlType::Graph | SdlType::Subgraph { .. } => Some("Schema"),
                 SdlType::Supergraph => Some("Supergraph Schema"),
             },
+            RoverOutput::ConvertedSchema { .. } => Some("Schema"),
             RoverOutput::CompositionResult(_) | RoverOutput::SupergraphSchema(_) => {
                 Some("Supergraph Schema")
             }
+            RoverOutput::MaskedSchema(_) => Some("Masked Schema"),
+            #[cfg(feature = "composition-js")]
+            RoverOutput::ComposeBenchmark(_) => Some("Composition Benchmark"),
             RoverOutput::TemplateUseSuccess { .. } => Some("Project generated"),
             RoverOutput::AsyncCheckResponse(_) => Some("Check Started"),
             RoverOutput::Profiles(_) => Some("Profiles"),
-            RoverOutput::Introspection(_) => Some("Introspection Response"),
+            RoverOutput::Introspection(_) | RoverOutput::SubgraphIntrospection { .. } => {
+                Some("Introspection Response")
+            }
             RoverOutput::ReadmeFetchResponse { .. } => Some("Readme"),
             RoverOutput::GraphPublishResponse { .. } => Some("Schema Hash"),
+            RoverOutput::ProposalPublishResponse(_) => Some("Proposal ID"),
+            RoverOutput::PersistedQueryListCreateResponse(_) => Some("Persisted Query List ID"),
             _ => None,
         }
     }
@@ -674,7 +1252,9 @@ mod tests {
     use rover_client::{
         operations::{
             graph::publish::{ChangeSummary, FieldChanges, TypeChanges},
-            persisted_queries::publish::PersistedQueriesOperationCounts,
+            persisted_queries::{
+                list::PersistedQueryListInfo, publish::PersistedQueriesOperationCounts,
+            },
             subgraph::{
                 delete::SubgraphDeleteResponse,
                 list::{SubgraphInfo, SubgraphUpdatedAt},
@@ -682,13 +1262,16 @@ mod tests {
         },
         shared::{
             ChangeSeverity, CheckTaskStatus, CheckWorkflowResponse, CustomCheckResponse,
-            Diagnostic, LintCheckResponse, OperationCheckResponse, ProposalsCheckResponse,
+            Diagnostic, DownstreamCheckResponse, DownstreamVariantResult, LintCheckResponse,
+            OperationCheckResponse, ProposalInfo, ProposalStatus, ProposalsCheckResponse,
             ProposalsCheckSeverityLevel, ProposalsCoverage, RelatedProposal, SchemaChange, Sdl,
             SdlType, Violation,
         },
     };
+    use semver::Version;
 
     use crate::options::JsonOutput;
+    use crate::utils::migrations::MigrationKind;
 
     use super::*;
 
@@ -819,6 +1402,105 @@ mod tests {
         assert_json_eq!(expected_json, actual_json);
     }
 
+    #[test]
+    fn proposal_list_json() {
+        let mock_proposal_list_response = ProposalListResponse {
+            proposals: vec![ProposalInfo {
+                id: "proposal-id".to_string(),
+                display_name: "Add reviews".to_string(),
+                status: ProposalStatus::APPROVED,
+                source_variant: "current".to_string(),
+            }],
+            total_count: 3,
+            root_url: "https://studio.apollographql.com/".to_string(),
+            graph_ref: GraphRef {
+                name: "graph".to_string(),
+                variant: "current".to_string(),
+            },
+        };
+        let actual_json: JsonOutput = RoverOutput::ProposalList(mock_proposal_list_response).into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "proposals": [
+                    {
+                        "id": "proposal-id",
+                        "display_name": "Add reviews",
+                        "status": "APPROVED",
+                        "source_variant": "current"
+                    }
+                ],
+                "total_count": 3,
+                "success": true
+            },
+            "error": null
+        });
+        assert_json_eq!(expected_json, actual_json);
+    }
+
+    #[test]
+    fn persisted_query_lists_json() {
+        let mock_lists_response = PersistedQueryListsResponse {
+            graph_id: "graph".to_string(),
+            lists: vec![PersistedQueryListInfo {
+                id: "list-id".to_string(),
+                name: "web".to_string(),
+                linked_variants: vec!["current".to_string()],
+            }],
+            root_url: "https://studio.apollographql.com/".to_string(),
+        };
+        let actual_json: JsonOutput = RoverOutput::PersistedQueryLists(mock_lists_response).into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "lists": [
+                    {
+                        "id": "list-id",
+                        "name": "web",
+                        "linked_variants": ["current"]
+                    }
+                ],
+                "success": true
+            },
+            "error": null
+        });
+        assert_json_eq!(expected_json, actual_json);
+    }
+
+    #[test]
+    fn proposal_publish_response_json() {
+        let mock_publish_response = ProposalPublishResponse {
+            proposal: ProposalInfo {
+                id: "proposal-id".to_string(),
+                display_name: "Add reviews".to_string(),
+                status: ProposalStatus::OPEN,
+                source_variant: "current".to_string(),
+            },
+            proposal_url: "https://studio.apollographql.com/graph/graph/proposals/proposal-id"
+                .to_string(),
+        };
+        let actual_json: JsonOutput =
+            RoverOutput::ProposalPublishResponse(mock_publish_response).into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "proposal": {
+                    "id": "proposal-id",
+                    "display_name": "Add reviews",
+                    "status": "OPEN",
+                    "source_variant": "current"
+                },
+                "proposal_url": "https://studio.apollographql.com/graph/graph/proposals/proposal-id",
+                "success": true
+            },
+            "error": null
+        });
+        assert_json_eq!(expected_json, actual_json);
+    }
+
     #[test]
     fn subgraph_delete_success_json() {
         let mock_subgraph_delete = SubgraphDeleteResponse {
@@ -887,6 +1569,7 @@ mod tests {
             "error": {
                 "message": "Encountered 2 build errors while trying to build subgraph \"subgraph\" into supergraph \"name@current\".",
                 "code": "E029",
+                "code_name": "E029_BUILD_ERRORS",
                 "details": {
                     "build_errors": [
                         {
@@ -958,7 +1641,8 @@ mod tests {
                         }
                     ]
                 },
-                "code": "E027"
+                "code": "E027",
+                "code_name": "E027_COMPOSITION_ERRORS"
             }
         });
         assert_json_eq!(actual_json, expected_json);
@@ -1153,6 +1837,59 @@ View custom check details at: https://studio.apollographql.com/graph/my-graph/va
         assert_eq!(actual_text, expected_text);
     }
 
+    #[test]
+    fn check_success_response_with_non_blocking_downstream_failure_text() {
+        let mock_check_response = CheckWorkflowResponse {
+            default_target_url:
+                "https://studio.apollographql.com/graph/my-graph/variant/current/operationsCheck/1"
+                    .to_string(),
+            maybe_core_schema_modified: None,
+            maybe_operations_response: None,
+            maybe_lint_response: None,
+            maybe_proposals_response: None,
+            maybe_custom_response: None,
+            maybe_downstream_response: Some(DownstreamCheckResponse {
+                task_status: CheckTaskStatus::PASSED,
+                target_url: Some(
+                    "https://studio.apollographql.com/graph/my-graph/variant/current/downstream/1"
+                        .to_string(),
+                ),
+                blocking_variants: vec![],
+                variants: vec![
+                    DownstreamVariantResult {
+                        variant_name: "public".to_string(),
+                        blocking: true,
+                        status: CheckTaskStatus::PASSED,
+                    },
+                    DownstreamVariantResult {
+                        variant_name: "internal".to_string(),
+                        blocking: false,
+                        status: CheckTaskStatus::FAILED,
+                    },
+                ],
+            }),
+        };
+
+        assert!(mock_check_response.has_failed_downstream_variants());
+
+        let actual_text = RoverOutput::CheckWorkflowResponse(mock_check_response)
+            .get_stdout()
+            .expect("Expected response to be Ok")
+            .expect("Expected response to exist");
+        let actual_text = strip_ansi_codes(&actual_text);
+
+        assert!(actual_text.contains("Downstream Check [PASSED]:"));
+        assert!(actual_text
+            .lines()
+            .any(|line| line.contains("PASSED") && line.contains("public")));
+        assert!(actual_text
+            .lines()
+            .any(|line| line.contains("FAILED") && line.contains("internal")));
+        assert!(actual_text.ends_with(
+            "View downstream check details at: https://studio.apollographql.com/graph/my-graph/variant/current/downstream/1"
+        ));
+    }
+
     #[test]
     fn check_failure_response_json() {
         let graph_ref = GraphRef {
@@ -1319,6 +2056,7 @@ View custom check details at: https://studio.apollographql.com/graph/my-graph/va
             "error": {
                 "message": "The changes in the schema you proposed caused operation, linter, proposal and custom checks to fail.",
                 "code": "E043",
+                "code_name": "E043_CHECK_FAILED",
             }
         });
         assert_json_eq!(expected_json, actual_json);
@@ -1460,6 +2198,7 @@ View custom check details at: https://studio.apollographql.com/graph/my-graph/va
             "error": {
                 "message": "Encountered 2 build errors while trying to build subgraph \"subgraph\" into supergraph \"name@current\".",
                 "code": "E029",
+                "code_name": "E029_BUILD_ERRORS",
                 "details": {
                     "build_errors": [
                         {
@@ -1557,6 +2296,126 @@ View custom check details at: https://studio.apollographql.com/graph/my-graph/va
         assert_json_eq!(expected_json, actual_json);
     }
 
+    #[test]
+    fn subgraph_introspection_with_subscriptions_json() {
+        let actual_json: JsonOutput = RoverOutput::SubgraphIntrospection {
+            sdl: "type Subscription { reviewAdded: Review }".to_string(),
+            subscriptions: Some(SubscriptionSupport {
+                url: "http://localhost:4001/graphql".parse().unwrap(),
+                protocol: "graphql-transport-ws".to_string(),
+            }),
+        }
+        .into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "introspection_response": "type Subscription { reviewAdded: Review }",
+                "subscriptions": {
+                    "url": "http://localhost:4001/graphql",
+                    "protocol": "graphql-transport-ws"
+                },
+                "success": true
+            },
+            "error": null
+        });
+        assert_json_eq!(expected_json, actual_json);
+    }
+
+    #[test]
+    fn meta_migrations_json() {
+        const MIGRATION: Migration = Migration {
+            version: "0.24.0",
+            kind: MigrationKind::RemovedFlag,
+            summary: "`--output json` was removed.",
+            migration: "Pass `--format json` instead.",
+            triggers: &[],
+        };
+        let actual_json: JsonOutput = RoverOutput::MetaMigrations(vec![&MIGRATION]).into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "migrations": [{
+                    "version": "0.24.0",
+                    "kind": "REMOVED_FLAG",
+                    "summary": "`--output json` was removed.",
+                    "migration": "Pass `--format json` instead.",
+                }],
+                "success": true
+            },
+            "error": null
+        });
+        assert_json_eq!(expected_json, actual_json);
+    }
+
+    #[test]
+    fn plugin_list_json() {
+        let actual_json: JsonOutput = RoverOutput::PluginList(vec![InstalledPlugin {
+            name: "supergraph".to_string(),
+            version: Version::new(2, 9, 3),
+            path: Utf8PathBuf::from("/home/me/.rover/bin/supergraph-v2.9.3"),
+            size_bytes: 52428800,
+            pinned: true,
+        }])
+        .into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "plugins": [{
+                    "name": "supergraph",
+                    "version": "2.9.3",
+                    "path": "/home/me/.rover/bin/supergraph-v2.9.3",
+                    "size_bytes": 52428800,
+                    "pinned": true,
+                }],
+                "success": true
+            },
+            "error": null
+        });
+        assert_json_eq!(expected_json, actual_json);
+    }
+
+    #[cfg(feature = "composition-js")]
+    #[test]
+    fn dev_crash_list_json() {
+        use crate::command::dev::legacy::router::CrashReport;
+
+        let actual_json: JsonOutput = RoverOutput::DevCrashList(vec![CrashBundle {
+            path: Utf8PathBuf::from("/tmp/supergraphAbc123/crashes/20261017T120000Z-4242"),
+            report: CrashReport {
+                crashed_at: "2026-10-17T12:00:00Z".parse().unwrap(),
+                pid: 4242,
+                exit_code: None,
+                signal: Some(11),
+                uptime_secs: 90,
+                supergraph_sha256: Some("abc123".to_string()),
+                command: "router --dev".to_string(),
+            },
+        }])
+        .into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "crashes": [{
+                    "path": "/tmp/supergraphAbc123/crashes/20261017T120000Z-4242",
+                    "crashed_at": "2026-10-17T12:00:00Z",
+                    "pid": 4242,
+                    "exit_code": null,
+                    "signal": 11,
+                    "uptime_secs": 90,
+                    "supergraph_sha256": "abc123",
+                    "command": "router --dev",
+                }],
+                "success": true
+            },
+            "error": null
+        });
+        assert_json_eq!(expected_json, actual_json);
+    }
+
     #[test]
     fn error_explanation_json() {
         let actual_json: JsonOutput = RoverOutput::ErrorExplanation(
@@ -1603,7 +2462,8 @@ View custom check details at: https://studio.apollographql.com/graph/my-graph/va
             },
             "error": {
                 "message": "Some random error",
-                "code": null
+                "code": null,
+                "code_name": null
             }
         });
         assert_json_eq!(expected_json, actual_json);
@@ -1624,12 +2484,150 @@ View custom check details at: https://studio.apollographql.com/graph/my-graph/va
             },
             "error": {
                 "message": "Could not find subgraph \"invalid_subgraph\".",
-                "code": "E009"
+                "code": "E009",
+                "code_name": "E009_SUBGRAPH_NOT_FOUND"
+            }
+        });
+        assert_json_eq!(expected_json, actual_json)
+    }
+
+    #[test]
+    fn composition_timeout_error_message_json() {
+        let actual_json: JsonOutput =
+            RoverError::new(crate::composition::CompositionError::Timeout {
+                binary: "supergraph".into(),
+                timeout: std::time::Duration::from_secs(30),
+            })
+            .into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "success": false
+            },
+            "error": {
+                "message": "`supergraph compose` was stopped after running for 30s, the most allowed by `--composition-timeout`",
+                "code": "E049",
+                "code_name": "E049_COMPOSITION_TIMEOUT"
             }
         });
         assert_json_eq!(expected_json, actual_json)
     }
 
+    #[test]
+    fn porcelain_prints_bare_fields() {
+        let fetch = RoverOutput::FetchResponse(FetchResponse {
+            sdl: Sdl {
+                contents: "type Query { me: User }".to_string(),
+                r#type: SdlType::Graph,
+            },
+        });
+        assert_eq!(
+            fetch.get_porcelain().unwrap(),
+            Some("type Query { me: User }".to_string())
+        );
+        assert_eq!(RoverOutput::EmptySuccess.get_porcelain().unwrap(), None);
+        assert_eq!(
+            RoverOutput::Profiles(vec!["default".to_string(), "work".to_string()])
+                .get_porcelain()
+                .unwrap(),
+            Some("default\nwork".to_string())
+        );
+
+        let lint_failure = RoverError::new(RoverClientError::LintFailures {
+            lint_response: LintResponse {
+                diagnostics: Vec::new(),
+                file_name: "schema.graphql".to_string(),
+                proposed_schema: String::new(),
+            },
+        });
+        assert_eq!(lint_failure.get_porcelain(), Some("failed".to_string()));
+        assert_eq!(RoverError::new(anyhow!("oops")).get_porcelain(), None);
+    }
+
+    #[test]
+    fn fetched_schemas_convert_to_other_formats() {
+        let fetch = FetchResponse {
+            sdl: Sdl {
+                contents: "type Query {\n  # who's asking\n  me: String\n}\n".to_string(),
+                r#type: SdlType::Graph,
+            },
+        };
+        assert_eq!(
+            SchemaFormat::Sdl.output(fetch.clone()).unwrap(),
+            RoverOutput::FetchResponse(fetch.clone())
+        );
+        let minified = SchemaFormat::MinifiedSdl.output(fetch.clone()).unwrap();
+        assert_eq!(
+            minified.get_stdout().unwrap(),
+            Some("type Query{me:String}".to_string())
+        );
+        let actual_json: JsonOutput = minified.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "schema": "type Query{me:String}",
+                "format": "minified-sdl",
+                "success": true
+            },
+            "error": null
+        });
+        assert_json_eq!(expected_json, actual_json);
+        let introspection = SchemaFormat::IntrospectionJson.output(fetch).unwrap();
+        let introspection: Value =
+            serde_json::from_str(&introspection.get_stdout().unwrap().unwrap()).unwrap();
+        assert_eq!(
+            introspection["data"]["__schema"]["queryType"],
+            json!({ "name": "Query" })
+        );
+    }
+
+    #[test]
+    fn dry_run_json() {
+        let request = DryRunRequest {
+            operation_name: "GraphDeleteMutation".to_string(),
+            query: "mutation GraphDeleteMutation { a }".to_string(),
+            variables: json!({ "graph_id": "my-graph", "variant": "current" }),
+            effect: "delete the variant 'my-graph@current' and all of its subgraphs".to_string(),
+        };
+        let actual_json: JsonOutput = RoverOutput::DryRun(request).into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "dry_run": {
+                    "operation_name": "GraphDeleteMutation",
+                    "query": "mutation GraphDeleteMutation { a }",
+                    "variables": { "graph_id": "my-graph", "variant": "current" },
+                    "effect": "delete the variant 'my-graph@current' and all of its subgraphs"
+                },
+                "success": true
+            },
+            "error": null
+        });
+        assert_json_eq!(expected_json, actual_json)
+    }
+
+    #[test]
+    fn error_catalog_json() {
+        let actual_json: JsonOutput = RoverOutput::ErrorCatalog(vec![RoverErrorCode::E031]).into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "codes": [{
+                    "code": "E031",
+                    "name": "E031_REQUEST_TIMEOUT",
+                    "summary": "This error occurs when Rover made an HTTP request and it timed out."
+                }],
+                "success": true
+            },
+            "error": null
+        });
+        assert_json_eq!(expected_json, actual_json)
+    }
+
     #[test]
     fn composition_error_message_json() {
         let source = BuildErrors::from(vec![
@@ -1677,7 +2675,8 @@ View custom check details at: https://studio.apollographql.com/graph/my-graph/va
                     ],
                 },
                 "message": "Encountered 2 build errors while trying to build a supergraph.",
-                "code": "E029"
+                "code": "E029",
+                "code_name": "E029_BUILD_ERRORS"
             }
         });
         assert_json_eq!(expected_json, actual_json)
@@ -1723,6 +2722,7 @@ View custom check details at: https://studio.apollographql.com/graph/my-graph/va
                 },
                 "error": {
                   "code": "E042",
+                  "code_name": "E042_LINT_VIOLATIONS",
                   "message": "While linting the proposed schema, some rule violations were found"
                 },
                 "json_version": "1"
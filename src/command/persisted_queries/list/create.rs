@@ -0,0 +1,52 @@
+use clap::Parser;
+use serde::Serialize;
+
+use rover_client::operations::persisted_queries::create::{self, PersistedQueryListCreateInput};
+use rover_std::{progressln, Style};
+
+use crate::options::ProfileOpt;
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Create {
+    /// The Graph ID to create the list for.
+    #[arg(long)]
+    graph_id: String,
+
+    /// The name of the new list. Names can be changed later and don't need to be unique.
+    #[arg(long)]
+    name: String,
+
+    /// A variant of the graph to link the new list to. Can be passed more than once.
+    #[arg(long = "link-variant", value_name = "VARIANT")]
+    link_variants: Vec<String>,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+}
+
+impl Create {
+    pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+
+        progressln!(
+            "Creating persisted query list {} for {} using credentials from the {} profile.",
+            Style::PersistedQueryList.paint(&self.name),
+            Style::Link.paint(&self.graph_id),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        let response = create::run(
+            PersistedQueryListCreateInput {
+                graph_id: self.graph_id.clone(),
+                name: self.name.clone(),
+                linked_variants: self.link_variants.clone(),
+            },
+            &client,
+        )
+        .await?;
+
+        Ok(RoverOutput::PersistedQueryListCreateResponse(response))
+    }
+}
@@ -0,0 +1,57 @@
+use clap::Parser;
+use serde::Serialize;
+
+use rover_client::operations::persisted_queries::delete::{self, PersistedQueryListDeleteInput};
+use rover_std::{progressln, prompt, Style};
+
+use crate::options::ProfileOpt;
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Delete {
+    /// The Graph ID the list belongs to.
+    #[arg(long)]
+    graph_id: String,
+
+    /// The ID of the list to delete.
+    #[arg(long)]
+    list_id: String,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// Skips the step where the command asks for user confirmation before
+    /// deleting the list
+    #[arg(long)]
+    confirm: bool,
+}
+
+impl Delete {
+    pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+
+        progressln!(
+            "Deleting persisted query list {} from {} using credentials from the {} profile.",
+            Style::PersistedQueryList.paint(&self.list_id),
+            Style::Link.paint(&self.graph_id),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        if !self.confirm && !prompt::confirm_delete()? {
+            eprintln!("Delete cancelled by user");
+            return Ok(RoverOutput::EmptySuccess);
+        }
+
+        let response = delete::run(
+            PersistedQueryListDeleteInput {
+                graph_id: self.graph_id.clone(),
+                list_id: self.list_id.clone(),
+            },
+            &client,
+        )
+        .await?;
+
+        Ok(RoverOutput::PersistedQueryListDeleteResponse(response))
+    }
+}
@@ -0,0 +1,46 @@
+use clap::Parser;
+use serde::Serialize;
+
+use rover_client::operations::persisted_queries::link::{self, PersistedQueryListLinkInput};
+use rover_std::{progressln, Style};
+
+use crate::options::{GraphRefOpt, ProfileOpt};
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Link {
+    #[clap(flatten)]
+    graph: GraphRefOpt,
+
+    /// The ID of the list to link to the variant.
+    #[arg(long)]
+    list_id: String,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+}
+
+impl Link {
+    pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+
+        progressln!(
+            "Linking persisted query list {} to {} using credentials from the {} profile.",
+            Style::PersistedQueryList.paint(&self.list_id),
+            Style::Link.paint(self.graph.graph_ref.to_string()),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        let response = link::run(
+            PersistedQueryListLinkInput {
+                graph_ref: self.graph.graph_ref.clone(),
+                list_id: self.list_id.clone(),
+            },
+            &client,
+        )
+        .await?;
+
+        Ok(RoverOutput::PersistedQueryListLinkResponse(response))
+    }
+}
@@ -0,0 +1,41 @@
+use clap::Parser;
+use serde::Serialize;
+
+use rover_client::operations::persisted_queries::list::{self, PersistedQueryListsInput};
+use rover_std::{progressln, Style};
+
+use crate::options::ProfileOpt;
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct List {
+    /// The Graph ID to list persisted query lists for.
+    #[arg(long)]
+    graph_id: String,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+}
+
+impl List {
+    pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+
+        progressln!(
+            "Listing persisted query lists for {} using credentials from the {} profile.",
+            Style::Link.paint(&self.graph_id),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        let response = list::run(
+            PersistedQueryListsInput {
+                graph_id: self.graph_id.clone(),
+            },
+            &client,
+        )
+        .await?;
+
+        Ok(RoverOutput::PersistedQueryLists(response))
+    }
+}
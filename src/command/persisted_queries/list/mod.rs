@@ -0,0 +1,42 @@
+mod create;
+mod delete;
+mod link;
+mod lists;
+
+use clap::Parser;
+use serde::Serialize;
+
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct List {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub enum Command {
+    /// Create a persisted query list for a graph
+    Create(create::Create),
+
+    /// List the persisted query lists for a graph
+    List(lists::List),
+
+    /// Delete a persisted query list that isn't linked to any variants
+    Delete(delete::Delete),
+
+    /// Link a persisted query list to a graph variant
+    Link(link::Link),
+}
+
+impl List {
+    pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        match &self.command {
+            Command::Create(command) => command.run(client_config).await,
+            Command::List(command) => command.run(client_config).await,
+            Command::Delete(command) => command.run(client_config).await,
+            Command::Link(command) => command.run(client_config).await,
+        }
+    }
+}
@@ -1,5 +1,7 @@
+mod list;
 mod publish;
 
+pub use list::List;
 pub use publish::Publish;
 
 use clap::Parser;
@@ -19,12 +21,16 @@ pub struct PersistedQueries {
 pub enum Command {
     /// Persist a list of queries (or mutations) to a graph in Apollo Studio
     Publish(persisted_queries::Publish),
+
+    /// Create, list, delete and link persisted query lists
+    List(persisted_queries::List),
 }
 
 impl PersistedQueries {
     pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
         match &self.command {
             Command::Publish(command) => command.run(client_config).await,
+            Command::List(command) => command.run(client_config).await,
         }
     }
 }
@@ -4,7 +4,7 @@ use rover_client::operations::persisted_queries::{
     name::{self, PersistedQueryListNameInput},
     publish::RelayPersistedQueryManifest,
 };
-use rover_std::Style;
+use rover_std::{progressln, Style};
 use serde::Serialize;
 
 use crate::options::{OptionalGraphRefOpt, PersistedQueriesManifestFormat, ProfileOpt};
@@ -90,7 +90,7 @@ impl Publish {
             (Some(_), Some(_), Some(_)) | (Some(_), Some(_), None) | (Some(_), None, Some(_)) => unreachable!("clap \"conflicts_with\" should make this impossible to reach")
         };
 
-        eprintln!(
+        progressln!(
             "Publishing operations to list {} for {} using credentials from the {} profile.",
             Style::Link.paint(list_name),
             Style::Link.paint(&graph_id),
@@ -0,0 +1,54 @@
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::command::install::{get_installer, Plugin, PluginInstaller};
+use crate::options::LicenseAccepter;
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult, PKG_NAME};
+
+#[derive(Debug, Serialize, Parser)]
+/// Install a plugin ahead of time, so it's available offline
+///
+/// Downloads the given version of the plugin into Rover's plugin directory, unless it's already
+/// installed there. Commands run with `--offline` or `--skip-update` use the versions installed
+/// this way. If the project in the current directory has pinned the plugin, a `latest` version
+/// installs the pinned version instead.
+pub struct Install {
+    /// The plugin and version to install, like `supergraph@=2.9.3`, `supergraph@latest-2`, or
+    /// `router@v1.57.1`
+    #[arg(value_name = "PLUGIN")]
+    #[serde(skip_serializing)]
+    plugin: Plugin,
+
+    /// Download the plugin again even if it's already installed
+    #[arg(long = "force", short = 'f')]
+    force: bool,
+
+    #[clap(flatten)]
+    elv2_license_accepter: LicenseAccepter,
+}
+
+impl Install {
+    pub async fn run(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+    ) -> RoverResult<RoverOutput> {
+        if self.plugin.requires_elv2_license() {
+            self.elv2_license_accepter
+                .require_elv2_license(&client_config)?;
+        }
+        let rover_installer =
+            get_installer(PKG_NAME.to_string(), self.force, override_install_path)?;
+        let path = PluginInstaller::new(client_config, rover_installer, self.force)
+            .install(&self.plugin, false)
+            .await?;
+        eprintln!(
+            "The '{}' plugin is installed at {}",
+            self.plugin.get_name(),
+            path
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
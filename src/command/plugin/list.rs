@@ -0,0 +1,23 @@
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::command::install::{list_installed_plugins, PluginPins};
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// List the plugin binaries Rover has installed
+///
+/// Shows each installed version of the `supergraph` and `router` plugins, how much disk space
+/// it takes up, and whether the project in the current directory has pinned it.
+pub struct List {}
+
+impl List {
+    pub fn run(&self, plugin_dir: Utf8PathBuf) -> RoverResult<RoverOutput> {
+        let pins = PluginPins::for_current_dir()?;
+        Ok(RoverOutput::PluginList(list_installed_plugins(
+            &plugin_dir,
+            &pins,
+        )?))
+    }
+}
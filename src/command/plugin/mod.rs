@@ -0,0 +1,72 @@
+mod install;
+mod list;
+mod pin;
+mod prune;
+mod remove;
+
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::command::install::{get_installer, list_installed_plugins, PluginPins};
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult, PKG_NAME};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Plugin {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub enum Command {
+    /// List the plugin binaries Rover has installed
+    List(list::List),
+
+    /// Install a plugin ahead of time, so it's available offline
+    Install(install::Install),
+
+    /// Remove installed versions of a plugin
+    Remove(remove::Remove),
+
+    /// Remove installed plugin versions that are no longer used
+    Prune(prune::Prune),
+
+    /// Pin the version of a plugin that Rover uses for the current project
+    Pin(pin::Pin),
+}
+
+impl Plugin {
+    pub async fn run(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+        dry_run: bool,
+    ) -> RoverResult<RoverOutput> {
+        match &self.command {
+            Command::List(command) => command.run(plugin_dir(override_install_path)?),
+            Command::Install(command) => command.run(override_install_path, client_config).await,
+            Command::Remove(command) => command.run(plugin_dir(override_install_path)?, dry_run),
+            Command::Prune(command) => command.run(plugin_dir(override_install_path)?, dry_run),
+            Command::Pin(command) => command.run(),
+        }
+    }
+}
+
+/// The space `rover plugin prune` would free in `plugin_dir`, or `None` if the installed plugins
+/// can't be listed
+pub(crate) fn reclaimable_bytes(plugin_dir: &Utf8PathBuf) -> Option<u64> {
+    let pins = PluginPins::for_current_dir().ok()?;
+    let installed_plugins = list_installed_plugins(plugin_dir, &pins).ok()?;
+    Some(
+        prune::unused_plugins(&installed_plugins)
+            .iter()
+            .map(|plugin| plugin.size_bytes)
+            .sum(),
+    )
+}
+
+/// The directory Rover installs plugins into, which is `~/.rover/bin` unless overridden
+fn plugin_dir(override_install_path: Option<Utf8PathBuf>) -> RoverResult<Utf8PathBuf> {
+    Ok(get_installer(PKG_NAME.to_string(), false, override_install_path)?.get_bin_dir_path()?)
+}
@@ -0,0 +1,59 @@
+use std::env;
+
+use anyhow::anyhow;
+use apollo_federation_types::config::{FederationVersion, RouterVersion};
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::command::install::{find_project_config, write_pin, Plugin, PROJECT_CONFIG_PATH};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// Pin the version of a plugin that Rover uses for the current project
+///
+/// The pin is written to the `plugins` key of the project's `.apollo/config.yaml`, found by
+/// looking in the current directory and then each of its parents. If there isn't one, it's
+/// created in the current directory. Whenever Rover would use the latest version of a plugin with
+/// the same major version as the pin, such as for `federation_version: 2` or `rover dev`, it
+/// uses the pinned version instead.
+pub struct Pin {
+    /// The plugin and exact version to pin, like `supergraph@=2.9.3` or `router@v1.57.1`
+    #[arg(value_name = "PLUGIN")]
+    #[serde(skip_serializing)]
+    plugin: Plugin,
+}
+
+impl Pin {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let version = match &self.plugin {
+            Plugin::Supergraph(
+                FederationVersion::ExactFedOne(version) | FederationVersion::ExactFedTwo(version),
+            )
+            | Plugin::Router(RouterVersion::Exact(version)) => version,
+            _ => {
+                let mut err = RoverError::new(anyhow!(
+                    "Only exact plugin versions can be pinned, but '{}' is not one.",
+                    self.plugin.get_tarball_version()
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Pass an exact version, like `supergraph@=2.9.3` or `router@v1.57.1`."
+                        .to_string(),
+                ));
+                return Err(err);
+            }
+        };
+
+        let current_dir = Utf8PathBuf::try_from(env::current_dir()?)?;
+        let config_path = find_project_config(&current_dir)
+            .unwrap_or_else(|| current_dir.join(PROJECT_CONFIG_PATH));
+        write_pin(&config_path, &self.plugin.get_name(), version)?;
+        eprintln!(
+            "Pinned the '{}' plugin to v{} in {}",
+            self.plugin.get_name(),
+            version,
+            config_path
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
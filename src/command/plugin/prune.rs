@@ -0,0 +1,113 @@
+use std::{collections::HashSet, fs};
+
+use anyhow::Context;
+
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::command::install::{list_installed_plugins, InstalledPlugin, PluginPins};
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// Remove installed plugin versions that are no longer used
+///
+/// For each plugin, Rover keeps the newest installed version of every major version, since
+/// that's the one it runs for `latest` versions, along with any version that the project in the
+/// current directory has pinned. Every other version is removed. With the global `--dry-run`
+/// flag, the versions that would be removed are listed instead.
+pub struct Prune {}
+
+impl Prune {
+    pub fn run(&self, plugin_dir: Utf8PathBuf, dry_run: bool) -> RoverResult<RoverOutput> {
+        let pins = PluginPins::for_current_dir()?;
+        let installed_plugins = list_installed_plugins(&plugin_dir, &pins)?;
+        let unused_plugins = unused_plugins(&installed_plugins);
+        if unused_plugins.is_empty() {
+            eprintln!("There are no unused plugin versions to remove.");
+            return Ok(RoverOutput::EmptySuccess);
+        }
+
+        let mut freed_bytes = 0;
+        for plugin in &unused_plugins {
+            if dry_run {
+                eprintln!("Would remove '{}' v{}", plugin.name, plugin.version);
+            } else {
+                fs::remove_file(&plugin.path)
+                    .with_context(|| format!("could not remove {}", plugin.path))?;
+                eprintln!("Removed '{}' v{}", plugin.name, plugin.version);
+            }
+            freed_bytes += plugin.size_bytes;
+        }
+        eprintln!(
+            "{} {} plugin {}, freeing {:.1} MiB",
+            if dry_run {
+                "Pruning would remove"
+            } else {
+                "Removed"
+            },
+            unused_plugins.len(),
+            if unused_plugins.len() == 1 {
+                "version"
+            } else {
+                "versions"
+            },
+            freed_bytes as f64 / (1024.0 * 1024.0)
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
+
+/// The plugins that are neither pinned nor the newest installed version of their major version.
+/// `installed_plugins` must be sorted by name and then version, which is how they're listed
+pub(super) fn unused_plugins(installed_plugins: &[InstalledPlugin]) -> Vec<&InstalledPlugin> {
+    let mut newest = HashSet::new();
+    installed_plugins
+        .iter()
+        .rev()
+        .filter(|plugin| {
+            let is_newest = newest.insert((plugin.name.as_str(), plugin.version.major));
+            !is_newest && !plugin.pinned
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8PathBuf;
+    use semver::Version;
+    use speculoos::prelude::*;
+
+    use super::unused_plugins;
+    use crate::command::install::InstalledPlugin;
+
+    fn installed(name: &str, version: &str, pinned: bool) -> InstalledPlugin {
+        InstalledPlugin {
+            name: name.to_string(),
+            version: Version::parse(version).unwrap(),
+            path: Utf8PathBuf::from(format!("{name}-v{version}")),
+            size_bytes: 1,
+            pinned,
+        }
+    }
+
+    #[test]
+    fn test_unused_plugins_keeps_newest_of_each_major_and_pins() {
+        let installed_plugins = vec![
+            installed("router", "1.56.0", false),
+            installed("router", "1.57.1", false),
+            installed("supergraph", "0.37.1", false),
+            installed("supergraph", "2.8.0", false),
+            installed("supergraph", "2.8.1", true),
+            installed("supergraph", "2.9.3", false),
+        ];
+        let unused = unused_plugins(&installed_plugins)
+            .into_iter()
+            .map(|plugin| format!("{}-v{}", plugin.name, plugin.version))
+            .collect::<Vec<_>>();
+        assert_that!(unused).is_equal_to(vec![
+            "supergraph-v2.8.0".to_string(),
+            "router-v1.56.0".to_string(),
+        ]);
+    }
+}
@@ -0,0 +1,137 @@
+use std::fs;
+
+use anyhow::{anyhow, Context};
+use apollo_federation_types::config::{FederationVersion, RouterVersion};
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::command::install::{list_installed_plugins, InstalledPlugin, Plugin, PluginPins};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// Remove installed versions of a plugin
+///
+/// An exact version, like `supergraph@=2.8.0`, removes just that version. A `latest` version, like
+/// `supergraph@latest-2`, removes every installed version with that major version. Versions the
+/// project in the current directory has pinned are kept unless `--force` is passed. With the
+/// global `--dry-run` flag, the versions that would be removed are listed instead.
+pub struct Remove {
+    /// The plugin and version to remove, like `supergraph@=2.8.0` or `router@latest`
+    #[arg(value_name = "PLUGIN")]
+    #[serde(skip_serializing)]
+    plugin: Plugin,
+
+    /// Remove versions the current project has pinned, too
+    #[arg(long = "force", short = 'f')]
+    force: bool,
+}
+
+impl Remove {
+    pub fn run(&self, plugin_dir: Utf8PathBuf, dry_run: bool) -> RoverResult<RoverOutput> {
+        let pins = PluginPins::for_current_dir()?;
+        let installed_plugins = list_installed_plugins(&plugin_dir, &pins)?;
+        let (pinned, matching): (Vec<_>, Vec<_>) = installed_plugins
+            .iter()
+            .filter(|installed| matches(&self.plugin, installed))
+            .partition(|installed| installed.pinned && !self.force);
+
+        if matching.is_empty() {
+            let err = if let Some(pinned) = pinned.first() {
+                let mut err = RoverError::new(anyhow!(
+                    "The '{}' plugin v{} is pinned by {}, so it wasn't removed.",
+                    pinned.name,
+                    pinned.version,
+                    pins.config_path().map(|p| p.as_str()).unwrap_or_default()
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Pass `--force` to remove it anyway. It's downloaded again the next time it's needed.".to_string(),
+                ));
+                err
+            } else {
+                let mut err = RoverError::new(anyhow!(
+                    "There are no installed versions of the '{}' plugin matching '{}' in {}.",
+                    self.plugin.get_name(),
+                    self.plugin.get_tarball_version(),
+                    plugin_dir
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Run `rover plugin list` to see which versions are installed.".to_string(),
+                ));
+                err
+            };
+            return Err(err);
+        }
+
+        for plugin in &pinned {
+            eprintln!(
+                "Keeping '{}' v{} because it's pinned for this project",
+                plugin.name, plugin.version
+            );
+        }
+        for plugin in &matching {
+            if dry_run {
+                eprintln!("Would remove '{}' v{}", plugin.name, plugin.version);
+            } else {
+                fs::remove_file(&plugin.path)
+                    .with_context(|| format!("could not remove {}", plugin.path))?;
+                eprintln!("Removed '{}' v{}", plugin.name, plugin.version);
+            }
+        }
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
+
+/// Whether `installed` is the exact version `plugin` names, or has the major version of a
+/// `latest` one
+fn matches(plugin: &Plugin, installed: &InstalledPlugin) -> bool {
+    if plugin.get_name() != installed.name {
+        return false;
+    }
+    match plugin {
+        Plugin::Supergraph(
+            FederationVersion::ExactFedOne(version) | FederationVersion::ExactFedTwo(version),
+        )
+        | Plugin::Router(RouterVersion::Exact(version)) => version == &installed.version,
+        Plugin::Supergraph(FederationVersion::LatestFedOne) => installed.version.major == 0,
+        Plugin::Supergraph(FederationVersion::LatestFedTwo) => installed.version.major == 2,
+        Plugin::Router(RouterVersion::Latest) => installed.version.major == 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use camino::Utf8PathBuf;
+    use rstest::rstest;
+    use semver::Version;
+    use speculoos::prelude::*;
+
+    use super::matches;
+    use crate::command::install::{InstalledPlugin, Plugin};
+
+    #[rstest]
+    #[case::exact("supergraph@=2.8.0", "supergraph", "2.8.0", true)]
+    #[case::other_exact("supergraph@=2.8.1", "supergraph", "2.8.0", false)]
+    #[case::latest_major("supergraph@latest-2", "supergraph", "2.8.0", true)]
+    #[case::other_major("supergraph@latest-0", "supergraph", "2.8.0", false)]
+    #[case::fed_one("supergraph@latest-0", "supergraph", "0.37.1", true)]
+    #[case::router("router@latest", "router", "1.57.1", true)]
+    #[case::other_plugin("router@latest", "supergraph", "1.0.0", false)]
+    fn test_matches(
+        #[case] plugin: &str,
+        #[case] name: &str,
+        #[case] version: &str,
+        #[case] expected: bool,
+    ) {
+        let installed = InstalledPlugin {
+            name: name.to_string(),
+            version: Version::parse(version).unwrap(),
+            path: Utf8PathBuf::from(format!("{name}-v{version}")),
+            size_bytes: 1,
+            pinned: false,
+        };
+        assert_that!(matches(&Plugin::from_str(plugin).unwrap(), &installed)).is_equal_to(expected);
+    }
+}
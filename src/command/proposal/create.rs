@@ -0,0 +1,96 @@
+use clap::Parser;
+use serde::Serialize;
+
+use rover_client::operations::proposal::create::{self, ProposalCreateInput};
+use rover_client::operations::proposal::publish::{self, ProposalPublishInput};
+use rover_client::shared::GitContext;
+use rover_std::Style;
+
+use crate::options::{GraphRefOpt, ProfileOpt, SubgraphOpt};
+use crate::utils::client::StudioClientConfig;
+use crate::utils::parsers::FileDescriptorType;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Create {
+    #[clap(flatten)]
+    graph: GraphRefOpt,
+
+    #[clap(flatten)]
+    subgraph: SubgraphOpt,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// The subgraph schema to propose. You can pass `-` to use stdin instead of a file.
+    #[arg(long, short = 's', alias = "schema")]
+    #[serde(skip_serializing)]
+    from_schema: FileDescriptorType,
+
+    /// The name of the proposal, as shown in Apollo Studio
+    #[arg(long)]
+    #[serde(skip_serializing)]
+    display_name: String,
+
+    /// A description of what the proposal changes and why
+    #[arg(long)]
+    #[serde(skip_serializing)]
+    description: Option<String>,
+
+    /// A summary of this revision of the proposal. Defaults to the name of the proposal.
+    #[arg(long)]
+    #[serde(skip_serializing)]
+    summary: Option<String>,
+
+    /// The routing URL of the subgraph, if the proposal adds a new subgraph
+    #[arg(long)]
+    #[serde(skip_serializing)]
+    routing_url: Option<String>,
+}
+
+impl Create {
+    pub async fn run(
+        &self,
+        client_config: StudioClientConfig,
+        git_context: GitContext,
+    ) -> RoverResult<RoverOutput> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        let proposed_schema = self
+            .from_schema
+            .read_file_descriptor("SDL", &mut std::io::stdin())?;
+
+        eprintln!(
+            "Opening a proposal against {} using credentials from the {} profile.",
+            Style::Link.paint(self.graph.graph_ref.to_string()),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        let create_response = create::run(
+            ProposalCreateInput {
+                graph_ref: self.graph.graph_ref.clone(),
+                display_name: self.display_name.clone(),
+                description: self.description.clone(),
+            },
+            &client,
+        )
+        .await?;
+
+        let publish_response = publish::run(
+            ProposalPublishInput {
+                proposal_id: create_response.proposal.id,
+                subgraph: self.subgraph.subgraph_name.clone(),
+                url: self.routing_url.clone(),
+                schema: proposed_schema,
+                summary: self
+                    .summary
+                    .clone()
+                    .unwrap_or_else(|| self.display_name.clone()),
+                git_context,
+            },
+            &client,
+        )
+        .await?;
+
+        Ok(RoverOutput::ProposalPublishResponse(publish_response))
+    }
+}
@@ -0,0 +1,52 @@
+use clap::Parser;
+use serde::Serialize;
+
+use rover_client::operations::proposal::list::{self, ProposalListInput};
+use rover_client::shared::ProposalStatus;
+use rover_std::{progressln, Style};
+
+use crate::options::{GraphRefOpt, ProfileOpt};
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct List {
+    #[clap(flatten)]
+    graph: GraphRefOpt,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// Only list proposals with this status. One of APPROVED, CLOSED, DRAFT, IMPLEMENTED or
+    /// OPEN. Can be passed more than once.
+    #[arg(long)]
+    status: Vec<ProposalStatus>,
+
+    /// The maximum number of proposals to list
+    #[arg(long)]
+    limit: Option<i64>,
+}
+
+impl List {
+    pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+
+        progressln!(
+            "Listing proposals for {} using credentials from the {} profile.",
+            Style::Link.paint(self.graph.graph_ref.to_string()),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        let list_details = list::run(
+            ProposalListInput {
+                graph_ref: self.graph.graph_ref.clone(),
+                statuses: self.status.clone(),
+                limit: self.limit,
+            },
+            &client,
+        )
+        .await?;
+
+        Ok(RoverOutput::ProposalList(list_details))
+    }
+}
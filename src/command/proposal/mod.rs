@@ -0,0 +1,43 @@
+mod create;
+mod list;
+mod update;
+
+use clap::Parser;
+use serde::Serialize;
+
+use rover_client::shared::GitContext;
+
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Proposal {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub enum Command {
+    /// Open a schema proposal from a local subgraph schema
+    Create(create::Create),
+
+    /// List the schema proposals opened against a graph variant
+    List(list::List),
+
+    /// Publish a local subgraph schema as a new revision of a schema proposal
+    Update(update::Update),
+}
+
+impl Proposal {
+    pub async fn run(
+        &self,
+        client_config: StudioClientConfig,
+        git_context: GitContext,
+    ) -> RoverResult<RoverOutput> {
+        match &self.command {
+            Command::Create(command) => command.run(client_config, git_context).await,
+            Command::List(command) => command.run(client_config).await,
+            Command::Update(command) => command.run(client_config, git_context).await,
+        }
+    }
+}
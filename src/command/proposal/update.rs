@@ -0,0 +1,77 @@
+use clap::Parser;
+use serde::Serialize;
+
+use rover_client::operations::proposal::publish::{self, ProposalPublishInput};
+use rover_client::shared::GitContext;
+use rover_std::Style;
+
+use crate::options::{ProfileOpt, SubgraphOpt};
+use crate::utils::client::StudioClientConfig;
+use crate::utils::parsers::FileDescriptorType;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Update {
+    /// The ID of the proposal to update, as shown by `rover proposal list`
+    #[arg(value_name = "PROPOSAL_ID")]
+    #[serde(skip_serializing)]
+    proposal_id: String,
+
+    #[clap(flatten)]
+    subgraph: SubgraphOpt,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// The subgraph schema to propose. You can pass `-` to use stdin instead of a file.
+    #[arg(long, short = 's', alias = "schema")]
+    #[serde(skip_serializing)]
+    from_schema: FileDescriptorType,
+
+    /// A summary of this revision of the proposal
+    #[arg(long)]
+    #[serde(skip_serializing)]
+    summary: Option<String>,
+
+    /// The routing URL of the subgraph, if the proposal adds a new subgraph
+    #[arg(long)]
+    #[serde(skip_serializing)]
+    routing_url: Option<String>,
+}
+
+impl Update {
+    pub async fn run(
+        &self,
+        client_config: StudioClientConfig,
+        git_context: GitContext,
+    ) -> RoverResult<RoverOutput> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        let proposed_schema = self
+            .from_schema
+            .read_file_descriptor("SDL", &mut std::io::stdin())?;
+
+        eprintln!(
+            "Publishing a new revision of subgraph {} to proposal {} using credentials from the {} profile.",
+            Style::Link.paint(&self.subgraph.subgraph_name),
+            Style::Link.paint(&self.proposal_id),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        let publish_response = publish::run(
+            ProposalPublishInput {
+                proposal_id: self.proposal_id.clone(),
+                subgraph: self.subgraph.subgraph_name.clone(),
+                url: self.routing_url.clone(),
+                schema: proposed_schema,
+                summary: self.summary.clone().unwrap_or_else(|| {
+                    format!("Update the {} subgraph", self.subgraph.subgraph_name)
+                }),
+                git_context,
+            },
+            &client,
+        )
+        .await?;
+
+        Ok(RoverOutput::ProposalPublishResponse(publish_response))
+    }
+}
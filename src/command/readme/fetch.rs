@@ -1,5 +1,5 @@
 use clap::Parser;
-use rover_std::Style;
+use rover_std::{progressln, Style};
 use serde::Serialize;
 
 use crate::options::{GraphRefOpt, ProfileOpt};
@@ -22,7 +22,7 @@ impl Fetch {
         let client = client_config.get_authenticated_client(&self.profile)?;
         let graph_ref = self.graph.graph_ref.to_string();
 
-        eprintln!(
+        progressln!(
             "Fetching README for {} using credentials from the {} profile.",
             Style::Link.paint(graph_ref),
             Style::Command.paint(&self.profile.profile_name)
@@ -0,0 +1,42 @@
+use camino::Utf8PathBuf;
+use clap::Parser;
+use rover_std::{Fs, Style};
+use serde::Serialize;
+
+use crate::schema_mask::{mask_schema, MaskConfig};
+use crate::utils::parsers::FileDescriptorType;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Mask {
+    /// The schema file to mask. You can pass `-` to use stdin instead of a file.
+    #[arg(long, short = 's')]
+    #[serde(skip_serializing)]
+    schema: FileDescriptorType,
+
+    /// The mask config, a YAML file with the `types` and `fields` to rename, rename `patterns`,
+    /// and the `strip_directives` to remove. Descriptions are removed unless it sets
+    /// `strip_descriptions: false`
+    #[arg(long = "config", value_name = "MASK_CONFIG")]
+    #[serde(skip_serializing)]
+    config: Utf8PathBuf,
+}
+
+impl Mask {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let config = MaskConfig::parse(&Fs::read_file(&self.config)?)?;
+        let sdl = self
+            .schema
+            .read_file_descriptor("schema", &mut std::io::stdin())?;
+
+        let masked = mask_schema(&sdl, &config)?;
+        eprintln!(
+            "Masked {} using {}: renamed {} types and {} fields.",
+            Style::Path.paint(self.schema.to_string()),
+            Style::Path.paint(self.config.as_str()),
+            masked.renamed_types,
+            masked.renamed_fields
+        );
+        Ok(RoverOutput::MaskedSchema(masked))
+    }
+}
@@ -0,0 +1,27 @@
+mod mask;
+
+use clap::Parser;
+use serde::Serialize;
+
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Schema {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub enum Command {
+    /// Rename internal types and fields and strip internal details from a schema, so it can be
+    /// shared outside your organization
+    Mask(mask::Mask),
+}
+
+impl Schema {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        match &self.command {
+            Command::Mask(command) => command.run(),
+        }
+    }
+}
@@ -1,14 +1,18 @@
+use anyhow::anyhow;
 use clap::Parser;
 use rover_client::operations::subgraph::check::{self, SubgraphCheckAsyncInput};
-use rover_std::Style;
+use rover_std::{progressln, Style};
 use serde::Serialize;
 
 use rover_client::operations::subgraph::check_workflow::{self, CheckWorkflowInput};
 use rover_client::shared::{CheckConfig, GitContext};
+use rover_client::RoverClientError;
 
-use crate::options::{CheckConfigOpts, GraphRefOpt, ProfileOpt, SchemaOpt, SubgraphOpt};
+use crate::options::{
+    CheckConfigOpts, GraphRefOpt, PolicyOpts, ProfileOpt, SchemaOpt, SubgraphOpt,
+};
 use crate::utils::client::StudioClientConfig;
-use crate::{RoverOutput, RoverResult};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Check {
@@ -27,6 +31,14 @@ pub struct Check {
 
     #[clap(flatten)]
     config: CheckConfigOpts,
+
+    #[clap(flatten)]
+    policies: PolicyOpts,
+
+    /// Fail the check unless every change in the proposed schema is part of an approved
+    /// schema proposal
+    #[arg(long)]
+    require_approved_proposal: bool,
 }
 
 impl Check {
@@ -38,11 +50,15 @@ impl Check {
     ) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
 
-        let proposed_schema = self
+        let file_with_metadata = self
             .schema
-            .read_file_descriptor("SDL", &mut std::io::stdin())?;
+            .read_file_descriptor_with_metadata("SDL", &mut std::io::stdin())?;
+        if let Some(policies) = self.policies.load()? {
+            policies.check(&file_with_metadata.file_path, &file_with_metadata.schema)?;
+        }
+        let proposed_schema = file_with_metadata.schema;
 
-        eprintln!(
+        progressln!(
             "Checking the proposed schema for subgraph {} against {}",
             &self.subgraph.subgraph_name,
             Style::Link.paint(self.graph.graph_ref.to_string())
@@ -77,6 +93,34 @@ impl Check {
             )
             .await?;
 
+            if self.config.fail_on_downstream && check_res.has_failed_downstream_variants() {
+                return Err(RoverClientError::CheckWorkflowFailure {
+                    graph_ref: self.graph.graph_ref.clone(),
+                    check_response: Box::new(check_res),
+                }
+                .into());
+            }
+
+            if self.require_approved_proposal {
+                if check_res.maybe_proposals_response.is_none() {
+                    return Err(RoverError::new(anyhow!(
+                        "--require-approved-proposal was passed, but no proposals check ran for {}",
+                        self.graph.graph_ref
+                    ))
+                    .with_suggestion(RoverErrorSuggestion::Adhoc(
+                        "Turn on proposal checks in the settings of your graph in Apollo Studio."
+                            .to_string(),
+                    )));
+                }
+                if check_res.has_unapproved_proposal_changes() {
+                    return Err(RoverClientError::CheckWorkflowFailure {
+                        graph_ref: self.graph.graph_ref.clone(),
+                        check_response: Box::new(check_res),
+                    }
+                    .into());
+                }
+            }
+
             Ok(RoverOutput::CheckWorkflowResponse(check_res))
         }
     }
@@ -1,12 +1,41 @@
+#[cfg(feature = "composition-js")]
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "composition-js")]
+use anyhow::anyhow;
+#[cfg(feature = "composition-js")]
+use apollo_federation_types::config::{FederationVersion, SchemaSource, SupergraphConfig};
+#[cfg(feature = "composition-js")]
+use apollo_parser::{cst, cst::CstNode, Parser as SdlParser};
+#[cfg(feature = "composition-js")]
+use camino::Utf8PathBuf;
 use clap::Parser;
+#[cfg(feature = "composition-js")]
+use rover_client::blocking::StudioClient;
+#[cfg(feature = "composition-js")]
+use rover_client::shared::GraphRef;
 use serde::Serialize;
+#[cfg(feature = "composition-js")]
+use serde_json::{json, Value};
+#[cfg(feature = "composition-js")]
+use tap::TapFallible;
 
+#[cfg(feature = "composition-js")]
+use crate::command::supergraph::compose::do_compose::Compose;
 use crate::options::{GraphRefOpt, ProfileOpt, SubgraphOpt};
+#[cfg(feature = "composition-js")]
+use crate::options::{LicenseAccepter, PluginOpts};
+#[cfg(feature = "composition-js")]
+use crate::schema_diff::SchemaDiff;
 use crate::utils::client::StudioClientConfig;
+#[cfg(feature = "composition-js")]
+use crate::utils::supergraph_config::RemoteSubgraphs;
+#[cfg(feature = "composition-js")]
+use crate::{RoverError, RoverErrorSuggestion};
 use crate::{RoverOutput, RoverResult};
 
 use rover_client::operations::subgraph::delete::{self, SubgraphDeleteInput};
-use rover_std::{prompt, Style};
+use rover_std::{progressln, prompt, Style};
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Delete {
@@ -24,12 +53,31 @@ pub struct Delete {
     /// might occur
     #[arg(long)]
     confirm: bool,
+
+    #[cfg(feature = "composition-js")]
+    #[clap(flatten)]
+    elv2_license_accepter: LicenseAccepter,
 }
 
 impl Delete {
-    pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+    pub async fn run(
+        &self,
+        client_config: StudioClientConfig,
+        #[cfg_attr(not(feature = "composition-js"), allow(unused_variables))]
+        override_install_path: Option<Utf8PathBuf>,
+    ) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
-        eprintln!(
+
+        // with `--dry-run`, nothing is deleted, so work out what deleting would change instead
+        #[cfg(feature = "composition-js")]
+        if client_config.is_dry_run() {
+            return self
+                .impact(&client, client_config, override_install_path)
+                .await
+                .map(RoverOutput::SubgraphDeleteImpact);
+        }
+
+        progressln!(
             "Checking for build errors resulting from deleting subgraph {} from {} using credentials from the {} profile.",
             Style::Link.paint(&self.subgraph.subgraph_name),
             Style::Link.paint(self.graph.graph_ref.to_string()),
@@ -85,4 +133,257 @@ impl Delete {
             delete_response,
         })
     }
+
+    /// Composes the graph's subgraphs from GraphOS locally, with and without this subgraph, and
+    /// diffs the API schemas of the two supergraphs
+    #[cfg(feature = "composition-js")]
+    async fn impact(
+        &self,
+        client: &StudioClient,
+        client_config: StudioClientConfig,
+        override_install_path: Option<Utf8PathBuf>,
+    ) -> RoverResult<SubgraphDeleteImpact> {
+        let graph_ref = &self.graph.graph_ref;
+        let subgraph = &self.subgraph.subgraph_name;
+        progressln!(
+            "Composing the subgraphs of {} with and without {} to find what deleting it would change.",
+            Style::Link.paint(graph_ref.to_string()),
+            Style::Link.paint(subgraph)
+        );
+
+        let mut supergraph_config = RemoteSubgraphs::fetch(client, graph_ref)
+            .await?
+            .inner()
+            .clone();
+        if supergraph_config.get_federation_version().is_none() {
+            supergraph_config.set_federation_version(FederationVersion::LatestFedTwo);
+        }
+        let federation_version = supergraph_config.get_federation_version();
+        let mut sdls: BTreeMap<String, String> = supergraph_config
+            .clone()
+            .into_iter()
+            .filter_map(|(name, config)| match config.schema {
+                SchemaSource::Sdl { sdl } => Some((name, sdl)),
+                _ => None,
+            })
+            .collect();
+        let Some(deleted_sdl) = sdls.remove(subgraph) else {
+            let mut err = RoverError::new(anyhow!(
+                "Subgraph `{}` isn't published to {}, so there's nothing to delete.",
+                subgraph,
+                graph_ref
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Run `rover subgraph list {}` to see the subgraphs that are.",
+                graph_ref
+            )));
+            return Err(err);
+        };
+        let mut remaining_config = SupergraphConfig::new(
+            supergraph_config
+                .clone()
+                .into_iter()
+                .filter(|(name, _)| name != subgraph)
+                .collect(),
+            federation_version,
+        );
+
+        let compose = Compose::new(PluginOpts {
+            profile: self.profile.clone(),
+            elv2_license_accepter: self.elv2_license_accepter,
+            skip_update: false,
+            env: None,
+            schema_size_opts: Default::default(),
+            composition_limit_opts: Default::default(),
+            introspection_policy_opts: Default::default(),
+        });
+        let before = compose
+            .exec(
+                override_install_path.clone(),
+                client_config.clone(),
+                &mut supergraph_config,
+                None,
+            )
+            .await?
+            .supergraph_sdl;
+        // with no subgraphs left, there's no API schema left either
+        let after = if sdls.is_empty() {
+            String::new()
+        } else {
+            compose
+                .exec(
+                    override_install_path,
+                    client_config,
+                    &mut remaining_config,
+                    None,
+                )
+                .await
+                .tap_err(|_| {
+                    rover_std::errln!(
+                        "The remaining subgraphs of {} don't compose without subgraph `{}`.",
+                        graph_ref,
+                        subgraph
+                    )
+                })?
+                .supergraph_sdl
+        };
+
+        Ok(SubgraphDeleteImpact {
+            graph_ref: graph_ref.clone(),
+            subgraph: subgraph.clone(),
+            api_schema_changes: SchemaDiff::new(&before, &after)?,
+            entity_references: entity_references(&deleted_sdl, &sdls),
+        })
+    }
+}
+
+/// What deleting a subgraph would change, worked out by `rover subgraph delete --dry-run`
+#[cfg(feature = "composition-js")]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SubgraphDeleteImpact {
+    pub graph_ref: GraphRef,
+    pub subgraph: String,
+    /// How the API schema would change without the subgraph
+    pub api_schema_changes: SchemaDiff,
+    /// The entities of the deleted subgraph that each of the other subgraphs references
+    pub entity_references: BTreeMap<String, BTreeSet<String>>,
+}
+
+#[cfg(feature = "composition-js")]
+impl SubgraphDeleteImpact {
+    pub fn get_output(&self) -> String {
+        let mut output = format!(
+            "Deleting subgraph {} from {} would make these changes to the API schema:\n{}",
+            Style::Link.paint(&self.subgraph),
+            Style::Link.paint(self.graph_ref.to_string()),
+            self.api_schema_changes.get_output()
+        );
+        if self.entity_references.is_empty() {
+            output.push_str("\nNo other subgraphs reference its entities.");
+        } else {
+            output.push_str("\nThese subgraphs reference its entities:");
+            for (subgraph, entities) in &self.entity_references {
+                output.push_str(&format!(
+                    "\n  {}: {}",
+                    subgraph,
+                    entities.iter().cloned().collect::<Vec<_>>().join(", ")
+                ));
+            }
+        }
+        output
+    }
+
+    pub fn get_json(&self) -> Value {
+        json!({
+            "graph_ref": self.graph_ref.to_string(),
+            "subgraph": self.subgraph,
+            "changes": self.api_schema_changes.changes,
+            "entity_references": self.entity_references,
+        })
+    }
+}
+
+/// Finds the entities (types with `@key`) that `deleted_sdl` defines or extends, and which of
+/// them each of the other subgraphs references, whether by contributing fields to the entity or by
+/// using it as a type
+#[cfg(feature = "composition-js")]
+fn entity_references(
+    deleted_sdl: &str,
+    others: &BTreeMap<String, String>,
+) -> BTreeMap<String, BTreeSet<String>> {
+    let entities = entities(deleted_sdl);
+    others
+        .iter()
+        .filter_map(|(name, sdl)| {
+            let referenced: BTreeSet<String> =
+                named_types(sdl).intersection(&entities).cloned().collect();
+            (!referenced.is_empty()).then(|| (name.clone(), referenced))
+        })
+        .collect()
+}
+
+#[cfg(feature = "composition-js")]
+fn entities(sdl: &str) -> BTreeSet<String> {
+    let has_key = |directives: Option<cst::Directives>| {
+        directives
+            .iter()
+            .flat_map(|d| d.directives())
+            .any(|directive| {
+                directive
+                    .name()
+                    .is_some_and(|name| name.text().as_str() == "key")
+            })
+    };
+    SdlParser::new(sdl)
+        .parse()
+        .document()
+        .definitions()
+        .filter_map(|definition| match definition {
+            cst::Definition::ObjectTypeDefinition(def) if has_key(def.directives()) => def.name(),
+            cst::Definition::ObjectTypeExtension(def) if has_key(def.directives()) => def.name(),
+            cst::Definition::InterfaceTypeDefinition(def) if has_key(def.directives()) => {
+                def.name()
+            }
+            _ => None,
+        })
+        .map(|name| name.text().to_string())
+        .collect()
+}
+
+/// The names of the types that `sdl` defines, extends, or refers to
+#[cfg(feature = "composition-js")]
+fn named_types(sdl: &str) -> BTreeSet<String> {
+    let document = SdlParser::new(sdl).parse().document();
+    let defined = document
+        .definitions()
+        .filter_map(|definition| match definition {
+            cst::Definition::ObjectTypeDefinition(def) => def.name(),
+            cst::Definition::ObjectTypeExtension(def) => def.name(),
+            cst::Definition::InterfaceTypeDefinition(def) => def.name(),
+            cst::Definition::InterfaceTypeExtension(def) => def.name(),
+            _ => None,
+        })
+        .map(|name| name.text().to_string());
+    let referenced = document
+        .syntax()
+        .descendants()
+        .filter_map(cst::NamedType::cast)
+        .filter_map(|named| named.name())
+        .map(|name| name.text().to_string());
+    defined.chain(referenced).collect()
+}
+
+#[cfg(all(test, feature = "composition-js"))]
+mod tests {
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    const DELETED: &str = r#"
+        type Query { products: [Product] }
+        type Product @key(fields: "id") { id: ID! name: String }
+        type Category @key(fields: "id") { id: ID! }
+        type Internal { id: ID! }
+    "#;
+
+    #[rstest]
+    #[case::extends_the_entity(
+        r#"type Product @key(fields: "id") { id: ID! reviews: [String] }"#,
+        &["Product"]
+    )]
+    #[case::uses_the_entity_as_a_type(
+        "type Query { featured: Category } type Shelf { items: [Product!]! }",
+        &["Category", "Product"]
+    )]
+    #[case::only_shares_value_types("type Internal { id: ID! } type Query { me: ID }", &[])]
+    fn test_entity_references(#[case] other_sdl: &str, #[case] expected: &[&str]) {
+        let others = BTreeMap::from([("reviews".to_string(), other_sdl.to_string())]);
+        let references = entity_references(DELETED, &others);
+        let referenced: Vec<&str> = references
+            .get("reviews")
+            .map(|entities| entities.iter().map(String::as_str).collect())
+            .unwrap_or_default();
+        assert_that!(referenced).is_equal_to(expected.to_vec());
+    }
 }
@@ -2,9 +2,9 @@ use clap::Parser;
 use serde::Serialize;
 
 use rover_client::operations::subgraph::fetch::{self, SubgraphFetchInput};
-use rover_std::Style;
+use rover_std::{progressln, Style};
 
-use crate::options::{GraphRefOpt, ProfileOpt, SubgraphOpt};
+use crate::options::{GraphRefOpt, ProfileOpt, SchemaFormat, SubgraphOpt};
 use crate::utils::client::StudioClientConfig;
 use crate::{RoverOutput, RoverResult};
 
@@ -18,13 +18,17 @@ pub struct Fetch {
 
     #[clap(flatten)]
     profile: ProfileOpt,
+
+    /// Which format to print the schema in
+    #[arg(long = "schema-format", value_enum, default_value_t = SchemaFormat::Sdl)]
+    schema_format: SchemaFormat,
 }
 
 impl Fetch {
     pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
         let graph_ref = self.graph.graph_ref.to_string();
-        eprintln!(
+        progressln!(
             "Fetching SDL from {} (subgraph: {}) using credentials from the {} profile.",
             Style::Link.paint(graph_ref),
             Style::Link.paint(&self.subgraph.subgraph_name),
@@ -40,6 +44,6 @@ impl Fetch {
         )
         .await?;
 
-        Ok(RoverOutput::FetchResponse(fetch_response))
+        self.schema_format.output(fetch_response)
     }
 }
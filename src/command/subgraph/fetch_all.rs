@@ -0,0 +1,148 @@
+use apollo_federation_types::config::{SchemaSource, SubgraphConfig, SupergraphConfig};
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+
+use rover_std::{progressln, Fs, Style};
+
+use crate::composition::supergraph::config::resolver::SupergraphConfigResolver;
+use crate::options::{GraphRefOpt, ProfileOpt};
+use crate::utils::client::StudioClientConfig;
+use crate::utils::parsers::FileDescriptorType;
+use crate::{RoverOutput, RoverResult};
+
+/// The name of the supergraph config written next to the subgraph schemas
+const SUPERGRAPH_CONFIG_FILE: &str = "supergraph.yaml";
+
+#[derive(Debug, Serialize, Parser)]
+pub struct FetchAll {
+    #[clap(flatten)]
+    graph: GraphRefOpt,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// The directory to write the subgraph schemas and supergraph config to. Defaults to a
+    /// directory named after the graph
+    #[arg(long = "dir", value_name = "DIR")]
+    #[serde(skip_serializing)]
+    dir: Option<Utf8PathBuf>,
+}
+
+impl FetchAll {
+    pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        let graph_ref = &self.graph.graph_ref;
+        progressln!(
+            "Fetching every subgraph from {} using credentials from the {} profile.",
+            Style::Link.paint(graph_ref.to_string()),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        let supergraph_config = SupergraphConfigResolver::default()
+            .load_remote_subgraphs(&client, Some(graph_ref))
+            .await?
+            .load_from_file_descriptor(&mut std::io::stdin(), None::<&FileDescriptorType>)?
+            .fully_resolve_subgraphs(&client_config, &client, None)
+            .await?;
+
+        let dir = self
+            .dir
+            .clone()
+            .unwrap_or_else(|| Utf8PathBuf::from(&graph_ref.name));
+        let subgraphs = write_local_supergraph(&dir, supergraph_config.into())?;
+
+        Ok(RoverOutput::SubgraphFetchAllResponse {
+            graph_ref: graph_ref.clone(),
+            supergraph_config: dir.join(SUPERGRAPH_CONFIG_FILE),
+            subgraphs,
+        })
+    }
+}
+
+/// Writes every subgraph's schema to `<name>.graphql` in `dir`, along with a supergraph config that
+/// points at them, returning the names of the subgraphs that were written
+fn write_local_supergraph(
+    dir: &Utf8PathBuf,
+    supergraph_config: SupergraphConfig,
+) -> RoverResult<Vec<String>> {
+    Fs::create_dir_all(dir)?;
+    let federation_version = supergraph_config.get_federation_version();
+    let mut subgraphs = Vec::new();
+    let mut local_subgraphs = Vec::new();
+    for (name, subgraph) in supergraph_config {
+        let file = Utf8PathBuf::from(format!("{name}.graphql"));
+        if let SchemaSource::Sdl { sdl } = &subgraph.schema {
+            Fs::write_file(dir.join(&file), sdl)?;
+        }
+        local_subgraphs.push((
+            name.clone(),
+            SubgraphConfig {
+                routing_url: subgraph.routing_url,
+                schema: SchemaSource::File { file },
+            },
+        ));
+        subgraphs.push(name);
+    }
+    let mut local_supergraph_config = SupergraphConfig::from_iter(local_subgraphs);
+    if let Some(federation_version) = federation_version {
+        local_supergraph_config.set_federation_version(federation_version);
+    }
+    Fs::write_file(
+        dir.join(SUPERGRAPH_CONFIG_FILE),
+        serde_yaml::to_string(&local_supergraph_config)?,
+    )?;
+    Ok(subgraphs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use apollo_federation_types::config::{
+        FederationVersion, SchemaSource, SubgraphConfig, SupergraphConfig,
+    };
+    use assert_fs::{prelude::*, TempDir};
+    use camino::Utf8PathBuf;
+    use speculoos::prelude::*;
+
+    use super::write_local_supergraph;
+
+    #[test]
+    fn test_write_local_supergraph_points_the_supergraph_config_at_the_schemas() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = Utf8PathBuf::from_path_buf(temp_dir.child("mygraph").to_path_buf()).unwrap();
+        let subgraph = |name: &str| SubgraphConfig {
+            routing_url: Some(format!("http://{name}")),
+            schema: SchemaSource::Sdl {
+                sdl: format!("type Query {{ {name}: [String] }}"),
+            },
+        };
+        let supergraph_config = SupergraphConfig::new(
+            BTreeMap::from([
+                ("products".to_string(), subgraph("products")),
+                ("reviews".to_string(), subgraph("reviews")),
+            ]),
+            Some(FederationVersion::LatestFedTwo),
+        );
+
+        let subgraphs = write_local_supergraph(&dir, supergraph_config).unwrap();
+
+        assert_that!(subgraphs).is_equal_to(vec!["products".to_string(), "reviews".to_string()]);
+        temp_dir
+            .child("mygraph/products.graphql")
+            .assert("type Query { products: [String] }");
+        temp_dir
+            .child("mygraph/reviews.graphql")
+            .assert("type Query { reviews: [String] }");
+        let written = SupergraphConfig::new_from_yaml_file(dir.join("supergraph.yaml")).unwrap();
+        assert_that!(written.get_federation_version())
+            .is_equal_to(Some(FederationVersion::LatestFedTwo));
+        let written: BTreeMap<_, _> = written.into_iter().collect();
+        assert_that!(written["products"].routing_url)
+            .is_equal_to(Some("http://products".to_string()));
+        assert_that!(written["products"].schema).is_equal_to(SchemaSource::File {
+            file: Utf8PathBuf::from("products.graphql"),
+        });
+    }
+}
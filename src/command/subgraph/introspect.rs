@@ -4,17 +4,30 @@ use serde::Serialize;
 use std::{collections::HashMap, time::Duration};
 
 use rover_client::{
-    blocking::GraphQLClient,
+    blocking::{GraphQLClient, GraphQLTransport},
     operations::subgraph::introspect::{self, SubgraphIntrospectInput},
 };
 
 use crate::options::{IntrospectOpts, OutputOpts};
-use crate::{RoverOutput, RoverResult};
+use crate::utils::{graphql_ws, introspection_auth::IntrospectionAuthenticator};
+use crate::{RoverError, RoverOutput, RoverResult};
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Introspect {
     #[clap(flatten)]
     pub opts: IntrospectOpts,
+
+    /// Also find out whether the subgraph serves subscriptions over a `graphql-transport-ws`
+    /// websocket at the same endpoint, and report it alongside the schema
+    #[arg(long, conflicts_with = "watch")]
+    #[serde(skip_serializing)]
+    pub subscriptions: bool,
+
+    /// Credentials from a subgraph's introspection settings in a supergraph config, added to the
+    /// headers of every request
+    #[arg(skip)]
+    #[serde(skip)]
+    pub auth: Option<IntrospectionAuthenticator>,
 }
 
 impl Introspect {
@@ -29,29 +42,53 @@ impl Introspect {
                 .await
         } else {
             let sdl = self.exec(&client, true, retry_period).await?;
-            Ok(RoverOutput::Introspection(sdl))
+            if self.subscriptions {
+                let subscriptions = graphql_ws::negotiate_subscriptions(
+                    &client,
+                    self.opts.endpoint.clone(),
+                    &self.headers(),
+                )
+                .await
+                .map_err(RoverError::new)?;
+                Ok(RoverOutput::SubgraphIntrospection { sdl, subscriptions })
+            } else {
+                Ok(RoverOutput::Introspection(sdl))
+            }
         }
     }
 
-    pub async fn exec(
-        &self,
-        client: &Client,
-        should_retry: bool,
-        retry_period: Option<Duration>,
-    ) -> RoverResult<String> {
-        let client = GraphQLClient::new(self.opts.endpoint.as_ref(), client.clone(), retry_period);
-
-        // add the flag headers to a hashmap to pass along to rover-client
+    /// The flag headers, as the map rover-client takes
+    fn headers(&self) -> HashMap<String, String> {
         let mut headers = HashMap::new();
         if let Some(arg_headers) = &self.opts.headers {
             for (header_key, header_value) in arg_headers {
                 headers.insert(header_key.to_string(), header_value.to_string());
             }
         };
+        headers
+    }
 
-        let sdl = introspect::run(SubgraphIntrospectInput { headers }, &client, should_retry)
-            .await?
-            .result;
+    pub async fn exec(
+        &self,
+        client: &Client,
+        should_retry: bool,
+        retry_period: Option<Duration>,
+    ) -> RoverResult<String> {
+        let mut headers = self.headers();
+        if let Some(auth) = &self.auth {
+            headers.extend(auth.headers(client).await.map_err(RoverError::new)?);
+        }
+        let client = GraphQLClient::new(self.opts.endpoint.as_ref(), client.clone(), retry_period);
+        let sdl = introspect::run(
+            SubgraphIntrospectInput {
+                headers,
+                transport: GraphQLTransport::default(),
+            },
+            &client,
+            should_retry,
+        )
+        .await?
+        .result;
 
         Ok(sdl)
     }
@@ -1,19 +1,27 @@
+use anyhow::anyhow;
 use clap::Parser;
 use rover_client::operations::subgraph::lint::{self, LintSubgraphInput};
+use rover_client::shared::{GraphRef, LintResponse};
 use serde::Serialize;
 
-use crate::options::{GraphRefOpt, LintOpts, ProfileOpt, SchemaOpt, SubgraphOpt};
+use crate::lint::LocalLinter;
+use crate::options::{LintOpts, ProfileOpt, SchemaOpt};
 
 use crate::utils::client::StudioClientConfig;
 use crate::{RoverOutput, RoverResult};
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Lint {
-    #[clap(flatten)]
-    graph: GraphRefOpt,
+    /// <NAME>@<VARIANT> of graph in Apollo Studio.
+    /// @<VARIANT> may be left off, defaulting to @current
+    #[arg(value_name = "GRAPH_REF", required_unless_present = "local")]
+    #[serde(skip_serializing)]
+    graph_ref: Option<GraphRef>,
 
-    #[clap(flatten)]
-    subgraph: SubgraphOpt,
+    /// The name of the subgraph.
+    #[arg(long = "name", required_unless_present = "local")]
+    #[serde(skip_serializing)]
+    subgraph_name: Option<String>,
 
     #[clap(flatten)]
     profile: ProfileOpt,
@@ -24,27 +32,57 @@ pub struct Lint {
 
     #[clap(flatten)]
     lint: LintOpts,
+
+    /// Lint the schema offline with Rover's built-in rules and the project's `.apollo/lint-rules`
+    /// plugins instead of Studio's linter, configured by the project's `.apollo/lint.toml`
+    #[arg(long, conflicts_with = "ignore_existing_lint_violations")]
+    local: bool,
 }
 
 impl Lint {
     pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
-        let client = client_config.get_authenticated_client(&self.profile)?;
+        let policies = self.lint.policies.load()?;
 
         let file_with_metadata = self
             .schema
             .read_file_descriptor_with_metadata("SDL", &mut std::io::stdin())?;
 
-        let lint_result: rover_client::shared::LintResponse = lint::run(
+        if self.local {
+            let linter = LocalLinter::for_current_dir()?;
+            let mut diagnostics = linter.evaluate(&file_with_metadata.schema)?;
+            if let Some(policies) = policies {
+                diagnostics.extend(
+                    policies.evaluate(&file_with_metadata.file_path, &file_with_metadata.schema)?,
+                );
+            }
+            let lint_result = linter.conclude(LintResponse {
+                diagnostics,
+                file_name: file_with_metadata.file_path,
+                proposed_schema: file_with_metadata.schema,
+            })?;
+            return Ok(RoverOutput::LintResponse(lint_result));
+        }
+
+        // clap requires both unless `--local` is passed
+        let (Some(graph_ref), Some(subgraph_name)) = (&self.graph_ref, &self.subgraph_name) else {
+            return Err(anyhow!("Linting in Studio needs a graph ref and a subgraph name.").into());
+        };
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        let lint_result = lint::run(
             LintSubgraphInput {
-                graph_ref: self.graph.graph_ref.clone(),
+                graph_ref: graph_ref.clone(),
                 file_name: file_with_metadata.file_path,
                 proposed_schema: file_with_metadata.schema,
-                subgraph_name: self.subgraph.subgraph_name.clone(),
+                subgraph_name: subgraph_name.clone(),
                 ignore_existing: self.lint.ignore_existing_lint_violations,
             },
             &client,
         )
-        .await?;
+        .await;
+        let lint_result = match policies {
+            Some(policies) => policies.lint(lint_result)?,
+            None => lint_result?,
+        };
 
         Ok(RoverOutput::LintResponse(lint_result))
     }
@@ -1,8 +1,11 @@
+use chrono::Duration;
 use clap::Parser;
 use serde::Serialize;
 
-use rover_client::operations::subgraph::list::{self, SubgraphListInput};
-use rover_std::Style;
+use rover_client::operations::subgraph::list::{
+    self, SubgraphInfo, SubgraphListInput, SubgraphListResponse,
+};
+use rover_std::{progressln, Style};
 
 use crate::options::{GraphRefOpt, ProfileOpt};
 use crate::utils::client::StudioClientConfig;
@@ -19,9 +22,33 @@ pub struct List {
 
 impl List {
     pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let (root_url, subgraphs) = client_config
+            .response_cache()
+            .fetch(
+                "subgraph-list",
+                &[
+                    &self.profile.profile_name,
+                    &self.graph.graph_ref.to_string(),
+                ],
+                Duration::days(7),
+                self.fetch_subgraphs(&client_config),
+            )
+            .await?;
+
+        Ok(RoverOutput::SubgraphList(SubgraphListResponse {
+            subgraphs,
+            root_url,
+            graph_ref: self.graph.graph_ref.clone(),
+        }))
+    }
+
+    async fn fetch_subgraphs(
+        &self,
+        client_config: &StudioClientConfig,
+    ) -> RoverResult<(String, Vec<SubgraphInfo>)> {
         let client = client_config.get_authenticated_client(&self.profile)?;
 
-        eprintln!(
+        progressln!(
             "Listing subgraphs for {} using credentials from the {} profile.",
             Style::Link.paint(self.graph.graph_ref.to_string()),
             Style::Link.paint(&self.profile.profile_name)
@@ -35,6 +62,6 @@ impl List {
         )
         .await?;
 
-        Ok(RoverOutput::SubgraphList(list_details))
+        Ok((list_details.root_url, list_details.subgraphs))
     }
 }
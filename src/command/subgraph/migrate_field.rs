@@ -0,0 +1,971 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+use std::ops::Range;
+
+use anyhow::anyhow;
+use apollo_federation_types::config::{
+    FederationVersion, SchemaSource, SubgraphConfig, SupergraphConfig,
+};
+use apollo_parser::{cst, cst::CstNode, Parser as SdlParser, SyntaxNode};
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use rover_client::shared::GraphRef;
+use rover_std::{progressln, Fs, Style};
+
+use crate::command::supergraph::compose::do_compose::Compose;
+use crate::lint::rules::field_set_names;
+use crate::options::{GraphRefOpt, LicenseAccepter, PluginOpts, ProfileOpt};
+use crate::utils::client::StudioClientConfig;
+use crate::utils::supergraph_config::RemoteSubgraphs;
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult, StructuredFailure};
+
+/// The root operation types, whose fields can move between subgraphs without being entities
+const ROOT_TYPES: [&str; 3] = ["Query", "Mutation", "Subscription"];
+
+const BUILT_IN_SCALARS: [&str; 5] = ["String", "Int", "Float", "Boolean", "ID"];
+
+/// The federation directives of a field that only make sense in the subgraph it's moving from
+const SOURCE_ONLY_DIRECTIVES: [&str; 4] = ["external", "requires", "provides", "override"];
+
+#[derive(Debug, Serialize, Parser)]
+pub struct MigrateField {
+    #[clap(flatten)]
+    graph: GraphRefOpt,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// The field to move, as `Type.field`
+    #[arg(long = "field", value_name = "TYPE.FIELD", value_parser = parse_field)]
+    field: FieldCoordinate,
+
+    /// The subgraph that resolves the field now
+    #[arg(long = "from", value_name = "SUBGRAPH")]
+    from: String,
+
+    /// The subgraph to move the field to
+    #[arg(long = "to", value_name = "SUBGRAPH")]
+    to: String,
+
+    /// Write the schema each step publishes into this directory, as `<step>-<subgraph>.graphql`
+    #[arg(long = "schema-dir", value_name = "DIR")]
+    schema_dir: Option<Utf8PathBuf>,
+
+    #[clap(flatten)]
+    elv2_license_accepter: LicenseAccepter,
+}
+
+impl MigrateField {
+    pub async fn run(
+        &self,
+        client_config: StudioClientConfig,
+        override_install_path: Option<Utf8PathBuf>,
+    ) -> RoverResult<RoverOutput> {
+        let graph_ref = &self.graph.graph_ref;
+        if self.from == self.to {
+            return Err(RoverError::new(anyhow!(
+                "`--from` and `--to` are both `{}`, so there's nothing to move.",
+                self.from
+            )));
+        }
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        progressln!(
+            "Planning the move of {} from {} to {} in {} using credentials from the {} profile.",
+            Style::Command.paint(self.field.to_string()),
+            Style::Link.paint(&self.from),
+            Style::Link.paint(&self.to),
+            Style::Link.paint(graph_ref.to_string()),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        let mut supergraph_config = RemoteSubgraphs::fetch(&client, graph_ref)
+            .await?
+            .inner()
+            .clone();
+        if supergraph_config.get_federation_version().is_none() {
+            supergraph_config.set_federation_version(FederationVersion::LatestFedTwo);
+        }
+        let federation_version = supergraph_config.get_federation_version();
+        let subgraphs: BTreeMap<String, SubgraphConfig> = supergraph_config.into_iter().collect();
+        let sdl_of = |name: &str| match subgraphs.get(name).map(|config| &config.schema) {
+            Some(SchemaSource::Sdl { sdl }) => Ok(sdl.clone()),
+            _ => {
+                let mut err = RoverError::new(anyhow!(
+                    "Subgraph `{}` isn't published to {}.",
+                    name,
+                    graph_ref
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                    "Run `rover subgraph list {}` to see the subgraphs that are.",
+                    graph_ref
+                )));
+                Err(err)
+            }
+        };
+        let mut plan = FieldMigrationPlan::new(
+            graph_ref.clone(),
+            self.field.clone(),
+            &self.from,
+            &sdl_of(&self.from)?,
+            &self.to,
+            &sdl_of(&self.to)?,
+        )
+        .map_err(|err| RoverError::new(anyhow!("{}", err)))?;
+
+        let compose = Compose::new(PluginOpts {
+            profile: self.profile.clone(),
+            elv2_license_accepter: self.elv2_license_accepter,
+            skip_update: false,
+            env: None,
+            schema_size_opts: Default::default(),
+            composition_limit_opts: Default::default(),
+            introspection_policy_opts: Default::default(),
+        });
+        // each step builds on the ones before it, so the graph is composed with all of them applied
+        let mut published: BTreeMap<String, String> = BTreeMap::new();
+        for step in plan.steps.iter_mut() {
+            published.insert(step.subgraph.clone(), step.sdl.clone());
+            let mut config = SupergraphConfig::new(
+                subgraphs
+                    .iter()
+                    .map(|(name, config)| {
+                        let mut config = config.clone();
+                        if let Some(sdl) = published.get(name) {
+                            config.schema = SchemaSource::Sdl { sdl: sdl.clone() };
+                        }
+                        (name.clone(), config)
+                    })
+                    .collect(),
+                federation_version.clone(),
+            );
+            let composition = compose
+                .exec(
+                    override_install_path.clone(),
+                    client_config.clone(),
+                    &mut config,
+                    None,
+                )
+                .await;
+            match composition {
+                Ok(_) => step.composes = Some(true),
+                Err(err) => {
+                    let Some(build_errors) = err.build_errors() else {
+                        return Err(err);
+                    };
+                    step.composes = Some(false);
+                    step.composition_errors = build_errors
+                        .iter()
+                        .map(|error| error.get_message().unwrap_or_else(|| error.to_string()))
+                        .collect();
+                    // the steps after this one can't be checked until it's fixed
+                    break;
+                }
+            }
+        }
+
+        if let Some(schema_dir) = &self.schema_dir {
+            Fs::create_dir_all(schema_dir)?;
+            for (index, step) in plan.steps.iter_mut().enumerate() {
+                let path = schema_dir.join(format!("{}-{}.graphql", index + 1, step.subgraph));
+                Fs::write_file(&path, &step.sdl)?;
+                step.schema_path = Some(path);
+            }
+        }
+
+        if plan.steps.iter().any(|step| step.composes == Some(false)) {
+            Err(RoverError::from_failure(FieldMigrationFailure(plan)))
+        } else {
+            Ok(RoverOutput::FieldMigrationPlan(plan))
+        }
+    }
+}
+
+/// A field of an object type, like `Product.price`
+#[derive(Debug, Clone, Serialize, Eq, PartialEq)]
+pub struct FieldCoordinate {
+    pub type_name: String,
+    pub field_name: String,
+}
+
+impl Display for FieldCoordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.type_name, self.field_name)
+    }
+}
+
+fn parse_field(field: &str) -> Result<FieldCoordinate, String> {
+    match field.split_once('.') {
+        Some((type_name, field_name))
+            if !type_name.is_empty() && !field_name.is_empty() && !field_name.contains('.') =>
+        {
+            Ok(FieldCoordinate {
+                type_name: type_name.to_string(),
+                field_name: field_name.to_string(),
+            })
+        }
+        _ => Err("expected a field as `Type.field`".to_string()),
+    }
+}
+
+/// A change to the schema of the subgraph a step publishes
+#[derive(Debug, Clone, Serialize, Eq, PartialEq)]
+#[serde(tag = "action", content = "sdl", rename_all = "lowercase")]
+pub enum SdlEdit {
+    Add(String),
+    Remove(String),
+}
+
+impl Display for SdlEdit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (prefix, sdl) = match self {
+            SdlEdit::Add(sdl) => ("+", sdl),
+            SdlEdit::Remove(sdl) => ("-", sdl),
+        };
+        let lines: Vec<String> = sdl
+            .lines()
+            .map(|line| format!("{} {}", prefix, line))
+            .collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// A subgraph publish in a field migration
+#[derive(Debug, Clone, Serialize, Eq, PartialEq)]
+pub struct MigrationStep {
+    pub subgraph: String,
+    pub description: String,
+    pub edits: Vec<SdlEdit>,
+    /// The subgraph's schema with this step's edits
+    pub sdl: String,
+    /// Where `--schema-dir` wrote `sdl`
+    pub schema_path: Option<Utf8PathBuf>,
+    /// Whether the graph composes after this step, unless an earlier step already doesn't
+    pub composes: Option<bool>,
+    pub composition_errors: Vec<String>,
+}
+
+impl MigrationStep {
+    fn new(subgraph: &str, description: String, edits: Vec<SdlEdit>, sdl: String) -> Self {
+        Self {
+            subgraph: subgraph.to_string(),
+            description,
+            edits,
+            sdl,
+            schema_path: None,
+            composes: None,
+            composition_errors: Vec::new(),
+        }
+    }
+}
+
+/// The staged rollout that moves a field between subgraphs with `@override`, worked out by
+/// `rover subgraph migrate-field`: the target subgraph takes the field over, the source subgraph
+/// stops resolving it, and then the target drops the `@override`
+#[derive(Debug, Clone, Serialize, Eq, PartialEq)]
+pub struct FieldMigrationPlan {
+    pub graph_ref: GraphRef,
+    pub field: FieldCoordinate,
+    pub from: String,
+    pub to: String,
+    /// The publishes, in the order they have to happen
+    pub steps: Vec<MigrationStep>,
+    /// What the edits can't do on their own
+    pub notes: Vec<String>,
+}
+
+impl FieldMigrationPlan {
+    pub fn new(
+        graph_ref: GraphRef,
+        field: FieldCoordinate,
+        from: &str,
+        source_sdl: &str,
+        to: &str,
+        target_sdl: &str,
+    ) -> Result<Self, String> {
+        let source = SdlParser::new(source_sdl).parse().document();
+        let target = SdlParser::new(target_sdl).parse().document();
+        let source_field = find_field(&source, &field)
+            .ok_or_else(|| format!("Subgraph `{}` doesn't define {}.", from, field))?;
+        if find_directive(source_field.directives(), "external").is_some() {
+            return Err(format!(
+                "Subgraph `{}` marks {} `@external`, so another subgraph resolves it.",
+                from, field
+            ));
+        }
+        let key = first_key(&source, &field.type_name);
+        if key.is_none() && !ROOT_TYPES.contains(&field.type_name.as_str()) {
+            return Err(format!(
+                "{} isn't an entity in subgraph `{}`, so its fields can't be overridden. Only fields of entities and root types can move between subgraphs.",
+                field.type_name, from
+            ));
+        }
+
+        let mut notes = Vec::new();
+        if let Some(requires) = find_directive(source_field.directives(), "requires") {
+            notes.push(format!(
+                "Subgraph `{}` resolves {} with `{}`. Subgraph `{}` has to resolve those fields itself, or declare them `@external` and require them too.",
+                from,
+                field,
+                requires.syntax().text().to_string().trim(),
+                to
+            ));
+        }
+        let defined = defined_types(&target);
+        let missing: BTreeSet<String> = source_field
+            .syntax()
+            .descendants()
+            .filter_map(cst::NamedType::cast)
+            .filter_map(|named| named.name())
+            .map(|name| name.text().to_string())
+            .filter(|name| !BUILT_IN_SCALARS.contains(&name.as_str()) && !defined.contains(name))
+            .collect();
+        for name in missing {
+            notes.push(format!(
+                "Subgraph `{}` doesn't define `{}`, which {} uses. Add it before publishing.",
+                to, name, field
+            ));
+        }
+
+        // step 1: the target takes the field over
+        let bare_field = node_text(&source_field, &SOURCE_ONLY_DIRECTIVES);
+        let overriding_field = format!("{} @override(from: \"{}\")", bare_field, from);
+        let mut edits = Vec::new();
+        let with_field = match find_field(&target, &field) {
+            Some(existing) => {
+                edits.push(SdlEdit::Remove(node_text(&existing, &[])));
+                edits.push(SdlEdit::Add(overriding_field.clone()));
+                replace(
+                    target_sdl,
+                    trimmed_range(existing.syntax()),
+                    &overriding_field,
+                )
+            }
+            None => match object_types(&target, &field.type_name)
+                .into_iter()
+                .filter_map(|ty| ty.fields)
+                .last()
+            {
+                Some(fields) => {
+                    edits.push(SdlEdit::Add(overriding_field.clone()));
+                    insert_field(target_sdl, &fields, &overriding_field)
+                }
+                None => {
+                    let type_sdl =
+                        new_type(&source, &field, key.as_ref(), &overriding_field, from)?;
+                    edits.push(SdlEdit::Add(type_sdl.clone()));
+                    format!("{}\n\n{}\n", target_sdl.trim_end(), type_sdl)
+                }
+            },
+        };
+        let with_override = match import_override(&with_field)
+            .map_err(|err| format!("Subgraph `{}` {}", to, err))?
+        {
+            Some(imported) => {
+                edits.push(SdlEdit::Remove(imported.old_link));
+                edits.push(SdlEdit::Add(imported.new_link));
+                imported.sdl
+            }
+            None => with_field,
+        };
+        let take_over = MigrationStep::new(
+            to,
+            format!(
+                "Publish `{}`, which takes over {} from `{}` with `@override`.",
+                to, field, from
+            ),
+            edits,
+            with_override.clone(),
+        );
+
+        // step 2: the source stops resolving it
+        let (without_field, removed) = remove_field(source_sdl, &source, &field)?;
+        let hand_off = MigrationStep::new(
+            from,
+            format!(
+                "Publish `{}` without {}, once `{}` serves all of its traffic.",
+                from, field, to
+            ),
+            vec![SdlEdit::Remove(removed)],
+            without_field,
+        );
+
+        // step 3: the override has nothing left to override
+        let (cleaned_up, removed_override) =
+            remove_override(&with_override, &field).ok_or_else(|| {
+                format!(
+                    "Could not find the `@override` added to {} in `{}`.",
+                    field, to
+                )
+            })?;
+        let clean_up = MigrationStep::new(
+            to,
+            format!(
+                "Clean up: publish `{}` without the `@override` on {}.",
+                to, field
+            ),
+            vec![SdlEdit::Remove(removed_override)],
+            cleaned_up,
+        );
+
+        Ok(Self {
+            graph_ref,
+            field,
+            from: from.to_string(),
+            to: to.to_string(),
+            steps: vec![take_over, hand_off, clean_up],
+            notes,
+        })
+    }
+
+    pub fn get_output(&self) -> String {
+        let mut output = format!(
+            "Moving {} from {} to {} in {} takes {} publishes, in this order:\n",
+            Style::Command.paint(self.field.to_string()),
+            Style::Link.paint(&self.from),
+            Style::Link.paint(&self.to),
+            Style::Link.paint(self.graph_ref.to_string()),
+            self.steps.len()
+        );
+        for (index, step) in self.steps.iter().enumerate() {
+            output.push_str(&format!("\n{}. {}\n\n", index + 1, step.description));
+            for edit in &step.edits {
+                for line in edit.to_string().lines() {
+                    output.push_str(&format!("     {}\n", line));
+                }
+            }
+            output.push('\n');
+            match step.composes {
+                Some(true) => output.push_str(&format!(
+                    "   {}\n",
+                    Style::Success.paint("The graph composes after this step.")
+                )),
+                Some(false) => {
+                    output.push_str(&format!(
+                        "   {}\n",
+                        Style::Failure.paint("The graph doesn't compose after this step:")
+                    ));
+                    for error in &step.composition_errors {
+                        output.push_str(&format!("     {}\n", error));
+                    }
+                }
+                None => output.push_str("   Not checked, since an earlier step doesn't compose.\n"),
+            }
+            let schema = step
+                .schema_path
+                .as_ref()
+                .map(|path| path.to_string())
+                .unwrap_or_else(|| format!("{}.graphql", step.subgraph));
+            output.push_str(&format!(
+                "   {}\n",
+                Style::Command.paint(format!(
+                    "rover subgraph publish {} --name {} --schema {}",
+                    self.graph_ref, step.subgraph, schema
+                ))
+            ));
+        }
+        if !self.notes.is_empty() {
+            output.push_str("\nNotes:\n");
+            for note in &self.notes {
+                output.push_str(&format!("  - {}\n", note));
+            }
+        }
+        output
+    }
+
+    pub fn get_json(&self) -> Value {
+        json!({
+            "graph_ref": self.graph_ref.to_string(),
+            "field": self.field.to_string(),
+            "from": self.from,
+            "to": self.to,
+            "steps": self.steps,
+            "notes": self.notes,
+        })
+    }
+}
+
+/// Returned by `rover subgraph migrate-field` when a step of the migration doesn't compose
+#[derive(Debug, thiserror::Error)]
+#[error("Moving {} from `{}` to `{}` breaks composition.", .0.field, .0.from, .0.to)]
+pub struct FieldMigrationFailure(pub FieldMigrationPlan);
+
+impl StructuredFailure for FieldMigrationFailure {
+    fn get_output(&self) -> String {
+        self.0.get_output()
+    }
+
+    fn get_json(&self) -> Value {
+        self.0.get_json()
+    }
+}
+
+/// The parts of an object type's definition and its extensions that fields can be added to
+struct ObjectType {
+    node: SyntaxNode,
+    directives: Option<cst::Directives>,
+    fields: Option<cst::FieldsDefinition>,
+}
+
+fn object_types(document: &cst::Document, type_name: &str) -> Vec<ObjectType> {
+    document
+        .definitions()
+        .filter_map(|definition| match definition {
+            cst::Definition::ObjectTypeDefinition(def) => Some((
+                def.name(),
+                ObjectType {
+                    node: def.syntax().clone(),
+                    directives: def.directives(),
+                    fields: def.fields_definition(),
+                },
+            )),
+            cst::Definition::ObjectTypeExtension(def) => Some((
+                def.name(),
+                ObjectType {
+                    node: def.syntax().clone(),
+                    directives: def.directives(),
+                    fields: def.fields_definition(),
+                },
+            )),
+            _ => None,
+        })
+        .filter(|(name, _)| name.as_ref().is_some_and(|name| name.text() == type_name))
+        .map(|(_, ty)| ty)
+        .collect()
+}
+
+fn find_field(document: &cst::Document, field: &FieldCoordinate) -> Option<cst::FieldDefinition> {
+    object_types(document, &field.type_name)
+        .into_iter()
+        .filter_map(|ty| ty.fields)
+        .flat_map(|fields| fields.field_definitions())
+        .find(|definition| {
+            definition
+                .name()
+                .is_some_and(|name| name.text() == field.field_name.as_str())
+        })
+}
+
+fn directive_name(directive: &cst::Directive) -> Option<String> {
+    directive.name().map(|name| name.text().to_string())
+}
+
+fn find_directive(directives: Option<cst::Directives>, name: &str) -> Option<cst::Directive> {
+    directives
+        .into_iter()
+        .flat_map(|directives| directives.directives())
+        .find(|directive| directive_name(directive).as_deref() == Some(name))
+}
+
+fn argument(directive: &cst::Directive, name: &str) -> Option<cst::Value> {
+    directive
+        .arguments()?
+        .arguments()
+        .find(|argument| {
+            argument
+                .name()
+                .is_some_and(|argument_name| argument_name.text() == name)
+        })?
+        .value()
+}
+
+fn first_key(document: &cst::Document, type_name: &str) -> Option<cst::Directive> {
+    object_types(document, type_name)
+        .into_iter()
+        .find_map(|ty| find_directive(ty.directives, "key"))
+}
+
+fn defined_types(document: &cst::Document) -> BTreeSet<String> {
+    document
+        .definitions()
+        .filter_map(|definition| match definition {
+            cst::Definition::ObjectTypeDefinition(def) => def.name(),
+            cst::Definition::ObjectTypeExtension(def) => def.name(),
+            cst::Definition::InterfaceTypeDefinition(def) => def.name(),
+            cst::Definition::UnionTypeDefinition(def) => def.name(),
+            cst::Definition::EnumTypeDefinition(def) => def.name(),
+            cst::Definition::InputObjectTypeDefinition(def) => def.name(),
+            cst::Definition::ScalarTypeDefinition(def) => def.name(),
+            _ => None,
+        })
+        .map(|name| name.text().to_string())
+        .collect()
+}
+
+/// The byte range of a node in the schema, without the whitespace and commas around it
+fn trimmed_range(node: &SyntaxNode) -> Range<usize> {
+    let ignored = |c: char| c.is_whitespace() || c == ',';
+    let range = node.text_range();
+    let text = node.text().to_string();
+    let start = usize::from(range.start()) + text.len() - text.trim_start_matches(ignored).len();
+    let end = usize::from(range.end()) - (text.len() - text.trim_end_matches(ignored).len());
+    start..end
+}
+
+/// The SDL of a field, without the directives named in `dropped`
+fn node_text(field: &cst::FieldDefinition, dropped: &[&str]) -> String {
+    let range = trimmed_range(field.syntax());
+    let offset = usize::from(field.syntax().text_range().start());
+    let mut text =
+        field.syntax().text().to_string()[range.start - offset..range.end - offset].to_string();
+    let removed: Vec<Range<usize>> = field
+        .directives()
+        .into_iter()
+        .flat_map(|directives| directives.directives())
+        .filter(|directive| {
+            directive_name(directive).is_some_and(|name| dropped.contains(&name.as_str()))
+        })
+        .map(|directive| {
+            let directive_range = trimmed_range(directive.syntax());
+            directive_range.start - range.start..directive_range.end - range.start
+        })
+        .collect();
+    for removed in removed.into_iter().rev() {
+        text = remove_inline(&text, removed);
+    }
+    text
+}
+
+fn replace(sdl: &str, range: Range<usize>, with: &str) -> String {
+    format!("{}{}{}", &sdl[..range.start], with, &sdl[range.end..])
+}
+
+/// Removes `range` and the spaces before it
+fn remove_inline(sdl: &str, range: Range<usize>) -> String {
+    let start = sdl[..range.start].trim_end_matches([' ', '\t']).len();
+    replace(sdl, start..range.end, "")
+}
+
+/// Removes `range`, and the line it's on when nothing else is
+fn remove_lines(sdl: &str, range: Range<usize>) -> String {
+    let line_start = sdl[..range.start].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = sdl[range.end..]
+        .find('\n')
+        .map_or(sdl.len(), |index| range.end + index + 1);
+    if sdl[line_start..range.start].trim().is_empty() && sdl[range.end..line_end].trim().is_empty()
+    {
+        replace(sdl, line_start..line_end, "")
+    } else {
+        remove_inline(sdl, range)
+    }
+}
+
+/// Adds `field` after the last field of `fields`, indented the same way
+fn insert_field(sdl: &str, fields: &cst::FieldsDefinition, field: &str) -> String {
+    let Some(last) = fields.field_definitions().last() else {
+        let at = fields
+            .r_curly_token()
+            .map_or(sdl.len(), |token| usize::from(token.text_range().start()));
+        return replace(sdl, at..at, &format!(" {} ", field));
+    };
+    let last = trimmed_range(last.syntax());
+    let line_start = sdl[..last.start].rfind('\n').map_or(0, |index| index + 1);
+    let indent = &sdl[line_start..last.start];
+    if line_start > 0 && indent.trim().is_empty() {
+        replace(sdl, last.end..last.end, &format!("\n{}{}", indent, field))
+    } else {
+        replace(sdl, last.end..last.end, &format!(" {}", field))
+    }
+}
+
+/// The definition of a type for a subgraph that doesn't have it yet, with the entity key the
+/// source subgraph uses and the fields of that key
+fn new_type(
+    source: &cst::Document,
+    field: &FieldCoordinate,
+    key: Option<&cst::Directive>,
+    overriding_field: &str,
+    from: &str,
+) -> Result<String, String> {
+    let mut fields = Vec::new();
+    let mut directives = String::new();
+    if let Some(key) = key {
+        let key_fields = argument(key, "fields")
+            .map(|value| value.syntax().text().to_string().trim().to_string())
+            .ok_or_else(|| format!("The `@key` of {} has no fields.", field.type_name))?;
+        for name in field_set_names(&key_fields, false) {
+            let key_field = FieldCoordinate {
+                type_name: field.type_name.clone(),
+                field_name: name,
+            };
+            let definition = find_field(source, &key_field).ok_or_else(|| {
+                format!(
+                    "Subgraph `{}` doesn't define {}, which is part of the key of {}.",
+                    from, key_field, field.type_name
+                )
+            })?;
+            fields.push(node_text(&definition, &SOURCE_ONLY_DIRECTIVES));
+        }
+        directives = format!(" @key(fields: {})", key_fields);
+    }
+    fields.push(overriding_field.to_string());
+    Ok(format!(
+        "type {}{} {{\n{}\n}}",
+        field.type_name,
+        directives,
+        fields
+            .iter()
+            .map(|field| format!("  {}", field))
+            .collect::<Vec<_>>()
+            .join("\n")
+    ))
+}
+
+/// The schema with `@override` imported from federation, and the `@link` before and after
+struct ImportedOverride {
+    sdl: String,
+    old_link: String,
+    new_link: String,
+}
+
+/// Adds `@override` to the imports of the schema's federation `@link`, unless it's already there
+fn import_override(sdl: &str) -> Result<Option<ImportedOverride>, String> {
+    let document = SdlParser::new(sdl).parse().document();
+    let link = document
+        .syntax()
+        .descendants()
+        .filter_map(cst::Directive::cast)
+        .find(|directive| {
+            directive_name(directive).as_deref() == Some("link")
+                && argument(directive, "url").is_some_and(|url| {
+                    url.syntax()
+                        .text()
+                        .to_string()
+                        .contains("specs.apollo.dev/federation/v2")
+                })
+        })
+        .ok_or_else(|| {
+            "doesn't `@link` to Federation 2, which `@override` needs. Upgrade it to Federation 2 first.".to_string()
+        })?;
+    let (at, insertion) = match argument(&link, "import") {
+        Some(cst::Value::ListValue(list)) => {
+            let values: Vec<cst::Value> = list.values().collect();
+            if values.iter().any(|value| {
+                value.syntax().text().to_string().trim().trim_matches('"') == "@override"
+            }) {
+                return Ok(None);
+            }
+            match values.last() {
+                Some(last) => (trimmed_range(last.syntax()).end, ", \"@override\""),
+                None => (
+                    list.r_brack_token()
+                        .map(|token| usize::from(token.text_range().start()))
+                        .ok_or("has a federation `@link` with a malformed `import`.")?,
+                    "\"@override\"",
+                ),
+            }
+        }
+        Some(_) => return Err("has a federation `@link` whose `import` isn't a list.".to_string()),
+        None => (
+            link.arguments()
+                .and_then(|arguments| arguments.arguments().last())
+                .map(|last| trimmed_range(last.syntax()).end)
+                .ok_or("has a federation `@link` without arguments.")?,
+            ", import: [\"@override\"]",
+        ),
+    };
+    let link_range = trimmed_range(link.syntax());
+    let imported = replace(sdl, at..at, insertion);
+    Ok(Some(ImportedOverride {
+        old_link: sdl[link_range.clone()].to_string(),
+        new_link: imported[link_range.start..link_range.end + insertion.len()].to_string(),
+        sdl: imported,
+    }))
+}
+
+/// Removes a field from the schema, or the whole type definition when it's the only field there,
+/// and returns the SDL that was removed
+fn remove_field(
+    sdl: &str,
+    document: &cst::Document,
+    field: &FieldCoordinate,
+) -> Result<(String, String), String> {
+    let definition = find_field(document, field)
+        .ok_or_else(|| format!("Could not find {} to remove it.", field))?;
+    let only_field = object_types(document, &field.type_name)
+        .into_iter()
+        .find(|ty| {
+            ty.fields.as_ref().is_some_and(|fields| {
+                fields
+                    .syntax()
+                    .text_range()
+                    .contains_range(definition.syntax().text_range())
+            })
+        })
+        .filter(|ty| {
+            ty.fields
+                .as_ref()
+                .is_some_and(|fields| fields.field_definitions().count() == 1)
+        });
+    let range = match only_field {
+        Some(ty) => trimmed_range(&ty.node),
+        None => trimmed_range(definition.syntax()),
+    };
+    Ok((remove_lines(sdl, range.clone()), sdl[range].to_string()))
+}
+
+/// Removes the `@override` from a field, and returns the directive that was removed
+fn remove_override(sdl: &str, field: &FieldCoordinate) -> Option<(String, String)> {
+    let document = SdlParser::new(sdl).parse().document();
+    let directive = find_directive(find_field(&document, field)?.directives(), "override")?;
+    let range = trimmed_range(directive.syntax());
+    Some((remove_inline(sdl, range.clone()), sdl[range].to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    const INVENTORY: &str = r#"extend schema
+  @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@shareable"])
+
+type Query {
+  products: [Product]
+}
+
+type Product @key(fields: "id") {
+  id: ID!
+  inStock: Boolean
+  "The price in cents"
+  price: Int @shareable
+}
+"#;
+
+    const PRICING: &str = r#"extend schema
+  @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key"])
+
+type Product @key(fields: "id") {
+  id: ID!
+  currency: String
+}
+"#;
+
+    fn graph_ref() -> GraphRef {
+        GraphRef {
+            name: "mygraph".to_string(),
+            variant: "current".to_string(),
+        }
+    }
+
+    fn plan(field: &str, source: &str, target: &str) -> Result<FieldMigrationPlan, String> {
+        FieldMigrationPlan::new(
+            graph_ref(),
+            parse_field(field).unwrap(),
+            "inventory",
+            source,
+            "pricing",
+            target,
+        )
+    }
+
+    #[rstest]
+    #[case::field("Product.price", true)]
+    #[case::no_field("Product", false)]
+    #[case::no_type(".price", false)]
+    #[case::nested("Product.price.amount", false)]
+    fn test_parse_field(#[case] input: &str, #[case] valid: bool) {
+        assert_that!(parse_field(input).is_ok()).is_equal_to(valid);
+    }
+
+    #[test]
+    fn test_plan_stages_the_override() {
+        let plan = plan("Product.price", INVENTORY, PRICING).unwrap();
+        let subgraphs: Vec<&str> = plan
+            .steps
+            .iter()
+            .map(|step| step.subgraph.as_str())
+            .collect();
+        assert_that!(subgraphs).is_equal_to(vec!["pricing", "inventory", "pricing"]);
+
+        assert_that!(plan.steps[0].sdl).is_equal_to(
+            r#"extend schema
+  @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@override"])
+
+type Product @key(fields: "id") {
+  id: ID!
+  currency: String
+  "The price in cents"
+  price: Int @shareable @override(from: "inventory")
+}
+"#
+            .to_string(),
+        );
+        assert_that!(plan.steps[1].sdl).is_equal_to(
+            INVENTORY.replace("  \"The price in cents\"\n  price: Int @shareable\n", ""),
+        );
+        assert_that!(plan.steps[1].edits).is_equal_to(vec![SdlEdit::Remove(
+            "\"The price in cents\"\n  price: Int @shareable".to_string(),
+        )]);
+        assert_that!(plan.steps[2].sdl).contains("  price: Int @shareable\n}");
+        assert_that!(plan.steps[2].sdl).does_not_contain("@override(");
+        assert_that!(plan.notes).is_empty();
+    }
+
+    #[test]
+    fn test_plan_adds_the_entity_to_the_target() {
+        let target = r#"extend schema @link(url: "https://specs.apollo.dev/federation/v2.0")
+
+type Query { currencies: [String] }
+"#;
+        let plan = plan("Product.price", INVENTORY, target).unwrap();
+        assert_that!(plan.steps[0].sdl).is_equal_to(
+            r#"extend schema @link(url: "https://specs.apollo.dev/federation/v2.0", import: ["@override"])
+
+type Query { currencies: [String] }
+
+type Product @key(fields: "id") {
+  id: ID!
+  "The price in cents"
+  price: Int @shareable @override(from: "inventory")
+}
+"#
+            .to_string(),
+        );
+    }
+
+    #[test]
+    fn test_plan_moves_root_fields_and_notes_missing_types() {
+        let source = r#"extend schema @link(url: "https://specs.apollo.dev/federation/v2.0", import: ["@key"])
+type Query { prices(currency: Currency): [Int] }
+enum Currency { USD }
+"#;
+        let target = r#"extend schema @link(url: "https://specs.apollo.dev/federation/v2.0", import: ["@key", "@override"])
+type Query { rates: [Int] }
+"#;
+        let plan = plan("Query.prices", source, target).unwrap();
+        assert_that!(plan.steps[0].sdl).contains(
+            "type Query { rates: [Int] prices(currency: Currency): [Int] @override(from: \"inventory\") }",
+        );
+        // the whole type goes when the field was its only one
+        assert_that!(plan.steps[1].sdl).does_not_contain("type Query");
+        assert_that!(plan.notes).has_length(1);
+        assert_that!(plan.notes[0]).contains("`Currency`");
+    }
+
+    #[rstest]
+    #[case::missing_field("Product.cost", INVENTORY, "doesn't define Product.cost")]
+    #[case::value_type("Product.price", "type Product { price: Int }", "isn't an entity")]
+    #[case::external(
+        "Product.price",
+        "type Product @key(fields: \"id\") { id: ID! price: Int @external }",
+        "@external"
+    )]
+    fn test_plan_rejects(#[case] field: &str, #[case] source: &str, #[case] message: &str) {
+        assert_that!(plan(field, source, PRICING))
+            .is_err()
+            .matches(|err| err.contains(message));
+    }
+
+    #[test]
+    fn test_plan_needs_federation_2() {
+        let target = "type Product @key(fields: \"id\") { id: ID! }";
+        assert_that!(plan("Product.price", INVENTORY, target))
+            .is_err()
+            .matches(|err| err.contains("Federation 2"));
+    }
+}
@@ -1,14 +1,18 @@
 mod check;
-mod delete;
+pub mod delete;
 mod fetch;
+#[cfg(feature = "composition-js")]
+mod fetch_all;
 pub mod introspect;
 mod lint;
 mod list;
+#[cfg(feature = "composition-js")]
+pub mod migrate_field;
 mod publish;
 
-#[cfg(not(feature = "dev-next"))]
 pub use introspect::Introspect;
 
+use camino::Utf8PathBuf;
 use clap::Parser;
 use serde::Serialize;
 
@@ -36,6 +40,11 @@ pub enum Command {
     /// Fetch a subgraph schema from the Apollo graph registry
     Fetch(fetch::Fetch),
 
+    /// Fetch every subgraph schema of a federated graph into a directory, along with a supergraph
+    /// config that composes them locally
+    #[cfg(feature = "composition-js")]
+    FetchAll(fetch_all::FetchAll),
+
     /// Introspect a running subgraph endpoint to retrieve its schema definition (SDL)
     Introspect(introspect::Introspect),
 
@@ -45,17 +54,28 @@ pub enum Command {
     /// List all subgraphs for a federated graph
     List(list::List),
 
+    /// Plan moving a field from one subgraph to another with `@override`, checking that the graph
+    /// composes after each publish
+    #[cfg(feature = "composition-js")]
+    MigrateField(migrate_field::MigrateField),
+
     /// Publish an updated subgraph schema to the Apollo graph registry and trigger composition in the graph router
     Publish(publish::Publish),
 }
 
 impl Subgraph {
+    /// Whether this is `subgraph check`, the output of which `--format junit` reports
+    pub(crate) fn is_check(&self) -> bool {
+        matches!(self.command, Command::Check(_))
+    }
+
     pub async fn run(
         &self,
         client_config: StudioClientConfig,
         git_context: GitContext,
         checks_timeout_seconds: u64,
         output_opts: &OutputOpts,
+        override_install_path: Option<Utf8PathBuf>,
     ) -> RoverResult<RoverOutput> {
         match &self.command {
             Command::Check(command) => {
@@ -63,7 +83,7 @@ impl Subgraph {
                     .run(client_config, git_context, checks_timeout_seconds)
                     .await
             }
-            Command::Delete(command) => command.run(client_config).await,
+            Command::Delete(command) => command.run(client_config, override_install_path).await,
             Command::Introspect(command) => {
                 command
                     .run(
@@ -74,9 +94,19 @@ impl Subgraph {
                     .await
             }
             Command::Fetch(command) => command.run(client_config).await,
+            #[cfg(feature = "composition-js")]
+            Command::FetchAll(command) => command.run(client_config).await,
             Command::Lint(command) => command.run(client_config).await,
             Command::List(command) => command.run(client_config).await,
-            Command::Publish(command) => command.run(client_config, git_context).await,
+            #[cfg(feature = "composition-js")]
+            Command::MigrateField(command) => {
+                command.run(client_config, override_install_path).await
+            }
+            Command::Publish(command) => {
+                command
+                    .run(client_config, git_context, override_install_path)
+                    .await
+            }
         }
     }
 }
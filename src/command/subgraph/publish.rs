@@ -1,19 +1,38 @@
 use std::io::{self, IsTerminal};
 
 use anyhow::anyhow;
+#[cfg(feature = "composition-js")]
+use apollo_federation_types::config::{
+    FederationVersion, SchemaSource, SubgraphConfig, SupergraphConfig,
+};
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use camino::Utf8PathBuf;
 use clap::Parser;
 use futures::Future;
+use hmac::{Hmac, Mac};
 use reqwest::Url;
+use rover_client::blocking::StudioClient;
+use rover_client::operations::subgraph::check_approval::{self, SubgraphCheckApprovalInput};
 use rover_client::operations::subgraph::routing_url::{self, SubgraphRoutingUrlInput};
+use rover_client::shared::GraphRef;
 use serde::Serialize;
+use sha2::Sha256;
+#[cfg(feature = "composition-js")]
+use tap::TapFallible;
 
+#[cfg(feature = "composition-js")]
+use crate::command::supergraph::compose::do_compose::Compose;
 use crate::options::{GraphRefOpt, ProfileOpt, SchemaOpt, SubgraphOpt};
+#[cfg(feature = "composition-js")]
+use crate::options::{LicenseAccepter, PluginOpts};
 use crate::utils::client::StudioClientConfig;
+#[cfg(feature = "composition-js")]
+use crate::utils::supergraph_config::RemoteSubgraphs;
 use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
 
 use rover_client::operations::subgraph::publish::{self, SubgraphPublishInput};
 use rover_client::shared::GitContext;
-use rover_std::Style;
+use rover_std::{progressln, Style};
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Publish {
@@ -51,6 +70,38 @@ pub struct Publish {
     /// This is shorthand for `--routing-url "" --allow-invalid-routing-url`.
     #[arg(long)]
     no_url: bool,
+
+    /// Publish despite failed checks, referencing the check whose failures were approved. The
+    /// check, or one of its reruns, has to have passed in Studio, unless `--approval-token`
+    /// approves it instead. The override is recorded with the publish
+    #[arg(long, value_name = "CHECK_ID")]
+    allow_breaking_with_approval: Option<String>,
+
+    /// A token approving `--allow-breaking-with-approval` for a check that wasn't approved in
+    /// Studio, signed with the secret in `APOLLO_PUBLISH_APPROVAL_SECRET`
+    #[arg(long, requires = "allow_breaking_with_approval")]
+    #[serde(skip_serializing)]
+    approval_token: Option<String>,
+
+    /// The secret that approval tokens are signed with
+    #[arg(
+        long,
+        env = "APOLLO_PUBLISH_APPROVAL_SECRET",
+        hide = true,
+        hide_env_values = true
+    )]
+    #[serde(skip_serializing)]
+    approval_secret: Option<String>,
+
+    /// Before publishing, compose the graph's other subgraphs with this schema locally, using
+    /// the `supergraph` plugin, and don't publish if composition fails
+    #[cfg(feature = "composition-js")]
+    #[arg(long)]
+    validate_composition: bool,
+
+    #[cfg(feature = "composition-js")]
+    #[clap(flatten)]
+    elv2_license_accepter: LicenseAccepter,
 }
 
 impl Publish {
@@ -58,6 +109,7 @@ impl Publish {
         &self,
         client_config: StudioClientConfig,
         git_context: GitContext,
+        override_install_path: Option<Utf8PathBuf>,
     ) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
 
@@ -81,7 +133,7 @@ impl Publish {
         )
         .await?;
 
-        eprintln!(
+        progressln!(
             "Publishing SDL to {} (subgraph: {}) using credentials from the {} profile.",
             Style::Link.paint(self.graph.graph_ref.to_string()),
             Style::Link.paint(&self.subgraph.subgraph_name),
@@ -94,6 +146,19 @@ impl Publish {
 
         tracing::debug!("Publishing \n{}", &schema);
 
+        #[cfg(feature = "composition-js")]
+        if self.validate_composition {
+            self.validate_composition(&schema, &url, &client, client_config, override_install_path)
+                .await?;
+        }
+        #[cfg(not(feature = "composition-js"))]
+        let _ = override_install_path;
+
+        let revision = match &self.allow_breaking_with_approval {
+            Some(check_id) => Some(self.verify_override(check_id, &client).await?),
+            None => None,
+        };
+
         let publish_response = publish::run(
             SubgraphPublishInput {
                 graph_ref: self.graph.graph_ref.clone(),
@@ -102,6 +167,7 @@ impl Publish {
                 schema,
                 git_context,
                 convert_to_federated_graph: self.convert,
+                revision,
             },
             &client,
         )
@@ -114,6 +180,129 @@ impl Publish {
         })
     }
 
+    /// Composes the graph's subgraphs from GraphOS locally, with `schema` in place of this
+    /// subgraph's published schema, failing if they don't compose
+    #[cfg(feature = "composition-js")]
+    async fn validate_composition(
+        &self,
+        schema: &str,
+        url: &Option<String>,
+        client: &StudioClient,
+        client_config: StudioClientConfig,
+        override_install_path: Option<Utf8PathBuf>,
+    ) -> RoverResult<()> {
+        let graph_ref = &self.graph.graph_ref;
+        let subgraph = &self.subgraph.subgraph_name;
+        progressln!(
+            "Composing {} with the other subgraphs of {} before publishing.",
+            Style::Link.paint(subgraph),
+            Style::Link.paint(graph_ref.to_string())
+        );
+        let mut supergraph_config = RemoteSubgraphs::fetch(client, graph_ref)
+            .await?
+            .inner()
+            .clone();
+        supergraph_config.merge_subgraphs(&SupergraphConfig::new(
+            [(
+                subgraph.clone(),
+                SubgraphConfig {
+                    routing_url: url.clone(),
+                    schema: SchemaSource::Sdl {
+                        sdl: schema.to_string(),
+                    },
+                },
+            )]
+            .into(),
+            None,
+        ));
+        if supergraph_config.get_federation_version().is_none() {
+            supergraph_config.set_federation_version(FederationVersion::LatestFedTwo);
+        }
+
+        let plugin_opts = PluginOpts {
+            profile: self.profile.clone(),
+            elv2_license_accepter: self.elv2_license_accepter,
+            skip_update: false,
+            env: None,
+            schema_size_opts: Default::default(),
+            composition_limit_opts: Default::default(),
+            introspection_policy_opts: Default::default(),
+        };
+        Compose::new(plugin_opts)
+            .exec(
+                override_install_path,
+                client_config,
+                &mut supergraph_config,
+                None,
+            )
+            .await
+            .tap_err(|_| {
+                rover_std::errln!(
+                    "Not publishing, because subgraph `{}` doesn't compose with the other subgraphs of {}.",
+                    subgraph,
+                    graph_ref
+                )
+            })?;
+        Ok(())
+    }
+
+    /// Makes sure that publishing despite failed checks was approved, either in Studio or with an
+    /// approval token, returning the revision that records the override with the publish
+    async fn verify_override(&self, check_id: &str, client: &StudioClient) -> RoverResult<String> {
+        let graph_ref = &self.graph.graph_ref;
+        let subgraph = &self.subgraph.subgraph_name;
+        if let Some(token) = &self.approval_token {
+            let secret = self.approval_secret.as_ref().ok_or_else(|| {
+                RoverError::new(anyhow!(
+                    "`--approval-token` needs the secret it was signed with, in `APOLLO_PUBLISH_APPROVAL_SECRET`."
+                ))
+            })?;
+            if !verify_approval_token(secret, graph_ref, subgraph, check_id, token) {
+                return Err(RoverError::new(anyhow!(
+                    "The approval token doesn't approve publishing subgraph `{subgraph}` to {graph_ref} despite check {check_id}."
+                )));
+            }
+            eprintln!(
+                "Publishing despite failed checks, as approved by a token for check {}.",
+                Style::Link.paint(check_id)
+            );
+            return Ok(format!("approved-override:{check_id}:token"));
+        }
+
+        let approval = check_approval::run(
+            SubgraphCheckApprovalInput {
+                graph_ref: graph_ref.clone(),
+                check_id: check_id.to_string(),
+            },
+            client,
+        )
+        .await?;
+        let checked = |checked: &Option<String>, expected: &str| {
+            checked
+                .as_deref()
+                .map_or(true, |checked| checked == expected)
+        };
+        if !checked(&approval.subgraph, subgraph) || !checked(&approval.variant, &graph_ref.variant)
+        {
+            return Err(RoverError::new(anyhow!(
+                "Check {check_id} wasn't run for subgraph `{subgraph}` on {graph_ref}, so it can't approve this publish."
+            )));
+        }
+        let approved_by = approval.approved_by.ok_or_else(|| {
+            RoverError::new(anyhow!("Check {check_id} hasn't been approved.")).with_suggestion(
+                RoverErrorSuggestion::Adhoc(
+                    "Approve the check's failures in Studio and rerun it, or pass an `--approval-token`."
+                        .to_string(),
+                ),
+            )
+        })?;
+        eprintln!(
+            "Publishing despite failed checks, as approved by check {}.",
+            Style::Link.paint(&approved_by)
+        );
+        Ok(format!("approved-override:{check_id}:{approved_by}"))
+    }
+
     async fn determine_routing_url<F, G>(
         no_url: bool,
         routing_url: &Option<String>,
@@ -261,9 +450,76 @@ impl Publish {
     }
 }
 
+/// Whether `token` approves publishing `subgraph` to `graph_ref` despite the check `check_id`.
+/// Tokens are the unpadded, URL-safe base64 of an HMAC-SHA256 of `<graph_ref>:<subgraph>:<check_id>`,
+/// keyed with `secret`
+fn verify_approval_token(
+    secret: &str,
+    graph_ref: &GraphRef,
+    subgraph: &str,
+    check_id: &str,
+    token: &str,
+) -> bool {
+    let Ok(signature) = BASE64_URL_SAFE_NO_PAD.decode(token.trim()) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(format!("{graph_ref}:{subgraph}:{check_id}").as_bytes());
+    mac.verify_slice(&signature).is_ok()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::command::subgraph::publish::Publish;
+    use std::str::FromStr;
+
+    use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+    use hmac::{Hmac, Mac};
+    use rover_client::shared::GraphRef;
+    use rstest::rstest;
+    use sha2::Sha256;
+    use speculoos::prelude::*;
+
+    use crate::command::subgraph::publish::{verify_approval_token, Publish};
+
+    fn approval_token(secret: &str, message: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(message.as_bytes());
+        BASE64_URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+    }
+
+    #[rstest]
+    #[case::approved("secret", "mygraph@current:products:check-1", true)]
+    #[case::different_secret("other-secret", "mygraph@current:products:check-1", false)]
+    #[case::different_variant("secret", "mygraph@staging:products:check-1", false)]
+    #[case::different_subgraph("secret", "mygraph@current:reviews:check-1", false)]
+    #[case::different_check("secret", "mygraph@current:products:check-2", false)]
+    fn test_verify_approval_token(
+        #[case] signed_with: &str,
+        #[case] signed: &str,
+        #[case] expected: bool,
+    ) {
+        let graph_ref = GraphRef::from_str("mygraph@current").unwrap();
+        let token = approval_token(signed_with, signed);
+        assert_that!(verify_approval_token(
+            "secret", &graph_ref, "products", "check-1", &token
+        ))
+        .is_equal_to(expected);
+    }
+
+    #[test]
+    fn test_verify_approval_token_rejects_malformed_tokens() {
+        let graph_ref = GraphRef::from_str("mygraph@current").unwrap();
+        assert_that!(verify_approval_token(
+            "secret",
+            &graph_ref,
+            "products",
+            "check-1",
+            "not a token!"
+        ))
+        .is_false();
+    }
 
     #[tokio::test]
     async fn test_no_url() {
@@ -0,0 +1,387 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use apollo_federation_types::config::SchemaSource;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use futures::future::join_all;
+use itertools::Itertools;
+use rover_client::operations::subgraph::{
+    check::{self, SubgraphCheckAsyncInput},
+    check_workflow::{self, CheckWorkflowInput},
+};
+use rover_client::shared::{
+    CheckConfig, CheckRequestSuccessResult, CheckWorkflowResponse, GitContext, GraphRef,
+};
+use rover_client::RoverClientError;
+use rover_std::{progressln, Style};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::command::supergraph::compose::do_compose::{Compose, SupergraphComposeOpts};
+use crate::options::{CheckConfigOpts, JsonVersion};
+use crate::utils::client::StudioClientConfig;
+use crate::utils::github_annotations::{self, GithubAnnotation};
+use crate::utils::junit::{self, JunitReport, JunitSuite};
+use crate::utils::supergraph_config::get_supergraph_config;
+use crate::{RoverError, RoverOutput, RoverResult, StructuredFailure};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Check {
+    /// <NAME>@<VARIANT> of the graph in Apollo Studio to check each subgraph against.
+    /// @<VARIANT> may be left off, defaulting to @current
+    #[arg(value_name = "GRAPH_REF")]
+    #[serde(skip_serializing)]
+    target: GraphRef,
+
+    #[clap(flatten)]
+    opts: SupergraphComposeOpts,
+
+    #[clap(flatten)]
+    config: CheckConfigOpts,
+}
+
+impl Check {
+    pub async fn run(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+        git_context: GitContext,
+        checks_timeout_seconds: u64,
+    ) -> RoverResult<RoverOutput> {
+        let supergraph_config = get_supergraph_config(
+            self.opts.supergraph_config_source.graph_ref(),
+            self.opts.supergraph_config_source.supergraph_yaml(),
+            self.opts.federation_version().as_ref(),
+            client_config.clone(),
+            &self.opts.plugin_opts.profile,
+            true,
+            self.opts.plugin_opts.env.as_deref(),
+            self.opts.plugin_opts.introspection_policy_opts.policy(),
+        )
+        .await?
+        .ok_or_else(|| anyhow!("error getting supergraph config"))?;
+
+        // Composition errors are reported the same way as `supergraph compose` reports them,
+        // before any checks are started.
+        Compose::from(self.opts.clone())
+            .exec(
+                override_install_path,
+                client_config.clone(),
+                &mut supergraph_config.clone(),
+                None,
+            )
+            .await?;
+
+        let client = client_config.get_authenticated_client(&self.opts.plugin_opts.profile)?;
+        let check_config = CheckConfig {
+            query_count_threshold: self.config.query_count_threshold,
+            query_count_threshold_percentage: self.config.query_percentage_threshold,
+            validation_period: self.config.validation_period.clone(),
+        };
+
+        let mut started = BTreeMap::new();
+        for (subgraph_name, subgraph) in supergraph_config.into_iter() {
+            let SchemaSource::Sdl { sdl } = subgraph.schema else {
+                return Err(anyhow!(
+                    "The schema for subgraph '{}' could not be resolved.",
+                    subgraph_name
+                )
+                .into());
+            };
+            progressln!(
+                "Checking the proposed schema for subgraph {} against {}",
+                &subgraph_name,
+                Style::Link.paint(self.target.to_string())
+            );
+            let workflow_res = check::run(
+                SubgraphCheckAsyncInput {
+                    graph_ref: self.target.clone(),
+                    subgraph: subgraph_name.clone(),
+                    git_context: git_context.clone(),
+                    proposed_schema: sdl,
+                    config: check_config.clone(),
+                },
+                &client,
+            )
+            .await?;
+            started.insert(subgraph_name, workflow_res);
+        }
+
+        let subgraphs = if self.config.background {
+            started
+                .into_iter()
+                .map(|(name, workflow_res)| (name, SubgraphCheckOutcome::Started(workflow_res)))
+                .collect()
+        } else {
+            let outcomes = join_all(started.into_iter().map(|(name, workflow_res)| {
+                let client = &client;
+                async move {
+                    let result = check_workflow::run(
+                        CheckWorkflowInput {
+                            graph_ref: self.target.clone(),
+                            workflow_id: workflow_res.workflow_id,
+                            checks_timeout_seconds,
+                        },
+                        name.clone(),
+                        client,
+                    )
+                    .await;
+                    (name, result)
+                }
+            }))
+            .await;
+
+            let mut subgraphs = BTreeMap::new();
+            for (name, result) in outcomes {
+                let outcome = match result {
+                    Ok(check_res)
+                        if self.config.fail_on_downstream
+                            && check_res.has_failed_downstream_variants() =>
+                    {
+                        SubgraphCheckOutcome::Failed(check_res)
+                    }
+                    Ok(check_res) => SubgraphCheckOutcome::Passed(check_res),
+                    Err(RoverClientError::CheckWorkflowFailure { check_response, .. }) => {
+                        SubgraphCheckOutcome::Failed(*check_response)
+                    }
+                    Err(err) => return Err(err.into()),
+                };
+                subgraphs.insert(name, outcome);
+            }
+            subgraphs
+        };
+
+        let response = SupergraphCheckResponse {
+            graph_ref: self.target.clone(),
+            subgraphs,
+        };
+        if response.failed_subgraphs().is_empty() {
+            Ok(RoverOutput::SupergraphCheckResponse(response))
+        } else {
+            Err(RoverError::from_failure(SupergraphCheckFailure(response)))
+        }
+    }
+}
+
+/// The result of checking a single subgraph as part of `rover supergraph check`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SubgraphCheckOutcome {
+    /// The check was started with `--background` and wasn't waited on
+    Started(CheckRequestSuccessResult),
+    Passed(CheckWorkflowResponse),
+    Failed(CheckWorkflowResponse),
+}
+
+impl SubgraphCheckOutcome {
+    fn status(&self) -> &'static str {
+        match self {
+            SubgraphCheckOutcome::Started(_) => "STARTED",
+            SubgraphCheckOutcome::Passed(_) => "PASSED",
+            SubgraphCheckOutcome::Failed(_) => "FAILED",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SupergraphCheckResponse {
+    pub graph_ref: GraphRef,
+    pub subgraphs: BTreeMap<String, SubgraphCheckOutcome>,
+}
+
+impl SupergraphCheckResponse {
+    pub fn failed_subgraphs(&self) -> Vec<&str> {
+        self.subgraphs
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, SubgraphCheckOutcome::Failed(_)))
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    pub fn get_output(&self) -> String {
+        let mut msg = String::new();
+
+        for (name, outcome) in &self.subgraphs {
+            let status = match outcome {
+                SubgraphCheckOutcome::Failed(_) => Style::Failure.paint(outcome.status()),
+                SubgraphCheckOutcome::Passed(_) => Style::Success.paint(outcome.status()),
+                SubgraphCheckOutcome::Started(_) => Style::Pending.paint(outcome.status()),
+            };
+            msg.push_str(&format!(
+                "\n{} [{}]:\n",
+                Style::Heading.paint(format!("Subgraph {}", name)),
+                status
+            ));
+            match outcome {
+                SubgraphCheckOutcome::Started(check_res) => msg.push_str(&format!(
+                    "Check successfully started with workflow ID: {}\nView full details at {}\n",
+                    check_res.workflow_id, check_res.target_url
+                )),
+                SubgraphCheckOutcome::Passed(check_res)
+                | SubgraphCheckOutcome::Failed(check_res) => {
+                    msg.push_str(check_res.get_output().trim_start());
+                    msg.push('\n');
+                }
+            }
+        }
+
+        msg
+    }
+
+    pub fn get_json(&self) -> Value {
+        let subgraphs: serde_json::Map<String, Value> = self
+            .subgraphs
+            .iter()
+            .map(|(name, outcome)| {
+                let mut subgraph_json = match outcome {
+                    SubgraphCheckOutcome::Started(check_res) => check_res.get_json(),
+                    SubgraphCheckOutcome::Passed(check_res)
+                    | SubgraphCheckOutcome::Failed(check_res) => check_res.get_json(),
+                };
+                subgraph_json["status"] = json!(outcome.status());
+                (name.clone(), subgraph_json)
+            })
+            .collect();
+        json!({ "subgraphs": subgraphs })
+    }
+
+    /// A line per subgraph with its name and `passed`, `failed`, or `started`, for `--porcelain`
+    pub fn get_porcelain(&self) -> String {
+        self.subgraphs
+            .iter()
+            .map(|(name, outcome)| format!("{} {}", name, outcome.status().to_lowercase()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// A test suite for each subgraph whose checks finished
+    pub fn get_junit_suites(&self) -> Vec<JunitSuite> {
+        self.subgraphs
+            .iter()
+            .filter_map(|(name, outcome)| match outcome {
+                SubgraphCheckOutcome::Started(_) => None,
+                SubgraphCheckOutcome::Passed(check_res)
+                | SubgraphCheckOutcome::Failed(check_res) => Some(junit::from_check_response(
+                    check_res,
+                    &format!("checks for subgraph {}", name),
+                )),
+            })
+            .collect()
+    }
+
+    pub fn get_github_annotations(&self) -> Vec<GithubAnnotation> {
+        self.subgraphs
+            .iter()
+            .flat_map(|(name, outcome)| match outcome {
+                SubgraphCheckOutcome::Started(_) => Vec::new(),
+                SubgraphCheckOutcome::Passed(check_res)
+                | SubgraphCheckOutcome::Failed(check_res) => {
+                    github_annotations::from_check_response(check_res, Some(name))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returned by `rover supergraph check` when the checks of at least one subgraph failed
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "The changes in the schemas you proposed caused checks to fail for {}: {}.",
+    match .0.failed_subgraphs().len() {
+        1 => "1 subgraph".to_string(),
+        n => format!("{} subgraphs", n),
+    },
+    .0.failed_subgraphs().iter().join(", ")
+)]
+pub struct SupergraphCheckFailure(pub SupergraphCheckResponse);
+
+impl StructuredFailure for SupergraphCheckFailure {
+    fn get_output(&self) -> String {
+        self.0.get_output()
+    }
+
+    fn get_json(&self) -> Value {
+        self.0.get_json()
+    }
+
+    fn get_json_version(&self) -> Option<JsonVersion> {
+        Some(JsonVersion::Two)
+    }
+
+    fn get_github_annotations(&self) -> Vec<GithubAnnotation> {
+        self.0.get_github_annotations()
+    }
+
+    fn get_porcelain(&self) -> Option<String> {
+        Some(self.0.get_porcelain())
+    }
+
+    fn get_junit(&self) -> Option<JunitReport> {
+        Some(JunitReport::new(self.0.get_junit_suites()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rover_client::shared::CheckTaskStatus;
+    use rover_client::shared::LintCheckResponse;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    fn check_response(task_status: CheckTaskStatus) -> CheckWorkflowResponse {
+        CheckWorkflowResponse {
+            default_target_url: "https://studio.apollographql.com/graph/my-graph/checks"
+                .to_string(),
+            maybe_core_schema_modified: Some(false),
+            maybe_operations_response: None,
+            maybe_lint_response: Some(LintCheckResponse {
+                task_status,
+                target_url: None,
+                diagnostics: vec![],
+                errors_count: 0,
+                warnings_count: 0,
+            }),
+            maybe_proposals_response: None,
+            maybe_custom_response: None,
+            maybe_downstream_response: None,
+        }
+    }
+
+    fn response() -> SupergraphCheckResponse {
+        SupergraphCheckResponse {
+            graph_ref: GraphRef {
+                name: "my-graph".to_string(),
+                variant: "current".to_string(),
+            },
+            subgraphs: BTreeMap::from([
+                (
+                    "products".to_string(),
+                    SubgraphCheckOutcome::Failed(check_response(CheckTaskStatus::FAILED)),
+                ),
+                (
+                    "reviews".to_string(),
+                    SubgraphCheckOutcome::Passed(check_response(CheckTaskStatus::PASSED)),
+                ),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_failure_message_names_failed_subgraphs() {
+        let failure = SupergraphCheckFailure(response());
+        assert_that!(failure.to_string()).is_equal_to(
+            "The changes in the schemas you proposed caused checks to fail for 1 subgraph: products."
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn test_json_reports_status_per_subgraph() {
+        let json = response().get_json();
+        assert_that!(json["subgraphs"]["products"]["status"]).is_equal_to(json!("FAILED"));
+        assert_that!(json["subgraphs"]["reviews"]["status"]).is_equal_to(json!("PASSED"));
+        assert_that!(json["subgraphs"]["reviews"]["tasks"]["lint"]["task_status"])
+            .is_equal_to(json!("PASSED"));
+    }
+}
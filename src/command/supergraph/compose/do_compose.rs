@@ -1,27 +1,28 @@
 // TODO: remove once we're no longer using the composition-rewrite feature flag
 #[allow(unused_imports)]
 use std::{
+    collections::BTreeMap,
     env::current_dir,
     fs::File,
     io::{stdin, Read, Write},
-    process::Command,
     str,
+    time::{Duration, Instant},
 };
 
 use anyhow::{anyhow, Context};
 
-// TODO: remove once we're no longer using the composition-rewrite feature flag
-#[cfg(not(feature = "dev-next"))]
 use apollo_federation_types::config::FederationVersion::LatestFedTwo;
 use apollo_federation_types::{
-    config::{FederationVersion, PluginVersion, SupergraphConfig},
+    config::{FederationVersion, PluginVersion, RouterVersion, SupergraphConfig},
     rover::BuildResult,
 };
-use camino::Utf8PathBuf;
+use calm_io::stderrln;
+use camino::{Utf8Path, Utf8PathBuf};
 use clap::{Args, Parser};
 use derive_getters::Getters;
 use rover_client::{shared::GraphRef, RoverClientError};
-use rover_std::warnln;
+use rover_std::progress::{Progress, ProgressBar};
+use rover_std::{progressln, timing, warnln, Style};
 use semver::Version;
 use serde::Serialize;
 
@@ -34,29 +35,40 @@ use tempfile::tempdir;
 use crate::{
     command::{
         install::{Install, Plugin},
-        supergraph::compose::CompositionOutput,
+        supergraph::{
+            compose::{
+                query_plans::{PlanOperations, PlanningRouter},
+                CompositionOutput,
+            },
+            provenance::Provenance,
+        },
     },
     composition::{
         events::CompositionEvent,
+        hints::{suggest_fixes_for_errors, suggest_fixes_for_hints, FixSuggestion},
+        profile::CompositionProfile,
         runner::{OneShotComposition, Runner},
         supergraph::{
-            binary::{OutputTarget, SupergraphBinary},
+            binary::{BinaryDiagnostics, OutputTarget, SupergraphBinary},
             config::{
                 full::{
                     FullyResolvedSubgraph, FullyResolvedSubgraphs, FullyResolvedSupergraphConfig,
                 },
                 resolver::SupergraphConfigResolver,
                 unresolved::UnresolvedSupergraphConfig,
+                validation::SupergraphConfigValidation,
             },
             install::InstallSupergraph,
             version::SupergraphVersion,
         },
+        validation::validate_compose_directives,
+        CompositionError,
     },
     options::PluginOpts,
     utils::{
         client::StudioClientConfig,
         effect::{
-            exec::TokioCommand,
+            exec::{ExecCommand, ExecCommandConfig, TokioCommand},
             fetch_remote_subgraph::RemoteSubgraph,
             install::InstallBinary,
             read_file::FsReadFile,
@@ -73,6 +85,37 @@ use crate::{
 pub struct Compose {
     #[clap(flatten)]
     opts: SupergraphComposeOpts,
+
+    /// Print how long each subgraph took to resolve, how large each subgraph's schema is, and how
+    /// long composition took to stderr after composing.
+    ///
+    /// The same breakdown is always included in `--format json` output.
+    #[arg(long = "profile-composition")]
+    profile_composition: bool,
+
+    /// Suggest the `@inaccessible`, `@shareable`, and `@override` directives that address
+    /// composition hints and field sharing errors, with a patch to each subgraph's schema.
+    ///
+    /// Suggestions are only patched into subgraphs whose schemas are resolved as SDL.
+    #[arg(long = "suggest-fixes")]
+    suggest_fixes: bool,
+
+    /// Prepend a comment header to the supergraph SDL recording the versions of Rover and the
+    /// `supergraph` binary that composed it, when, and hashes of the subgraphs it was composed
+    /// from and of the schema itself.
+    ///
+    /// Check a schema against its header with `rover supergraph verify`.
+    #[arg(long = "provenance")]
+    provenance: bool,
+
+    /// After composing, plan every operation in the `.graphql` and `.gql` files under this
+    /// directory with the router, and print each query plan along with the supergraph.
+    ///
+    /// The router only plans the operations, so none of your subgraphs are called. Compare the
+    /// plans before and after changing entity keys to see how planning changes.
+    #[arg(long = "emit-query-plans", value_name = "OPS_DIR")]
+    #[serde(skip_serializing)]
+    emit_query_plans: Option<Utf8PathBuf>,
 }
 
 #[cfg_attr(test, derive(Default))]
@@ -109,8 +152,19 @@ pub struct SupergraphComposeOpts {
     federation_version: Option<FederationVersion>,
 }
 
+impl From<SupergraphComposeOpts> for Compose {
+    fn from(opts: SupergraphComposeOpts) -> Self {
+        Self {
+            opts,
+            profile_composition: false,
+            suggest_fixes: false,
+            provenance: false,
+            emit_query_plans: None,
+        }
+    }
+}
+
 impl Compose {
-    #[cfg(not(feature = "dev-next"))]
     pub fn new(compose_opts: PluginOpts) -> Self {
         Self {
             opts: SupergraphComposeOpts {
@@ -121,6 +175,10 @@ impl Compose {
                     graph_ref: None,
                 },
             },
+            profile_composition: false,
+            suggest_fixes: false,
+            provenance: false,
+            emit_query_plans: None,
         }
     }
 
@@ -175,20 +233,37 @@ impl Compose {
         let graph_ref = self.opts.supergraph_config_source.graph_ref.clone();
 
         let one_shot_composition = OneShotComposition::builder()
-            .client_config(client_config)
+            .client_config(client_config.clone())
             .profile(profile)
             .elv2_license_accepter(self.opts.plugin_opts.elv2_license_accepter)
             .skip_update(self.opts.plugin_opts.skip_update)
+            .and_environment(self.opts.plugin_opts.env.clone())
+            .schema_size_opts(self.opts.plugin_opts.schema_size_opts)
+            .composition_limit_opts(self.opts.plugin_opts.composition_limit_opts)
+            .introspection_policy(self.opts.plugin_opts.introspection_policy_opts.policy())
+            // the composed supergraph is served outside of `rover dev`, so the router needs to
+            // know where to send requests for every subgraph
+            .supergraph_config_validation(SupergraphConfigValidation {
+                require_routing_urls: true,
+            })
             .and_federation_version(federation_version)
             .and_graph_ref(graph_ref)
             .and_supergraph_yaml(supergraph_yaml)
-            .and_override_install_path(override_install_path)
+            .and_override_install_path(override_install_path.clone())
             .and_output_file(output_file)
+            .suggest_fixes(self.suggest_fixes)
             .build();
 
-        Ok(RoverOutput::CompositionResult(
-            one_shot_composition.compose().await?,
-        ))
+        let mut output = one_shot_composition.compose().await?;
+        self.print_profile(&output)?;
+        if self.provenance {
+            // the resolved subgraphs stay inside the composition pipeline, so the header can't
+            // record them
+            self.add_provenance(&mut output, []);
+        }
+        self.add_query_plans(&mut output, override_install_path, client_config)
+            .await?;
+        Ok(RoverOutput::CompositionResult(output))
     }
 
     #[cfg(not(feature = "composition-rewrite"))]
@@ -198,6 +273,7 @@ impl Compose {
         client_config: StudioClientConfig,
         output_file: Option<Utf8PathBuf>,
     ) -> RoverResult<RoverOutput> {
+        let resolution_started_at = Instant::now();
         let mut supergraph_config = get_supergraph_config(
             &self.opts.supergraph_config_source.graph_ref,
             &self.opts.supergraph_config_source.supergraph_yaml.clone(),
@@ -205,35 +281,112 @@ impl Compose {
             client_config.clone(),
             &self.opts.plugin_opts.profile,
             true,
+            self.opts.plugin_opts.env.as_deref(),
+            self.opts.plugin_opts.introspection_policy_opts.policy(),
         )
         .await?
         .ok_or_else(|| anyhow!("error getting supergraph config"))?;
+        let resolution_time = resolution_started_at.elapsed();
 
-        self.compose(
-            override_install_path,
-            client_config,
-            &mut supergraph_config,
-            output_file,
-        )
-        .await
+        let mut output = self
+            .exec(
+                override_install_path.clone(),
+                client_config.clone(),
+                &mut supergraph_config,
+                output_file,
+            )
+            .await?;
+        output.profile = output
+            .profile
+            .map(|profile| profile.with_resolution_time(resolution_time));
+        self.print_profile(&output)?;
+        if self.provenance {
+            let definitions = supergraph_config.get_subgraph_definitions()?;
+            self.add_provenance(
+                &mut output,
+                definitions.iter().map(|definition| {
+                    (
+                        definition.name.as_str(),
+                        definition.url.as_str(),
+                        definition.sdl.as_str(),
+                    )
+                }),
+            );
+        }
+        self.add_query_plans(&mut output, override_install_path, client_config)
+            .await?;
+        Ok(RoverOutput::CompositionResult(output))
+    }
+
+    /// Prepends the provenance header to the composed supergraph SDL, recording `subgraphs` as
+    /// `(name, routing URL, SDL)`
+    fn add_provenance<'a>(
+        &self,
+        output: &mut CompositionOutput,
+        subgraphs: impl IntoIterator<Item = (&'a str, &'a str, &'a str)>,
+    ) {
+        let provenance = Provenance::new(
+            &output.supergraph_sdl,
+            output.federation_version.clone(),
+            subgraphs,
+        );
+        output.supergraph_sdl = provenance.prepend(&output.supergraph_sdl);
     }
 
-    pub async fn compose(
+    /// Plans the operations passed to `--emit-query-plans` against the composed supergraph
+    async fn add_query_plans(
         &self,
+        output: &mut CompositionOutput,
         override_install_path: Option<Utf8PathBuf>,
         client_config: StudioClientConfig,
-        supergraph_config: &mut SupergraphConfig,
-        output_file: Option<Utf8PathBuf>,
-    ) -> RoverResult<RoverOutput> {
-        let output = self
-            .exec(
+    ) -> RoverResult<()> {
+        let Some(ops_dir) = &self.emit_query_plans else {
+            return Ok(());
+        };
+        // read the operations first so that mistakes in them don't wait on the router
+        let operations = PlanOperations::from_dir(ops_dir)?;
+
+        self.opts
+            .plugin_opts
+            .elv2_license_accepter
+            .require_elv2_license(&client_config)?;
+        let install_command = Install {
+            force: false,
+            plugin: Some(Plugin::Router(RouterVersion::Latest)),
+            elv2_license_accepter: self.opts.plugin_opts.elv2_license_accepter,
+        };
+        let router_exe = install_command
+            .get_versioned_plugin(
                 override_install_path,
-                client_config,
-                supergraph_config,
-                output_file,
+                client_config.clone(),
+                self.opts.plugin_opts.skip_update,
             )
             .await?;
-        Ok(RoverOutput::CompositionResult(output))
+
+        let _timing = timing::time_phase("query planning");
+        progressln!("planning {} operations", operations.len());
+        let client = client_config.get_reqwest_client()?;
+        let mut router = PlanningRouter::start(&router_exe, &output.supergraph_sdl)?;
+        router.wait_for_startup(&client).await?;
+        output.query_plans = Some(router.plan(&client, &operations).await);
+        Ok(())
+    }
+
+    /// Prints the breakdown of where composition spent its time when `--profile-composition` is
+    /// passed
+    fn print_profile(&self, output: &CompositionOutput) -> RoverResult<()> {
+        if let (true, Some(profile)) = (self.profile_composition, &output.profile) {
+            stderrln!("{}", profile)?;
+        }
+        Ok(())
+    }
+
+    /// Prints the directives suggested for build errors, which don't make it into the output
+    fn print_suggestions(suggestions: &[FixSuggestion]) -> RoverResult<()> {
+        for suggestion in suggestions {
+            stderrln!("{} {}", Style::HintPrefix.paint("SUGGESTION:"), suggestion)?;
+        }
+        Ok(())
     }
 
     pub async fn exec(
@@ -268,7 +421,21 @@ impl Compose {
             _ => unreachable!("This version of Rover does not support major versions of federation other than 1 and 2.")
         };
         supergraph_config.set_federation_version(v);
-        let num_subgraphs = supergraph_config.get_subgraph_definitions()?.len();
+        self.opts
+            .plugin_opts
+            .schema_size_opts
+            .check(supergraph_config)?;
+        let definitions = supergraph_config.get_subgraph_definitions()?;
+        let num_subgraphs = definitions.len();
+        // composition fails on these too, but without saying which subgraph to fix
+        let compose_directive_problems = validate_compose_directives(
+            definitions
+                .iter()
+                .map(|definition| (definition.name.as_str(), definition.sdl.as_str())),
+        );
+        for (subgraph_name, problem) in compose_directive_problems {
+            warnln!("subgraph '{}': {}", subgraph_name, problem);
+        }
         let supergraph_config_yaml = serde_yaml::to_string(&supergraph_config)?;
         let dir = tempfile::Builder::new().prefix("supergraph").tempdir()?;
         tracing::debug!("temp dir created at {}", dir.path().display());
@@ -287,7 +454,7 @@ impl Compose {
                 "failed to get exact Federation version"
             )))?;
 
-        eprintln!(
+        progressln!(
             "composing supergraph with Federation {}",
             &federation_version.get_tarball_version()
         );
@@ -301,38 +468,92 @@ impl Compose {
             output_file = None;
         }
 
-        // Whether we use stdout or a file dependson whether the the `--output` option was used
-        let content = match output_file {
-            // If it was, we use a file in the supergraph binary; this cuts down the overall time
-            // it takes to do composition when we're working on really large compositions, but it
-            // carries with it the assumption that stdout is superfluous
-            Some(filepath) => {
-                Command::new(&exe)
-                    .args(["compose", yaml_path.as_ref(), filepath.as_ref()])
-                    .output()
-                    .context("Failed to execute command")?;
-
-                let mut composition_file = std::fs::File::open(&filepath).unwrap();
-                let mut content: String = String::new();
-                composition_file.read_to_string(&mut content).unwrap();
-                content
-            }
-            // When we aren't using `--output`, we dump the composition directly to stdout
-            None => {
-                let output = Command::new(&exe)
-                    .args(["compose", yaml_path.as_ref()])
-                    .output()
-                    .context("Failed to execute command")?;
-
-                let content = str::from_utf8(&output.stdout)
-                    .with_context(|| format!("Could not parse output of `{} compose`", &exe))?;
-                content.to_string()
-            }
+        // The composed supergraph is written next to its subgraphs' schemas, and usually
+        // weighs less than twice as much
+        if let Some(filepath) = &output_file {
+            let sdl_bytes: u64 = definitions
+                .iter()
+                .map(|definition| definition.sdl.len() as u64)
+                .sum();
+            let dir = filepath
+                .parent()
+                .filter(|parent| !parent.as_str().is_empty())
+                .unwrap_or(Utf8Path::new("."));
+            binstall::require_disk_space(dir, sdl_bytes * 2)?;
+        }
+
+        let composition_started_at = Instant::now();
+        let composition_timing = timing::time_phase("composition");
+        let composition_progress = ProgressBar::new("composing supergraph");
+        let mut args = vec!["compose".to_string(), yaml_path.to_string()];
+        // If `--output` was used, we use a file in the supergraph binary; this cuts down the
+        // overall time it takes to do composition when we're working on really large
+        // compositions, but it carries with it the assumption that stdout is superfluous
+        if let Some(filepath) = &output_file {
+            args.push(filepath.to_string());
+        }
+        let limits = self.opts.plugin_opts.composition_limit_opts;
+        let exec_command = TokioCommand::default();
+        let exec = exec_command.exec_command(
+            ExecCommandConfig::builder()
+                .exe(exe.clone())
+                .args(args.clone())
+                .and_max_stdout_bytes(limits.max_output_bytes())
+                .build(),
+        );
+        let output = match limits.timeout() {
+            Some(timeout) => tokio::time::timeout(timeout, exec).await.map_err(|_| {
+                RoverError::new(CompositionError::Timeout {
+                    binary: exe.clone(),
+                    timeout,
+                })
+            })?,
+            None => exec.await,
+        }
+        .context("Failed to execute command")?;
+        let composition_time = composition_started_at.elapsed();
+        composition_progress.finish();
+        drop(composition_timing);
+
+        // When the binary crashes or bails out before reporting a composition result, everything
+        // it printed is the only clue as to why
+        let binary_failed = || -> RoverError {
+            let mut argv = vec![exe.to_string()];
+            argv.extend(args.clone());
+            let diagnostics = BinaryDiagnostics::new(argv, yaml_path.clone(), &output);
+            let mut error = RoverError::new(anyhow!(
+                "`{} compose` failed without producing a composition result\n{}",
+                &exe,
+                diagnostics
+            ));
+            error.set_suggestion(RoverErrorSuggestion::SubmitIssue);
+            error
+        };
+
+        // Whether we read the composition from a file or stdout depends on whether the
+        // `--output` option was used
+        let content = match &output_file {
+            Some(filepath) => match std::fs::read_to_string(filepath) {
+                Ok(content) => content,
+                Err(_) if !output.status.success() => return Err(binary_failed()),
+                Err(err) => {
+                    return Err(RoverError::new(anyhow!(
+                        "Could not read the output of `{} compose` at {}: {}",
+                        &exe,
+                        filepath,
+                        err
+                    )))
+                }
+            },
+            None => str::from_utf8(&output.stdout)
+                .with_context(|| format!("Could not parse output of `{} compose`", &exe))?
+                .to_string(),
         };
 
         // Make sure the composition is well-formed
         let composition = match serde_json::from_str::<BuildResult>(&content) {
             Ok(res) => res,
+            Err(_) if !output.status.success() => return Err(binary_failed()),
             Err(err) => {
                 return Err(anyhow!("{}", err))
                     .with_context(|| anyhow!("{} compose output: {}", &exe, content))
@@ -347,14 +568,34 @@ impl Compose {
 
         match composition {
             Ok(build_output) => Ok(CompositionOutput {
+                query_plans: None,
+                suggestions: self
+                    .suggest_fixes
+                    .then(|| suggest_fixes_for_hints(&build_output.hints, supergraph_config)),
                 hints: build_output.hints,
                 supergraph_sdl: build_output.supergraph_sdl,
                 federation_version: Some(format_version(federation_version.to_string())),
+                // subgraphs are resolved before they get here, so only composition is timed
+                profile: Some(CompositionProfile::new(
+                    supergraph_config,
+                    &BTreeMap::new(),
+                    Duration::ZERO,
+                    composition_time,
+                    composition_time,
+                )),
             }),
-            Err(build_errors) => Err(RoverError::from(RoverClientError::BuildErrors {
-                source: build_errors,
-                num_subgraphs,
-            })),
+            Err(build_errors) => {
+                if self.suggest_fixes {
+                    Self::print_suggestions(&suggest_fixes_for_errors(
+                        &build_errors,
+                        supergraph_config,
+                    ))?;
+                }
+                Err(RoverError::from(RoverClientError::BuildErrors {
+                    source: build_errors,
+                    num_subgraphs,
+                }))
+            }
         }
     }
 
@@ -11,15 +11,29 @@ pub(crate) mod do_compose;
 pub(crate) use do_compose::Compose;
 
 #[cfg(feature = "composition-js")]
-use crate::composition::CompositionSuccess;
+pub(crate) mod query_plans;
+
+#[cfg(feature = "composition-js")]
+use crate::composition::{hints::FixSuggestion, CompositionSuccess};
+#[cfg(feature = "composition-js")]
+use query_plans::QueryPlan;
 
 use apollo_federation_types::rover::BuildHint;
 
+use crate::composition::profile::CompositionProfile;
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CompositionOutput {
     pub supergraph_sdl: String,
     pub hints: Vec<BuildHint>,
     pub federation_version: Option<String>,
+    pub profile: Option<CompositionProfile>,
+    /// Directives to add to subgraphs to address the hints, when `--suggest-fixes` is passed
+    #[cfg(feature = "composition-js")]
+    pub suggestions: Option<Vec<FixSuggestion>>,
+    /// The plans of the operations passed to `--emit-query-plans`
+    #[cfg(feature = "composition-js")]
+    pub query_plans: Option<Vec<QueryPlan>>,
 }
 
 // Temporary conversion from new CompositionSuccess type to old CompositionOutput. In the future,
@@ -32,6 +46,9 @@ impl From<CompositionSuccess> for CompositionOutput {
             supergraph_sdl: value.supergraph_sdl().clone(),
             hints: value.hints().to_vec(),
             federation_version: Some(value.federation_version().to_string()),
+            profile: value.profile().clone(),
+            suggestions: None,
+            query_plans: None,
         }
     }
 }
@@ -0,0 +1,350 @@
+use std::{
+    fs::File,
+    net::TcpListener,
+    process::Stdio,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context};
+use apollo_parser::{cst, Parser};
+use camino::{Utf8Path, Utf8PathBuf};
+use reqwest::Client;
+use rover_std::Fs;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tempfile::TempDir;
+use tokio::process::{Child, Command};
+
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+/// How long the router gets to load the supergraph before planning is given up on
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The query plan of one operation, or why the router couldn't plan it
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct QueryPlan {
+    /// The file the operation is in, relative to the operations directory, followed by
+    /// `#<operation name>` when the file has more than one operation
+    pub operation: String,
+    pub query_plan: Option<String>,
+    pub error: Option<String>,
+}
+
+/// An operation to plan, found in the directory passed to `--emit-query-plans`
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct PlanOperation {
+    id: String,
+    document: String,
+    operation_name: Option<String>,
+}
+
+/// Every operation in the `.graphql` and `.gql` files under a directory
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PlanOperations {
+    operations: Vec<PlanOperation>,
+}
+
+impl PlanOperations {
+    pub fn from_dir(dir: &Utf8Path) -> RoverResult<Self> {
+        let mut paths = Vec::new();
+        collect_operation_files(dir, &mut paths)
+            .with_context(|| format!("Could not read the operations in {}", dir))?;
+        paths.sort();
+
+        let mut operations = Vec::new();
+        for path in paths {
+            let relative_path = path.strip_prefix(dir).unwrap_or(&path).to_string();
+            let document = Fs::read_file(&path)?;
+            operations.extend(Self::from_document(&relative_path, document)?);
+        }
+
+        if operations.is_empty() {
+            return Err(RoverError::new(anyhow!(
+                "No operations were found in the `.graphql` or `.gql` files in {}",
+                dir
+            )));
+        }
+        Ok(Self { operations })
+    }
+
+    fn from_document(relative_path: &str, document: String) -> RoverResult<Vec<PlanOperation>> {
+        let parsed = Parser::new(&document).parse();
+        if let Some(err) = parsed.errors().next() {
+            return Err(RoverError::new(anyhow!(
+                "Could not parse the operations in {}: {}",
+                relative_path,
+                err.message()
+            )));
+        }
+
+        let operation_names: Vec<Option<String>> = parsed
+            .document()
+            .definitions()
+            .filter_map(|definition| match definition {
+                cst::Definition::OperationDefinition(operation) => {
+                    Some(operation.name().map(|name| name.text().to_string()))
+                }
+                _ => None,
+            })
+            .collect();
+        if operation_names.len() > 1 && operation_names.iter().any(Option::is_none) {
+            let mut err =
+                RoverError::new(anyhow!("Found an anonymous operation in {}", relative_path));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Give every operation a name when there is more than one in a file.".to_string(),
+            ));
+            return Err(err);
+        }
+
+        let is_single = operation_names.len() == 1;
+        Ok(operation_names
+            .into_iter()
+            .map(|operation_name| PlanOperation {
+                id: match (&operation_name, is_single) {
+                    (Some(name), false) => format!("{}#{}", relative_path, name),
+                    _ => relative_path.to_string(),
+                },
+                document: document.clone(),
+                operation_name,
+            })
+            .collect())
+    }
+
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+}
+
+fn collect_operation_files(dir: &Utf8Path, paths: &mut Vec<Utf8PathBuf>) -> anyhow::Result<()> {
+    for entry in dir.read_dir_utf8()? {
+        let path = entry?.into_path();
+        if path.is_dir() {
+            collect_operation_files(&path, paths)?;
+        } else if matches!(path.extension(), Some("graphql" | "gql")) {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// A router serving a supergraph only to plan operations. The router is asked for each plan
+/// with the `Apollo-Expose-Query-Plan: dry-run` header, so no subgraph is ever called.
+pub struct PlanningRouter {
+    child: Child,
+    endpoint: String,
+    health_endpoint: String,
+    log_path: Utf8PathBuf,
+    // holds the supergraph, config, and log until the router is stopped
+    _dir: TempDir,
+}
+
+impl PlanningRouter {
+    pub fn start(router_exe: &Utf8Path, supergraph_sdl: &str) -> RoverResult<Self> {
+        let dir = tempfile::Builder::new().prefix("query-plans").tempdir()?;
+        let dir_path = Utf8PathBuf::try_from(dir.path().to_path_buf())?;
+        let supergraph_path = dir_path.join("supergraph.graphql");
+        let config_path = dir_path.join("router.yaml");
+        Fs::write_file(&supergraph_path, supergraph_sdl)?;
+
+        let listen = format!("127.0.0.1:{}", free_port()?);
+        let health_listen = format!("127.0.0.1:{}", free_port()?);
+        let config = json!({
+            "supergraph": { "listen": listen },
+            "health_check": { "enabled": true, "listen": health_listen },
+            "homepage": { "enabled": false },
+            "plugins": { "experimental.expose_query_plan": true },
+        });
+        Fs::write_file(&config_path, serde_yaml::to_string(&config)?)?;
+
+        let log_path = dir_path.join("router.log");
+        let log = File::create(&log_path)?;
+        let child = Command::new(router_exe)
+            .args([
+                "--supergraph",
+                supergraph_path.as_str(),
+                "--config",
+                config_path.as_str(),
+                "--log",
+                "error",
+            ])
+            .env("APOLLO_TELEMETRY_DISABLED", "true")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(log)
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Could not start {}", router_exe))?;
+
+        Ok(Self {
+            child,
+            endpoint: format!("http://{}/", listen),
+            health_endpoint: format!("http://{}/health?ready", health_listen),
+            log_path,
+            _dir: dir,
+        })
+    }
+
+    pub async fn wait_for_startup(&mut self, client: &Client) -> RoverResult<()> {
+        let started_at = Instant::now();
+        while started_at.elapsed() < STARTUP_TIMEOUT {
+            if let Some(status) = self.child.try_wait()? {
+                return Err(self.startup_error(format!("The router exited with {}", status)));
+            }
+            let ready = client
+                .get(&self.health_endpoint)
+                .send()
+                .await
+                .is_ok_and(|response| response.status().is_success());
+            if ready {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+        Err(self.startup_error(format!(
+            "The router didn't start within {}s",
+            STARTUP_TIMEOUT.as_secs()
+        )))
+    }
+
+    fn startup_error(&self, reason: String) -> RoverError {
+        let log = Fs::read_file(&self.log_path).unwrap_or_default();
+        RoverError::new(anyhow!(
+            "{}, so query plans could not be emitted.\n{}",
+            reason,
+            log.trim()
+        ))
+    }
+
+    pub async fn plan(&self, client: &Client, operations: &PlanOperations) -> Vec<QueryPlan> {
+        let mut plans = Vec::with_capacity(operations.len());
+        for operation in &operations.operations {
+            let plan = request_plan(client, &self.endpoint, operation).await;
+            plans.push(QueryPlan {
+                operation: operation.id.clone(),
+                error: plan.as_ref().err().cloned(),
+                query_plan: plan.ok(),
+            });
+        }
+        plans
+    }
+}
+
+/// Binds to a port the OS picks and releases it again, for the router to listen on
+fn free_port() -> RoverResult<u16> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+async fn request_plan(
+    client: &Client,
+    endpoint: &str,
+    operation: &PlanOperation,
+) -> Result<String, String> {
+    let response = client
+        .post(endpoint)
+        .header("Apollo-Expose-Query-Plan", "dry-run")
+        .json(&json!({
+            "query": operation.document,
+            "operationName": operation.operation_name,
+        }))
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+    let status = response.status();
+    let body: Value = response.json().await.map_err(|err| err.to_string())?;
+
+    if let Some(plan) = body["extensions"]["apolloQueryPlan"]["text"].as_str() {
+        return Ok(plan.trim().to_string());
+    }
+    match body["errors"].as_array() {
+        Some(errors) if !errors.is_empty() => Err(errors
+            .iter()
+            .map(|error| error["message"].as_str().unwrap_or("unknown error"))
+            .collect::<Vec<_>>()
+            .join("; ")),
+        _ if !status.is_success() => Err(format!("the router responded with {}", status)),
+        _ => Err("the router didn't return a query plan".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::MockServer;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_from_dir_finds_operations_in_nested_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = Utf8PathBuf::try_from(dir.path().to_path_buf()).unwrap();
+        Fs::write_file(dir_path.join("single.graphql"), "{ products { id } }").unwrap();
+        std::fs::create_dir(dir_path.join("nested")).unwrap();
+        Fs::write_file(
+            dir_path.join("nested/many.gql"),
+            "query A { a } query B { ...F } fragment F on Query { b }",
+        )
+        .unwrap();
+        Fs::write_file(dir_path.join("README.md"), "not an operation").unwrap();
+
+        let operations = PlanOperations::from_dir(&dir_path).unwrap();
+        let ids = operations
+            .operations
+            .iter()
+            .map(|operation| operation.id.as_str())
+            .collect::<Vec<_>>();
+        assert_that!(ids).is_equal_to(vec![
+            "nested/many.gql#A",
+            "nested/many.gql#B",
+            "single.graphql",
+        ]);
+    }
+
+    #[rstest]
+    #[case::mixed_anonymous("query A { a } { b }")]
+    #[case::invalid("query A {")]
+    #[case::empty("fragment F on Query { a }")]
+    fn test_from_dir_rejects_invalid_operations(#[case] document: &str) {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = Utf8PathBuf::try_from(dir.path().to_path_buf()).unwrap();
+        Fs::write_file(dir_path.join("ops.graphql"), document).unwrap();
+        assert_that!(PlanOperations::from_dir(&dir_path)).is_err();
+    }
+
+    #[tokio::test]
+    async fn test_request_plan_returns_the_plan_text_or_errors() {
+        let server = MockServer::start();
+        let plan_mock = server.mock(|when, then| {
+            when.method("POST")
+                .path("/")
+                .header("Apollo-Expose-Query-Plan", "dry-run")
+                .json_body_partial(r#"{"operationName": "Ok"}"#);
+            then.status(200).json_body(json!({
+                "data": null,
+                "extensions": { "apolloQueryPlan": { "text": "QueryPlan {\n  Fetch(service: \"products\") {}\n}\n" } }
+            }));
+        });
+        let error_mock = server.mock(|when, then| {
+            when.method("POST")
+                .path("/")
+                .json_body_partial(r#"{"operationName": "Failing"}"#);
+            then.status(400)
+                .json_body(json!({ "errors": [{ "message": "Cannot query field \"b\"" }] }));
+        });
+
+        let operations = PlanOperations::from_document(
+            "ops.graphql",
+            "query Ok { a } query Failing { b }".to_string(),
+        )
+        .unwrap();
+        let client = Client::new();
+        let endpoint = server.url("/");
+        assert_that!(request_plan(&client, &endpoint, &operations[0]).await).is_equal_to(Ok(
+            "QueryPlan {\n  Fetch(service: \"products\") {}\n}".to_string(),
+        ));
+        assert_that!(request_plan(&client, &endpoint, &operations[1]).await)
+            .is_equal_to(Err("Cannot query field \"b\"".to_string()));
+        plan_mock.assert();
+        error_mock.assert();
+    }
+}
@@ -0,0 +1,89 @@
+use apollo_federation_types::config::{SchemaSource, SubgraphConfig};
+use camino::Utf8PathBuf;
+use clap::{ArgGroup, Parser};
+use rover_client::shared::GraphRef;
+use serde::Serialize;
+use url::Url;
+
+use crate::{RoverOutput, RoverResult};
+
+use super::edit_supergraph_config;
+
+#[derive(Debug, Serialize, Parser)]
+#[clap(
+    group = ArgGroup::new("schema_source")
+        .args(&["schema", "subgraph_url", "graph_ref"]).required(true)
+)]
+/// Add a subgraph to a supergraph config file
+///
+/// The subgraph is added after the last one in the file, and everything else in the file is left
+/// as it was. Its schema comes from exactly one of `--schema`, `--subgraph-url`, or `--graph-ref`.
+pub struct AddSubgraph {
+    /// The name of the subgraph to add
+    #[serde(skip_serializing)]
+    name: String,
+
+    /// The path to the supergraph config file to edit
+    #[arg(long = "config")]
+    #[serde(skip_serializing)]
+    supergraph_yaml: Utf8PathBuf,
+
+    /// The URL the router should send requests for this subgraph to
+    #[arg(long = "routing-url")]
+    #[serde(skip_serializing)]
+    routing_url: Option<String>,
+
+    /// The path to a file containing the subgraph's schema, relative to the supergraph config
+    #[arg(long = "schema", short = 's')]
+    #[serde(skip_serializing)]
+    schema: Option<Utf8PathBuf>,
+
+    /// The URL of a running subgraph to introspect for its schema
+    #[arg(long = "subgraph-url")]
+    #[serde(skip_serializing)]
+    subgraph_url: Option<Url>,
+
+    /// A graph ref in Apollo Studio to fetch the subgraph's schema from
+    #[arg(long = "graph-ref")]
+    #[serde(skip_serializing)]
+    graph_ref: Option<GraphRef>,
+
+    /// The name of the subgraph in `--graph-ref` to fetch the schema of. Defaults to the name of
+    /// the subgraph being added
+    #[arg(long = "subgraph", requires = "graph_ref")]
+    #[serde(skip_serializing)]
+    subgraph: Option<String>,
+}
+
+impl AddSubgraph {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let subgraph_config = SubgraphConfig {
+            routing_url: self.routing_url.clone(),
+            schema: self.schema_source(),
+        };
+        edit_supergraph_config(&self.supergraph_yaml, |editor| {
+            editor.add_subgraph(&self.name, &subgraph_config)
+        })?;
+        eprintln!(
+            "Added subgraph '{}' to {}",
+            &self.name, &self.supergraph_yaml
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+
+    fn schema_source(&self) -> SchemaSource {
+        match (&self.schema, &self.subgraph_url, &self.graph_ref) {
+            (Some(file), _, _) => SchemaSource::File { file: file.clone() },
+            (_, Some(subgraph_url), _) => SchemaSource::SubgraphIntrospection {
+                subgraph_url: subgraph_url.clone(),
+                introspection_headers: None,
+            },
+            (_, _, Some(graph_ref)) => SchemaSource::Subgraph {
+                graphref: graph_ref.to_string(),
+                subgraph: self.subgraph.clone().unwrap_or_else(|| self.name.clone()),
+            },
+            // clap requires exactly one of the schema sources
+            (None, None, None) => unreachable!("a schema source is required"),
+        }
+    }
+}
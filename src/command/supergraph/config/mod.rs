@@ -0,0 +1,54 @@
+mod add_subgraph;
+mod remove_subgraph;
+mod set_routing_url;
+
+use camino::Utf8PathBuf;
+use clap::Parser;
+use rover_std::Fs;
+use serde::Serialize;
+
+use crate::composition::supergraph::config::editor::{
+    SupergraphConfigEditError, SupergraphConfigEditor,
+};
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub enum Command {
+    /// Add a subgraph to a supergraph config file
+    AddSubgraph(add_subgraph::AddSubgraph),
+
+    /// Remove a subgraph from a supergraph config file
+    RemoveSubgraph(remove_subgraph::RemoveSubgraph),
+
+    /// Change the routing URL of a subgraph in a supergraph config file
+    SetRoutingUrl(set_routing_url::SetRoutingUrl),
+}
+
+impl Config {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        match &self.command {
+            Command::AddSubgraph(command) => command.run(),
+            Command::RemoveSubgraph(command) => command.run(),
+            Command::SetRoutingUrl(command) => command.run(),
+        }
+    }
+}
+
+/// Applies an edit to the supergraph config file at `path`, replacing the file only once the
+/// whole edit has succeeded
+fn edit_supergraph_config(
+    path: &Utf8PathBuf,
+    edit: impl FnOnce(&mut SupergraphConfigEditor) -> Result<(), SupergraphConfigEditError>,
+) -> RoverResult<()> {
+    let contents = Fs::read_file(path)?;
+    let mut editor = SupergraphConfigEditor::new(&contents);
+    edit(&mut editor)?;
+    Fs::write_file_atomically(path, editor.finish()?)?;
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::{RoverOutput, RoverResult};
+
+use super::edit_supergraph_config;
+
+#[derive(Debug, Serialize, Parser)]
+/// Remove a subgraph from a supergraph config file
+///
+/// Comments directly above the subgraph are removed along with it. Everything else in the file is
+/// left as it was.
+pub struct RemoveSubgraph {
+    /// The name of the subgraph to remove
+    #[serde(skip_serializing)]
+    name: String,
+
+    /// The path to the supergraph config file to edit
+    #[arg(long = "config")]
+    #[serde(skip_serializing)]
+    supergraph_yaml: Utf8PathBuf,
+}
+
+impl RemoveSubgraph {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        edit_supergraph_config(&self.supergraph_yaml, |editor| {
+            editor.remove_subgraph(&self.name)
+        })?;
+        eprintln!(
+            "Removed subgraph '{}' from {}",
+            &self.name, &self.supergraph_yaml
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
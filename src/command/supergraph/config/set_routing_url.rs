@@ -0,0 +1,41 @@
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::{RoverOutput, RoverResult};
+
+use super::edit_supergraph_config;
+
+#[derive(Debug, Serialize, Parser)]
+/// Change the routing URL of a subgraph in a supergraph config file
+///
+/// The subgraph is given a `routing_url` if it doesn't have one yet. Everything else in the file
+/// is left as it was.
+pub struct SetRoutingUrl {
+    /// The name of the subgraph to change
+    #[serde(skip_serializing)]
+    name: String,
+
+    /// The path to the supergraph config file to edit
+    #[arg(long = "config")]
+    #[serde(skip_serializing)]
+    supergraph_yaml: Utf8PathBuf,
+
+    /// The URL the router should send requests for this subgraph to
+    #[arg(long = "routing-url")]
+    #[serde(skip_serializing)]
+    routing_url: String,
+}
+
+impl SetRoutingUrl {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        edit_supergraph_config(&self.supergraph_yaml, |editor| {
+            editor.set_routing_url(&self.name, &self.routing_url)
+        })?;
+        eprintln!(
+            "Set the routing URL of subgraph '{}' to {} in {}",
+            &self.name, &self.routing_url, &self.supergraph_yaml
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
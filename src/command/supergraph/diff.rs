@@ -0,0 +1,123 @@
+use anyhow::anyhow;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::command::supergraph::compose::do_compose::Compose;
+use crate::options::PluginOpts;
+use crate::schema_diff::SchemaDiff;
+use crate::utils::client::StudioClientConfig;
+use crate::utils::parsers::FileDescriptorType;
+use crate::utils::supergraph_config::get_supergraph_config;
+use crate::{RoverError, RoverOutput, RoverResult, StructuredFailure};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Diff {
+    /// The supergraph to compare from: either a supergraph configuration file (`.yaml` or `.yml`)
+    /// to compose, or a supergraph SDL file. You can pass `-` to read SDL from stdin instead.
+    #[arg(value_name = "OLD")]
+    #[serde(skip_serializing)]
+    old: FileDescriptorType,
+
+    /// The supergraph to compare to, in the same formats as OLD
+    #[arg(value_name = "NEW")]
+    #[serde(skip_serializing)]
+    new: FileDescriptorType,
+
+    #[clap(flatten)]
+    plugin_opts: PluginOpts,
+
+    /// Exit with an error if the API schemas of the two supergraphs differ
+    #[arg(long)]
+    fail_on_changes: bool,
+}
+
+impl Diff {
+    pub async fn run(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+    ) -> RoverResult<RoverOutput> {
+        let old_sdl = load_supergraph_sdl(
+            &self.old,
+            &self.plugin_opts,
+            override_install_path.clone(),
+            &client_config,
+        )
+        .await?;
+        let new_sdl = load_supergraph_sdl(
+            &self.new,
+            &self.plugin_opts,
+            override_install_path,
+            &client_config,
+        )
+        .await?;
+
+        let diff = SchemaDiff::new(&old_sdl, &new_sdl)?;
+        if self.fail_on_changes && !diff.is_empty() {
+            Err(RoverError::from_failure(SupergraphDiffFailure(diff)))
+        } else {
+            Ok(RoverOutput::SupergraphDiff(diff))
+        }
+    }
+}
+
+/// Reads supergraph SDL from `source`, composing it first if `source` is a supergraph config
+pub(crate) async fn load_supergraph_sdl(
+    source: &FileDescriptorType,
+    plugin_opts: &PluginOpts,
+    override_install_path: Option<Utf8PathBuf>,
+    client_config: &StudioClientConfig,
+) -> RoverResult<String> {
+    let is_config = matches!(
+        source,
+        FileDescriptorType::File(path) if matches!(path.extension(), Some("yaml" | "yml"))
+    );
+    if !is_config {
+        return source.read_file_descriptor("supergraph SDL", &mut std::io::stdin());
+    }
+
+    let mut supergraph_config = get_supergraph_config(
+        &None,
+        &Some(source.clone()),
+        None,
+        client_config.clone(),
+        &plugin_opts.profile,
+        true,
+        plugin_opts.env.as_deref(),
+        plugin_opts.introspection_policy_opts.policy(),
+    )
+    .await?
+    .ok_or_else(|| anyhow!("error getting supergraph config"))?;
+    let output = Compose::new(plugin_opts.clone())
+        .exec(
+            override_install_path,
+            client_config.clone(),
+            &mut supergraph_config,
+            None,
+        )
+        .await?;
+    Ok(output.supergraph_sdl)
+}
+
+/// Returned by `rover supergraph diff --fail-on-changes` when the API schemas differ
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "The API schemas of the two supergraphs differ by {}.",
+    match .0.changes.len() {
+        1 => "1 change".to_string(),
+        n => format!("{} changes", n),
+    }
+)]
+pub struct SupergraphDiffFailure(pub SchemaDiff);
+
+impl StructuredFailure for SupergraphDiffFailure {
+    fn get_output(&self) -> String {
+        self.0.get_output()
+    }
+
+    fn get_json(&self) -> Value {
+        self.0.get_json()
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
 };
 
 use rover_client::operations::supergraph::fetch::{self, SupergraphFetchInput};
-use rover_std::Style;
+use rover_std::{progressln, Style};
 
 use clap::Parser;
 use serde::Serialize;
@@ -23,7 +23,7 @@ impl Fetch {
     pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
         let graph_ref = self.graph.graph_ref.to_string();
-        eprintln!(
+        progressln!(
             "Fetching supergraph SDL from {} using credentials from the {} profile.",
             Style::Link.paint(graph_ref),
             Style::Command.paint(&self.profile.profile_name)
@@ -0,0 +1,279 @@
+use std::{
+    collections::HashMap,
+    io::{self, IsTerminal},
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use apollo_federation_types::config::{SchemaSource, SubgraphConfig};
+use camino::Utf8PathBuf;
+use clap::{Args, Parser};
+use dialoguer::Input;
+use rover_std::{progressln, Fs, Style};
+use serde::Serialize;
+use url::Url;
+
+use crate::command::dev::legacy::netstat::{listening_loopback_ports, PortRange};
+use crate::composition::supergraph::config::editor::{
+    SupergraphConfigEditError, SupergraphConfigEditor,
+};
+use crate::utils::client::StudioClientConfig;
+use crate::utils::effect::introspect::{IntrospectSubgraph, IntrospectionPolicy};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+/// How long to wait for a port to accept a connection while scanning
+const PORT_PROBE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// How long to wait for an endpoint that accepted a connection to answer introspection
+const INTROSPECTION_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Parser)]
+/// Create a supergraph config from the subgraphs running locally
+///
+/// Scans ports on the local machine for GraphQL endpoints that can be introspected as subgraphs,
+/// asks for a name for each one when attached to a TTY, and writes a supergraph config that
+/// composes them.
+pub struct Init {
+    /// The path to write the supergraph config to
+    #[arg(long = "config", default_value = "supergraph.yaml")]
+    #[serde(skip_serializing)]
+    supergraph_yaml: Utf8PathBuf,
+
+    #[clap(flatten)]
+    discovery: SubgraphDiscoveryOpts,
+
+    /// Write each subgraph's introspected schema to a file next to the supergraph config, and
+    /// compose from the files instead of introspecting the subgraphs
+    #[arg(long = "stub-schemas")]
+    stub_schemas: bool,
+}
+
+/// Where to look for subgraphs running locally
+#[derive(Debug, Serialize, Args)]
+pub(crate) struct SubgraphDiscoveryOpts {
+    /// The local ports to scan for subgraphs, as a comma-separated list of ports and port ranges
+    #[arg(
+        long = "ports",
+        value_delimiter = ',',
+        default_value = "3000-3010,4000-4010,8000-8010,8080-8090"
+    )]
+    #[serde(skip_serializing)]
+    ports: Vec<PortRange>,
+
+    /// The paths to look for a GraphQL endpoint at on each port, in order
+    #[arg(
+        long = "graphql-path",
+        value_delimiter = ',',
+        default_value = "/graphql,/"
+    )]
+    #[serde(skip_serializing)]
+    graphql_paths: Vec<String>,
+}
+
+/// A local GraphQL endpoint that answered subgraph introspection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiscoveredSubgraph {
+    pub(crate) name: String,
+    pub(crate) url: Url,
+    pub(crate) sdl: String,
+}
+
+impl Init {
+    pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        if Fs::assert_path_exists(&self.supergraph_yaml).is_ok() {
+            let mut err = RoverError::new(anyhow!("{} already exists", self.supergraph_yaml));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Add subgraphs to it with `{}`, or pass a different path with `--config`.",
+                Style::Command.paint("rover supergraph config add-subgraph")
+            )));
+            return Err(err);
+        }
+
+        let discovered = self.discovery.discover(&client_config).await?;
+        if discovered.is_empty() {
+            let mut err = RoverError::new(anyhow!("Could not find any subgraphs running locally"));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Start your subgraphs, or scan the ports they run on with `--ports`.".to_string(),
+            ));
+            return Err(err);
+        }
+
+        let (contents, stubs) = render_supergraph_config(&discovered, self.stub_schemas)?;
+        let root = self
+            .supergraph_yaml
+            .parent()
+            .map(Utf8PathBuf::from)
+            .unwrap_or_default();
+        for (file, sdl) in stubs {
+            Fs::write_file(root.join(file), sdl)?;
+        }
+        Fs::write_file(&self.supergraph_yaml, contents)?;
+        eprintln!(
+            "Wrote {} with {} subgraphs. Run `{}` to start developing against them",
+            self.supergraph_yaml,
+            discovered.len(),
+            Style::Command.paint(format!(
+                "rover dev --supergraph-config {}",
+                self.supergraph_yaml
+            ))
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
+
+impl SubgraphDiscoveryOpts {
+    /// Scans the ports for subgraphs, asking for a name for each one when attached to a TTY
+    pub(crate) async fn discover(
+        &self,
+        client_config: &StudioClientConfig,
+    ) -> RoverResult<Vec<DiscoveredSubgraph>> {
+        let ports = self.ports.iter().flat_map(PortRange::ports);
+        progressln!(
+            "Scanning ports {} for subgraphs...",
+            self.ports
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let mut discovered = Vec::new();
+        for port in listening_loopback_ports(ports, PORT_PROBE_TIMEOUT).await {
+            if let Some((url, sdl)) = self.introspect_port(client_config, port).await {
+                progressln!("Found a subgraph at {}", Style::Link.paint(url.as_str()));
+                discovered.push(DiscoveredSubgraph {
+                    name: format!("subgraph-{port}"),
+                    url,
+                    sdl,
+                });
+            }
+        }
+
+        if io::stderr().is_terminal() {
+            for subgraph in &mut discovered {
+                subgraph.name = Input::new()
+                    .with_prompt(format!(
+                        "what is the name of the subgraph at {}?",
+                        subgraph.url
+                    ))
+                    .default(subgraph.name.clone())
+                    .interact_text()?;
+            }
+        }
+        Ok(discovered)
+    }
+
+    /// Introspects each of the GraphQL paths on `port`, returning the first that answers
+    async fn introspect_port(
+        &self,
+        client_config: &StudioClientConfig,
+        port: u16,
+    ) -> Option<(Url, String)> {
+        for path in &self.graphql_paths {
+            let Ok(url) = Url::parse(&format!("http://localhost:{port}{path}")) else {
+                continue;
+            };
+            let policy = IntrospectionPolicy {
+                timeout: Some(INTROSPECTION_TIMEOUT),
+                ..Default::default()
+            };
+            match client_config
+                .introspect_subgraph(url.clone(), HashMap::new(), None, policy)
+                .await
+            {
+                Ok(sdl) => return Some((url, sdl)),
+                Err(err) => tracing::debug!("{url} is not a subgraph: {err}"),
+            }
+        }
+        None
+    }
+}
+
+/// Renders a supergraph config for the discovered subgraphs, along with the schema files it
+/// points at when `stub_schemas` is set
+pub(crate) fn render_supergraph_config(
+    subgraphs: &[DiscoveredSubgraph],
+    stub_schemas: bool,
+) -> Result<(String, Vec<(Utf8PathBuf, String)>), SupergraphConfigEditError> {
+    let mut editor = SupergraphConfigEditor::new("");
+    let mut stubs = Vec::new();
+    for subgraph in subgraphs {
+        let schema = if stub_schemas {
+            let file = Utf8PathBuf::from(format!("{}.graphql", subgraph.name));
+            stubs.push((file.clone(), subgraph.sdl.clone()));
+            SchemaSource::File { file }
+        } else {
+            SchemaSource::SubgraphIntrospection {
+                subgraph_url: subgraph.url.clone(),
+                introspection_headers: None,
+            }
+        };
+        editor.add_subgraph(
+            &subgraph.name,
+            &SubgraphConfig {
+                routing_url: Some(subgraph.url.to_string()),
+                schema,
+            },
+        )?;
+    }
+    Ok((editor.finish()?, stubs))
+}
+
+#[cfg(test)]
+mod tests {
+    use apollo_federation_types::config::{SchemaSource, SupergraphConfig};
+    use camino::Utf8PathBuf;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+    use url::Url;
+
+    use super::{render_supergraph_config, DiscoveredSubgraph};
+
+    fn discovered() -> Vec<DiscoveredSubgraph> {
+        vec![
+            DiscoveredSubgraph {
+                name: "products".to_string(),
+                url: Url::parse("http://localhost:4001/graphql").unwrap(),
+                sdl: "type Query { products: [String] }".to_string(),
+            },
+            DiscoveredSubgraph {
+                name: "reviews".to_string(),
+                url: Url::parse("http://localhost:4002/").unwrap(),
+                sdl: "type Query { reviews: [String] }".to_string(),
+            },
+        ]
+    }
+
+    #[rstest]
+    fn test_render_supergraph_config(#[values(true, false)] stub_schemas: bool) {
+        let (contents, stubs) = render_supergraph_config(&discovered(), stub_schemas).unwrap();
+
+        let supergraph_config = SupergraphConfig::new_from_yaml(&contents).unwrap();
+        let subgraphs: Vec<_> = supergraph_config.into_iter().collect();
+        assert_that!(subgraphs).has_length(2);
+        let (name, products) = &subgraphs[0];
+        assert_that!(name).is_equal_to(&"products".to_string());
+        assert_that!(products.routing_url)
+            .is_equal_to(Some("http://localhost:4001/graphql".to_string()));
+        if stub_schemas {
+            assert_that!(products.schema).is_equal_to(SchemaSource::File {
+                file: Utf8PathBuf::from("products.graphql"),
+            });
+            assert_that!(stubs).is_equal_to(vec![
+                (
+                    Utf8PathBuf::from("products.graphql"),
+                    "type Query { products: [String] }".to_string(),
+                ),
+                (
+                    Utf8PathBuf::from("reviews.graphql"),
+                    "type Query { reviews: [String] }".to_string(),
+                ),
+            ]);
+        } else {
+            assert_that!(products.schema).is_equal_to(SchemaSource::SubgraphIntrospection {
+                subgraph_url: Url::parse("http://localhost:4001/graphql").unwrap(),
+                introspection_headers: None,
+            });
+            assert_that!(stubs).is_empty();
+        }
+    }
+}
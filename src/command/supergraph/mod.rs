@@ -1,12 +1,24 @@
 use camino::Utf8PathBuf;
 use clap::Parser;
+use rover_client::shared::GitContext;
 use serde::Serialize;
 
 use crate::utils::client::StudioClientConfig;
 use crate::{RoverOutput, RoverResult};
 
+#[cfg(feature = "composition-js")]
+pub(crate) mod check;
 pub(crate) mod compose;
+mod config;
+#[cfg(feature = "composition-js")]
+pub(crate) mod diff;
 mod fetch;
+#[cfg(feature = "composition-js")]
+pub(crate) mod init;
+pub(crate) mod provenance;
+#[cfg(feature = "composition-js")]
+pub(crate) mod validate_operations;
+mod verify;
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Supergraph {
@@ -16,27 +28,82 @@ pub struct Supergraph {
 
 #[derive(Debug, Serialize, Parser)]
 pub enum Command {
+    /// Compose a supergraph config locally and check every subgraph in it against a graph in
+    /// Apollo Studio
+    #[cfg(feature = "composition-js")]
+    Check(check::Check),
+
     /// Locally compose supergraph SDL from a set of subgraph schemas
     Compose(compose::Compose),
 
+    /// Edit the subgraphs in a supergraph config file
+    Config(config::Config),
+
+    /// Compare the API schemas of two supergraphs, composing them locally if needed
+    #[cfg(feature = "composition-js")]
+    Diff(diff::Diff),
+
     /// Fetch supergraph SDL from the graph registry
     Fetch(fetch::Fetch),
+
+    /// Create a supergraph config from the subgraphs running locally
+    #[cfg(feature = "composition-js")]
+    Init(init::Init),
+
+    /// Validate operation documents against a supergraph, composing it locally if needed
+    #[cfg(feature = "composition-js")]
+    ValidateOperations(validate_operations::ValidateOperations),
+
+    /// Check a supergraph schema against the provenance header it was composed with
+    Verify(verify::Verify),
 }
 
 impl Supergraph {
+    /// Whether this is `supergraph check`, the output of which `--format junit` reports
+    pub(crate) fn is_check(&self) -> bool {
+        #[cfg(feature = "composition-js")]
+        if let Command::Check(_) = self.command {
+            return true;
+        }
+        false
+    }
+
     pub async fn run(
         &self,
         override_install_path: Option<Utf8PathBuf>,
         client_config: StudioClientConfig,
         output_file: Option<Utf8PathBuf>,
+        git_context: GitContext,
+        checks_timeout_seconds: u64,
     ) -> RoverResult<RoverOutput> {
         match &self.command {
+            #[cfg(feature = "composition-js")]
+            Command::Check(command) => {
+                command
+                    .run(
+                        override_install_path,
+                        client_config,
+                        git_context,
+                        checks_timeout_seconds,
+                    )
+                    .await
+            }
+            #[cfg(feature = "composition-js")]
+            Command::Diff(command) => command.run(override_install_path, client_config).await,
+            Command::Config(command) => command.run(),
             Command::Fetch(command) => command.run(client_config).await,
+            #[cfg(feature = "composition-js")]
+            Command::Init(command) => command.run(client_config).await,
             Command::Compose(command) => {
                 command
                     .run(override_install_path, client_config, output_file)
                     .await
             }
+            #[cfg(feature = "composition-js")]
+            Command::ValidateOperations(command) => {
+                command.run(override_install_path, client_config).await
+            }
+            Command::Verify(command) => command.run(client_config).await,
         }
     }
 }
@@ -0,0 +1,286 @@
+//! The provenance header `rover supergraph compose --provenance` prepends to supergraph SDL. It
+//! records what the supergraph was composed from and with, so `rover supergraph verify` can later
+//! check that a schema is the one that was composed and that it matches a supergraph config.
+
+use std::{collections::BTreeMap, fmt};
+
+use anyhow::anyhow;
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{RoverError, RoverErrorSuggestion, RoverResult, PKG_VERSION};
+
+/// The first line of every provenance header, naming the version of its format
+const HEADER_START: &str = "# rover-provenance: 1";
+
+/// Where a supergraph schema came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Provenance {
+    /// The version of Rover that composed the supergraph
+    pub rover_version: String,
+
+    /// The version of the `supergraph` binary that composed it
+    pub supergraph_version: Option<String>,
+
+    /// When it was composed, in RFC 3339
+    pub composed_at: String,
+
+    /// The hash of each subgraph's routing URL and schema, keyed by subgraph name
+    pub subgraphs: BTreeMap<String, String>,
+
+    /// The hash of every subgraph's hash, which changes whenever any input to composition does
+    pub inputs: String,
+
+    /// The hash of the supergraph schema the header is prepended to
+    pub schema: String,
+}
+
+impl Provenance {
+    /// Records the provenance of `supergraph_sdl`, composed from `subgraphs` given as
+    /// `(name, routing URL, SDL)`
+    pub fn new<'a>(
+        supergraph_sdl: &str,
+        supergraph_version: Option<String>,
+        subgraphs: impl IntoIterator<Item = (&'a str, &'a str, &'a str)>,
+    ) -> Self {
+        let subgraphs = subgraph_hashes(subgraphs);
+        Self {
+            rover_version: PKG_VERSION.to_string(),
+            supergraph_version,
+            composed_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            inputs: inputs_hash(&subgraphs),
+            subgraphs,
+            schema: sha256(supergraph_sdl),
+        }
+    }
+
+    /// Prepends the header to the supergraph SDL it describes
+    pub fn prepend(&self, supergraph_sdl: &str) -> String {
+        format!("{self}{supergraph_sdl}")
+    }
+
+    /// Splits a supergraph schema into its provenance header and the SDL the header describes
+    pub fn split(contents: &str) -> RoverResult<(Provenance, &str)> {
+        let no_header = || {
+            let mut err = RoverError::new(anyhow!(
+                "The schema does not start with a provenance header."
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Compose the supergraph with `rover supergraph compose --provenance` to add one."
+                    .to_string(),
+            ));
+            err
+        };
+        if !contents.starts_with(HEADER_START) {
+            return Err(no_header());
+        }
+
+        let mut fields: Vec<(&str, &str)> = Vec::new();
+        let mut body_start = 0;
+        for line in contents.split_inclusive('\n') {
+            let Some((key, value)) = line
+                .strip_prefix("# ")
+                .and_then(|line| line.trim_end().split_once(": "))
+            else {
+                break;
+            };
+            fields.push((key, value));
+            body_start += line.len();
+        }
+
+        let field = |key: &str| {
+            fields
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, value)| value.to_string())
+        };
+        let required = |key: &str| {
+            field(key).ok_or_else(|| {
+                RoverError::new(anyhow!(
+                    "The provenance header is missing its `{key}` line."
+                ))
+            })
+        };
+        let subgraphs = fields
+            .iter()
+            .filter(|(key, _)| *key == "subgraph")
+            .map(|(_, value)| {
+                value
+                    .split_once(' ')
+                    .map(|(name, hash)| (name.to_string(), hash.to_string()))
+                    .ok_or_else(|| {
+                        RoverError::new(anyhow!(
+                            "`subgraph: {value}` in the provenance header should be a subgraph name followed by a hash."
+                        ))
+                    })
+            })
+            .collect::<RoverResult<BTreeMap<_, _>>>()?;
+
+        let provenance = Provenance {
+            rover_version: required("rover-version")?,
+            supergraph_version: field("supergraph-version"),
+            composed_at: required("composed-at")?,
+            subgraphs,
+            inputs: required("inputs")?,
+            schema: required("schema")?,
+        };
+        Ok((provenance, &contents[body_start..]))
+    }
+
+    /// Everything about `supergraph_sdl` that doesn't match the header, checking the subgraphs
+    /// against `subgraphs` (as hashed by [`subgraph_hashes`]) when they're given
+    pub fn mismatches(
+        &self,
+        supergraph_sdl: &str,
+        subgraphs: Option<&BTreeMap<String, String>>,
+    ) -> Vec<String> {
+        let mut mismatches = Vec::new();
+        if sha256(supergraph_sdl) != self.schema {
+            mismatches.push("the schema has changed since it was composed".to_string());
+        }
+        if inputs_hash(&self.subgraphs) != self.inputs {
+            mismatches.push(
+                "the subgraph hashes in the header don't add up to its `inputs` hash".to_string(),
+            );
+        }
+        if let Some(subgraphs) = subgraphs {
+            for (name, hash) in subgraphs {
+                match self.subgraphs.get(name) {
+                    None => {
+                        mismatches.push(format!("subgraph '{name}' wasn't part of the composition"))
+                    }
+                    Some(recorded) if recorded != hash => mismatches.push(format!(
+                        "subgraph '{name}' has changed since the supergraph was composed"
+                    )),
+                    Some(_) => {}
+                }
+            }
+            for name in self.subgraphs.keys() {
+                if !subgraphs.contains_key(name) {
+                    mismatches.push(format!(
+                        "subgraph '{name}' was composed but isn't in the supergraph config"
+                    ));
+                }
+            }
+        }
+        mismatches
+    }
+}
+
+impl fmt::Display for Provenance {
+    /// Writes the header, one `# key: value` comment per line, so the SDL stays valid GraphQL
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{HEADER_START}")?;
+        writeln!(f, "# rover-version: {}", self.rover_version)?;
+        if let Some(supergraph_version) = &self.supergraph_version {
+            writeln!(f, "# supergraph-version: {supergraph_version}")?;
+        }
+        writeln!(f, "# composed-at: {}", self.composed_at)?;
+        for (name, hash) in &self.subgraphs {
+            writeln!(f, "# subgraph: {name} {hash}")?;
+        }
+        writeln!(f, "# inputs: {}", self.inputs)?;
+        writeln!(f, "# schema: {}", self.schema)
+    }
+}
+
+/// Hashes the routing URL and schema of each subgraph, given as `(name, routing URL, SDL)`
+pub fn subgraph_hashes<'a>(
+    subgraphs: impl IntoIterator<Item = (&'a str, &'a str, &'a str)>,
+) -> BTreeMap<String, String> {
+    subgraphs
+        .into_iter()
+        .map(|(name, url, sdl)| (name.to_string(), sha256(&format!("{url}\n{sdl}"))))
+        .collect()
+}
+
+fn inputs_hash(subgraphs: &BTreeMap<String, String>) -> String {
+    let mut hasher = Sha256::new();
+    for (name, hash) in subgraphs {
+        hasher.update(format!("{name} {hash}\n"));
+    }
+    format!("sha256:{:x}", hasher.finalize())
+}
+
+fn sha256(contents: &str) -> String {
+    format!("sha256:{:x}", Sha256::digest(contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use rstest::{fixture, rstest};
+    use speculoos::prelude::*;
+
+    use super::{subgraph_hashes, Provenance};
+
+    const SDL: &str = "schema { query: Query }\ntype Query { hello: String }\n";
+
+    #[fixture]
+    fn provenance() -> Provenance {
+        Provenance::new(
+            SDL,
+            Some("v2.9.3".to_string()),
+            [
+                ("products", "http://localhost:4001", "type Query { a: ID }"),
+                ("reviews", "http://localhost:4002", "type Query { b: ID }"),
+            ],
+        )
+    }
+
+    #[rstest]
+    fn test_split_round_trips(provenance: Provenance) {
+        let contents = provenance.prepend(SDL);
+        assert_that!(contents).starts_with("# rover-provenance: 1\n# rover-version: ");
+
+        let (parsed, body) = Provenance::split(&contents).unwrap();
+        assert_that!(parsed).is_equal_to(&provenance);
+        assert_that!(body).is_equal_to(SDL);
+        assert_that!(parsed.mismatches(body, None)).is_empty();
+    }
+
+    #[rstest]
+    fn test_mismatches_reports_changed_schema(provenance: Provenance) {
+        let contents = provenance.prepend(SDL).replace("hello", "goodbye");
+        let (parsed, body) = Provenance::split(&contents).unwrap();
+        assert_that!(parsed.mismatches(body, None)).is_equal_to(vec![
+            "the schema has changed since it was composed".to_string(),
+        ]);
+    }
+
+    #[rstest]
+    fn test_mismatches_reports_changed_subgraphs(provenance: Provenance) {
+        let subgraphs = subgraph_hashes([
+            ("products", "http://localhost:4001", "type Query { a: ID! }"),
+            ("inventory", "http://localhost:4003", "type Query { c: ID }"),
+        ]);
+        assert_that!(provenance.mismatches(SDL, Some(&subgraphs))).is_equal_to(vec![
+            "subgraph 'inventory' wasn't part of the composition".to_string(),
+            "subgraph 'products' has changed since the supergraph was composed".to_string(),
+            "subgraph 'reviews' was composed but isn't in the supergraph config".to_string(),
+        ]);
+    }
+
+    #[rstest]
+    fn test_mismatches_reports_edited_subgraph_hashes(provenance: Provenance) {
+        let mut edited = provenance;
+        edited.subgraphs.remove("reviews");
+        assert_that!(edited.mismatches(SDL, None)).is_equal_to(vec![
+            "the subgraph hashes in the header don't add up to its `inputs` hash".to_string(),
+        ]);
+    }
+
+    #[rstest]
+    #[case::no_header(SDL)]
+    #[case::missing_schema_hash(indoc! {"
+        # rover-provenance: 1
+        # rover-version: 0.27.0
+        # composed-at: 2026-10-17T12:00:00Z
+        # inputs: sha256:abc
+        type Query { hello: String }
+    "})]
+    fn test_split_rejects_invalid_headers(#[case] contents: &str) {
+        assert_that!(Provenance::split(contents)).is_err();
+    }
+}
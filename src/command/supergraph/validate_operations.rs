@@ -0,0 +1,201 @@
+use anyhow::anyhow;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use rover_client::operations::operation_collection::fetch::{self, OperationCollectionFetchInput};
+use rover_std::{progressln, Fs, Style};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::command::supergraph::diff::load_supergraph_sdl;
+use crate::operation_validation::{OperationSource, OperationValidation};
+use crate::options::PluginOpts;
+use crate::utils::client::StudioClientConfig;
+use crate::utils::parsers::FileDescriptorType;
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult, StructuredFailure};
+
+/// The extensions of the files that are read as operation documents from a directory
+const OPERATION_EXTENSIONS: [&str; 2] = ["graphql", "gql"];
+
+#[derive(Debug, Serialize, Parser)]
+pub struct ValidateOperations {
+    /// The supergraph to validate operations against: either a supergraph configuration file
+    /// (`.yaml` or `.yml`) to compose, or a supergraph SDL file. You can pass `-` to read SDL from
+    /// stdin instead.
+    #[arg(value_name = "SUPERGRAPH")]
+    #[serde(skip_serializing)]
+    supergraph: FileDescriptorType,
+
+    /// An operation document, or a directory to search for `.graphql` and `.gql` operation
+    /// documents. Can be passed more than once
+    #[arg(
+        long = "operations",
+        value_name = "PATH",
+        required_unless_present = "collection"
+    )]
+    #[serde(skip_serializing)]
+    operations: Vec<Utf8PathBuf>,
+
+    /// The ID of an operation collection in GraphOS to validate the saved operations of
+    #[arg(long = "collection", value_name = "COLLECTION_ID")]
+    collection: Option<String>,
+
+    #[clap(flatten)]
+    plugin_opts: PluginOpts,
+}
+
+impl ValidateOperations {
+    pub async fn run(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+    ) -> RoverResult<RoverOutput> {
+        let mut sources = Vec::new();
+        for path in &self.operations {
+            sources.extend(read_operations(path)?);
+        }
+        if let Some(collection_id) = &self.collection {
+            let client = client_config.get_authenticated_client(&self.plugin_opts.profile)?;
+            let collection = fetch::run(
+                OperationCollectionFetchInput {
+                    collection_id: collection_id.clone(),
+                },
+                &client,
+            )
+            .await?;
+            sources.extend(
+                collection
+                    .operations
+                    .into_iter()
+                    .map(|operation| OperationSource {
+                        name: format!("{}/{}", collection.name, operation.name),
+                        body: operation.body,
+                    }),
+            );
+        }
+
+        let supergraph_sdl = load_supergraph_sdl(
+            &self.supergraph,
+            &self.plugin_opts,
+            override_install_path,
+            &client_config,
+        )
+        .await?;
+        progressln!(
+            "Validating {} against the supergraph.",
+            match sources.len() {
+                1 => "1 operation document".to_string(),
+                n => format!("{} operation documents", n),
+            }
+        );
+        let validation = OperationValidation::new(&supergraph_sdl, &sources)?;
+        if validation.is_valid() {
+            Ok(RoverOutput::OperationValidation(validation))
+        } else {
+            Err(RoverError::from_failure(OperationValidationFailure(
+                validation,
+            )))
+        }
+    }
+}
+
+/// Reads the operation document at `path`, or every operation document in it if it's a directory
+fn read_operations(path: &Utf8PathBuf) -> RoverResult<Vec<OperationSource>> {
+    if !path.is_dir() {
+        return Ok(vec![OperationSource {
+            name: path.to_string(),
+            body: Fs::read_file(path)?,
+        }]);
+    }
+
+    let mut paths = Vec::new();
+    for extension in OPERATION_EXTENSIONS {
+        let pattern = path.join("**").join(format!("*.{}", extension));
+        let matches = glob::glob(pattern.as_str()).map_err(|err| anyhow!(err))?;
+        for entry in matches {
+            let entry = entry.map_err(|err| anyhow!(err))?;
+            let entry = Utf8PathBuf::try_from(entry).map_err(|err| anyhow!(err))?;
+            paths.push(entry);
+        }
+    }
+    if paths.is_empty() {
+        let mut err = RoverError::new(anyhow!(
+            "There are no operation documents in {}.",
+            Style::Path.paint(path.as_str())
+        ));
+        err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+            "Operation documents are read from files ending in {}.",
+            OPERATION_EXTENSIONS
+                .iter()
+                .map(|extension| format!("`.{}`", extension))
+                .collect::<Vec<_>>()
+                .join(" or ")
+        )));
+        return Err(err);
+    }
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            Ok(OperationSource {
+                body: Fs::read_file(&path)?,
+                name: path.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Returned by `rover supergraph validate-operations` when an operation isn't valid against the
+/// supergraph
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "Found {} in the operation documents.",
+    match .0.errors.len() {
+        1 => "1 error".to_string(),
+        n => format!("{} errors", n),
+    }
+)]
+pub struct OperationValidationFailure(pub OperationValidation);
+
+impl StructuredFailure for OperationValidationFailure {
+    fn get_output(&self) -> String {
+        self.0.get_output()
+    }
+
+    fn get_json(&self) -> Value {
+        self.0.get_json()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_reads_operation_documents_from_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8PathBuf::try_from(dir.path().to_path_buf()).unwrap();
+        Fs::create_dir_all(root.join("cart")).unwrap();
+        Fs::write_file(root.join("products.graphql"), "{ products { id } }").unwrap();
+        Fs::write_file(root.join("cart/cart.gql"), "{ cart { id } }").unwrap();
+        Fs::write_file(root.join("README.md"), "# operations").unwrap();
+
+        let names: Vec<String> = read_operations(&root)
+            .unwrap()
+            .into_iter()
+            .map(|source| source.name.trim_start_matches(root.as_str()).to_string())
+            .collect();
+        assert_that!(names).is_equal_to(vec![
+            "/cart/cart.gql".to_string(),
+            "/products.graphql".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_errs_on_directories_without_operation_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8PathBuf::try_from(dir.path().to_path_buf()).unwrap();
+        assert_that!(read_operations(&root)).is_err();
+    }
+}
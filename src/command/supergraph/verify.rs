@@ -0,0 +1,85 @@
+use anyhow::anyhow;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::command::supergraph::provenance::{subgraph_hashes, Provenance};
+use crate::options::PluginOpts;
+use crate::utils::client::StudioClientConfig;
+use crate::utils::parsers::FileDescriptorType;
+use crate::utils::supergraph_config::get_supergraph_config;
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// Check a supergraph schema against the provenance header it was composed with
+///
+/// The schema must have been composed with `rover supergraph compose --provenance`. Verifying
+/// checks that the schema hasn't changed since, and with `--config`, that the subgraphs in the
+/// supergraph config are the ones it was composed from.
+pub struct Verify {
+    /// The supergraph schema to verify. You can pass `-` to read it from stdin instead.
+    #[arg(value_name = "SCHEMA")]
+    #[serde(skip_serializing)]
+    schema: FileDescriptorType,
+
+    /// A supergraph config to check the subgraphs the schema was composed from against
+    #[arg(long = "config")]
+    #[serde(skip_serializing)]
+    supergraph_yaml: Option<FileDescriptorType>,
+
+    #[clap(flatten)]
+    plugin_opts: PluginOpts,
+}
+
+impl Verify {
+    pub async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let contents = self
+            .schema
+            .read_file_descriptor("supergraph schema", &mut std::io::stdin())?;
+        let (provenance, supergraph_sdl) = Provenance::split(&contents)?;
+
+        let subgraphs = match &self.supergraph_yaml {
+            Some(supergraph_yaml) => {
+                let supergraph_config = get_supergraph_config(
+                    &None,
+                    &Some(supergraph_yaml.clone()),
+                    None,
+                    client_config,
+                    &self.plugin_opts.profile,
+                    true,
+                    self.plugin_opts.env.as_deref(),
+                    self.plugin_opts.introspection_policy_opts.policy(),
+                )
+                .await?
+                .ok_or_else(|| anyhow!("error getting supergraph config"))?;
+                let definitions = supergraph_config.get_subgraph_definitions()?;
+                Some(subgraph_hashes(definitions.iter().map(|definition| {
+                    (
+                        definition.name.as_str(),
+                        definition.url.as_str(),
+                        definition.sdl.as_str(),
+                    )
+                })))
+            }
+            None => None,
+        };
+
+        let mismatches = provenance.mismatches(supergraph_sdl, subgraphs.as_ref());
+        if mismatches.is_empty() {
+            Ok(RoverOutput::SupergraphProvenance(provenance))
+        } else {
+            let mut err = RoverError::new(anyhow!(
+                "The supergraph schema does not match its provenance header:\n{}",
+                mismatches
+                    .iter()
+                    .map(|mismatch| format!("  - {mismatch}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Compose the supergraph again with `rover supergraph compose --provenance`."
+                    .to_string(),
+            ));
+            Err(err)
+        }
+    }
+}
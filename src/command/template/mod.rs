@@ -30,6 +30,7 @@ enum Command {
 
 impl Template {
     pub(crate) async fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        client_config.require_network("Fetching templates")?;
         match &self.command {
             Command::Use(use_template) => use_template.run(client_config).await,
             Command::List(list) => list.run().await,
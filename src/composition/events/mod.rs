@@ -1,4 +1,15 @@
-use super::{CompositionError, CompositionSuccess};
+use std::collections::BTreeMap;
+
+use apollo_parser::Parser;
+use camino::Utf8PathBuf;
+use derive_getters::Getters;
+use sha2::{Digest, Sha256};
+
+use super::{
+    supergraph::config::error::ResolveSubgraphError,
+    validation::{validate_compose_directives, validate_subgraph_sdl},
+    CompositionError, CompositionSuccess,
+};
 
 /// Events emitted from composition
 #[derive(Debug, Eq, PartialEq)]
@@ -10,4 +21,187 @@ pub enum CompositionEvent {
     Success(CompositionSuccess),
     /// Composition errored
     Error(CompositionError),
+    /// A subgraph couldn't be resolved, so composition is carrying on without it
+    SubgraphResolutionFailed(SubgraphResolutionFailed),
+    /// A subgraph couldn't be fetched again, so composition is using the last SDL that was
+    /// resolved for it. These are emitted when the fetch fails, and again before each composition
+    /// that uses the stale SDL, until a background retry succeeds
+    SubgraphStale(SubgraphStale),
+    /// A subgraph's schema has problems that will stop it from composing. These are emitted before
+    /// composition starts, and composition still runs afterwards
+    SubgraphValidationError(SubgraphValidationError),
+    /// A file the composed supergraph was written to doesn't hold what was written, so anything
+    /// reading it, like the router's hot reload, may have picked up a broken supergraph. These
+    /// are emitted after the composition's [`CompositionEvent::Success`]
+    OutputCorrupted(SupergraphOutputCorrupted),
+}
+
+/// A subgraph that was left out of composition because it couldn't be resolved
+#[derive(Clone, Debug, Eq, PartialEq, Getters)]
+pub struct SubgraphResolutionFailed {
+    /// The name of the subgraph that was left out
+    subgraph_name: String,
+    /// Why the subgraph couldn't be resolved
+    message: String,
+}
+
+impl SubgraphResolutionFailed {
+    /// Describes a subgraph that failed to resolve with the given error
+    pub fn new(subgraph_name: String, error: &ResolveSubgraphError) -> SubgraphResolutionFailed {
+        SubgraphResolutionFailed {
+            subgraph_name,
+            message: error.to_string(),
+        }
+    }
+
+    /// Describes a subgraph that failed to resolve for the given reason
+    pub fn from_message(subgraph_name: String, message: String) -> SubgraphResolutionFailed {
+        SubgraphResolutionFailed {
+            subgraph_name,
+            message,
+        }
+    }
+}
+
+/// A subgraph that's composed with the last SDL resolved for it, because fetching it again failed
+#[derive(Clone, Debug, Eq, PartialEq, Getters)]
+pub struct SubgraphStale {
+    /// The name of the stale subgraph
+    subgraph_name: String,
+    /// Why the subgraph couldn't be fetched again
+    message: String,
+}
+
+impl SubgraphStale {
+    /// Describes a subgraph whose SDL couldn't be fetched again for the given reason
+    pub fn new(subgraph_name: String, message: String) -> SubgraphStale {
+        SubgraphStale {
+            subgraph_name,
+            message,
+        }
+    }
+}
+
+/// A subgraph whose schema failed the checks that run before composition
+#[derive(Clone, Debug, Eq, PartialEq, Getters)]
+pub struct SubgraphValidationError {
+    /// The name of the subgraph with problems
+    subgraph_name: String,
+    /// What's wrong with the subgraph's schema
+    errors: Vec<String>,
+}
+
+impl SubgraphValidationError {
+    /// Checks a subgraph's SDL, describing what's wrong with it if anything is
+    pub fn check(subgraph_name: &str, sdl: &str) -> Option<SubgraphValidationError> {
+        let errors = validate_subgraph_sdl(sdl);
+        if errors.is_empty() {
+            None
+        } else {
+            Some(SubgraphValidationError {
+                subgraph_name: subgraph_name.to_string(),
+                errors,
+            })
+        }
+    }
+
+    /// Checks each subgraph's SDL, and the custom directives the subgraphs export with
+    /// `@composeDirective` together, describing what's wrong with each subgraph that has problems
+    pub fn check_all<'a>(
+        subgraphs: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Vec<SubgraphValidationError> {
+        let subgraphs = subgraphs.into_iter().collect::<Vec<_>>();
+        let mut errors = BTreeMap::<String, Vec<String>>::new();
+        for (subgraph_name, sdl) in &subgraphs {
+            if let Some(validation_error) = Self::check(subgraph_name, sdl) {
+                errors.insert(validation_error.subgraph_name, validation_error.errors);
+            }
+        }
+        for (subgraph_name, error) in validate_compose_directives(subgraphs) {
+            errors.entry(subgraph_name).or_default().push(error);
+        }
+        errors
+            .into_iter()
+            .map(|(subgraph_name, errors)| SubgraphValidationError {
+                subgraph_name,
+                errors,
+            })
+            .collect()
+    }
+}
+
+/// A supergraph file that didn't read back as the supergraph that was written to it
+#[derive(Clone, Debug, Eq, PartialEq, Getters)]
+pub struct SupergraphOutputCorrupted {
+    /// The file the supergraph was written to
+    path: Utf8PathBuf,
+    /// What's wrong with the file
+    reason: String,
+}
+
+impl SupergraphOutputCorrupted {
+    /// Checks that the `contents` read back from `path` parse as SDL and hash the same as the
+    /// `supergraph_sdl` that was written there, describing the difference if they don't
+    pub fn check(
+        path: &Utf8PathBuf,
+        contents: &str,
+        supergraph_sdl: &str,
+    ) -> Option<SupergraphOutputCorrupted> {
+        let parse_errors = Parser::new(contents).parse().errors().len();
+        let reason = if parse_errors > 0 {
+            format!("the file isn't valid SDL, with {parse_errors} parse errors")
+        } else {
+            let written = Sha256::digest(contents);
+            let expected = Sha256::digest(supergraph_sdl);
+            if written == expected {
+                return None;
+            }
+            format!(
+                "the file's SHA-256 hash is {written:x}, but the composed supergraph's is {expected:x}"
+            )
+        };
+        Some(SupergraphOutputCorrupted {
+            path: path.clone(),
+            reason,
+        })
+    }
+
+    /// Describes a supergraph file that couldn't be read back at all
+    pub fn unreadable(
+        path: &Utf8PathBuf,
+        error: &impl std::error::Error,
+    ) -> SupergraphOutputCorrupted {
+        SupergraphOutputCorrupted {
+            path: path.clone(),
+            reason: format!("the file couldn't be read: {error}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8PathBuf;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::SupergraphOutputCorrupted;
+
+    const SUPERGRAPH_SDL: &str = "type Query { products: [String] }";
+
+    #[rstest]
+    #[case::intact(SUPERGRAPH_SDL, None)]
+    #[case::partly_written("type Query { prod", Some("the file isn't valid SDL"))]
+    #[case::stale("type Query { reviews: [String] }", Some("the file's SHA-256 hash is"))]
+    fn test_check_supergraph_output(#[case] contents: &str, #[case] expected: Option<&str>) {
+        let path = Utf8PathBuf::from("supergraph.graphql");
+        let corrupted = SupergraphOutputCorrupted::check(&path, contents, SUPERGRAPH_SDL);
+        match expected {
+            Some(reason) => {
+                let corrupted = corrupted.unwrap();
+                assert_that!(corrupted.path()).is_equal_to(&path);
+                assert_that!(corrupted.reason()).starts_with(reason);
+            }
+            None => assert_that!(corrupted).is_none(),
+        }
+    }
 }
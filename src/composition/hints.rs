@@ -0,0 +1,513 @@
+//! Turns composition hints, and the build errors that have an obvious fix, into suggestions of
+//! federation directives to add to subgraph schemas, each with a patch that applies it
+//!
+//! Hints only describe what composition noticed, like an input field that's left out of the
+//! supergraph because some subgraphs don't define it. The suggestions say what to change in which
+//! subgraph to make that intentional, or to fix it
+
+use std::{collections::BTreeMap, fmt};
+
+use apollo_federation_types::{
+    config::{SchemaSource, SupergraphConfig},
+    rover::{BuildErrors, BuildHint},
+};
+use apollo_parser::{cst, cst::CstNode, Parser};
+use regex::Regex;
+use serde::Serialize;
+
+lazy_static::lazy_static! {
+    static ref QUOTED: Regex = Regex::new(r#""([^"]+)""#).unwrap();
+    static ref DEFINED_IN: Regex = Regex::new(r"is defined in (subgraphs? .*?) but not in").unwrap();
+    static ref INPUT_OBJECT_FIELD: Regex =
+        Regex::new(r#"^Input object field "([^"]+)" will not be added to "([^"]+)""#).unwrap();
+    static ref ENUM_VALUE: Regex =
+        Regex::new(r#"^Value "([^"]+)" of enum type "([^"]+)""#).unwrap();
+    static ref ARGUMENT: Regex =
+        Regex::new(r#"^Optional argument "([^".]+)\.([^"(]+)\(([^":]+):\)""#).unwrap();
+    static ref FIELD_SHARING: Regex = Regex::new(
+        r#"^Non-shareable field "([^".]+)\.([^"]+)" is resolved from multiple subgraphs: it is resolved from (.*?) and defined as non-shareable in (.*)$"#,
+    )
+    .unwrap();
+}
+
+/// An element of a subgraph schema that a directive can be added to
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SchemaCoordinate {
+    Field {
+        ty: String,
+        field: String,
+    },
+    Argument {
+        ty: String,
+        field: String,
+        argument: String,
+    },
+    InputField {
+        ty: String,
+        field: String,
+    },
+    EnumValue {
+        ty: String,
+        value: String,
+    },
+}
+
+impl fmt::Display for SchemaCoordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaCoordinate::Field { ty, field } | SchemaCoordinate::InputField { ty, field } => {
+                write!(f, "{ty}.{field}")
+            }
+            SchemaCoordinate::Argument {
+                ty,
+                field,
+                argument,
+            } => write!(f, "{ty}.{field}({argument}:)"),
+            SchemaCoordinate::EnumValue { ty, value } => write!(f, "{ty}.{value}"),
+        }
+    }
+}
+
+/// A directive to add to an element of a subgraph schema, suggested by a composition hint or error
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FixSuggestion {
+    /// The subgraph to change
+    pub subgraph: String,
+    /// The schema coordinate of the element to add the directive to, like `Product.weight`
+    pub coordinate: String,
+    /// The directive to add, like `@inaccessible`
+    pub directive: String,
+    /// The code of the hint or error that the suggestion fixes
+    pub code: String,
+    /// Why the directive is suggested
+    pub reason: String,
+    /// A patch to the subgraph's schema that adds the directive, when the element could be found
+    pub patch: Option<String>,
+}
+
+impl fmt::Display for FixSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "add `{}` to `{}` in subgraph \"{}\" ({}): {}",
+            self.directive, self.coordinate, self.subgraph, self.code, self.reason
+        )?;
+        if let Some(patch) = &self.patch {
+            write!(f, "\n{patch}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Suggests directives that make the elements composition hinted about consistent across
+/// subgraphs
+pub fn suggest_fixes_for_hints(
+    hints: &[BuildHint],
+    supergraph_config: &SupergraphConfig,
+) -> Vec<FixSuggestion> {
+    let sdls = subgraph_sdls(supergraph_config);
+    hints
+        .iter()
+        .flat_map(|hint| suggest(hint.code.as_deref(), &hint.message, &sdls))
+        .collect()
+}
+
+/// Suggests directives that fix the build errors that have an obvious fix
+pub fn suggest_fixes_for_errors(
+    errors: &BuildErrors,
+    supergraph_config: &SupergraphConfig,
+) -> Vec<FixSuggestion> {
+    let sdls = subgraph_sdls(supergraph_config);
+    errors
+        .iter()
+        .flat_map(|error| {
+            suggest(
+                error.get_code().as_deref(),
+                &error.get_message().unwrap_or_default(),
+                &sdls,
+            )
+        })
+        .collect()
+}
+
+fn subgraph_sdls(supergraph_config: &SupergraphConfig) -> BTreeMap<String, String> {
+    supergraph_config
+        .clone()
+        .into_iter()
+        .filter_map(|(name, subgraph)| match subgraph.schema {
+            SchemaSource::Sdl { sdl } => Some((name, sdl)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn quoted(text: &str) -> Vec<String> {
+    QUOTED
+        .captures_iter(text)
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+/// The subgraphs that a hint about an element missing from some subgraphs says define it
+fn defining_subgraphs(message: &str) -> Vec<String> {
+    DEFINED_IN
+        .captures(message)
+        .map(|captures| quoted(&captures[1]))
+        .unwrap_or_default()
+}
+
+fn suggest(
+    code: Option<&str>,
+    message: &str,
+    sdls: &BTreeMap<String, String>,
+) -> Vec<FixSuggestion> {
+    let Some(code) = code else {
+        return Vec::new();
+    };
+    let suggestion =
+        |subgraph: &str, coordinate: &SchemaCoordinate, directive: &str, reason| FixSuggestion {
+            subgraph: subgraph.to_string(),
+            coordinate: coordinate.to_string(),
+            directive: directive.to_string(),
+            code: code.to_string(),
+            reason,
+            patch: sdls
+                .get(subgraph)
+                .and_then(|sdl| patch(subgraph, sdl, coordinate, directive)),
+        };
+    let inaccessible = |coordinate: SchemaCoordinate| {
+        defining_subgraphs(message)
+            .iter()
+            .map(|subgraph| {
+                suggestion(
+                    subgraph,
+                    &coordinate,
+                    "@inaccessible",
+                    format!(
+                        "`{coordinate}` isn't defined in every subgraph, so it's left out of the supergraph. Mark it inaccessible to make that intentional, or define it in the other subgraphs"
+                    ),
+                )
+            })
+            .collect()
+    };
+
+    match code {
+        "INCONSISTENT_INPUT_OBJECT_FIELD" => INPUT_OBJECT_FIELD
+            .captures(message)
+            .map(|captures| {
+                inaccessible(SchemaCoordinate::InputField {
+                    ty: captures[2].to_string(),
+                    field: captures[1].to_string(),
+                })
+            })
+            .unwrap_or_default(),
+        "INCONSISTENT_ENUM_VALUE_FOR_INPUT_ENUM" => ENUM_VALUE
+            .captures(message)
+            .map(|captures| {
+                inaccessible(SchemaCoordinate::EnumValue {
+                    ty: captures[2].to_string(),
+                    value: captures[1].to_string(),
+                })
+            })
+            .unwrap_or_default(),
+        "INCONSISTENT_ARGUMENT_PRESENCE" => ARGUMENT
+            .captures(message)
+            .map(|captures| {
+                inaccessible(SchemaCoordinate::Argument {
+                    ty: captures[1].to_string(),
+                    field: captures[2].to_string(),
+                    argument: captures[3].to_string(),
+                })
+            })
+            .unwrap_or_default(),
+        "INVALID_FIELD_SHARING" => {
+            let Some(captures) = FIELD_SHARING.captures(message) else {
+                return Vec::new();
+            };
+            let coordinate = SchemaCoordinate::Field {
+                ty: captures[1].to_string(),
+                field: captures[2].to_string(),
+            };
+            let resolving = quoted(&captures[3]);
+            let non_shareable = if captures[4].starts_with("all of them") {
+                resolving.clone()
+            } else {
+                quoted(&captures[4])
+            };
+            let mut suggestions: Vec<FixSuggestion> = non_shareable
+                .iter()
+                .map(|subgraph| {
+                    suggestion(
+                        subgraph,
+                        &coordinate,
+                        "@shareable",
+                        format!(
+                            "`{coordinate}` is resolved by several subgraphs, which needs it to be shareable in all of them"
+                        ),
+                    )
+                })
+                .collect();
+            // moving a field between two subgraphs is the other common reason for resolving it
+            // from both
+            if let [from, to] = resolving.as_slice() {
+                suggestions.push(suggestion(
+                    to,
+                    &coordinate,
+                    &format!("@override(from: \"{from}\")"),
+                    format!(
+                        "Alternatively, if `{coordinate}` is moving to subgraph \"{to}\", override it there instead of sharing it"
+                    ),
+                ));
+            }
+            suggestions
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Finds where the directive goes for the element at `coordinate`: right after the element,
+/// including any directives it already has
+fn directive_offset(document: &cst::Document, coordinate: &SchemaCoordinate) -> Option<usize> {
+    let end = |node: &dyn CstNode| usize::from(node.syntax().text_range().end());
+    let named = |name: Option<cst::Name>, expected: &str| {
+        name.is_some_and(|name| name.text().as_str() == expected)
+    };
+    for definition in document.definitions() {
+        use cst::Definition::*;
+        match coordinate {
+            SchemaCoordinate::Field { ty, field }
+            | SchemaCoordinate::Argument { ty, field, .. } => {
+                let (name, fields) = match definition {
+                    ObjectTypeDefinition(def) => (def.name(), def.fields_definition()),
+                    ObjectTypeExtension(def) => (def.name(), def.fields_definition()),
+                    InterfaceTypeDefinition(def) => (def.name(), def.fields_definition()),
+                    InterfaceTypeExtension(def) => (def.name(), def.fields_definition()),
+                    _ => continue,
+                };
+                if !named(name, ty) {
+                    continue;
+                }
+                let Some(field_definition) = fields
+                    .iter()
+                    .flat_map(|fields| fields.field_definitions())
+                    .find(|field_definition| named(field_definition.name(), field))
+                else {
+                    continue;
+                };
+                match coordinate {
+                    SchemaCoordinate::Argument { argument, .. } => {
+                        if let Some(argument) = field_definition
+                            .arguments_definition()
+                            .iter()
+                            .flat_map(|arguments| arguments.input_value_definitions())
+                            .find(|definition| named(definition.name(), argument))
+                        {
+                            return Some(end(&argument));
+                        }
+                    }
+                    _ => return Some(end(&field_definition)),
+                }
+            }
+            SchemaCoordinate::InputField { ty, field } => {
+                let (name, fields) = match definition {
+                    InputObjectTypeDefinition(def) => (def.name(), def.input_fields_definition()),
+                    InputObjectTypeExtension(def) => (def.name(), def.input_fields_definition()),
+                    _ => continue,
+                };
+                if !named(name, ty) {
+                    continue;
+                }
+                if let Some(input_field) = fields
+                    .iter()
+                    .flat_map(|fields| fields.input_value_definitions())
+                    .find(|definition| named(definition.name(), field))
+                {
+                    return Some(end(&input_field));
+                }
+            }
+            SchemaCoordinate::EnumValue { ty, value } => {
+                let (name, values) = match definition {
+                    EnumTypeDefinition(def) => (def.name(), def.enum_values_definition()),
+                    EnumTypeExtension(def) => (def.name(), def.enum_values_definition()),
+                    _ => continue,
+                };
+                if !named(name, ty) {
+                    continue;
+                }
+                if let Some(enum_value) = values
+                    .iter()
+                    .flat_map(|values| values.enum_value_definitions())
+                    .find(|definition| named(definition.enum_value().and_then(|v| v.name()), value))
+                {
+                    return Some(end(&enum_value));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// A patch to `sdl` that adds `directive` to the element at `coordinate`, in the unified diff
+/// format
+fn patch(
+    subgraph: &str,
+    sdl: &str,
+    coordinate: &SchemaCoordinate,
+    directive: &str,
+) -> Option<String> {
+    let document = Parser::new(sdl).parse().document();
+    let offset = directive_offset(&document, coordinate)?;
+    // the element's node can end in the whitespace before whatever follows it
+    let offset = sdl.get(..offset)?.trim_end().len();
+    let line_start = sdl[..offset].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = sdl[offset..]
+        .find('\n')
+        .map_or(sdl.len(), |index| offset + index);
+    let line_number = sdl[..offset].matches('\n').count() + 1;
+    Some(format!(
+        "--- a/{subgraph}\n+++ b/{subgraph}\n@@ -{line_number} +{line_number} @@\n-{}\n+{} {directive}{}",
+        &sdl[line_start..line_end],
+        &sdl[line_start..offset],
+        &sdl[offset..line_end],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use apollo_federation_types::{
+        config::{SchemaSource, SubgraphConfig, SupergraphConfig},
+        rover::{BuildError, BuildErrors, BuildHint},
+    };
+    use indoc::indoc;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::{suggest_fixes_for_errors, suggest_fixes_for_hints};
+
+    fn supergraph_config() -> SupergraphConfig {
+        let subgraph = |sdl: &str| SubgraphConfig {
+            routing_url: None,
+            schema: SchemaSource::Sdl {
+                sdl: sdl.to_string(),
+            },
+        };
+        SupergraphConfig::new(
+            BTreeMap::from([
+                (
+                    "products".to_string(),
+                    subgraph(indoc! {r#"
+                        type Query {
+                          products(first: Int, after: String): [Product]
+                        }
+
+                        type Product @key(fields: "id") {
+                          id: ID!
+                          name: String
+                        }
+
+                        input ProductFilter {
+                          name: String
+                          inStock: Boolean
+                        }
+
+                        enum Sort {
+                          NAME
+                          PRICE @deprecated
+                        }
+                    "#}),
+                ),
+                (
+                    "inventory".to_string(),
+                    subgraph(indoc! {r#"
+                        type Product @key(fields: "id") {
+                          id: ID!
+                          name: String
+                        }
+                    "#}),
+                ),
+            ]),
+            None,
+        )
+    }
+
+    fn hint(code: &str, message: &str) -> BuildHint {
+        BuildHint::new(message.to_string(), code.to_string(), None, None)
+    }
+
+    #[rstest]
+    #[case::input_field(
+        "INCONSISTENT_INPUT_OBJECT_FIELD",
+        r#"Input object field "inStock" will not be added to "ProductFilter" in the supergraph as it does not appear in all subgraphs: it is defined in subgraph "products" but not in subgraph "search"."#,
+        "ProductFilter.inStock",
+        "@@ -12 +12 @@\n-  inStock: Boolean\n+  inStock: Boolean @inaccessible"
+    )]
+    #[case::enum_value(
+        "INCONSISTENT_ENUM_VALUE_FOR_INPUT_ENUM",
+        r#"Value "PRICE" of enum type "Sort" will not be part of the supergraph as it is not defined in all the subgraphs defining "Sort": "PRICE" is defined in subgraph "products" but not in subgraph "search"."#,
+        "Sort.PRICE",
+        "@@ -17 +17 @@\n-  PRICE @deprecated\n+  PRICE @deprecated @inaccessible"
+    )]
+    #[case::argument(
+        "INCONSISTENT_ARGUMENT_PRESENCE",
+        r#"Optional argument "Query.products(after:)" will not be included in the supergraph as it does not appear in all subgraphs: it is defined in subgraph "products" but not in subgraph "search"."#,
+        "Query.products(after:)",
+        "@@ -2 +2 @@\n-  products(first: Int, after: String): [Product]\n+  products(first: Int, after: String @inaccessible): [Product]"
+    )]
+    fn test_suggest_fixes_for_hints(
+        #[case] code: &str,
+        #[case] message: &str,
+        #[case] coordinate: &str,
+        #[case] patch: &str,
+    ) {
+        let suggestions = suggest_fixes_for_hints(&[hint(code, message)], &supergraph_config());
+
+        assert_that!(suggestions).has_length(1);
+        let suggestion = &suggestions[0];
+        assert_that!(suggestion.subgraph).is_equal_to("products".to_string());
+        assert_that!(suggestion.coordinate).is_equal_to(coordinate.to_string());
+        assert_that!(suggestion.directive).is_equal_to("@inaccessible".to_string());
+        assert_that!(suggestion.patch)
+            .is_equal_to(Some(format!("--- a/products\n+++ b/products\n{patch}")));
+    }
+
+    #[test]
+    fn test_suggest_fixes_for_hints_ignores_other_hints() {
+        let suggestions = suggest_fixes_for_hints(
+            &[hint(
+                "INCONSISTENT_DESCRIPTION",
+                r#"Element "Product" has inconsistent descriptions across subgraphs."#,
+            )],
+            &supergraph_config(),
+        );
+        assert_that!(suggestions).is_empty();
+    }
+
+    #[test]
+    fn test_suggest_fixes_for_field_sharing_errors() {
+        let mut errors = BuildErrors::new();
+        errors.push(BuildError::composition_error(
+            Some("INVALID_FIELD_SHARING".to_string()),
+            Some(r#"Non-shareable field "Product.name" is resolved from multiple subgraphs: it is resolved from subgraphs "inventory" and "products" and defined as non-shareable in all of them"#.to_string()),
+            None,
+            None,
+        ));
+
+        let suggestions = suggest_fixes_for_errors(&errors, &supergraph_config());
+
+        let directives: Vec<_> = suggestions
+            .iter()
+            .map(|suggestion| (suggestion.subgraph.as_str(), suggestion.directive.as_str()))
+            .collect();
+        assert_that!(directives).is_equal_to(vec![
+            ("inventory", "@shareable"),
+            ("products", "@shareable"),
+            ("products", r#"@override(from: "inventory")"#),
+        ]);
+        assert_that!(suggestions[0].patch).is_equal_to(Some(
+            "--- a/inventory\n+++ b/inventory\n@@ -3 +3 @@\n-  name: String\n+  name: String @shareable"
+                .to_string(),
+        ));
+    }
+}
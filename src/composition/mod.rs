@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, time::Duration};
 
 use apollo_federation_types::{
     config::FederationVersion,
@@ -6,13 +6,18 @@ use apollo_federation_types::{
 };
 use camino::Utf8PathBuf;
 use derive_getters::Getters;
+use profile::CompositionProfile;
+use supergraph::binary::BinaryDiagnostics;
 
 pub mod events;
+pub mod hints;
+pub mod profile;
 pub mod runner;
 pub mod supergraph;
 #[cfg(test)]
 pub mod test;
 pub mod types;
+pub mod validation;
 
 #[cfg(feature = "composition-js")]
 mod watchers;
@@ -22,6 +27,17 @@ pub struct CompositionSuccess {
     supergraph_sdl: String,
     hints: Vec<BuildHint>,
     federation_version: FederationVersion,
+    profile: Option<CompositionProfile>,
+}
+
+impl CompositionSuccess {
+    /// Attaches a breakdown of how long composition took
+    pub fn with_profile(self, profile: CompositionProfile) -> Self {
+        Self {
+            profile: Some(profile),
+            ..self
+        }
+    }
 }
 
 #[derive(thiserror::Error, Debug, Eq, PartialEq)]
@@ -34,6 +50,21 @@ pub enum CompositionError {
     InvalidInput { binary: Utf8PathBuf, error: String },
     #[error("Failed to read the file at: {path}")]
     ReadFile { path: Utf8PathBuf, error: String },
+    #[error("Failed to write the supergraph to: {path}")]
+    WriteFile { path: Utf8PathBuf, error: String },
     #[error("Encountered {} while trying to build a supergraph.", .source.length_string())]
     Build { source: BuildErrors },
+    #[error("`{binary} compose` failed without producing a composition result\n{diagnostics}")]
+    BinaryFailed {
+        binary: Utf8PathBuf,
+        diagnostics: Box<BinaryDiagnostics>,
+    },
+    #[error(
+        "`{binary} compose` was stopped after running for {}s, the most allowed by `--composition-timeout`",
+        .timeout.as_secs()
+    )]
+    Timeout {
+        binary: Utf8PathBuf,
+        timeout: Duration,
+    },
 }
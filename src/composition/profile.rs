@@ -0,0 +1,180 @@
+//! Where the time goes when composing a supergraph. Composing a large graph can take a while, and
+//! this breaks that time down into resolving each subgraph's schema and running composition itself
+
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+use apollo_federation_types::config::{SchemaSource, SupergraphConfig};
+use derive_getters::Getters;
+use serde::{Serialize, Serializer};
+
+use crate::{
+    options::SchemaSize,
+    utils::table::{self, row},
+};
+
+/// Timings and schema sizes for a single composition
+#[derive(Getters, Debug, Clone, Default, Eq, PartialEq, Serialize)]
+pub struct CompositionProfile {
+    /// How long it took to resolve every subgraph's schema, which happens concurrently
+    #[serde(rename = "resolution_ms", serialize_with = "serialize_millis")]
+    resolution_time: Duration,
+    /// How long composition itself took, once every schema was resolved
+    #[serde(rename = "composition_ms", serialize_with = "serialize_millis")]
+    composition_time: Duration,
+    /// How long everything took, from reading the supergraph config to having a supergraph schema
+    #[serde(rename = "total_ms", serialize_with = "serialize_millis")]
+    total_time: Duration,
+    subgraphs: BTreeMap<String, SubgraphProfile>,
+}
+
+/// Timings and schema size for a single subgraph
+#[derive(Getters, Debug, Clone, Default, Eq, PartialEq, Serialize)]
+pub struct SubgraphProfile {
+    /// How long the subgraph's schema took to resolve, when it was timed separately from the rest
+    #[serde(
+        rename = "resolution_ms",
+        serialize_with = "serialize_optional_millis",
+        skip_serializing_if = "Option::is_none"
+    )]
+    resolution_time: Option<Duration>,
+    /// The size of the subgraph's SDL, in bytes
+    sdl_bytes: u64,
+}
+
+impl CompositionProfile {
+    /// Builds a profile for the subgraphs in a resolved `supergraph_config`, taking the time each
+    /// one spent resolving from `subgraph_resolution_times` when it's known
+    pub fn new(
+        supergraph_config: &SupergraphConfig,
+        subgraph_resolution_times: &BTreeMap<String, Duration>,
+        resolution_time: Duration,
+        composition_time: Duration,
+        total_time: Duration,
+    ) -> CompositionProfile {
+        let subgraphs = supergraph_config
+            .clone()
+            .into_iter()
+            .map(|(name, subgraph_config)| {
+                let sdl_bytes = match subgraph_config.schema {
+                    SchemaSource::Sdl { sdl } => sdl.len() as u64,
+                    _ => 0,
+                };
+                let profile = SubgraphProfile {
+                    resolution_time: subgraph_resolution_times.get(&name).copied(),
+                    sdl_bytes,
+                };
+                (name, profile)
+            })
+            .collect();
+        CompositionProfile {
+            resolution_time,
+            composition_time,
+            total_time,
+            subgraphs,
+        }
+    }
+
+    /// Accounts for time spent resolving subgraphs before composition was profiled
+    pub fn with_resolution_time(self, resolution_time: Duration) -> CompositionProfile {
+        CompositionProfile {
+            resolution_time,
+            total_time: self.total_time + resolution_time,
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for CompositionProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut table = table::get_table();
+        // bc => sets top row to be bold and center
+        table.add_row(row![bc => "Subgraph", "Resolution", "SDL Size"]);
+        // slowest subgraphs first, since those are the ones worth looking into
+        let mut subgraphs = self.subgraphs.iter().collect::<Vec<_>>();
+        subgraphs.sort_by_key(|(_, profile)| std::cmp::Reverse(profile.resolution_time));
+        for (name, profile) in subgraphs {
+            let resolution_time = profile
+                .resolution_time
+                .map(|resolution_time| format!("{:.2?}", resolution_time))
+                .unwrap_or_else(|| "-".to_string());
+            table.add_row(row![
+                name,
+                resolution_time,
+                SchemaSize::from(profile.sdl_bytes)
+            ]);
+        }
+        writeln!(f, "{}", table)?;
+        writeln!(f, "Resolved subgraphs in {:.2?}", self.resolution_time)?;
+        writeln!(f, "Composed in {:.2?}", self.composition_time)?;
+        write!(f, "Total {:.2?}", self.total_time)
+    }
+}
+
+fn serialize_millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u128(duration.as_millis())
+}
+
+fn serialize_optional_millis<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serialize_millis(duration, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, time::Duration};
+
+    use apollo_federation_types::config::{SchemaSource, SubgraphConfig, SupergraphConfig};
+    use serde_json::json;
+    use speculoos::prelude::*;
+
+    use super::CompositionProfile;
+
+    #[test]
+    fn test_profile_serializes_to_milliseconds() {
+        let supergraph_config = SupergraphConfig::new(
+            BTreeMap::from([
+                (
+                    "products".to_string(),
+                    SubgraphConfig {
+                        routing_url: None,
+                        schema: SchemaSource::Sdl {
+                            sdl: "type Query { a: String }".to_string(),
+                        },
+                    },
+                ),
+                (
+                    "reviews".to_string(),
+                    SubgraphConfig {
+                        routing_url: None,
+                        schema: SchemaSource::Sdl {
+                            sdl: "type Query { b: String }".to_string(),
+                        },
+                    },
+                ),
+            ]),
+            None,
+        );
+        let profile = CompositionProfile::new(
+            &supergraph_config,
+            &BTreeMap::from([("products".to_string(), Duration::from_millis(120))]),
+            Duration::from_millis(150),
+            Duration::from_millis(2500),
+            Duration::from_millis(2700),
+        );
+        assert_that!(serde_json::to_value(&profile).unwrap()).is_equal_to(json!({
+            "resolution_ms": 150,
+            "composition_ms": 2500,
+            "total_ms": 2700,
+            "subgraphs": {
+                "products": { "resolution_ms": 120, "sdl_bytes": 24 },
+                "reviews": { "sdl_bytes": 24 }
+            }
+        }));
+        assert_that!(profile.to_string()).contains("Composed in 2.50s");
+    }
+}
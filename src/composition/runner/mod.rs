@@ -3,7 +3,7 @@
 
 #![warn(missing_docs)]
 
-use std::{collections::BTreeMap, env::current_dir, fmt::Debug, io::stdin};
+use std::{collections::BTreeMap, env::current_dir, fmt::Debug, io::stdin, time::Instant};
 
 //use std::{env::current_dir, fs::File, process::Command, str};
 
@@ -13,8 +13,9 @@ use buildstructor::Builder;
 use camino::Utf8PathBuf;
 use futures::stream::{BoxStream, StreamExt};
 use rover_client::shared::GraphRef;
-use rover_std::warnln;
+use rover_std::{timing, warnln, Style};
 use tempfile::tempdir;
+use uuid::Uuid;
 
 use crate::{
     command::supergraph::compose::CompositionOutput,
@@ -22,34 +23,42 @@ use crate::{
         supergraph::install::InstallSupergraph,
         watchers::watcher::{file::FileWatcher, supergraph_config::SupergraphConfigWatcher},
     },
-    options::{LicenseAccepter, ProfileOpt},
+    options::{CompositionLimitOpts, LicenseAccepter, ProfileOpt, SchemaSizeOpts},
     subtask::{Subtask, SubtaskRunStream, SubtaskRunUnit},
     utils::{
         client::StudioClientConfig,
         effect::{
             exec::{ExecCommand, TokioCommand},
             install::InstallBinary,
+            introspect::IntrospectionPolicy,
             read_file::{FsReadFile, ReadFile},
             write_file::{FsWriteFile, WriteFile},
         },
         parsers::FileDescriptorType,
     },
-    RoverError, RoverResult,
+    RoverError, RoverErrorSuggestion, RoverResult,
 };
 
 use self::state::SetupSubgraphWatchers;
 
 use super::{
     events::CompositionEvent,
+    hints::{suggest_fixes_for_errors, suggest_fixes_for_hints},
+    profile::CompositionProfile,
     supergraph::{
         binary::{OutputTarget, SupergraphBinary},
         config::{
             full::FullyResolvedSubgraphs,
             lazy::{LazilyResolvedSubgraph, LazilyResolvedSupergraphConfig},
-            resolver::SupergraphConfigResolver,
+            resolver::{
+                SubgraphResolutionOptions, SubgraphResolutionPolicy, SubgraphRetryPolicy,
+                SupergraphConfigResolver,
+            },
+            validation::SupergraphConfigValidation,
         },
     },
     watchers::{composition::CompositionWatcher, subgraphs::SubgraphWatchers},
+    CompositionError,
 };
 
 mod state;
@@ -81,11 +90,21 @@ pub struct OneShotComposition {
     graph_ref: Option<GraphRef>,
     elv2_license_accepter: LicenseAccepter,
     skip_update: bool,
+    environment: Option<String>,
+    schema_size_opts: Option<SchemaSizeOpts>,
+    composition_limit_opts: Option<CompositionLimitOpts>,
+    subgraph_resolution_policy: Option<SubgraphResolutionPolicy>,
+    subgraph_retry_policy: Option<SubgraphRetryPolicy>,
+    introspection_policy: Option<IntrospectionPolicy>,
+    supergraph_config_validation: Option<SupergraphConfigValidation>,
+    suggest_fixes: Option<bool>,
 }
 
 impl OneShotComposition {
-    /// Runs composition
+    /// Runs composition, in a span with an ID that ties together everything logged along the way
+    #[tracing::instrument(name = "composition", skip_all, fields(composition_id = %Uuid::new_v4()))]
     pub async fn compose(self) -> RoverResult<CompositionOutput> {
+        let started_at = Instant::now();
         let mut stdin = stdin();
         let write_file = FsWriteFile::default();
         let read_file = FsReadFile::default();
@@ -103,29 +122,70 @@ impl OneShotComposition {
             FileDescriptorType::Stdin => None,
         });
 
+        let offline = self.client_config.is_offline();
+        if let (true, Some(graph_ref)) = (offline, self.graph_ref.as_ref()) {
+            let mut err = RoverError::new(anyhow!(
+                "Can't load the subgraphs of {} from GraphOS while offline",
+                graph_ref
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Describe every subgraph in a supergraph config instead, or run without `--offline`."
+                    .to_string(),
+            ));
+            return Err(err);
+        }
+
         let studio_client = self
             .client_config
             .get_authenticated_client(&self.profile.clone())?;
 
+        let resolution_started_at = Instant::now();
+        let resolution_timing = timing::time_phase("resolution");
         // Get a FullyResolvedSupergraphConfig from first loading in any remote subgraphs and then
         // a local supergraph config (if present) and then combining them into a fully resolved
         // supergraph config
-        let resolver = SupergraphConfigResolver::default()
+        let (resolver, resolution_report) = SupergraphConfigResolver::default()
             .load_remote_subgraphs(&studio_client, self.graph_ref.as_ref())
             .await?
-            .load_from_file_descriptor(&mut stdin, self.supergraph_yaml.as_ref())?
-            .fully_resolve_subgraphs(
+            .load_from_file_descriptor_with_validation(
+                &mut stdin,
+                self.supergraph_yaml.as_ref(),
+                self.supergraph_config_validation.unwrap_or_default(),
+                self.environment.as_deref(),
+            )?
+            .fully_resolve_subgraphs_with_options(
                 &self.client_config,
                 &studio_client,
                 supergraph_root.as_ref(),
+                &SubgraphResolutionOptions {
+                    policy: self.subgraph_resolution_policy.unwrap_or_default(),
+                    retry_policy: self.subgraph_retry_policy.unwrap_or_default(),
+                    offline,
+                    introspection_policy: self.introspection_policy.unwrap_or_default(),
+                },
             )
             .await?;
+        let resolution_time = resolution_started_at.elapsed();
+        drop(resolution_timing);
+
+        // Only populated under `SubgraphResolutionPolicy::AllowPartial`, in which case we compose
+        // what we could resolve and let the user know what was left out
+        for (subgraph_name, err) in &resolution_report.failures {
+            warnln!(
+                "composing without subgraph `{}` because it could not be resolved: {}",
+                subgraph_name,
+                err
+            );
+        }
 
         // We convert the FullyResolvedSupergraphConfig into a Supergraph because it makes using
         // Serde easier (said differently: we're using the Federation-rs types here for
         // compatability with Federation-rs tooling later on when we use their supergraph binary to
         // actually run composition)
         let supergraph_config: SupergraphConfig = resolver.clone().into();
+        self.schema_size_opts
+            .unwrap_or_default()
+            .check(&supergraph_config)?;
 
         // Convert the FullyResolvedSupergraphConfig to yaml before we save it
         let supergraph_config_yaml = serde_yaml::to_string(&supergraph_config)?;
@@ -182,8 +242,11 @@ impl OneShotComposition {
                     self.elv2_license_accepter,
                     self.skip_update,
                 )
-                .await?;
+                .await?
+                .with_limits(&self.composition_limit_opts.unwrap_or_default());
 
+        let composition_started_at = Instant::now();
+        let suggest_fixes = self.suggest_fixes.unwrap_or_default();
         let result = supergraph_binary
             .compose(
                 &exec_command,
@@ -193,9 +256,29 @@ impl OneShotComposition {
                     .unwrap_or(OutputTarget::Stdout),
                 supergraph_config_filepath,
             )
-            .await?;
+            .await
+            .inspect_err(|err| {
+                if let (true, CompositionError::Build { source }) = (suggest_fixes, err) {
+                    for suggestion in suggest_fixes_for_errors(source, &supergraph_config) {
+                        eprintln!("{} {}", Style::HintPrefix.paint("SUGGESTION:"), suggestion);
+                    }
+                }
+            })?;
+        let profile = CompositionProfile::new(
+            &supergraph_config,
+            &resolution_report.resolution_times,
+            resolution_time,
+            composition_started_at.elapsed(),
+            started_at.elapsed(),
+        );
 
-        Ok(result.into())
+        let suggestions =
+            suggest_fixes.then(|| suggest_fixes_for_hints(result.hints(), &supergraph_config));
+        Ok(CompositionOutput {
+            suggestions,
+            query_plans: None,
+            ..result.with_profile(profile).into()
+        })
     }
 }
 
@@ -255,7 +338,9 @@ impl Runner<state::SetupSupergraphConfigWatcher> {
 }
 
 impl Runner<state::SetupCompositionWatcher> {
-    /// Configures the composition watcher
+    /// Configures the composition watcher. With `verify_output`, every file the supergraph is
+    /// written to is read back after each composition, and a
+    /// [`CompositionEvent::OutputCorrupted`] is emitted for any that doesn't match
     #[allow(clippy::too_many_arguments)]
     pub fn setup_composition_watcher<ReadF, ExecC, WriteF>(
         self,
@@ -266,6 +351,7 @@ impl Runner<state::SetupCompositionWatcher> {
         write_file: WriteF,
         output_target: OutputTarget,
         temp_dir: Utf8PathBuf,
+        verify_output: bool,
     ) -> Runner<state::Run<ReadF, ExecC, WriteF>>
     where
         ReadF: ReadFile + Debug + Eq + PartialEq + Send + Sync + 'static,
@@ -281,6 +367,7 @@ impl Runner<state::SetupCompositionWatcher> {
             .write_file(write_file)
             .output_target(output_target)
             .temp_dir(temp_dir)
+            .verify_output(verify_output)
             .build();
         Runner {
             state: state::Run {
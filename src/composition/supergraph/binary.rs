@@ -1,15 +1,23 @@
-use std::fmt::Debug;
+use std::{
+    fmt::{self, Debug},
+    process::{ExitStatus, Output},
+    time::Duration,
+};
 
 use apollo_federation_types::{
     config::FederationVersion,
     rover::{BuildErrors, BuildOutput, BuildResult},
 };
 use buildstructor::Builder;
+use calm_io::stdoutln;
 use camino::Utf8PathBuf;
+use rover_std::{timing, Fs};
 use tap::TapFallible;
+use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
     composition::{CompositionError, CompositionSuccess},
+    options::CompositionLimitOpts,
     utils::effect::{
         exec::{ExecCommand, ExecCommandConfig},
         read_file::ReadFile,
@@ -22,9 +30,81 @@ use super::version::SupergraphVersion;
 pub enum OutputTarget {
     File(Utf8PathBuf),
     Stdout,
+    /// Reads the composition result from stdout, then writes the supergraph SDL to every sink
+    /// once composition succeeds
+    Sinks(Vec<SupergraphSink>),
+}
+
+/// Somewhere to send the SDL of a successfully composed supergraph
+#[derive(Clone, Debug)]
+pub enum SupergraphSink {
+    /// Writes the SDL to a file, replacing it atomically so that anything watching the file, like
+    /// the router's hot reload, never reads half of a supergraph
+    File(Utf8PathBuf),
+    /// Prints the SDL to stdout
+    Stdout,
+    /// Sends the SDL to whatever's listening on the other end of the channel
+    Channel(UnboundedSender<String>),
+}
+
+impl PartialEq for SupergraphSink {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (SupergraphSink::File(path), SupergraphSink::File(other_path)) => path == other_path,
+            (SupergraphSink::Stdout, SupergraphSink::Stdout) => true,
+            (SupergraphSink::Channel(sender), SupergraphSink::Channel(other_sender)) => {
+                sender.same_channel(other_sender)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for SupergraphSink {}
+
+impl SupergraphSink {
+    fn write(&self, supergraph_sdl: &str) -> Result<(), CompositionError> {
+        match self {
+            SupergraphSink::File(path) => {
+                Fs::write_file_atomically(path, supergraph_sdl).map_err(|err| {
+                    CompositionError::WriteFile {
+                        path: path.clone(),
+                        error: format!("{:?}", err),
+                    }
+                })
+            }
+            SupergraphSink::Stdout => {
+                stdoutln!("{}", supergraph_sdl)?;
+                Ok(())
+            }
+            SupergraphSink::Channel(sender) => {
+                // nobody listening anymore isn't a reason to fail the composition
+                let _ = sender
+                    .send(supergraph_sdl.to_string())
+                    .tap_err(|err| tracing::warn!("{:?}", err));
+                Ok(())
+            }
+        }
+    }
 }
 
 impl OutputTarget {
+    /// The files a successful composition writes the supergraph SDL to. A [`OutputTarget::File`]
+    /// isn't one of them, since the supergraph binary writes its whole result there rather than
+    /// only the SDL
+    pub fn supergraph_files(&self) -> Vec<&Utf8PathBuf> {
+        match self {
+            OutputTarget::Sinks(sinks) => sinks
+                .iter()
+                .filter_map(|sink| match sink {
+                    SupergraphSink::File(path) => Some(path),
+                    SupergraphSink::Stdout | SupergraphSink::Channel(_) => None,
+                })
+                .collect(),
+            OutputTarget::File(_) | OutputTarget::Stdout => Vec::new(),
+        }
+    }
+
     pub fn align_to_version(self, version: &SupergraphVersion) -> OutputTarget {
         match self {
             OutputTarget::File(path) => {
@@ -36,6 +116,7 @@ impl OutputTarget {
                 }
             }
             OutputTarget::Stdout => OutputTarget::Stdout,
+            OutputTarget::Sinks(sinks) => OutputTarget::Sinks(sinks),
         }
     }
 }
@@ -64,9 +145,20 @@ impl From<std::io::Error> for CompositionError {
 pub struct SupergraphBinary {
     exe: Utf8PathBuf,
     version: SupergraphVersion,
+    timeout: Option<Duration>,
+    max_output_bytes: Option<u64>,
 }
 
 impl SupergraphBinary {
+    /// Applies `--composition-timeout` and `--max-composition-output` to every run of the binary
+    pub fn with_limits(self, limits: &CompositionLimitOpts) -> Self {
+        Self {
+            timeout: limits.timeout(),
+            max_output_bytes: limits.max_output_bytes(),
+            ..self
+        }
+    }
+
     fn prepare_compose_args(
         &self,
         output_target: &OutputTarget,
@@ -88,22 +180,32 @@ impl SupergraphBinary {
         output_target: &OutputTarget,
         supergraph_config_path: Utf8PathBuf,
     ) -> Result<CompositionSuccess, CompositionError> {
+        let _timing = timing::time_phase("composition");
         let args = self.prepare_compose_args(output_target, &supergraph_config_path);
 
-        let output = exec_impl
-            .exec_command(
-                ExecCommandConfig::builder()
-                    .exe(self.exe.clone())
-                    .args(args)
-                    .build(),
-            )
-            .await
-            .tap_err(|err| tracing::error!("{:?}", err))
-            .map_err(|err| CompositionError::Binary {
-                error: format!("{:?}", err),
-            })?;
+        let exec = exec_impl.exec_command(
+            ExecCommandConfig::builder()
+                .exe(self.exe.clone())
+                .args(args.clone())
+                .and_max_stdout_bytes(self.max_output_bytes)
+                .build(),
+        );
+        // dropping `exec` when the timeout is up is what stops the binary
+        let output = match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, exec).await.map_err(|_| {
+                CompositionError::Timeout {
+                    binary: self.exe.clone(),
+                    timeout,
+                }
+            })?,
+            None => exec.await,
+        }
+        .tap_err(|err| tracing::error!("{:?}", err))
+        .map_err(|err| CompositionError::Binary {
+            error: format!("{:?}", err),
+        })?;
 
-        let output = match output_target {
+        let composition = match output_target {
             OutputTarget::File(path) => {
                 read_file_impl
                     .read_file(path)
@@ -111,17 +213,45 @@ impl SupergraphBinary {
                     .map_err(|err| CompositionError::ReadFile {
                         path: path.clone(),
                         error: format!("{:?}", err),
-                    })?
+                    })
             }
-            OutputTarget::Stdout => std::str::from_utf8(&output.stdout)
+            OutputTarget::Stdout | OutputTarget::Sinks(_) => std::str::from_utf8(&output.stdout)
+                .map(|stdout| stdout.to_string())
                 .map_err(|err| CompositionError::InvalidOutput {
                     binary: self.exe.clone(),
                     error: format!("{:?}", err),
-                })?
-                .to_string(),
-        };
-
-        self.validate_composition(&output)
+                }),
+        }
+        .and_then(|composition| self.validate_composition(&composition));
+
+        match composition {
+            // Build errors are the binary reporting a failed composition the way it's supposed to,
+            // but anything else from a binary that exited unsuccessfully means it crashed or
+            // bailed out before it could report anything, so we pass on whatever it printed
+            Err(err)
+                if !output.status.success() && !matches!(err, CompositionError::Build { .. }) =>
+            {
+                let mut argv = vec![self.exe.to_string()];
+                argv.extend(args);
+                Err(CompositionError::BinaryFailed {
+                    binary: self.exe.clone(),
+                    diagnostics: Box::new(BinaryDiagnostics::new(
+                        argv,
+                        supergraph_config_path,
+                        &output,
+                    )),
+                })
+            }
+            Ok(success) => {
+                if let OutputTarget::Sinks(sinks) = output_target {
+                    for sink in sinks {
+                        sink.write(success.supergraph_sdl())?;
+                    }
+                }
+                Ok(success)
+            }
+            composition => composition,
+        }
     }
 
     /// Validate that the output of the supergraph binary contains either build errors or build
@@ -153,6 +283,7 @@ impl SupergraphBinary {
                 hints: build_output.hints,
                 supergraph_sdl: build_output.supergraph_sdl,
                 federation_version,
+                profile: None,
             })
             .map_err(|build_errors| CompositionError::Build {
                 source: build_errors,
@@ -174,11 +305,66 @@ impl SupergraphBinary {
     }
 }
 
+/// The most of each output stream kept in [`BinaryDiagnostics`], since a crashing binary can
+/// print far more than is useful to show
+const DIAGNOSTIC_OUTPUT_LIMIT: usize = 4096;
+
+/// What's known about a run of the supergraph binary that failed without a composition result
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BinaryDiagnostics {
+    argv: Vec<String>,
+    supergraph_config_path: Utf8PathBuf,
+    status: ExitStatus,
+    stdout: String,
+    stderr: String,
+}
+
+impl BinaryDiagnostics {
+    pub fn new(argv: Vec<String>, supergraph_config_path: Utf8PathBuf, output: &Output) -> Self {
+        Self {
+            argv,
+            supergraph_config_path,
+            status: output.status,
+            stdout: output_tail(&output.stdout),
+            stderr: output_tail(&output.stderr),
+        }
+    }
+}
+
+/// The end of an output stream, where errors usually are, bounded by [`DIAGNOSTIC_OUTPUT_LIMIT`]
+fn output_tail(output: &[u8]) -> String {
+    let output = String::from_utf8_lossy(output);
+    let output = output.trim_end();
+    if output.len() <= DIAGNOSTIC_OUTPUT_LIMIT {
+        return output.to_string();
+    }
+    let mut start = output.len() - DIAGNOSTIC_OUTPUT_LIMIT;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("[... {} bytes omitted]\n{}", start, &output[start..])
+}
+
+impl fmt::Display for BinaryDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "command: {}", self.argv.join(" "))?;
+        writeln!(f, "supergraph config: {}", self.supergraph_config_path)?;
+        write!(f, "{}", self.status)?;
+        for (name, output) in [("stderr", &self.stderr), ("stdout", &self.stdout)] {
+            if !output.is_empty() {
+                write!(f, "\n{}:\n{}", name, output)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
         process::{ExitStatus, Output},
         str::FromStr,
+        time::Duration,
     };
 
     use anyhow::Result;
@@ -199,7 +385,10 @@ mod tests {
         },
     };
 
-    use super::{CompositionSuccess, OutputTarget, SupergraphBinary};
+    use super::{
+        output_tail, CompositionSuccess, OutputTarget, SupergraphBinary, SupergraphSink,
+        DIAGNOSTIC_OUTPUT_LIMIT,
+    };
 
     fn fed_one() -> Version {
         Version::from_str("1.0.0").unwrap()
@@ -242,6 +431,7 @@ mod tests {
             hints: res.hints,
             supergraph_sdl: res.supergraph_sdl,
             federation_version: FederationVersion::ExactFedTwo(fed_two_eight()),
+            profile: None,
         }
     }
 
@@ -349,4 +539,160 @@ mod tests {
 
         Ok(())
     }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_compose_writes_to_every_sink(
+        composition_output: CompositionSuccess,
+    ) -> Result<()> {
+        let supergraph_binary = SupergraphBinary::builder()
+            .exe(Utf8PathBuf::from_str("/tmp/supergraph")?)
+            .version(SupergraphVersion::new(fed_two_eight()))
+            .build();
+
+        let mut mock_read_file = MockReadFile::new();
+        mock_read_file.expect_read_file().times(0);
+        let mut mock_exec = MockExecCommand::new();
+        mock_exec
+            .expect_exec_command()
+            .times(1)
+            .withf(|actual_config| {
+                actual_config.args()
+                    == &Some(vec![
+                        "compose".to_string(),
+                        "/tmp/target/supergraph_config.yaml".to_string(),
+                    ])
+            })
+            .returning(|_| {
+                let stdout = serde_json::to_string(&default_composition_json()).unwrap();
+                Ok(Output {
+                    status: ExitStatus::default(),
+                    stdout: stdout.as_bytes().into(),
+                    stderr: Vec::default(),
+                })
+            });
+
+        let temp_dir = TempDir::new()?;
+        let supergraph_path =
+            Utf8PathBuf::from_path_buf(temp_dir.path().join("supergraph.graphql")).unwrap();
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let result = supergraph_binary
+            .compose(
+                &mock_exec,
+                &mock_read_file,
+                &OutputTarget::Sinks(vec![
+                    SupergraphSink::File(supergraph_path.clone()),
+                    SupergraphSink::Channel(sender),
+                ]),
+                Utf8PathBuf::from_str("/tmp/target/supergraph_config.yaml")?,
+            )
+            .await;
+
+        assert_that!(result)
+            .is_ok()
+            .is_equal_to(composition_output.clone());
+        assert_that!(std::fs::read_to_string(&supergraph_path)?)
+            .is_equal_to(composition_output.supergraph_sdl().to_string());
+        assert_that!(receiver.try_recv())
+            .is_ok()
+            .is_equal_to(composition_output.supergraph_sdl().to_string());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[rstest]
+    #[tokio::test]
+    async fn test_compose_times_out() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        use crate::{composition::CompositionError, utils::effect::exec::TokioCommand};
+
+        let temp_dir = TempDir::new()?;
+        let exe = Utf8PathBuf::from_path_buf(temp_dir.path().join("supergraph")).unwrap();
+        std::fs::write(&exe, "#!/bin/sh\nsleep 30\n")?;
+        std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755))?;
+
+        let supergraph_binary = SupergraphBinary::builder()
+            .exe(exe.clone())
+            .version(SupergraphVersion::new(fed_two_eight()))
+            .timeout(Duration::from_millis(200))
+            .build();
+
+        let mut mock_read_file = MockReadFile::new();
+        mock_read_file.expect_read_file().times(0);
+
+        let result = supergraph_binary
+            .compose(
+                &TokioCommand::default(),
+                &mock_read_file,
+                &OutputTarget::Stdout,
+                Utf8PathBuf::from_str("/tmp/target/supergraph_config.yaml")?,
+            )
+            .await;
+
+        assert_that!(result)
+            .is_err()
+            .is_equal_to(CompositionError::Timeout {
+                binary: exe,
+                timeout: Duration::from_millis(200),
+            });
+        Ok(())
+    }
+
+    fn failed_exit_status() -> ExitStatus {
+        #[cfg(unix)]
+        let status = std::os::unix::process::ExitStatusExt::from_raw(1 << 8);
+        #[cfg(windows)]
+        let status = std::os::windows::process::ExitStatusExt::from_raw(1);
+        status
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_compose_reports_diagnostics_when_binary_fails() -> Result<()> {
+        let supergraph_binary = SupergraphBinary::builder()
+            .exe(Utf8PathBuf::from_str("/tmp/supergraph")?)
+            .version(SupergraphVersion::new(fed_two_eight()))
+            .build();
+
+        let mut mock_read_file = MockReadFile::new();
+        mock_read_file.expect_read_file().times(0);
+        let mut mock_exec = MockExecCommand::new();
+        mock_exec.expect_exec_command().times(1).returning(|_| {
+            Ok(Output {
+                status: failed_exit_status(),
+                stdout: Vec::default(),
+                stderr: "thread 'main' panicked at 'out of memory'"
+                    .as_bytes()
+                    .into(),
+            })
+        });
+
+        let result = supergraph_binary
+            .compose(
+                &mock_exec,
+                &mock_read_file,
+                &OutputTarget::Stdout,
+                Utf8PathBuf::from_str("/tmp/target/supergraph_config.yaml")?,
+            )
+            .await;
+
+        let message = assert_that!(result).is_err().subject.to_string();
+        assert_that!(message)
+            .contains("command: /tmp/supergraph compose /tmp/target/supergraph_config.yaml");
+        assert_that!(message).contains("supergraph config: /tmp/target/supergraph_config.yaml");
+        assert_that!(message).contains("stderr:\nthread 'main' panicked at 'out of memory'");
+        Ok(())
+    }
+
+    #[test]
+    fn test_output_tail_is_bounded() {
+        let output = format!("{}{}", "a".repeat(100), "b".repeat(DIAGNOSTIC_OUTPUT_LIMIT));
+        assert_that!(output_tail(output.as_bytes())).is_equal_to(format!(
+            "[... 100 bytes omitted]\n{}",
+            "b".repeat(DIAGNOSTIC_OUTPUT_LIMIT)
+        ));
+        assert_that!(output_tail(b"short\n")).is_equal_to("short".to_string());
+    }
 }
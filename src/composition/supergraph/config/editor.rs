@@ -0,0 +1,477 @@
+//! Edits the subgraphs of a supergraph config file in place, leaving everything else in the file,
+//! including comments, blank lines, and the formatting of untouched subgraphs, as it was
+//!
+//! Like [`super::source_map`], this reads the file with [`crate::utils::yaml_lines`], so it only
+//! understands the subset of YAML that supergraph configs are written in. Anything it can't edit
+//! safely, such as a `subgraphs` section written as a flow mapping, is reported rather than
+//! rewritten
+
+use apollo_federation_types::config::SubgraphConfig;
+use serde_yaml::Value;
+
+use crate::utils::yaml_lines::{indent_of, is_trivia, key_at, strip_comment, trailing_comment};
+
+/// Something that stopped an edit of a supergraph config
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+pub enum SupergraphConfigEditError {
+    /// Occurs when adding a subgraph whose name is already taken
+    #[error("Subgraph `{subgraph_name}` is already in the supergraph config")]
+    SubgraphExists {
+        /// The name of the subgraph being added
+        subgraph_name: String,
+    },
+    /// Occurs when editing a subgraph that isn't in the supergraph config
+    #[error("Subgraph `{subgraph_name}` is not in the supergraph config")]
+    SubgraphNotFound {
+        /// The name of the subgraph being edited
+        subgraph_name: String,
+    },
+    /// Occurs when the part of the file being edited is written in a style the editor can't
+    /// change without risking the rest of the file
+    #[error("`{key}` on line {line} is written in a style that can't be edited automatically. Edit it by hand instead")]
+    UnsupportedStyle {
+        /// The key that couldn't be edited
+        key: String,
+        /// The 1-based line the key is on
+        line: usize,
+    },
+    /// Occurs when a new value can't be written as YAML, or the edited file no longer parses
+    #[error("The edited supergraph config is not valid YAML: {reason}")]
+    InvalidYaml {
+        /// What went wrong
+        reason: String,
+    },
+}
+
+/// A subgraph entry under the `subgraphs` key
+#[derive(Debug)]
+struct SubgraphEntry {
+    /// The first line of the entry, holding its name
+    start: usize,
+    /// The line after the last line of the entry, not counting the comments and blank lines
+    /// between it and the next entry
+    end: usize,
+}
+
+/// A supergraph config being edited, line by line
+#[derive(Debug, Clone)]
+pub struct SupergraphConfigEditor {
+    lines: Vec<String>,
+}
+
+impl SupergraphConfigEditor {
+    /// Starts editing the contents of a supergraph config
+    pub fn new(contents: &str) -> SupergraphConfigEditor {
+        SupergraphConfigEditor {
+            lines: contents.lines().map(|line| line.to_string()).collect(),
+        }
+    }
+
+    /// Adds a subgraph after the last one in the supergraph config, adding a `subgraphs` section
+    /// if there isn't one yet
+    pub fn add_subgraph(
+        &mut self,
+        subgraph_name: &str,
+        subgraph_config: &SubgraphConfig,
+    ) -> Result<(), SupergraphConfigEditError> {
+        if self.find_subgraph(subgraph_name)?.is_some() {
+            return Err(SupergraphConfigEditError::SubgraphExists {
+                subgraph_name: subgraph_name.to_string(),
+            });
+        }
+        let rendered = render_subgraph(subgraph_name, subgraph_config)?;
+
+        let Some((header, block_end)) = self.subgraphs_section()? else {
+            while self.lines.last().is_some_and(|line| line.trim().is_empty()) {
+                self.lines.pop();
+            }
+            if !self.lines.is_empty() {
+                self.lines.push(String::new());
+            }
+            self.lines.push("subgraphs:".to_string());
+            self.lines
+                .extend(rendered.lines().map(|line| format!("  {line}")));
+            return Ok(());
+        };
+
+        let entries = self.entries(header, block_end);
+        let indent = self.entry_indent(header, block_end).unwrap_or(2);
+        let insert_at = entries.last().map_or(header + 1, |(_, entry)| entry.end);
+        let new_lines = rendered
+            .lines()
+            .map(|line| format!("{}{line}", " ".repeat(indent)));
+        self.lines.splice(insert_at..insert_at, new_lines);
+        Ok(())
+    }
+
+    /// Removes a subgraph, along with any comment directly above it
+    pub fn remove_subgraph(
+        &mut self,
+        subgraph_name: &str,
+    ) -> Result<(), SupergraphConfigEditError> {
+        let entry = self.require_subgraph(subgraph_name)?;
+        let mut start = entry.start;
+        while start > 0 && self.lines[start - 1].trim_start().starts_with('#') {
+            start -= 1;
+        }
+        self.lines.drain(start..entry.end);
+        Ok(())
+    }
+
+    /// Sets the routing URL of a subgraph, adding one if it doesn't have one yet. A comment at the
+    /// end of an existing `routing_url` line is kept
+    pub fn set_routing_url(
+        &mut self,
+        subgraph_name: &str,
+        routing_url: &str,
+    ) -> Result<(), SupergraphConfigEditError> {
+        let entry = self.require_subgraph(subgraph_name)?;
+        self.require_block_mapping(entry.start)?;
+        let rendered_url = render_scalar(routing_url)?;
+
+        let fields = (entry.start + 1..entry.end)
+            .filter_map(|idx| {
+                key_at(&self.lines[idx]).map(|(indent, key, offset)| (idx, indent, key, offset))
+            })
+            .collect::<Vec<_>>();
+        let Some(field_indent) = fields.first().map(|(_, indent, _, _)| *indent) else {
+            let indent = indent_of(&self.lines[entry.start]) + 2;
+            self.lines.insert(
+                entry.start + 1,
+                format!("{}routing_url: {rendered_url}", " ".repeat(indent)),
+            );
+            return Ok(());
+        };
+
+        let existing = fields
+            .iter()
+            .find(|(_, indent, key, _)| *indent == field_indent && key == "routing_url");
+        match existing {
+            Some((idx, indent, _, value_offset)) => {
+                let value = &self.lines[*idx][indent + value_offset..];
+                let comment = trailing_comment(value).unwrap_or_default();
+                self.lines[*idx] = format!(
+                    "{}routing_url: {rendered_url}{comment}",
+                    " ".repeat(*indent)
+                );
+            }
+            None => self.lines.insert(
+                entry.start + 1,
+                format!("{}routing_url: {rendered_url}", " ".repeat(field_indent)),
+            ),
+        }
+        Ok(())
+    }
+
+    /// Finishes editing, checking that the result still parses as YAML
+    pub fn finish(self) -> Result<String, SupergraphConfigEditError> {
+        let mut contents = self.lines.join("\n");
+        contents.push('\n');
+        serde_yaml::from_str::<Value>(&contents).map_err(|err| {
+            SupergraphConfigEditError::InvalidYaml {
+                reason: err.to_string(),
+            }
+        })?;
+        Ok(contents)
+    }
+
+    /// Finds the `subgraphs:` line and the line after the end of its section
+    fn subgraphs_section(&self) -> Result<Option<(usize, usize)>, SupergraphConfigEditError> {
+        let Some((header, value_offset)) = self.lines.iter().enumerate().find_map(|(idx, line)| {
+            key_at(line)
+                .filter(|(indent, key, _)| *indent == 0 && key == "subgraphs")
+                .map(|(_, _, offset)| (idx, offset))
+        }) else {
+            return Ok(None);
+        };
+        if !strip_comment(&self.lines[header][value_offset..]).is_empty() {
+            return Err(SupergraphConfigEditError::UnsupportedStyle {
+                key: "subgraphs".to_string(),
+                line: header + 1,
+            });
+        }
+        let block_end = (header + 1..self.lines.len())
+            .find(|idx| {
+                let line = &self.lines[*idx];
+                !is_trivia(line) && indent_of(line) == 0
+            })
+            .unwrap_or(self.lines.len());
+        Ok(Some((header, block_end)))
+    }
+
+    /// The indentation of the subgraph names in the `subgraphs` section
+    fn entry_indent(&self, header: usize, block_end: usize) -> Option<usize> {
+        (header + 1..block_end)
+            .find_map(|idx| key_at(&self.lines[idx]).map(|(indent, _, _)| indent))
+    }
+
+    fn entries(&self, header: usize, block_end: usize) -> Vec<(String, SubgraphEntry)> {
+        let Some(entry_indent) = self.entry_indent(header, block_end) else {
+            return Vec::new();
+        };
+        let starts = (header + 1..block_end)
+            .filter_map(|idx| {
+                key_at(&self.lines[idx])
+                    .filter(|(indent, _, _)| *indent == entry_indent)
+                    .map(|(_, key, _)| (idx, key))
+            })
+            .collect::<Vec<_>>();
+        starts
+            .iter()
+            .enumerate()
+            .map(|(position, (start, name))| {
+                let next = starts
+                    .get(position + 1)
+                    .map_or(block_end, |(next_start, _)| *next_start);
+                // comments and blank lines at the end of an entry usually introduce the next one,
+                // unless they're indented as part of this one
+                let end = (start + 1..next)
+                    .rev()
+                    .find(|idx| {
+                        let line = &self.lines[*idx];
+                        let trimmed = line.trim_start();
+                        !trimmed.is_empty()
+                            && (!trimmed.starts_with('#') || indent_of(line) > entry_indent)
+                    })
+                    .map_or(start + 1, |last| last + 1);
+                (name.clone(), SubgraphEntry { start: *start, end })
+            })
+            .collect()
+    }
+
+    fn find_subgraph(
+        &self,
+        subgraph_name: &str,
+    ) -> Result<Option<SubgraphEntry>, SupergraphConfigEditError> {
+        Ok(self.subgraphs_section()?.and_then(|(header, block_end)| {
+            self.entries(header, block_end)
+                .into_iter()
+                .find(|(name, _)| name == subgraph_name)
+                .map(|(_, entry)| entry)
+        }))
+    }
+
+    fn require_subgraph(
+        &self,
+        subgraph_name: &str,
+    ) -> Result<SubgraphEntry, SupergraphConfigEditError> {
+        self.find_subgraph(subgraph_name)?.ok_or_else(|| {
+            SupergraphConfigEditError::SubgraphNotFound {
+                subgraph_name: subgraph_name.to_string(),
+            }
+        })
+    }
+
+    /// Makes sure the value of the key on `line` is a block mapping spread over the lines below it
+    fn require_block_mapping(&self, line: usize) -> Result<(), SupergraphConfigEditError> {
+        match key_at(&self.lines[line]) {
+            Some((indent, key, value_offset))
+                if !strip_comment(&self.lines[line][indent + value_offset..]).is_empty() =>
+            {
+                Err(SupergraphConfigEditError::UnsupportedStyle {
+                    key,
+                    line: line + 1,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Renders a subgraph as a YAML mapping of its name to its config, leaving out empty values
+fn render_subgraph(
+    subgraph_name: &str,
+    subgraph_config: &SubgraphConfig,
+) -> Result<String, SupergraphConfigEditError> {
+    let mut config = serde_yaml::to_value(subgraph_config).map_err(invalid_yaml)?;
+    remove_nulls(&mut config);
+    let mut mapping = serde_yaml::Mapping::new();
+    mapping.insert(Value::String(subgraph_name.to_string()), config);
+    serde_yaml::to_string(&mapping).map_err(invalid_yaml)
+}
+
+fn render_scalar(value: &str) -> Result<String, SupergraphConfigEditError> {
+    serde_yaml::to_string(value)
+        .map(|rendered| rendered.trim_end().to_string())
+        .map_err(invalid_yaml)
+}
+
+fn remove_nulls(value: &mut Value) {
+    if let Value::Mapping(mapping) = value {
+        mapping.retain(|_, value| !value.is_null());
+        mapping.values_mut().for_each(remove_nulls);
+    }
+}
+
+fn invalid_yaml(err: serde_yaml::Error) -> SupergraphConfigEditError {
+    SupergraphConfigEditError::InvalidYaml {
+        reason: err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apollo_federation_types::config::{SchemaSource, SubgraphConfig};
+    use indoc::indoc;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::{SupergraphConfigEditError, SupergraphConfigEditor};
+
+    const SUPERGRAPH_CONFIG: &str = indoc! {r#"
+        # shared by the whole team
+        federation_version: =2.9.0
+        subgraphs:
+          # the product catalog
+          products:
+            routing_url: http://localhost:4001 # local only
+            schema:
+              file: ./products.graphql
+
+          reviews:
+            schema: { subgraph_url: http://localhost:4002 }
+    "#};
+
+    fn edit(
+        contents: &str,
+        edit: impl FnOnce(&mut SupergraphConfigEditor) -> Result<(), SupergraphConfigEditError>,
+    ) -> Result<String, SupergraphConfigEditError> {
+        let mut editor = SupergraphConfigEditor::new(contents);
+        edit(&mut editor)?;
+        editor.finish()
+    }
+
+    #[test]
+    fn test_add_subgraph_keeps_comments() {
+        let edited = edit(SUPERGRAPH_CONFIG, |editor| {
+            editor.add_subgraph(
+                "inventory",
+                &SubgraphConfig {
+                    routing_url: Some("http://localhost:4003".to_string()),
+                    schema: SchemaSource::File {
+                        file: "./inventory.graphql".into(),
+                    },
+                },
+            )
+        });
+        assert_that!(edited).is_ok().is_equal_to(format!(
+            "{}{}",
+            SUPERGRAPH_CONFIG,
+            concat!(
+                "  inventory:\n",
+                "    routing_url: http://localhost:4003\n",
+                "    schema:\n",
+                "      file: ./inventory.graphql\n",
+            )
+        ));
+    }
+
+    #[test]
+    fn test_add_subgraph_without_subgraphs_section() {
+        let edited = edit("federation_version: =2.9.0\n", |editor| {
+            editor.add_subgraph(
+                "products",
+                &SubgraphConfig {
+                    routing_url: None,
+                    schema: SchemaSource::SubgraphIntrospection {
+                        subgraph_url: "http://localhost:4001/".parse().unwrap(),
+                        introspection_headers: None,
+                    },
+                },
+            )
+        });
+        assert_that!(edited).is_ok().is_equal_to(
+            indoc! {r#"
+                federation_version: =2.9.0
+
+                subgraphs:
+                  products:
+                    schema:
+                      subgraph_url: http://localhost:4001/
+            "#}
+            .to_string(),
+        );
+    }
+
+    #[test]
+    fn test_add_existing_subgraph() {
+        let edited = edit(SUPERGRAPH_CONFIG, |editor| {
+            editor.add_subgraph(
+                "reviews",
+                &SubgraphConfig {
+                    routing_url: None,
+                    schema: SchemaSource::Sdl {
+                        sdl: "type Query { a: String }".to_string(),
+                    },
+                },
+            )
+        });
+        assert_that!(edited)
+            .is_err()
+            .is_equal_to(SupergraphConfigEditError::SubgraphExists {
+                subgraph_name: "reviews".to_string(),
+            });
+    }
+
+    #[test]
+    fn test_remove_subgraph_with_its_comment() {
+        let edited = edit(SUPERGRAPH_CONFIG, |editor| {
+            editor.remove_subgraph("products")
+        });
+        assert_that!(edited).is_ok().is_equal_to(
+            indoc! {r#"
+                # shared by the whole team
+                federation_version: =2.9.0
+                subgraphs:
+
+                  reviews:
+                    schema: { subgraph_url: http://localhost:4002 }
+            "#}
+            .to_string(),
+        );
+    }
+
+    #[rstest]
+    #[case::replaces_existing(
+        "products",
+        "  products:\n    routing_url: https://products.example.com # local only\n"
+    )]
+    #[case::adds_missing(
+        "reviews",
+        "  reviews:\n    routing_url: https://products.example.com\n"
+    )]
+    fn test_set_routing_url(#[case] subgraph_name: &str, #[case] expected: &str) {
+        let edited = edit(SUPERGRAPH_CONFIG, |editor| {
+            editor.set_routing_url(subgraph_name, "https://products.example.com")
+        })
+        .unwrap();
+        assert_that!(edited).contains(expected);
+    }
+
+    #[rstest]
+    #[case::missing_subgraph(
+        "subgraphs:\n  products:\n    schema:\n      file: ./products.graphql\n",
+        "inventory",
+        SupergraphConfigEditError::SubgraphNotFound { subgraph_name: "inventory".to_string() }
+    )]
+    #[case::flow_subgraph(
+        "subgraphs:\n  products: { schema: { file: ./products.graphql } }\n",
+        "products",
+        SupergraphConfigEditError::UnsupportedStyle { key: "products".to_string(), line: 2 }
+    )]
+    #[case::flow_section(
+        "subgraphs: {}\n",
+        "products",
+        SupergraphConfigEditError::UnsupportedStyle { key: "subgraphs".to_string(), line: 1 }
+    )]
+    fn test_set_routing_url_errors(
+        #[case] contents: &str,
+        #[case] subgraph_name: &str,
+        #[case] expected: SupergraphConfigEditError,
+    ) {
+        let edited = edit(contents, |editor| {
+            editor.set_routing_url(subgraph_name, "http://localhost:4001")
+        });
+        assert_that!(edited).is_err().is_equal_to(expected);
+    }
+}
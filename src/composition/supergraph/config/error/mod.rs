@@ -1,4 +1,6 @@
 //! This module errors related to resolving SupergraphConfigs that are also shared across multiple submodules of [`rover::composition::supergraph::config`]
 
+mod report;
 mod subgraph;
+pub use report::*;
 pub use subgraph::*;
@@ -0,0 +1,161 @@
+use std::{collections::BTreeMap, error::Error, fmt};
+
+use serde_json::{json, Value};
+
+use super::ResolveSubgraphError;
+
+/// Groups the errors from resolving a supergraph config's subgraphs by the subgraph they relate
+/// to, so they can be reported together as human readable text or as JSON
+#[derive(Debug)]
+pub struct ResolveSubgraphErrorReport<'a> {
+    subgraphs: BTreeMap<&'a str, Vec<&'a ResolveSubgraphError>>,
+    other: Vec<&'a ResolveSubgraphError>,
+}
+
+impl<'a> ResolveSubgraphErrorReport<'a> {
+    /// Groups `errors` by subgraph, keeping errors that don't relate to a subgraph apart
+    pub fn new(errors: &'a [ResolveSubgraphError]) -> ResolveSubgraphErrorReport<'a> {
+        let mut subgraphs: BTreeMap<&str, Vec<&ResolveSubgraphError>> = BTreeMap::new();
+        let mut other = Vec::new();
+        for error in errors {
+            match error.subgraph_name() {
+                Some(subgraph_name) => subgraphs.entry(subgraph_name).or_default().push(error),
+                None => other.push(error),
+            }
+        }
+        ResolveSubgraphErrorReport { subgraphs, other }
+    }
+
+    /// Renders the report as JSON, with the errors of each subgraph listed under its name
+    pub fn get_json(&self) -> Value {
+        let subgraphs: BTreeMap<&str, Vec<Value>> = self
+            .subgraphs
+            .iter()
+            .map(|(subgraph_name, errors)| {
+                (
+                    *subgraph_name,
+                    errors.iter().map(|e| error_json(e)).collect(),
+                )
+            })
+            .collect();
+        let other: Vec<Value> = self.other.iter().map(|e| error_json(e)).collect();
+        json!({ "subgraphs": subgraphs, "other": other })
+    }
+}
+
+impl fmt::Display for ResolveSubgraphErrorReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.subgraphs.len() {
+            0 => write!(f, "Unable to resolve subgraphs:")?,
+            1 => write!(f, "Unable to resolve 1 subgraph:")?,
+            n => write!(f, "Unable to resolve {} subgraphs:", n)?,
+        }
+        for (subgraph_name, errors) in &self.subgraphs {
+            write!(f, "\n  {}:", subgraph_name)?;
+            for error in errors {
+                write_error(f, error, "    ")?;
+            }
+        }
+        for error in &self.other {
+            write_error(f, error, "  ")?;
+        }
+        Ok(())
+    }
+}
+
+fn write_error(
+    f: &mut fmt::Formatter<'_>,
+    error: &ResolveSubgraphError,
+    indent: &str,
+) -> fmt::Result {
+    write!(f, "\n{}- {}", indent, message(error.unlocated()))?;
+    if let Some(location) = error.location() {
+        write!(f, " (at {})", location)?;
+    }
+    if let Some(suggestion) = error.suggestion() {
+        write!(f, "\n{}  {}", indent, suggestion)?;
+    }
+    Ok(())
+}
+
+fn error_json(error: &ResolveSubgraphError) -> Value {
+    json!({
+        "code": error.code(),
+        "message": message(error.unlocated()),
+        "location": error.location().map(|location| location.to_string()),
+        "suggestion": error.suggestion(),
+    })
+}
+
+/// The error's message along with its cause, for the variants that don't already include it
+fn message(error: &ResolveSubgraphError) -> String {
+    let cause = match error {
+        ResolveSubgraphError::FileNotFound { .. }
+        | ResolveSubgraphError::IntrospectionError { .. }
+        | ResolveSubgraphError::InvalidGraphRef { .. }
+        | ResolveSubgraphError::FetchRemoteSdlError { .. } => error.source(),
+        _ => None,
+    };
+    match cause {
+        Some(cause) => format!("{}: {}", error.to_string().trim_end_matches('.'), cause),
+        None => error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8PathBuf;
+    use serde_json::json;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    fn errors() -> Vec<ResolveSubgraphError> {
+        vec![
+            ResolveSubgraphError::InvalidGraphRef {
+                subgraph_name: "reviews".to_string(),
+                graph_ref: "my graph".to_string(),
+                source: "invalid characters".into(),
+            },
+            ResolveSubgraphError::FileNotFound {
+                subgraph_name: "products".to_string(),
+                supergraph_config_path: Utf8PathBuf::from("/supergraph"),
+                path: "products.graphql".into(),
+                source: std::io::Error::from(std::io::ErrorKind::NotFound),
+            },
+            ResolveSubgraphError::SupergraphConfigMissing,
+        ]
+    }
+
+    #[test]
+    fn test_report_groups_errors_by_subgraph() {
+        let errors = errors();
+        let report = ResolveSubgraphErrorReport::new(&errors);
+        assert_that!(report.to_string()).is_equal_to(
+            "Unable to resolve 2 subgraphs:
+  products:
+    - Could not find schema file (products.graphql) relative to (/supergraph) for subgraph `products`: entity not found
+      Check that the schema file exists and that its path is relative to the supergraph config.
+  reviews:
+    - Invalid graph ref: my graph: invalid characters
+      Graph refs are formatted as `graph@variant`, where the variant is optional and defaults to `current`.
+  - Failed to find the supergraph config, which is required when resolving schemas in a file relative to a supergraph config
+    Pass a supergraph config file to resolve `file` schemas relative to it."
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn test_report_json() {
+        let errors = errors();
+        let report = ResolveSubgraphErrorReport::new(&errors);
+        assert_that!(report.get_json()["subgraphs"]["reviews"]).is_equal_to(json!([{
+            "code": "INVALID_GRAPH_REF",
+            "message": "Invalid graph ref: my graph: invalid characters",
+            "location": null,
+            "suggestion": "Graph refs are formatted as `graph@variant`, where the variant is optional and defaults to `current`.",
+        }]));
+        assert_that!(report.get_json()["other"][0]["code"])
+            .is_equal_to(json!("SUPERGRAPH_CONFIG_MISSING"));
+    }
+}
@@ -2,6 +2,13 @@ use std::path::PathBuf;
 
 use camino::Utf8PathBuf;
 
+use crate::{
+    composition::supergraph::config::source_map::{
+        SourceLocation, SubgraphConfigKey, SupergraphConfigSourceMap,
+    },
+    utils::effect::introspect::RoverIntrospectSubgraphError,
+};
+
 /// Errors that may occur as a result of resolving subgraphs
 #[derive(thiserror::Error, Debug)]
 pub enum ResolveSubgraphError {
@@ -32,9 +39,19 @@ pub enum ResolveSubgraphError {
         /// The source error
         source: Box<dyn std::error::Error + Send + Sync>,
     },
+    /// Occurs when downloading a subgraph's schema from its `url` fails
+    #[error("Failed to download the schema of the subgraph {subgraph_name}.")]
+    DownloadError {
+        /// The subgraph name that failed to be resolved
+        subgraph_name: String,
+        /// The source error
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
     /// Occurs when a supplied graph ref cannot be parsed
     #[error("Invalid graph ref: {graph_ref}")]
     InvalidGraphRef {
+        /// The subgraph name that failed to be resolved
+        subgraph_name: String,
         /// The supplied graph ref
         graph_ref: String,
         /// The source error
@@ -48,7 +65,183 @@ pub enum ResolveSubgraphError {
         /// The source error
         source: Box<dyn std::error::Error + Send + Sync>,
     },
+    /// Occurs when a subgraph's schema comes from introspection while resolving offline
+    #[error("Can't introspect the subgraph `{subgraph_name}` while offline")]
+    IntrospectionOffline {
+        /// The subgraph name that failed to be resolved
+        subgraph_name: String,
+    },
+    /// Occurs when a subgraph's schema is downloaded from a URL while resolving offline
+    #[error("Can't download the schema of the subgraph `{subgraph_name}` while offline")]
+    DownloadOffline {
+        /// The subgraph name that failed to be resolved
+        subgraph_name: String,
+    },
+    /// Occurs when a subgraph's schema comes from GraphOS while resolving offline
+    #[error("Can't fetch the sdl for subgraph `{subgraph_name}` from remote while offline")]
+    FetchRemoteSdlOffline {
+        /// The subgraph name that failed to be resolved
+        subgraph_name: String,
+    },
     /// Occurs when a supergraph config filepath waqs expected but not found
     #[error("Failed to find the supergraph config, which is required when resolving schemas in a file relative to a supergraph config")]
     SupergraphConfigMissing,
+    /// Wraps another error with the position in the supergraph config that it originated from
+    #[error("{source} (at {location})")]
+    Located {
+        /// The position in the supergraph config
+        location: SourceLocation,
+        /// The source error
+        source: Box<ResolveSubgraphError>,
+    },
+}
+
+impl ResolveSubgraphError {
+    /// The name of the subgraph this error relates to, if it relates to a specific subgraph
+    pub fn subgraph_name(&self) -> Option<&str> {
+        match self {
+            ResolveSubgraphError::FileNotFound { subgraph_name, .. }
+            | ResolveSubgraphError::IntrospectionError { subgraph_name, .. }
+            | ResolveSubgraphError::DownloadError { subgraph_name, .. }
+            | ResolveSubgraphError::InvalidGraphRef { subgraph_name, .. }
+            | ResolveSubgraphError::FetchRemoteSdlError { subgraph_name, .. }
+            | ResolveSubgraphError::IntrospectionOffline { subgraph_name }
+            | ResolveSubgraphError::DownloadOffline { subgraph_name }
+            | ResolveSubgraphError::FetchRemoteSdlOffline { subgraph_name } => Some(subgraph_name),
+            ResolveSubgraphError::Located { source, .. } => source.subgraph_name(),
+            ResolveSubgraphError::Io(_)
+            | ResolveSubgraphError::Fs(_)
+            | ResolveSubgraphError::SupergraphConfigMissing => None,
+        }
+    }
+
+    /// The key of the subgraph's config entry that caused this error, if known
+    pub fn config_key(&self) -> Option<SubgraphConfigKey> {
+        match self {
+            ResolveSubgraphError::FileNotFound { .. } => Some(SubgraphConfigKey::SchemaFile),
+            ResolveSubgraphError::IntrospectionError { .. }
+            | ResolveSubgraphError::IntrospectionOffline { .. } => {
+                Some(SubgraphConfigKey::SchemaSubgraphUrl)
+            }
+            ResolveSubgraphError::DownloadError { .. }
+            | ResolveSubgraphError::DownloadOffline { .. } => Some(SubgraphConfigKey::SchemaUrl),
+            ResolveSubgraphError::InvalidGraphRef { .. }
+            | ResolveSubgraphError::FetchRemoteSdlOffline { .. } => {
+                Some(SubgraphConfigKey::SchemaGraphRef)
+            }
+            ResolveSubgraphError::FetchRemoteSdlError { .. } => {
+                Some(SubgraphConfigKey::SchemaSubgraph)
+            }
+            ResolveSubgraphError::Located { source, .. } => source.config_key(),
+            ResolveSubgraphError::Io(_)
+            | ResolveSubgraphError::Fs(_)
+            | ResolveSubgraphError::SupergraphConfigMissing => None,
+        }
+    }
+
+    /// A stable, machine-readable identifier for the kind of this error
+    pub fn code(&self) -> &'static str {
+        match self {
+            ResolveSubgraphError::FileNotFound { .. } => "FILE_NOT_FOUND",
+            ResolveSubgraphError::Io(_) | ResolveSubgraphError::Fs(_) => "IO",
+            ResolveSubgraphError::IntrospectionError { .. } => "INTROSPECTION_FAILED",
+            ResolveSubgraphError::DownloadError { .. } => "DOWNLOAD_FAILED",
+            ResolveSubgraphError::InvalidGraphRef { .. } => "INVALID_GRAPH_REF",
+            ResolveSubgraphError::FetchRemoteSdlError { .. } => "FETCH_REMOTE_SDL_FAILED",
+            ResolveSubgraphError::IntrospectionOffline { .. } => "INTROSPECTION_OFFLINE",
+            ResolveSubgraphError::DownloadOffline { .. } => "DOWNLOAD_OFFLINE",
+            ResolveSubgraphError::FetchRemoteSdlOffline { .. } => "FETCH_REMOTE_SDL_OFFLINE",
+            ResolveSubgraphError::SupergraphConfigMissing => "SUPERGRAPH_CONFIG_MISSING",
+            ResolveSubgraphError::Located { source, .. } => source.code(),
+        }
+    }
+
+    /// What the user can do about this error, if there's anything more specific to say than the
+    /// error itself
+    pub fn suggestion(&self) -> Option<&'static str> {
+        match self {
+            ResolveSubgraphError::FileNotFound { .. } => Some(
+                "Check that the schema file exists and that its path is relative to the supergraph config.",
+            ),
+            ResolveSubgraphError::IntrospectionError { .. } => Some(
+                "Make sure the subgraph is running and reachable at its `subgraph_url`, and that any `introspection_headers` it needs are set.",
+            ),
+            ResolveSubgraphError::DownloadError { .. } => Some(
+                "Make sure the schema's `url` serves it as raw SDL, and that any `headers` it needs are set.",
+            ),
+            ResolveSubgraphError::InvalidGraphRef { .. } => Some(
+                "Graph refs are formatted as `graph@variant`, where the variant is optional and defaults to `current`.",
+            ),
+            ResolveSubgraphError::FetchRemoteSdlError { .. } => Some(
+                "Check that the subgraph is published to the variant in `graphref`, and that your API key can read that graph.",
+            ),
+            ResolveSubgraphError::IntrospectionOffline { .. }
+            | ResolveSubgraphError::DownloadOffline { .. }
+            | ResolveSubgraphError::FetchRemoteSdlOffline { .. } => Some(
+                "Point the subgraph's `schema` at a local `file` or `sdl`, or run without `--offline`.",
+            ),
+            ResolveSubgraphError::SupergraphConfigMissing => Some(
+                "Pass a supergraph config file to resolve `file` schemas relative to it.",
+            ),
+            ResolveSubgraphError::Located { source, .. } => source.suggestion(),
+            ResolveSubgraphError::Io(_) | ResolveSubgraphError::Fs(_) => None,
+        }
+    }
+
+    /// This error without the position in the supergraph config that may have been attached to it
+    pub fn unlocated(&self) -> &ResolveSubgraphError {
+        match self {
+            ResolveSubgraphError::Located { source, .. } => source.unlocated(),
+            _ => self,
+        }
+    }
+
+    /// Whether this error came from reaching a subgraph or GraphOS over the network, such that
+    /// trying again may succeed
+    pub fn is_transient(&self) -> bool {
+        match self {
+            // an introspection response that isn't a valid result won't get any better for asking
+            // again
+            ResolveSubgraphError::IntrospectionError { source, .. }
+            | ResolveSubgraphError::DownloadError { source, .. } => source
+                .downcast_ref::<RoverIntrospectSubgraphError>()
+                .map_or(true, RoverIntrospectSubgraphError::is_retryable),
+            ResolveSubgraphError::FetchRemoteSdlError { .. } => true,
+            ResolveSubgraphError::Located { source, .. } => source.is_transient(),
+            ResolveSubgraphError::FileNotFound { .. }
+            | ResolveSubgraphError::Io(_)
+            | ResolveSubgraphError::Fs(_)
+            | ResolveSubgraphError::InvalidGraphRef { .. }
+            | ResolveSubgraphError::IntrospectionOffline { .. }
+            | ResolveSubgraphError::DownloadOffline { .. }
+            | ResolveSubgraphError::FetchRemoteSdlOffline { .. }
+            | ResolveSubgraphError::SupergraphConfigMissing => false,
+        }
+    }
+
+    /// The position in the supergraph config this error originated from, if known
+    pub fn location(&self) -> Option<&SourceLocation> {
+        match self {
+            ResolveSubgraphError::Located { location, .. } => Some(location),
+            _ => None,
+        }
+    }
+
+    /// Attaches the position of the offending key in the supergraph config to this error, when
+    /// it can be found in the given [`SupergraphConfigSourceMap`]
+    pub fn locate(self, source_map: &SupergraphConfigSourceMap) -> ResolveSubgraphError {
+        if self.location().is_some() {
+            return self;
+        }
+        let location = self
+            .subgraph_name()
+            .and_then(|subgraph_name| source_map.locate_subgraph(subgraph_name, self.config_key()));
+        match location {
+            Some(location) => ResolveSubgraphError::Located {
+                location,
+                source: Box::new(self),
+            },
+            None => self,
+        }
+    }
 }
@@ -0,0 +1,811 @@
+//! A supergraph config model that's a superset of [`SupergraphConfig`] from
+//! `apollo_federation_types`. It understands the settings Rover supports on top of the upstream
+//! format, like a subgraph's `dev` settings and `glob`, `url` or `git` schema sources, and keeps any other keys it
+//! doesn't know about, so a config can be read, changed, and written back without losing anything.
+//! Before a config is handed to the supergraph binary, it's down-converted to the upstream type
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    process::Command,
+};
+
+use apollo_federation_types::config::{
+    ConfigError, ConfigResult, FederationVersion, SchemaSource, SubgraphConfig, SupergraphConfig,
+};
+use camino::{Utf8Path, Utf8PathBuf};
+use reqwest::{
+    header::{ETAG, IF_NONE_MATCH},
+    Client, StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Mapping;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+/// A supergraph config, including the settings `apollo_federation_types` can't represent
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct ExtendedSupergraphConfig {
+    /// The subgraphs in the supergraph, by name
+    #[serde(default)]
+    pub subgraphs: BTreeMap<String, ExtendedSubgraphConfig>,
+    /// The version of federation to compose with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub federation_version: Option<FederationVersion>,
+    /// Every other top-level key, such as `include`, kept as written
+    #[serde(flatten)]
+    pub other: Mapping,
+}
+
+/// A subgraph in an [`ExtendedSupergraphConfig`]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ExtendedSubgraphConfig {
+    /// The URL the router sends requests for this subgraph to, or one per environment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing_url: Option<ExtendedRoutingUrl>,
+    /// Where the subgraph's schema comes from
+    pub schema: ExtendedSchemaSource,
+    /// Settings that only apply while running the subgraph with `rover dev`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dev: Option<SubgraphDevConfig>,
+    /// The subgraph's endpoints in each named environment, picked with `--env`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environments: BTreeMap<String, SubgraphEnvironment>,
+    /// Every other key, such as `introspection`, kept as written
+    #[serde(flatten)]
+    pub other: Mapping,
+}
+
+/// Where the router sends requests for a subgraph
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ExtendedRoutingUrl {
+    /// The same URL in every environment
+    Url(String),
+    /// A URL per environment, picked with `--env`, like
+    /// `routing_url: { dev: http://localhost:4001, prod: https://users.example.com }`. The URL
+    /// under `default` is used for environments that aren't listed, and when none is picked
+    PerEnvironment(BTreeMap<String, String>),
+}
+
+/// The environment whose URL is used when no other one applies
+const DEFAULT_ENVIRONMENT: &str = "default";
+
+impl ExtendedRoutingUrl {
+    /// The routing URL of `subgraph_name` in `environment`
+    pub fn resolve(&self, subgraph_name: &str, environment: Option<&str>) -> ConfigResult<String> {
+        let urls = match self {
+            ExtendedRoutingUrl::Url(url) => return Ok(url.to_string()),
+            ExtendedRoutingUrl::PerEnvironment(urls) => urls,
+        };
+        environment
+            .and_then(|environment| urls.get(environment))
+            .or_else(|| urls.get(DEFAULT_ENVIRONMENT))
+            .cloned()
+            .ok_or_else(|| {
+                let expected = urls.keys().cloned().collect::<Vec<_>>().join(", ");
+                let message = match environment {
+                    Some(environment) => format!(
+                        "the routing_url of subgraph `{subgraph_name}` has no URL for environment `{environment}`, and no `{DEFAULT_ENVIRONMENT}` one. Expected one of: {expected}"
+                    ),
+                    None => format!(
+                        "the routing_url of subgraph `{subgraph_name}` has a URL per environment, but no environment was picked. Pass `--env` with one of: {expected}"
+                    ),
+                };
+                ConfigError::InvalidConfiguration { message }
+            })
+    }
+
+    fn environments(&self) -> impl Iterator<Item = &String> {
+        match self {
+            ExtendedRoutingUrl::Url(_) => None,
+            ExtendedRoutingUrl::PerEnvironment(urls) => Some(urls.keys()),
+        }
+        .into_iter()
+        .flatten()
+    }
+}
+
+/// Where a subgraph runs in one environment, so that a single config can compose against local
+/// subgraphs in `dev` and deployed ones in `staging` or `prod`
+///
+/// ```yaml
+/// subgraphs:
+///   products:
+///     routing_url: http://localhost:4001
+///     schema:
+///       file: ./products.graphql
+///     environments:
+///       prod:
+///         routing_url: https://products.example.com
+///         subgraph_url: https://products.example.com/graphql
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SubgraphEnvironment {
+    /// The URL the router sends requests for the subgraph to in this environment, in place of
+    /// the subgraph's `routing_url`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing_url: Option<String>,
+    /// The endpoint to introspect the subgraph's schema from in this environment, in place of the
+    /// subgraph's `schema`. Introspection headers of the subgraph's schema are kept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subgraph_url: Option<Url>,
+}
+
+/// Where a subgraph's schema comes from, which is any upstream [`SchemaSource`], a glob, a URL or
+/// a git repository
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ExtendedSchemaSource {
+    /// A schema split across several files, matched by a glob relative to the supergraph config,
+    /// like `schema: { glob: ./products/**/*.graphql }`
+    Glob {
+        /// The pattern matching every file that's part of the schema
+        glob: String,
+    },
+    /// A schema downloaded as raw SDL, like `schema: { url: https://example.com/products.graphql }`
+    Url(SchemaUrl),
+    /// A schema file in a git repository, like
+    /// `schema: { git: https://github.com/example/products, path: schema.graphql, rev: main }`
+    Git(SchemaGit),
+    /// A schema source that `apollo_federation_types` understands
+    Upstream(SchemaSource),
+}
+
+/// A URL serving a subgraph's schema as raw SDL, rather than answering introspection queries,
+/// like an artifact store or a raw file on GitHub
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SchemaUrl {
+    /// The URL to download the schema from
+    pub url: String,
+    /// Headers to send with every request for the schema, like an `Authorization` header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub headers: Option<BTreeMap<String, String>>,
+}
+
+impl SchemaUrl {
+    /// Downloads the schema along with its `ETag`, or nothing when it hasn't changed since `etag`
+    pub async fn download(
+        &self,
+        client: &Client,
+        etag: Option<&str>,
+    ) -> Result<Option<(Option<String>, String)>, reqwest::Error> {
+        let mut request = client.get(&self.url);
+        for (name, value) in self.headers.iter().flatten() {
+            request = request.header(name, value);
+        }
+        if let Some(etag) = etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = request.send().await?.error_for_status()?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(str::to_string);
+        Ok(Some((etag, response.text().await?)))
+    }
+
+    /// The schema source that stands in for this URL until the schema is downloaded. The upstream
+    /// [`SchemaSource`] has no way to say "raw SDL at a URL", so it's written as the subgraph URL
+    /// it's fetched from, and the [`SchemaUrl`] kept in the subgraph's introspection settings
+    /// tells resolution and watchers to download it rather than introspect it
+    fn into_schema_source(self, subgraph_name: &str) -> ConfigResult<SchemaSource> {
+        let subgraph_url =
+            Url::parse(&self.url).map_err(|e| ConfigError::InvalidConfiguration {
+                message: format!(
+                    "the schema URL `{}` of subgraph `{subgraph_name}` is invalid: {e}",
+                    self.url
+                ),
+            })?;
+        Ok(SchemaSource::SubgraphIntrospection {
+            subgraph_url,
+            introspection_headers: self.headers.map(|headers| headers.into_iter().collect()),
+        })
+    }
+}
+
+/// A schema file in a git repository, read from a shallow fetch kept in Rover's cache, so that
+/// developers don't need a checkout of every subgraph's repository
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SchemaGit {
+    /// The repository to fetch, as any URL that `git fetch` accepts
+    pub git: String,
+    /// The path of the schema file within the repository
+    pub path: Utf8PathBuf,
+    /// The branch, tag or commit to read the schema from, or the default branch when there isn't
+    /// one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+}
+
+impl SchemaGit {
+    /// Fetches the latest commit of `rev` and reads the schema of `subgraph_name` from it,
+    /// returning the commit along with the schema. This runs `git`, so the repository can be
+    /// fetched with whatever credentials `git` is set up with
+    pub fn fetch(&self, subgraph_name: &str) -> ConfigResult<(String, String)> {
+        let invalid = |message: String| {
+            ConfigError::InvalidConfiguration {
+            message: format!(
+                "the schema of subgraph `{subgraph_name}` could not be read from `{}` in `{}`: {message}",
+                self.path, self.git
+            ),
+        }
+        };
+        let rev = self.rev.as_deref().unwrap_or("HEAD");
+        // `git` would take these as options, like `--upload-pack` running a command of the config's
+        // choosing, rather than as the repository and revision to fetch
+        for (key, value) in [("git", self.git.as_str()), ("rev", rev)] {
+            if value.starts_with('-') {
+                return Err(invalid(format!("`{key}` can't start with `-`")));
+            }
+        }
+        let home = std::env::var("APOLLO_HOME")
+            .ok()
+            .map(Utf8PathBuf::from)
+            .or_else(|| {
+                directories_next::BaseDirs::new()
+                    .and_then(|dirs| Utf8PathBuf::from_path_buf(dirs.home_dir().to_path_buf()).ok())
+            })
+            .ok_or_else(|| invalid("there's no home directory to cache it in".to_string()))?;
+        // every repository and revision gets a cache of its own, so that fetching one doesn't
+        // move the `FETCH_HEAD` of another that's being read
+        let cache_dir = home.join(".rover/cache/git").join(format!(
+            "{:x}",
+            Sha256::digest(format!("{}#{rev}", self.git))
+        ));
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(&cache_dir)
+                .args(args)
+                .output()
+                .map_err(|e| invalid(format!("`git` could not be run: {e}")))?;
+            if output.status.success() {
+                Ok(String::from_utf8_lossy(&output.stdout).to_string())
+            } else {
+                Err(invalid(
+                    String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                ))
+            }
+        };
+        if !cache_dir.join("HEAD").exists() {
+            std::fs::create_dir_all(&cache_dir).map_err(|e| invalid(e.to_string()))?;
+            git(&["init", "--bare", "--quiet"])?;
+        }
+        git(&[
+            "fetch",
+            "--quiet",
+            "--depth",
+            "1",
+            "--end-of-options",
+            &self.git,
+            rev,
+        ])?;
+        let commit = git(&["rev-parse", "FETCH_HEAD"])?.trim().to_string();
+        let sdl = git(&["show", &format!("{commit}:{}", self.path)])?;
+        Ok((commit, sdl))
+    }
+}
+
+/// Settings for a subgraph that only apply while running it with `rover dev`
+///
+/// ```yaml
+/// subgraphs:
+///   products:
+///     routing_url: http://localhost:4001
+///     schema:
+///       file: ./products.graphql
+///     dev:
+///       command: npm run start:products
+///       mocks: false
+///       tunnel: false
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct SubgraphDevConfig {
+    /// A command that starts the subgraph
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Whether to serve mocked responses from the subgraph's schema instead of running it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mocks: Option<bool>,
+    /// Whether to expose the subgraph through a tunnel
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tunnel: Option<bool>,
+    /// Every other key, kept as written
+    #[serde(flatten)]
+    pub other: Mapping,
+}
+
+impl ExtendedSupergraphConfig {
+    /// Parses a supergraph config from YAML
+    pub fn new_from_yaml(yaml: &str) -> ConfigResult<ExtendedSupergraphConfig> {
+        serde_yaml::from_str(yaml).map_err(|e| ConfigError::InvalidConfiguration {
+            message: e.to_string(),
+        })
+    }
+
+    /// Writes the supergraph config back out as YAML, including any keys it didn't recognize
+    pub fn to_yaml(&self) -> ConfigResult<String> {
+        serde_yaml::to_string(self).map_err(|e| ConfigError::InvalidConfiguration {
+            message: e.to_string(),
+        })
+    }
+
+    /// Converts to the upstream [`SupergraphConfig`], dropping the settings it can't represent.
+    /// Glob schema sources are resolved against `base_dir`, and the files they match are read
+    /// and joined into a single inline SDL schema source. Git schema sources are fetched into an
+    /// inline SDL schema source too, while URL schema sources are left to be downloaded when the
+    /// subgraphs are resolved, with the client and offline settings Rover runs with. Routing URLs given per environment, and the
+    /// endpoints under a subgraph's `environments`, are resolved for `environment`, which has to
+    /// be one that at least one of them lists
+    pub fn into_supergraph_config(
+        self,
+        base_dir: &Utf8Path,
+        environment: Option<&str>,
+    ) -> ConfigResult<SupergraphConfig> {
+        if let Some(environment) = environment {
+            self.check_environment(environment)?;
+        }
+        let subgraphs = self
+            .subgraphs
+            .into_iter()
+            .map(|(name, mut subgraph)| {
+                let endpoints = environment
+                    .and_then(|environment| subgraph.environments.remove(environment))
+                    .unwrap_or_default();
+                if let Some(subgraph_url) = endpoints.subgraph_url {
+                    let introspection_headers = match subgraph.schema {
+                        ExtendedSchemaSource::Upstream(SchemaSource::SubgraphIntrospection {
+                            introspection_headers,
+                            ..
+                        }) => introspection_headers,
+                        _ => None,
+                    };
+                    subgraph.schema =
+                        ExtendedSchemaSource::Upstream(SchemaSource::SubgraphIntrospection {
+                            subgraph_url,
+                            introspection_headers,
+                        });
+                }
+                let schema = match subgraph.schema {
+                    ExtendedSchemaSource::Upstream(schema) => schema,
+                    ExtendedSchemaSource::Glob { glob } => SchemaSource::Sdl {
+                        sdl: read_glob(&name, &glob, base_dir)?,
+                    },
+                    ExtendedSchemaSource::Url(schema_url) => {
+                        schema_url.into_schema_source(&name)?
+                    }
+                    ExtendedSchemaSource::Git(schema_git) => SchemaSource::Sdl {
+                        sdl: schema_git.fetch(&name)?.1,
+                    },
+                };
+                let routing_url = match endpoints.routing_url {
+                    Some(routing_url) => Some(routing_url),
+                    None => subgraph
+                        .routing_url
+                        .map(|routing_url| routing_url.resolve(&name, environment))
+                        .transpose()?,
+                };
+                Ok((
+                    name,
+                    SubgraphConfig {
+                        routing_url,
+                        schema,
+                    },
+                ))
+            })
+            .collect::<ConfigResult<BTreeMap<_, _>>>()?;
+        Ok(SupergraphConfig::new(subgraphs, self.federation_version))
+    }
+
+    /// Makes sure a picked environment is one the routing URLs or subgraph environments know
+    /// about, so that a typo isn't quietly covered up by `default` URLs. Configs without any
+    /// environments work in every environment
+    fn check_environment(&self, environment: &str) -> ConfigResult<()> {
+        let environments = self
+            .subgraphs
+            .values()
+            .flat_map(|subgraph| {
+                subgraph
+                    .routing_url
+                    .iter()
+                    .flat_map(ExtendedRoutingUrl::environments)
+                    .chain(subgraph.environments.keys())
+            })
+            .map(String::as_str)
+            .filter(|name| *name != DEFAULT_ENVIRONMENT)
+            .collect::<BTreeSet<_>>();
+        if environments.is_empty() || environments.contains(&environment) {
+            Ok(())
+        } else {
+            Err(ConfigError::InvalidConfiguration {
+                message: format!(
+                    "environment `{environment}` isn't one that any routing_url or subgraph `environments` lists. Expected one of: {}",
+                    environments.into_iter().collect::<Vec<_>>().join(", ")
+                ),
+            })
+        }
+    }
+}
+
+/// Parses a supergraph config read from `origin_path`, or from stdin when there isn't one, and
+/// down-converts it to the upstream [`SupergraphConfig`] for `environment`. Globs are resolved
+/// against the directory the config is in, or the current directory for stdin
+pub fn parse_supergraph_config(
+    contents: &str,
+    origin_path: Option<&Utf8Path>,
+    environment: Option<&str>,
+) -> ConfigResult<SupergraphConfig> {
+    let base_dir = origin_path
+        .and_then(Utf8Path::parent)
+        .filter(|parent| !parent.as_str().is_empty())
+        .unwrap_or_else(|| Utf8Path::new("."));
+    ExtendedSupergraphConfig::new_from_yaml(contents)?.into_supergraph_config(base_dir, environment)
+}
+
+/// Reads every file that `pattern` matches, in path order, and joins them into one schema
+fn read_glob(subgraph_name: &str, pattern: &str, base_dir: &Utf8Path) -> ConfigResult<String> {
+    let invalid = |message: String| ConfigError::InvalidConfiguration {
+        message: format!("the schema glob `{pattern}` of subgraph `{subgraph_name}` {message}"),
+    };
+    let full_pattern = if Utf8Path::new(pattern).is_absolute() {
+        pattern.to_string()
+    } else {
+        base_dir.join(pattern).to_string()
+    };
+    let mut paths = glob::glob(&full_pattern)
+        .map_err(|e| invalid(format!("is not a valid pattern: {e}")))?
+        .map(|path| {
+            path.map_err(|e| invalid(format!("could not be read: {e}")))
+                .and_then(|path| {
+                    Utf8PathBuf::try_from(path)
+                        .map_err(|e| invalid(format!("matched a non UTF-8 path: {e}")))
+                })
+        })
+        .collect::<ConfigResult<Vec<_>>>()?;
+    paths.retain(|path| path.is_file());
+    if paths.is_empty() {
+        return Err(invalid("did not match any files".to_string()));
+    }
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| {
+            std::fs::read_to_string(path).map_err(|e| ConfigError::MissingFile {
+                file_path: path.to_string(),
+                message: e.to_string(),
+            })
+        })
+        .collect::<ConfigResult<Vec<_>>>()
+        .map(|schemas| schemas.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, process::Command};
+
+    use apollo_federation_types::config::{FederationVersion, SchemaSource};
+    use assert_fs::{prelude::*, TempDir};
+    use camino::Utf8PathBuf;
+    use indoc::indoc;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::{parse_supergraph_config, ExtendedSchemaSource, ExtendedSupergraphConfig};
+
+    const CONFIG: &str = indoc! {r#"
+        federation_version: =2.9.3
+        include:
+        - ./shared.yaml
+        subgraphs:
+          products:
+            routing_url: http://localhost:4001
+            schema:
+              glob: ./products/*.graphql
+            dev:
+              command: npm run start:products
+              mocks: true
+              restart_on_failure: true
+            introspection:
+              timeout: 30
+          reviews:
+            schema:
+              file: ./reviews.graphql
+    "#};
+
+    #[test]
+    fn test_round_trips_unknown_keys() {
+        let config = ExtendedSupergraphConfig::new_from_yaml(CONFIG).unwrap();
+        let products = &config.subgraphs["products"];
+        assert_that!(products.schema).is_equal_to(ExtendedSchemaSource::Glob {
+            glob: "./products/*.graphql".to_string(),
+        });
+        let dev = products.dev.as_ref().unwrap();
+        assert_that!(dev.command.as_deref()).is_equal_to(Some("npm run start:products"));
+        assert_that!(dev.other.contains_key("restart_on_failure")).is_true();
+        assert_that!(products.other.contains_key("introspection")).is_true();
+        assert_that!(config.other.contains_key("include")).is_true();
+
+        let reparsed = ExtendedSupergraphConfig::new_from_yaml(&config.to_yaml().unwrap()).unwrap();
+        assert_that!(reparsed).is_equal_to(config);
+    }
+
+    #[test]
+    fn test_into_supergraph_config_resolves_globs() {
+        let temp_dir = TempDir::new().unwrap();
+        temp_dir
+            .child("products/b.graphql")
+            .write_str("type Product @key(fields: \"id\") { id: ID! }")
+            .unwrap();
+        temp_dir
+            .child("products/a.graphql")
+            .write_str("type Query { products: [Product] }")
+            .unwrap();
+        let base_dir = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf()).unwrap();
+
+        let supergraph_config = ExtendedSupergraphConfig::new_from_yaml(CONFIG)
+            .unwrap()
+            .into_supergraph_config(&base_dir, None)
+            .unwrap();
+
+        assert_that!(supergraph_config.get_federation_version()).is_equal_to(Some(
+            FederationVersion::ExactFedTwo(semver::Version::new(2, 9, 3)),
+        ));
+        let subgraphs = supergraph_config.into_iter().collect::<Vec<_>>();
+        assert_that!(subgraphs[0].1.schema).is_equal_to(SchemaSource::Sdl {
+            sdl:
+                "type Query { products: [Product] }\ntype Product @key(fields: \"id\") { id: ID! }"
+                    .to_string(),
+        });
+        assert_that!(subgraphs[1].1.schema).is_equal_to(SchemaSource::File {
+            file: "./reviews.graphql".into(),
+        });
+    }
+
+    #[test]
+    fn test_into_supergraph_config_reports_empty_globs() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf()).unwrap();
+        let err = ExtendedSupergraphConfig::new_from_yaml(CONFIG)
+            .unwrap()
+            .into_supergraph_config(&base_dir, None)
+            .unwrap_err();
+        assert_that!(err.to_string()).contains(
+            "the schema glob `./products/*.graphql` of subgraph `products` did not match any files",
+        );
+    }
+
+    #[test]
+    fn test_into_supergraph_config_leaves_urls_to_be_downloaded() {
+        let config = indoc! {r#"
+            subgraphs:
+              products:
+                routing_url: http://localhost:4001
+                schema:
+                  url: https://example.com/products.graphql
+                  headers:
+                    Authorization: Bearer token
+        "#};
+
+        let subgraphs = parse_supergraph_config(config, None, None)
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        assert_that!(subgraphs[0].1.schema).is_equal_to(SchemaSource::SubgraphIntrospection {
+            subgraph_url: "https://example.com/products.graphql".parse().unwrap(),
+            introspection_headers: Some(HashMap::from([(
+                "Authorization".to_string(),
+                "Bearer token".to_string(),
+            )])),
+        });
+    }
+
+    #[test]
+    fn test_into_supergraph_config_reads_git_schemas() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.child("products");
+        repo.child("schemas/products.graphql")
+            .write_str("type Query { products: [String] }")
+            .unwrap();
+        for args in [
+            vec!["init", "--quiet", "--initial-branch", "main"],
+            vec!["add", "."],
+            vec!["commit", "--quiet", "--message", "Add the products schema"],
+        ] {
+            let status = Command::new("git")
+                .args([
+                    "-c",
+                    "user.name=rover",
+                    "-c",
+                    "user.email=rover@example.com",
+                ])
+                .arg("-C")
+                .arg(repo.path())
+                .args(args)
+                .status()
+                .unwrap();
+            assert_that!(status.success()).is_true();
+        }
+        let config = format!(
+            indoc! {r#"
+                subgraphs:
+                  products:
+                    routing_url: http://localhost:4001
+                    schema:
+                      git: file://{}
+                      path: schemas/products.graphql
+                      rev: main
+            "#},
+            repo.path().display()
+        );
+
+        let supergraph_config =
+            temp_env::with_var("APOLLO_HOME", Some(temp_dir.child("home").path()), || {
+                parse_supergraph_config(&config, None, None)
+            })
+            .unwrap();
+
+        let subgraphs = supergraph_config.into_iter().collect::<Vec<_>>();
+        assert_that!(subgraphs[0].1.schema).is_equal_to(SchemaSource::Sdl {
+            sdl: "type Query { products: [String] }".to_string(),
+        });
+        assert_that!(temp_dir.child("home/.rover/cache/git").path().is_dir()).is_true();
+    }
+
+    #[rstest]
+    #[case::git("git: --upload-pack=touch pwned\n      path: schema.graphql", "`git`")]
+    #[case::rev(
+        "git: https://github.com/example/products\n      path: schema.graphql\n      rev: --upload-pack=touch pwned",
+        "`rev`"
+    )]
+    fn test_into_supergraph_config_rejects_git_options(#[case] schema: &str, #[case] key: &str) {
+        let temp_dir = TempDir::new().unwrap();
+        let config = format!(
+            "subgraphs:\n  products:\n    routing_url: http://localhost:4001\n    schema:\n      {schema}\n"
+        );
+
+        let err = temp_env::with_var("APOLLO_HOME", Some(temp_dir.path()), || {
+            parse_supergraph_config(&config, None, None)
+        })
+        .unwrap_err();
+
+        assert_that!(err.to_string()).contains(format!("{key} can't start with `-`").as_str());
+        // nothing was fetched, so there's no cache to have run anything in
+        assert_that!(temp_dir.child(".rover/cache/git").path().exists()).is_false();
+    }
+
+    const PER_ENVIRONMENT_CONFIG: &str = indoc! {r#"
+        subgraphs:
+          products:
+            routing_url:
+              dev: http://localhost:4001
+              prod: https://products.example.com
+            schema:
+              sdl: "type Query { products: [String] }"
+          reviews:
+            routing_url:
+              default: http://localhost:4002
+              prod: https://reviews.example.com
+            schema:
+              sdl: "type Query { reviews: [String] }"
+          users:
+            routing_url: https://users.example.com
+            schema:
+              sdl: "type Query { users: [String] }"
+    "#};
+
+    #[rstest]
+    #[case::prod(
+        "prod",
+        ["https://products.example.com", "https://reviews.example.com", "https://users.example.com"]
+    )]
+    #[case::dev_falls_back_on_default(
+        "dev",
+        ["http://localhost:4001", "http://localhost:4002", "https://users.example.com"]
+    )]
+    fn test_routing_urls_per_environment(#[case] environment: &str, #[case] expected: [&str; 3]) {
+        let supergraph_config =
+            parse_supergraph_config(PER_ENVIRONMENT_CONFIG, None, Some(environment)).unwrap();
+        let routing_urls = supergraph_config
+            .into_iter()
+            .map(|(_, subgraph)| subgraph.routing_url.unwrap())
+            .collect::<Vec<_>>();
+        assert_that!(routing_urls).is_equal_to(expected.map(str::to_string).to_vec());
+    }
+
+    #[rstest]
+    #[case::unknown_environment(
+        Some("staging"),
+        "environment `staging` isn't one that any routing_url or subgraph `environments` lists. Expected one of: dev, prod"
+    )]
+    #[case::no_environment(
+        None,
+        "the routing_url of subgraph `products` has a URL per environment, but no environment was picked. Pass `--env` with one of: dev, prod"
+    )]
+    fn test_routing_urls_per_environment_errors(
+        #[case] environment: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        let err = parse_supergraph_config(PER_ENVIRONMENT_CONFIG, None, environment).unwrap_err();
+        assert_that!(err.to_string()).contains(expected);
+    }
+
+    const SUBGRAPH_ENVIRONMENTS_CONFIG: &str = indoc! {r#"
+        subgraphs:
+          products:
+            routing_url: http://localhost:4001
+            schema:
+              sdl: "type Query { products: [String] }"
+            environments:
+              staging:
+                routing_url: https://products.staging.example.com
+              prod:
+                routing_url: https://products.example.com
+                subgraph_url: https://products.example.com/graphql
+          reviews:
+            routing_url: http://localhost:4002
+            schema:
+              subgraph_url: http://localhost:4002/graphql
+              introspection_headers:
+                Authorization: Bearer dev
+            environments:
+              prod:
+                subgraph_url: https://reviews.example.com/graphql
+    "#};
+
+    #[rstest]
+    #[case::none(None, ["http://localhost:4001", "http://localhost:4002"])]
+    #[case::staging(
+        Some("staging"),
+        ["https://products.staging.example.com", "http://localhost:4002"]
+    )]
+    #[case::prod(
+        Some("prod"),
+        ["https://products.example.com", "http://localhost:4002"]
+    )]
+    fn test_subgraph_environments_routing_urls(
+        #[case] environment: Option<&str>,
+        #[case] expected: [&str; 2],
+    ) {
+        let supergraph_config =
+            parse_supergraph_config(SUBGRAPH_ENVIRONMENTS_CONFIG, None, environment).unwrap();
+        let routing_urls = supergraph_config
+            .into_iter()
+            .map(|(_, subgraph)| subgraph.routing_url.unwrap())
+            .collect::<Vec<_>>();
+        assert_that!(routing_urls).is_equal_to(expected.map(str::to_string).to_vec());
+    }
+
+    #[test]
+    fn test_subgraph_environments_introspect_remote_endpoints() {
+        let supergraph_config =
+            parse_supergraph_config(SUBGRAPH_ENVIRONMENTS_CONFIG, None, Some("prod")).unwrap();
+        let schemas = supergraph_config
+            .into_iter()
+            .map(|(_, subgraph)| subgraph.schema)
+            .collect::<Vec<_>>();
+        assert_that!(schemas).is_equal_to(vec![
+            SchemaSource::SubgraphIntrospection {
+                subgraph_url: "https://products.example.com/graphql".parse().unwrap(),
+                introspection_headers: None,
+            },
+            SchemaSource::SubgraphIntrospection {
+                subgraph_url: "https://reviews.example.com/graphql".parse().unwrap(),
+                introspection_headers: Some(
+                    [("Authorization".to_string(), "Bearer dev".to_string())].into(),
+                ),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_subgraph_environments_round_trip() {
+        let config = ExtendedSupergraphConfig::new_from_yaml(SUBGRAPH_ENVIRONMENTS_CONFIG).unwrap();
+        let written = ExtendedSupergraphConfig::new_from_yaml(&config.to_yaml().unwrap()).unwrap();
+        assert_that!(written).is_equal_to(config);
+    }
+}
@@ -10,9 +10,15 @@ use rover_std::Fs;
 
 use crate::{
     composition::supergraph::config::{
-        error::ResolveSubgraphError, unresolved::UnresolvedSubgraph,
+        error::ResolveSubgraphError, resolver::SubgraphRetryPolicy, unresolved::UnresolvedSubgraph,
+    },
+    utils::{
+        effect::{
+            fetch_remote_subgraph::FetchRemoteSubgraph,
+            introspect::{IntrospectSubgraph, IntrospectionPolicy},
+        },
+        graphql_ws::{schema_has_subscriptions, SubscriptionSupport},
     },
-    utils::effect::{fetch_remote_subgraph::FetchRemoteSubgraph, introspect::IntrospectSubgraph},
 };
 
 /// Represents a [`SubgraphConfig`] that has been resolved down to an SDL
@@ -23,6 +29,8 @@ pub struct FullyResolvedSubgraph {
     #[getter(skip)]
     schema: String,
     is_fed_two: bool,
+    /// How the subgraph serves subscriptions, when it was introspected and found to serve them
+    subscriptions: Option<SubscriptionSupport>,
 }
 
 #[buildstructor]
@@ -33,19 +41,59 @@ impl FullyResolvedSubgraph {
         schema: String,
         routing_url: Option<String>,
         is_fed_two: Option<bool>,
+        subscriptions: Option<SubscriptionSupport>,
     ) -> FullyResolvedSubgraph {
         FullyResolvedSubgraph {
             schema,
             routing_url,
             is_fed_two: is_fed_two.unwrap_or_default(),
+            subscriptions,
+        }
+    }
+    /// Resolves a [`UnresolvedSubgraph`] to a [`FullyResolvedSubgraph`], retrying failures that
+    /// may be transient as the [`SubgraphRetryPolicy`] allows
+    pub async fn resolve_with_retries(
+        introspect_subgraph_impl: &impl IntrospectSubgraph,
+        fetch_remote_subgraph_impl: &impl FetchRemoteSubgraph,
+        supergraph_config_root: Option<&Utf8PathBuf>,
+        unresolved_subgraph: UnresolvedSubgraph,
+        retry_policy: &SubgraphRetryPolicy,
+        introspection_policy: IntrospectionPolicy,
+    ) -> Result<FullyResolvedSubgraph, ResolveSubgraphError> {
+        let mut retry = 0;
+        loop {
+            let result = Self::resolve(
+                introspect_subgraph_impl,
+                fetch_remote_subgraph_impl,
+                supergraph_config_root,
+                unresolved_subgraph.clone(),
+                introspection_policy.clone(),
+            )
+            .await;
+            match result {
+                Err(err) if err.is_transient() && retry < retry_policy.max_retries => {
+                    tracing::debug!(
+                        "retrying subgraph `{}` after error: {}",
+                        unresolved_subgraph.name(),
+                        err
+                    );
+                    tokio::time::sleep(retry_policy.backoff(retry)).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
         }
     }
-    /// Resolves a [`UnresolvedSubgraph`] to a [`FullyResolvedSubgraph`]
+
+    /// Resolves a [`UnresolvedSubgraph`] to a [`FullyResolvedSubgraph`]. Subgraphs are
+    /// introspected as the [`IntrospectionPolicy`] says, unless their own introspection settings
+    /// say otherwise
     pub async fn resolve(
         introspect_subgraph_impl: &impl IntrospectSubgraph,
         fetch_remote_subgraph_impl: &impl FetchRemoteSubgraph,
         supergraph_config_root: Option<&Utf8PathBuf>,
         unresolved_subgraph: UnresolvedSubgraph,
+        introspection_policy: IntrospectionPolicy,
     ) -> Result<FullyResolvedSubgraph, ResolveSubgraphError> {
         match unresolved_subgraph.schema() {
             SchemaSource::File { file } => {
@@ -59,16 +107,46 @@ impl FullyResolvedSubgraph {
                     routing_url: unresolved_subgraph.routing_url().clone(),
                     schema,
                     is_fed_two,
+                    subscriptions: None,
                 })
             }
             SchemaSource::SubgraphIntrospection {
                 subgraph_url,
                 introspection_headers,
             } => {
+                let headers = introspection_headers.clone().unwrap_or_default();
+                let introspection = unresolved_subgraph.introspection().as_ref();
+                let client_identity =
+                    introspection.and_then(|introspection| introspection.client_identity());
+                let introspection_policy = match introspection {
+                    Some(introspection) => introspection.policy(introspection_policy),
+                    None => introspection_policy,
+                };
+                // a `url` schema source stands in as the URL it's downloaded from
+                if let Some(schema_url) =
+                    introspection.and_then(|introspection| introspection.schema_url.clone())
+                {
+                    let schema = introspect_subgraph_impl
+                        .download_schema(schema_url, client_identity, introspection_policy.timeout)
+                        .await
+                        .map_err(|err| ResolveSubgraphError::DownloadError {
+                            subgraph_name: unresolved_subgraph.name().to_string(),
+                            source: Box::new(err),
+                        })?;
+                    let is_fed_two = schema_contains_link_directive(&schema);
+                    return Ok(FullyResolvedSubgraph {
+                        routing_url: unresolved_subgraph.routing_url().clone(),
+                        schema,
+                        is_fed_two,
+                        subscriptions: None,
+                    });
+                }
                 let schema = introspect_subgraph_impl
                     .introspect_subgraph(
                         subgraph_url.clone(),
-                        introspection_headers.clone().unwrap_or_default(),
+                        headers.clone(),
+                        client_identity.clone(),
+                        introspection_policy,
                     )
                     .await
                     .map_err(|err| ResolveSubgraphError::IntrospectionError {
@@ -80,10 +158,28 @@ impl FullyResolvedSubgraph {
                     .clone()
                     .or_else(|| Some(subgraph_url.to_string()));
                 let is_fed_two = schema_contains_link_directive(&schema);
+                // subscriptions are only worth a websocket connection when the schema has any, and
+                // a subgraph that can't serve them over websockets still resolves
+                let subscriptions = if schema_has_subscriptions(&schema) {
+                    introspect_subgraph_impl
+                        .negotiate_subscriptions(subgraph_url.clone(), headers, client_identity)
+                        .await
+                        .unwrap_or_else(|err| {
+                            tracing::debug!(
+                                "could not negotiate subscriptions with subgraph `{}`: {}",
+                                unresolved_subgraph.name(),
+                                err
+                            );
+                            None
+                        })
+                } else {
+                    None
+                };
                 Ok(FullyResolvedSubgraph {
                     routing_url,
                     schema,
                     is_fed_two,
+                    subscriptions,
                 })
             }
             SchemaSource::Subgraph {
@@ -92,6 +188,7 @@ impl FullyResolvedSubgraph {
             } => {
                 let graph_ref = GraphRef::from_str(graph_ref).map_err(|err| {
                     ResolveSubgraphError::InvalidGraphRef {
+                        subgraph_name: unresolved_subgraph.name().to_string(),
                         graph_ref: graph_ref.clone(),
                         source: Box::new(err),
                     }
@@ -100,7 +197,7 @@ impl FullyResolvedSubgraph {
                     .fetch_remote_subgraph(graph_ref, subgraph.to_string())
                     .await
                     .map_err(|err| ResolveSubgraphError::FetchRemoteSdlError {
-                        subgraph_name: subgraph.to_string(),
+                        subgraph_name: unresolved_subgraph.name().to_string(),
                         source: Box::new(err),
                     })?;
                 let schema = remote_subgraph.schema().clone();
@@ -112,6 +209,7 @@ impl FullyResolvedSubgraph {
                         .or(Some(remote_subgraph.routing_url().to_string())),
                     schema,
                     is_fed_two,
+                    subscriptions: None,
                 })
             }
             SchemaSource::Sdl { sdl } => {
@@ -120,6 +218,7 @@ impl FullyResolvedSubgraph {
                     routing_url: None,
                     schema: sdl.to_string(),
                     is_fed_two,
+                    subscriptions: None,
                 })
             }
         }
@@ -157,3 +256,164 @@ fn schema_contains_link_directive(sdl: &str) -> bool {
         .unwrap_or_default()
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use anyhow::Result;
+    use apollo_federation_types::config::{SchemaSource, SubgraphConfig};
+    use mockall::predicate;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use crate::{
+        composition::supergraph::config::{
+            extended::SchemaUrl,
+            introspection::SubgraphIntrospectionConfig,
+            scenario::{introspect_subgraph_scenario, IntrospectSubgraphScenario},
+            unresolved::UnresolvedSubgraph,
+        },
+        utils::{
+            effect::{
+                fetch_remote_subgraph::MockFetchRemoteSubgraph,
+                introspect::{
+                    IntrospectionPolicy, MockIntrospectSubgraph, MockIntrospectSubgraphError,
+                },
+            },
+            graphql_ws::{SubscriptionSupport, GRAPHQL_TRANSPORT_WS},
+        },
+    };
+
+    use super::FullyResolvedSubgraph;
+
+    #[rstest]
+    #[case::negotiated(true)]
+    #[case::negotiation_failed(false)]
+    #[tokio::test]
+    async fn test_resolve_introspection_subgraph_with_subscriptions(
+        introspect_subgraph_scenario: IntrospectSubgraphScenario,
+        #[case] negotiated: bool,
+    ) -> Result<()> {
+        let IntrospectSubgraphScenario {
+            routing_url,
+            introspection_headers,
+            unresolved_subgraph,
+            ..
+        } = introspect_subgraph_scenario;
+        let sdl = "type Query { a: Int } type Subscription { a: Int }".to_string();
+        let routing_url = url::Url::from_str(&routing_url)?;
+        let subscription_support = SubscriptionSupport {
+            url: routing_url.clone(),
+            protocol: GRAPHQL_TRANSPORT_WS.to_string(),
+        };
+        let mut mock_introspect_subgraph = MockIntrospectSubgraph::new();
+        mock_introspect_subgraph
+            .expect_introspect_subgraph()
+            .times(1)
+            .returning({
+                let sdl = sdl.to_string();
+                move |_, _, _, _| Ok(sdl.to_string())
+            });
+        mock_introspect_subgraph
+            .expect_negotiate_subscriptions()
+            .times(1)
+            .with(
+                predicate::eq(routing_url.clone()),
+                predicate::eq(introspection_headers),
+                predicate::eq(None),
+            )
+            .returning({
+                let subscription_support = subscription_support.clone();
+                move |_, _, _| {
+                    if negotiated {
+                        Ok(Some(subscription_support.clone()))
+                    } else {
+                        Err(MockIntrospectSubgraphError(
+                            "connection refused".to_string(),
+                        ))
+                    }
+                }
+            });
+        let mut mock_fetch_remote_subgraph = MockFetchRemoteSubgraph::new();
+        mock_fetch_remote_subgraph
+            .expect_fetch_remote_subgraph()
+            .times(0);
+
+        // WHEN we resolve a subgraph whose schema has subscriptions
+        let result = FullyResolvedSubgraph::resolve(
+            &mock_introspect_subgraph,
+            &mock_fetch_remote_subgraph,
+            None,
+            unresolved_subgraph,
+            IntrospectionPolicy::default(),
+        )
+        .await;
+
+        // THEN it resolves either way, with its subscription support when it was negotiated
+        mock_introspect_subgraph.checkpoint();
+        assert_that!(result.map(|subgraph| subgraph.subscriptions().clone()))
+            .is_ok()
+            .is_equal_to(negotiated.then_some(subscription_support));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_downloads_url_schemas() -> Result<()> {
+        let schema_url = SchemaUrl {
+            url: "https://example.com/products.graphql".to_string(),
+            headers: None,
+        };
+        let unresolved_subgraph = UnresolvedSubgraph::new(
+            "products".to_string(),
+            SubgraphConfig {
+                routing_url: Some("http://localhost:4001".to_string()),
+                schema: SchemaSource::SubgraphIntrospection {
+                    subgraph_url: url::Url::from_str(&schema_url.url)?,
+                    introspection_headers: None,
+                },
+            },
+        )
+        .with_introspection(Some(SubgraphIntrospectionConfig {
+            schema_url: Some(schema_url.clone()),
+            ..Default::default()
+        }));
+        let mut mock_introspect_subgraph = MockIntrospectSubgraph::new();
+        mock_introspect_subgraph
+            .expect_introspect_subgraph()
+            .times(0);
+        mock_introspect_subgraph
+            .expect_download_schema()
+            .times(1)
+            .with(
+                predicate::eq(schema_url),
+                predicate::eq(None),
+                predicate::eq(None),
+            )
+            .returning(|_, _, _| Ok("type Query { products: [String] }".to_string()));
+        let mut mock_fetch_remote_subgraph = MockFetchRemoteSubgraph::new();
+        mock_fetch_remote_subgraph
+            .expect_fetch_remote_subgraph()
+            .times(0);
+
+        // WHEN we resolve a subgraph whose schema comes from a `url`
+        let result = FullyResolvedSubgraph::resolve(
+            &mock_introspect_subgraph,
+            &mock_fetch_remote_subgraph,
+            None,
+            unresolved_subgraph,
+            IntrospectionPolicy::default(),
+        )
+        .await;
+
+        // THEN it's downloaded rather than introspected, keeping its routing URL
+        mock_introspect_subgraph.checkpoint();
+        assert_that!(result).is_ok().is_equal_to(
+            FullyResolvedSubgraph::builder()
+                .schema("type Query { products: [String] }".to_string())
+                .routing_url("http://localhost:4001".to_string())
+                .build(),
+        );
+        Ok(())
+    }
+}
@@ -31,6 +31,16 @@ impl FullyResolvedSubgraphs {
     pub fn remove_subgraph(&mut self, name: &str) {
         self.subgraphs.remove(name);
     }
+
+    /// Whether a subgraph with this name has been resolved
+    pub fn contains_subgraph(&self, name: &str) -> bool {
+        self.subgraphs.contains_key(name)
+    }
+
+    /// Iterates over each subgraph's name and SDL
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.subgraphs.iter()
+    }
 }
 
 impl TryFrom<SupergraphConfig> for FullyResolvedSubgraphs {
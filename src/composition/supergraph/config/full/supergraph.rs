@@ -1,14 +1,20 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, time::Instant};
 
 use apollo_federation_types::config::{FederationVersion, SupergraphConfig};
 use camino::Utf8PathBuf;
 use derive_getters::Getters;
-use futures::{stream, StreamExt, TryFutureExt};
-use itertools::Itertools;
+use futures::{stream, StreamExt};
+use itertools::{Either, Itertools};
+use rover_std::progress::{Progress, ProgressBar};
+use tracing::Instrument;
 
 use crate::{
     composition::supergraph::config::{
-        error::ResolveSubgraphError, resolver::ResolveSupergraphConfigError,
+        error::ResolveSubgraphError,
+        resolver::{
+            ResolveSupergraphConfigError, SubgraphResolutionOptions, SubgraphResolutionPolicy,
+            SubgraphResolutionReport,
+        },
         unresolved::UnresolvedSupergraphConfig,
     },
     utils::effect::{fetch_remote_subgraph::FetchRemoteSubgraph, introspect::IntrospectSubgraph},
@@ -45,37 +51,132 @@ impl FullyResolvedSupergraphConfig {
         supergraph_config_root: Option<&Utf8PathBuf>,
         unresolved_supergraph_config: UnresolvedSupergraphConfig,
     ) -> Result<FullyResolvedSupergraphConfig, ResolveSupergraphConfigError> {
+        let (resolved_supergraph_config, _) = Self::resolve_with_policy(
+            introspect_subgraph_impl,
+            fetch_remote_subgraph_impl,
+            supergraph_config_root,
+            unresolved_supergraph_config,
+            SubgraphResolutionPolicy::RequireAll,
+        )
+        .await?;
+        Ok(resolved_supergraph_config)
+    }
+
+    /// Resolves an [`UnresolvedSupergraphConfig`] like [`FullyResolvedSupergraphConfig::resolve`],
+    /// but lets the [`SubgraphResolutionPolicy`] decide whether subgraphs that can't be resolved
+    /// fail resolution altogether, or are left out of the result and returned by name alongside it
+    pub async fn resolve_with_policy(
+        introspect_subgraph_impl: &impl IntrospectSubgraph,
+        fetch_remote_subgraph_impl: &impl FetchRemoteSubgraph,
+        supergraph_config_root: Option<&Utf8PathBuf>,
+        unresolved_supergraph_config: UnresolvedSupergraphConfig,
+        policy: SubgraphResolutionPolicy,
+    ) -> Result<
+        (
+            FullyResolvedSupergraphConfig,
+            BTreeMap<String, ResolveSubgraphError>,
+        ),
+        ResolveSupergraphConfigError,
+    > {
+        let (resolved_supergraph_config, report) = Self::resolve_with_options(
+            introspect_subgraph_impl,
+            fetch_remote_subgraph_impl,
+            supergraph_config_root,
+            unresolved_supergraph_config,
+            &SubgraphResolutionOptions {
+                policy,
+                ..Default::default()
+            },
+        )
+        .await?;
+        Ok((resolved_supergraph_config, report.failures))
+    }
+
+    /// Resolves an [`UnresolvedSupergraphConfig`] like
+    /// [`FullyResolvedSupergraphConfig::resolve_with_policy`], additionally retrying subgraphs that
+    /// fail to resolve over the network. When resolving offline, subgraphs whose schemas can only
+    /// be resolved over the network fail without being tried
+    pub async fn resolve_with_options(
+        introspect_subgraph_impl: &impl IntrospectSubgraph,
+        fetch_remote_subgraph_impl: &impl FetchRemoteSubgraph,
+        supergraph_config_root: Option<&Utf8PathBuf>,
+        unresolved_supergraph_config: UnresolvedSupergraphConfig,
+        options: &SubgraphResolutionOptions,
+    ) -> Result<
+        (FullyResolvedSupergraphConfig, SubgraphResolutionReport),
+        ResolveSupergraphConfigError,
+    > {
+        let progress = ProgressBar::new("resolving subgraphs");
+        progress.set_length(unresolved_supergraph_config.subgraphs().len() as u64);
+        let progress = &progress;
         let subgraphs = stream::iter(unresolved_supergraph_config.subgraphs().iter().map(
             |(name, unresolved_subgraph)| {
-                FullyResolvedSubgraph::resolve(
-                    introspect_subgraph_impl,
-                    fetch_remote_subgraph_impl,
-                    supergraph_config_root,
-                    unresolved_subgraph.clone(),
-                )
-                .map_ok(|result| (name.to_string(), result))
+                async move {
+                    let started_at = Instant::now();
+                    let result = match options.offline {
+                        true => unresolved_subgraph.require_offline_schema(),
+                        false => Ok(()),
+                    };
+                    let result = match result {
+                        Ok(()) => {
+                            FullyResolvedSubgraph::resolve_with_retries(
+                                introspect_subgraph_impl,
+                                fetch_remote_subgraph_impl,
+                                supergraph_config_root,
+                                unresolved_subgraph.clone(),
+                                &options.retry_policy,
+                                options.introspection_policy.clone(),
+                            )
+                            .await
+                        }
+                        Err(err) => Err(err),
+                    };
+                    progress.inc(1);
+                    (name.to_string(), result, started_at.elapsed())
+                }
+                .instrument(tracing::info_span!("subgraph", subgraph = %name))
             },
         ))
         .buffer_unordered(50)
-        .collect::<Vec<Result<(String, FullyResolvedSubgraph), ResolveSubgraphError>>>()
+        .collect::<Vec<_>>()
         .await;
-        let (subgraphs, errors): (
-            Vec<(String, FullyResolvedSubgraph)>,
-            Vec<ResolveSubgraphError>,
-        ) = subgraphs.into_iter().partition_result();
-        if errors.is_empty() {
+        progress.finish();
+        let resolution_times = subgraphs
+            .iter()
+            .map(|(name, _, elapsed)| (name.clone(), *elapsed))
+            .collect();
+        let (subgraphs, failures): (Vec<_>, Vec<_>) =
+            subgraphs
+                .into_iter()
+                .partition_map(|(name, result, _)| match result {
+                    Ok(subgraph) => Either::Left((name, subgraph)),
+                    Err(err) => Either::Right((name, err)),
+                });
+        let proceed = match options.policy {
+            SubgraphResolutionPolicy::RequireAll => failures.is_empty(),
+            SubgraphResolutionPolicy::AllowPartial => !subgraphs.is_empty(),
+        };
+        if proceed {
             let subgraphs = BTreeMap::from_iter(subgraphs);
             let federation_version = Self::resolve_federation_version(
                 unresolved_supergraph_config.federation_version().as_ref(),
                 &mut subgraphs.iter(),
             )?;
-            Ok(FullyResolvedSupergraphConfig {
-                origin_path: unresolved_supergraph_config.origin_path().clone(),
-                subgraphs,
-                federation_version,
-            })
+            Ok((
+                FullyResolvedSupergraphConfig {
+                    origin_path: unresolved_supergraph_config.origin_path().clone(),
+                    subgraphs,
+                    federation_version,
+                },
+                SubgraphResolutionReport {
+                    failures: BTreeMap::from_iter(failures),
+                    resolution_times,
+                },
+            ))
         } else {
-            Err(ResolveSupergraphConfigError::ResolveSubgraphs(errors))
+            Err(ResolveSupergraphConfigError::ResolveSubgraphs(
+                failures.into_iter().map(|(_, err)| err).collect(),
+            ))
         }
     }
 
@@ -0,0 +1,468 @@
+//! Per-subgraph introspection settings, read from the `introspection` key of each subgraph in a
+//! supergraph config. `apollo_federation_types` doesn't know about the key, so it's parsed
+//! separately from the rest of the config. The same settings apply to polling schemas that are
+//! downloaded from a `url` or read from a `git` repository
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+
+use apollo_federation_types::config::SchemaSource;
+use camino::Utf8PathBuf;
+use serde::Deserialize;
+
+use super::extended::{ExtendedSchemaSource, SchemaGit, SchemaUrl, SubgraphEnvironment};
+use crate::utils::{
+    client::ClientIdentity,
+    effect::introspect::{IntrospectionMode, IntrospectionPolicy},
+    expansion::expand,
+    introspection_auth::IntrospectionAuthenticator,
+};
+
+/// How a subgraph whose schema comes from introspection is polled, overriding the defaults that
+/// apply to every subgraph
+///
+/// ```yaml
+/// subgraphs:
+///   products:
+///     schema:
+///       subgraph_url: http://localhost:4001
+///     introspection:
+///       poll_interval_seconds: 10
+///       timeout: 30
+///       retries: 3
+///       mode: get
+///       headers:
+///         x-products-team: rover
+///       client_cert: ./certs/rover.crt
+///       client_key: ./certs/rover.key
+///       auth:
+///         type: oauth_client_credentials
+///         token_url: https://auth.example.com/oauth/token
+///         client_id: rover
+///         client_secret: ${env.PRODUCTS_CLIENT_SECRET}
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct SubgraphIntrospectionConfig {
+    /// How often to introspect the subgraph for changes, in seconds
+    pub poll_interval_seconds: Option<u64>,
+    /// Headers to send with every introspection request, on top of `introspection_headers`
+    pub headers: Option<HashMap<String, String>>,
+    /// How long to wait for an introspection response, in seconds
+    pub timeout: Option<u64>,
+    /// How many times to retry introspection after it times out or gets an HTTP error
+    pub retries: Option<u32>,
+    /// How to send the introspection query: `post`, `get` or `apq`
+    pub mode: Option<IntrospectionMode>,
+    /// A PEM-encoded certificate to present to subgraphs that require mutual TLS
+    pub client_cert: Option<Utf8PathBuf>,
+    /// The PEM-encoded PKCS #8 private key for `client_cert`
+    pub client_key: Option<Utf8PathBuf>,
+    /// How to authenticate each introspection request, with a fixed header or a token that's
+    /// fetched again when it expires
+    pub auth: Option<IntrospectionAuthenticator>,
+    /// Where the subgraph's schema is downloaded from, when it's a `url` schema source. The
+    /// schema source itself only says which URL it comes from, so this is how resolution and
+    /// watchers know to download it as raw SDL rather than introspect it
+    #[serde(skip)]
+    pub schema_url: Option<SchemaUrl>,
+    /// Where the subgraph's schema is read from, when it's a `git` schema source, so watchers
+    /// know to keep polling the repository for new commits
+    #[serde(skip)]
+    pub schema_git: Option<SchemaGit>,
+}
+
+#[derive(Deserialize)]
+struct SupergraphIntrospectionConfigs {
+    subgraphs: Option<BTreeMap<String, SubgraphIntrospectionEntry>>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SubgraphIntrospectionEntry {
+    introspection: Option<SubgraphIntrospectionConfig>,
+    schema: Option<ExtendedSchemaSource>,
+    #[serde(default)]
+    environments: BTreeMap<String, SubgraphEnvironment>,
+}
+
+/// Expands variables like `${env.CLIENT_SECRET}` in the `auth` settings of each subgraph, so
+/// secrets don't have to be written into the supergraph config
+fn expand_auth(configs: &mut serde_yaml::Value) -> Result<(), serde_yaml::Error> {
+    let subgraphs = configs
+        .get_mut("subgraphs")
+        .and_then(serde_yaml::Value::as_mapping_mut);
+    for subgraph in subgraphs
+        .into_iter()
+        .flat_map(|subgraphs| subgraphs.values_mut())
+    {
+        if let Some(auth) = subgraph
+            .get_mut("introspection")
+            .and_then(|introspection| introspection.get_mut("auth"))
+        {
+            *auth =
+                expand(auth.clone()).map_err(<serde_yaml::Error as serde::de::Error>::custom)?;
+        }
+    }
+    Ok(())
+}
+
+impl SubgraphIntrospectionConfig {
+    /// Reads the introspection settings of every subgraph in the supergraph config `contents`
+    /// that has any, or whose schema is downloaded from a URL or git repository, by subgraph name. Relative
+    /// certificate and key paths are resolved against the directory of `origin_path`, like
+    /// schema files. A subgraph that `environment` points at a `subgraph_url` is introspected
+    /// there, so its schema's URL or git repository is left out
+    pub fn load_all(
+        contents: &str,
+        origin_path: Option<&Utf8PathBuf>,
+        environment: Option<&str>,
+    ) -> Result<BTreeMap<String, SubgraphIntrospectionConfig>, serde_yaml::Error> {
+        let mut configs: serde_yaml::Value = serde_yaml::from_str(contents)?;
+        expand_auth(&mut configs)?;
+        let configs: SupergraphIntrospectionConfigs = serde_yaml::from_value(configs)?;
+        let root = origin_path.and_then(|path| path.parent());
+        Ok(configs
+            .subgraphs
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(name, entry)| {
+                let introspected_in_environment = environment
+                    .and_then(|environment| entry.environments.get(environment))
+                    .is_some_and(|endpoints| endpoints.subgraph_url.is_some());
+                let (schema_url, schema_git) = match entry.schema {
+                    _ if introspected_in_environment => (None, None),
+                    Some(ExtendedSchemaSource::Url(schema_url)) => (Some(schema_url), None),
+                    Some(ExtendedSchemaSource::Git(schema_git)) => (None, Some(schema_git)),
+                    _ => (None, None),
+                };
+                match (entry.introspection, schema_url, schema_git) {
+                    (None, None, None) => None,
+                    (introspection, schema_url, schema_git) => Some((
+                        name,
+                        SubgraphIntrospectionConfig {
+                            schema_url,
+                            schema_git,
+                            ..introspection.unwrap_or_default()
+                        },
+                    )),
+                }
+            })
+            .map(|(name, mut config)| {
+                if let Some(root) = root {
+                    for path in [&mut config.client_cert, &mut config.client_key]
+                        .into_iter()
+                        .flatten()
+                    {
+                        if path.is_relative() {
+                            *path = root.join(&*path);
+                        }
+                    }
+                }
+                (name, config)
+            })
+            .collect())
+    }
+
+    /// The certificate and key to introspect the subgraph with, when both are set
+    pub fn client_identity(&self) -> Option<ClientIdentity> {
+        self.client_cert
+            .clone()
+            .zip(self.client_key.clone())
+            .map(|(cert, key)| ClientIdentity::new(cert, key))
+    }
+
+    /// The `defaults` for introspecting any subgraph, with the timeout and retries that these
+    /// settings give in their place
+    pub fn policy(&self, defaults: IntrospectionPolicy) -> IntrospectionPolicy {
+        IntrospectionPolicy {
+            timeout: self.timeout.map(Duration::from_secs).or(defaults.timeout),
+            retries: self.retries.unwrap_or(defaults.retries),
+            mode: self.mode.unwrap_or(defaults.mode),
+            auth: self.auth.clone().or(defaults.auth),
+        }
+    }
+
+    /// Adds these headers to an introspected schema source, replacing any `introspection_headers`
+    /// with the same name. Other schema sources are returned as-is
+    pub fn apply_headers(&self, schema: SchemaSource) -> SchemaSource {
+        match (schema, &self.headers) {
+            (
+                SchemaSource::SubgraphIntrospection {
+                    subgraph_url,
+                    introspection_headers,
+                },
+                Some(headers),
+            ) => {
+                let mut introspection_headers = introspection_headers.unwrap_or_default();
+                introspection_headers.extend(headers.clone());
+                SchemaSource::SubgraphIntrospection {
+                    subgraph_url,
+                    introspection_headers: Some(introspection_headers),
+                }
+            }
+            (schema, _) => schema,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        time::Duration,
+    };
+
+    use apollo_federation_types::config::SchemaSource;
+    use camino::Utf8PathBuf;
+    use indoc::indoc;
+    use speculoos::prelude::*;
+    use url::Url;
+
+    use super::SubgraphIntrospectionConfig;
+    use crate::{
+        composition::supergraph::config::extended::{SchemaGit, SchemaUrl},
+        utils::{
+            client::ClientIdentity,
+            effect::introspect::{IntrospectionMode, IntrospectionPolicy},
+            introspection_auth::IntrospectionAuth,
+        },
+    };
+
+    #[test]
+    fn test_load_all_reads_introspection_settings() {
+        let contents = indoc! {r#"
+            federation_version: =2.7.1
+            subgraphs:
+              products:
+                routing_url: http://localhost:4001
+                schema:
+                  subgraph_url: http://localhost:4001
+                introspection:
+                  poll_interval_seconds: 10
+                  timeout: 30
+                  retries: 3
+                  mode: apq
+                  headers:
+                    Authorization: Bearer token
+              reviews:
+                schema:
+                  file: ./reviews.graphql
+        "#};
+        let configs = SubgraphIntrospectionConfig::load_all(contents, None, None).unwrap();
+        assert_that!(configs.keys().cloned().collect::<Vec<_>>())
+            .is_equal_to(vec!["products".to_string()]);
+        assert_that!(configs["products"]).is_equal_to(SubgraphIntrospectionConfig {
+            poll_interval_seconds: Some(10),
+            headers: Some(HashMap::from([(
+                "Authorization".to_string(),
+                "Bearer token".to_string(),
+            )])),
+            timeout: Some(30),
+            retries: Some(3),
+            mode: Some(IntrospectionMode::Apq),
+            client_cert: None,
+            client_key: None,
+            auth: None,
+            schema_url: None,
+            schema_git: None,
+        });
+    }
+
+    #[test]
+    fn test_load_all_expands_auth_variables() {
+        std::env::set_var("ROVER_TEST_INTROSPECTION_CLIENT_SECRET", "hunter2");
+        let contents = indoc! {r#"
+            subgraphs:
+              products:
+                schema:
+                  subgraph_url: http://localhost:4001
+                introspection:
+                  auth:
+                    type: oauth_client_credentials
+                    token_url: https://auth.example.com/oauth/token
+                    client_id: rover
+                    client_secret: ${env.ROVER_TEST_INTROSPECTION_CLIENT_SECRET}
+        "#};
+        let configs = SubgraphIntrospectionConfig::load_all(contents, None, None).unwrap();
+        assert_that!(configs["products"]
+            .auth
+            .as_ref()
+            .map(|auth| auth.auth().clone()))
+        .is_equal_to(Some(IntrospectionAuth::OauthClientCredentials {
+            token_url: Url::parse("https://auth.example.com/oauth/token").unwrap(),
+            client_id: "rover".to_string(),
+            client_secret: "hunter2".to_string(),
+            scope: None,
+            audience: None,
+            header: None,
+        }));
+    }
+
+    #[test]
+    fn test_load_all_rejects_unknown_auth() {
+        let contents = indoc! {r#"
+            subgraphs:
+              products:
+                introspection:
+                  auth:
+                    type: kerberos
+        "#};
+        assert_that!(SubgraphIntrospectionConfig::load_all(contents, None, None)).is_err();
+    }
+
+    #[test]
+    fn test_load_all_resolves_client_identity_paths() {
+        let contents = indoc! {r#"
+            subgraphs:
+              products:
+                introspection:
+                  client_cert: ./certs/rover.crt
+                  client_key: /etc/rover/rover.key
+              reviews:
+                introspection:
+                  client_cert: ./certs/rover.crt
+        "#};
+        let origin_path = Utf8PathBuf::from("/supergraph/supergraph.yaml");
+        let configs =
+            SubgraphIntrospectionConfig::load_all(contents, Some(&origin_path), None).unwrap();
+        assert_that!(configs["products"].client_identity()).is_equal_to(Some(ClientIdentity::new(
+            Utf8PathBuf::from("/supergraph/./certs/rover.crt"),
+            Utf8PathBuf::from("/etc/rover/rover.key"),
+        )));
+        assert_that!(configs["reviews"].client_identity()).is_none();
+    }
+
+    #[test]
+    fn test_load_all_reads_polled_schema_sources() {
+        let contents = indoc! {r#"
+            subgraphs:
+              products:
+                schema:
+                  url: https://example.com/products.graphql
+                  headers:
+                    Authorization: Bearer token
+                introspection:
+                  poll_interval_seconds: 30
+              reviews:
+                schema:
+                  url: https://example.com/reviews.graphql
+              users:
+                schema:
+                  file: ./users.graphql
+              inventory:
+                schema:
+                  git: https://github.com/example/inventory
+                  path: schema.graphql
+        "#};
+        let configs = SubgraphIntrospectionConfig::load_all(contents, None, None).unwrap();
+        assert_that!(configs.keys().cloned().collect::<Vec<_>>()).is_equal_to(vec![
+            "inventory".to_string(),
+            "products".to_string(),
+            "reviews".to_string(),
+        ]);
+        assert_that!(configs["products"]).is_equal_to(SubgraphIntrospectionConfig {
+            poll_interval_seconds: Some(30),
+            schema_url: Some(SchemaUrl {
+                url: "https://example.com/products.graphql".to_string(),
+                headers: Some(BTreeMap::from([(
+                    "Authorization".to_string(),
+                    "Bearer token".to_string(),
+                )])),
+            }),
+            ..Default::default()
+        });
+        assert_that!(configs["reviews"].schema_url).is_equal_to(Some(SchemaUrl {
+            url: "https://example.com/reviews.graphql".to_string(),
+            headers: None,
+        }));
+        assert_that!(configs["inventory"].schema_git).is_equal_to(Some(SchemaGit {
+            git: "https://github.com/example/inventory".to_string(),
+            path: "schema.graphql".into(),
+            rev: None,
+        }));
+    }
+
+    #[test]
+    fn test_load_all_leaves_out_schema_urls_introspected_in_the_environment() {
+        let contents = indoc! {r#"
+            subgraphs:
+              products:
+                schema:
+                  url: https://example.com/products.graphql
+                environments:
+                  prod:
+                    subgraph_url: https://products.example.com/graphql
+        "#};
+        let configs = SubgraphIntrospectionConfig::load_all(contents, None, None).unwrap();
+        assert_that!(configs["products"].schema_url).is_some();
+        let configs = SubgraphIntrospectionConfig::load_all(contents, None, Some("prod")).unwrap();
+        assert_that!(configs.get("products")).is_none();
+    }
+
+    #[test]
+    fn test_load_all_rejects_malformed_settings() {
+        let contents = indoc! {r#"
+            subgraphs:
+              products:
+                introspection:
+                  poll_interval_seconds: often
+        "#};
+        assert_that!(SubgraphIntrospectionConfig::load_all(contents, None, None)).is_err();
+    }
+
+    #[test]
+    fn test_policy_overrides_defaults() {
+        let defaults = IntrospectionPolicy {
+            timeout: Some(Duration::from_secs(10)),
+            retries: 1,
+            mode: IntrospectionMode::Post,
+            auth: None,
+        };
+        let config = SubgraphIntrospectionConfig {
+            retries: Some(4),
+            mode: Some(IntrospectionMode::Get),
+            ..Default::default()
+        };
+        assert_that!(config.policy(defaults.clone())).is_equal_to(IntrospectionPolicy {
+            timeout: Some(Duration::from_secs(10)),
+            retries: 4,
+            mode: IntrospectionMode::Get,
+            auth: None,
+        });
+        assert_that!(SubgraphIntrospectionConfig::default().policy(defaults.clone()))
+            .is_equal_to(defaults);
+    }
+
+    #[test]
+    fn test_apply_headers_overrides_introspection_headers() {
+        let config = SubgraphIntrospectionConfig {
+            headers: Some(HashMap::from([
+                ("Authorization".to_string(), "Bearer new".to_string()),
+                ("X-Team".to_string(), "products".to_string()),
+            ])),
+            ..Default::default()
+        };
+        let subgraph_url = Url::parse("http://localhost:4001").unwrap();
+        let schema = config.apply_headers(SchemaSource::SubgraphIntrospection {
+            subgraph_url: subgraph_url.clone(),
+            introspection_headers: Some(HashMap::from([
+                ("Authorization".to_string(), "Bearer old".to_string()),
+                ("X-Client".to_string(), "rover".to_string()),
+            ])),
+        });
+        assert_that!(schema).is_equal_to(SchemaSource::SubgraphIntrospection {
+            subgraph_url,
+            introspection_headers: Some(HashMap::from([
+                ("Authorization".to_string(), "Bearer new".to_string()),
+                ("X-Client".to_string(), "rover".to_string()),
+                ("X-Team".to_string(), "products".to_string()),
+            ])),
+        });
+
+        let sdl = SchemaSource::Sdl {
+            sdl: "type Query { a: String }".to_string(),
+        };
+        assert_that!(config.apply_headers(sdl.clone())).is_equal_to(sdl);
+    }
+}
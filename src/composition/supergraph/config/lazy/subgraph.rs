@@ -4,7 +4,8 @@ use camino::Utf8PathBuf;
 use derive_getters::Getters;
 
 use crate::composition::supergraph::config::{
-    error::ResolveSubgraphError, unresolved::UnresolvedSubgraph,
+    error::ResolveSubgraphError, introspection::SubgraphIntrospectionConfig,
+    unresolved::UnresolvedSubgraph,
 };
 
 /// A subgraph config that has had its file paths validated and
@@ -13,6 +14,8 @@ use crate::composition::supergraph::config::{
 pub struct LazilyResolvedSubgraph {
     routing_url: Option<String>,
     schema: SchemaSource,
+    /// How to poll the subgraph when its schema comes from introspection
+    introspection: Option<SubgraphIntrospectionConfig>,
 }
 
 impl LazilyResolvedSubgraph {
@@ -28,11 +31,13 @@ impl LazilyResolvedSubgraph {
                 Ok(LazilyResolvedSubgraph {
                     routing_url: unresolved_subgraph.routing_url().clone(),
                     schema: SchemaSource::File { file },
+                    introspection: unresolved_subgraph.introspection().clone(),
                 })
             }
             _ => Ok(LazilyResolvedSubgraph {
                 routing_url: unresolved_subgraph.routing_url().clone(),
                 schema: unresolved_subgraph.schema().clone(),
+                introspection: unresolved_subgraph.introspection().clone(),
             }),
         }
     }
@@ -18,8 +18,13 @@ use super::LazilyResolvedSubgraph;
 #[derive(Clone, Debug, Eq, PartialEq, Getters)]
 pub struct LazilyResolvedSupergraphConfig {
     origin_path: Option<Utf8PathBuf>,
+    /// The supergraph configs that the one at `origin_path` extends or includes
+    included_paths: Vec<Utf8PathBuf>,
     subgraphs: BTreeMap<String, LazilyResolvedSubgraph>,
     federation_version: Option<FederationVersion>,
+    /// The environment routing URLs were picked for, so that they're picked the same way when the
+    /// supergraph config changes
+    environment: Option<String>,
 }
 
 impl LazilyResolvedSupergraphConfig {
@@ -48,8 +53,10 @@ impl LazilyResolvedSupergraphConfig {
         if errors.is_empty() {
             Ok(LazilyResolvedSupergraphConfig {
                 origin_path: unresolved_supergraph_config.origin_path().clone(),
+                included_paths: unresolved_supergraph_config.included_paths().clone(),
                 subgraphs: BTreeMap::from_iter(subgraphs),
                 federation_version: unresolved_supergraph_config.federation_version().clone(),
+                environment: unresolved_supergraph_config.environment().clone(),
             })
         } else {
             Err(errors)
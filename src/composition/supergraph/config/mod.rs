@@ -2,13 +2,18 @@
 
 #![warn(missing_docs)]
 
+pub mod editor;
 pub mod error;
+pub mod extended;
 pub mod full;
+pub mod introspection;
 pub mod lazy;
 pub mod resolver;
 #[cfg(test)]
 pub mod scenario;
+pub mod source_map;
 pub mod unresolved;
+pub mod validation;
 
 #[cfg(test)]
 mod tests {
@@ -12,11 +12,9 @@
 //!         from [`SupergraphBinary`]. This must be written to a file first, using the format defined
 //!         by [`SupergraphConfig`]
 
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, time::Duration};
 
-use apollo_federation_types::config::{
-    ConfigError, FederationVersion, SubgraphConfig, SupergraphConfig,
-};
+use apollo_federation_types::config::{ConfigError, FederationVersion};
 use camino::Utf8PathBuf;
 use rover_client::shared::GraphRef;
 
@@ -24,7 +22,8 @@ use crate::{
     utils::{
         effect::{
             fetch_remote_subgraph::FetchRemoteSubgraph,
-            fetch_remote_subgraphs::FetchRemoteSubgraphs, introspect::IntrospectSubgraph,
+            fetch_remote_subgraphs::FetchRemoteSubgraphs,
+            introspect::{IntrospectSubgraph, IntrospectionPolicy},
             read_stdin::ReadStdin,
         },
         parsers::FileDescriptorType,
@@ -35,8 +34,19 @@ use crate::{
 use self::state::ResolveSubgraphs;
 
 use super::{
-    error::ResolveSubgraphError, full::FullyResolvedSupergraphConfig,
-    lazy::LazilyResolvedSupergraphConfig, unresolved::UnresolvedSupergraphConfig,
+    error::{ResolveSubgraphError, ResolveSubgraphErrorReport},
+    extended::parse_supergraph_config,
+    full::FullyResolvedSupergraphConfig,
+    introspection::SubgraphIntrospectionConfig,
+    lazy::LazilyResolvedSupergraphConfig,
+    source_map::SupergraphConfigSourceMap,
+    unresolved::{
+        merge_subgraph_configs, IncludeSupergraphConfigError, IncludedSupergraphConfigs,
+        UnresolvedSupergraphConfig,
+    },
+    validation::{
+        validate_supergraph_config, SupergraphConfigValidation, SupergraphConfigValidationError,
+    },
 };
 
 mod state;
@@ -119,6 +129,18 @@ pub enum LoadSupergraphConfigError {
     /// IO error that occurs when the supergraph contents can't be access via File IO or Stdin
     #[error("Failed to read file descriptor. Error: {0}")]
     ReadFileDescriptor(RoverError),
+    /// Occurs when a supergraph config that this one extends or includes can't be loaded
+    #[error(transparent)]
+    Include(IncludeSupergraphConfigError),
+    /// Occurs when the supergraph config parses, but doesn't pass validation
+    #[error(
+        "The supergraph config is invalid:\n{}",
+        .0.iter().map(|err| format!("  {}", err)).collect::<Vec<_>>().join("\n")
+    )]
+    Invalid(Vec<SupergraphConfigValidationError>),
+    /// Occurs when the `introspection` settings of a subgraph can't be parsed
+    #[error("Failed to parse the introspection settings of a subgraph. Error: {0}")]
+    Introspection(serde_yaml::Error),
 }
 
 impl SupergraphConfigResolver<state::LoadSupergraphConfig> {
@@ -128,48 +150,78 @@ impl SupergraphConfigResolver<state::LoadSupergraphConfig> {
         self,
         read_stdin_impl: &mut impl ReadStdin,
         file_descriptor_type: Option<&FileDescriptorType>,
+    ) -> Result<SupergraphConfigResolver<ResolveSubgraphs>, LoadSupergraphConfigError> {
+        self.load_from_file_descriptor_with_validation(
+            read_stdin_impl,
+            file_descriptor_type,
+            SupergraphConfigValidation::default(),
+            None,
+        )
+    }
+
+    /// Optionally loads the file from a specified [`FileDescriptorType`], validating it with the
+    /// checks that `validation` asks for before any of its subgraphs are resolved. Routing URLs
+    /// given per environment are picked for `environment`
+    pub fn load_from_file_descriptor_with_validation(
+        self,
+        read_stdin_impl: &mut impl ReadStdin,
+        file_descriptor_type: Option<&FileDescriptorType>,
+        validation: SupergraphConfigValidation,
+        environment: Option<&str>,
     ) -> Result<SupergraphConfigResolver<ResolveSubgraphs>, LoadSupergraphConfigError> {
         if let Some(file_descriptor_type) = file_descriptor_type {
-            let supergraph_config = file_descriptor_type
+            let contents = file_descriptor_type
                 .read_file_descriptor("supergraph config", read_stdin_impl)
-                .map_err(LoadSupergraphConfigError::ReadFileDescriptor)
-                .and_then(|contents| {
-                    SupergraphConfig::new_from_yaml(&contents)
-                        .map_err(LoadSupergraphConfigError::SupergraphConfig)
-                })?;
+                .map_err(LoadSupergraphConfigError::ReadFileDescriptor)?;
             let origin_path = match file_descriptor_type {
                 FileDescriptorType::File(file) => Some(file.clone()),
                 FileDescriptorType::Stdin => None,
             };
+            let source_map = SupergraphConfigSourceMap::new(&contents, origin_path.clone());
+            // Subgraphs from GraphOS are overridden by the ones in included supergraph configs,
+            // which are in turn overridden by the ones in this supergraph config
+            let included =
+                IncludedSupergraphConfigs::load(&contents, origin_path.as_ref(), environment)
+                    .map_err(LoadSupergraphConfigError::Include)?;
+            let mut merged_subgraphs = self.state.subgraphs;
+            merge_subgraph_configs(&mut merged_subgraphs, included.subgraphs().clone());
+            validate_supergraph_config(&contents, &source_map, &merged_subgraphs, validation)
+                .map_err(LoadSupergraphConfigError::Invalid)?;
+            let supergraph_config =
+                parse_supergraph_config(&contents, origin_path.as_deref(), environment)
+                    .map_err(LoadSupergraphConfigError::SupergraphConfig)?;
+            let introspection_configs = included.merge_introspection_configs(
+                &supergraph_config,
+                SubgraphIntrospectionConfig::load_all(&contents, origin_path.as_ref(), environment)
+                    .map_err(LoadSupergraphConfigError::Introspection)?,
+            );
             let federation_version = self
                 .state
                 .federation_version
-                .or_else(|| supergraph_config.get_federation_version());
-            let mut merged_subgraphs = self.state.subgraphs;
-            for (name, subgraph_config) in supergraph_config.into_iter() {
-                let subgraph_config = SubgraphConfig {
-                    routing_url: subgraph_config.routing_url.or_else(|| {
-                        merged_subgraphs
-                            .get(&name)
-                            .and_then(|remote_config| remote_config.routing_url.clone())
-                    }),
-                    schema: subgraph_config.schema,
-                };
-                merged_subgraphs.insert(name, subgraph_config);
-            }
+                .or_else(|| supergraph_config.get_federation_version())
+                .or_else(|| included.federation_version().clone());
+            merge_subgraph_configs(&mut merged_subgraphs, supergraph_config);
             Ok(SupergraphConfigResolver {
                 state: ResolveSubgraphs {
                     origin_path,
+                    included_paths: included.paths().clone(),
+                    source_map: Some(source_map),
                     federation_version,
                     subgraphs: merged_subgraphs,
+                    introspection_configs,
+                    environment: environment.map(str::to_string),
                 },
             })
         } else {
             Ok(SupergraphConfigResolver {
                 state: ResolveSubgraphs {
                     origin_path: None,
+                    included_paths: Vec::new(),
+                    source_map: None,
                     federation_version: self.state.federation_version,
                     subgraphs: self.state.subgraphs,
+                    introspection_configs: BTreeMap::new(),
+                    environment: environment.map(str::to_string),
                 },
             })
         }
@@ -184,7 +236,7 @@ pub enum ResolveSupergraphConfigError {
     NoSource,
     /// Occurs when the underlying resolver strategy can't resolve one or more
     /// of the subgraphs described in the supergraph config
-    #[error("Unable to resolve subgraphs")]
+    #[error("{}", ResolveSubgraphErrorReport::new(.0))]
     ResolveSubgraphs(Vec<ResolveSubgraphError>),
     /// Occurs when the user-selected `FederationVersion` is within Federation 1 boundaries, but the
     /// subgraphs use the `@link` directive, which requires Federation 2
@@ -201,6 +253,81 @@ pub enum ResolveSupergraphConfigError {
     },
 }
 
+/// Determines what happens when some, but not all, of the subgraphs in a supergraph config can be
+/// resolved
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SubgraphResolutionPolicy {
+    /// Fail resolution if any subgraph can't be resolved
+    #[default]
+    RequireAll,
+    /// Carry on with the subgraphs that can be resolved, reporting the ones that can't. Resolution
+    /// still fails when none of the subgraphs can be resolved
+    AllowPartial,
+}
+
+/// Determines how often a subgraph whose schema comes over the network (via introspection or
+/// GraphOS) is retried before it counts as unresolvable
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SubgraphRetryPolicy {
+    /// How many times to retry after the first attempt fails
+    pub max_retries: u32,
+    /// How long to wait before the first retry. Every following retry waits twice as long as the
+    /// one before it, up to [`SubgraphRetryPolicy::MAX_BACKOFF`]
+    pub initial_backoff: Duration,
+}
+
+impl SubgraphRetryPolicy {
+    /// The longest a single retry waits, however many retries came before it
+    pub const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+    /// Creates a policy that retries up to `max_retries` times, with the default initial backoff
+    pub fn new(max_retries: u32) -> SubgraphRetryPolicy {
+        SubgraphRetryPolicy {
+            max_retries,
+            ..Default::default()
+        }
+    }
+
+    /// How long to wait before the given retry, counting from zero
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(Self::MAX_BACKOFF)
+    }
+}
+
+impl Default for SubgraphRetryPolicy {
+    fn default() -> Self {
+        SubgraphRetryPolicy {
+            max_retries: 0,
+            initial_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Everything that decides how subgraphs are fully resolved, beyond where they come from
+#[derive(Clone, Debug, Default)]
+pub struct SubgraphResolutionOptions {
+    /// What to do when some of the subgraphs can't be resolved
+    pub policy: SubgraphResolutionPolicy,
+    /// How subgraphs that fail to resolve over the network are retried
+    pub retry_policy: SubgraphRetryPolicy,
+    /// Whether subgraphs whose schemas can only be resolved over the network are refused rather
+    /// than resolved
+    pub offline: bool,
+    /// How subgraphs are introspected, unless their own introspection settings say otherwise
+    pub introspection_policy: IntrospectionPolicy,
+}
+
+/// The subgraphs that couldn't be resolved from their configured source, by name
+#[derive(Debug, Default)]
+pub struct SubgraphResolutionReport {
+    /// Subgraphs that [`SubgraphResolutionPolicy::AllowPartial`] left out of the result
+    pub failures: BTreeMap<String, ResolveSubgraphError>,
+    /// How long resolving each subgraph took, including retries, whether or not it succeeded
+    pub resolution_times: BTreeMap<String, Duration>,
+}
+
 impl SupergraphConfigResolver<ResolveSubgraphs> {
     /// Fully resolves the subgraph configurations in the supergraph config file to their SDLs
     pub async fn fully_resolve_subgraphs(
@@ -209,19 +336,91 @@ impl SupergraphConfigResolver<ResolveSubgraphs> {
         fetch_remote_subgraph_impl: &impl FetchRemoteSubgraph,
         supergraph_config_root: Option<&Utf8PathBuf>,
     ) -> Result<FullyResolvedSupergraphConfig, ResolveSupergraphConfigError> {
-        if !self.state.subgraphs.is_empty() {
-            let unresolved_supergraph_config = UnresolvedSupergraphConfig::builder()
-                .subgraphs(self.state.subgraphs.clone())
-                .and_federation_version(self.state.federation_version.clone())
-                .build();
-            let resolved_supergraph_config = FullyResolvedSupergraphConfig::resolve(
+        let (resolved_supergraph_config, _) = self
+            .fully_resolve_subgraphs_with_policy(
                 introspect_subgraph_impl,
                 fetch_remote_subgraph_impl,
                 supergraph_config_root,
-                unresolved_supergraph_config,
+                SubgraphResolutionPolicy::RequireAll,
             )
             .await?;
-            Ok(resolved_supergraph_config)
+        Ok(resolved_supergraph_config)
+    }
+
+    /// Fully resolves the subgraph configurations in the supergraph config file to their SDLs,
+    /// returning any subgraphs that [`SubgraphResolutionPolicy::AllowPartial`] left out by name
+    pub async fn fully_resolve_subgraphs_with_policy(
+        &self,
+        introspect_subgraph_impl: &impl IntrospectSubgraph,
+        fetch_remote_subgraph_impl: &impl FetchRemoteSubgraph,
+        supergraph_config_root: Option<&Utf8PathBuf>,
+        policy: SubgraphResolutionPolicy,
+    ) -> Result<
+        (
+            FullyResolvedSupergraphConfig,
+            BTreeMap<String, ResolveSubgraphError>,
+        ),
+        ResolveSupergraphConfigError,
+    > {
+        let (resolved_supergraph_config, report) = self
+            .fully_resolve_subgraphs_with_options(
+                introspect_subgraph_impl,
+                fetch_remote_subgraph_impl,
+                supergraph_config_root,
+                &SubgraphResolutionOptions {
+                    policy,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        Ok((resolved_supergraph_config, report.failures))
+    }
+
+    /// Fully resolves the subgraph configurations in the supergraph config file to their SDLs,
+    /// retrying them as the [`SubgraphResolutionOptions`] allow
+    pub async fn fully_resolve_subgraphs_with_options(
+        &self,
+        introspect_subgraph_impl: &impl IntrospectSubgraph,
+        fetch_remote_subgraph_impl: &impl FetchRemoteSubgraph,
+        supergraph_config_root: Option<&Utf8PathBuf>,
+        options: &SubgraphResolutionOptions,
+    ) -> Result<
+        (FullyResolvedSupergraphConfig, SubgraphResolutionReport),
+        ResolveSupergraphConfigError,
+    > {
+        if !self.state.subgraphs.is_empty() {
+            let unresolved_supergraph_config = UnresolvedSupergraphConfig::builder()
+                .and_origin_path(self.state.origin_path.clone())
+                .included_paths(self.state.included_paths.clone())
+                .subgraphs(self.state.subgraphs.clone())
+                .introspection_configs(self.state.introspection_configs.clone())
+                .and_federation_version(self.state.federation_version.clone())
+                .and_environment(self.state.environment.clone())
+                .build();
+            let (resolved_supergraph_config, report) =
+                FullyResolvedSupergraphConfig::resolve_with_options(
+                    introspect_subgraph_impl,
+                    fetch_remote_subgraph_impl,
+                    supergraph_config_root,
+                    unresolved_supergraph_config,
+                    options,
+                )
+                .await
+                .map_err(|err| self.locate_errors(err))?;
+            let locate = |errors: BTreeMap<String, ResolveSubgraphError>| {
+                errors
+                    .into_iter()
+                    .map(|(name, err)| match &self.state.source_map {
+                        Some(source_map) => (name, err.locate(source_map)),
+                        None => (name, err),
+                    })
+                    .collect()
+            };
+            let report = SubgraphResolutionReport {
+                failures: locate(report.failures),
+                resolution_times: report.resolution_times,
+            };
+            Ok((resolved_supergraph_config, report))
         } else {
             Err(ResolveSupergraphConfigError::NoSource)
         }
@@ -236,18 +435,39 @@ impl SupergraphConfigResolver<ResolveSubgraphs> {
     ) -> Result<LazilyResolvedSupergraphConfig, ResolveSupergraphConfigError> {
         if !self.state.subgraphs.is_empty() {
             let unresolved_supergraph_config = UnresolvedSupergraphConfig::builder()
+                .and_origin_path(self.state.origin_path.clone())
+                .included_paths(self.state.included_paths.clone())
                 .subgraphs(self.state.subgraphs.clone())
+                .introspection_configs(self.state.introspection_configs.clone())
                 .and_federation_version(self.state.federation_version.clone())
+                .and_environment(self.state.environment.clone())
                 .build();
             let resolved_supergraph_config = LazilyResolvedSupergraphConfig::resolve(
                 supergraph_config_root,
                 unresolved_supergraph_config,
             )
             .await
-            .map_err(ResolveSupergraphConfigError::ResolveSubgraphs)?;
+            .map_err(ResolveSupergraphConfigError::ResolveSubgraphs)
+            .map_err(|err| self.locate_errors(err))?;
             Ok(resolved_supergraph_config)
         } else {
             Err(ResolveSupergraphConfigError::NoSource)
         }
     }
+
+    /// Points any subgraph resolution errors at their position in the supergraph config file,
+    /// when the config was loaded from one
+    fn locate_errors(&self, error: ResolveSupergraphConfigError) -> ResolveSupergraphConfigError {
+        match (error, &self.state.source_map) {
+            (ResolveSupergraphConfigError::ResolveSubgraphs(errors), Some(source_map)) => {
+                ResolveSupergraphConfigError::ResolveSubgraphs(
+                    errors
+                        .into_iter()
+                        .map(|err| err.locate(source_map))
+                        .collect(),
+                )
+            }
+            (error, _) => error,
+        }
+    }
 }
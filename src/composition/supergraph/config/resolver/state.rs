@@ -3,6 +3,10 @@ use std::collections::BTreeMap;
 use apollo_federation_types::config::{FederationVersion, SubgraphConfig};
 use camino::Utf8PathBuf;
 
+use crate::composition::supergraph::config::{
+    introspection::SubgraphIntrospectionConfig, source_map::SupergraphConfigSourceMap,
+};
+
 /// In this stage, we await the caller to optionally load subgraphs from the Studio API using
 /// the contents of the `--graph-ref` flag
 pub struct LoadRemoteSubgraphs {
@@ -20,6 +24,10 @@ pub struct LoadSupergraphConfig {
 /// and exist) or fully: rendering the subgraph source down to an SDL
 pub struct ResolveSubgraphs {
     pub origin_path: Option<Utf8PathBuf>,
+    pub included_paths: Vec<Utf8PathBuf>,
+    pub source_map: Option<SupergraphConfigSourceMap>,
     pub federation_version: Option<FederationVersion>,
     pub subgraphs: BTreeMap<String, SubgraphConfig>,
+    pub introspection_configs: BTreeMap<String, SubgraphIntrospectionConfig>,
+    pub environment: Option<String>,
 }
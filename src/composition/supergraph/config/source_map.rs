@@ -0,0 +1,286 @@
+//! Provides a lightweight index of where keys live in a supergraph config file, so that errors
+//! originating from the config can point at an exact line and column rather than only naming
+//! the subgraph they belong to
+//!
+//! `serde_yaml` doesn't report where the values it deserializes came from, so the index is built
+//! by scanning the file line by line with [`crate::utils::yaml_lines`]. That's enough for supergraph configs, which are written as
+//! block mappings, but only a subset of YAML is understood:
+//!
+//! - block mappings, at any consistent indentation
+//! - flow mappings that open and close on the same line, like `schema: { file: ./a.graphql }`
+//! - block scalars (`|` and `>`), whose contents are skipped
+//! - comments and blank lines
+//!
+//! Keys in flow mappings spread over several lines, inside sequences, behind anchors and aliases,
+//! or written as complex keys (`? key`) aren't indexed. Looking one of them up returns `None`, so
+//! an error about it names the subgraph without a location instead of pointing at the wrong line.
+//! Files with more than one document never get this far, since `serde_yaml` rejects them
+
+use std::{collections::HashMap, fmt};
+
+use camino::Utf8PathBuf;
+use derive_getters::Getters;
+use serde::Serialize;
+
+use crate::utils::yaml_lines::{indent_of, split_key};
+
+/// A 1-based position within a supergraph config file
+#[derive(Clone, Debug, Eq, PartialEq, Getters, Serialize)]
+pub struct SourceLocation {
+    path: Option<Utf8PathBuf>,
+    line: usize,
+    column: usize,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}:{}:{}", path, self.line, self.column),
+            None => write!(f, "line {}, column {}", self.line, self.column),
+        }
+    }
+}
+
+/// The keys of a subgraph entry in a supergraph config that errors may originate from
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SubgraphConfigKey {
+    /// `subgraphs.<name>.routing_url`
+    RoutingUrl,
+    /// `subgraphs.<name>.schema`
+    Schema,
+    /// `subgraphs.<name>.schema.file`
+    SchemaFile,
+    /// `subgraphs.<name>.schema.subgraph_url`
+    SchemaSubgraphUrl,
+    /// `subgraphs.<name>.schema.url`
+    SchemaUrl,
+    /// `subgraphs.<name>.schema.graphref`
+    SchemaGraphRef,
+    /// `subgraphs.<name>.schema.subgraph`
+    SchemaSubgraph,
+}
+
+impl SubgraphConfigKey {
+    fn path(&self) -> &'static [&'static str] {
+        match self {
+            SubgraphConfigKey::RoutingUrl => &["routing_url"],
+            SubgraphConfigKey::Schema => &["schema"],
+            SubgraphConfigKey::SchemaFile => &["schema", "file"],
+            SubgraphConfigKey::SchemaSubgraphUrl => &["schema", "subgraph_url"],
+            SubgraphConfigKey::SchemaUrl => &["schema", "url"],
+            SubgraphConfigKey::SchemaGraphRef => &["schema", "graphref"],
+            SubgraphConfigKey::SchemaSubgraph => &["schema", "subgraph"],
+        }
+    }
+}
+
+/// Positions of every mapping key found in a supergraph config, indexed by their key path
+/// (eg, `["subgraphs", "products", "routing_url"]`)
+///
+/// This intentionally understands only the subset of YAML that supergraph configs are written
+/// in (block mappings, single-line flow mappings, and block scalars); keys it can't find simply
+/// don't produce a location
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SupergraphConfigSourceMap {
+    origin_path: Option<Utf8PathBuf>,
+    positions: HashMap<Vec<String>, (usize, usize)>,
+}
+
+impl SupergraphConfigSourceMap {
+    /// Indexes the contents of a supergraph config, optionally remembering the file it came from
+    pub fn new(contents: &str, origin_path: Option<Utf8PathBuf>) -> SupergraphConfigSourceMap {
+        let mut positions = HashMap::new();
+        let mut parents: Vec<(usize, String)> = Vec::new();
+        let mut block_scalar_indent: Option<usize> = None;
+
+        for (line_idx, line) in contents.lines().enumerate() {
+            let trimmed = line.trim_start();
+            let indent = indent_of(line);
+
+            // Lines belonging to a block scalar (eg, `sdl: |`) are values, not keys
+            if let Some(scalar_indent) = block_scalar_indent {
+                if trimmed.is_empty() || indent > scalar_indent {
+                    continue;
+                }
+                block_scalar_indent = None;
+            }
+
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("---") {
+                continue;
+            }
+
+            let Some((key, value_offset)) = split_key(trimmed) else {
+                continue;
+            };
+
+            while parents
+                .last()
+                .is_some_and(|(parent_indent, _)| *parent_indent >= indent)
+            {
+                parents.pop();
+            }
+            parents.push((indent, key));
+
+            let path: Vec<String> = parents.iter().map(|(_, key)| key.clone()).collect();
+            positions.insert(path.clone(), (line_idx + 1, indent + 1));
+
+            let value = &trimmed[value_offset..];
+            let value_start = indent + value_offset + (value.len() - value.trim_start().len());
+            let value = value.trim();
+            if value.starts_with('|') || value.starts_with('>') {
+                block_scalar_indent = Some(indent);
+            } else if value.starts_with('{') {
+                index_flow_mapping(line, value_start, line_idx + 1, &path, &mut positions);
+            }
+        }
+
+        SupergraphConfigSourceMap {
+            origin_path,
+            positions,
+        }
+    }
+
+    /// Finds the position of a key path within the supergraph config
+    pub fn locate(&self, key_path: &[&str]) -> Option<SourceLocation> {
+        let key_path: Vec<String> = key_path.iter().map(|key| key.to_string()).collect();
+        self.positions
+            .get(&key_path)
+            .map(|(line, column)| SourceLocation {
+                path: self.origin_path.clone(),
+                line: *line,
+                column: *column,
+            })
+    }
+
+    /// Finds the position of a key belonging to a subgraph entry, falling back to the position
+    /// of the subgraph entry itself when that particular key can't be found
+    pub fn locate_subgraph(
+        &self,
+        subgraph_name: &str,
+        key: Option<SubgraphConfigKey>,
+    ) -> Option<SourceLocation> {
+        key.and_then(|key| {
+            let mut key_path = vec!["subgraphs", subgraph_name];
+            key_path.extend_from_slice(key.path());
+            self.locate(&key_path)
+        })
+        .or_else(|| self.locate(&["subgraphs", subgraph_name]))
+    }
+}
+
+/// Indexes the keys of a single-line flow mapping, such as `schema: { file: ./schema.graphql }`
+fn index_flow_mapping(
+    line: &str,
+    open_brace: usize,
+    line_number: usize,
+    parent_path: &[String],
+    positions: &mut HashMap<Vec<String>, (usize, usize)>,
+) {
+    let mut depth = 0;
+    let mut entry_start = open_brace + 1;
+    for (idx, c) in line.char_indices().skip_while(|(idx, _)| *idx < open_brace) {
+        let entry_end = match c {
+            '{' => {
+                depth += 1;
+                None
+            }
+            '}' => {
+                depth -= 1;
+                (depth == 0).then_some(idx)
+            }
+            ',' if depth == 1 => Some(idx),
+            _ => None,
+        };
+        if let Some(entry_end) = entry_end {
+            let entry = &line[entry_start..entry_end];
+            let trimmed = entry.trim_start();
+            if let Some((key, _)) = split_key(trimmed) {
+                let column = entry_start + (entry.len() - trimmed.len()) + 1;
+                let mut path = parent_path.to_vec();
+                path.push(key);
+                positions.insert(path, (line_number, column));
+            }
+            entry_start = idx + 1;
+            if depth == 0 {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8PathBuf;
+    use indoc::indoc;
+    use rstest::{fixture, rstest};
+    use speculoos::prelude::*;
+
+    use super::{SourceLocation, SubgraphConfigKey, SupergraphConfigSourceMap};
+
+    #[fixture]
+    fn supergraph_config() -> &'static str {
+        indoc! {r#"
+            # a comment: with a colon
+            federation_version: =2.9.0
+            subgraphs:
+              products:
+                routing_url: http://localhost:4001
+                schema:
+                  file: ./products.graphql
+              "reviews":
+                routing_url: http://localhost:4002
+                schema: { subgraph_url: http://localhost:4002, introspection_headers: { a: b } }
+              inline:
+                schema:
+                  sdl: |
+                    type Query {
+                      file: String
+                    }
+              remote:
+                schema:
+                  graphref: mygraph@current
+                  subgraph: remote
+        "#}
+    }
+
+    #[rstest]
+    #[case::block_key("products", Some(SubgraphConfigKey::SchemaFile), (7, 7))]
+    #[case::routing_url("products", Some(SubgraphConfigKey::RoutingUrl), (5, 5))]
+    #[case::quoted_subgraph_name("reviews", Some(SubgraphConfigKey::RoutingUrl), (9, 5))]
+    #[case::flow_mapping("reviews", Some(SubgraphConfigKey::SchemaSubgraphUrl), (10, 15))]
+    #[case::graph_ref("remote", Some(SubgraphConfigKey::SchemaGraphRef), (19, 7))]
+    #[case::falls_back_to_subgraph("inline", Some(SubgraphConfigKey::SchemaFile), (11, 3))]
+    #[case::subgraph_entry("remote", None, (17, 3))]
+    fn test_locate_subgraph(
+        supergraph_config: &str,
+        #[case] subgraph_name: &str,
+        #[case] key: Option<SubgraphConfigKey>,
+        #[case] expected: (usize, usize),
+    ) {
+        let path = Utf8PathBuf::from("supergraph.yaml");
+        let source_map = SupergraphConfigSourceMap::new(supergraph_config, Some(path.clone()));
+        let location = source_map.locate_subgraph(subgraph_name, key);
+        assert_that!(location)
+            .is_some()
+            .is_equal_to(SourceLocation {
+                path: Some(path),
+                line: expected.0,
+                column: expected.1,
+            });
+    }
+
+    #[rstest]
+    fn test_block_scalar_contents_are_not_keys(supergraph_config: &str) {
+        let source_map = SupergraphConfigSourceMap::new(supergraph_config, None);
+        assert_that!(source_map.locate(&["subgraphs", "inline", "schema", "sdl", "file"]))
+            .is_none();
+        assert_that!(source_map.locate_subgraph("missing", None)).is_none();
+    }
+
+    #[rstest]
+    fn test_display_without_path(supergraph_config: &str) {
+        let source_map = SupergraphConfigSourceMap::new(supergraph_config, None);
+        let location = source_map.locate(&["federation_version"]).unwrap();
+        assert_that!(location.to_string()).is_equal_to("line 2, column 1".to_string());
+    }
+}
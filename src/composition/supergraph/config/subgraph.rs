@@ -180,10 +180,11 @@ mod tests {
             .with(
                 predicate::eq(url::Url::from_str(&routing_url)?),
                 predicate::eq(introspection_headers),
+            predicate::eq(None),
             )
             .returning({
                 let sdl = sdl.to_string();
-                move |_, _| Ok(sdl.to_string())
+                move |_, _, _| Ok(sdl.to_string())
             });
 
         let mut mock_fetch_remote_subgraph = MockFetchRemoteSubgraph::new();
@@ -302,6 +303,7 @@ mod tests {
                     .unwrap()
                     .canonicalize_utf8()?,
                 },
+                introspection: None,
             });
         Ok(())
     }
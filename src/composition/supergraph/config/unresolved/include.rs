@@ -0,0 +1,402 @@
+//! Lets a supergraph config build on other supergraph configs, so that subgraph definitions shared
+//! across environments can live in one file. A supergraph config can declare a base config with
+//! `extends: <path>`, a list of configs with `include: [<path>, ...]`, or both
+
+use std::collections::BTreeMap;
+
+use apollo_federation_types::config::{
+    ConfigError, FederationVersion, SchemaSource, SubgraphConfig, SupergraphConfig,
+};
+use camino::{Utf8Path, Utf8PathBuf};
+use derive_getters::Getters;
+use itertools::Itertools;
+use rover_std::Fs;
+use serde::Deserialize;
+
+use crate::composition::supergraph::config::{
+    extended::parse_supergraph_config,
+    introspection::SubgraphIntrospectionConfig,
+    source_map::SupergraphConfigSourceMap,
+    validation::{
+        validate_supergraph_config, SupergraphConfigValidation, SupergraphConfigValidationError,
+    },
+};
+
+/// The keys of a supergraph config that point at other supergraph configs
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct SupergraphConfigIncludes {
+    extends: Option<Utf8PathBuf>,
+    #[serde(default)]
+    include: Vec<Utf8PathBuf>,
+}
+
+/// Errors that may occur while loading the supergraph configs that another one extends or includes
+#[derive(thiserror::Error, Debug)]
+pub enum IncludeSupergraphConfigError {
+    /// Occurs when a supergraph config ends up extending or including itself
+    #[error("Supergraph config includes itself: {}", chain.iter().join(" -> "))]
+    Cycle {
+        /// Every supergraph config along the way, starting and ending with the same one
+        chain: Vec<Utf8PathBuf>,
+    },
+    /// Occurs when an included supergraph config can't be read
+    #[error("Failed to read included supergraph config {path}: {source}")]
+    Read {
+        /// The path to the included supergraph config
+        path: Utf8PathBuf,
+        /// The source error
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// Occurs when an included supergraph config can't be parsed
+    #[error("Failed to parse included supergraph config {path}: {source}")]
+    Parse {
+        /// The path to the included supergraph config
+        path: Utf8PathBuf,
+        /// The source error
+        source: ConfigError,
+    },
+    /// Occurs when the introspection settings of an included supergraph config can't be parsed
+    #[error(
+        "Failed to parse the introspection settings of included supergraph config {path}: {source}"
+    )]
+    Introspection {
+        /// The path to the included supergraph config
+        path: Utf8PathBuf,
+        /// The source error
+        source: serde_yaml::Error,
+    },
+    /// Occurs when an included supergraph config parses, but doesn't pass validation
+    #[error(
+        "Included supergraph config {path} is invalid:\n{}",
+        errors.iter().map(|err| format!("  {}", err)).join("\n")
+    )]
+    Invalid {
+        /// The path to the included supergraph config
+        path: Utf8PathBuf,
+        /// Every problem found in it
+        errors: Vec<SupergraphConfigValidationError>,
+    },
+}
+
+/// Everything a supergraph config inherits from the supergraph configs it extends or includes,
+/// transitively
+#[derive(Clone, Debug, Default, Eq, PartialEq, Getters)]
+pub struct IncludedSupergraphConfigs {
+    subgraphs: BTreeMap<String, SubgraphConfig>,
+    /// The introspection settings of the included subgraphs, which also say where `url` and `git`
+    /// schemas come from
+    introspection_configs: BTreeMap<String, SubgraphIntrospectionConfig>,
+    federation_version: Option<FederationVersion>,
+    /// Every included supergraph config, so that changes to them can be watched for
+    paths: Vec<Utf8PathBuf>,
+}
+
+impl IncludedSupergraphConfigs {
+    /// Loads the supergraph configs that `contents` extends or includes. Their paths are relative
+    /// to the directory of `origin_path`, or the working directory if the supergraph config was
+    /// read from stdin
+    ///
+    /// Included configs are merged in order, starting with the one it `extends`, so later ones
+    /// override earlier ones. File paths in an included config stay relative to that config, and
+    /// their routing URLs are picked for `environment` like the including config's are
+    pub fn load(
+        contents: &str,
+        origin_path: Option<&Utf8PathBuf>,
+        environment: Option<&str>,
+    ) -> Result<IncludedSupergraphConfigs, IncludeSupergraphConfigError> {
+        let mut chain = Vec::new();
+        let dir = match origin_path {
+            Some(origin_path) => {
+                let origin_path = origin_path
+                    .canonicalize_utf8()
+                    .unwrap_or_else(|_| origin_path.clone());
+                let dir = parent_dir(&origin_path);
+                chain.push(origin_path);
+                dir
+            }
+            None => Utf8PathBuf::from("."),
+        };
+        let mut included = IncludedSupergraphConfigs::default();
+        included.load_into(contents, &dir, environment, &mut chain)?;
+        Ok(included)
+    }
+
+    /// Merges a supergraph config on top of everything it includes
+    pub fn merge(self, supergraph_config: SupergraphConfig) -> SupergraphConfig {
+        let federation_version = supergraph_config
+            .get_federation_version()
+            .or(self.federation_version);
+        let mut subgraphs = self.subgraphs;
+        merge_subgraph_configs(&mut subgraphs, supergraph_config);
+        SupergraphConfig::new(subgraphs, federation_version)
+    }
+
+    /// Merges the introspection settings of a supergraph config on top of the ones of the configs
+    /// it includes. A subgraph that `supergraph_config` defines only keeps the settings it has
+    /// there, since it replaces the included subgraph's schema source
+    pub fn merge_introspection_configs(
+        &self,
+        supergraph_config: &SupergraphConfig,
+        introspection_configs: BTreeMap<String, SubgraphIntrospectionConfig>,
+    ) -> BTreeMap<String, SubgraphIntrospectionConfig> {
+        let mut merged = self.introspection_configs.clone();
+        for (name, _) in supergraph_config.clone() {
+            merged.remove(&name);
+        }
+        merged.extend(introspection_configs);
+        merged
+    }
+
+    fn load_into(
+        &mut self,
+        contents: &str,
+        dir: &Utf8Path,
+        environment: Option<&str>,
+        chain: &mut Vec<Utf8PathBuf>,
+    ) -> Result<(), IncludeSupergraphConfigError> {
+        // Supergraph configs that can't be parsed are reported by whatever parses them next
+        let includes: SupergraphConfigIncludes = serde_yaml::from_str(contents).unwrap_or_default();
+
+        for path in includes.extends.into_iter().chain(includes.include) {
+            let path = canonicalize(&dir.join(path))?;
+            if chain.contains(&path) {
+                let mut chain = chain.clone();
+                chain.push(path);
+                return Err(IncludeSupergraphConfigError::Cycle { chain });
+            }
+
+            let contents =
+                Fs::read_file(&path).map_err(|source| IncludeSupergraphConfigError::Read {
+                    path: path.clone(),
+                    source: Box::new(source),
+                })?;
+            let source_map = SupergraphConfigSourceMap::new(&contents, Some(path.clone()));
+            validate_supergraph_config(
+                &contents,
+                &source_map,
+                &BTreeMap::new(),
+                SupergraphConfigValidation::default(),
+            )
+            .map_err(|errors| IncludeSupergraphConfigError::Invalid {
+                path: path.clone(),
+                errors,
+            })?;
+            let supergraph_config = parse_supergraph_config(&contents, Some(&path), environment)
+                .map_err(|source| IncludeSupergraphConfigError::Parse {
+                    path: path.clone(),
+                    source,
+                })?;
+            let introspection_configs =
+                SubgraphIntrospectionConfig::load_all(&contents, Some(&path), environment)
+                    .map_err(|source| IncludeSupergraphConfigError::Introspection {
+                        path: path.clone(),
+                        source,
+                    })?;
+
+            let included_dir = parent_dir(&path);
+            chain.push(path.clone());
+            self.load_into(&contents, &included_dir, environment, chain)?;
+            chain.pop();
+
+            if let Some(federation_version) = supergraph_config.get_federation_version() {
+                self.federation_version = Some(federation_version);
+            }
+            self.introspection_configs =
+                self.merge_introspection_configs(&supergraph_config, introspection_configs);
+            merge_subgraph_configs(
+                &mut self.subgraphs,
+                supergraph_config
+                    .into_iter()
+                    .map(|(name, subgraph)| (name, rebase_file_path(subgraph, &included_dir))),
+            );
+            if !self.paths.contains(&path) {
+                self.paths.push(path);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Merges `overrides` into `base`, replacing any subgraph with the same name. An overriding
+/// subgraph without a routing URL keeps the routing URL of the subgraph it replaces
+pub fn merge_subgraph_configs(
+    base: &mut BTreeMap<String, SubgraphConfig>,
+    overrides: impl IntoIterator<Item = (String, SubgraphConfig)>,
+) {
+    for (name, subgraph_config) in overrides {
+        let subgraph_config = SubgraphConfig {
+            routing_url: subgraph_config.routing_url.or_else(|| {
+                base.get(&name)
+                    .and_then(|base_config| base_config.routing_url.clone())
+            }),
+            schema: subgraph_config.schema,
+        };
+        base.insert(name, subgraph_config);
+    }
+}
+
+/// Makes a relative schema file path in an included supergraph config absolute, since it would
+/// otherwise be resolved relative to the supergraph config that includes it
+fn rebase_file_path(subgraph_config: SubgraphConfig, dir: &Utf8Path) -> SubgraphConfig {
+    match subgraph_config.schema {
+        SchemaSource::File { file } if file.is_relative() => SubgraphConfig {
+            schema: SchemaSource::File {
+                file: dir.join(file),
+            },
+            ..subgraph_config
+        },
+        _ => subgraph_config,
+    }
+}
+
+fn canonicalize(path: &Utf8Path) -> Result<Utf8PathBuf, IncludeSupergraphConfigError> {
+    path.canonicalize_utf8()
+        .map_err(|err| IncludeSupergraphConfigError::Read {
+            path: path.to_path_buf(),
+            source: Box::new(err),
+        })
+}
+
+fn parent_dir(path: &Utf8Path) -> Utf8PathBuf {
+    path.parent()
+        .map(Utf8Path::to_path_buf)
+        .unwrap_or_else(|| Utf8PathBuf::from("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use assert_fs::{prelude::*, TempDir};
+    use indoc::indoc;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    fn utf8_path(dir: &TempDir, path: &str) -> Utf8PathBuf {
+        Utf8PathBuf::from_path_buf(dir.child(path).path().to_path_buf())
+            .unwrap()
+            .canonicalize_utf8()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_merges_included_configs_in_order() {
+        let dir = TempDir::new().unwrap();
+        dir.child("shared/base.yaml")
+            .write_str(indoc! {r#"
+                federation_version: =2.8.0
+                subgraphs:
+                  products:
+                    routing_url: https://products.example.com
+                    schema:
+                      file: ./products.graphql
+                  reviews:
+                    routing_url: https://reviews.example.com
+                    schema:
+                      file: ./reviews.graphql
+            "#})
+            .unwrap();
+        dir.child("local.yaml")
+            .write_str(indoc! {r#"
+                subgraphs:
+                  reviews:
+                    schema:
+                      subgraph_url: http://localhost:4002
+            "#})
+            .unwrap();
+        let contents = indoc! {r#"
+            extends: ./shared/base.yaml
+            include:
+              - ./local.yaml
+            subgraphs:
+              products:
+                routing_url: http://localhost:4001
+                schema:
+                  file: ./products.graphql
+        "#};
+        dir.child("supergraph.yaml").write_str(contents).unwrap();
+        let origin_path = utf8_path(&dir, "supergraph.yaml");
+
+        let included = IncludedSupergraphConfigs::load(contents, Some(&origin_path), None).unwrap();
+        assert_that!(included.paths()).is_equal_to(&vec![
+            utf8_path(&dir, "shared/base.yaml"),
+            utf8_path(&dir, "local.yaml"),
+        ]);
+
+        let merged = included.merge(SupergraphConfig::new_from_yaml(contents).unwrap());
+        assert_that!(merged.get_federation_version())
+            .is_equal_to(Some(FederationVersion::from_str("=2.8.0").unwrap()));
+        let subgraphs: BTreeMap<String, SubgraphConfig> = merged.into_iter().collect();
+        assert_that!(subgraphs.get("products")).is_equal_to(Some(&SubgraphConfig {
+            routing_url: Some("http://localhost:4001".to_string()),
+            schema: SchemaSource::File {
+                file: Utf8PathBuf::from("./products.graphql"),
+            },
+        }));
+        assert_that!(subgraphs.get("reviews")).is_equal_to(Some(&SubgraphConfig {
+            routing_url: Some("https://reviews.example.com".to_string()),
+            schema: SchemaSource::SubgraphIntrospection {
+                subgraph_url: "http://localhost:4002".parse().unwrap(),
+                introspection_headers: None,
+            },
+        }));
+    }
+
+    #[test]
+    fn test_rebases_file_paths_of_included_configs() {
+        let dir = TempDir::new().unwrap();
+        dir.child("shared/base.yaml")
+            .write_str(indoc! {r#"
+                subgraphs:
+                  products:
+                    routing_url: https://products.example.com
+                    schema:
+                      file: ./products.graphql
+            "#})
+            .unwrap();
+        let contents = "extends: ./shared/base.yaml\nsubgraphs: {}\n";
+        dir.child("supergraph.yaml").write_str(contents).unwrap();
+
+        let included = IncludedSupergraphConfigs::load(
+            contents,
+            Some(&utf8_path(&dir, "supergraph.yaml")),
+            None,
+        )
+        .unwrap();
+        assert_that!(included.subgraphs().get("products").map(|s| &s.schema)).is_equal_to(Some(
+            &SchemaSource::File {
+                file: utf8_path(&dir, "shared").join("./products.graphql"),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_detects_cycles() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a.yaml")
+            .write_str("extends: ./b.yaml\nsubgraphs: {}\n")
+            .unwrap();
+        dir.child("b.yaml")
+            .write_str("include: [./a.yaml]\nsubgraphs: {}\n")
+            .unwrap();
+        let contents = "extends: ./a.yaml\nsubgraphs: {}\n";
+        dir.child("supergraph.yaml").write_str(contents).unwrap();
+
+        let result = IncludedSupergraphConfigs::load(
+            contents,
+            Some(&utf8_path(&dir, "supergraph.yaml")),
+            None,
+        );
+        let chain = match result {
+            Err(IncludeSupergraphConfigError::Cycle { chain }) => chain,
+            other => panic!("expected a cycle, got {:?}", other),
+        };
+        assert_that!(chain).is_equal_to(vec![
+            utf8_path(&dir, "supergraph.yaml"),
+            utf8_path(&dir, "a.yaml"),
+            utf8_path(&dir, "b.yaml"),
+            utf8_path(&dir, "a.yaml"),
+        ]);
+    }
+}
@@ -4,8 +4,10 @@
 //! any use case for rover, which is either watching a subgraph or using it as part of a
 //! supergraph composition pipeline
 
+mod include;
 mod subgraph;
 mod supergraph;
 
+pub use include::*;
 pub use subgraph::*;
 pub use supergraph::*;
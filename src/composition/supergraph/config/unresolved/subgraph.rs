@@ -2,7 +2,9 @@ use apollo_federation_types::config::{SchemaSource, SubgraphConfig};
 use camino::Utf8PathBuf;
 use derive_getters::Getters;
 
-use crate::composition::supergraph::config::error::ResolveSubgraphError;
+use crate::composition::supergraph::config::{
+    error::ResolveSubgraphError, introspection::SubgraphIntrospectionConfig,
+};
 
 /// Represents a `SubgraphConfig` that needs to be resolved, either fully or lazily
 #[derive(Clone, Debug, Getters)]
@@ -10,6 +12,7 @@ pub struct UnresolvedSubgraph {
     name: String,
     schema: SchemaSource,
     routing_url: Option<String>,
+    introspection: Option<SubgraphIntrospectionConfig>,
 }
 
 impl UnresolvedSubgraph {
@@ -19,6 +22,48 @@ impl UnresolvedSubgraph {
             name,
             schema: config.schema,
             routing_url: config.routing_url,
+            introspection: None,
+        }
+    }
+
+    /// Attaches the subgraph's `introspection` settings from the supergraph config, adding their
+    /// headers to the ones its schema is introspected with
+    pub fn with_introspection(
+        mut self,
+        introspection: Option<SubgraphIntrospectionConfig>,
+    ) -> UnresolvedSubgraph {
+        if let Some(introspection) = &introspection {
+            self.schema = introspection.apply_headers(self.schema);
+        }
+        self.introspection = introspection;
+        self
+    }
+
+    /// Whether the subgraph's schema is downloaded as raw SDL from a `url`, rather than
+    /// introspected from the URL its schema source gives
+    pub fn downloads_schema(&self) -> bool {
+        self.introspection
+            .as_ref()
+            .is_some_and(|introspection| introspection.schema_url.is_some())
+    }
+
+    /// Refuses the subgraph when its schema can only be resolved over the network
+    pub fn require_offline_schema(&self) -> Result<(), ResolveSubgraphError> {
+        match self.schema {
+            SchemaSource::SubgraphIntrospection { .. } if self.downloads_schema() => {
+                Err(ResolveSubgraphError::DownloadOffline {
+                    subgraph_name: self.name.to_string(),
+                })
+            }
+            SchemaSource::SubgraphIntrospection { .. } => {
+                Err(ResolveSubgraphError::IntrospectionOffline {
+                    subgraph_name: self.name.to_string(),
+                })
+            }
+            SchemaSource::Subgraph { .. } => Err(ResolveSubgraphError::FetchRemoteSdlOffline {
+                subgraph_name: self.name.to_string(),
+            }),
+            SchemaSource::File { .. } | SchemaSource::Sdl { .. } => Ok(()),
         }
     }
 
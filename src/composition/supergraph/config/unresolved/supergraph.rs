@@ -7,13 +7,18 @@ use camino::Utf8PathBuf;
 use derive_getters::Getters;
 
 use super::UnresolvedSubgraph;
+use crate::composition::supergraph::config::introspection::SubgraphIntrospectionConfig;
 
 /// Object that represents a [`SupergraphConfig`] that requires resolution
 #[derive(Getters)]
 pub struct UnresolvedSupergraphConfig {
     origin_path: Option<Utf8PathBuf>,
+    /// The supergraph configs that the one at `origin_path` extends or includes
+    included_paths: Vec<Utf8PathBuf>,
     subgraphs: BTreeMap<String, UnresolvedSubgraph>,
     federation_version: Option<FederationVersion>,
+    /// The environment routing URLs were picked for, if any
+    environment: Option<String>,
 }
 
 #[buildstructor]
@@ -22,18 +27,25 @@ impl UnresolvedSupergraphConfig {
     #[builder]
     pub fn new(
         origin_path: Option<Utf8PathBuf>,
+        included_paths: Vec<Utf8PathBuf>,
         subgraphs: BTreeMap<String, SubgraphConfig>,
+        mut introspection_configs: BTreeMap<String, SubgraphIntrospectionConfig>,
         federation_version: Option<FederationVersion>,
+        environment: Option<String>,
     ) -> UnresolvedSupergraphConfig {
-        let subgraphs = BTreeMap::from_iter(
-            subgraphs
-                .into_iter()
-                .map(|(name, config)| (name.to_string(), UnresolvedSubgraph::new(name, config))),
-        );
+        let subgraphs = BTreeMap::from_iter(subgraphs.into_iter().map(|(name, config)| {
+            let introspection = introspection_configs.remove(&name);
+            (
+                name.to_string(),
+                UnresolvedSubgraph::new(name, config).with_introspection(introspection),
+            )
+        }));
         UnresolvedSupergraphConfig {
             origin_path,
+            included_paths,
             subgraphs,
             federation_version,
+            environment,
         }
     }
 }
@@ -44,10 +56,11 @@ mod tests {
     use std::{
         collections::{BTreeMap, HashSet},
         str::FromStr,
+        time::Duration,
     };
 
     use anyhow::Result;
-    use apollo_federation_types::config::{FederationVersion, SchemaSource};
+    use apollo_federation_types::config::{FederationVersion, SchemaSource, SubgraphConfig};
     use assert_fs::TempDir;
     use camino::Utf8PathBuf;
     use mockall::predicate;
@@ -56,15 +69,21 @@ mod tests {
 
     use crate::{
         composition::supergraph::config::{
+            error::ResolveSubgraphError,
             full::{FullyResolvedSubgraph, FullyResolvedSupergraphConfig},
             lazy::{LazilyResolvedSubgraph, LazilyResolvedSupergraphConfig},
-            resolver::ResolveSupergraphConfigError,
+            resolver::{
+                ResolveSupergraphConfigError, SubgraphResolutionOptions, SubgraphResolutionPolicy,
+                SubgraphRetryPolicy,
+            },
             scenario::*,
             unresolved::UnresolvedSupergraphConfig,
         },
         utils::effect::{
             fetch_remote_subgraph::{MockFetchRemoteSubgraph, RemoteSubgraph},
-            introspect::MockIntrospectSubgraph,
+            introspect::{
+                IntrospectionPolicy, MockIntrospectSubgraph, MockIntrospectSubgraphError,
+            },
         },
     };
 
@@ -278,8 +297,10 @@ mod tests {
 
         let unresolved_supergraph_config = UnresolvedSupergraphConfig {
             origin_path: None,
+            included_paths: Vec::new(),
             subgraphs: unresolved_subgraphs,
             federation_version: target_federation_version,
+            environment: None,
         };
 
         let RemoteSubgraphScenario {
@@ -324,10 +345,12 @@ mod tests {
             .with(
                 predicate::eq(url::Url::from_str(&introspect_subgraph_routing_url)?),
                 predicate::eq(introspect_subgraph_introspection_headers),
+                predicate::eq(None),
+                predicate::eq(IntrospectionPolicy::default()),
             )
             .returning({
                 let introspect_subgraph_sdl = introspect_subgraph_sdl.to_string();
-                move |_, _| Ok(introspect_subgraph_sdl.to_string())
+                move |_, _, _, _| Ok(introspect_subgraph_sdl.to_string())
             });
 
         let result = FullyResolvedSupergraphConfig::resolve(
@@ -483,8 +506,10 @@ mod tests {
 
         let unresolved_supergraph_config = UnresolvedSupergraphConfig {
             origin_path: None,
+            included_paths: Vec::new(),
             subgraphs: unresolved_subgraphs,
             federation_version: Some(target_federation_version.clone()),
+            environment: None,
         };
 
         let RemoteSubgraphScenario {
@@ -529,10 +554,12 @@ mod tests {
             .with(
                 predicate::eq(url::Url::from_str(&introspect_subgraph_routing_url)?),
                 predicate::eq(introspect_subgraph_introspection_headers),
+                predicate::eq(None),
+                predicate::eq(IntrospectionPolicy::default()),
             )
             .returning({
                 let introspect_subgraph_sdl = introspect_subgraph_sdl.to_string();
-                move |_, _| Ok(introspect_subgraph_sdl.to_string())
+                move |_, _, _, _| Ok(introspect_subgraph_sdl.to_string())
             });
 
         let result = FullyResolvedSupergraphConfig::resolve(
@@ -591,6 +618,254 @@ mod tests {
         Ok(())
     }
 
+    #[rstest]
+    #[case::require_all(SubgraphResolutionPolicy::RequireAll)]
+    #[case::allow_partial(SubgraphResolutionPolicy::AllowPartial)]
+    #[tokio::test]
+    async fn test_fully_resolve_subgraphs_with_policy(
+        supergraph_config_root_dir: TempDir,
+        sdl_subgraph_scenario: SdlSubgraphScenario,
+        file_subgraph_scenario: FileSubgraphScenario,
+        #[case] policy: SubgraphResolutionPolicy,
+    ) -> Result<()> {
+        // the file subgraph's schema is never written, so it can't be resolved
+        let sdl_subgraph_name = "sdl_subgraph".to_string();
+        let file_subgraph_name = "file_subgraph".to_string();
+        let unresolved_supergraph_config = UnresolvedSupergraphConfig {
+            origin_path: None,
+            included_paths: Vec::new(),
+            subgraphs: BTreeMap::from_iter([
+                (
+                    sdl_subgraph_name.clone(),
+                    sdl_subgraph_scenario.unresolved_subgraph,
+                ),
+                (
+                    file_subgraph_name.clone(),
+                    file_subgraph_scenario.unresolved_subgraph,
+                ),
+            ]),
+            federation_version: None,
+            environment: None,
+        };
+
+        let mut mock_fetch_remote_subgraph = MockFetchRemoteSubgraph::new();
+        mock_fetch_remote_subgraph
+            .expect_fetch_remote_subgraph()
+            .times(0);
+        let mut mock_introspect_subgraph = MockIntrospectSubgraph::new();
+        mock_introspect_subgraph
+            .expect_introspect_subgraph()
+            .times(0);
+
+        let result = FullyResolvedSupergraphConfig::resolve_with_policy(
+            &mock_introspect_subgraph,
+            &mock_fetch_remote_subgraph,
+            Some(
+                &Utf8PathBuf::from_path_buf(supergraph_config_root_dir.path().to_path_buf())
+                    .unwrap(),
+            ),
+            unresolved_supergraph_config,
+            policy,
+        )
+        .await;
+
+        mock_fetch_remote_subgraph.checkpoint();
+        mock_introspect_subgraph.checkpoint();
+
+        match policy {
+            SubgraphResolutionPolicy::RequireAll => {
+                let err = assert_that!(result).is_err().subject;
+                if let ResolveSupergraphConfigError::ResolveSubgraphs(errors) = err {
+                    assert_that!(errors).has_length(1);
+                } else {
+                    panic!("Result contains the wrong type of error: {:?}", err);
+                }
+            }
+            SubgraphResolutionPolicy::AllowPartial => {
+                let (resolved_supergraph_config, failures) = assert_that!(result).is_ok().subject;
+                assert_that!(resolved_supergraph_config
+                    .subgraphs()
+                    .keys()
+                    .collect::<Vec<_>>())
+                .is_equal_to(vec![&sdl_subgraph_name]);
+                assert_that!(failures.keys().collect::<Vec<_>>())
+                    .is_equal_to(vec![&file_subgraph_name]);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case::recovers_on_retry(1, false)]
+    #[case::gives_up_after_retries(1, true)]
+    #[tokio::test]
+    async fn test_fully_resolve_subgraphs_with_retries(
+        introspect_subgraph_scenario: IntrospectSubgraphScenario,
+        #[case] max_retries: u32,
+        #[case] keeps_failing: bool,
+    ) -> Result<()> {
+        let introspect_subgraph_name = "introspect_subgraph".to_string();
+        let unresolved_supergraph_config = UnresolvedSupergraphConfig {
+            origin_path: None,
+            included_paths: Vec::new(),
+            subgraphs: BTreeMap::from_iter([(
+                introspect_subgraph_name.clone(),
+                introspect_subgraph_scenario.unresolved_subgraph,
+            )]),
+            federation_version: None,
+            environment: None,
+        };
+
+        let mut mock_fetch_remote_subgraph = MockFetchRemoteSubgraph::new();
+        mock_fetch_remote_subgraph
+            .expect_fetch_remote_subgraph()
+            .times(0);
+        // the first attempt always fails, as if the subgraph were still starting up
+        let mut mock_introspect_subgraph = MockIntrospectSubgraph::new();
+        let mut attempts = 0;
+        mock_introspect_subgraph
+            .expect_introspect_subgraph()
+            .times(max_retries as usize + 1)
+            .returning({
+                let sdl = introspect_subgraph_scenario.sdl.to_string();
+                move |_, _, _, _| {
+                    attempts += 1;
+                    if attempts == 1 || keeps_failing {
+                        Err(MockIntrospectSubgraphError(
+                            "connection refused".to_string(),
+                        ))
+                    } else {
+                        Ok(sdl.to_string())
+                    }
+                }
+            });
+
+        let options = SubgraphResolutionOptions {
+            retry_policy: SubgraphRetryPolicy {
+                max_retries,
+                initial_backoff: Duration::ZERO,
+            },
+            ..Default::default()
+        };
+
+        let result = FullyResolvedSupergraphConfig::resolve_with_options(
+            &mock_introspect_subgraph,
+            &mock_fetch_remote_subgraph,
+            None,
+            unresolved_supergraph_config,
+            &options,
+        )
+        .await;
+
+        mock_fetch_remote_subgraph.checkpoint();
+        mock_introspect_subgraph.checkpoint();
+
+        if keeps_failing {
+            assert_that!(result).is_err();
+        } else {
+            let (resolved_supergraph_config, report) = assert_that!(result).is_ok().subject;
+            assert_that!(report.failures.is_empty()).is_true();
+            let resolved_subgraph = resolved_supergraph_config
+                .subgraphs()
+                .get(&introspect_subgraph_name)
+                .unwrap();
+            let subgraph_config: SubgraphConfig = resolved_subgraph.clone().into();
+            assert_that!(subgraph_config.schema).is_equal_to(SchemaSource::Sdl {
+                sdl: introspect_subgraph_scenario.sdl.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_fully_resolve_subgraphs_offline(
+        sdl_subgraph_scenario: SdlSubgraphScenario,
+        remote_subgraph_scenario: RemoteSubgraphScenario,
+        introspect_subgraph_scenario: IntrospectSubgraphScenario,
+    ) -> Result<()> {
+        let sdl_subgraph_name = sdl_subgraph_scenario.unresolved_subgraph.name().to_string();
+        let remote_subgraph_name = remote_subgraph_scenario.subgraph_name.to_string();
+        let introspect_subgraph_name = introspect_subgraph_scenario
+            .unresolved_subgraph
+            .name()
+            .to_string();
+        let unresolved_supergraph_config = UnresolvedSupergraphConfig {
+            origin_path: None,
+            included_paths: Vec::new(),
+            subgraphs: BTreeMap::from_iter([
+                (
+                    sdl_subgraph_name.clone(),
+                    sdl_subgraph_scenario.unresolved_subgraph,
+                ),
+                (
+                    remote_subgraph_name.clone(),
+                    remote_subgraph_scenario.unresolved_subgraph,
+                ),
+                (
+                    introspect_subgraph_name.clone(),
+                    introspect_subgraph_scenario.unresolved_subgraph,
+                ),
+            ]),
+            federation_version: None,
+            environment: None,
+        };
+
+        // nothing is fetched or introspected while offline
+        let mut mock_fetch_remote_subgraph = MockFetchRemoteSubgraph::new();
+        mock_fetch_remote_subgraph
+            .expect_fetch_remote_subgraph()
+            .times(0);
+        let mut mock_introspect_subgraph = MockIntrospectSubgraph::new();
+        mock_introspect_subgraph
+            .expect_introspect_subgraph()
+            .times(0);
+
+        let result = FullyResolvedSupergraphConfig::resolve_with_options(
+            &mock_introspect_subgraph,
+            &mock_fetch_remote_subgraph,
+            None,
+            unresolved_supergraph_config,
+            &SubgraphResolutionOptions {
+                policy: SubgraphResolutionPolicy::AllowPartial,
+                offline: true,
+                ..Default::default()
+            },
+        )
+        .await;
+
+        mock_fetch_remote_subgraph.checkpoint();
+        mock_introspect_subgraph.checkpoint();
+
+        let (resolved_supergraph_config, report) = assert_that!(result).is_ok().subject;
+        assert_that!(resolved_supergraph_config
+            .subgraphs()
+            .keys()
+            .collect::<Vec<_>>())
+        .is_equal_to(vec![&sdl_subgraph_name]);
+        assert_that!(report.failures.get(&remote_subgraph_name))
+            .is_some()
+            .matches(|err| matches!(err, ResolveSubgraphError::FetchRemoteSdlOffline { .. }));
+        assert_that!(report.failures.get(&introspect_subgraph_name))
+            .is_some()
+            .matches(|err| matches!(err, ResolveSubgraphError::IntrospectionOffline { .. }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_subgraph_retry_policy_backoff() {
+        let retry_policy = SubgraphRetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_secs(1),
+        };
+        assert_that!(retry_policy.backoff(0)).is_equal_to(Duration::from_secs(1));
+        assert_that!(retry_policy.backoff(3)).is_equal_to(Duration::from_secs(8));
+        assert_that!(retry_policy.backoff(9)).is_equal_to(SubgraphRetryPolicy::MAX_BACKOFF);
+    }
+
     #[rstest]
     #[tokio::test]
     async fn lazily_resolve_subgraphs(
@@ -630,8 +905,10 @@ mod tests {
 
         let unresolved_supergraph_config = UnresolvedSupergraphConfig {
             origin_path: Some(supergraph_config_origin_path),
+            included_paths: Vec::new(),
             subgraphs: unresolved_subgraphs,
             federation_version: None,
+            environment: None,
         };
 
         let result = LazilyResolvedSupergraphConfig::resolve(
@@ -0,0 +1,808 @@
+//! Checks a supergraph config against the shape Rover expects before any of its subgraphs are
+//! resolved, so that mistakes like a misspelled key or a malformed URL are reported with their
+//! position in the file instead of surfacing later as a confusing resolution or composition error
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use apollo_federation_types::config::{FederationVersion, SchemaSource, SubgraphConfig};
+use camino::Utf8PathBuf;
+use serde::{
+    de::{self, DeserializeOwned, Visitor},
+    Deserializer, Serialize,
+};
+use serde_yaml::{Mapping, Value};
+use url::Url;
+
+use super::{
+    extended::{
+        ExtendedRoutingUrl, ExtendedSchemaSource, ExtendedSubgraphConfig, ExtendedSupergraphConfig,
+        SchemaGit, SchemaUrl, SubgraphDevConfig, SubgraphEnvironment,
+    },
+    introspection::{SubgraphIntrospectionConfig, SubgraphIntrospectionEntry},
+    source_map::{SourceLocation, SupergraphConfigSourceMap},
+    unresolved::SupergraphConfigIncludes,
+};
+
+// The keys each part of a supergraph config supports come from the models that read them, so
+// that a key can't be added to one without validation learning about it. The models keep keys
+// they don't know about, so the supported ones are found by serializing a value of each model
+// with every optional field set, or by asking a model's `Deserialize` implementation which fields
+// it reads. Samples are written out field by field, so adding a field doesn't compile until it's
+// set here too
+lazy_static::lazy_static! {
+    static ref SUPERGRAPH_CONFIG_KEYS: Vec<String> = merge_keys([
+        serialized_keys(&ExtendedSupergraphConfig {
+            subgraphs: BTreeMap::new(),
+            federation_version: Some(FederationVersion::LatestFedTwo),
+            other: Mapping::new(),
+        }),
+        struct_fields::<SupergraphConfigIncludes>(),
+    ]);
+    static ref SUBGRAPH_CONFIG_KEYS: Vec<String> = merge_keys([
+        serialized_keys(&ExtendedSubgraphConfig {
+            routing_url: Some(ExtendedRoutingUrl::Url(String::new())),
+            schema: ExtendedSchemaSource::Glob {
+                glob: String::new(),
+            },
+            dev: Some(SubgraphDevConfig::default()),
+            environments: BTreeMap::from([(String::new(), SubgraphEnvironment::default())]),
+            other: Mapping::new(),
+        }),
+        struct_fields::<SubgraphIntrospectionEntry>(),
+    ]);
+    static ref SCHEMA_SOURCE_KEYS: Vec<String> = merge_keys(
+        [
+            ExtendedSchemaSource::Glob {
+                glob: String::new(),
+            },
+            ExtendedSchemaSource::Url(SchemaUrl {
+                url: String::new(),
+                headers: Some(BTreeMap::new()),
+            }),
+            ExtendedSchemaSource::Git(SchemaGit {
+                git: String::new(),
+                path: Utf8PathBuf::new(),
+                rev: Some(String::new()),
+            }),
+            ExtendedSchemaSource::Upstream(SchemaSource::File {
+                file: Utf8PathBuf::new(),
+            }),
+            ExtendedSchemaSource::Upstream(SchemaSource::SubgraphIntrospection {
+                subgraph_url: Url::parse("http://localhost").unwrap(),
+                introspection_headers: Some(HashMap::new()),
+            }),
+            ExtendedSchemaSource::Upstream(SchemaSource::Subgraph {
+                graphref: String::new(),
+                subgraph: String::new(),
+            }),
+            ExtendedSchemaSource::Upstream(SchemaSource::Sdl { sdl: String::new() }),
+        ]
+        .iter()
+        .map(serialized_keys)
+    );
+    static ref DEV_KEYS: Vec<String> = serialized_keys(&SubgraphDevConfig {
+        command: Some(String::new()),
+        mocks: Some(false),
+        tunnel: Some(false),
+        other: Mapping::new(),
+    });
+    static ref ENVIRONMENT_KEYS: Vec<String> = struct_fields::<SubgraphEnvironment>();
+    static ref INTROSPECTION_KEYS: Vec<String> = struct_fields::<SubgraphIntrospectionConfig>();
+}
+
+/// The keys a model serializes `sample` to
+fn serialized_keys<T: Serialize>(sample: &T) -> Vec<String> {
+    match serde_yaml::to_value(sample) {
+        Ok(Value::Mapping(mapping)) => mapping
+            .keys()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The fields a struct without flattened fields reads, as listed by its `Deserialize`
+/// implementation
+fn struct_fields<T: DeserializeOwned>() -> Vec<String> {
+    struct FieldsDeserializer<'a>(&'a mut Vec<&'static str>);
+
+    impl<'de> Deserializer<'de> for FieldsDeserializer<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("only the fields of structs are listed"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            self.0.extend_from_slice(fields);
+            Err(de::Error::custom("the fields have been listed"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+            option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+            ignored_any
+        }
+    }
+
+    let mut fields = Vec::new();
+    let _ = T::deserialize(FieldsDeserializer(&mut fields));
+    fields.into_iter().map(str::to_string).collect()
+}
+
+/// Every key in `lists`, in the order they first appear
+fn merge_keys(lists: impl IntoIterator<Item = Vec<String>>) -> Vec<String> {
+    let mut merged: Vec<String> = Vec::new();
+    for key in lists.into_iter().flatten() {
+        if !merged.contains(&key) {
+            merged.push(key);
+        }
+    }
+    merged
+}
+
+/// Decides which checks [`validate_supergraph_config`] applies beyond the ones every supergraph
+/// config has to pass
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SupergraphConfigValidation {
+    /// Whether every subgraph needs a routing URL. `rover dev` can do without one for subgraphs
+    /// whose schema comes from a file or inline SDL, but a supergraph served anywhere else can't
+    pub require_routing_urls: bool,
+}
+
+/// Something wrong with a supergraph config
+#[derive(thiserror::Error, Clone, Debug, Eq, PartialEq)]
+pub enum SupergraphConfigProblem {
+    /// Occurs when a key isn't one that supergraph configs support, which is usually a typo
+    #[error("Unknown key `{key}`. Expected one of: {}", expected.join(", "))]
+    UnknownKey {
+        /// The key as written in the supergraph config
+        key: String,
+        /// The keys supported in its place
+        expected: Vec<String>,
+    },
+    /// Occurs when a URL in the supergraph config can't be parsed
+    #[error("`{key}` of subgraph `{subgraph_name}` is not a valid URL ({value}): {reason}")]
+    MalformedUrl {
+        /// The subgraph the URL belongs to
+        subgraph_name: String,
+        /// The key holding the URL
+        key: &'static str,
+        /// The URL as written in the supergraph config
+        value: String,
+        /// Why the URL couldn't be parsed
+        reason: String,
+    },
+    /// Occurs when two subgraph names only differ by casing, which is almost always a mistake
+    #[error("Subgraph `{subgraph_name}` has the same name as subgraph `{other_subgraph_name}` apart from casing")]
+    DuplicateSubgraphName {
+        /// The name of the subgraph that appears later in the supergraph config
+        subgraph_name: String,
+        /// The name of the subgraph it collides with
+        other_subgraph_name: String,
+    },
+    /// Occurs when a subgraph has neither a routing URL nor a schema source that provides one
+    #[error("Subgraph `{subgraph_name}` needs a `routing_url`, since its schema source doesn't provide one")]
+    MissingRoutingUrl {
+        /// The name of the subgraph missing a routing URL
+        subgraph_name: String,
+    },
+    /// Occurs when a subgraph's introspection settings have a client certificate without a key,
+    /// or the other way around
+    #[error("The introspection settings of subgraph `{subgraph_name}` need both `client_cert` and `client_key`, but only have `{present}`")]
+    IncompleteClientIdentity {
+        /// The name of the subgraph with the incomplete settings
+        subgraph_name: String,
+        /// The one of the two keys that is set
+        present: &'static str,
+    },
+}
+
+/// A [`SupergraphConfigProblem`] along with where it appears in the supergraph config
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SupergraphConfigValidationError {
+    /// The position of the problem in the supergraph config, when it can be found
+    pub location: Option<SourceLocation>,
+    /// The problem itself
+    pub problem: SupergraphConfigProblem,
+}
+
+impl fmt::Display for SupergraphConfigValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{}: {}", location, self.problem),
+            None => write!(f, "{}", self.problem),
+        }
+    }
+}
+
+impl std::error::Error for SupergraphConfigValidationError {}
+
+/// Validates the raw `contents` of a supergraph config, returning every problem found in the
+/// order they appear in the file
+///
+/// `remote_subgraphs` are the subgraphs already loaded from GraphOS or included supergraph
+/// configs, whose routing URLs local subgraphs of the same name fall back on. Contents that aren't even a YAML mapping pass
+/// validation, since parsing the supergraph config reports them more precisely
+pub fn validate_supergraph_config(
+    contents: &str,
+    source_map: &SupergraphConfigSourceMap,
+    remote_subgraphs: &BTreeMap<String, SubgraphConfig>,
+    validation: SupergraphConfigValidation,
+) -> Result<(), Vec<SupergraphConfigValidationError>> {
+    let Ok(Value::Mapping(supergraph_config)) = serde_yaml::from_str::<Value>(contents) else {
+        return Ok(());
+    };
+
+    let empty = Mapping::new();
+    let mut problems = Vec::new();
+    unknown_keys(
+        &supergraph_config,
+        &[],
+        &SUPERGRAPH_CONFIG_KEYS,
+        &mut problems,
+    );
+
+    let subgraphs = match supergraph_config.get("subgraphs") {
+        Some(Value::Mapping(subgraphs)) => subgraphs,
+        _ => &empty,
+    };
+    let mut lowercase_names: BTreeMap<String, String> = BTreeMap::new();
+    for (name, subgraph) in subgraphs {
+        let Some(name) = name.as_str() else {
+            continue;
+        };
+        if let Some(other_name) = lowercase_names.get(&name.to_lowercase()) {
+            problems.push((
+                vec!["subgraphs", name],
+                SupergraphConfigProblem::DuplicateSubgraphName {
+                    subgraph_name: name.to_string(),
+                    other_subgraph_name: other_name.to_string(),
+                },
+            ));
+        } else {
+            lowercase_names.insert(name.to_lowercase(), name.to_string());
+        }
+
+        let Value::Mapping(subgraph) = subgraph else {
+            continue;
+        };
+        unknown_keys(
+            subgraph,
+            &["subgraphs", name],
+            &SUBGRAPH_CONFIG_KEYS,
+            &mut problems,
+        );
+        let schema = match subgraph.get("schema") {
+            Some(Value::Mapping(schema)) => schema,
+            _ => &empty,
+        };
+        unknown_keys(
+            schema,
+            &["subgraphs", name, "schema"],
+            &SCHEMA_SOURCE_KEYS,
+            &mut problems,
+        );
+        if let Some(Value::Mapping(dev)) = subgraph.get("dev") {
+            unknown_keys(dev, &["subgraphs", name, "dev"], &DEV_KEYS, &mut problems);
+        }
+        if let Some(Value::Mapping(environments)) = subgraph.get("environments") {
+            for (environment_name, environment) in environments {
+                let (Some(environment_name), Value::Mapping(environment)) =
+                    (environment_name.as_str(), environment)
+                else {
+                    continue;
+                };
+                unknown_keys(
+                    environment,
+                    &["subgraphs", name, "environments", environment_name],
+                    &ENVIRONMENT_KEYS,
+                    &mut problems,
+                );
+            }
+        }
+        if let Some(Value::Mapping(introspection)) = subgraph.get("introspection") {
+            unknown_keys(
+                introspection,
+                &["subgraphs", name, "introspection"],
+                &INTROSPECTION_KEYS,
+                &mut problems,
+            );
+            let has_key = |key: &str| introspection.get(key).is_some_and(|value| !value.is_null());
+            let present = match (has_key("client_cert"), has_key("client_key")) {
+                (true, false) => Some("client_cert"),
+                (false, true) => Some("client_key"),
+                _ => None,
+            };
+            if let Some(present) = present {
+                problems.push((
+                    vec!["subgraphs", name, "introspection", present],
+                    SupergraphConfigProblem::IncompleteClientIdentity {
+                        subgraph_name: name.to_string(),
+                        present,
+                    },
+                ));
+            }
+        }
+
+        // a routing URL can also be given per environment, each of which has to be valid
+        let routing_urls = match subgraph.get("routing_url") {
+            Some(Value::Mapping(urls)) => urls
+                .iter()
+                .filter_map(|(environment, url)| {
+                    Some((
+                        vec!["subgraphs", name, "routing_url", environment.as_str()?],
+                        url,
+                    ))
+                })
+                .collect(),
+            Some(url) => vec![(vec!["subgraphs", name, "routing_url"], url)],
+            None => Vec::new(),
+        };
+        let urls = routing_urls
+            .into_iter()
+            .map(|(key_path, url)| ("routing_url", key_path, url))
+            .chain(schema.get("subgraph_url").map(|url| {
+                (
+                    "subgraph_url",
+                    vec!["subgraphs", name, "schema", "subgraph_url"],
+                    url,
+                )
+            }))
+            .chain(
+                schema
+                    .get("url")
+                    .map(|url| ("url", vec!["subgraphs", name, "schema", "url"], url)),
+            );
+        for (key, key_path, value) in urls {
+            let Some(url) = value.as_str() else {
+                continue;
+            };
+            if let Err(reason) = parse_url(url) {
+                problems.push((
+                    key_path,
+                    SupergraphConfigProblem::MalformedUrl {
+                        subgraph_name: name.to_string(),
+                        key,
+                        value: url.to_string(),
+                        reason,
+                    },
+                ));
+            }
+        }
+
+        // Subgraphs that are introspected are routed to their introspection URL, and subgraphs
+        // fetched from GraphOS are routed to the URL published alongside them
+        let has_routing_url = subgraph
+            .get("routing_url")
+            .is_some_and(|url| !url.is_null())
+            || schema.contains_key("subgraph_url")
+            || schema.contains_key("graphref")
+            || remote_subgraphs
+                .get(name)
+                .is_some_and(|remote| remote.routing_url.is_some());
+        if validation.require_routing_urls && !has_routing_url {
+            problems.push((
+                vec!["subgraphs", name],
+                SupergraphConfigProblem::MissingRoutingUrl {
+                    subgraph_name: name.to_string(),
+                },
+            ));
+        }
+    }
+
+    let mut errors: Vec<SupergraphConfigValidationError> = problems
+        .into_iter()
+        .map(|(key_path, problem)| SupergraphConfigValidationError {
+            location: source_map.locate(&key_path),
+            problem,
+        })
+        .collect();
+    errors.sort_by_key(|error| {
+        error
+            .location
+            .as_ref()
+            .map(|location| (*location.line(), *location.column()))
+    });
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn unknown_keys<'a>(
+    mapping: &'a Mapping,
+    parent_path: &[&'a str],
+    expected: &[String],
+    problems: &mut Vec<(Vec<&'a str>, SupergraphConfigProblem)>,
+) {
+    for key in mapping.keys().filter_map(Value::as_str) {
+        if !expected.iter().any(|expected| expected == key) {
+            let mut key_path = parent_path.to_vec();
+            key_path.push(key);
+            problems.push((
+                key_path,
+                SupergraphConfigProblem::UnknownKey {
+                    key: key.to_string(),
+                    expected: expected.to_vec(),
+                },
+            ));
+        }
+    }
+}
+
+/// Parses a URL from the supergraph config, leaving alone any URL that still contains variables
+/// to be expanded
+fn parse_url(url: &str) -> Result<(), String> {
+    if url.contains("${") {
+        return Ok(());
+    }
+    let parsed = Url::parse(url).map_err(|err| err.to_string())?;
+    // Forgetting the scheme (eg, `localhost:4001/graphql`) still parses, with `localhost` as the
+    // scheme, but leaves a URL that nothing can be sent to
+    if parsed.cannot_be_a_base() {
+        return Err("expected an absolute URL, such as `http://localhost:4001`".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use apollo_federation_types::config::SchemaSource;
+    use indoc::indoc;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    fn validate(
+        contents: &str,
+        remote_subgraphs: &BTreeMap<String, SubgraphConfig>,
+        require_routing_urls: bool,
+    ) -> Vec<(usize, usize, SupergraphConfigProblem)> {
+        let source_map = SupergraphConfigSourceMap::new(contents, None);
+        validate_supergraph_config(
+            contents,
+            &source_map,
+            remote_subgraphs,
+            SupergraphConfigValidation {
+                require_routing_urls,
+            },
+        )
+        .err()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|error| {
+            let location = error.location.unwrap();
+            (*location.line(), *location.column(), error.problem)
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_keys_come_from_the_config_models() {
+        let sorted = |keys: &[String]| {
+            let mut keys = keys.to_vec();
+            keys.sort();
+            keys
+        };
+        assert_that!(sorted(&SUPERGRAPH_CONFIG_KEYS)).is_equal_to(
+            ["extends", "federation_version", "include", "subgraphs"]
+                .map(String::from)
+                .to_vec(),
+        );
+        assert_that!(sorted(&SUBGRAPH_CONFIG_KEYS)).is_equal_to(
+            [
+                "dev",
+                "environments",
+                "introspection",
+                "routing_url",
+                "schema",
+            ]
+            .map(String::from)
+            .to_vec(),
+        );
+        assert_that!(sorted(&SCHEMA_SOURCE_KEYS)).is_equal_to(
+            [
+                "file",
+                "git",
+                "glob",
+                "graphref",
+                "headers",
+                "introspection_headers",
+                "path",
+                "rev",
+                "sdl",
+                "subgraph",
+                "subgraph_url",
+                "url",
+            ]
+            .map(String::from)
+            .to_vec(),
+        );
+        assert_that!(sorted(&DEV_KEYS))
+            .is_equal_to(["command", "mocks", "tunnel"].map(String::from).to_vec());
+        assert_that!(sorted(&ENVIRONMENT_KEYS))
+            .is_equal_to(["routing_url", "subgraph_url"].map(String::from).to_vec());
+        assert_that!(sorted(&INTROSPECTION_KEYS)).is_equal_to(
+            [
+                "auth",
+                "client_cert",
+                "client_key",
+                "headers",
+                "mode",
+                "poll_interval_seconds",
+                "retries",
+                "timeout",
+            ]
+            .map(String::from)
+            .to_vec(),
+        );
+    }
+
+    #[test]
+    fn test_valid_config_has_no_problems() {
+        let contents = indoc! {r#"
+            federation_version: =2.9.0
+            subgraphs:
+              products:
+                routing_url: http://localhost:4001
+                schema:
+                  file: ./products.graphql
+              reviews:
+                schema:
+                  subgraph_url: ${env.REVIEWS_URL}
+                  introspection_headers:
+                    Custom-Header: value
+                introspection:
+                  poll_interval_seconds: 5
+              users:
+                schema:
+                  graphref: mygraph@current
+                  subgraph: users
+        "#};
+        assert_that!(validate(contents, &BTreeMap::new(), true)).is_empty();
+    }
+
+    #[test]
+    fn test_environments_and_introspection_auth_are_valid() {
+        let contents = indoc! {r#"
+            subgraphs:
+              products:
+                routing_url: http://localhost:4001
+                schema:
+                  subgraph_url: http://localhost:4001
+                environments:
+                  prod:
+                    routing_url: https://products.example.com
+                    subgraph_url: https://products.example.com/graphql
+                introspection:
+                  auth:
+                    type: oauth_client_credentials
+                    token_url: https://auth.example.com/oauth/token
+                    client_id: rover
+                    client_secret: ${env.PRODUCTS_CLIENT_SECRET}
+        "#};
+        assert_that!(validate(contents, &BTreeMap::new(), true)).is_empty();
+    }
+
+    #[test]
+    fn test_reports_unknown_environment_keys() {
+        let contents = indoc! {r#"
+            subgraphs:
+              products:
+                routing_url: http://localhost:4001
+                schema:
+                  file: ./products.graphql
+                environments:
+                  prod:
+                    routing: https://products.example.com
+        "#};
+        assert_that!(validate(contents, &BTreeMap::new(), true)).is_equal_to(vec![(
+            8,
+            9,
+            SupergraphConfigProblem::UnknownKey {
+                key: "routing".to_string(),
+                expected: ENVIRONMENT_KEYS.clone(),
+            },
+        )]);
+    }
+
+    #[test]
+    fn test_checks_routing_urls_per_environment() {
+        let contents = indoc! {r#"
+            subgraphs:
+              products:
+                routing_url:
+                  dev: http://localhost:4001
+                  prod: products.example.com:443
+                schema:
+                  file: ./products.graphql
+        "#};
+        assert_that!(validate(contents, &BTreeMap::new(), true)).is_equal_to(vec![(
+            5,
+            7,
+            SupergraphConfigProblem::MalformedUrl {
+                subgraph_name: "products".to_string(),
+                key: "routing_url",
+                value: "products.example.com:443".to_string(),
+                reason: "expected an absolute URL, such as `http://localhost:4001`".to_string(),
+            },
+        )]);
+    }
+
+    #[test]
+    fn test_reports_every_problem_with_its_position() {
+        let contents = indoc! {r#"
+            federation_versoin: =2.9.0
+            subgraphs:
+              products:
+                routing_url: localhost:4001/graphql
+                schema:
+                  files: ./products.graphql
+              Products:
+                routing_url: http://localhost:4002
+                schema: { subgraph_url: "http://[::1" }
+        "#};
+        assert_that!(validate(contents, &BTreeMap::new(), false)).is_equal_to(vec![
+            (
+                1,
+                1,
+                SupergraphConfigProblem::UnknownKey {
+                    key: "federation_versoin".to_string(),
+                    expected: SUPERGRAPH_CONFIG_KEYS.clone(),
+                },
+            ),
+            (
+                4,
+                5,
+                SupergraphConfigProblem::MalformedUrl {
+                    subgraph_name: "products".to_string(),
+                    key: "routing_url",
+                    value: "localhost:4001/graphql".to_string(),
+                    reason: "expected an absolute URL, such as `http://localhost:4001`".to_string(),
+                },
+            ),
+            (
+                6,
+                7,
+                SupergraphConfigProblem::UnknownKey {
+                    key: "files".to_string(),
+                    expected: SCHEMA_SOURCE_KEYS.clone(),
+                },
+            ),
+            (
+                7,
+                3,
+                SupergraphConfigProblem::DuplicateSubgraphName {
+                    subgraph_name: "Products".to_string(),
+                    other_subgraph_name: "products".to_string(),
+                },
+            ),
+            (
+                9,
+                15,
+                SupergraphConfigProblem::MalformedUrl {
+                    subgraph_name: "Products".to_string(),
+                    key: "subgraph_url",
+                    value: "http://[::1".to_string(),
+                    reason: "invalid IPv6 address".to_string(),
+                },
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_reports_malformed_schema_urls() {
+        let contents = indoc! {r#"
+            subgraphs:
+              products:
+                routing_url: http://localhost:4001
+                schema:
+                  url: products.graphql
+        "#};
+        assert_that!(validate(contents, &BTreeMap::new(), false)).is_equal_to(vec![(
+            5,
+            7,
+            SupergraphConfigProblem::MalformedUrl {
+                subgraph_name: "products".to_string(),
+                key: "url",
+                value: "products.graphql".to_string(),
+                reason: "relative URL without a base".to_string(),
+            },
+        )]);
+    }
+
+    #[test]
+    fn test_reports_unknown_introspection_keys() {
+        let contents = indoc! {r#"
+            subgraphs:
+              products:
+                schema:
+                  subgraph_url: http://localhost:4001
+                introspection:
+                  poll_interval: 5
+        "#};
+        assert_that!(validate(contents, &BTreeMap::new(), false)).is_equal_to(vec![(
+            6,
+            7,
+            SupergraphConfigProblem::UnknownKey {
+                key: "poll_interval".to_string(),
+                expected: INTROSPECTION_KEYS.clone(),
+            },
+        )]);
+    }
+
+    #[test]
+    fn test_reports_incomplete_client_identity() {
+        let contents = indoc! {r#"
+            subgraphs:
+              products:
+                schema:
+                  subgraph_url: http://localhost:4001
+                introspection:
+                  client_key: ./rover.key
+        "#};
+        assert_that!(validate(contents, &BTreeMap::new(), false)).is_equal_to(vec![(
+            6,
+            7,
+            SupergraphConfigProblem::IncompleteClientIdentity {
+                subgraph_name: "products".to_string(),
+                present: "client_key",
+            },
+        )]);
+    }
+
+    #[rstest]
+    #[case::required(true, &[], vec![(2, 3)])]
+    #[case::not_required(false, &[], vec![])]
+    #[case::provided_remotely(true, &["products"], vec![])]
+    fn test_missing_routing_url(
+        #[case] require_routing_urls: bool,
+        #[case] remote_subgraph_names: &[&str],
+        #[case] expected_positions: Vec<(usize, usize)>,
+    ) {
+        let contents = indoc! {r#"
+            subgraphs:
+              products:
+                routing_url: null
+                schema:
+                  sdl: "type Query { products: [String] }"
+        "#};
+        let remote_subgraphs = remote_subgraph_names
+            .iter()
+            .map(|name| {
+                (
+                    name.to_string(),
+                    SubgraphConfig {
+                        routing_url: Some("http://localhost:4001".to_string()),
+                        schema: SchemaSource::Subgraph {
+                            graphref: "mygraph@current".to_string(),
+                            subgraph: name.to_string(),
+                        },
+                    },
+                )
+            })
+            .collect();
+        let positions: Vec<(usize, usize)> =
+            validate(contents, &remote_subgraphs, require_routing_urls)
+                .into_iter()
+                .filter(|(_, _, problem)| {
+                    matches!(problem, SupergraphConfigProblem::MissingRoutingUrl { .. })
+                })
+                .map(|(line, column, _)| (line, column))
+                .collect();
+        assert_that!(positions).is_equal_to(expected_positions);
+    }
+}
@@ -40,5 +40,6 @@ pub fn default_composition_success(
             other: BTreeMap::default(),
         }],
         federation_version,
+        profile: None,
     }
 }
@@ -0,0 +1,386 @@
+//! Cheap checks on subgraph schemas that can run before composition. Composition reports the same
+//! problems eventually, but only once every subgraph has been composed together, so these let us
+//! point at the broken subgraph straight away
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use apollo_parser::{
+    cst::{self, CstNode},
+    Parser,
+};
+
+/// Federation directives that can't be used without a particular argument, paired with the name
+/// of that argument
+const REQUIRED_DIRECTIVE_ARGUMENTS: &[(&str, &str)] = &[
+    ("key", "fields"),
+    ("requires", "fields"),
+    ("provides", "fields"),
+    ("override", "from"),
+];
+
+/// Parses a subgraph's SDL and checks how it uses federation directives, returning a description
+/// of each problem found. An empty list doesn't mean the subgraph will compose, only that nothing
+/// is obviously wrong with it on its own
+pub fn validate_subgraph_sdl(sdl: &str) -> Vec<String> {
+    let parsed = Parser::new(sdl).parse();
+    let parse_errors = parsed
+        .errors()
+        .map(|err| format!("{} at {}", err.message(), location(sdl, err.index())))
+        .collect::<Vec<_>>();
+    if !parse_errors.is_empty() {
+        return parse_errors;
+    }
+
+    let document = parsed.document();
+    // a subgraph that defines a directive itself, like a Federation 1 subgraph that includes the
+    // federation directive definitions or one with its own `@key`, gets to decide what it means
+    let defined_directives = document
+        .definitions()
+        .filter_map(|definition| match definition {
+            cst::Definition::DirectiveDefinition(def) => def.name(),
+            _ => None,
+        })
+        .map(|name| name.text().to_string())
+        .collect::<HashSet<_>>();
+
+    document
+        .syntax()
+        .descendants()
+        .filter_map(cst::Directive::cast)
+        .filter_map(|directive| {
+            let name = directive.name()?.text().to_string();
+            if defined_directives.contains(&name) {
+                return None;
+            }
+            let (_, argument) = REQUIRED_DIRECTIVE_ARGUMENTS
+                .iter()
+                .find(|(directive_name, _)| *directive_name == name)?;
+            if has_string_argument(&directive, argument) {
+                return None;
+            }
+            let start: usize = directive.syntax().text_range().start().into();
+            Some(format!(
+                "@{name} requires a string `{argument}` argument at {}",
+                location(sdl, start)
+            ))
+        })
+        .collect()
+}
+
+fn has_string_argument(directive: &cst::Directive, argument_name: &str) -> bool {
+    directive
+        .arguments()
+        .map(|arguments| {
+            arguments.arguments().any(|argument| {
+                argument
+                    .name()
+                    .map(|name| name.text() == argument_name)
+                    .unwrap_or_default()
+                    && matches!(argument.value(), Some(cst::Value::StringValue(_)))
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// A custom directive's definition, as far as composition compares it between subgraphs
+#[derive(Debug, Eq, PartialEq)]
+struct DirectiveShape {
+    /// Each argument's type, and default value if it has one
+    arguments: BTreeMap<String, (String, Option<String>)>,
+    locations: BTreeSet<String>,
+    repeatable: bool,
+    /// Where the definition starts, as a `line:column`
+    at: String,
+}
+
+impl DirectiveShape {
+    fn new(sdl: &str, definition: &cst::DirectiveDefinition) -> Self {
+        let arguments = definition
+            .arguments_definition()
+            .map(|arguments| {
+                arguments
+                    .input_value_definitions()
+                    .filter_map(|argument| {
+                        let name = argument.name()?.text().to_string();
+                        let ty = compact(&argument.ty()?.syntax().to_string());
+                        let default = argument
+                            .default_value()
+                            .and_then(|default| default.value())
+                            .map(|value| compact(&value.syntax().to_string()));
+                        Some((name, (ty, default)))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let locations = definition
+            .directive_locations()
+            .map(|locations| {
+                locations
+                    .directive_locations()
+                    .map(|location| location.syntax().to_string().trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let start: usize = definition.syntax().text_range().start().into();
+        DirectiveShape {
+            arguments,
+            locations,
+            repeatable: definition.repeatable_token().is_some(),
+            at: location(sdl, start),
+        }
+    }
+
+    /// Describes how `other` differs from this definition
+    fn differences(&self, other: &DirectiveShape) -> Vec<String> {
+        let mut differences = Vec::new();
+        if self.locations != other.locations {
+            differences.push(format!(
+                "locations {} vs {}",
+                join(&self.locations),
+                join(&other.locations)
+            ));
+        }
+        let names = self
+            .arguments
+            .keys()
+            .chain(other.arguments.keys())
+            .collect::<BTreeSet<_>>();
+        for name in names {
+            match (self.arguments.get(name), other.arguments.get(name)) {
+                (Some(ours), Some(theirs)) if ours != theirs => differences.push(format!(
+                    "argument `{name}` is {} vs {}",
+                    describe_argument(ours),
+                    describe_argument(theirs)
+                )),
+                (Some(_), None) | (None, Some(_)) => {
+                    differences.push(format!("argument `{name}` is only defined in one of them"))
+                }
+                _ => {}
+            }
+        }
+        if self.repeatable != other.repeatable {
+            differences.push("only one of them is repeatable".to_string());
+        }
+        differences
+    }
+}
+
+fn describe_argument((ty, default): &(String, Option<String>)) -> String {
+    match default {
+        Some(default) => format!("`{ty} = {default}`"),
+        None => format!("`{ty}`"),
+    }
+}
+
+fn join(locations: &BTreeSet<String>) -> String {
+    if locations.is_empty() {
+        "(none)".to_string()
+    } else {
+        locations.iter().cloned().collect::<Vec<_>>().join(" | ")
+    }
+}
+
+/// Drops the whitespace that doesn't change what a type or value means
+fn compact(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The directives a subgraph exports with `@composeDirective(name: "@...")`, each with where it's
+/// exported, and the definitions of the directives it defines
+fn compose_directives(
+    sdl: &str,
+) -> Option<(BTreeMap<String, String>, BTreeMap<String, DirectiveShape>)> {
+    let parsed = Parser::new(sdl).parse();
+    // parse errors are reported on their own
+    if parsed.errors().next().is_some() {
+        return None;
+    }
+    let document = parsed.document();
+    let exported = document
+        .syntax()
+        .descendants()
+        .filter_map(cst::Directive::cast)
+        .filter(|directive| {
+            directive
+                .name()
+                .is_some_and(|name| name.text() == "composeDirective")
+        })
+        .filter_map(|directive| {
+            let name = directive
+                .arguments()?
+                .arguments()
+                .find(|argument| argument.name().is_some_and(|name| name.text() == "name"))?
+                .value()?;
+            let cst::Value::StringValue(name) = name else {
+                return None;
+            };
+            let name = String::from(name);
+            let start: usize = directive.syntax().text_range().start().into();
+            Some((
+                name.trim_start_matches('@').to_string(),
+                location(sdl, start),
+            ))
+        })
+        .collect();
+    let definitions = document
+        .definitions()
+        .filter_map(|definition| match definition {
+            cst::Definition::DirectiveDefinition(definition) => Some(definition),
+            _ => None,
+        })
+        .filter_map(|definition| {
+            let name = definition.name()?.text().to_string();
+            Some((name, DirectiveShape::new(sdl, &definition)))
+        })
+        .collect();
+    Some((exported, definitions))
+}
+
+/// Checks the custom directives that subgraphs export with `@composeDirective`: each subgraph has
+/// to define the directives it exports, and every subgraph that exports one has to define it the
+/// same way. Returns each problem found along with the name of the subgraph it's in
+pub fn validate_compose_directives<'a>(
+    subgraphs: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<(String, String)> {
+    let mut problems = Vec::new();
+    // the first definition of each exported directive, which the others are compared against
+    let mut first_definitions: BTreeMap<String, (&str, DirectiveShape)> = BTreeMap::new();
+    for (subgraph_name, sdl) in subgraphs {
+        let Some((exported, mut definitions)) = compose_directives(sdl) else {
+            continue;
+        };
+        for (directive, at) in exported {
+            let Some(definition) = definitions.remove(&directive) else {
+                problems.push((
+                    subgraph_name.to_string(),
+                    format!(
+                        "@{directive} is exported with @composeDirective at {at}, but isn't defined in this subgraph"
+                    ),
+                ));
+                continue;
+            };
+            match first_definitions.get(&directive) {
+                Some((first_subgraph, first)) => {
+                    let differences = first.differences(&definition);
+                    if !differences.is_empty() {
+                        problems.push((
+                            subgraph_name.to_string(),
+                            format!(
+                                "@{directive} is defined at {} differently than in subgraph '{first_subgraph}' at {}: {}",
+                                definition.at,
+                                first.at,
+                                differences.join(", ")
+                            ),
+                        ));
+                    }
+                }
+                None => {
+                    first_definitions.insert(directive, (subgraph_name, definition));
+                }
+            }
+        }
+    }
+    problems
+}
+
+/// Turns a byte offset into the SDL into a 1-based `line:column`
+fn location(sdl: &str, index: usize) -> String {
+    let before = &sdl[..index.min(sdl.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    format!("{line}:{column}")
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::{validate_compose_directives, validate_subgraph_sdl};
+
+    const LINK: &str = r#"extend schema
+  @link(url: "https://specs.apollo.dev/federation/v2.1", import: ["@composeDirective"])
+  @link(url: "https://myspecs.dev/cache/v1.0", import: ["@cache"])
+  @composeDirective(name: "@cache")
+"#;
+
+    #[rstest]
+    #[case::valid(indoc! {r#"
+        type Query { product: Product }
+        type Product @key(fields: "id") { id: ID! }
+    "#})]
+    #[case::own_directive_definition(indoc! {r#"
+        directive @key on OBJECT
+        type Product @key { id: ID! }
+    "#})]
+    fn test_validate_subgraph_sdl_accepts(#[case] sdl: &str) {
+        assert_that!(validate_subgraph_sdl(sdl)).is_empty();
+    }
+
+    #[test]
+    fn test_validate_subgraph_sdl_reports_parse_errors() {
+        let errors = validate_subgraph_sdl("type Query {\n  product: \n}");
+        assert_that!(errors).has_length(1);
+        assert_that!(errors[0]).contains("at 3:1");
+    }
+
+    #[test]
+    fn test_validate_subgraph_sdl_reports_directive_misuse() {
+        let sdl = indoc! {r#"
+            type Query { product: Product }
+            type Product @key { id: ID! name: String @override(from: 1) }
+        "#};
+        assert_that!(validate_subgraph_sdl(sdl)).is_equal_to(vec![
+            "@key requires a string `fields` argument at 2:14".to_string(),
+            "@override requires a string `from` argument at 2:42".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_validate_compose_directives_accepts_matching_definitions() {
+        let products = format!(
+            "{LINK}directive @cache(maxAge: Int = 60) on FIELD_DEFINITION | OBJECT\ntype Query {{ a: Int }}"
+        );
+        let reviews = format!(
+            "{LINK}directive @cache(maxAge: Int = 60) on OBJECT | FIELD_DEFINITION\ntype Query {{ b: Int }}"
+        );
+        assert_that!(validate_compose_directives([
+            ("products", products.as_str()),
+            ("reviews", reviews.as_str()),
+        ]))
+        .is_empty();
+    }
+
+    #[test]
+    fn test_validate_compose_directives_reports_mismatches() {
+        let products = format!(
+            "{LINK}directive @cache(maxAge: Int = 60) on FIELD_DEFINITION\ntype Query {{ a: Int }}"
+        );
+        let reviews = format!(
+            "{LINK}directive @cache(maxAge: String, scope: String) repeatable on FIELD_DEFINITION | OBJECT\ntype Query {{ b: Int }}"
+        );
+        let users = format!("{LINK}type Query {{ c: Int }}");
+        assert_that!(validate_compose_directives([
+            ("products", products.as_str()),
+            ("reviews", reviews.as_str()),
+            ("users", users.as_str()),
+        ]))
+        .is_equal_to(vec![
+            (
+                "reviews".to_string(),
+                "@cache is defined at 5:1 differently than in subgraph 'products' at 5:1: \
+                 locations FIELD_DEFINITION vs FIELD_DEFINITION | OBJECT, \
+                 argument `maxAge` is `Int = 60` vs `String`, \
+                 argument `scope` is only defined in one of them, \
+                 only one of them is repeatable"
+                    .to_string(),
+            ),
+            (
+                "users".to_string(),
+                "@cache is exported with @composeDirective at 4:3, but isn't defined in this subgraph"
+                    .to_string(),
+            ),
+        ]);
+    }
+}
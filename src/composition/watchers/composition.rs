@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use apollo_federation_types::config::SupergraphConfig;
 use buildstructor::Builder;
 use camino::Utf8PathBuf;
@@ -6,10 +8,15 @@ use rover_std::errln;
 use tap::TapFallible;
 use tokio::{sync::mpsc::UnboundedSender, task::AbortHandle};
 use tokio_stream::StreamExt;
+use tracing::Instrument;
+use uuid::Uuid;
 
 use crate::{
     composition::{
-        events::CompositionEvent,
+        events::{
+            CompositionEvent, SubgraphResolutionFailed, SubgraphStale, SubgraphValidationError,
+            SupergraphOutputCorrupted,
+        },
         supergraph::{
             binary::{OutputTarget, SupergraphBinary},
             config::full::FullyResolvedSubgraphs,
@@ -29,6 +36,9 @@ pub struct CompositionWatcher<ReadF, ExecC, WriteF> {
     read_file: ReadF,
     write_file: WriteF,
     temp_dir: Utf8PathBuf,
+    /// Whether to read back every file the supergraph is written to after each composition, to
+    /// catch files that were only partly written or changed underneath us
+    verify_output: bool,
 }
 
 impl<ReadF, ExecC, WriteF> SubtaskHandleStream for CompositionWatcher<ReadF, ExecC, WriteF>
@@ -48,18 +58,39 @@ where
         tokio::task::spawn({
             let mut subgraphs = self.subgraphs.clone();
             let target_file = self.temp_dir.join("supergraph.yaml");
+            // Subgraphs composed with their last good SDL, and why they couldn't be fetched again
+            let mut stale = BTreeMap::<String, String>::new();
             async move {
                 while let Some(event) = input.next().await {
                     match event {
                         SubgraphEvent::SubgraphChanged(subgraph_schema_changed) => {
                             let name = subgraph_schema_changed.name();
                             let sdl = subgraph_schema_changed.sdl();
+                            stale.remove(name);
                             subgraphs.upsert_subgraph(name.to_string(), sdl.to_string());
                         }
                         SubgraphEvent::SubgraphRemoved(subgraph_removed) => {
                             let name = subgraph_removed.name();
+                            stale.remove(name);
                             subgraphs.remove_subgraph(name);
                         }
+                        // None of the subgraphs' SDL changed, so there's nothing to recompose
+                        SubgraphEvent::SubgraphStale(subgraph_stale) => {
+                            let name = subgraph_stale.name().to_string();
+                            let message = subgraph_stale.message().to_string();
+                            let event = if subgraphs.contains_subgraph(&name) {
+                                stale.insert(name.clone(), message.clone());
+                                CompositionEvent::SubgraphStale(SubgraphStale::new(name, message))
+                            } else {
+                                CompositionEvent::SubgraphResolutionFailed(
+                                    SubgraphResolutionFailed::from_message(name, message),
+                                )
+                            };
+                            let _ = sender
+                                .send(event)
+                                .tap_err(|err| tracing::error!("{:?}", err));
+                            continue;
+                        }
                     }
 
                     let supergraph_config = SupergraphConfig::from(subgraphs.clone());
@@ -85,6 +116,25 @@ where
                         continue;
                     }
 
+                    // point at any subgraph that's obviously broken before waiting on composition
+                    let subgraph_sdls = subgraphs
+                        .iter()
+                        .map(|(name, sdl)| (name.as_str(), sdl.as_str()));
+                    for validation_error in SubgraphValidationError::check_all(subgraph_sdls) {
+                        let _ = sender
+                            .send(CompositionEvent::SubgraphValidationError(validation_error))
+                            .tap_err(|err| tracing::error!("{:?}", err));
+                    }
+
+                    for (name, message) in &stale {
+                        let _ = sender
+                            .send(CompositionEvent::SubgraphStale(SubgraphStale::new(
+                                name.clone(),
+                                message.clone(),
+                            )))
+                            .tap_err(|err| tracing::error!("{:?}", err));
+                    }
+
                     let _ = sender
                         .send(CompositionEvent::Started)
                         .tap_err(|err| tracing::error!("{:?}", err));
@@ -97,13 +147,37 @@ where
                             &self.output_target,
                             target_file.clone(),
                         )
+                        .instrument(tracing::info_span!(
+                            "composition",
+                            composition_id = %Uuid::new_v4()
+                        ))
                         .await;
 
                     match output {
                         Ok(success) => {
+                            let supergraph_sdl = success.supergraph_sdl().to_string();
                             let _ = sender
                                 .send(CompositionEvent::Success(success))
                                 .tap_err(|err| tracing::error!("{:?}", err));
+                            if self.verify_output {
+                                for path in self.output_target.supergraph_files() {
+                                    let corrupted = match self.read_file.read_file(path).await {
+                                        Ok(contents) => SupergraphOutputCorrupted::check(
+                                            path,
+                                            &contents,
+                                            &supergraph_sdl,
+                                        ),
+                                        Err(err) => {
+                                            Some(SupergraphOutputCorrupted::unreadable(path, &err))
+                                        }
+                                    };
+                                    if let Some(corrupted) = corrupted {
+                                        let _ = sender
+                                            .send(CompositionEvent::OutputCorrupted(corrupted))
+                                            .tap_err(|err| tracing::error!("{:?}", err));
+                                    }
+                                }
+                            }
                         }
                         Err(err) => {
                             let _ = sender
@@ -130,7 +204,7 @@ mod tests {
     use apollo_federation_types::config::FederationVersion;
     use camino::Utf8PathBuf;
     use futures::{
-        stream::{once, BoxStream},
+        stream::{iter, once, BoxStream},
         StreamExt,
     };
     use mockall::predicate;
@@ -141,14 +215,14 @@ mod tests {
 
     use crate::{
         composition::{
-            events::CompositionEvent,
+            events::{CompositionEvent, SubgraphResolutionFailed, SubgraphStale},
             supergraph::{
-                binary::{OutputTarget, SupergraphBinary},
+                binary::{OutputTarget, SupergraphBinary, SupergraphSink},
                 config::full::FullyResolvedSubgraphs,
                 version::SupergraphVersion,
             },
             test::{default_composition_json, default_composition_success},
-            watchers::subgraphs::{SubgraphEvent, SubgraphSchemaChanged},
+            watchers::subgraphs::{SubgraphEvent, SubgraphSchemaChanged, SubgraphSchemaStale},
         },
         subtask::{Subtask, SubtaskRunStream},
         utils::effect::{
@@ -228,6 +302,7 @@ mod tests {
             .write_file(mock_write_file)
             .temp_dir(temp_dir_path)
             .output_target(OutputTarget::Stdout)
+            .verify_output(false)
             .build();
 
         let subgraph_change_events: BoxStream<SubgraphEvent> = once(async {
@@ -261,4 +336,216 @@ mod tests {
         abort_handle.abort();
         Ok(())
     }
+
+    /// A supergraph binary that composes successfully each of the `times` it's run
+    fn succeeding_supergraph_binary(times: usize) -> (SupergraphBinary, MockExecCommand) {
+        let supergraph_binary = SupergraphBinary::builder()
+            .version(SupergraphVersion::new(Version::from_str("2.8.0").unwrap()))
+            .exe(Utf8PathBuf::from_str("some/binary").unwrap())
+            .build();
+        let mut mock_exec = MockExecCommand::new();
+        mock_exec.expect_exec_command().times(times).returning(|_| {
+            Ok(Output {
+                status: ExitStatus::default(),
+                stdout: serde_json::to_vec(&default_composition_json()).unwrap(),
+                stderr: Vec::default(),
+            })
+        });
+        (supergraph_binary, mock_exec)
+    }
+
+    fn fed_two_eight() -> FederationVersion {
+        FederationVersion::ExactFedTwo(Version::from_str("2.8.0").unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_runcomposition_handle_composes_with_stale_subgraphs() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let temp_dir_path = Utf8PathBuf::from_path_buf(temp_dir.to_path_buf()).unwrap();
+
+        let (supergraph_binary, mock_exec) = succeeding_supergraph_binary(2);
+        let mut mock_read_file = MockReadFile::new();
+        mock_read_file.expect_read_file().times(0);
+        let mut mock_write_file = MockWriteFile::new();
+        mock_write_file
+            .expect_write_file()
+            .times(2)
+            .returning(|_, _| Ok(()));
+
+        let sdl = "type Query { test: String! }".to_string();
+        let composition_handler = CompositionWatcher::builder()
+            .subgraphs(FullyResolvedSubgraphs::new(BTreeMap::from([(
+                "products".to_string(),
+                sdl.clone(),
+            )])))
+            .supergraph_binary(supergraph_binary)
+            .exec_command(mock_exec)
+            .read_file(mock_read_file)
+            .write_file(mock_write_file)
+            .temp_dir(temp_dir_path)
+            .output_target(OutputTarget::Stdout)
+            .verify_output(false)
+            .build();
+
+        let subgraph_change_events: BoxStream<SubgraphEvent> = iter([
+            SubgraphEvent::SubgraphStale(SubgraphSchemaStale::new(
+                "products".to_string(),
+                "studio is unreachable".to_string(),
+            )),
+            SubgraphEvent::SubgraphStale(SubgraphSchemaStale::new(
+                "reviews".to_string(),
+                "studio is unreachable".to_string(),
+            )),
+            SubgraphEvent::SubgraphChanged(SubgraphSchemaChanged::new(
+                "inventory".to_string(),
+                sdl.clone(),
+            )),
+            // the background retry succeeded
+            SubgraphEvent::SubgraphChanged(SubgraphSchemaChanged::new("products".to_string(), sdl)),
+        ])
+        .boxed();
+        let (composition_messages, composition_subtask) = Subtask::new(composition_handler);
+        let abort_handle = composition_subtask.run(subgraph_change_events);
+
+        let stale = || {
+            CompositionEvent::SubgraphStale(SubgraphStale::new(
+                "products".to_string(),
+                "studio is unreachable".to_string(),
+            ))
+        };
+        let success = || CompositionEvent::Success(default_composition_success(fed_two_eight()));
+        let events = composition_messages.take(7).collect::<Vec<_>>().await;
+        assert_that!(events).is_equal_to(vec![
+            stale(),
+            // there's no earlier SDL to fall back to
+            CompositionEvent::SubgraphResolutionFailed(SubgraphResolutionFailed::from_message(
+                "reviews".to_string(),
+                "studio is unreachable".to_string(),
+            )),
+            stale(),
+            CompositionEvent::Started,
+            success(),
+            CompositionEvent::Started,
+            success(),
+        ]);
+
+        abort_handle.abort();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_runcomposition_handle_reports_invalid_subgraphs_first() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let temp_dir_path = Utf8PathBuf::from_path_buf(temp_dir.to_path_buf()).unwrap();
+
+        let (supergraph_binary, mock_exec) = succeeding_supergraph_binary(1);
+        let mut mock_read_file = MockReadFile::new();
+        mock_read_file.expect_read_file().times(0);
+        let mut mock_write_file = MockWriteFile::new();
+        mock_write_file
+            .expect_write_file()
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let composition_handler = CompositionWatcher::builder()
+            .subgraphs(FullyResolvedSubgraphs::new(BTreeMap::from([(
+                "products".to_string(),
+                "type Query { product: String }".to_string(),
+            )])))
+            .supergraph_binary(supergraph_binary)
+            .exec_command(mock_exec)
+            .read_file(mock_read_file)
+            .write_file(mock_write_file)
+            .temp_dir(temp_dir_path)
+            .output_target(OutputTarget::Stdout)
+            .verify_output(false)
+            .build();
+
+        let subgraph_change_events: BoxStream<SubgraphEvent> = once(async {
+            SubgraphEvent::SubgraphChanged(SubgraphSchemaChanged::new(
+                "reviews".to_string(),
+                "type Review @key { id: ID! }".to_string(),
+            ))
+        })
+        .boxed();
+        let (mut composition_messages, composition_subtask) = Subtask::new(composition_handler);
+        let abort_handle = composition_subtask.run(subgraph_change_events);
+
+        let Some(CompositionEvent::SubgraphValidationError(validation_error)) =
+            composition_messages.next().await
+        else {
+            panic!("expected a subgraph validation error before composition started");
+        };
+        assert_that!(validation_error.subgraph_name()).is_equal_to(&"reviews".to_string());
+        assert_that!(validation_error.errors()).has_length(1);
+        assert_that!(composition_messages.next().await)
+            .is_some()
+            .is_equal_to(CompositionEvent::Started);
+        assert_that!(composition_messages.next().await)
+            .is_some()
+            .matches(|event| matches!(event, CompositionEvent::Success(..)));
+
+        abort_handle.abort();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_runcomposition_handle_reports_corrupted_output() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let temp_dir_path = Utf8PathBuf::from_path_buf(temp_dir.to_path_buf()).unwrap();
+        let output_path = temp_dir_path.join("supergraph.graphql");
+
+        let (supergraph_binary, mock_exec) = succeeding_supergraph_binary(1);
+        let mut mock_read_file = MockReadFile::new();
+        mock_read_file
+            .expect_read_file()
+            .times(1)
+            .with(predicate::eq(output_path.clone()))
+            .returning(|_| Ok("schema @link(url: \"https://specs.apollo".to_string()));
+        let mut mock_write_file = MockWriteFile::new();
+        mock_write_file
+            .expect_write_file()
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let composition_handler = CompositionWatcher::builder()
+            .subgraphs(FullyResolvedSubgraphs::new(BTreeMap::new()))
+            .supergraph_binary(supergraph_binary)
+            .exec_command(mock_exec)
+            .read_file(mock_read_file)
+            .write_file(mock_write_file)
+            .temp_dir(temp_dir_path)
+            .output_target(OutputTarget::Sinks(vec![
+                SupergraphSink::Stdout,
+                SupergraphSink::File(output_path.clone()),
+            ]))
+            .verify_output(true)
+            .build();
+
+        let subgraph_change_events: BoxStream<SubgraphEvent> = once(async {
+            SubgraphEvent::SubgraphChanged(SubgraphSchemaChanged::new(
+                "subgraph-name".to_string(),
+                "type Query { test: String! }".to_string(),
+            ))
+        })
+        .boxed();
+        let (mut composition_messages, composition_subtask) = Subtask::new(composition_handler);
+        let abort_handle = composition_subtask.run(subgraph_change_events);
+
+        assert_that!(composition_messages.next().await)
+            .is_some()
+            .is_equal_to(CompositionEvent::Started);
+        assert_that!(composition_messages.next().await)
+            .is_some()
+            .matches(|event| matches!(event, CompositionEvent::Success(..)));
+        let Some(CompositionEvent::OutputCorrupted(corrupted)) = composition_messages.next().await
+        else {
+            panic!("expected the partly written supergraph to be reported");
+        };
+        assert_that!(corrupted.path()).is_equal_to(&output_path);
+        assert_that!(corrupted.reason()).starts_with("the file isn't valid SDL");
+
+        abort_handle.abort();
+        Ok(())
+    }
 }
@@ -7,14 +7,16 @@ use tokio::{sync::mpsc::UnboundedSender, task::AbortHandle};
 use tokio_stream::{wrappers::UnboundedReceiverStream, StreamExt};
 
 use crate::{
-    composition::supergraph::config::lazy::LazilyResolvedSubgraph,
+    composition::supergraph::config::{
+        lazy::LazilyResolvedSubgraph, resolver::SubgraphRetryPolicy,
+    },
     options::ProfileOpt,
     subtask::{Subtask, SubtaskHandleStream, SubtaskRunUnit},
     utils::client::StudioClientConfig,
 };
 
 use super::watcher::{
-    subgraph::{SubgraphWatcher, SubgraphWatcherKind, WatchedSdlChange},
+    subgraph::{NonRepeatingFetch, SubgraphWatcher, SubgraphWatcherKind, WatchedSdlChange},
     supergraph_config::SupergraphConfigDiff,
 };
 
@@ -44,12 +46,15 @@ impl SubgraphWatchers {
         let watchers = subgraphs
             .into_iter()
             .filter_map(|(name, resolved_subgraph)| {
+                let introspection = resolved_subgraph.introspection().clone();
                 let subgraph_config = SubgraphConfig::from(resolved_subgraph);
                 SubgraphWatcher::from_schema_source(
+                    &name,
                     subgraph_config.schema,
                     profile,
                     client_config,
                     introspection_polling_interval,
+                    introspection.as_ref(),
                 )
                 .tap_err(|err| tracing::warn!("Skipping subgraph {}: {:?}", name, err))
                 .ok()
@@ -69,11 +74,16 @@ impl SubgraphWatchers {
 /// Events about watched subgraphs. If they're changed, the subgraph's name and changed SDL are
 /// emitted via SubgraphChanged. If they're removed, a SubgraphRemoved event is emitted with the
 /// name of the subgraph
+#[allow(clippy::enum_variant_names)]
 pub enum SubgraphEvent {
     /// A change to the watched subgraph
     SubgraphChanged(SubgraphSchemaChanged),
     /// The subgraph is no longer watched
     SubgraphRemoved(SubgraphSchemaRemoved),
+    /// The subgraph's SDL couldn't be fetched again after its source changed, so whatever SDL it
+    /// had before is stale. The fetch is retried in the background, which emits a
+    /// SubgraphChanged event once it succeeds
+    SubgraphStale(SubgraphSchemaStale),
 }
 /// An event denoting that the subgraph has changed, emitting its name and the SDL reflecting that
 /// change
@@ -99,6 +109,83 @@ pub struct SubgraphSchemaRemoved {
     name: String,
 }
 
+/// The subgraph's SDL couldn't be fetched, and is being retried in the background
+#[derive(derive_getters::Getters, Default)]
+pub struct SubgraphSchemaStale {
+    /// The name of the stale subgraph
+    name: String,
+    /// Why the SDL couldn't be fetched
+    message: String,
+}
+
+impl SubgraphSchemaStale {
+    #[cfg(test)]
+    pub fn new(name: String, message: String) -> SubgraphSchemaStale {
+        SubgraphSchemaStale { name, message }
+    }
+}
+
+/// Fetches a subgraph's SDL from a source that isn't watched. If that fails, for instance because
+/// Studio is briefly unreachable, the subgraph is reported as stale rather than failing the
+/// recomposition, and the fetch is retried in the background until it succeeds. Any retry still
+/// running for the subgraph is stopped first, since it's fetching from the old source
+async fn fetch_once(
+    name: &str,
+    fetch: NonRepeatingFetch,
+    sender: &UnboundedSender<SubgraphEvent>,
+    retries: &mut HashMap<String, AbortHandle>,
+) {
+    if let Some(retry) = retries.remove(name) {
+        retry.abort();
+    }
+    match fetch.run().await {
+        Ok(sdl) => {
+            let _ = sender
+                .send(SubgraphEvent::SubgraphChanged(SubgraphSchemaChanged {
+                    name: name.to_string(),
+                    sdl,
+                }))
+                .tap_err(|err| tracing::error!("{:?}", err));
+        }
+        Err(err) => {
+            tracing::error!("failed to get {name}'s SDL: {err:?}");
+            let _ = sender
+                .send(SubgraphEvent::SubgraphStale(SubgraphSchemaStale {
+                    name: name.to_string(),
+                    message: err.to_string(),
+                }))
+                .tap_err(|err| tracing::error!("{:?}", err));
+
+            let name_c = name.to_string();
+            let sender = sender.clone();
+            let retry = tokio::spawn(async move {
+                let retry_policy = SubgraphRetryPolicy::default();
+                let mut retry = 0;
+                loop {
+                    tokio::time::sleep(retry_policy.backoff(retry)).await;
+                    match fetch.run().await {
+                        Ok(sdl) => {
+                            let _ = sender
+                                .send(SubgraphEvent::SubgraphChanged(SubgraphSchemaChanged {
+                                    name: name_c,
+                                    sdl,
+                                }))
+                                .tap_err(|err| tracing::error!("{:?}", err));
+                            break;
+                        }
+                        Err(err) => {
+                            tracing::debug!("retry {retry} for {name_c}'s SDL failed: {err:?}");
+                            retry = retry.saturating_add(1);
+                        }
+                    }
+                }
+            })
+            .abort_handle();
+            retries.insert(name.to_string(), retry);
+        }
+    }
+}
+
 impl SubtaskHandleStream for SubgraphWatchers {
     type Input = SupergraphConfigDiff;
     type Output = SubgraphEvent;
@@ -110,6 +197,8 @@ impl SubtaskHandleStream for SubgraphWatchers {
     ) -> AbortHandle {
         tokio::task::spawn(async move {
             let mut abort_handles: HashMap<String, (AbortHandle, AbortHandle)> = HashMap::new();
+            // Background retries of subgraphs whose SDL couldn't be fetched, by subgraph name
+            let mut retries: HashMap<String, AbortHandle> = HashMap::new();
             // Start a background task for each of the subtask watchers that listens for change
             // events and send each event to the parent sender to be consumed by the composition
             // handler.
@@ -139,10 +228,12 @@ impl SubtaskHandleStream for SubgraphWatchers {
                 // Adding the abort handle to the currentl collection of handles.
                 for (subgraph_name, subgraph_config) in diff.added() {
                     if let Ok(subgraph_watcher) = SubgraphWatcher::from_schema_source(
+                        subgraph_name,
                         subgraph_config.schema.clone(),
                         &self.profile,
                         &self.client_config,
                         self.introspection_polling_interval,
+                        diff.introspection_configs().get(subgraph_name),
                     )
                     .tap_err(|err| {
                         tracing::warn!(
@@ -157,22 +248,13 @@ impl SubtaskHandleStream for SubgraphWatchers {
                         if let SubgraphWatcherKind::Once(non_repeating_fetch) =
                             subgraph_watcher.watcher()
                         {
-                            let _ = non_repeating_fetch
-                                .run()
-                                .await
-                                .tap_err(|err| {
-                                    tracing::error!("failed to get {subgraph_name}'s SDL: {err:?}")
-                                })
-                                .map(|sdl| {
-                                    let _ = sender
-                                        .send(SubgraphEvent::SubgraphChanged(
-                                            SubgraphSchemaChanged {
-                                                name: subgraph_name.to_string(),
-                                                sdl,
-                                            },
-                                        ))
-                                        .tap_err(|err| tracing::error!("{:?}", err));
-                                });
+                            fetch_once(
+                                subgraph_name,
+                                non_repeating_fetch.clone(),
+                                &sender,
+                                &mut retries,
+                            )
+                            .await;
                         // When we have a SchemaSource that's watchable, we start a new subtask
                         // and add it to our list of subtasks
                         } else {
@@ -205,36 +287,27 @@ impl SubtaskHandleStream for SubgraphWatchers {
 
                 for (name, subgraph_config) in diff.changed() {
                     if let Ok(watcher) = SubgraphWatcher::from_schema_source(
+                        name,
                         subgraph_config.schema.clone(),
                         &self.profile,
                         &self.client_config,
                         self.introspection_polling_interval,
+                        diff.introspection_configs().get(name),
                     )
                     .tap_err(|err| tracing::error!("Unable to get watcher: {err:?}"))
                     {
                         if let SubgraphWatcherKind::Once(non_repeating_fetch) = watcher.watcher() {
-                            let _ = non_repeating_fetch
-                                .run()
-                                .await
-                                .tap_err(|err| {
-                                    tracing::error!("failed to get {name}'s SDL: {err:?}")
-                                })
-                                .map(|sdl| {
-                                    let _ = sender
-                                        .send(SubgraphEvent::SubgraphChanged(
-                                            SubgraphSchemaChanged {
-                                                name: name.to_string(),
-                                                sdl,
-                                            },
-                                        ))
-                                        .tap_err(|err| tracing::error!("{:?}", err));
-                                });
+                            fetch_once(name, non_repeating_fetch.clone(), &sender, &mut retries)
+                                .await;
                         }
                     }
                 }
 
                 // If we detect removal diffs, stop the subtask for the removed subgraph.
                 for name in diff.removed() {
+                    if let Some(retry) = retries.remove(name) {
+                        retry.abort();
+                    }
                     if let Some((messages_abort_handle, subtask_abort_handle)) =
                         abort_handles.get(name)
                     {
@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use futures::{stream::BoxStream, StreamExt};
+use tap::TapFallible;
+
+use crate::{
+    composition::supergraph::config::extended::SchemaUrl,
+    utils::client::{ClientIdentity, StudioClientConfig},
+};
+
+/// A schema downloaded as raw SDL from a URL, polled for changes
+///
+/// Every poll sends the `ETag` of the last download in an `If-None-Match` header, so servers that
+/// support it answer with `304 Not Modified` instead of the whole schema when it hasn't changed
+#[derive(Debug, Clone)]
+pub struct SchemaDownload {
+    schema_url: SchemaUrl,
+    client_config: StudioClientConfig,
+    polling_interval: Duration,
+    timeout: Duration,
+    client_identity: Option<ClientIdentity>,
+    /// The schema as it was last downloaded, which changes are compared against. Without one, the
+    /// first download is only compared against later ones, since it's the schema the subgraph was
+    /// resolved with when watching started
+    sdl: Option<String>,
+}
+
+impl SchemaDownload {
+    pub fn new(
+        schema_url: SchemaUrl,
+        client_config: &StudioClientConfig,
+        polling_interval: u64,
+        timeout: Option<u64>,
+        client_identity: Option<ClientIdentity>,
+        sdl: Option<String>,
+    ) -> Self {
+        Self {
+            schema_url,
+            client_config: client_config.clone(),
+            polling_interval: Duration::from_secs(polling_interval),
+            // The same default timeout as introspected subgraphs
+            timeout: Duration::from_secs(timeout.unwrap_or(5)),
+            client_identity,
+            sdl,
+        }
+    }
+
+    /// Polls the URL, emitting the schema whenever it's different from the last download
+    pub fn watch(&self) -> BoxStream<'static, String> {
+        let mut client_builder = self.client_config.get_builder().with_timeout(self.timeout);
+        if let Some(client_identity) = &self.client_identity {
+            client_builder = client_builder.with_client_identity(Some(client_identity.clone()));
+        }
+        let client = client_builder
+            .build()
+            .tap_err(|err| {
+                tracing::error!("Something went wrong when trying to construct a client: {err:?}")
+            })
+            .expect("Failed to construct a client");
+
+        let schema_url = self.schema_url.clone();
+        let polling_interval = self.polling_interval;
+        futures::stream::unfold(
+            (None, self.sdl.clone()),
+            move |(mut etag, mut last_sdl): (Option<String>, Option<String>)| {
+                let client = client.clone();
+                let schema_url = schema_url.clone();
+                async move {
+                    loop {
+                        tokio::time::sleep(polling_interval).await;
+                        match schema_url.download(&client, etag.as_deref()).await {
+                            Ok(Some((new_etag, sdl))) => {
+                                etag = new_etag;
+                                match last_sdl.replace(sdl.clone()) {
+                                    Some(previous) if previous != sdl => {
+                                        return Some((sdl, (etag, last_sdl)));
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            Ok(None) => {}
+                            Err(err) => tracing::error!(
+                                "Could not download the schema from {}: {err:?}",
+                                schema_url.url
+                            ),
+                        }
+                    }
+                }
+            },
+        )
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, str::FromStr, time::Duration};
+
+    use camino::Utf8PathBuf;
+    use futures::StreamExt;
+    use houston::Config;
+    use httpmock::MockServer;
+    use speculoos::prelude::*;
+
+    use super::SchemaDownload;
+    use crate::{
+        composition::supergraph::config::extended::SchemaUrl,
+        utils::client::{ClientBuilder, StudioClientConfig},
+    };
+
+    #[tokio::test]
+    async fn test_watch_emits_changed_schemas() {
+        let server = MockServer::start();
+        let unchanged = server.mock(|when, then| {
+            when.method("GET")
+                .path("/products.graphql")
+                .header("authorization", "Bearer token")
+                .header("if-none-match", "\"v1\"");
+            then.status(304);
+        });
+        let first = server.mock(|when, then| {
+            when.method("GET")
+                .path("/products.graphql")
+                .matches(|request| {
+                    !request
+                        .headers
+                        .iter()
+                        .flatten()
+                        .any(|(name, _)| name.eq_ignore_ascii_case("if-none-match"))
+                });
+            then.status(200)
+                .header("etag", "\"v1\"")
+                .body("type Query { products: [String] }");
+        });
+        let client_config = StudioClientConfig::new(
+            None,
+            Config {
+                home: Utf8PathBuf::from_str("path").unwrap(),
+                override_api_key: None,
+            },
+            false,
+            ClientBuilder::new(),
+            None,
+        );
+        let schema_download = SchemaDownload::new(
+            SchemaUrl {
+                url: server.url("/products.graphql"),
+                headers: Some(BTreeMap::from([(
+                    "Authorization".to_string(),
+                    "Bearer token".to_string(),
+                )])),
+            },
+            &client_config,
+            0,
+            None,
+            None,
+            Some("type Query { products: [Int] }".to_string()),
+        );
+        let mut watching = schema_download.watch();
+
+        let sdl = tokio::time::timeout(Duration::from_secs(5), watching.next()).await;
+        assert_that!(sdl.unwrap())
+            .is_equal_to(Some("type Query { products: [String] }".to_string()));
+        first.assert();
+
+        // later polls send the ETag of the download, and nothing is emitted while it's unchanged
+        let sdl = tokio::time::timeout(Duration::from_millis(500), watching.next()).await;
+        assert_that!(sdl).is_err();
+        assert_that!(unchanged.hits()).is_greater_than(0);
+    }
+}
@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use futures::{stream::BoxStream, StreamExt};
+
+use crate::composition::supergraph::config::extended::SchemaGit;
+
+/// A schema file in a git repository, polled for new commits
+#[derive(Debug, Clone)]
+pub struct GitSchema {
+    subgraph_name: String,
+    schema_git: SchemaGit,
+    polling_interval: Duration,
+    /// The schema as it was last read, which changes are compared against
+    sdl: String,
+}
+
+impl GitSchema {
+    pub fn new(
+        subgraph_name: String,
+        schema_git: SchemaGit,
+        polling_interval: u64,
+        sdl: String,
+    ) -> Self {
+        Self {
+            subgraph_name,
+            schema_git,
+            polling_interval: Duration::from_secs(polling_interval),
+            sdl,
+        }
+    }
+
+    /// Fetches the repository every polling interval, emitting the schema whenever a new commit
+    /// changes it. Commits that leave the schema file as it was aren't emitted
+    pub fn watch(&self) -> BoxStream<'static, String> {
+        let subgraph_name = self.subgraph_name.clone();
+        let schema_git = self.schema_git.clone();
+        let polling_interval = self.polling_interval;
+        futures::stream::unfold(
+            (None, self.sdl.clone()),
+            move |(mut last_commit, mut last_sdl): (Option<String>, String)| {
+                let subgraph_name = subgraph_name.clone();
+                let schema_git = schema_git.clone();
+                async move {
+                    loop {
+                        tokio::time::sleep(polling_interval).await;
+                        let fetched = {
+                            let subgraph_name = subgraph_name.clone();
+                            let schema_git = schema_git.clone();
+                            // fetching runs `git`, which blocks
+                            tokio::task::spawn_blocking(move || schema_git.fetch(&subgraph_name))
+                                .await
+                        };
+                        match fetched {
+                            Ok(Ok((commit, sdl))) => {
+                                if last_commit.as_ref() == Some(&commit) {
+                                    continue;
+                                }
+                                last_commit = Some(commit);
+                                if sdl != last_sdl {
+                                    last_sdl = sdl.clone();
+                                    return Some((sdl, (last_commit, last_sdl)));
+                                }
+                            }
+                            Ok(Err(err)) => tracing::error!("{err}"),
+                            Err(err) => tracing::error!("{err:?}"),
+                        }
+                    }
+                }
+            },
+        )
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{process::Command, time::Duration};
+
+    use assert_fs::{prelude::*, TempDir};
+    use futures::StreamExt;
+    use speculoos::prelude::*;
+
+    use super::GitSchema;
+    use crate::composition::supergraph::config::extended::SchemaGit;
+
+    fn commit(repo: &std::path::Path, message: &str) {
+        for args in [
+            vec!["add", "."],
+            vec!["commit", "--quiet", "--message", message],
+        ] {
+            let status = Command::new("git")
+                .args([
+                    "-c",
+                    "user.name=rover",
+                    "-c",
+                    "user.email=rover@example.com",
+                ])
+                .arg("-C")
+                .arg(repo)
+                .args(args)
+                .status()
+                .unwrap();
+            assert_that!(status.success()).is_true();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watch_emits_schemas_changed_by_new_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.child("products");
+        repo.create_dir_all().unwrap();
+        let status = Command::new("git")
+            .args(["init", "--quiet", "--initial-branch", "main"])
+            .arg(repo.path())
+            .status()
+            .unwrap();
+        assert_that!(status.success()).is_true();
+        repo.child("schema.graphql")
+            .write_str("type Query { products: [String] }")
+            .unwrap();
+        commit(repo.path(), "Add the products schema");
+
+        let git_schema = GitSchema::new(
+            "products".to_string(),
+            SchemaGit {
+                git: format!("file://{}", repo.path().display()),
+                path: "schema.graphql".into(),
+                rev: Some("main".to_string()),
+            },
+            0,
+            "type Query { products: [Int] }".to_string(),
+        );
+
+        let sdl = temp_env::async_with_vars(
+            [("APOLLO_HOME", Some(temp_dir.child("home").path()))],
+            async {
+                let mut watching = git_schema.watch();
+                tokio::time::timeout(Duration::from_secs(10), watching.next()).await
+            },
+        )
+        .await;
+        assert_that!(sdl.unwrap())
+            .is_equal_to(Some("type Query { products: [String] }".to_string()));
+    }
+}
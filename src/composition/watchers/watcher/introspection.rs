@@ -10,7 +10,10 @@ use crate::{
     command::subgraph::introspect::Introspect as SubgraphIntrospect,
     composition::types::SubgraphUrl,
     options::{IntrospectOpts, OutputChannelKind, OutputOpts},
-    utils::client::StudioClientConfig,
+    utils::{
+        client::{ClientIdentity, StudioClientConfig},
+        introspection_auth::IntrospectionAuthenticator,
+    },
 };
 
 /// Subgraph introspection
@@ -21,6 +24,9 @@ pub struct SubgraphIntrospection {
     headers: Option<Vec<(String, String)>>,
     client_config: StudioClientConfig,
     polling_interval: Duration,
+    timeout: Duration,
+    client_identity: Option<ClientIdentity>,
+    auth: Option<IntrospectionAuthenticator>,
 }
 
 //TODO: impl retry (needed at least for dev)
@@ -30,25 +36,32 @@ impl SubgraphIntrospection {
         headers: Option<Vec<(String, String)>>,
         client_config: &StudioClientConfig,
         polling_interval: u64,
+        timeout: Option<u64>,
+        client_identity: Option<ClientIdentity>,
+        auth: Option<IntrospectionAuthenticator>,
     ) -> Self {
         Self {
             endpoint,
             headers,
             client_config: client_config.clone(),
             polling_interval: Duration::from_secs(polling_interval),
+            // This was the previous subgraph watching implementation's default timeout
+            timeout: Duration::from_secs(timeout.unwrap_or(5)),
+            client_identity,
+            auth,
         }
     }
 
     // TODO: better typing so that it's over some impl, not string; makes all watch() fns require
     // returning a string
     pub fn watch(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
-        let client = self
-            .client_config
-            .get_builder()
-            // TODO: this was the previous subgraph watching implementation's default timeout, but
-            // we might want to let users control it (or at least override it if they pass in a
-            // timeout)
-            .with_timeout(Duration::from_secs(5))
+        let mut client_builder = self.client_config.get_builder().with_timeout(self.timeout);
+        // a certificate from the subgraph's introspection settings takes precedence over the one
+        // passed with `--client-cert`
+        if let Some(client_identity) = &self.client_identity {
+            client_builder = client_builder.with_client_identity(Some(client_identity.clone()));
+        }
+        let client = client_builder
             .build()
             .tap_err(|err| {
                 tracing::error!(
@@ -61,6 +74,7 @@ impl SubgraphIntrospection {
         let endpoint = self.endpoint.clone();
         let headers = self.headers.clone();
         let polling_interval = self.polling_interval;
+        let auth = self.auth.clone();
 
         let (tx, rx) = unbounded_channel();
         let rx_stream = UnboundedReceiverStream::new(rx);
@@ -73,14 +87,22 @@ impl SubgraphIntrospection {
                     endpoint,
                     headers,
                     watch: true,
+                    diff: false,
                     polling_interval,
                 },
+                subscriptions: false,
+                // fetched again on whichever poll finds the token about to expire
+                auth,
             }
             .run(
                 client,
                 &OutputOpts {
                     format_kind: RoverOutputFormatKind::default(),
                     output_file: None,
+                    verbose: false,
+                    json_path: None,
+                    quiet: false,
+                    porcelain: false,
                     // Attach a transmitter to stream back any subgraph changes
                     channel: Some(tx),
                 },
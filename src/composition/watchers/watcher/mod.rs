@@ -1,4 +1,6 @@
+pub mod download;
 pub mod file;
+pub mod git;
 pub mod introspection;
 pub mod remote;
 pub mod sdl;
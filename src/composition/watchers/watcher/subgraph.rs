@@ -6,11 +6,13 @@ use tap::TapFallible;
 use tokio::{sync::mpsc::UnboundedSender, task::AbortHandle};
 
 use crate::{
+    composition::supergraph::config::introspection::SubgraphIntrospectionConfig,
     options::ProfileOpt, subtask::SubtaskHandleUnit, utils::client::StudioClientConfig, RoverError,
 };
 
 use super::{
-    file::FileWatcher, introspection::SubgraphIntrospection, remote::RemoteSchema, sdl::Sdl,
+    download::SchemaDownload, file::FileWatcher, git::GitSchema,
+    introspection::SubgraphIntrospection, remote::RemoteSchema, sdl::Sdl,
 };
 
 #[derive(thiserror::Error, Debug)]
@@ -50,6 +52,10 @@ pub enum SubgraphWatcherKind {
     File(FileWatcher),
     /// Poll an endpoint via introspection.
     Introspect(SubgraphIntrospection),
+    /// Poll a URL that serves the schema as raw SDL.
+    Download(SchemaDownload),
+    /// Poll a git repository for new commits to the schema file.
+    Git(GitSchema),
     /// When there's an in-place change (eg, the SDL in the SupergraphConfig has changed or the
     /// SchemaSource::Subgraph now has a different subgraph name or points to a different
     /// GraphRef), we don't watch for changes: we either emit the changed SDL directly or call into
@@ -59,11 +65,17 @@ pub enum SubgraphWatcherKind {
 
 impl SubgraphWatcher {
     /// Derive the right SubgraphWatcher (ie, File, Introspection) from the federation-rs SchemaSource
+    ///
+    /// The subgraph's own introspection settings, if any, take precedence over
+    /// `introspection_polling_interval`. A schema downloaded from a URL or read from a git
+    /// repository arrives as SDL, and is told apart from inline SDL by its source in those settings
     pub fn from_schema_source(
+        subgraph_name: &str,
         schema_source: SchemaSource,
         profile: &ProfileOpt,
         client_config: &StudioClientConfig,
         introspection_polling_interval: u64,
+        introspection: Option<&SubgraphIntrospectionConfig>,
     ) -> Result<Self, Box<UnsupportedSchemaSource>> {
         // SchemaSource comes from Apollo Federation types. Importantly, it strips comments and
         // directives from introspection (but not when the source is a file)
@@ -74,22 +86,58 @@ impl SubgraphWatcher {
             SchemaSource::SubgraphIntrospection {
                 subgraph_url,
                 introspection_headers,
-            } => Ok(Self {
-                watcher: SubgraphWatcherKind::Introspect(SubgraphIntrospection::new(
-                    subgraph_url,
-                    introspection_headers.map(|header_map| header_map.into_iter().collect()),
-                    client_config,
-                    introspection_polling_interval,
-                )),
-            }),
+            } => {
+                let polling_interval = introspection
+                    .and_then(|introspection| introspection.poll_interval_seconds)
+                    .unwrap_or(introspection_polling_interval);
+                let watcher = match introspection {
+                    // a `url` schema source stands in as the URL it's downloaded from
+                    Some(SubgraphIntrospectionConfig {
+                        schema_url: Some(schema_url),
+                        ..
+                    }) => SubgraphWatcherKind::Download(SchemaDownload::new(
+                        schema_url.clone(),
+                        client_config,
+                        polling_interval,
+                        introspection.and_then(|introspection| introspection.timeout),
+                        introspection.and_then(|introspection| introspection.client_identity()),
+                        None,
+                    )),
+                    _ => SubgraphWatcherKind::Introspect(SubgraphIntrospection::new(
+                        subgraph_url,
+                        introspection_headers.map(|header_map| header_map.into_iter().collect()),
+                        client_config,
+                        polling_interval,
+                        introspection.and_then(|introspection| introspection.timeout),
+                        introspection.and_then(|introspection| introspection.client_identity()),
+                        introspection.and_then(|introspection| introspection.auth.clone()),
+                    )),
+                };
+                Ok(Self { watcher })
+            }
             SchemaSource::Subgraph { graphref, subgraph } => Ok(Self {
                 watcher: SubgraphWatcherKind::Once(NonRepeatingFetch::RemoteSchema(
                     RemoteSchema::new(graphref, subgraph, profile, client_config),
                 )),
             }),
-            SchemaSource::Sdl { sdl } => Ok(Self {
-                watcher: SubgraphWatcherKind::Once(NonRepeatingFetch::Sdl(Sdl::new(sdl))),
-            }),
+            SchemaSource::Sdl { sdl } => {
+                let polling_interval = introspection
+                    .and_then(|introspection| introspection.poll_interval_seconds)
+                    .unwrap_or(introspection_polling_interval);
+                let watcher = match introspection {
+                    Some(SubgraphIntrospectionConfig {
+                        schema_git: Some(schema_git),
+                        ..
+                    }) => SubgraphWatcherKind::Git(GitSchema::new(
+                        subgraph_name.to_string(),
+                        schema_git.clone(),
+                        polling_interval,
+                        sdl,
+                    )),
+                    _ => SubgraphWatcherKind::Once(NonRepeatingFetch::Sdl(Sdl::new(sdl))),
+                };
+                Ok(Self { watcher })
+            }
         }
     }
 }
@@ -103,6 +151,8 @@ impl SubgraphWatcherKind {
         match self {
             Self::File(file_watcher) => file_watcher.clone().watch(),
             Self::Introspect(introspection) => introspection.watch(),
+            Self::Download(schema_download) => schema_download.watch(),
+            Self::Git(git_schema) => git_schema.watch(),
             kind => unimplemented!("{kind:?} is not a watcher"),
         }
     }
@@ -1,14 +1,18 @@
 use std::collections::{BTreeMap, HashSet};
 
 use apollo_federation_types::config::{ConfigError, SubgraphConfig, SupergraphConfig};
+use camino::Utf8PathBuf;
 use derive_getters::Getters;
-use futures::StreamExt;
-use rover_std::errln;
+use futures::{stream, StreamExt};
+use rover_std::{errln, Fs};
 use tap::TapFallible;
 use tokio::{sync::mpsc::UnboundedSender, task::AbortHandle};
 
 use crate::{
-    composition::supergraph::config::lazy::LazilyResolvedSupergraphConfig,
+    composition::supergraph::config::{
+        extended::parse_supergraph_config, introspection::SubgraphIntrospectionConfig,
+        lazy::LazilyResolvedSupergraphConfig, unresolved::IncludedSupergraphConfigs,
+    },
     subtask::SubtaskHandleUnit,
 };
 
@@ -17,6 +21,9 @@ use super::file::FileWatcher;
 #[derive(Debug)]
 pub struct SupergraphConfigWatcher {
     file_watcher: FileWatcher,
+    origin_path: Option<Utf8PathBuf>,
+    included_paths: Vec<Utf8PathBuf>,
+    environment: Option<String>,
     supergraph_config: SupergraphConfig,
 }
 
@@ -27,6 +34,9 @@ impl SupergraphConfigWatcher {
     ) -> SupergraphConfigWatcher {
         SupergraphConfigWatcher {
             file_watcher,
+            origin_path: supergraph_config.origin_path().clone(),
+            included_paths: supergraph_config.included_paths().clone(),
+            environment: supergraph_config.environment().clone(),
             supergraph_config: supergraph_config.into(),
         }
     }
@@ -38,13 +48,82 @@ impl SubtaskHandleUnit for SupergraphConfigWatcher {
     fn handle(self, sender: UnboundedSender<Self::Output>) -> AbortHandle {
         tokio::spawn(async move {
             let mut latest_supergraph_config = self.supergraph_config.clone();
-            while let Some(contents) = self.file_watcher.clone().watch().next().await {
-                match SupergraphConfig::new_from_yaml(&contents) {
-                    Ok(supergraph_config) => {
+            // A change to an included supergraph config means re-reading the supergraph config
+            // that includes it, so that everything gets merged again. Supergraph configs that
+            // are only included after the watcher starts aren't watched
+            let origin_path = self.origin_path.clone();
+            let included_changes = self.included_paths.iter().map(|included_path| {
+                let origin_path = origin_path.clone();
+                FileWatcher::new(included_path.clone())
+                    .watch()
+                    .filter_map(move |_| {
+                        let contents = origin_path.as_ref().and_then(|origin_path| {
+                            Fs::read_file(origin_path)
+                                .tap_err(|err| tracing::error!("Could not read file: {:?}", err))
+                                .ok()
+                        });
+                        async move { contents }
+                    })
+                    .boxed()
+            });
+            let mut changes = stream::select_all(
+                std::iter::once(self.file_watcher.clone().watch()).chain(included_changes),
+            );
+            while let Some(contents) = changes.next().await {
+                let environment = self.environment.as_deref();
+                match parse_supergraph_config(&contents, self.origin_path.as_deref(), environment)
+                    .and_then(|supergraph_config| {
+                        IncludedSupergraphConfigs::load(
+                            &contents,
+                            self.origin_path.as_ref(),
+                            environment,
+                        )
+                        .map(|included| {
+                            let introspection_configs = SubgraphIntrospectionConfig::load_all(
+                                &contents,
+                                self.origin_path.as_ref(),
+                                environment,
+                            )
+                            .tap_err(|err| {
+                                tracing::error!("could not parse introspection settings: {:?}", err)
+                            })
+                            .unwrap_or_default();
+                            (
+                                included.merge_introspection_configs(
+                                    &supergraph_config,
+                                    introspection_configs,
+                                ),
+                                included.merge(supergraph_config),
+                            )
+                        })
+                        .map_err(|err| ConfigError::InvalidConfiguration {
+                            message: err.to_string(),
+                        })
+                    }) {
+                    Ok((introspection_configs, supergraph_config)) => {
+                        // Headers from the introspection settings end up in the schema source
+                        // when the supergraph config is first resolved, so they're applied here
+                        // too for the comparison with the latest supergraph config to hold up
+                        let federation_version = supergraph_config.get_federation_version();
+                        let supergraph_config = SupergraphConfig::new(
+                            supergraph_config
+                                .into_iter()
+                                .map(|(name, mut subgraph_config)| {
+                                    if let Some(introspection) = introspection_configs.get(&name) {
+                                        subgraph_config.schema =
+                                            introspection.apply_headers(subgraph_config.schema);
+                                    }
+                                    (name, subgraph_config)
+                                })
+                                .collect(),
+                            federation_version,
+                        );
                         if let Ok(supergraph_config_diff) = SupergraphConfigDiff::new(
                             &latest_supergraph_config,
                             supergraph_config.clone(),
                         ) {
+                            let supergraph_config_diff = supergraph_config_diff
+                                .with_introspection_configs(introspection_configs);
                             let _ = sender
                                 .send(supergraph_config_diff)
                                 .tap_err(|err| tracing::error!("{:?}", err));
@@ -67,6 +146,8 @@ pub struct SupergraphConfigDiff {
     added: Vec<(String, SubgraphConfig)>,
     changed: Vec<(String, SubgraphConfig)>,
     removed: Vec<String>,
+    /// The introspection settings of subgraphs in the new supergraph config, by subgraph name
+    introspection_configs: BTreeMap<String, SubgraphIntrospectionConfig>,
 }
 
 impl SupergraphConfigDiff {
@@ -127,8 +208,18 @@ impl SupergraphConfigDiff {
             added,
             changed,
             removed,
+            introspection_configs: BTreeMap::new(),
         })
     }
+
+    /// Attaches the introspection settings that apply to added and changed subgraphs
+    pub fn with_introspection_configs(
+        mut self,
+        introspection_configs: BTreeMap<String, SubgraphIntrospectionConfig>,
+    ) -> SupergraphConfigDiff {
+        self.introspection_configs = introspection_configs;
+        self
+    }
 }
 
 #[cfg(test)]
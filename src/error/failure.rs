@@ -0,0 +1,63 @@
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+
+use serde_json::Value;
+
+use crate::options::JsonVersion;
+use crate::utils::github_annotations::GithubAnnotation;
+use crate::utils::junit::JunitReport;
+
+/// An error for a command that fails on a result it still prints, like a check with failed tasks
+/// or a diff that isn't empty. Returned with [`RoverError::from_failure`], each output format
+/// prints the result before the error's message.
+///
+/// [`RoverError::from_failure`]: super::RoverError::from_failure
+pub trait StructuredFailure: Error + Send + Sync + 'static {
+    /// The result as plain output
+    fn get_output(&self) -> String;
+
+    /// The result as the `data` of `--format json`
+    fn get_json(&self) -> Value;
+
+    /// The version of the JSON output, if the result isn't in the error's default version
+    fn get_json_version(&self) -> Option<JsonVersion> {
+        None
+    }
+
+    /// Annotations for `--format github-annotations`, if the result has more specific ones than
+    /// the error message
+    fn get_github_annotations(&self) -> Vec<GithubAnnotation> {
+        Vec::new()
+    }
+
+    /// What `--porcelain` prints to stdout, if anything
+    fn get_porcelain(&self) -> Option<String> {
+        None
+    }
+
+    /// The report for `--format junit`, if the result has test cases of its own
+    fn get_junit(&self) -> Option<JunitReport> {
+        None
+    }
+}
+
+/// Erases the type of a [`StructuredFailure`], so that a single downcast finds any of them
+pub(super) struct Failure(pub(super) Box<dyn StructuredFailure>);
+
+impl Debug for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Error for Failure {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.source()
+    }
+}
@@ -1,10 +1,15 @@
 use serde::Serialize;
 use std::collections::HashMap;
 use std::fmt::{self, Display};
-use strum_macros::EnumString;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
 
 /// `Code` contains the error codes associated with specific errors.
-#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, EnumString)]
+///
+/// Codes are stable: once released, a code keeps its number and its name, and isn't reused for a
+/// different kind of error
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, EnumIter)]
 pub enum RoverErrorCode {
     E001,
     E002,
@@ -50,6 +55,15 @@ pub enum RoverErrorCode {
     E042,
     E043,
     E044,
+    E045,
+    E046,
+    E047,
+    E048,
+    E049,
+    E050,
+    E051,
+    E052,
+    E053,
 }
 
 impl Display for RoverErrorCode {
@@ -239,19 +253,190 @@ impl RoverErrorCode {
                 RoverErrorCode::E044,
                 include_str!("./codes/E044.md").to_string(),
             ),
+            (
+                RoverErrorCode::E045,
+                include_str!("./codes/E045.md").to_string(),
+            ),
+            (
+                RoverErrorCode::E046,
+                include_str!("./codes/E046.md").to_string(),
+            ),
+            (
+                RoverErrorCode::E047,
+                include_str!("./codes/E047.md").to_string(),
+            ),
+            (
+                RoverErrorCode::E048,
+                include_str!("./codes/E048.md").to_string(),
+            ),
+            (
+                RoverErrorCode::E049,
+                include_str!("./codes/E049.md").to_string(),
+            ),
+            (
+                RoverErrorCode::E050,
+                include_str!("./codes/E050.md").to_string(),
+            ),
+            (
+                RoverErrorCode::E051,
+                include_str!("./codes/E051.md").to_string(),
+            ),
+            (
+                RoverErrorCode::E052,
+                include_str!("./codes/E052.md").to_string(),
+            ),
+            (
+                RoverErrorCode::E053,
+                include_str!("./codes/E053.md").to_string(),
+            ),
         ];
         contents.into_iter().collect()
     }
 
+    /// The code's stable name, which starts with the code itself, like `E049_COMPOSITION_TIMEOUT`
+    pub fn name(&self) -> &'static str {
+        match self {
+            RoverErrorCode::E001 => "E001_INVALID_JSON_RESPONSE",
+            RoverErrorCode::E002 => "E002_INVALID_HEADER_NAME",
+            RoverErrorCode::E003 => "E003_INVALID_HEADER_VALUE",
+            RoverErrorCode::E004 => "E004_REQUEST_FAILED",
+            RoverErrorCode::E005 => "E005_MALFORMED_RESPONSE",
+            RoverErrorCode::E006 => "E006_INVALID_SEVERITY",
+            RoverErrorCode::E007 => "E007_EXPECTED_FEDERATED_GRAPH",
+            RoverErrorCode::E008 => "E008_INVALID_VARIANT",
+            RoverErrorCode::E009 => "E009_SUBGRAPH_NOT_FOUND",
+            RoverErrorCode::E010 => "E010_GRAPH_NOT_FOUND",
+            RoverErrorCode::E011 => "E011_INVALID_INTROSPECTION_RESPONSE",
+            RoverErrorCode::E012 => "E012_HTTP_ERROR_STATUS",
+            RoverErrorCode::E013 => "E013_INVALID_API_KEY",
+            RoverErrorCode::E014 => "E014_MALFORMED_API_KEY",
+            RoverErrorCode::E015 => "E015_INVALID_RELEASE_VERSION",
+            RoverErrorCode::E016 => "E016_CANNOT_CREATE_CONFIG_HOME",
+            RoverErrorCode::E017 => "E017_CONFIG_HOME_NOT_FOUND",
+            RoverErrorCode::E018 => "E018_INVALID_CONFIG_HOME",
+            RoverErrorCode::E019 => "E019_NO_CONFIG_FOUND",
+            RoverErrorCode::E020 => "E020_NO_CONFIG_PROFILES",
+            RoverErrorCode::E021 => "E021_PROFILE_NOT_FOUND",
+            RoverErrorCode::E022 => "E022_EMPTY_PROFILE",
+            RoverErrorCode::E023 => "E023_INVALID_CONFIG_PATH",
+            RoverErrorCode::E024 => "E024_INVALID_PROFILE",
+            RoverErrorCode::E025 => "E025_PROFILE_SERIALIZATION_FAILED",
+            RoverErrorCode::E026 => "E026_CONFIG_IO_ERROR",
+            RoverErrorCode::E027 => "E027_COMPOSITION_ERRORS",
+            RoverErrorCode::E028 => "E028_CONNECTION_FAILED",
+            RoverErrorCode::E029 => "E029_BUILD_ERRORS",
+            RoverErrorCode::E030 => "E030_OPERATION_CHECK_FAILED",
+            RoverErrorCode::E031 => "E031_REQUEST_TIMEOUT",
+            RoverErrorCode::E032 => "E032_INVALID_CHECK_INPUT",
+            RoverErrorCode::E033 => "E033_PERMISSION_DENIED",
+            RoverErrorCode::E034 => "E034_PLAN_LIMIT_REACHED",
+            RoverErrorCode::E035 => "E035_CORRUPTED_PROFILE",
+            RoverErrorCode::E036 => "E036_OTHER_CHECK_TASK_FAILED",
+            RoverErrorCode::E037 => "E037_DOWNSTREAM_CHECK_FAILED",
+            RoverErrorCode::E038 => "E038_SUBGRAPH_RESOLUTION_FAILED",
+            RoverErrorCode::E039 => "E039_EXPECTED_CONTRACT_VARIANT",
+            RoverErrorCode::E040 => "E040_CONTRACT_PUBLISH_FAILED",
+            RoverErrorCode::E041 => "E041_MISSING_ROUTING_URL",
+            RoverErrorCode::E042 => "E042_LINT_VIOLATIONS",
+            RoverErrorCode::E043 => "E043_CHECK_FAILED",
+            RoverErrorCode::E044 => "E044_OFFLINE_LICENSE_UNAVAILABLE",
+            RoverErrorCode::E045 => "E045_KEYCHAIN_UNAVAILABLE",
+            RoverErrorCode::E046 => "E046_SSO_REFRESH_FAILED",
+            RoverErrorCode::E047 => "E047_NETWORK_DISABLED",
+            RoverErrorCode::E048 => "E048_COMPOSITION_BINARY_FAILED",
+            RoverErrorCode::E049 => "E049_COMPOSITION_TIMEOUT",
+            RoverErrorCode::E050 => "E050_SUPERGRAPH_FILE_ACCESS",
+            RoverErrorCode::E051 => "E051_INSUFFICIENT_DISK_SPACE",
+            RoverErrorCode::E052 => "E052_POLICY_FAILED",
+            RoverErrorCode::E053 => "E053_LINT_RULE_FAILED",
+        }
+    }
+
     /// For a given error code, returns a markdown string with a given error's
     /// explanation. Explanations are in ./codes
     pub fn explain(&self) -> String {
         let all_explanations = RoverErrorCode::explanations();
         let explanation = all_explanations.get(self);
         if let Some(explanation) = explanation {
-            format!("**{}**\n\n{}\n\n", &self, &explanation)
+            format!("**{}**\n\n{}\n\n", self.name(), &explanation)
         } else {
             "Explanation not available".to_string()
         }
     }
+
+    /// The first sentence of the code's explanation
+    pub fn summary(&self) -> String {
+        let explanation = RoverErrorCode::explanations()
+            .remove(self)
+            .unwrap_or_default();
+        let first_line = explanation.lines().next().unwrap_or_default();
+        match first_line.split_once(". ") {
+            Some((sentence, _)) => format!("{sentence}."),
+            None => first_line.trim_end().to_string(),
+        }
+    }
+
+    /// Every code, in order
+    pub fn all() -> Vec<RoverErrorCode> {
+        RoverErrorCode::iter().collect()
+    }
+}
+
+/// Parses a code like `E049`, or its name, like `E049_COMPOSITION_TIMEOUT`, in any case
+impl FromStr for RoverErrorCode {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        RoverErrorCode::iter()
+            .find(|candidate| {
+                candidate.to_string().eq_ignore_ascii_case(code)
+                    || candidate.name().eq_ignore_ascii_case(code)
+            })
+            .ok_or_else(|| {
+                format!("{code} is not a Rover error code. Run `rover explain` to list them all.")
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, str::FromStr};
+
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::RoverErrorCode;
+
+    #[test]
+    fn every_code_has_a_unique_name_and_an_explanation() {
+        let explanations = RoverErrorCode::explanations();
+        let mut names = HashSet::new();
+        for code in RoverErrorCode::all() {
+            assert_that!(code.name()).starts_with(format!("{code}_"));
+            assert_that!(names.insert(code.name())).is_true();
+            assert_that!(explanations.contains_key(&code)).is_true();
+        }
+    }
+
+    #[rstest]
+    #[case::code("E049")]
+    #[case::lowercase("e049")]
+    #[case::name("E049_COMPOSITION_TIMEOUT")]
+    fn parses_codes_and_names(#[case] code: &str) {
+        assert_that!(RoverErrorCode::from_str(code))
+            .is_ok()
+            .is_equal_to(RoverErrorCode::E049);
+    }
+
+    #[test]
+    fn rejects_unknown_codes() {
+        assert_that!(RoverErrorCode::from_str("E999")).is_err();
+    }
+
+    #[test]
+    fn summarizes_the_first_sentence() {
+        assert_that!(RoverErrorCode::E031.summary()).is_equal_to(
+            "This error occurs when Rover made an HTTP request and it timed out.".to_string(),
+        );
+    }
 }
@@ -1,13 +1,21 @@
 use std::env;
 
-use serde::Serialize;
+use serde::{ser::SerializeMap, Serialize, Serializer};
 
+use binstall::InstallerError;
 pub use code::RoverErrorCode;
 use houston::HoustonProblem;
 use rover_client::{EndpointKind, RoverClientError};
 pub use suggestion::RoverErrorSuggestion;
 
-use crate::{options::JsonVersion, utils::env::RoverEnvKey};
+#[cfg(feature = "composition-js")]
+use crate::composition::CompositionError;
+use crate::{
+    lint::plugins::LintPluginError,
+    options::JsonVersion,
+    policy::PolicyError,
+    utils::{env::RoverEnvKey, runtime_mode::NetworkDisabled},
+};
 
 mod code;
 mod suggestion;
@@ -15,22 +23,29 @@ mod suggestion;
 /// Metadata contains extra information about specific errors
 /// Currently this includes an optional error `Code`
 /// and an optional `Suggestion`
-#[derive(Default, Serialize, Debug)]
+#[derive(Default, Debug)]
 pub struct RoverErrorMetadata {
-    // skip serializing for now until we can appropriately strip color codes
-    #[serde(skip_serializing)]
+    // not serialized for now until we can appropriately strip color codes
     pub suggestions: Vec<RoverErrorSuggestion>,
     pub code: Option<RoverErrorCode>,
 
     // anyhow's debug implementation prints the error cause, most of the time we want this
     // but sometimes the cause is already included in the error's Display impl (like reqwest::Error)
-    #[serde(skip_serializing)]
     pub skip_printing_cause: bool,
 
-    #[serde(skip_serializing)]
     pub(crate) json_version: JsonVersion,
 }
 
+/// Alongside the error's message, as `code` and its stable name, `code_name`
+impl Serialize for RoverErrorMetadata {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("code", &self.code)?;
+        map.serialize_entry("code_name", &self.code.as_ref().map(RoverErrorCode::name))?;
+        map.end()
+    }
+}
+
 /// `Metadata` structs can be created from an `anyhow::Error`
 /// This works by downcasting the errors to their underlying types
 /// and creating `Suggestion`s and `Code`s where applicable
@@ -115,7 +130,8 @@ impl From<&mut anyhow::Error> for RoverErrorMetadata {
                         (None, Some(RoverErrorCode::E004))
                     }
                 }
-                RoverClientError::MalformedResponse { null_field: _ } => (
+                RoverClientError::MalformedResponse { null_field: _ }
+                | RoverClientError::UnexpectedResponse { .. } => (
                     Some(RoverErrorSuggestion::SubmitIssue),
                     Some(RoverErrorCode::E005),
                 ),
@@ -216,7 +232,8 @@ impl From<&mut anyhow::Error> for RoverErrorMetadata {
                     Some(RoverErrorCode::E009),
                 ),
                 RoverClientError::GraphNotFound { .. }
-                | RoverClientError::GraphIdNotFound { .. } => (
+                | RoverClientError::GraphIdNotFound { .. }
+                | RoverClientError::NotFound { .. } => (
                     Some(RoverErrorSuggestion::CheckGraphNameAndAuth),
                     Some(RoverErrorCode::E010),
                 ),
@@ -315,9 +332,14 @@ impl From<&mut anyhow::Error> for RoverErrorMetadata {
                 RoverClientError::OrganizationNotFound { .. } => {
                     (Some(RoverErrorSuggestion::CheckGraphNameAndAuth), None)
                 }
+                RoverClientError::ProposalNotFound { .. } => {
+                    (Some(RoverErrorSuggestion::CheckGraphNameAndAuth), None)
+                }
+                RoverClientError::ProposalError { .. } => (None, None),
                 RoverClientError::InvalidRouterConfig { .. } => (None, None),
                 RoverClientError::NonCloudGraphRef { .. } => (None, None),
                 RoverClientError::ServiceError(_) => (None, None),
+                RoverClientError::DryRun { .. } => (None, None),
             };
             return RoverErrorMetadata {
                 json_version: JsonVersion::default(),
@@ -327,6 +349,98 @@ impl From<&mut anyhow::Error> for RoverErrorMetadata {
             };
         }
 
+        #[cfg(feature = "composition-js")]
+        if let Some(composition_error) = error.downcast_ref::<CompositionError>() {
+            let (suggestion, code) = match composition_error {
+                CompositionError::Build { source } if source.is_config => (
+                    Some(RoverErrorSuggestion::FixSupergraphConfigErrors),
+                    Some(RoverErrorCode::E038),
+                ),
+                CompositionError::Build { .. } => (None, Some(RoverErrorCode::E027)),
+                CompositionError::Binary { .. }
+                | CompositionError::BinaryFailed { .. }
+                | CompositionError::InvalidInput { .. }
+                | CompositionError::InvalidOutput { .. } => {
+                    (Some(RoverErrorSuggestion::SubmitIssue), Some(RoverErrorCode::E048))
+                }
+                CompositionError::Timeout { .. } => (
+                    Some(RoverErrorSuggestion::Adhoc(
+                        "Raise `--composition-timeout`, or leave it unset to wait for as long as composition takes."
+                            .to_string(),
+                    )),
+                    Some(RoverErrorCode::E049),
+                ),
+                CompositionError::ReadFile { .. } | CompositionError::WriteFile { .. } => {
+                    (None, Some(RoverErrorCode::E050))
+                }
+            };
+            return RoverErrorMetadata {
+                json_version: JsonVersion::default(),
+                suggestions: suggestion.into_iter().collect(),
+                code,
+                skip_printing_cause,
+            };
+        }
+
+        if let Some(InstallerError::InsufficientDiskSpace { .. }) = error.downcast_ref() {
+            return RoverErrorMetadata {
+                json_version: JsonVersion::default(),
+                suggestions: vec![RoverErrorSuggestion::FreeDiskSpace {
+                    reclaimable_bytes: None,
+                }],
+                code: Some(RoverErrorCode::E051),
+                skip_printing_cause,
+            };
+        }
+
+        if let Some(policy_error) = error.downcast_ref::<PolicyError>() {
+            let suggestion = match policy_error {
+                PolicyError::UnsupportedRuntime { .. } => {
+                    "Run the script from an executable in the policies directory, like a shell script that passes its input to your script's runtime, or move it out of the directory.".to_string()
+                }
+                PolicyError::InvalidOutput { .. } => {
+                    "Policies have to print a JSON object with a `violations` list on stdout. Print anything else to stderr.".to_string()
+                }
+                PolicyError::ReadDir { .. }
+                | PolicyError::Spawn { .. }
+                | PolicyError::Failed { .. } => {
+                    "Make sure the policy runs, and exits successfully when it finds violations.".to_string()
+                }
+            };
+            return RoverErrorMetadata {
+                json_version: JsonVersion::default(),
+                suggestions: vec![RoverErrorSuggestion::Adhoc(suggestion)],
+                code: Some(RoverErrorCode::E052),
+                skip_printing_cause,
+            };
+        }
+
+        if let Some(plugin_error) = error.downcast_ref::<LintPluginError>() {
+            let suggestion = match plugin_error {
+                LintPluginError::ReadDir { .. } => {
+                    "Make sure the lint rules directory can be read.".to_string()
+                }
+                LintPluginError::Load { .. } | LintPluginError::Failed { .. } => {
+                    "Fix or remove the lint rule, or move it out of the lint rules directory to stop running it.".to_string()
+                }
+            };
+            return RoverErrorMetadata {
+                json_version: JsonVersion::default(),
+                suggestions: vec![RoverErrorSuggestion::Adhoc(suggestion)],
+                code: Some(RoverErrorCode::E053),
+                skip_printing_cause,
+            };
+        }
+
+        if error.downcast_ref::<NetworkDisabled>().is_some() {
+            return RoverErrorMetadata {
+                json_version: JsonVersion::default(),
+                suggestions: vec![RoverErrorSuggestion::RunOnline],
+                code: Some(RoverErrorCode::E047),
+                skip_printing_cause,
+            };
+        }
+
         if let Some(houston_problem) = error.downcast_ref::<HoustonProblem>() {
             let (suggestion, code) = match houston_problem {
                 HoustonProblem::CouldNotCreateConfigHome(_) => (
@@ -358,6 +472,10 @@ impl From<&mut anyhow::Error> for RoverErrorMetadata {
                     Some(RoverErrorSuggestion::ListProfiles),
                     Some(RoverErrorCode::E021),
                 ),
+                HoustonProblem::ProfileAlreadyExists(_) => (
+                    Some(RoverErrorSuggestion::ListProfiles),
+                    Some(RoverErrorCode::E021),
+                ),
                 HoustonProblem::NoNonSensitiveConfigFound(_) => (
                     Some(RoverErrorSuggestion::SubmitIssue),
                     Some(RoverErrorCode::E022),
@@ -368,6 +486,20 @@ impl From<&mut anyhow::Error> for RoverErrorMetadata {
                     )),
                     Some(RoverErrorCode::E035),
                 ),
+                HoustonProblem::KeychainUnavailable(profile_name) => (
+                    Some(RoverErrorSuggestion::RecreateConfig(
+                        profile_name.to_string(),
+                    )),
+                    Some(RoverErrorCode::E045),
+                ),
+                HoustonProblem::KeychainError(_) => (
+                    Some(RoverErrorSuggestion::CheckKeychain),
+                    Some(RoverErrorCode::E045),
+                ),
+                HoustonProblem::SsoRefreshFailed(profile_name, _) => (
+                    Some(RoverErrorSuggestion::LogInWithSso(profile_name.to_string())),
+                    Some(RoverErrorCode::E046),
+                ),
                 HoustonProblem::PathNotUtf8(_) => (
                     Some(RoverErrorSuggestion::SubmitIssue),
                     Some(RoverErrorCode::E023),
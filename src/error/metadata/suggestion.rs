@@ -19,6 +19,13 @@ pub enum RoverErrorSuggestion {
     CreateConfig,
     RecreateConfig(String),
     ListProfiles,
+    CheckKeychain,
+    LogInWithSso(String),
+    RunOnline,
+    FreeDiskSpace {
+        /// What `rover plugin prune` would free, when known
+        reclaimable_bytes: Option<u64>,
+    },
     UseFederatedGraph,
     UseContractVariant,
     RunComposition,
@@ -132,6 +139,41 @@ impl Display for RoverErrorSuggestion {
                     Style::Command.paint("`--profile`")
                 )
             }
+            CheckKeychain => {
+                format!(
+                    "Make sure the OS keychain is unlocked and Rover is allowed to access it, or save the API key to a file by running {} without {}.",
+                    Style::Command.paint("`rover config auth`"),
+                    Style::Command.paint("`--keychain`")
+                )
+            }
+            LogInWithSso(profile_name) => {
+                format!("Log in again by running {}.", Style::Command.paint(format!("`rover config auth --sso{}`", match profile_name.as_str() {
+                    "default" => "".to_string(),
+                    profile_name => format!(" --profile {}", profile_name)
+                })))
+            }
+RunOnline => {
+                format!(
+                    "Re-run this command without {}, and make sure {} isn't set.",
+                    Style::Command.paint("`--offline`"),
+                    Style::Command.paint(format!("`${}`", RoverEnvKey::Offline))
+                )
+            }
+            FreeDiskSpace { reclaimable_bytes } => {
+                let prune = Style::Command.paint("`rover plugin prune`");
+                match reclaimable_bytes {
+                    Some(0) => "Free up some disk space and try again.".to_string(),
+                    Some(bytes) => format!(
+                        "Free up some disk space and try again. Running {} would free {:.1} MiB by removing the plugin versions Rover no longer uses.",
+                        prune,
+                        *bytes as f64 / (1024.0 * 1024.0)
+                    ),
+                    None => format!(
+                        "Free up some disk space and try again. Running {} removes the plugin versions Rover no longer uses.",
+                        prune
+                    ),
+                }
+            }
 RunComposition => {
                 format!("Try resolving the build errors in your subgraph(s), and publish them with the {} command.", Style::Command.paint("`rover subgraph publish`"))
             }
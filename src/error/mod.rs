@@ -1,11 +1,13 @@
+mod failure;
 mod metadata;
 
+pub use failure::StructuredFailure;
 pub use metadata::{RoverErrorCode, RoverErrorMetadata, RoverErrorSuggestion};
 
 pub type RoverResult<T> = std::result::Result<T, RoverError>;
 
 use calm_io::{stderr, stdoutln};
-use rover_client::RoverClientError;
+use rover_client::{shared::DryRunRequest, RoverClientError};
 use rover_std::Style;
 use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
@@ -17,7 +19,19 @@ use std::fmt::{self, Debug, Display};
 
 use apollo_federation_types::rover::BuildErrors;
 
+#[cfg(feature = "composition-js")]
+use crate::composition::supergraph::config::{
+    error::ResolveSubgraphErrorReport, resolver::ResolveSupergraphConfigError,
+};
+#[cfg(feature = "composition-js")]
+use crate::composition::CompositionError;
 use crate::options::JsonVersion;
+use crate::utils::credentials::mask_api_keys;
+use crate::utils::github_annotations::{self, AnnotationLevel, GithubAnnotation};
+use crate::utils::junit::{self, JunitReport};
+use crate::utils::runtime_mode::NetworkDisabled;
+
+use self::failure::Failure;
 
 /// A specialized `Error` type for Rover that wraps `anyhow`
 /// and provides some extra `Metadata` for end users depending
@@ -43,15 +57,29 @@ where
         if let Some(rover_client_error_source) = rover_client_error.source() {
             if let Some(build_errors) = rover_client_error_source.downcast_ref::<BuildErrors>() {
                 let mut top_level_data = serializer.serialize_struct(top_level_struct, 2)?;
-                top_level_data.serialize_field(message_field_name, &error.to_string())?;
+                top_level_data
+                    .serialize_field(message_field_name, &mask_api_keys(&error.to_string()))?;
                 top_level_data.serialize_field(details_struct, &build_errors)?;
                 return top_level_data.end();
             }
         }
     }
 
+    #[cfg(feature = "composition-js")]
+    if let Some(ResolveSupergraphConfigError::ResolveSubgraphs(errors)) =
+        error.downcast_ref::<ResolveSupergraphConfigError>()
+    {
+        let mut top_level_data = serializer.serialize_struct(top_level_struct, 2)?;
+        top_level_data.serialize_field(message_field_name, &mask_api_keys(&error.to_string()))?;
+        top_level_data.serialize_field(
+            details_struct,
+            &ResolveSubgraphErrorReport::new(errors).get_json(),
+        )?;
+        return top_level_data.end();
+    }
+
     let mut data = serializer.serialize_struct(top_level_struct, 1)?;
-    data.serialize_field(message_field_name, &error.to_string())?;
+    data.serialize_field(message_field_name, &mask_api_keys(&error.to_string()))?;
     data.end()
 }
 
@@ -66,6 +94,18 @@ impl RoverError {
         Self { error, metadata }
     }
 
+    /// An error for a command that failed on a result it still prints
+    pub fn from_failure(failure: impl StructuredFailure) -> Self {
+        Self::new(Failure(Box::new(failure)))
+    }
+
+    /// The result the command failed on, if it has one
+    fn failure(&self) -> Option<&dyn StructuredFailure> {
+        self.error
+            .downcast_ref::<Failure>()
+            .map(|failure| failure.0.as_ref())
+    }
+
     pub fn set_suggestion(&mut self, suggestion: RoverErrorSuggestion) {
         self.metadata.suggestions.push(suggestion);
     }
@@ -80,14 +120,140 @@ impl RoverError {
     }
 
     pub fn message(&self) -> String {
-        self.error.to_string()
+        mask_api_keys(&self.error.to_string()).into_owned()
     }
 
     pub fn code(&self) -> Option<RoverErrorCode> {
         self.metadata.code.clone()
     }
 
+    /// The build errors this error was caused by, if it comes from a failed composition
+    pub(crate) fn build_errors(&self) -> Option<&BuildErrors> {
+        match self.error.downcast_ref::<RoverClientError>() {
+            Some(RoverClientError::BuildErrors { source, .. }) => Some(source),
+            _ => None,
+        }
+    }
+
+    /// The mutation `--dry-run` stopped from being sent, if that's what this error is
+    pub(crate) fn dry_run_request(&self) -> Option<&DryRunRequest> {
+        match self.error.downcast_ref::<RoverClientError>() {
+            Some(RoverClientError::DryRun { request }) => Some(request),
+            _ => None,
+        }
+    }
+
+    /// Whether Apollo Studio couldn't be reached, rather than answering with an error: Rover is
+    /// offline, the connection failed or timed out, or Studio responded with a server error
+    pub(crate) fn is_studio_unreachable(&self) -> bool {
+        if self.error.downcast_ref::<NetworkDisabled>().is_some() {
+            return true;
+        }
+        match self.error.downcast_ref::<RoverClientError>() {
+            Some(RoverClientError::SendRequest { source, .. }) => {
+                source.is_connect()
+                    || source.is_timeout()
+                    || source
+                        .status()
+                        .is_some_and(|status| status.is_server_error())
+            }
+            Some(RoverClientError::ClientError { msg }) => msg
+                .split_whitespace()
+                .next()
+                .and_then(|code| code.parse::<u16>().ok())
+                .is_some_and(|code| code >= 500),
+            _ => false,
+        }
+    }
+
+    /// The status Rover exits with for this error. Failures a script might handle differently,
+    /// like a bad API key or a rate limit, get their own status, and everything else exits with 1
+    pub fn exit_code(&self) -> i32 {
+        match self.error.downcast_ref::<RoverClientError>() {
+            Some(RoverClientError::InvalidKey | RoverClientError::MalformedKey) => 3,
+            Some(RoverClientError::PermissionError { .. }) => 4,
+            Some(
+                RoverClientError::GraphNotFound { .. }
+                | RoverClientError::GraphIdNotFound { .. }
+                | RoverClientError::NotFound { .. },
+            ) => 5,
+            Some(RoverClientError::RateLimitExceeded) => 6,
+            _ => 1,
+        }
+    }
+
+    /// Annotations for `--format github-annotations`. Check failures, lint violations, and
+    /// composition errors get one each, anything else a single annotation with the error message
+    pub fn get_github_annotations(&self) -> Vec<GithubAnnotation> {
+        let mut annotations = match self.error.downcast_ref::<RoverClientError>() {
+            Some(
+                RoverClientError::BuildErrors { source, .. }
+                | RoverClientError::SubgraphBuildErrors { source, .. }
+                | RoverClientError::NoSupergraphBuilds { source, .. },
+            ) => github_annotations::from_build_errors(source),
+            Some(RoverClientError::CheckWorkflowFailure { check_response, .. }) => {
+                github_annotations::from_check_response(check_response, None)
+            }
+            Some(RoverClientError::LintFailures { lint_response }) => {
+                github_annotations::from_lint_response(lint_response)
+            }
+            _ => Vec::new(),
+        };
+        #[cfg(feature = "composition-js")]
+        if let Some(CompositionError::Build { source }) = self.error.downcast_ref() {
+            annotations = github_annotations::from_build_errors(source);
+        }
+        if let Some(failure) = self.failure() {
+            annotations = failure.get_github_annotations();
+        }
+        if annotations.is_empty() {
+            let annotation = GithubAnnotation::new(AnnotationLevel::Error, self.message());
+            annotations.push(match self.code() {
+                Some(code) => annotation.with_title(code.to_string()),
+                None => annotation,
+            });
+        }
+        annotations
+    }
+
+    /// What `--porcelain` prints to stdout when a command fails: the status of failed checks and
+    /// lints, and nothing for other errors, whose message goes to stderr
+    pub fn get_porcelain(&self) -> Option<String> {
+        if let Some(failure) = self.failure() {
+            return failure.get_porcelain();
+        }
+        match self.error.downcast_ref::<RoverClientError>() {
+            Some(
+                RoverClientError::CheckWorkflowFailure { .. }
+                | RoverClientError::LintFailures { .. },
+            ) => Some("failed".to_string()),
+            _ => None,
+        }
+    }
+
+    /// The JUnit report for `--format junit`. Failed checks get a test case per task, anything
+    /// else a single failed test case with the error message
+    pub fn get_junit(&self) -> JunitReport {
+        if let Some(RoverClientError::CheckWorkflowFailure { check_response, .. }) =
+            self.error.downcast_ref()
+        {
+            return JunitReport::new(vec![junit::from_check_response(check_response, "checks")]);
+        }
+        if let Some(report) = self.failure().and_then(|failure| failure.get_junit()) {
+            return report;
+        }
+        JunitReport::new(vec![junit::from_error(
+            "rover",
+            self.code().map(|code| code.to_string()),
+            self.message(),
+        )])
+    }
+
     pub fn print(&self) -> RoverResult<()> {
+        if let Some(failure) = self.failure() {
+            stdoutln!("{}", failure.get_output())?;
+        }
+
         match self.error.downcast_ref::<RoverClientError>() {
             Some(RoverClientError::CheckWorkflowFailure {
                 graph_ref: _,
@@ -104,6 +270,10 @@ impl RoverError {
     }
 
     pub(crate) fn get_internal_data_json(&self) -> Value {
+        if let Some(failure) = self.failure() {
+            return failure.get_json();
+        }
+
         return match self.error.downcast_ref::<RoverClientError>() {
             Some(RoverClientError::CheckWorkflowFailure {
                 graph_ref: _,
@@ -119,6 +289,13 @@ impl RoverError {
     }
 
     pub(crate) fn get_json_version(&self) -> JsonVersion {
+        if let Some(version) = self
+            .failure()
+            .and_then(|failure| failure.get_json_version())
+        {
+            return version;
+        }
+
         match &self.error.downcast_ref::<RoverClientError>() {
             Some(RoverClientError::CheckWorkflowFailure {
                 graph_ref: _,
@@ -138,11 +315,18 @@ impl Display for RoverError {
         };
         let error_descriptor = Style::ErrorPrefix.paint(error_descriptor_message);
 
-        if self.metadata.skip_printing_cause {
-            writeln!(formatter, "{} {}", error_descriptor, &self.error)?;
+        // the API key might be echoed back by a failed request, so it's masked wherever it shows up
+        let message = if self.metadata.skip_printing_cause {
+            self.error.to_string()
         } else {
-            writeln!(formatter, "{} {:?}", error_descriptor, &self.error)?;
-        }
+            format!("{:?}", &self.error)
+        };
+        writeln!(
+            formatter,
+            "{} {}",
+            error_descriptor,
+            mask_api_keys(&message)
+        )?;
 
         for suggestion in &self.metadata.suggestions {
             writeln!(formatter, "        {}", suggestion)?;
@@ -3,10 +3,16 @@ pub mod command;
 #[cfg(feature = "composition-js")]
 pub mod composition;
 mod error;
+mod lint;
+#[cfg(feature = "composition-js")]
+mod operation_validation;
 mod options;
+mod policy;
+mod schema_diff;
+mod schema_mask;
 mod subtask;
 pub mod utils;
 
 pub use command::RoverOutput;
-pub use error::{RoverError, RoverErrorCode, RoverErrorSuggestion, RoverResult};
+pub use error::{RoverError, RoverErrorCode, RoverErrorSuggestion, RoverResult, StructuredFailure};
 pub use utils::pkg::*;
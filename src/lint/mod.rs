@@ -0,0 +1,322 @@
+//! Lints a schema file offline for `rover subgraph lint --local`, with the rules in [`rules`]
+//! and the project's own [`plugins`], instead of Studio's linter.
+//!
+//! Rules are configured by the project's `.apollo/lint.toml`, which can change the level of any
+//! rule and which levels fail the lint:
+//!
+//! ```toml
+//! # fail on "error" (the default), "warning", or "never"
+//! fail_on = "warning"
+//!
+//! [rules]
+//! DESCRIPTION_MISSING = "off"
+//! DEPRECATED_DIRECTIVE_MISSING_REASON = "error"
+//! ```
+
+pub(crate) mod plugins;
+pub(crate) mod rules;
+
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use camino::{Utf8Path, Utf8PathBuf};
+use rover_client::shared::{Diagnostic, LintResponse};
+use rover_client::RoverClientError;
+use rover_std::Fs;
+use serde::Deserialize;
+
+use crate::policy::schema::PolicySchema;
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+use self::plugins::LintPlugin;
+use self::rules::{Finding, RULES};
+
+/// Where the lint configuration is kept, relative to the project's root
+const CONFIG_FILE: &str = ".apollo/lint.toml";
+
+#[derive(Deserialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum RuleLevel {
+    Error,
+    Warning,
+    Off,
+}
+
+impl RuleLevel {
+    /// The level as Studio's linter reports it, so local and remote diagnostics look the same
+    fn as_lint_level(&self) -> Option<&'static str> {
+        match self {
+            RuleLevel::Error => Some("ERROR"),
+            RuleLevel::Warning => Some("WARNING"),
+            RuleLevel::Off => None,
+        }
+    }
+}
+
+/// The least severe level of violation that fails a lint, and makes Rover exit with an error
+#[derive(Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum FailOn {
+    #[default]
+    Error,
+    Warning,
+    Never,
+}
+
+impl FailOn {
+    fn fails(&self, diagnostics: &[Diagnostic]) -> bool {
+        diagnostics.iter().any(|diagnostic| match self {
+            FailOn::Error => diagnostic.level == "ERROR",
+            FailOn::Warning => diagnostic.level == "ERROR" || diagnostic.level == "WARNING",
+            FailOn::Never => false,
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LintConfig {
+    #[serde(default)]
+    pub(crate) fail_on: FailOn,
+    /// Levels for rules that don't report at their default level
+    #[serde(default)]
+    pub(crate) rules: BTreeMap<String, RuleLevel>,
+}
+
+impl LintConfig {
+    /// Loads the configuration of the project the current directory is in. Without one, every
+    /// rule reports at its default level
+    pub(crate) fn for_current_dir() -> RoverResult<Self> {
+        let current_dir = Utf8PathBuf::try_from(std::env::current_dir()?)?;
+        match find_config_file(&current_dir) {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub(crate) fn load(path: &Utf8Path) -> RoverResult<Self> {
+        let contents = Fs::read_file(path)?;
+        Ok(
+            toml::from_str(&contents)
+                .map_err(|err| anyhow!("Could not parse {}: {}", path, err))?,
+        )
+    }
+
+    fn level(&self, rule: &str, default_level: RuleLevel) -> RuleLevel {
+        self.rules.get(rule).copied().unwrap_or(default_level)
+    }
+}
+
+/// The built-in rules and the project's rule plugins, and how they're configured
+pub(crate) struct LocalLinter {
+    config: LintConfig,
+    plugins: Vec<LintPlugin>,
+}
+
+impl LocalLinter {
+    /// Loads the configuration and rule plugins of the project the current directory is in
+    pub(crate) fn for_current_dir() -> RoverResult<Self> {
+        Self::new(LintConfig::for_current_dir()?, plugins::for_current_dir()?)
+    }
+
+    /// Fails when `config` configures a rule that's neither built in nor a plugin
+    pub(crate) fn new(config: LintConfig, plugins: Vec<LintPlugin>) -> RoverResult<Self> {
+        let rule_names: Vec<&str> = RULES
+            .iter()
+            .map(|rule| rule.name)
+            .chain(plugins.iter().map(|plugin| plugin.name.as_str()))
+            .collect();
+        if let Some(unknown) = config
+            .rules
+            .keys()
+            .find(|name| !rule_names.contains(&name.as_str()))
+        {
+            let mut err = RoverError::new(anyhow!(
+                "{} configures the rule {}, which doesn't exist.",
+                CONFIG_FILE,
+                unknown
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "The rules are {}.",
+                rule_names.join(", ")
+            )));
+            return Err(err);
+        }
+        Ok(Self { config, plugins })
+    }
+
+    /// Runs every rule that isn't turned off over `sdl`, returning what they found as lint
+    /// diagnostics in the order they appear in the file. Plugins report errors unless they're
+    /// configured otherwise
+    pub(crate) fn evaluate(&self, sdl: &str) -> RoverResult<Vec<Diagnostic>> {
+        let schema = PolicySchema::parse(sdl)?;
+        let mut diagnostics = Vec::new();
+        let mut push = |rule: &str, level: RuleLevel, findings: Vec<Finding>| {
+            let Some(level) = level.as_lint_level() else {
+                return;
+            };
+            for finding in findings {
+                diagnostics.push(Diagnostic {
+                    level: level.to_string(),
+                    message: finding.message,
+                    coordinate: finding.coordinate,
+                    start_line: finding.location.line as i64,
+                    start_byte_offset: finding.location.start_byte_offset,
+                    end_byte_offset: finding.location.end_byte_offset,
+                    rule: rule.to_string(),
+                });
+            }
+        };
+        for rule in RULES {
+            let level = self.config.level(rule.name, rule.default_level);
+            if level != RuleLevel::Off {
+                push(rule.name, level, (rule.check)(&schema));
+            }
+        }
+        for plugin in &self.plugins {
+            let level = self.config.level(&plugin.name, RuleLevel::Error);
+            if level != RuleLevel::Off {
+                push(&plugin.name, level, plugin.check(&schema)?);
+            }
+        }
+        diagnostics.sort_by_key(|diagnostic| diagnostic.start_byte_offset);
+        Ok(diagnostics)
+    }
+
+    /// Fails a lint whose diagnostics are at a level the configuration fails on
+    pub(crate) fn conclude(&self, lint_response: LintResponse) -> RoverResult<LintResponse> {
+        if self.config.fail_on.fails(&lint_response.diagnostics) {
+            Err(RoverClientError::LintFailures { lint_response }.into())
+        } else {
+            Ok(lint_response)
+        }
+    }
+}
+
+/// Finds the lint configuration of the project `dir` is in
+fn find_config_file(dir: &Utf8Path) -> Option<Utf8PathBuf> {
+    dir.ancestors()
+        .map(|ancestor| ancestor.join(CONFIG_FILE))
+        .find(|config_file| config_file.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    const SCHEMA: &str = "type Query {\n  me: user\n}\n\n\"A user\"\ntype user {\n  ID: ID!\n}\n";
+
+    fn config(contents: &str) -> LintConfig {
+        let temp = TempDir::new().unwrap();
+        let path = Utf8PathBuf::from_path_buf(temp.path().join("lint.toml")).unwrap();
+        Fs::write_file(&path, contents).unwrap();
+        LintConfig::load(&path).unwrap()
+    }
+
+    fn rules(linter: &LocalLinter) -> Vec<(String, String, i64)> {
+        linter
+            .evaluate(SCHEMA)
+            .unwrap()
+            .into_iter()
+            .map(|diagnostic| (diagnostic.rule, diagnostic.level, diagnostic.start_line))
+            .collect()
+    }
+
+    #[test]
+    fn test_rules_report_at_their_default_levels() {
+        let linter = LocalLinter::new(LintConfig::default(), Vec::new()).unwrap();
+        assert_that!(rules(&linter)).is_equal_to(vec![
+            ("DESCRIPTION_MISSING".to_string(), "WARNING".to_string(), 1),
+            ("DESCRIPTION_MISSING".to_string(), "WARNING".to_string(), 2),
+            (
+                "TYPE_NAMES_SHOULD_BE_PASCAL_CASE".to_string(),
+                "WARNING".to_string(),
+                5,
+            ),
+            (
+                "FIELD_NAMES_SHOULD_BE_CAMEL_CASE".to_string(),
+                "WARNING".to_string(),
+                7,
+            ),
+            ("DESCRIPTION_MISSING".to_string(), "WARNING".to_string(), 7),
+        ]);
+    }
+
+    #[test]
+    fn test_config_changes_rule_levels() {
+        let config = config(
+            "[rules]\nDESCRIPTION_MISSING = \"off\"\nTYPE_NAMES_SHOULD_BE_PASCAL_CASE = \"error\"\n",
+        );
+        let linter = LocalLinter::new(config, Vec::new()).unwrap();
+        assert_that!(rules(&linter)).is_equal_to(vec![
+            (
+                "TYPE_NAMES_SHOULD_BE_PASCAL_CASE".to_string(),
+                "ERROR".to_string(),
+                5,
+            ),
+            (
+                "FIELD_NAMES_SHOULD_BE_CAMEL_CASE".to_string(),
+                "WARNING".to_string(),
+                7,
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_plugins_run_alongside_built_in_rules() {
+        let temp = TempDir::new().unwrap();
+        let dir = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+        Fs::write_file(
+            dir.join("no_me.rhai"),
+            "fn visit_field(field, ty) { if field.name == \"me\" { report(\"no me\"); } }",
+        )
+        .unwrap();
+        Fs::write_file(
+            dir.join("no_user.rhai"),
+            "fn visit_type(ty) { if ty.name == \"user\" { report(\"no user\"); } }",
+        )
+        .unwrap();
+        let config = config(
+            "[rules]\nDESCRIPTION_MISSING = \"off\"\nFIELD_NAMES_SHOULD_BE_CAMEL_CASE = \"off\"\nTYPE_NAMES_SHOULD_BE_PASCAL_CASE = \"off\"\nno_user = \"warning\"\n",
+        );
+        let linter = LocalLinter::new(config, plugins::load(&dir).unwrap()).unwrap();
+        assert_that!(rules(&linter)).is_equal_to(vec![
+            ("no_me".to_string(), "ERROR".to_string(), 2),
+            ("no_user".to_string(), "WARNING".to_string(), 5),
+        ]);
+    }
+
+    #[test]
+    fn test_unknown_rules_are_errors() {
+        let config = config("[rules]\nNOT_A_RULE = \"error\"\n");
+        let err = LocalLinter::new(config, Vec::new()).err().unwrap();
+        assert_that!(err.to_string()).contains("NOT_A_RULE");
+    }
+
+    #[rstest]
+    #[case::error_with_warnings("fail_on = \"error\"", "WARNING", false)]
+    #[case::error_with_errors("fail_on = \"error\"", "ERROR", true)]
+    #[case::warning_with_warnings("fail_on = \"warning\"", "WARNING", true)]
+    #[case::never_with_errors("fail_on = \"never\"", "ERROR", false)]
+    fn test_fail_on(#[case] contents: &str, #[case] level: &str, #[case] fails: bool) {
+        let linter = LocalLinter::new(config(contents), Vec::new()).unwrap();
+        let lint_response = LintResponse {
+            diagnostics: vec![Diagnostic {
+                level: level.to_string(),
+                message: "message".to_string(),
+                coordinate: "Query".to_string(),
+                start_line: 1,
+                start_byte_offset: 0,
+                end_byte_offset: 5,
+                rule: "RULE".to_string(),
+            }],
+            file_name: "schema.graphql".to_string(),
+            proposed_schema: SCHEMA.to_string(),
+        };
+        assert_that!(linter.conclude(lint_response).is_err()).is_equal_to(fails);
+    }
+}
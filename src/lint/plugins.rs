@@ -0,0 +1,462 @@
+//! Rule plugins: an organization's own lint rules, kept in the project's `.apollo/lint-rules`
+//! directory and run by `rover subgraph lint --local` alongside the built-in rules. Each plugin is
+//! one rule, named after its file.
+//!
+//! A Rhai script visits the schema by defining any of these functions, which are given the same
+//! maps that [schema policies](crate::policy) get as JSON. `report(message)` reports the element
+//! being visited, and `report(coordinate, message)` any other element:
+//!
+//! ```rhai
+//! fn visit_schema(schema) {}
+//! fn visit_type(ty) {}
+//! fn visit_field(field, ty) {}
+//! fn visit_argument(argument, field, ty) {}
+//! fn visit_value(value, ty) {} // input fields and enum values
+//! ```
+//!
+//! A WASM module exports its `memory`, an `alloc(len: i32) -> i32` function, and a
+//! `lint(ptr: i32, len: i32) -> i64` function. `lint` is given `{ "version": 1, "schema": ... }`
+//! as JSON, and returns where its JSON output is as `ptr << 32 | len`. The output is a list of
+//! violations, like a policy's: `{ "violations": [{ "message": "...", "coordinate": "..." }] }`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use rhai::{CallFnOptions, Dynamic, Engine, Scope, AST};
+use rover_std::{Fs, RoverStdError};
+use serde::{Deserialize, Serialize};
+
+use crate::policy::schema::PolicySchema;
+use crate::RoverResult;
+
+use super::rules::Finding;
+
+/// Where rule plugins are kept, relative to the project's root
+const PLUGINS_DIR: &str = ".apollo/lint-rules";
+
+/// The version of the JSON that WASM plugins are given
+const PROTOCOL_VERSION: u32 = 1;
+
+/// The most operations a Rhai plugin can run, and the most fuel a WASM plugin can burn, on one
+/// schema. Both are far more than a rule needs, and stop plugins that never finish
+const MAX_OPERATIONS: u64 = 10_000_000;
+
+#[derive(thiserror::Error, Debug)]
+pub enum LintPluginError {
+    #[error("Could not read the lint rules in {path}: {source}")]
+    ReadDir {
+        path: Utf8PathBuf,
+        source: RoverStdError,
+    },
+    #[error("Could not load the lint rule {path}: {message}")]
+    Load { path: Utf8PathBuf, message: String },
+    #[error("The lint rule {path} failed: {message}")]
+    Failed { path: Utf8PathBuf, message: String },
+}
+
+#[derive(Serialize)]
+struct WasmInput<'a> {
+    version: u32,
+    schema: &'a PolicySchema,
+}
+
+#[derive(Deserialize)]
+struct WasmOutput {
+    #[serde(default)]
+    violations: Vec<WasmViolation>,
+}
+
+#[derive(Deserialize)]
+struct WasmViolation {
+    message: String,
+    coordinate: Option<String>,
+}
+
+enum Runtime {
+    Rhai(AST),
+    Wasm(wasmi::Module),
+}
+
+/// A rule loaded from the plugins directory
+pub(crate) struct LintPlugin {
+    /// The plugin's file name without its extension, which is how it's configured and reported
+    pub(crate) name: String,
+    path: Utf8PathBuf,
+    runtime: Runtime,
+}
+
+impl LintPlugin {
+    fn load(path: Utf8PathBuf) -> Result<Option<Self>, LintPluginError> {
+        let load_error = |message: String| LintPluginError::Load {
+            path: path.clone(),
+            message,
+        };
+        let runtime = match path.extension() {
+            Some("rhai") => {
+                let script = Fs::read_file(&path).map_err(|err| load_error(err.to_string()))?;
+                let ast = rhai_engine()
+                    .compile(script)
+                    .map_err(|err| load_error(err.to_string()))?;
+                Runtime::Rhai(ast)
+            }
+            Some("wasm") => {
+                let bytes = std::fs::read(&path).map_err(|err| load_error(err.to_string()))?;
+                let module = wasmi::Module::new(&wasm_engine(), &bytes[..])
+                    .map_err(|err| load_error(err.to_string()))?;
+                Runtime::Wasm(module)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(Self {
+            name: path.file_stem().unwrap_or(path.as_str()).to_string(),
+            path,
+            runtime,
+        }))
+    }
+
+    /// Runs the rule over `schema`, returning the elements it reported
+    pub(crate) fn check(&self, schema: &PolicySchema) -> Result<Vec<Finding>, LintPluginError> {
+        let failed = |message: String| LintPluginError::Failed {
+            path: self.path.clone(),
+            message,
+        };
+        let violations = match &self.runtime {
+            Runtime::Rhai(ast) => run_rhai(ast, schema).map_err(failed)?,
+            Runtime::Wasm(module) => run_wasm(module, schema).map_err(failed)?,
+        };
+        Ok(violations
+            .into_iter()
+            .map(|(coordinate, message)| Finding {
+                location: coordinate
+                    .as_deref()
+                    .and_then(|coordinate| schema.locate(coordinate))
+                    .unwrap_or_default(),
+                coordinate: coordinate.unwrap_or_default(),
+                message,
+            })
+            .collect())
+    }
+}
+
+/// Loads the plugins of the project the current directory is in, in the order their file names
+/// sort in. Without a plugins directory there are none
+pub(crate) fn for_current_dir() -> RoverResult<Vec<LintPlugin>> {
+    let current_dir = Utf8PathBuf::try_from(std::env::current_dir()?)?;
+    let Some(dir) = current_dir
+        .ancestors()
+        .map(|ancestor| ancestor.join(PLUGINS_DIR))
+        .find(|dir| dir.is_dir())
+    else {
+        return Ok(Vec::new());
+    };
+    Ok(load(&dir)?)
+}
+
+pub(crate) fn load(dir: &Utf8Path) -> Result<Vec<LintPlugin>, LintPluginError> {
+    let entries = Fs::get_dir_entries(dir).map_err(|source| LintPluginError::ReadDir {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+    let mut paths: Vec<Utf8PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path().to_path_buf())
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+
+    let mut plugins = Vec::new();
+    for path in paths {
+        match LintPlugin::load(path.clone())? {
+            Some(plugin) => plugins.push(plugin),
+            None => tracing::debug!(
+                "skipping {}, which isn't a Rhai script or WASM module",
+                path
+            ),
+        }
+    }
+    Ok(plugins)
+}
+
+fn rhai_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine
+}
+
+/// Where the element a Rhai plugin is visiting is, and what it has reported so far
+#[derive(Default)]
+struct RhaiVisit {
+    coordinate: Option<String>,
+    violations: Vec<(Option<String>, String)>,
+}
+
+fn run_rhai(ast: &AST, schema: &PolicySchema) -> Result<Vec<(Option<String>, String)>, String> {
+    let visit = Rc::new(RefCell::new(RhaiVisit::default()));
+    let mut engine = rhai_engine();
+    let report_visit = visit.clone();
+    engine.register_fn("report", move |message: &str| {
+        let mut visit = report_visit.borrow_mut();
+        let coordinate = visit.coordinate.clone();
+        visit.violations.push((coordinate, message.to_string()));
+    });
+    let report_visit = visit.clone();
+    engine.register_fn("report", move |coordinate: &str, message: &str| {
+        report_visit
+            .borrow_mut()
+            .violations
+            .push((Some(coordinate.to_string()), message.to_string()));
+    });
+
+    let defines = |name: &str| ast.iter_functions().any(|function| function.name == name);
+    let mut scope = Scope::new();
+    engine
+        .run_ast_with_scope(&mut scope, ast)
+        .map_err(|err| err.to_string())?;
+    let mut call = |name: &str, coordinate: Option<&str>, args: Vec<Dynamic>| {
+        if !defines(name) {
+            return Ok(());
+        }
+        visit.borrow_mut().coordinate = coordinate.map(str::to_string);
+        engine
+            .call_fn_with_options::<Dynamic>(
+                CallFnOptions::new().eval_ast(false),
+                &mut scope,
+                ast,
+                name,
+                args,
+            )
+            .map(|_| ())
+            .map_err(|err| format!("{} {}", name, err))
+    };
+
+    call("visit_schema", None, vec![to_dynamic(schema)?])?;
+    for ty in &schema.types {
+        let ty_value = to_dynamic(ty)?;
+        call("visit_type", Some(&ty.name), vec![ty_value.clone()])?;
+        for field in &ty.fields {
+            let field_value = to_dynamic(field)?;
+            call(
+                "visit_field",
+                Some(&field.coordinate),
+                vec![field_value.clone(), ty_value.clone()],
+            )?;
+            for argument in &field.arguments {
+                call(
+                    "visit_argument",
+                    Some(&argument.coordinate),
+                    vec![to_dynamic(argument)?, field_value.clone(), ty_value.clone()],
+                )?;
+            }
+        }
+        for value in &ty.values {
+            call(
+                "visit_value",
+                Some(&value.coordinate),
+                vec![to_dynamic(value)?, ty_value.clone()],
+            )?;
+        }
+    }
+
+    let violations = std::mem::take(&mut visit.borrow_mut().violations);
+    Ok(violations)
+}
+
+fn to_dynamic(value: &impl Serialize) -> Result<Dynamic, String> {
+    rhai::serde::to_dynamic(value).map_err(|err| err.to_string())
+}
+
+fn wasm_engine() -> wasmi::Engine {
+    let mut config = wasmi::Config::default();
+    config.consume_fuel(true);
+    wasmi::Engine::new(&config)
+}
+
+fn run_wasm(
+    module: &wasmi::Module,
+    schema: &PolicySchema,
+) -> Result<Vec<(Option<String>, String)>, String> {
+    let input = serde_json::to_vec(&WasmInput {
+        version: PROTOCOL_VERSION,
+        schema,
+    })
+    .map_err(|err| err.to_string())?;
+
+    let mut store = wasmi::Store::new(module.engine(), ());
+    store
+        .add_fuel(MAX_OPERATIONS)
+        .map_err(|err| err.to_string())?;
+    let instance = wasmi::Linker::<()>::new(module.engine())
+        .instantiate(&mut store, module)
+        .and_then(|instance| instance.start(&mut store))
+        .map_err(|err| err.to_string())?;
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or("it doesn't export its memory")?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "alloc")
+        .map_err(|err| format!("alloc: {}", err))?;
+    let lint = instance
+        .get_typed_func::<(i32, i32), i64>(&store, "lint")
+        .map_err(|err| format!("lint: {}", err))?;
+
+    let len = i32::try_from(input.len()).map_err(|err| err.to_string())?;
+    let ptr = alloc
+        .call(&mut store, len)
+        .map_err(|err| format!("alloc: {}", err))?;
+    memory
+        .write(&mut store, ptr as u32 as usize, &input)
+        .map_err(|err| err.to_string())?;
+    let result = lint
+        .call(&mut store, (ptr, len))
+        .map_err(|err| format!("lint: {}", err))? as u64;
+
+    let mut output = vec![0; (result & 0xffff_ffff) as usize];
+    memory
+        .read(&store, (result >> 32) as usize, &mut output)
+        .map_err(|err| err.to_string())?;
+    let output: WasmOutput = serde_json::from_slice(&output).map_err(|err| {
+        format!(
+            "it returned output that isn't a list of violations: {}",
+            err
+        )
+    })?;
+    Ok(output
+        .violations
+        .into_iter()
+        .map(|violation| (violation.coordinate, violation.message))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    const SCHEMA: &str =
+        "type Query {\n  me: User\n}\n\ntype User {\n  id: ID!\n  secret: String\n}\n";
+
+    fn plugins_dir(plugins: &[(&str, &[u8])]) -> (TempDir, Utf8PathBuf) {
+        let temp = TempDir::new().unwrap();
+        let dir = Utf8PathBuf::from_path_buf(temp.path().join(PLUGINS_DIR)).unwrap();
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, contents) in plugins {
+            std::fs::write(dir.join(name), contents).unwrap();
+        }
+        (temp, dir)
+    }
+
+    fn check(dir: &Utf8Path) -> Vec<(String, String, String, usize)> {
+        let schema = PolicySchema::parse(SCHEMA).unwrap();
+        load(dir)
+            .unwrap()
+            .iter()
+            .flat_map(|plugin| {
+                plugin.check(&schema).unwrap().into_iter().map(|finding| {
+                    (
+                        plugin.name.clone(),
+                        finding.coordinate,
+                        finding.message,
+                        finding.location.line,
+                    )
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rhai_plugins_visit_the_schema() {
+        let (_temp, dir) = plugins_dir(&[(
+            "no_secrets.rhai",
+            br#"
+const FORBIDDEN = "secret";
+
+fn visit_type(ty) {
+    if ty.fields.len() > 1 && ty.name == "User" {
+        report("User has " + ty.fields.len() + " fields");
+    }
+}
+
+fn visit_field(field, ty) {
+    if field.name == FORBIDDEN {
+        report(field.name + " on " + ty.name + " is forbidden");
+    }
+}
+
+fn visit_schema(schema) {
+    report("Query.me", "checked " + schema.types.len() + " types");
+}
+"#,
+        )]);
+        assert_that!(check(&dir)).is_equal_to(vec![
+            (
+                "no_secrets".to_string(),
+                "Query.me".to_string(),
+                "checked 2 types".to_string(),
+                2,
+            ),
+            (
+                "no_secrets".to_string(),
+                "User".to_string(),
+                "User has 2 fields".to_string(),
+                5,
+            ),
+            (
+                "no_secrets".to_string(),
+                "User.secret".to_string(),
+                "secret on User is forbidden".to_string(),
+                7,
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_wasm_plugins_get_the_schema_as_json() {
+        // returns a fixed violation, after checking that its input is the schema
+        let output = r#"{"violations":[{"message":"no ids","coordinate":"User.id"}]}"#;
+        let wat = format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (data (i32.const 0) "{}")
+                (func (export "alloc") (param i32) (result i32) i32.const 1024)
+                (func (export "lint") (param $ptr i32) (param $len i32) (result i64)
+                    ;; the input starts with {{"version":1
+                    (if (i32.ne (i32.load8_u offset=11 (local.get $ptr)) (i32.const 49))
+                        (then unreachable))
+                    i64.const {}))"#,
+            output.replace('"', "\\\""),
+            output.len()
+        );
+        let (_temp, dir) = plugins_dir(&[("ids.wasm", &wat::parse_str(wat).unwrap())]);
+        assert_that!(check(&dir)).is_equal_to(vec![(
+            "ids".to_string(),
+            "User.id".to_string(),
+            "no ids".to_string(),
+            6,
+        )]);
+    }
+
+    #[test]
+    fn test_plugins_that_never_finish_are_stopped() {
+        let (_temp, dir) = plugins_dir(&[("forever.rhai", b"fn visit_schema(schema) { loop {} }")]);
+        let schema = PolicySchema::parse(SCHEMA).unwrap();
+        let plugins = load(&dir).unwrap();
+        let err = crate::RoverError::new(plugins[0].check(&schema).unwrap_err());
+        assert_that!(err.message()).contains("forever.rhai");
+        assert_that!(err.code()).is_equal_to(Some(crate::RoverErrorCode::E053));
+    }
+
+    #[test]
+    fn test_scripts_that_dont_compile_are_reported() {
+        let (_temp, dir) = plugins_dir(&[("broken.rhai", b"fn visit_type(ty) {")]);
+        assert_that!(matches!(load(&dir), Err(LintPluginError::Load { .. }))).is_true();
+    }
+
+    #[test]
+    fn test_other_files_are_skipped() {
+        let (_temp, dir) = plugins_dir(&[("README.md", b"# rules")]);
+        assert_that!(load(&dir).unwrap()).is_empty();
+    }
+}
@@ -0,0 +1,490 @@
+//! The rules `rover subgraph lint --local` runs. Each rule walks the parsed schema and reports
+//! the elements that break it
+
+use std::collections::BTreeSet;
+
+use crate::policy::schema::{
+    DirectiveApplication, Location, PolicySchema, TypeDefinition, TypeKind, ValueDefinition,
+};
+
+use super::RuleLevel;
+
+/// An element of the schema that breaks a rule
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Finding {
+    pub(crate) coordinate: String,
+    pub(crate) message: String,
+    pub(crate) location: Location,
+}
+
+impl Finding {
+    fn new(coordinate: &str, location: Location, message: String) -> Self {
+        Self {
+            coordinate: coordinate.to_string(),
+            message,
+            location,
+        }
+    }
+}
+
+pub(crate) struct Rule {
+    pub(crate) name: &'static str,
+    /// The level the rule reports at unless `.apollo/lint.toml` says otherwise
+    pub(crate) default_level: RuleLevel,
+    pub(crate) check: fn(&PolicySchema) -> Vec<Finding>,
+}
+
+/// Every built-in rule, in the order they run
+pub(crate) const RULES: &[Rule] = &[
+    Rule {
+        name: "TYPE_NAMES_SHOULD_BE_PASCAL_CASE",
+        default_level: RuleLevel::Warning,
+        check: type_names_should_be_pascal_case,
+    },
+    Rule {
+        name: "FIELD_NAMES_SHOULD_BE_CAMEL_CASE",
+        default_level: RuleLevel::Warning,
+        check: field_names_should_be_camel_case,
+    },
+    Rule {
+        name: "INPUT_ARGUMENT_NAMES_SHOULD_BE_CAMEL_CASE",
+        default_level: RuleLevel::Warning,
+        check: input_argument_names_should_be_camel_case,
+    },
+    Rule {
+        name: "ENUM_VALUES_SHOULD_BE_SCREAMING_SNAKE_CASE",
+        default_level: RuleLevel::Warning,
+        check: enum_values_should_be_screaming_snake_case,
+    },
+    Rule {
+        name: "DESCRIPTION_MISSING",
+        default_level: RuleLevel::Warning,
+        check: description_missing,
+    },
+    Rule {
+        name: "DEPRECATED_DIRECTIVE_MISSING_REASON",
+        default_level: RuleLevel::Warning,
+        check: deprecated_directive_missing_reason,
+    },
+    Rule {
+        name: "DEPRECATED_REQUIRED_INPUT",
+        default_level: RuleLevel::Error,
+        check: deprecated_required_input,
+    },
+    Rule {
+        name: "KEY_FIELD_MISSING",
+        default_level: RuleLevel::Error,
+        check: key_field_missing,
+    },
+    Rule {
+        name: "EXTERNAL_FIELD_UNUSED",
+        default_level: RuleLevel::Warning,
+        check: external_field_unused,
+    },
+];
+
+/// Names that GraphQL or federation reserve, like `__typename`, `_entities`, or `join__Graph`,
+/// which naming rules don't apply to
+fn is_reserved(name: &str) -> bool {
+    name.starts_with('_') || name.contains("__")
+}
+
+fn is_pascal_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase()) && !name.contains('_')
+}
+
+fn is_camel_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase()) && !name.contains('_')
+}
+
+fn is_screaming_snake_case(name: &str) -> bool {
+    name.chars()
+        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn directive<'a>(
+    directives: &'a [DirectiveApplication],
+    name: &str,
+) -> Option<&'a DirectiveApplication> {
+    directives.iter().find(|directive| directive.name == name)
+}
+
+/// The argument values of a field, and the fields of an input object
+fn input_values(ty: &TypeDefinition) -> impl Iterator<Item = &ValueDefinition> {
+    let arguments = ty.fields.iter().flat_map(|field| field.arguments.iter());
+    let input_fields = ty
+        .values
+        .iter()
+        .filter(move |_| ty.kind == TypeKind::InputObject);
+    arguments.chain(input_fields)
+}
+
+fn type_names_should_be_pascal_case(schema: &PolicySchema) -> Vec<Finding> {
+    schema
+        .types
+        .iter()
+        .filter(|ty| !ty.is_extension && !is_reserved(&ty.name) && !is_pascal_case(&ty.name))
+        .map(|ty| {
+            Finding::new(
+                &ty.name,
+                ty.location,
+                format!("Type names should use PascalCase, but {} doesn't.", ty.name),
+            )
+        })
+        .collect()
+}
+
+fn field_names_should_be_camel_case(schema: &PolicySchema) -> Vec<Finding> {
+    let object_fields = schema.types.iter().flat_map(|ty| {
+        ty.fields.iter().map(|field| {
+            (
+                field.name.as_str(),
+                field.coordinate.as_str(),
+                field.location,
+            )
+        })
+    });
+    let input_fields = schema
+        .types
+        .iter()
+        .filter(|ty| ty.kind == TypeKind::InputObject)
+        .flat_map(|ty| {
+            ty.values.iter().map(|value| {
+                (
+                    value.name.as_str(),
+                    value.coordinate.as_str(),
+                    value.location,
+                )
+            })
+        });
+    object_fields
+        .chain(input_fields)
+        .filter(|(name, ..)| !is_reserved(name) && !is_camel_case(name))
+        .map(|(name, coordinate, location)| {
+            Finding::new(
+                coordinate,
+                location,
+                format!("Field names should use camelCase, but {} doesn't.", name),
+            )
+        })
+        .collect()
+}
+
+fn input_argument_names_should_be_camel_case(schema: &PolicySchema) -> Vec<Finding> {
+    schema
+        .types
+        .iter()
+        .flat_map(|ty| ty.fields.iter().flat_map(|field| field.arguments.iter()))
+        .filter(|arg| !is_reserved(&arg.name) && !is_camel_case(&arg.name))
+        .map(|arg| {
+            Finding::new(
+                &arg.coordinate,
+                arg.location,
+                format!(
+                    "Argument names should use camelCase, but {} doesn't.",
+                    arg.name
+                ),
+            )
+        })
+        .collect()
+}
+
+fn enum_values_should_be_screaming_snake_case(schema: &PolicySchema) -> Vec<Finding> {
+    schema
+        .types
+        .iter()
+        .filter(|ty| ty.kind == TypeKind::Enum && !is_reserved(&ty.name))
+        .flat_map(|ty| ty.values.iter())
+        .filter(|value| !is_screaming_snake_case(&value.name))
+        .map(|value| {
+            Finding::new(
+                &value.coordinate,
+                value.location,
+                format!(
+                    "Enum values should use SCREAMING_SNAKE_CASE, but {} doesn't.",
+                    value.name
+                ),
+            )
+        })
+        .collect()
+}
+
+fn description_missing(schema: &PolicySchema) -> Vec<Finding> {
+    let is_missing = |description: &Option<String>| {
+        description
+            .as_deref()
+            .map_or(true, |description| description.trim().is_empty())
+    };
+    let mut findings = Vec::new();
+    for ty in schema.types.iter().filter(|ty| !is_reserved(&ty.name)) {
+        if !ty.is_extension && is_missing(&ty.description) {
+            findings.push(Finding::new(
+                &ty.name,
+                ty.location,
+                format!("Schema element {} is missing a description.", ty.name),
+            ));
+        }
+        let fields = ty.fields.iter().map(|field| {
+            (
+                &field.name,
+                &field.coordinate,
+                &field.description,
+                field.location,
+            )
+        });
+        let values = ty.values.iter().map(|value| {
+            (
+                &value.name,
+                &value.coordinate,
+                &value.description,
+                value.location,
+            )
+        });
+        for (name, coordinate, description, location) in fields.chain(values) {
+            if !is_reserved(name) && is_missing(description) {
+                findings.push(Finding::new(
+                    coordinate,
+                    location,
+                    format!("Schema element {} is missing a description.", coordinate),
+                ));
+            }
+        }
+    }
+    findings
+}
+
+fn deprecated_directive_missing_reason(schema: &PolicySchema) -> Vec<Finding> {
+    let fields = schema.types.iter().flat_map(|ty| {
+        ty.fields
+            .iter()
+            .map(|field| (&field.coordinate, &field.directives, field.location))
+    });
+    let values = schema.types.iter().flat_map(|ty| {
+        let arguments = ty.fields.iter().flat_map(|field| field.arguments.iter());
+        arguments
+            .chain(ty.values.iter())
+            .map(|value| (&value.coordinate, &value.directives, value.location))
+    });
+    fields
+        .chain(values)
+        .filter_map(|(coordinate, directives, location)| {
+            let deprecated = directive(directives, "deprecated")?;
+            let reason = deprecated
+                .arguments
+                .get("reason")
+                .map(|reason| reason.trim_matches('"').trim());
+            reason.map_or(true, str::is_empty).then(|| {
+                Finding::new(
+                    coordinate,
+                    location,
+                    format!(
+                        "{} is deprecated without a reason. Say what clients should use instead.",
+                        coordinate
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+fn deprecated_required_input(schema: &PolicySchema) -> Vec<Finding> {
+    schema
+        .types
+        .iter()
+        .flat_map(input_values)
+        .filter(|value| {
+            directive(&value.directives, "deprecated").is_some()
+                && value.ty.as_deref().is_some_and(|ty| ty.ends_with('!'))
+                && value.default_value.is_none()
+        })
+        .map(|value| {
+            Finding::new(
+                &value.coordinate,
+                value.location,
+                format!(
+                    "{} is required, so it can't be deprecated. Make it nullable or give it a default value first.",
+                    value.coordinate
+                ),
+            )
+        })
+        .collect()
+}
+
+/// The names in a field set, like the `fields` of `@key(fields: "id sku")`. Only the top-level
+/// names are returned, unless `nested` is set
+pub(crate) fn field_set_names(fields: &str, nested: bool) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut depth = 0;
+    let mut name = String::new();
+    for c in fields.trim_matches('"').chars().chain(std::iter::once(' ')) {
+        if c.is_alphanumeric() || c == '_' {
+            name.push(c);
+            continue;
+        }
+        if !name.is_empty() && (depth == 0 || nested) {
+            names.push(name.clone());
+        }
+        name.clear();
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    names
+}
+
+fn key_field_missing(schema: &PolicySchema) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for ty in &schema.types {
+        let defined: BTreeSet<&str> = schema
+            .types
+            .iter()
+            .filter(|other| other.name == ty.name)
+            .flat_map(|other| other.fields.iter().map(|field| field.name.as_str()))
+            .collect();
+        let keys = ty
+            .directives
+            .iter()
+            .filter(|directive| directive.name == "key")
+            .filter_map(|key| key.arguments.get("fields"));
+        for fields in keys {
+            for name in field_set_names(fields, false) {
+                if !defined.contains(name.as_str()) {
+                    findings.push(Finding::new(
+                        &ty.name,
+                        ty.location,
+                        format!(
+                            "The key {} of {} uses the field {}, which {} doesn't have.",
+                            fields, ty.name, name, ty.name
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    findings
+}
+
+fn external_field_unused(schema: &PolicySchema) -> Vec<Finding> {
+    let type_directives = schema.types.iter().flat_map(|ty| ty.directives.iter());
+    let field_directives = schema
+        .types
+        .iter()
+        .flat_map(|ty| ty.fields.iter().flat_map(|field| field.directives.iter()));
+    let used: BTreeSet<String> = type_directives
+        .chain(field_directives)
+        .filter(|directive| matches!(directive.name.as_str(), "key" | "requires" | "provides"))
+        .filter_map(|directive| directive.arguments.get("fields"))
+        .flat_map(|fields| field_set_names(fields, true))
+        .collect();
+
+    schema
+        .types
+        .iter()
+        .flat_map(|ty| {
+            // `@external` on a type marks all of its fields
+            let type_is_external = directive(&ty.directives, "external").is_some();
+            ty.fields.iter().filter(move |field| {
+                type_is_external || directive(&field.directives, "external").is_some()
+            })
+        })
+        .filter(|field| !used.contains(&field.name))
+        .map(|field| {
+            Finding::new(
+                &field.coordinate,
+                field.location,
+                format!(
+                    "{} is marked @external, but no @key, @requires, or @provides uses it.",
+                    field.coordinate
+                ),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    fn coordinates(check: fn(&PolicySchema) -> Vec<Finding>, sdl: &str) -> Vec<String> {
+        let schema = PolicySchema::parse(sdl).unwrap();
+        check(&schema)
+            .into_iter()
+            .map(|finding| finding.coordinate)
+            .collect()
+    }
+
+    #[rstest]
+    #[case::type_names(
+        type_names_should_be_pascal_case,
+        "type Query { a: ID } type snake_case { a: ID } type _Service { sdl: String }",
+        &["snake_case"]
+    )]
+    #[case::field_names(
+        field_names_should_be_camel_case,
+        "type Query { goodName: ID BadName: ID _entities: ID } input In { bad_name: ID }",
+        &["Query.BadName", "In.bad_name"]
+    )]
+    #[case::argument_names(
+        input_argument_names_should_be_camel_case,
+        "type Query { user(user_id: ID, name: String): ID }",
+        &["Query.user(user_id:)"]
+    )]
+    #[case::enum_values(
+        enum_values_should_be_screaming_snake_case,
+        "enum Color { DARK_RED blue }",
+        &["Color.blue"]
+    )]
+    #[case::descriptions(
+        description_missing,
+        r#""The root" type Query { "Me" me: ID them: ID } extend type Query { us: ID }"#,
+        &["Query.them", "Query.us"]
+    )]
+    #[case::deprecation_reasons(
+        deprecated_directive_missing_reason,
+        r#"type Query { a: ID @deprecated b: ID @deprecated(reason: "") c: ID @deprecated(reason: "use d") }"#,
+        &["Query.a", "Query.b"]
+    )]
+    #[case::deprecated_required_inputs(
+        deprecated_required_input,
+        "type Query { a(x: ID! @deprecated, y: ID @deprecated, z: ID! = 1 @deprecated): ID } input In { w: ID! @deprecated }",
+        &["Query.a(x:)", "In.w"]
+    )]
+    #[case::key_fields(
+        key_field_missing,
+        r#"type Product @key(fields: "id") @key(fields: "sku variation { id }") { id: ID! sku: String }"#,
+        &["Product"]
+    )]
+    #[case::external_fields(
+        external_field_unused,
+        r#"type Product @key(fields: "id") { id: ID! weight: Int @external size: Int @external shipping: Int @requires(fields: "size") }"#,
+        &["Product.weight"]
+    )]
+    fn test_rules(
+        #[case] check: fn(&PolicySchema) -> Vec<Finding>,
+        #[case] sdl: &str,
+        #[case] expected: &[&str],
+    ) {
+        assert_that!(coordinates(check, sdl)).is_equal_to(
+            expected
+                .iter()
+                .map(|coordinate| coordinate.to_string())
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn test_field_set_names() {
+        assert_that!(field_set_names("\"id organization { id }\"", false))
+            .is_equal_to(vec!["id".to_string(), "organization".to_string()]);
+        assert_that!(field_set_names("\"id organization { id }\"", true)).is_equal_to(vec![
+            "id".to_string(),
+            "organization".to_string(),
+            "id".to_string(),
+        ]);
+    }
+}
@@ -0,0 +1,409 @@
+//! Validates operation documents against the API schema of a supergraph.
+//!
+//! This covers the mistakes a schema change makes in the operations of its clients: fields and
+//! arguments that don't exist (anymore), types that fragments are conditioned on, and fragments
+//! that are spread without being defined. It isn't a full implementation of GraphQL validation, so
+//! it doesn't check things like variable usage or the types of argument values.
+
+use std::collections::BTreeMap;
+
+use apollo_parser::{cst, cst::CstNode, Parser};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::schema_diff::{api_types, SchemaDiffError, TypeKind, TypeSummary};
+
+/// An operation document to validate, and where it came from
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OperationSource {
+    /// A file path, or the collection and name of a saved operation
+    pub name: String,
+    pub body: String,
+}
+
+/// A problem with an operation document, located by line and column (both 1-based)
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct OperationError {
+    pub source: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct OperationValidation {
+    /// How many documents were validated
+    pub documents: usize,
+    pub errors: Vec<OperationError>,
+}
+
+impl OperationValidation {
+    /// Validates each of `sources` against the API schema of `schema_sdl`
+    pub fn new(schema_sdl: &str, sources: &[OperationSource]) -> Result<Self, SchemaDiffError> {
+        let schema = Schema::new(schema_sdl)?;
+        let errors = sources
+            .iter()
+            .flat_map(|source| schema.validate(source))
+            .collect();
+        Ok(Self {
+            documents: sources.len(),
+            errors,
+        })
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn get_output(&self) -> String {
+        if self.is_valid() {
+            return match self.documents {
+                1 => "The operation document is valid against the supergraph.".to_string(),
+                n => format!(
+                    "All {} operation documents are valid against the supergraph.",
+                    n
+                ),
+            };
+        }
+        self.errors
+            .iter()
+            .map(|error| {
+                format!(
+                    "{}:{}:{}: {}",
+                    error.source, error.line, error.column, error.message
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn get_json(&self) -> Value {
+        json!(self)
+    }
+}
+
+struct Schema {
+    types: BTreeMap<String, TypeSummary>,
+    /// The root type of each kind of operation, keyed by `query`, `mutation` or `subscription`
+    roots: BTreeMap<String, String>,
+}
+
+impl Schema {
+    fn new(sdl: &str) -> Result<Self, SchemaDiffError> {
+        let types = api_types(sdl, "supergraph")?;
+        let mut roots: BTreeMap<String, String> = [
+            ("query", "Query"),
+            ("mutation", "Mutation"),
+            ("subscription", "Subscription"),
+        ]
+        .into_iter()
+        .map(|(operation, root)| (operation.to_string(), root.to_string()))
+        .collect();
+        let document = Parser::new(sdl).parse().document();
+        for definition in document.definitions() {
+            if let cst::Definition::SchemaDefinition(def) = definition {
+                for root in def.root_operation_type_definitions() {
+                    if let (Some(operation), Some(name)) = (
+                        root.operation_type(),
+                        root.named_type().and_then(|named| named.name()),
+                    ) {
+                        roots.insert(operation.source_string(), name.text().to_string());
+                    }
+                }
+            }
+        }
+        Ok(Self { types, roots })
+    }
+
+    fn validate(&self, source: &OperationSource) -> Vec<OperationError> {
+        let mut validator = Validator {
+            schema: self,
+            source,
+            fragments: BTreeMap::new(),
+            errors: Vec::new(),
+        };
+        let parsed = Parser::new(&source.body).parse();
+        if parsed.errors().len() > 0 {
+            for error in parsed.errors() {
+                validator.error_at(error.index(), error.message().to_string());
+            }
+            return validator.errors;
+        }
+
+        let document = parsed.document();
+        for definition in document.definitions() {
+            if let cst::Definition::FragmentDefinition(def) = definition {
+                if let Some(name) = def.fragment_name().and_then(|name| name.name()) {
+                    validator.fragments.insert(name.text().to_string(), def);
+                }
+            }
+        }
+        for definition in document.definitions() {
+            match definition {
+                cst::Definition::OperationDefinition(def) => validator.operation(def),
+                cst::Definition::FragmentDefinition(def) => validator.fragment(def),
+                _ => (),
+            }
+        }
+        validator.errors
+    }
+
+    /// The type of `field` on `type_name`, without list and non-null wrappers
+    fn field_type(&self, type_name: &str, field: &str) -> Option<String> {
+        let ty = self
+            .types
+            .get(type_name)?
+            .members
+            .get(field)?
+            .ty
+            .as_deref()?;
+        Some(
+            ty.trim_matches(|c| matches!(c, '[' | ']' | '!'))
+                .to_string(),
+        )
+    }
+
+    fn is_composite(&self, type_name: &str) -> bool {
+        self.types.get(type_name).is_some_and(|ty| {
+            matches!(
+                ty.kind,
+                TypeKind::Object | TypeKind::Interface | TypeKind::Union
+            )
+        })
+    }
+}
+
+struct Validator<'a> {
+    schema: &'a Schema,
+    source: &'a OperationSource,
+    fragments: BTreeMap<String, cst::FragmentDefinition>,
+    errors: Vec<OperationError>,
+}
+
+impl Validator<'_> {
+    fn operation(&mut self, def: cst::OperationDefinition) {
+        let operation = def
+            .operation_type()
+            .map(|ty| ty.source_string())
+            .unwrap_or_else(|| "query".to_string());
+        let root = self
+            .schema
+            .roots
+            .get(&operation)
+            .filter(|root| self.schema.types.contains_key(*root));
+        match root {
+            Some(root) => self.selection_set(root, def.selection_set()),
+            None => self.error_at_node(
+                def.syntax(),
+                format!("The supergraph doesn't support {} operations.", operation),
+            ),
+        }
+    }
+
+    fn fragment(&mut self, def: cst::FragmentDefinition) {
+        if let Some(type_name) = self.type_condition(def.type_condition()) {
+            self.selection_set(&type_name, def.selection_set());
+        }
+    }
+
+    /// The type a fragment is conditioned on, if it's a type the fragment can select fields of
+    fn type_condition(&mut self, condition: Option<cst::TypeCondition>) -> Option<String> {
+        let named = condition?.named_type()?;
+        let type_name = named.name()?.text().to_string();
+        if self.schema.is_composite(&type_name) {
+            Some(type_name)
+        } else {
+            self.error_at_node(
+                named.syntax(),
+                format!("Unknown type `{}` in fragment condition.", type_name),
+            );
+            None
+        }
+    }
+
+    fn selection_set(&mut self, type_name: &str, selection_set: Option<cst::SelectionSet>) {
+        for selection in selection_set.iter().flat_map(|set| set.selections()) {
+            match selection {
+                cst::Selection::Field(field) => self.field(type_name, field),
+                cst::Selection::InlineFragment(fragment) => {
+                    let type_name = match fragment.type_condition() {
+                        Some(condition) => match self.type_condition(Some(condition)) {
+                            Some(type_name) => type_name,
+                            None => continue,
+                        },
+                        None => type_name.to_string(),
+                    };
+                    self.selection_set(&type_name, fragment.selection_set());
+                }
+                cst::Selection::FragmentSpread(spread) => {
+                    let Some(name) = spread.fragment_name().and_then(|name| name.name()) else {
+                        continue;
+                    };
+                    if !self.fragments.contains_key(&name.text().to_string()) {
+                        self.error_at_node(
+                            name.syntax(),
+                            format!("Unknown fragment `{}`.", name.text()),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn field(&mut self, type_name: &str, field: cst::Field) {
+        let Some(name) = field.name() else {
+            return;
+        };
+        let field_name = name.text().to_string();
+        if field_name == "__typename" {
+            return;
+        }
+        // introspection is answered by the router, from the API schema these are validated against
+        if matches!(field_name.as_str(), "__schema" | "__type")
+            && self.schema.roots.get("query").map(String::as_str) == Some(type_name)
+        {
+            return;
+        }
+
+        let ty = self.schema.types.get(type_name);
+        let member = ty
+            .filter(|ty| matches!(ty.kind, TypeKind::Object | TypeKind::Interface))
+            .and_then(|ty| ty.members.get(&field_name));
+        let Some(member) = member else {
+            self.error_at_node(
+                name.syntax(),
+                format!(
+                    "Cannot query field `{}` on type `{}`.",
+                    field_name, type_name
+                ),
+            );
+            return;
+        };
+
+        for argument in field.arguments().iter().flat_map(|args| args.arguments()) {
+            let Some(argument_name) = argument.name() else {
+                continue;
+            };
+            if !member
+                .arguments
+                .contains_key(&argument_name.text().to_string())
+            {
+                self.error_at_node(
+                    argument_name.syntax(),
+                    format!(
+                        "Unknown argument `{}` on field `{}.{}`.",
+                        argument_name.text(),
+                        type_name,
+                        field_name
+                    ),
+                );
+            }
+        }
+
+        if let Some(field_type) = self.schema.field_type(type_name, &field_name) {
+            if self.schema.is_composite(&field_type) {
+                self.selection_set(&field_type, field.selection_set());
+            }
+        }
+    }
+
+    fn error_at_node(&mut self, node: &apollo_parser::SyntaxNode, message: String) {
+        self.error_at(usize::from(node.text_range().start()), message);
+    }
+
+    fn error_at(&mut self, offset: usize, message: String) {
+        let before = &self.source.body[..offset.min(self.source.body.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before
+            .rsplit('\n')
+            .next()
+            .map(|line| line.chars().count())
+            .unwrap_or_default()
+            + 1;
+        self.errors.push(OperationError {
+            source: self.source.name.clone(),
+            line,
+            column,
+            message,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    const SCHEMA: &str = r#"
+        schema { query: Query mutation: Mutation }
+        type Query { product(id: ID!): Product search: [SearchResult!]! }
+        type Mutation { addToCart(id: ID!): Boolean }
+        interface Node { id: ID! }
+        type Product implements Node { id: ID! name: String secret: String @inaccessible }
+        type Review implements Node { id: ID! body: String }
+        union SearchResult = Product | Review
+    "#;
+
+    fn validate(body: &str) -> Vec<String> {
+        let source = OperationSource {
+            name: "ops/products.graphql".to_string(),
+            body: body.to_string(),
+        };
+        OperationValidation::new(SCHEMA, &[source])
+            .unwrap()
+            .errors
+            .into_iter()
+            .map(|error| format!("{}:{}: {}", error.line, error.column, error.message))
+            .collect()
+    }
+
+    #[rstest]
+    #[case::fields_and_arguments("{ product(id: 1) { id name __typename } }")]
+    #[case::mutations("mutation { addToCart(id: 1) }")]
+    #[case::inline_fragments("{ search { ... on Product { name } ... on Review { body } } }")]
+    #[case::named_fragments("{ product(id: 1) { ...P } } fragment P on Node { id }")]
+    #[case::introspection("{ __schema { types { name } } }")]
+    fn test_valid_operations(#[case] body: &str) {
+        assert_that!(validate(body)).is_empty();
+    }
+
+    #[rstest]
+    #[case::unknown_field(
+        "query {\n  product(id: 1) {\n    price\n  }\n}",
+        "3:5: Cannot query field `price` on type `Product`."
+    )]
+    #[case::inaccessible_field(
+        "{ product(id: 1) { secret } }",
+        "1:20: Cannot query field `secret` on type `Product`."
+    )]
+    #[case::unknown_argument(
+        "{ product(id: 1, sku: 2) { id } }",
+        "1:18: Unknown argument `sku` on field `Query.product`."
+    )]
+    #[case::union_field(
+        "{ search { id } }",
+        "1:12: Cannot query field `id` on type `SearchResult`."
+    )]
+    #[case::unknown_type_condition(
+        "{ search { ... on Category { id } } }",
+        "1:19: Unknown type `Category` in fragment condition."
+    )]
+    #[case::unknown_fragment(
+        "{ product(id: 1) { ...Missing } }",
+        "1:23: Unknown fragment `Missing`."
+    )]
+    #[case::unsupported_operation(
+        "subscription { product(id: 1) { id } }",
+        "1:1: The supergraph doesn't support subscription operations."
+    )]
+    fn test_invalid_operations(#[case] body: &str, #[case] expected: &str) {
+        assert_that!(validate(body)).is_equal_to(vec![expected.to_string()]);
+    }
+
+    #[test]
+    fn test_reports_syntax_errors() {
+        assert_that!(validate("{ product(id: 1) { id }")).has_length(1);
+    }
+}
@@ -26,6 +26,11 @@ pub struct CheckConfigOpts {
     /// If the check should be run asynchronously and exit without waiting for check results
     #[arg(long)]
     pub background: bool,
+
+    /// Fail the check if the checks of any downstream variant (such as a contract)
+    /// fail, even if that variant isn't configured to block the check
+    #[arg(long, conflicts_with = "background")]
+    pub fail_on_downstream: bool,
 }
 
 fn parse_query_count_threshold(threshold: &str) -> Result<i64, io::Error> {
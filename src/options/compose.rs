@@ -1,10 +1,10 @@
-use super::ProfileOpt;
+use super::{CompositionLimitOpts, IntrospectionPolicyOpts, ProfileOpt, SchemaSizeOpts};
 use crate::options::LicenseAccepter;
 
 use clap::Parser;
 use serde::Serialize;
 
-#[cfg(all(feature = "composition-js", not(feature = "dev-next")))]
+#[cfg(feature = "composition-js")]
 use crate::{utils::client::StudioClientConfig, RoverResult};
 
 #[cfg_attr(test, derive(Default))]
@@ -21,9 +21,24 @@ pub struct PluginOpts {
     /// Passing this flag will attempt to use the latest compatible version of a plugin already installed on this machine.
     #[arg(long = "skip-update")]
     pub skip_update: bool,
+
+    /// The environment to pick subgraph endpoints for.
+    ///
+    /// Subgraphs in a supergraph config can give their `routing_url` as one URL per environment, like `{ dev: http://localhost:4001, prod: https://users.example.com }`, falling back on the one under `default`. They can also list a `routing_url` and a `subgraph_url` to introspect under `environments`, which replace the subgraph's own in that environment.
+    #[arg(long = "env", value_name = "ENVIRONMENT")]
+    pub env: Option<String>,
+
+    #[clap(flatten)]
+    pub schema_size_opts: SchemaSizeOpts,
+
+    #[clap(flatten)]
+    pub composition_limit_opts: CompositionLimitOpts,
+
+    #[clap(flatten)]
+    pub introspection_policy_opts: IntrospectionPolicyOpts,
 }
 
-#[cfg(all(feature = "composition-js", not(feature = "dev-next")))]
+#[cfg(feature = "composition-js")]
 impl PluginOpts {
     pub fn prompt_for_license_accept(&self, client_config: &StudioClientConfig) -> RoverResult<()> {
         self.elv2_license_accepter
@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+use clap::Parser;
+use serde::Serialize;
+
+use super::SchemaSize;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Parser)]
+pub struct CompositionLimitOpts {
+    /// The longest, in seconds, that the composition binary may run before it's stopped.
+    ///
+    /// Without a limit, a schema that composition gets stuck on is waited on forever.
+    #[arg(long = "composition-timeout", value_name = "SECONDS")]
+    #[serde(skip_serializing)]
+    pub composition_timeout: Option<u64>,
+
+    /// The most the composition binary may print to stdout before it's stopped, such as `100MB`.
+    ///
+    /// Sizes without a unit are in bytes, and `KB`, `MB`, and `GB` are multiples of 1024.
+    #[arg(long = "max-composition-output", value_name = "SIZE")]
+    #[serde(skip_serializing)]
+    pub max_composition_output: Option<SchemaSize>,
+}
+
+impl CompositionLimitOpts {
+    pub fn timeout(&self) -> Option<Duration> {
+        self.composition_timeout.map(Duration::from_secs)
+    }
+
+    pub fn max_output_bytes(&self) -> Option<u64> {
+        self.max_composition_output.map(|size| size.bytes())
+    }
+}
@@ -1,7 +1,12 @@
-use clap::Parser;
-use rover_client::shared::GraphRef;
+use clap::{Parser, ValueEnum};
+use rover_client::{
+    operations::graph::introspect,
+    shared::{FetchResponse, GraphRef},
+};
 use serde::{Deserialize, Serialize};
 
+use crate::{RoverOutput, RoverResult};
+
 #[derive(Debug, Serialize, Deserialize, Parser)]
 pub struct GraphRefOpt {
     /// <NAME>@<VARIANT> of graph in Apollo Studio.
@@ -19,3 +24,47 @@ pub struct OptionalGraphRefOpt {
     #[serde(skip_serializing)]
     pub graph_ref: Option<GraphRef>,
 }
+
+/// Which of a graph variant's schemas to fetch
+#[derive(Debug, Default, Serialize, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum SchemaKind {
+    /// The API schema that clients query
+    #[default]
+    Api,
+
+    /// The composed supergraph schema the router runs, including its `join__` and `@link` machinery
+    Supergraph,
+}
+
+/// Which format to print a fetched schema in
+#[derive(Debug, Default, Serialize, Clone, Copy, Eq, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum SchemaFormat {
+    /// The schema as SDL, the way it was published
+    #[default]
+    Sdl,
+
+    /// The result of an introspection query, for codegen tools that read that instead of SDL
+    IntrospectionJson,
+
+    /// SDL on a single line, without comments
+    MinifiedSdl,
+}
+
+impl SchemaFormat {
+    /// The output for a fetched schema, converted to this format
+    pub fn output(self, fetch_response: FetchResponse) -> RoverResult<RoverOutput> {
+        let sdl = &fetch_response.sdl.contents;
+        let schema = match self {
+            SchemaFormat::Sdl => return Ok(RoverOutput::FetchResponse(fetch_response)),
+            SchemaFormat::IntrospectionJson => {
+                serde_json::to_string_pretty(&introspect::introspection_json(sdl)?)?
+            }
+            SchemaFormat::MinifiedSdl => introspect::minify_sdl(sdl)?,
+        };
+        Ok(RoverOutput::ConvertedSchema {
+            schema,
+            format: self,
+        })
+    }
+}
@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     options::{OutputOpts, RoverPrinter},
+    schema_diff::SchemaDiff,
     utils::parsers::parse_header,
     RoverOutput, RoverResult,
 };
@@ -33,12 +34,18 @@ pub struct IntrospectOpts {
     #[arg(long)]
     pub watch: bool,
 
+    /// With `--watch`, print what changed since the last time the schema changed instead of the
+    /// whole schema. The first schema is printed in full
+    #[arg(long, requires = "watch")]
+    #[serde(default)]
+    pub diff: bool,
+
     /// The interval at which to poll the endpoint
     #[serde(skip_serializing)]
     // We skip this because we're already using one from the dev command
     // TODO: eventually we should reocncile the dev option with this one and figure out which is
     // best to use
-    #[arg(skip)]
+    #[arg(skip = Duration::from_secs(1))]
     pub polling_interval: Duration,
 }
 
@@ -49,6 +56,7 @@ impl IntrospectOpts {
         G: Future<Output = RoverResult<String>>,
     {
         let mut last_result = None;
+        let mut last_sdl: Option<String> = None;
         loop {
             match exec_fn().await {
                 Ok(sdl) => {
@@ -61,13 +69,14 @@ impl IntrospectOpts {
 
                     if was_updated {
                         let sdl = sdl.to_string();
-                        let output = RoverOutput::Introspection(sdl.clone());
+                        let output = self.watch_output(last_sdl.as_deref(), &sdl);
                         let _ = output.write_or_print(output_opts).map_err(|e| e.print());
                         if let Some(channel) = &output_opts.channel {
                             // TODO: error handling
                             let _ = channel.send(OutputChannelKind::Sdl(sdl));
                         }
                     }
+                    last_sdl = Some(sdl.clone());
                     last_result = Some(sdl);
                 }
                 Err(error) => {
@@ -91,4 +100,64 @@ impl IntrospectOpts {
             tokio::time::sleep(self.polling_interval).await
         }
     }
+
+    /// What to print when the schema has changed, which is the whole schema unless `--diff` was
+    /// passed and there's an earlier schema to compare against
+    fn watch_output(&self, last_sdl: Option<&str>, sdl: &str) -> RoverOutput {
+        match last_sdl {
+            Some(last_sdl) if self.diff => match SchemaDiff::new(last_sdl, sdl) {
+                Ok(diff) => RoverOutput::SupergraphDiff(diff),
+                Err(err) => {
+                    tracing::debug!("could not diff introspection results: {}", err);
+                    RoverOutput::Introspection(sdl.to_string())
+                }
+            },
+            _ => RoverOutput::Introspection(sdl.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Url;
+    use speculoos::prelude::*;
+
+    use super::IntrospectOpts;
+    use crate::RoverOutput;
+
+    const OLD_SDL: &str = "type Query { products: [Product] }\ntype Product { id: ID! }";
+    const NEW_SDL: &str =
+        "type Query { products: [Product] }\ntype Product { id: ID! name: String }";
+
+    fn opts(diff: bool) -> IntrospectOpts {
+        IntrospectOpts {
+            endpoint: Url::parse("http://localhost:4001").unwrap(),
+            headers: None,
+            watch: true,
+            diff,
+            polling_interval: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_watch_output_prints_first_schema_in_full() {
+        let output = opts(true).watch_output(None, OLD_SDL);
+        assert!(matches!(output, RoverOutput::Introspection(sdl) if sdl == OLD_SDL));
+    }
+
+    #[test]
+    fn test_watch_output_diffs_against_last_schema() {
+        let output = opts(true).watch_output(Some(OLD_SDL), NEW_SDL);
+        let RoverOutput::SupergraphDiff(diff) = output else {
+            panic!("expected a diff, got {:?}", output);
+        };
+        assert_that!(diff.changes).has_length(1);
+        assert_that!(diff.changes[0].coordinate.as_str()).is_equal_to("Product.name");
+    }
+
+    #[test]
+    fn test_watch_output_without_diff() {
+        let output = opts(false).watch_output(Some(OLD_SDL), NEW_SDL);
+        assert!(matches!(output, RoverOutput::Introspection(sdl) if sdl == NEW_SDL));
+    }
 }
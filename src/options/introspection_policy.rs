@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+use clap::Parser;
+use serde::Serialize;
+
+use crate::utils::effect::introspect::IntrospectionPolicy;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Parser)]
+pub struct IntrospectionPolicyOpts {
+    /// The longest, in seconds, to wait for a subgraph to respond to introspection.
+    ///
+    /// A subgraph's `introspection.timeout` in a supergraph config takes precedence.
+    #[arg(long = "introspection-timeout", value_name = "SECONDS")]
+    #[serde(skip_serializing)]
+    pub introspection_timeout: Option<u64>,
+
+    /// How many times to retry introspecting a subgraph after it times out or returns an HTTP error.
+    ///
+    /// A subgraph's `introspection.retries` in a supergraph config takes precedence.
+    #[arg(
+        long = "introspection-retries",
+        value_name = "RETRIES",
+        default_value = "0"
+    )]
+    #[serde(skip_serializing)]
+    pub introspection_retries: u32,
+}
+
+impl IntrospectionPolicyOpts {
+    pub fn policy(&self) -> IntrospectionPolicy {
+        IntrospectionPolicy {
+            timeout: self.introspection_timeout.map(Duration::from_secs),
+            retries: self.introspection_retries,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::anyhow;
+use serde_json::Value;
+
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+/// A path into Rover's JSON output, written like a `jq` filter: `.data.tasks[0].status`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    segments: Vec<JsonPathSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum JsonPathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl JsonPath {
+    /// The value at the path, if there is one
+    pub fn select<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(value, |value, segment| match segment {
+                JsonPathSegment::Key(key) => value.get(key),
+                JsonPathSegment::Index(index) => value.get(index),
+            })
+    }
+
+    /// The value at the path as it should be printed: strings without their quotes, so they can
+    /// be compared in a shell, and everything else as JSON
+    pub fn extract(&self, value: &Value) -> RoverResult<String> {
+        match self.select(value) {
+            Some(Value::String(string)) => Ok(string.clone()),
+            Some(value) => Ok(value.to_string()),
+            None => {
+                let mut err = RoverError::new(anyhow!(
+                    "There is nothing at `{}` in the JSON output.",
+                    self
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Run the command with `--format json` instead of `--json-path` to see the whole output.".to_string(),
+                ));
+                Err(err)
+            }
+        }
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| anyhow!("`{input}` is not a valid JSON path: {reason}");
+        let mut rest = input
+            .strip_prefix('.')
+            .ok_or_else(|| invalid("it must start with `.`"))?;
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            if let Some(index) = rest.strip_prefix('[') {
+                let (index, after) = index
+                    .split_once(']')
+                    .ok_or_else(|| invalid("a `[` is never closed"))?;
+                let index = index
+                    .parse()
+                    .map_err(|_| invalid(&format!("`{index}` is not an array index")))?;
+                segments.push(JsonPathSegment::Index(index));
+                rest = after;
+            } else {
+                let key = rest.strip_prefix('.').unwrap_or(rest);
+                if segments.is_empty() && key.len() != rest.len() {
+                    return Err(invalid("it has an empty key"));
+                }
+                if !segments.is_empty() && key.len() == rest.len() {
+                    return Err(invalid("keys after the first must start with `.`"));
+                }
+                let end = key.find(['.', '[']).unwrap_or(key.len());
+                if end == 0 {
+                    return Err(invalid("it has an empty key"));
+                }
+                segments.push(JsonPathSegment::Key(key[..end].to_string()));
+                rest = &key[end..];
+            }
+        }
+        Ok(Self { segments })
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.segments.is_empty() {
+            return write!(f, ".");
+        }
+        for segment in &self.segments {
+            match segment {
+                JsonPathSegment::Key(key) => write!(f, ".{key}")?,
+                JsonPathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use serde_json::json;
+    use speculoos::prelude::*;
+
+    use super::JsonPath;
+
+    #[rstest]
+    #[case::root(".", r#"{"data":{"success":true,"tasks":[{"status":"PASSED"}]}}"#)]
+    #[case::key(".data.success", "true")]
+    #[case::string(".data.tasks[0].status", "PASSED")]
+    #[case::array(".data.tasks", r#"[{"status":"PASSED"}]"#)]
+    fn test_extract(#[case] path: &str, #[case] expected: &str) {
+        let output = json!({"data": {"success": true, "tasks": [{"status": "PASSED"}]}});
+        let path: JsonPath = path.parse().unwrap();
+        assert_that!(path.extract(&output))
+            .is_ok()
+            .is_equal_to(expected.to_string());
+    }
+
+    #[rstest]
+    #[case::missing_key(".data.failure")]
+    #[case::out_of_bounds(".data.tasks[1]")]
+    #[case::index_into_object(".data[0]")]
+    fn test_extract_reports_missing_values(#[case] path: &str) {
+        let output = json!({"data": {"tasks": [{"status": "PASSED"}]}});
+        let path: JsonPath = path.parse().unwrap();
+        assert_that!(path.extract(&output)).is_err();
+    }
+
+    #[rstest]
+    #[case::no_leading_dot("data.success")]
+    #[case::empty_key(".data..success")]
+    #[case::leading_empty_key("..data")]
+    #[case::unclosed_index(".data.tasks[0")]
+    #[case::invalid_index(".data.tasks[first]")]
+    #[case::key_after_index(".tasks[0]status")]
+    fn test_parse_rejects_invalid_paths(#[case] path: &str) {
+        assert_that!(path.parse::<JsonPath>()).is_err();
+    }
+
+    #[rstest]
+    #[case(".")]
+    #[case(".data.tasks[0].status")]
+    #[case(".tasks[0][1]")]
+    fn test_display_round_trips(#[case] path: &str) {
+        assert_that!(path.parse::<JsonPath>().unwrap().to_string()).is_equal_to(path.to_string());
+    }
+}
@@ -1,9 +1,38 @@
 use clap::Parser;
+use rover_std::warnln;
 use serde::{Deserialize, Serialize};
 
+use crate::policy::LocalPolicies;
+use crate::RoverResult;
+
 #[derive(Debug, Serialize, Deserialize, Parser)]
 pub struct LintOpts {
     /// Ignore existing lint violations for a published subgraph. If passed, the command will only report lint violations introduced by recent changes.
     #[arg(long)]
     pub ignore_existing_lint_violations: bool,
+
+    #[clap(flatten)]
+    pub policies: PolicyOpts,
+}
+
+#[derive(Debug, Serialize, Deserialize, Parser)]
+pub struct PolicyOpts {
+    /// Also evaluate the schema policies in the project's `.apollo/policies` directory, locally,
+    /// and fail on the errors they report
+    #[arg(long)]
+    pub local_policies: bool,
+}
+
+impl PolicyOpts {
+    /// The project's policies, when `--local-policies` was passed
+    pub(crate) fn load(&self) -> RoverResult<Option<LocalPolicies>> {
+        if !self.local_policies {
+            return Ok(None);
+        }
+        let policies = LocalPolicies::for_current_dir()?;
+        if policies.is_empty() {
+            warnln!("--local-policies was passed, but the project's policies directory has no executable policies.");
+        }
+        Ok(Some(policies))
+    }
 }
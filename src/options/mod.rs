@@ -1,27 +1,37 @@
 mod check;
 mod compose;
+mod composition_limits;
 mod file;
 mod graph;
 mod introspect;
+mod introspection_policy;
+mod json_path;
 mod license;
 mod lint;
 mod output;
 mod persisted_queries;
+mod process_limits;
 mod profile;
 mod schema;
+mod schema_size;
 mod subgraph;
 mod template;
 
 pub(crate) use check::*;
 pub(crate) use compose::*;
+pub(crate) use composition_limits::*;
 pub(crate) use file::*;
 pub(crate) use graph::*;
 pub(crate) use introspect::*;
+pub(crate) use introspection_policy::*;
+pub(crate) use json_path::*;
 pub(crate) use license::*;
 pub(crate) use lint::*;
 pub(crate) use output::*;
 pub(crate) use persisted_queries::*;
+pub(crate) use process_limits::*;
 pub(crate) use profile::*;
 pub(crate) use schema::*;
+pub(crate) use schema_size::*;
 pub(crate) use subgraph::*;
 pub(crate) use template::*;
@@ -2,6 +2,7 @@ use std::{
     fmt,
     io::{self, IsTerminal},
     path,
+    time::Duration,
 };
 
 use calm_io::{stderrln, stdoutln};
@@ -10,10 +11,19 @@ use clap::Parser;
 use serde::Serialize;
 use serde_json::{json, Value};
 
-use rover_std::{Fs, Style};
+use rover_std::{
+    timing::{self, PhaseTiming},
+    Fs, Style,
+};
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::{cli::RoverOutputFormatKind, RoverError, RoverOutput, RoverResult};
+use crate::{
+    cli::RoverOutputFormatKind,
+    options::JsonPath,
+    utils::github_annotations::GithubAnnotation,
+    utils::table::{self, row},
+    RoverError, RoverOutput, RoverResult,
+};
 
 pub trait RoverPrinter {
     fn write_or_print(self, output_opts: &OutputOpts) -> RoverResult<()>;
@@ -22,9 +32,24 @@ pub trait RoverPrinter {
 impl RoverPrinter for RoverOutput {
     fn write_or_print(self, output_opts: &OutputOpts) -> RoverResult<()> {
         // Format the RoverOutput as either plain text or JSON.
-        let output = match output_opts.format_kind {
-            RoverOutputFormatKind::Plain => self.get_stdout(),
-            RoverOutputFormatKind::Json => Ok(Some(JsonOutput::from(self.clone()).to_string())),
+        let output = match (output_opts.format_kind, &output_opts.json_path) {
+            _ if output_opts.porcelain => self.get_porcelain(),
+            (_, Some(json_path)) => {
+                let json = JsonOutput::from(self.clone()).with_phase_timings(output_opts.verbose);
+                Ok(Some(json_path.extract(&json!(json))?))
+            }
+            (RoverOutputFormatKind::Plain | RoverOutputFormatKind::GithubAnnotations, None) => {
+                self.get_stdout()
+            }
+            (RoverOutputFormatKind::Json, None) => Ok(Some(
+                JsonOutput::from(self.clone())
+                    .with_phase_timings(output_opts.verbose)
+                    .to_string(),
+            )),
+            (RoverOutputFormatKind::Junit, None) => match self.get_junit() {
+                Some(report) => Ok(Some(report.with_time(command_time()).to_string())),
+                None => self.get_stdout(),
+            },
         };
 
         // Print the RoverOutput to file or stdout.
@@ -52,23 +77,64 @@ impl RoverPrinter for RoverOutput {
             }
         }
 
+        if output_opts.format_kind == RoverOutputFormatKind::GithubAnnotations {
+            print_github_annotations(&self.get_github_annotations())?;
+        }
+
         Ok(())
     }
 }
 
 impl RoverPrinter for RoverError {
     fn write_or_print(self, output_opts: &OutputOpts) -> RoverResult<()> {
-        match output_opts.format_kind {
-            RoverOutputFormatKind::Plain => self.print(),
-            RoverOutputFormatKind::Json => {
-                let json = JsonOutput::from(self);
+        match (output_opts.format_kind, &output_opts.json_path) {
+            _ if output_opts.porcelain => {
+                if let Some(status) = self.get_porcelain() {
+                    stdoutln!("{}", status)?;
+                }
+                stderrln!("{}", self)?;
+                Ok(())
+            }
+            (RoverOutputFormatKind::Plain, None) => self.print(),
+            (RoverOutputFormatKind::GithubAnnotations, None) => {
+                self.print()?;
+                print_github_annotations(&self.get_github_annotations())
+            }
+            (RoverOutputFormatKind::Junit, None) => {
+                let report = self.get_junit().with_time(command_time()).to_string();
+                match &output_opts.output_file {
+                    // with the report in a file, stdout has room for the usual output
+                    Some(file) => {
+                        Fs::write_file(file, report)?;
+                        stderrln!(
+                            "{} {}",
+                            Style::Heading.paint("JUnit report was printed to"),
+                            file
+                        )?;
+                        self.print()
+                    }
+                    None => {
+                        stdoutln!("{}", report)?;
+                        stderrln!("{}", self)?;
+                        Ok(())
+                    }
+                }
+            }
+            (_, json_path) => {
+                let json = JsonOutput::from(self).with_phase_timings(output_opts.verbose);
+                // the command already failed, so when there's nothing at the path the whole error
+                // is more useful than a second error about the path
+                let json = json_path
+                    .as_ref()
+                    .and_then(|json_path| json_path.extract(&json!(json)).ok())
+                    .unwrap_or_else(|| json.to_string());
                 match &output_opts.output_file {
                     Some(file) => {
                         let success_heading = Style::Heading.paint("Error JSON was printed to");
-                        Fs::write_file(file, json.to_string())?;
+                        Fs::write_file(file, json)?;
                         stderrln!("{} {}", success_heading, file)?;
                     }
-                    None => json.print()?,
+                    None => stdoutln!("{}", json)?,
                 }
                 Ok(())
             }
@@ -78,6 +144,23 @@ impl RoverPrinter for RoverError {
     }
 }
 
+/// Workflow commands go to stderr, which GitHub Actions reads them from too, so that stdout
+/// stays the same as with `--format plain` for scripts that capture it
+fn print_github_annotations(annotations: &[GithubAnnotation]) -> RoverResult<()> {
+    for annotation in annotations {
+        stderrln!("{}", annotation)?;
+    }
+    Ok(())
+}
+
+/// How long the command ran for, to time JUnit reports
+fn command_time() -> Option<Duration> {
+    timing::phase_timings()
+        .into_iter()
+        .find(|timing| timing.phase == "command")
+        .map(|timing| timing.duration)
+}
+
 /// The output expected by the channel used for OutputOpts
 pub enum OutputChannelKind {
     /// SDL as a String, often via introspection
@@ -94,6 +177,38 @@ pub struct OutputOpts {
     #[arg(long = "output", short = 'o', global = true, value_parser = Self::parse_absolute_path)]
     pub output_file: Option<Utf8PathBuf>,
 
+    /// Print how long each phase of the command took, such as authenticating, network requests,
+    /// and composition, to stderr once it's done.
+    ///
+    /// With `--format json`, the same timings are included in the output.
+    #[arg(long = "verbose", global = true)]
+    pub verbose: bool,
+
+    /// Print only the value at this path in Rover's JSON output, such as `.data.success`, which
+    /// implies `--format json`.
+    ///
+    /// Strings are printed without their quotes and everything else as JSON, so CI scripts can
+    /// gate on a single value without `jq`. The command fails if there is nothing at the path.
+    #[arg(long = "json-path", global = true, value_name = "PATH")]
+    #[serde(skip_serializing)]
+    pub json_path: Option<JsonPath>,
+
+    /// Don't print progress messages, headings, or warnings to stderr, only the command's output
+    /// and errors
+    #[arg(long = "quiet", short = 'q', global = true)]
+    pub quiet: bool,
+
+    /// Print only a minimal set of fields that stays the same across versions, for scripts, such
+    /// as the bare SDL of a fetched schema or `passed` and `failed` for checks. Implies `--quiet`.
+    ///
+    /// Commands without a porcelain format print their plain output.
+    #[arg(
+        long = "porcelain",
+        global = true,
+        conflicts_with_all = ["format_kind", "json_path"]
+    )]
+    pub porcelain: bool,
+
     #[arg(skip)]
     #[serde(skip_serializing)]
     pub channel: Option<UnboundedSender<OutputChannelKind>>,
@@ -107,6 +222,15 @@ impl OutputOpts {
         }
     }
 
+    /// Silences progress messages and warnings for `--quiet` and `--porcelain`, and progress bars
+    /// for output that's meant for machines too
+    pub fn set_quiet(&self) {
+        rover_std::print::set_quiet(self.quiet || self.porcelain);
+        rover_std::progress::set_enabled(
+            matches!(self.format_kind, RoverOutputFormatKind::Plain) && self.json_path.is_none(),
+        );
+    }
+
     /// Handle output and errors from a Rover command.
     pub fn handle_output<T>(&self, rover_command_output: T) -> RoverResult<()>
     where
@@ -115,6 +239,24 @@ impl OutputOpts {
         rover_command_output.write_or_print(self)
     }
 
+    /// Prints how long each phase of the command took when `--verbose` is passed
+    pub fn print_phase_timings(&self) -> io::Result<()> {
+        if !self.verbose {
+            return Ok(());
+        }
+        let mut table = table::get_table();
+        // bc => sets top row to be bold and center
+        table.add_row(row![bc => "Phase", "Time", "Count"]);
+        for timing in timing::phase_timings() {
+            table.add_row(row![
+                timing.phase,
+                format!("{:.2?}", timing.duration),
+                timing.count
+            ]);
+        }
+        stderrln!("{}", table)
+    }
+
     /// Handle the parsing of output file to ensure we get an absolute path every time
     pub fn parse_absolute_path(path_input: &str) -> Result<Utf8PathBuf, clap::Error> {
         let starter = Utf8PathBuf::from(path_input);
@@ -128,6 +270,26 @@ pub struct JsonOutput {
     json_version: JsonVersion,
     data: JsonData,
     error: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timings: Option<Vec<JsonPhaseTiming>>,
+}
+
+/// How long one phase of the command took, for `--verbose`
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+struct JsonPhaseTiming {
+    phase: &'static str,
+    duration_ms: u128,
+    count: u32,
+}
+
+impl From<PhaseTiming> for JsonPhaseTiming {
+    fn from(timing: PhaseTiming) -> Self {
+        JsonPhaseTiming {
+            phase: timing.phase,
+            duration_ms: timing.duration.as_millis(),
+            count: timing.count,
+        }
+    }
 }
 
 impl JsonOutput {
@@ -136,6 +298,7 @@ impl JsonOutput {
             json_version,
             data: JsonData::success(data),
             error,
+            timings: None,
         }
     }
 
@@ -144,11 +307,24 @@ impl JsonOutput {
             json_version,
             data: JsonData::failure(data),
             error,
+            timings: None,
         }
     }
 
-    fn print(&self) -> io::Result<()> {
-        stdoutln!("{}", self)
+    /// Includes every phase timed so far when `verbose` is set
+    fn with_phase_timings(self, verbose: bool) -> JsonOutput {
+        if !verbose {
+            return self;
+        }
+        JsonOutput {
+            timings: Some(
+                timing::phase_timings()
+                    .into_iter()
+                    .map(JsonPhaseTiming::from)
+                    .collect(),
+            ),
+            ..self
+        }
     }
 }
 
@@ -205,3 +381,34 @@ pub enum JsonVersion {
     #[serde(rename = "2")]
     Two,
 }
+
+#[cfg(test)]
+mod tests {
+    use rover_std::timing;
+    use serde_json::json;
+    use speculoos::prelude::*;
+
+    use super::JsonOutput;
+    use crate::RoverOutput;
+
+    #[test]
+    fn test_phase_timings_are_only_included_when_verbose() {
+        drop(timing::time_phase("auth"));
+
+        let quiet = serde_json::to_value(JsonOutput::from(RoverOutput::EmptySuccess)).unwrap();
+        assert_that!(quiet.get("timings")).is_none();
+
+        let verbose = serde_json::to_value(
+            JsonOutput::from(RoverOutput::EmptySuccess).with_phase_timings(true),
+        )
+        .unwrap();
+        let auth = verbose["timings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|timing| timing["phase"] == json!("auth"))
+            .unwrap();
+        assert_that!(auth["count"].as_u64()).is_some();
+        assert_that!(auth["duration_ms"].as_u64()).is_some();
+    }
+}
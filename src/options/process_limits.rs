@@ -0,0 +1,24 @@
+use clap::Parser;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Parser)]
+pub struct ProcessLimitOpts {
+    /// The most resident memory, in megabytes, that a process `rover dev` starts may use.
+    ///
+    /// Processes over the limit are killed, and `rover dev` reports which one it was.
+    #[arg(long = "max-process-memory", value_name = "MEGABYTES")]
+    #[serde(skip_serializing)]
+    pub max_process_memory: Option<u64>,
+
+    /// The most CPU time, in seconds, that a process `rover dev` starts may use before it's
+    /// stopped. Only enforced on Linux and macOS.
+    #[arg(long = "max-process-cpu", value_name = "SECONDS")]
+    #[serde(skip_serializing)]
+    pub max_process_cpu: Option<u64>,
+
+    /// The most files and sockets that a process `rover dev` starts may have open at once. Only
+    /// enforced on Linux and macOS.
+    #[arg(long = "max-open-files", value_name = "COUNT")]
+    #[serde(skip_serializing)]
+    pub max_open_files: Option<u64>,
+}
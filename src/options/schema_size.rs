@@ -0,0 +1,234 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{anyhow, bail};
+use apollo_federation_types::config::{SchemaSource, SupergraphConfig};
+use clap::Parser;
+use serde::Serialize;
+
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Parser)]
+pub struct SchemaSizeOpts {
+    /// The largest SDL that any one subgraph can have when composing, such as `512KB` or `2MB`.
+    ///
+    /// Sizes without a unit are in bytes, and `KB`, `MB`, and `GB` are multiples of 1024.
+    #[arg(long = "max-schema-size", value_name = "SIZE")]
+    #[serde(skip_serializing)]
+    pub max_schema_size: Option<SchemaSize>,
+
+    /// The largest combined SDL of every subgraph when composing, such as `20MB`.
+    ///
+    /// Sizes without a unit are in bytes, and `KB`, `MB`, and `GB` are multiples of 1024.
+    #[arg(long = "max-total-schema-size", value_name = "SIZE")]
+    #[serde(skip_serializing)]
+    pub max_total_schema_size: Option<SchemaSize>,
+}
+
+impl SchemaSizeOpts {
+    /// Measures the SDL of every subgraph in a resolved supergraph config, failing when a subgraph
+    /// or all of them together are over the configured limits
+    pub fn check(&self, supergraph_config: &SupergraphConfig) -> RoverResult<()> {
+        let sizes: Vec<(String, SchemaSize)> = supergraph_config
+            .clone()
+            .into_iter()
+            .filter_map(|(name, subgraph_config)| match subgraph_config.schema {
+                SchemaSource::Sdl { sdl } => Some((name, SchemaSize(sdl.len() as u64))),
+                _ => None,
+            })
+            .collect();
+        let total = SchemaSize(sizes.iter().map(|(_, size)| size.0).sum());
+        for (name, size) in &sizes {
+            tracing::info!("subgraph `{}` has a schema of {}", name, size);
+        }
+        tracing::info!("composing {} of subgraph schemas", total);
+
+        let mut problems = Vec::new();
+        if let Some(max_schema_size) = self.max_schema_size {
+            problems.extend(
+                sizes
+                    .iter()
+                    .filter(|(_, size)| *size > max_schema_size)
+                    .map(|(name, size)| {
+                        format!(
+                            "subgraph `{}` has a schema of {}, over the {} allowed by `--max-schema-size`",
+                            name, size, max_schema_size
+                        )
+                    }),
+            );
+        }
+        if let Some(max_total_schema_size) = self.max_total_schema_size {
+            if total > max_total_schema_size {
+                problems.push(format!(
+                    "the {} subgraph schemas add up to {}, over the {} allowed by `--max-total-schema-size`",
+                    sizes.len(),
+                    total,
+                    max_total_schema_size
+                ));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            let mut err = RoverError::new(anyhow!(
+                "Refusing to compose schemas that are too large:\n  {}",
+                problems.join("\n  ")
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Check whether these schemas were generated as expected, or raise the limits if they're supposed to be this large.".to_string(),
+            ));
+            Err(err)
+        }
+    }
+}
+
+/// A schema size in bytes, parsed from values such as `4096`, `512KB`, or `2MB`
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Serialize)]
+pub struct SchemaSize(u64);
+
+impl SchemaSize {
+    const UNITS: [(&'static str, u64); 4] = [
+        ("GB", 1024 * 1024 * 1024),
+        ("MB", 1024 * 1024),
+        ("KB", 1024),
+        ("B", 1),
+    ];
+
+    pub fn bytes(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for SchemaSize {
+    fn from(bytes: u64) -> Self {
+        SchemaSize(bytes)
+    }
+}
+
+impl FromStr for SchemaSize {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let trimmed = input.trim();
+        let upper = trimmed.to_ascii_uppercase();
+        let (number, multiplier) = Self::UNITS
+            .iter()
+            .find_map(|(unit, multiplier)| {
+                upper
+                    .strip_suffix(unit)
+                    .map(|number| (number.trim(), *multiplier))
+            })
+            .unwrap_or((trimmed, 1));
+        let number: u64 = match number.parse() {
+            Ok(number) => number,
+            Err(_) => bail!(
+                "`{}` is not a size; use a number of bytes, optionally followed by `KB`, `MB`, or `GB`",
+                input
+            ),
+        };
+        number
+            .checked_mul(multiplier)
+            .map(SchemaSize)
+            .ok_or_else(|| anyhow!("`{}` is too large", input))
+    }
+}
+
+impl fmt::Display for SchemaSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match Self::UNITS
+            .iter()
+            .find(|(_, multiplier)| *multiplier > 1 && self.0 >= *multiplier)
+        {
+            Some((unit, multiplier)) => {
+                write!(
+                    f,
+                    "{:.1} {} ({} bytes)",
+                    self.0 as f64 / *multiplier as f64,
+                    unit,
+                    self.0
+                )
+            }
+            None => write!(f, "{} bytes", self.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use apollo_federation_types::config::SubgraphConfig;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case::bytes("4096", 4096)]
+    #[case::kilobytes("512KB", 512 * 1024)]
+    #[case::megabytes_lowercase("2mb", 2 * 1024 * 1024)]
+    #[case::spaced("1 GB", 1024 * 1024 * 1024)]
+    #[case::explicit_bytes("10B", 10)]
+    fn test_parse_schema_size(#[case] input: &str, #[case] expected: u64) {
+        assert_that!(input.parse::<SchemaSize>())
+            .is_ok()
+            .is_equal_to(SchemaSize(expected));
+    }
+
+    #[rstest]
+    #[case::no_number("MB")]
+    #[case::unknown_unit("2TB")]
+    #[case::negative("-1")]
+    fn test_parse_schema_size_rejects_invalid_sizes(#[case] input: &str) {
+        assert_that!(input.parse::<SchemaSize>()).is_err();
+    }
+
+    #[test]
+    fn test_display_schema_size() {
+        assert_that!(SchemaSize(512).to_string()).is_equal_to("512 bytes".to_string());
+        assert_that!(SchemaSize(1536).to_string()).is_equal_to("1.5 KB (1536 bytes)".to_string());
+    }
+
+    fn supergraph_config(sdls: &[(&str, usize)]) -> SupergraphConfig {
+        SupergraphConfig::new(
+            sdls.iter()
+                .map(|(name, len)| {
+                    (
+                        name.to_string(),
+                        SubgraphConfig {
+                            routing_url: None,
+                            schema: SchemaSource::Sdl {
+                                sdl: "x".repeat(*len),
+                            },
+                        },
+                    )
+                })
+                .collect::<BTreeMap<_, _>>(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_check_names_oversized_subgraphs() {
+        let opts = SchemaSizeOpts {
+            max_schema_size: Some(SchemaSize(100)),
+            max_total_schema_size: None,
+        };
+        let result = opts.check(&supergraph_config(&[("products", 150), ("reviews", 50)]));
+        let message = assert_that!(result).is_err().subject.to_string();
+        assert_that!(message).contains("subgraph `products` has a schema of 150 bytes");
+        assert_that!(message).does_not_contain("reviews");
+    }
+
+    #[test]
+    fn test_check_total_size() {
+        let opts = SchemaSizeOpts {
+            max_schema_size: Some(SchemaSize(100)),
+            max_total_schema_size: Some(SchemaSize(150)),
+        };
+        assert_that!(opts.check(&supergraph_config(&[("products", 100), ("reviews", 50)]))).is_ok();
+        let result = opts.check(&supergraph_config(&[("products", 100), ("reviews", 60)]));
+        assert_that!(result.unwrap_err().to_string())
+            .contains("the 2 subgraph schemas add up to 160 bytes");
+    }
+}
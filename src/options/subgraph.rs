@@ -1,22 +1,22 @@
-#[cfg(not(feature = "dev-next"))]
+#[cfg(feature = "composition-js")]
 use std::io::{self, IsTerminal};
 
-#[cfg(all(feature = "composition-js", not(feature = "dev-next")))]
+#[cfg(feature = "composition-js")]
 use anyhow::{Context, Result};
 use camino::Utf8PathBuf;
 use clap::{self, Parser};
-#[cfg(all(feature = "composition-js", not(feature = "dev-next")))]
+#[cfg(feature = "composition-js")]
 use clap::{error::ErrorKind as ClapErrorKind, CommandFactory};
-#[cfg(all(feature = "composition-js", not(feature = "dev-next")))]
+#[cfg(feature = "composition-js")]
 use dialoguer::Input;
-#[cfg(all(feature = "composition-js", not(feature = "dev-next")))]
+#[cfg(feature = "composition-js")]
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 
-#[cfg(all(feature = "composition-js", not(feature = "dev-next")))]
+#[cfg(feature = "composition-js")]
 use rover_std::{Fs, Style};
 
-#[cfg(all(feature = "composition-js", not(feature = "dev-next")))]
+#[cfg(feature = "composition-js")]
 use crate::cli::Rover;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Parser)]
@@ -70,7 +70,7 @@ pub struct OptionalSubgraphOpts {
     pub subgraph_retries: u64,
 }
 
-#[cfg(all(feature = "composition-js", not(feature = "dev-next")))]
+#[cfg(feature = "composition-js")]
 impl OptionalSubgraphOpts {
     pub fn prompt_for_name(&self) -> Result<String> {
         if let Some(name) = &self.subgraph_name {
@@ -159,6 +159,11 @@ impl OptionalSubgraphOpts {
         }
     }
 
+    /// Whether the subgraph's schema is introspected from `--url` rather than read from `--schema`
+    pub fn introspects_schema(&self) -> bool {
+        self.subgraph_schema_path.is_none()
+    }
+
     fn maybe_name_from_dir() -> Option<String> {
         std::env::current_dir()
             .ok()
@@ -9,7 +9,7 @@ use dialoguer::Select;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use rover_std::Fs;
+use rover_std::{progressln, Fs};
 
 use crate::command::template::queries::{get_templates_for_language, list_templates_for_language};
 use crate::{RoverError, RoverResult};
@@ -54,7 +54,7 @@ pub(crate) async fn extract_tarball(
     let file_name = format!("{}.tar.gz", template_path);
     let tarball_path = download_dir_path.join(file_name);
     let mut f = std::fs::File::create(&tarball_path)?;
-    eprintln!("Downloading from {}", &download_url);
+    progressln!("Downloading from {}", &download_url);
     let response_bytes = client
         .get(download_url)
         .header(reqwest::header::USER_AGENT, "rover-client")
@@ -0,0 +1,414 @@
+//! Runs an organization's own schema policies, kept in the project's `.apollo/policies`
+//! directory, over a schema file for `lint` and `check` with `--local-policies`.
+//!
+//! A policy is a program in any language. It's run with the project's root as its working
+//! directory, and is given a JSON document on stdin with the file's name and its
+//! [`PolicySchema`]:
+//!
+//! ```json
+//! { "version": 1, "file_name": "products.graphql", "schema": { "types": [...] } }
+//! ```
+//!
+//! It prints the violations it finds as JSON on stdout, and exits successfully even when it finds
+//! some. `level` is `ERROR` or `WARNING` and defaults to `ERROR`, and `coordinate` points at the
+//! element the violation is about, like `Query.user(id:)`:
+//!
+//! ```json
+//! { "violations": [{ "rule": "NO_PUBLIC_IDS", "message": "...", "coordinate": "User.id" }] }
+//! ```
+//!
+//! Rhai scripts and WASM modules need a runtime embedded in Rover, which isn't part of this build,
+//! so they're reported instead of being silently skipped.
+
+pub(crate) mod schema;
+
+use std::io::{self, Write};
+use std::process::{Command, ExitStatus, Stdio};
+
+use anyhow::anyhow;
+use camino::{Utf8Path, Utf8PathBuf};
+use rover_client::shared::{Diagnostic, LintResponse};
+use rover_client::RoverClientError;
+use rover_std::{print::is_quiet, Fs, RoverStdError};
+use serde::{Deserialize, Serialize};
+
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+use self::schema::PolicySchema;
+
+/// Where policies are kept, relative to the project's root
+const POLICIES_DIR: &str = ".apollo/policies";
+
+/// The version of the JSON that policies are given, which changes when that JSON changes in a way
+/// that would break existing policies
+const PROTOCOL_VERSION: u32 = 1;
+
+#[derive(thiserror::Error, Debug)]
+pub enum PolicyError {
+    #[error("Could not read the policies in {path}: {source}")]
+    ReadDir {
+        path: Utf8PathBuf,
+        source: RoverStdError,
+    },
+    #[error("{path} is a {runtime} policy, but this build of Rover can't run {runtime} policies.")]
+    UnsupportedRuntime {
+        path: Utf8PathBuf,
+        runtime: &'static str,
+    },
+    #[error("Could not run the policy {path}: {source}")]
+    Spawn {
+        path: Utf8PathBuf,
+        source: io::Error,
+    },
+    #[error("The policy {path} exited with {status}:\n{stderr}")]
+    Failed {
+        path: Utf8PathBuf,
+        status: ExitStatus,
+        stderr: String,
+    },
+    #[error("The policy {path} printed output that isn't a list of violations: {source}")]
+    InvalidOutput {
+        path: Utf8PathBuf,
+        source: serde_json::Error,
+    },
+}
+
+#[derive(Serialize)]
+struct PolicyInput<'a> {
+    version: u32,
+    file_name: &'a str,
+    schema: &'a PolicySchema,
+}
+
+#[derive(Deserialize)]
+struct PolicyOutput {
+    #[serde(default)]
+    violations: Vec<Violation>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum ViolationLevel {
+    #[default]
+    Error,
+    Warning,
+}
+
+impl ViolationLevel {
+    /// The level as Studio's linter reports it, so local and remote diagnostics look the same
+    fn as_lint_level(&self) -> &'static str {
+        match self {
+            ViolationLevel::Error => "ERROR",
+            ViolationLevel::Warning => "WARNING",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+struct Violation {
+    rule: String,
+    #[serde(default)]
+    level: ViolationLevel,
+    message: String,
+    coordinate: Option<String>,
+}
+
+/// The policies of a project, in the order their file names sort in
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LocalPolicies {
+    root: Utf8PathBuf,
+    policies: Vec<Utf8PathBuf>,
+}
+
+impl LocalPolicies {
+    /// Loads the policies of the project the current directory is in. It's an error for there
+    /// to be none, since `--local-policies` was passed to run them
+    pub(crate) fn for_current_dir() -> RoverResult<Self> {
+        let current_dir = Utf8PathBuf::try_from(std::env::current_dir()?)?;
+        let Some(dir) = find_policies_dir(&current_dir) else {
+            let mut err = RoverError::new(anyhow!(
+                "--local-policies was passed, but there's no {} directory in {} or any directory above it.",
+                POLICIES_DIR,
+                current_dir
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Add your policies to {} at the root of your project.",
+                POLICIES_DIR
+            )));
+            return Err(err);
+        };
+        Ok(Self::load(&dir)?)
+    }
+
+    pub(crate) fn load(dir: &Utf8Path) -> Result<Self, PolicyError> {
+        let mut policies = Vec::new();
+        let entries = Fs::get_dir_entries(dir).map_err(|source| PolicyError::ReadDir {
+            path: dir.to_path_buf(),
+            source,
+        })?;
+        for entry in entries.flatten() {
+            let path = entry.path().to_path_buf();
+            if path.is_dir() || entry.file_name().starts_with('.') {
+                continue;
+            }
+            match path.extension() {
+                Some("rhai") => {
+                    return Err(PolicyError::UnsupportedRuntime {
+                        path,
+                        runtime: "Rhai",
+                    })
+                }
+                Some("wasm") => {
+                    return Err(PolicyError::UnsupportedRuntime {
+                        path,
+                        runtime: "WASM",
+                    })
+                }
+                _ if is_executable(&path) => policies.push(path),
+                _ => tracing::debug!("skipping {}, which isn't executable", path),
+            }
+        }
+        policies.sort();
+        let root = dir
+            .parent()
+            .and_then(Utf8Path::parent)
+            .unwrap_or(dir)
+            .to_path_buf();
+        Ok(Self { root, policies })
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+
+    /// Runs every policy over `sdl`, returning what they found as lint diagnostics. Rules are
+    /// named after the policy that reported them, like `naming/NO_PUBLIC_IDS`
+    pub(crate) fn evaluate(&self, file_name: &str, sdl: &str) -> RoverResult<Vec<Diagnostic>> {
+        let schema = PolicySchema::parse(sdl)?;
+        let input = serde_json::to_vec(&PolicyInput {
+            version: PROTOCOL_VERSION,
+            file_name,
+            schema: &schema,
+        })?;
+
+        let mut diagnostics = Vec::new();
+        for policy in &self.policies {
+            let policy_name = policy.file_stem().unwrap_or(policy.as_str());
+            for violation in self.run(policy, &input)? {
+                let location = violation
+                    .coordinate
+                    .as_deref()
+                    .and_then(|coordinate| schema.locate(coordinate))
+                    .unwrap_or_default();
+                diagnostics.push(Diagnostic {
+                    level: violation.level.as_lint_level().to_string(),
+                    message: violation.message,
+                    coordinate: violation.coordinate.unwrap_or_default(),
+                    start_line: location.line as i64,
+                    start_byte_offset: location.start_byte_offset,
+                    end_byte_offset: location.end_byte_offset,
+                    rule: format!("{}/{}", policy_name, violation.rule),
+                });
+            }
+        }
+        Ok(diagnostics)
+    }
+
+    fn run(&self, policy: &Utf8Path, input: &[u8]) -> Result<Vec<Violation>, PolicyError> {
+        let spawn_error = |source| PolicyError::Spawn {
+            path: policy.to_path_buf(),
+            source,
+        };
+        let mut child = Command::new(policy)
+            .current_dir(&self.root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(spawn_error)?;
+
+        // written from another thread so a policy that prints before it's done reading can't
+        // block on a full pipe. Policies that don't read the schema at all close stdin early,
+        // which isn't an error
+        let stdin = child.stdin.take();
+        let input = input.to_vec();
+        let writer = std::thread::spawn(move || {
+            if let Some(mut stdin) = stdin {
+                let _ = stdin.write_all(&input);
+            }
+        });
+        let output = child.wait_with_output().map_err(spawn_error)?;
+        let _ = writer.join();
+
+        if !output.status.success() {
+            return Err(PolicyError::Failed {
+                path: policy.to_path_buf(),
+                status: output.status,
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        let output: PolicyOutput = serde_json::from_slice(&output.stdout).map_err(|source| {
+            PolicyError::InvalidOutput {
+                path: policy.to_path_buf(),
+                source,
+            }
+        })?;
+        Ok(output.violations)
+    }
+
+    /// Adds what the policies find in the linted schema to the result of linting it in Studio,
+    /// failing when either found errors
+    pub(crate) fn lint(
+        &self,
+        result: Result<LintResponse, RoverClientError>,
+    ) -> RoverResult<LintResponse> {
+        let mut lint_response = match result {
+            Ok(lint_response) | Err(RoverClientError::LintFailures { lint_response }) => {
+                lint_response
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let diagnostics =
+            self.evaluate(&lint_response.file_name, &lint_response.proposed_schema)?;
+        lint_response.diagnostics.extend(diagnostics);
+        if has_errors(&lint_response.diagnostics) {
+            Err(RoverClientError::LintFailures { lint_response }.into())
+        } else {
+            Ok(lint_response)
+        }
+    }
+
+    /// Runs the policies before a schema is checked, so that a schema that breaks them fails
+    /// without being sent to Studio. Warnings are printed, and don't stop the check
+    pub(crate) fn check(&self, file_name: &str, sdl: &str) -> RoverResult<()> {
+        let lint_response = LintResponse {
+            diagnostics: self.evaluate(file_name, sdl)?,
+            file_name: file_name.to_string(),
+            proposed_schema: sdl.to_string(),
+        };
+        if has_errors(&lint_response.diagnostics) {
+            return Err(RoverClientError::LintFailures { lint_response }.into());
+        }
+        if !lint_response.diagnostics.is_empty() && !is_quiet() {
+            eprint!("{}", lint_response.get_ariadne()?);
+        }
+        Ok(())
+    }
+}
+
+fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.level == "ERROR")
+}
+
+/// Finds the policies directory of the project `dir` is in
+fn find_policies_dir(dir: &Utf8Path) -> Option<Utf8PathBuf> {
+    dir.ancestors()
+        .map(|ancestor| ancestor.join(POLICIES_DIR))
+        .find(|policies_dir| policies_dir.is_dir())
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Utf8Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|metadata| metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Utf8Path) -> bool {
+    matches!(path.extension(), Some("exe" | "bat" | "cmd"))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use assert_fs::TempDir;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    const SCHEMA: &str = "type Query {\n  me: User\n}\n\ntype User {\n  id: ID!\n}\n";
+
+    fn policies_dir(policies: &[(&str, &str)]) -> (TempDir, Utf8PathBuf) {
+        let temp = TempDir::new().unwrap();
+        let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+        let dir = root.join(POLICIES_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, script) in policies {
+            let path = dir.join(name);
+            std::fs::write(&path, script).unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        (temp, dir)
+    }
+
+    #[test]
+    fn test_violations_become_located_diagnostics() {
+        let (_temp, dir) = policies_dir(&[(
+            "naming",
+            r#"#!/bin/sh
+cat > /dev/null
+echo '{"violations": [{"rule": "NO_IDS", "message": "ids are private", "coordinate": "User.id"}, {"rule": "ROOT", "level": "WARNING", "message": "hi"}]}'
+"#,
+        )]);
+        let policies = LocalPolicies::load(&dir).unwrap();
+        let diagnostics = policies.evaluate("schema.graphql", SCHEMA).unwrap();
+
+        assert_that!(diagnostics).has_length(2);
+        assert_that!(diagnostics[0].rule).is_equal_to("naming/NO_IDS".to_string());
+        assert_that!(diagnostics[0].level).is_equal_to("ERROR".to_string());
+        assert_that!(diagnostics[0].start_line).is_equal_to(6);
+        let range = diagnostics[0].start_byte_offset..diagnostics[0].end_byte_offset;
+        assert_that!(SCHEMA[range].to_string()).is_equal_to("id: ID!".to_string());
+        assert_that!(diagnostics[1].level).is_equal_to("WARNING".to_string());
+        assert_that!(diagnostics[1].start_line).is_equal_to(0);
+    }
+
+    #[test]
+    fn test_policies_are_given_the_schema() {
+        // fails unless the input names the file and has the `User` type
+        let (_temp, dir) = policies_dir(&[(
+            "input",
+            r#"#!/bin/sh
+input=$(cat)
+case "$input" in
+  *'"file_name":"schema.graphql"'*'"name":"User"'*) echo '{"violations": []}' ;;
+  *) echo "unexpected input: $input" >&2; exit 1 ;;
+esac
+"#,
+        )]);
+        let policies = LocalPolicies::load(&dir).unwrap();
+        assert_that!(policies.evaluate("schema.graphql", SCHEMA)).is_ok();
+    }
+
+    #[test]
+    fn test_failing_policies_are_errors() {
+        let (_temp, dir) = policies_dir(&[(
+            "broken",
+            "#!/bin/sh\necho 'something went wrong' >&2\nexit 3\n",
+        )]);
+        let policies = LocalPolicies::load(&dir).unwrap();
+        let err = policies.evaluate("schema.graphql", SCHEMA).unwrap_err();
+        assert_that!(err.to_string()).contains("something went wrong");
+    }
+
+    #[test]
+    fn test_scripts_without_a_runtime_are_reported() {
+        let (_temp, dir) = policies_dir(&[("naming.rhai", "fn check(schema) {}")]);
+        assert_that!(LocalPolicies::load(&dir))
+            .is_err()
+            .matches(|err| {
+                matches!(
+                    err,
+                    PolicyError::UnsupportedRuntime {
+                        runtime: "Rhai",
+                        ..
+                    }
+                )
+            });
+    }
+}
@@ -0,0 +1,520 @@
+//! The schema that policies are given: every type and field of a schema file, with descriptions,
+//! directive applications, and where each is in the file, so rules can be written without parsing
+//! GraphQL themselves
+
+use std::collections::BTreeMap;
+
+use apollo_parser::{cst, cst::CstNode, Parser};
+use itertools::Itertools;
+use serde::Serialize;
+
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+#[error("Failed to parse the schema:\n{}", .errors.iter().join("\n"))]
+pub struct PolicySchemaError {
+    errors: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TypeKind {
+    Object,
+    Interface,
+    Union,
+    Enum,
+    InputObject,
+    Scalar,
+}
+
+/// Where an element is in the schema file. Lines start at 1
+#[derive(Serialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Location {
+    pub line: usize,
+    pub start_byte_offset: usize,
+    pub end_byte_offset: usize,
+}
+
+/// A directive applied to an element, with its arguments as they're written
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct DirectiveApplication {
+    pub name: String,
+    pub arguments: BTreeMap<String, String>,
+}
+
+/// An argument, input field, or enum value
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct ValueDefinition {
+    pub name: String,
+    pub coordinate: String,
+    /// The type as it's written, like `[ID!]!`. Enum values don't have one
+    #[serde(rename = "type")]
+    pub ty: Option<String>,
+    pub default_value: Option<String>,
+    pub description: Option<String>,
+    pub directives: Vec<DirectiveApplication>,
+    pub location: Location,
+}
+
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct FieldDefinition {
+    pub name: String,
+    pub coordinate: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub description: Option<String>,
+    pub arguments: Vec<ValueDefinition>,
+    pub directives: Vec<DirectiveApplication>,
+    pub location: Location,
+}
+
+/// A type definition or extension. A type that's extended is listed once for its definition and
+/// once for each extension
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct TypeDefinition {
+    pub name: String,
+    pub kind: TypeKind,
+    pub is_extension: bool,
+    pub description: Option<String>,
+    pub directives: Vec<DirectiveApplication>,
+    /// The interfaces an object or interface implements
+    pub interfaces: Vec<String>,
+    /// The fields of an object or interface
+    pub fields: Vec<FieldDefinition>,
+    /// The fields of an input object, or the values of an enum
+    pub values: Vec<ValueDefinition>,
+    /// The members of a union
+    pub members: Vec<String>,
+    pub location: Location,
+}
+
+/// Every type in a schema file, in the order they're written
+#[derive(Serialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct PolicySchema {
+    pub types: Vec<TypeDefinition>,
+}
+
+impl PolicySchema {
+    pub fn parse(sdl: &str) -> Result<Self, PolicySchemaError> {
+        let parsed = Parser::new(sdl).parse();
+        if parsed.errors().len() > 0 {
+            return Err(PolicySchemaError {
+                errors: parsed
+                    .errors()
+                    .map(|err| err.message().to_string())
+                    .collect(),
+            });
+        }
+
+        let lines = LineIndex::new(sdl);
+        let mut types = Vec::new();
+        for definition in parsed.document().definitions() {
+            use cst::Definition::*;
+            let location = lines.locate(definition.syntax());
+            let ty = match definition {
+                ObjectTypeDefinition(def) => TypeBuilder::new(def.name(), TypeKind::Object)
+                    .description(def.description())
+                    .directives(def.directives())
+                    .interfaces(def.implements_interfaces())
+                    .fields(def.fields_definition(), &lines),
+                ObjectTypeExtension(def) => TypeBuilder::new(def.name(), TypeKind::Object)
+                    .extension()
+                    .directives(def.directives())
+                    .interfaces(def.implements_interfaces())
+                    .fields(def.fields_definition(), &lines),
+                InterfaceTypeDefinition(def) => TypeBuilder::new(def.name(), TypeKind::Interface)
+                    .description(def.description())
+                    .directives(def.directives())
+                    .interfaces(def.implements_interfaces())
+                    .fields(def.fields_definition(), &lines),
+                InterfaceTypeExtension(def) => TypeBuilder::new(def.name(), TypeKind::Interface)
+                    .extension()
+                    .directives(def.directives())
+                    .interfaces(def.implements_interfaces())
+                    .fields(def.fields_definition(), &lines),
+                InputObjectTypeDefinition(def) => {
+                    let input_fields = def
+                        .input_fields_definition()
+                        .into_iter()
+                        .flat_map(|def| def.input_value_definitions());
+                    TypeBuilder::new(def.name(), TypeKind::InputObject)
+                        .description(def.description())
+                        .directives(def.directives())
+                        .input_values(input_fields, &lines)
+                }
+                InputObjectTypeExtension(def) => {
+                    let input_fields = def
+                        .input_fields_definition()
+                        .into_iter()
+                        .flat_map(|def| def.input_value_definitions());
+                    TypeBuilder::new(def.name(), TypeKind::InputObject)
+                        .extension()
+                        .directives(def.directives())
+                        .input_values(input_fields, &lines)
+                }
+                EnumTypeDefinition(def) => TypeBuilder::new(def.name(), TypeKind::Enum)
+                    .description(def.description())
+                    .directives(def.directives())
+                    .enum_values(def.enum_values_definition(), &lines),
+                EnumTypeExtension(def) => TypeBuilder::new(def.name(), TypeKind::Enum)
+                    .extension()
+                    .directives(def.directives())
+                    .enum_values(def.enum_values_definition(), &lines),
+                UnionTypeDefinition(def) => TypeBuilder::new(def.name(), TypeKind::Union)
+                    .description(def.description())
+                    .directives(def.directives())
+                    .members(def.union_member_types()),
+                UnionTypeExtension(def) => TypeBuilder::new(def.name(), TypeKind::Union)
+                    .extension()
+                    .directives(def.directives())
+                    .members(def.union_member_types()),
+                ScalarTypeDefinition(def) => TypeBuilder::new(def.name(), TypeKind::Scalar)
+                    .description(def.description())
+                    .directives(def.directives()),
+                ScalarTypeExtension(def) => TypeBuilder::new(def.name(), TypeKind::Scalar)
+                    .extension()
+                    .directives(def.directives()),
+                _ => continue,
+            };
+            if let Some(ty) = ty.build(location) {
+                types.push(ty);
+            }
+        }
+        Ok(Self { types })
+    }
+
+    /// Where the element at `coordinate` is, like `Query`, `Query.me`, or `Query.user(id:)`
+    pub fn locate(&self, coordinate: &str) -> Option<Location> {
+        let (type_name, member) = match coordinate.split_once('.') {
+            Some((type_name, member)) => (type_name, Some(member)),
+            None => (coordinate, None),
+        };
+        let mut types = self.types.iter().filter(|ty| ty.name == type_name);
+        let Some(member) = member else {
+            return types.next().map(|ty| ty.location);
+        };
+        let (member, argument) = match member.split_once('(') {
+            Some((field, argument)) => (field, Some(argument.trim_end_matches(&[':', ')'][..]))),
+            None => (member, None),
+        };
+        types.find_map(|ty| {
+            if let Some(field) = ty.fields.iter().find(|field| field.name == member) {
+                return match argument {
+                    Some(argument) => field
+                        .arguments
+                        .iter()
+                        .find(|arg| arg.name == argument)
+                        .map(|arg| arg.location),
+                    None => Some(field.location),
+                };
+            }
+            ty.values
+                .iter()
+                .find(|value| value.name == member)
+                .map(|value| value.location)
+        })
+    }
+}
+
+/// Maps byte offsets to line numbers
+struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(sdl: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(sdl.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+        Self { line_starts }
+    }
+
+    fn locate(&self, node: &apollo_parser::SyntaxNode) -> Location {
+        let range = node.text_range();
+        let start_byte_offset: usize = range.start().into();
+        let line = match self.line_starts.binary_search(&start_byte_offset) {
+            Ok(line) => line + 1,
+            Err(line) => line,
+        };
+        Location {
+            line,
+            start_byte_offset,
+            end_byte_offset: range.end().into(),
+        }
+    }
+}
+
+struct TypeBuilder {
+    name: Option<String>,
+    kind: TypeKind,
+    is_extension: bool,
+    description: Option<String>,
+    directives: Vec<DirectiveApplication>,
+    interfaces: Vec<String>,
+    fields: Vec<FieldDefinition>,
+    values: Vec<ValueDefinition>,
+    members: Vec<String>,
+}
+
+impl TypeBuilder {
+    fn new(name: Option<cst::Name>, kind: TypeKind) -> Self {
+        Self {
+            name: name.map(|name| name.text().to_string()),
+            kind,
+            is_extension: false,
+            description: None,
+            directives: Vec::new(),
+            interfaces: Vec::new(),
+            fields: Vec::new(),
+            values: Vec::new(),
+            members: Vec::new(),
+        }
+    }
+
+    fn extension(self) -> Self {
+        Self {
+            is_extension: true,
+            ..self
+        }
+    }
+
+    fn description(self, description: Option<cst::Description>) -> Self {
+        Self {
+            description: render_description(description),
+            ..self
+        }
+    }
+
+    fn directives(self, directives: Option<cst::Directives>) -> Self {
+        Self {
+            directives: render_directives(directives),
+            ..self
+        }
+    }
+
+    fn interfaces(self, interfaces: Option<cst::ImplementsInterfaces>) -> Self {
+        let interfaces = interfaces
+            .iter()
+            .flat_map(|interfaces| interfaces.named_types())
+            .filter_map(|ty| Some(ty.name()?.text().to_string()))
+            .collect();
+        Self { interfaces, ..self }
+    }
+
+    fn fields(mut self, fields: Option<cst::FieldsDefinition>, lines: &LineIndex) -> Self {
+        let Some(type_name) = self.name.clone() else {
+            return self;
+        };
+        self.fields = fields
+            .iter()
+            .flat_map(|fields| fields.field_definitions())
+            .filter_map(|field| {
+                let name = field.name()?.text().to_string();
+                let coordinate = format!("{}.{}", type_name, name);
+                let arguments = field
+                    .arguments_definition()
+                    .iter()
+                    .flat_map(|args| args.input_value_definitions())
+                    .filter_map(|arg| {
+                        let arg_name = arg.name()?.text().to_string();
+                        value_definition(arg, format!("{}({}:)", coordinate, arg_name), lines)
+                    })
+                    .collect();
+                Some(FieldDefinition {
+                    name,
+                    ty: render_type(field.ty())?,
+                    description: render_description(field.description()),
+                    arguments,
+                    directives: render_directives(field.directives()),
+                    location: lines.locate(field.syntax()),
+                    coordinate,
+                })
+            })
+            .collect();
+        self
+    }
+
+    fn input_values(
+        mut self,
+        values: impl Iterator<Item = cst::InputValueDefinition>,
+        lines: &LineIndex,
+    ) -> Self {
+        let Some(type_name) = self.name.clone() else {
+            return self;
+        };
+        self.values = values
+            .filter_map(|value| {
+                let name = value.name()?.text().to_string();
+                value_definition(value, format!("{}.{}", type_name, name), lines)
+            })
+            .collect();
+        self
+    }
+
+    fn enum_values(mut self, values: Option<cst::EnumValuesDefinition>, lines: &LineIndex) -> Self {
+        let Some(type_name) = self.name.clone() else {
+            return self;
+        };
+        self.values = values
+            .iter()
+            .flat_map(|values| values.enum_value_definitions())
+            .filter_map(|value| {
+                let name = value.enum_value()?.name()?.text().to_string();
+                Some(ValueDefinition {
+                    coordinate: format!("{}.{}", type_name, name),
+                    name,
+                    ty: None,
+                    default_value: None,
+                    description: render_description(value.description()),
+                    directives: render_directives(value.directives()),
+                    location: lines.locate(value.syntax()),
+                })
+            })
+            .collect();
+        self
+    }
+
+    fn members(self, members: Option<cst::UnionMemberTypes>) -> Self {
+        let members = members
+            .iter()
+            .flat_map(|members| members.named_types())
+            .filter_map(|member| Some(member.name()?.text().to_string()))
+            .collect();
+        Self { members, ..self }
+    }
+
+    fn build(self, location: Location) -> Option<TypeDefinition> {
+        Some(TypeDefinition {
+            name: self.name?,
+            kind: self.kind,
+            is_extension: self.is_extension,
+            description: self.description,
+            directives: self.directives,
+            interfaces: self.interfaces,
+            fields: self.fields,
+            values: self.values,
+            members: self.members,
+            location,
+        })
+    }
+}
+
+fn value_definition(
+    def: cst::InputValueDefinition,
+    coordinate: String,
+    lines: &LineIndex,
+) -> Option<ValueDefinition> {
+    Some(ValueDefinition {
+        name: def.name()?.text().to_string(),
+        coordinate,
+        ty: render_type(def.ty()),
+        default_value: def
+            .default_value()
+            .and_then(|default| default.value())
+            .map(|value| value.source_string()),
+        description: render_description(def.description()),
+        directives: render_directives(def.directives()),
+        location: lines.locate(def.syntax()),
+    })
+}
+
+fn render_type(ty: Option<cst::Type>) -> Option<String> {
+    ty.map(|ty| ty.source_string().split_whitespace().collect())
+}
+
+fn render_description(description: Option<cst::Description>) -> Option<String> {
+    description
+        .and_then(|description| description.string_value())
+        .map(String::from)
+}
+
+fn render_directives(directives: Option<cst::Directives>) -> Vec<DirectiveApplication> {
+    directives
+        .iter()
+        .flat_map(|directives| directives.directives())
+        .filter_map(|directive| {
+            let arguments = directive
+                .arguments()
+                .iter()
+                .flat_map(|args| args.arguments())
+                .filter_map(|arg| {
+                    Some((arg.name()?.text().to_string(), arg.value()?.source_string()))
+                })
+                .collect();
+            Some(DirectiveApplication {
+                name: directive.name()?.text().to_string(),
+                arguments,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    const SCHEMA: &str = r#"
+"The root query"
+type Query {
+  "Look up a user"
+  user(id: ID!, include: [String!] = ["name"]): User @deprecated(reason: "use `me`")
+  me: User
+}
+
+type User @key(fields: "id") {
+  id: ID!
+}
+
+extend type User {
+  name: String
+}
+
+enum Role {
+  ADMIN
+  "A reader"
+  READER
+}
+"#;
+
+    #[test]
+    fn test_parses_types_fields_and_directives() {
+        let schema = PolicySchema::parse(SCHEMA).unwrap();
+        let names = schema
+            .types
+            .iter()
+            .map(|ty| (ty.name.as_str(), ty.is_extension))
+            .collect::<Vec<_>>();
+        assert_that!(names).is_equal_to(vec![
+            ("Query", false),
+            ("User", false),
+            ("User", true),
+            ("Role", false),
+        ]);
+
+        let query = &schema.types[0];
+        assert_that!(query.description).is_equal_to(Some("The root query".to_string()));
+        let user = &query.fields[0];
+        assert_that!(user.ty).is_equal_to("User".to_string());
+        assert_that!(user.description).is_equal_to(Some("Look up a user".to_string()));
+        assert_that!(user.directives).is_equal_to(vec![DirectiveApplication {
+            name: "deprecated".to_string(),
+            arguments: BTreeMap::from([("reason".to_string(), "\"use `me`\"".to_string())]),
+        }]);
+        assert_that!(user.arguments[1].coordinate).is_equal_to("Query.user(include:)".to_string());
+        assert_that!(user.arguments[1].default_value).is_equal_to(Some("[\"name\"]".to_string()));
+        assert_that!(schema.types[3].values[1].description)
+            .is_equal_to(Some("A reader".to_string()));
+    }
+
+    #[test]
+    fn test_locates_coordinates() {
+        let schema = PolicySchema::parse(SCHEMA).unwrap();
+        assert_that!(schema.locate("Query").map(|l| l.line)).is_equal_to(Some(2));
+        assert_that!(schema.locate("Query.me").map(|l| l.line)).is_equal_to(Some(6));
+        assert_that!(schema.locate("Query.user(id:)").map(|l| l.line)).is_equal_to(Some(5));
+        // fields of extensions are found too
+        assert_that!(schema.locate("User.name").map(|l| l.line)).is_equal_to(Some(14));
+        assert_that!(schema.locate("Role.READER").map(|l| l.line)).is_equal_to(Some(19));
+        assert_that!(schema.locate("Mutation")).is_none();
+    }
+}
@@ -0,0 +1,648 @@
+//! Compares the API schemas of two supergraphs.
+//!
+//! Supergraph SDL carries federation metadata (`join__` types, `@link`, `@inaccessible`, and so
+//! on) that clients never see, so both schemas are reduced to their API surface before they are
+//! compared. Elements marked `@inaccessible` are dropped, as are federation types and directive
+//! applications.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+
+use apollo_parser::{cst, cst::CstNode, Parser};
+use itertools::Itertools;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::utils::table::{self, row};
+
+/// Directive applications that are removed when building an API schema
+const FEDERATION_DIRECTIVES: [&str; 4] = ["link", "core", "inaccessible", "tag"];
+/// Prefixes of the types and directives that federation adds to a supergraph
+const FEDERATION_PREFIXES: [&str; 3] = ["join__", "link__", "core__"];
+
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+#[error("Failed to parse the {which} schema:\n{}", .errors.iter().join("\n"))]
+pub struct SchemaDiffError {
+    which: &'static str,
+    errors: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChangeKind {
+    TypeAdded,
+    TypeRemoved,
+    TypeKindChanged,
+    FieldAdded,
+    FieldRemoved,
+    FieldTypeChanged,
+    ArgumentAdded,
+    ArgumentRemoved,
+    ArgumentTypeChanged,
+    EnumValueAdded,
+    EnumValueRemoved,
+    UnionMemberAdded,
+    UnionMemberRemoved,
+    DirectiveAdded,
+    DirectiveRemoved,
+}
+
+impl Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printable = match self {
+            ChangeKind::TypeAdded => "TYPE_ADDED",
+            ChangeKind::TypeRemoved => "TYPE_REMOVED",
+            ChangeKind::TypeKindChanged => "TYPE_KIND_CHANGED",
+            ChangeKind::FieldAdded => "FIELD_ADDED",
+            ChangeKind::FieldRemoved => "FIELD_REMOVED",
+            ChangeKind::FieldTypeChanged => "FIELD_TYPE_CHANGED",
+            ChangeKind::ArgumentAdded => "ARGUMENT_ADDED",
+            ChangeKind::ArgumentRemoved => "ARGUMENT_REMOVED",
+            ChangeKind::ArgumentTypeChanged => "ARGUMENT_TYPE_CHANGED",
+            ChangeKind::EnumValueAdded => "ENUM_VALUE_ADDED",
+            ChangeKind::EnumValueRemoved => "ENUM_VALUE_REMOVED",
+            ChangeKind::UnionMemberAdded => "UNION_MEMBER_ADDED",
+            ChangeKind::UnionMemberRemoved => "UNION_MEMBER_REMOVED",
+            ChangeKind::DirectiveAdded => "DIRECTIVE_ADDED",
+            ChangeKind::DirectiveRemoved => "DIRECTIVE_REMOVED",
+        };
+        write!(f, "{}", printable)
+    }
+}
+
+impl ChangeKind {
+    /// How likely a change of this kind is to break existing clients
+    pub fn severity(&self) -> ChangeSeverity {
+        match self {
+            ChangeKind::TypeRemoved
+            | ChangeKind::TypeKindChanged
+            | ChangeKind::FieldRemoved
+            | ChangeKind::FieldTypeChanged
+            | ChangeKind::ArgumentRemoved
+            | ChangeKind::ArgumentTypeChanged
+            | ChangeKind::EnumValueRemoved
+            | ChangeKind::UnionMemberRemoved => ChangeSeverity::Breaking,
+            // new arguments may be required, and clients may not handle new enum values or union
+            // members exhaustively
+            ChangeKind::ArgumentAdded
+            | ChangeKind::EnumValueAdded
+            | ChangeKind::UnionMemberAdded
+            | ChangeKind::DirectiveAdded
+            | ChangeKind::DirectiveRemoved => ChangeSeverity::Dangerous,
+            ChangeKind::TypeAdded | ChangeKind::FieldAdded => ChangeSeverity::Safe,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChangeSeverity {
+    Breaking,
+    Dangerous,
+    Safe,
+}
+
+impl Display for ChangeSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printable = match self {
+            ChangeSeverity::Breaking => "Breaking",
+            ChangeSeverity::Dangerous => "Dangerous",
+            ChangeSeverity::Safe => "Safe",
+        };
+        write!(f, "{}", printable)
+    }
+}
+
+/// A single difference between two API schemas, located by its schema coordinate
+/// (for example `Query.products(limit:)`)
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct SchemaChange {
+    pub kind: ChangeKind,
+    pub coordinate: String,
+    pub detail: Option<String>,
+}
+
+impl SchemaChange {
+    fn new(kind: ChangeKind, coordinate: impl Into<String>) -> Self {
+        Self {
+            kind,
+            coordinate: coordinate.into(),
+            detail: None,
+        }
+    }
+
+    fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct SchemaDiff {
+    pub changes: Vec<SchemaChange>,
+}
+
+impl SchemaDiff {
+    /// Diffs the API schemas of two supergraph (or plain GraphQL) SDL documents
+    pub fn new(old_sdl: &str, new_sdl: &str) -> Result<Self, SchemaDiffError> {
+        let old = api_types(old_sdl, "old")?;
+        let new = api_types(new_sdl, "new")?;
+        let mut changes = Vec::new();
+
+        for (name, old_type) in &old {
+            match new.get(name) {
+                None => changes.push(SchemaChange::new(ChangeKind::TypeRemoved, name)),
+                Some(new_type) if new_type.kind != old_type.kind => changes.push(
+                    SchemaChange::new(ChangeKind::TypeKindChanged, name)
+                        .with_detail(format!("{} -> {}", old_type.kind, new_type.kind)),
+                ),
+                Some(new_type) => diff_type(name, old_type, new_type, &mut changes),
+            }
+        }
+        for name in new.keys().filter(|name| !old.contains_key(*name)) {
+            changes.push(SchemaChange::new(ChangeKind::TypeAdded, name));
+        }
+
+        changes.sort_by(|a, b| a.coordinate.cmp(&b.coordinate).then(a.kind.cmp(&b.kind)));
+        Ok(Self { changes })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn get_output(&self) -> String {
+        if self.is_empty() {
+            return "There were no changes detected between the API schemas.".to_string();
+        }
+
+        let mut table = table::get_table();
+
+        // bc => sets top row to be bold and center
+        table.add_row(row![bc => "Change", "Coordinate", "Detail"]);
+        for change in &self.changes {
+            table.add_row(row![
+                change.kind,
+                change.coordinate,
+                change.detail.as_deref().unwrap_or_default()
+            ]);
+        }
+        table.to_string()
+    }
+
+    pub fn get_json(&self) -> Value {
+        json!(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum TypeKind {
+    Object,
+    Interface,
+    InputObject,
+    Enum,
+    Union,
+    Scalar,
+}
+
+impl Display for TypeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printable = match self {
+            TypeKind::Object => "object",
+            TypeKind::Interface => "interface",
+            TypeKind::InputObject => "input object",
+            TypeKind::Enum => "enum",
+            TypeKind::Union => "union",
+            TypeKind::Scalar => "scalar",
+        };
+        write!(f, "{}", printable)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TypeSummary {
+    pub(crate) kind: TypeKind,
+    directives: BTreeSet<String>,
+    /// Fields, input fields, enum values or union members, depending on `kind`
+    pub(crate) members: BTreeMap<String, MemberSummary>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct MemberSummary {
+    pub(crate) ty: Option<String>,
+    directives: BTreeSet<String>,
+    pub(crate) arguments: BTreeMap<String, MemberSummary>,
+}
+
+/// Reduces SDL to the types of its API schema, keyed by name
+pub(crate) fn api_types(
+    sdl: &str,
+    which: &'static str,
+) -> Result<BTreeMap<String, TypeSummary>, SchemaDiffError> {
+    let parsed = Parser::new(sdl).parse();
+    if parsed.errors().len() > 0 {
+        return Err(SchemaDiffError {
+            which,
+            errors: parsed
+                .errors()
+                .map(|err| err.message().to_string())
+                .collect(),
+        });
+    }
+
+    let mut types = BTreeMap::new();
+    for definition in parsed.document().definitions() {
+        use cst::Definition::*;
+        let (name, kind, directives, members) = match definition {
+            ObjectTypeDefinition(def) => (
+                def.name(),
+                TypeKind::Object,
+                def.directives(),
+                fields(def.fields_definition()),
+            ),
+            ObjectTypeExtension(def) => (
+                def.name(),
+                TypeKind::Object,
+                def.directives(),
+                fields(def.fields_definition()),
+            ),
+            InterfaceTypeDefinition(def) => (
+                def.name(),
+                TypeKind::Interface,
+                def.directives(),
+                fields(def.fields_definition()),
+            ),
+            InterfaceTypeExtension(def) => (
+                def.name(),
+                TypeKind::Interface,
+                def.directives(),
+                fields(def.fields_definition()),
+            ),
+            InputObjectTypeDefinition(def) => (
+                def.name(),
+                TypeKind::InputObject,
+                def.directives(),
+                input_fields(def.input_fields_definition()),
+            ),
+            InputObjectTypeExtension(def) => (
+                def.name(),
+                TypeKind::InputObject,
+                def.directives(),
+                input_fields(def.input_fields_definition()),
+            ),
+            EnumTypeDefinition(def) => (
+                def.name(),
+                TypeKind::Enum,
+                def.directives(),
+                enum_values(def.enum_values_definition()),
+            ),
+            EnumTypeExtension(def) => (
+                def.name(),
+                TypeKind::Enum,
+                def.directives(),
+                enum_values(def.enum_values_definition()),
+            ),
+            UnionTypeDefinition(def) => (
+                def.name(),
+                TypeKind::Union,
+                def.directives(),
+                union_members(def.union_member_types()),
+            ),
+            UnionTypeExtension(def) => (
+                def.name(),
+                TypeKind::Union,
+                def.directives(),
+                union_members(def.union_member_types()),
+            ),
+            ScalarTypeDefinition(def) => {
+                (def.name(), TypeKind::Scalar, def.directives(), Vec::new())
+            }
+            ScalarTypeExtension(def) => {
+                (def.name(), TypeKind::Scalar, def.directives(), Vec::new())
+            }
+            _ => continue,
+        };
+
+        let Some(name) = name.map(|name| name.text().to_string()) else {
+            continue;
+        };
+        if is_federation_name(&name) {
+            continue;
+        }
+        let Some(directives) = api_directives(directives) else {
+            continue;
+        };
+        let summary = types.entry(name).or_insert_with(|| TypeSummary {
+            kind,
+            directives: BTreeSet::new(),
+            members: BTreeMap::new(),
+        });
+        summary.directives.extend(directives);
+        summary.members.extend(members);
+    }
+    Ok(types)
+}
+
+fn is_federation_name(name: &str) -> bool {
+    FEDERATION_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
+/// Renders the directive applications that are part of the API schema, or returns `None` if the
+/// element is `@inaccessible` and so isn't part of the API schema at all.
+fn api_directives(directives: Option<cst::Directives>) -> Option<BTreeSet<String>> {
+    let mut rendered = BTreeSet::new();
+    for directive in directives.iter().flat_map(|d| d.directives()) {
+        let Some(name) = directive.name().map(|name| name.text().to_string()) else {
+            continue;
+        };
+        if name == "inaccessible" {
+            return None;
+        }
+        if FEDERATION_DIRECTIVES.contains(&name.as_str()) || is_federation_name(&name) {
+            continue;
+        }
+        rendered.insert(directive.source_string().split_whitespace().join(" "));
+    }
+    Some(rendered)
+}
+
+fn render_type(ty: Option<cst::Type>) -> Option<String> {
+    ty.map(|ty| ty.source_string().split_whitespace().collect())
+}
+
+fn fields(def: Option<cst::FieldsDefinition>) -> Vec<(String, MemberSummary)> {
+    def.iter()
+        .flat_map(|def| def.field_definitions())
+        .filter_map(|field| {
+            let summary = MemberSummary {
+                ty: render_type(field.ty()),
+                directives: api_directives(field.directives())?,
+                arguments: field
+                    .arguments_definition()
+                    .iter()
+                    .flat_map(|args| args.input_value_definitions())
+                    .filter_map(input_value)
+                    .collect(),
+            };
+            Some((field.name()?.text().to_string(), summary))
+        })
+        .collect()
+}
+
+fn input_fields(def: Option<cst::InputFieldsDefinition>) -> Vec<(String, MemberSummary)> {
+    def.iter()
+        .flat_map(|def| def.input_value_definitions())
+        .filter_map(input_value)
+        .collect()
+}
+
+fn input_value(def: cst::InputValueDefinition) -> Option<(String, MemberSummary)> {
+    let summary = MemberSummary {
+        ty: render_type(def.ty()),
+        directives: api_directives(def.directives())?,
+        arguments: BTreeMap::new(),
+    };
+    Some((def.name()?.text().to_string(), summary))
+}
+
+fn enum_values(def: Option<cst::EnumValuesDefinition>) -> Vec<(String, MemberSummary)> {
+    def.iter()
+        .flat_map(|def| def.enum_value_definitions())
+        .filter_map(|value| {
+            let summary = MemberSummary {
+                directives: api_directives(value.directives())?,
+                ..Default::default()
+            };
+            Some((value.enum_value()?.name()?.text().to_string(), summary))
+        })
+        .collect()
+}
+
+fn union_members(def: Option<cst::UnionMemberTypes>) -> Vec<(String, MemberSummary)> {
+    def.iter()
+        .flat_map(|def| def.named_types())
+        .filter_map(|member| Some((member.name()?.text().to_string(), MemberSummary::default())))
+        .collect()
+}
+
+fn diff_type(name: &str, old: &TypeSummary, new: &TypeSummary, changes: &mut Vec<SchemaChange>) {
+    diff_directives(name, &old.directives, &new.directives, changes);
+
+    let (added, removed) = match old.kind {
+        TypeKind::Enum => (ChangeKind::EnumValueAdded, ChangeKind::EnumValueRemoved),
+        TypeKind::Union => (ChangeKind::UnionMemberAdded, ChangeKind::UnionMemberRemoved),
+        _ => (ChangeKind::FieldAdded, ChangeKind::FieldRemoved),
+    };
+    for (member_name, old_member) in &old.members {
+        let coordinate = format!("{}.{}", name, member_name);
+        match new.members.get(member_name) {
+            None => changes.push(SchemaChange::new(removed, coordinate)),
+            Some(new_member) => {
+                diff_member(
+                    &coordinate,
+                    ChangeKind::FieldTypeChanged,
+                    old_member,
+                    new_member,
+                    changes,
+                );
+                for (arg_name, old_arg) in &old_member.arguments {
+                    let arg_coordinate = format!("{}({}:)", coordinate, arg_name);
+                    match new_member.arguments.get(arg_name) {
+                        None => changes.push(SchemaChange::new(
+                            ChangeKind::ArgumentRemoved,
+                            arg_coordinate,
+                        )),
+                        Some(new_arg) => diff_member(
+                            &arg_coordinate,
+                            ChangeKind::ArgumentTypeChanged,
+                            old_arg,
+                            new_arg,
+                            changes,
+                        ),
+                    }
+                }
+                for arg_name in new_member
+                    .arguments
+                    .keys()
+                    .filter(|arg_name| !old_member.arguments.contains_key(*arg_name))
+                {
+                    changes.push(SchemaChange::new(
+                        ChangeKind::ArgumentAdded,
+                        format!("{}({}:)", coordinate, arg_name),
+                    ));
+                }
+            }
+        }
+    }
+    for member_name in new
+        .members
+        .keys()
+        .filter(|member_name| !old.members.contains_key(*member_name))
+    {
+        changes.push(SchemaChange::new(
+            added,
+            format!("{}.{}", name, member_name),
+        ));
+    }
+}
+
+fn diff_member(
+    coordinate: &str,
+    type_changed: ChangeKind,
+    old: &MemberSummary,
+    new: &MemberSummary,
+    changes: &mut Vec<SchemaChange>,
+) {
+    if let (Some(old_ty), Some(new_ty)) = (&old.ty, &new.ty) {
+        if old_ty != new_ty {
+            changes.push(
+                SchemaChange::new(type_changed, coordinate)
+                    .with_detail(format!("{} -> {}", old_ty, new_ty)),
+            );
+        }
+    }
+    diff_directives(coordinate, &old.directives, &new.directives, changes);
+}
+
+fn diff_directives(
+    coordinate: &str,
+    old: &BTreeSet<String>,
+    new: &BTreeSet<String>,
+    changes: &mut Vec<SchemaChange>,
+) {
+    for directive in old.difference(new) {
+        changes.push(
+            SchemaChange::new(ChangeKind::DirectiveRemoved, coordinate).with_detail(directive),
+        );
+    }
+    for directive in new.difference(old) {
+        changes
+            .push(SchemaChange::new(ChangeKind::DirectiveAdded, coordinate).with_detail(directive));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    const SUPERGRAPH: &str = r#"
+        schema
+          @link(url: "https://specs.apollo.dev/link/v1.0")
+          @link(url: "https://specs.apollo.dev/join/v0.3", for: EXECUTION)
+        {
+          query: Query
+        }
+
+        directive @join__type(graph: join__Graph!, key: join__FieldSet) repeatable on OBJECT
+
+        scalar join__FieldSet
+
+        enum join__Graph {
+          PRODUCTS @join__graph(name: "products", url: "http://localhost:4001")
+        }
+
+        type Query @join__type(graph: PRODUCTS) {
+          products(limit: Int): [Product!]! @join__field(graph: PRODUCTS)
+          secret: String @inaccessible
+        }
+
+        type Product @join__type(graph: PRODUCTS, key: "id") {
+          id: ID!
+          name: String @deprecated(reason: "Use title")
+        }
+
+        enum Category {
+          BOOKS
+          GAMES
+        }
+    "#;
+
+    fn changes(old: &str, new: &str) -> Vec<(ChangeKind, String, Option<String>)> {
+        SchemaDiff::new(old, new)
+            .unwrap()
+            .changes
+            .into_iter()
+            .map(|change| (change.kind, change.coordinate, change.detail))
+            .collect()
+    }
+
+    #[test]
+    fn test_identical_schemas_have_no_changes() {
+        assert_that!(SchemaDiff::new(SUPERGRAPH, SUPERGRAPH).unwrap().is_empty()).is_true();
+    }
+
+    #[test]
+    fn test_federation_metadata_is_ignored() {
+        let new = SUPERGRAPH
+            .replace(
+                r#"PRODUCTS @join__graph(name: "products", url: "http://localhost:4001")"#,
+                r#"PRODUCTS @join__graph(name: "products", url: "http://localhost:5001")"#,
+            )
+            .replace("secret: String @inaccessible", "hidden: Int @inaccessible");
+        assert_that!(changes(SUPERGRAPH, &new)).is_empty();
+    }
+
+    #[rstest]
+    #[case::type_added(
+        "scalar Date\n",
+        vec![(ChangeKind::TypeAdded, "Date".to_string(), None)]
+    )]
+    #[case::field_added(
+        "extend type Product { price: Int }\n",
+        vec![(ChangeKind::FieldAdded, "Product.price".to_string(), None)]
+    )]
+    fn test_additions(
+        #[case] addition: &str,
+        #[case] expected: Vec<(ChangeKind, String, Option<String>)>,
+    ) {
+        let new = format!("{}{}", SUPERGRAPH, addition);
+        assert_that!(changes(SUPERGRAPH, &new)).is_equal_to(expected);
+    }
+
+    #[test]
+    fn test_changes_are_reported_by_coordinate() {
+        let new = SUPERGRAPH
+            .replace("products(limit: Int)", "products(first: Int!)")
+            .replace("id: ID!", "id: String!")
+            .replace(r#"@deprecated(reason: "Use title")"#, "")
+            .replace("GAMES", "");
+        assert_that!(changes(SUPERGRAPH, &new)).is_equal_to(vec![
+            (
+                ChangeKind::EnumValueRemoved,
+                "Category.GAMES".to_string(),
+                None,
+            ),
+            (
+                ChangeKind::FieldTypeChanged,
+                "Product.id".to_string(),
+                Some("ID! -> String!".to_string()),
+            ),
+            (
+                ChangeKind::DirectiveRemoved,
+                "Product.name".to_string(),
+                Some(r#"@deprecated(reason: "Use title")"#.to_string()),
+            ),
+            (
+                ChangeKind::ArgumentAdded,
+                "Query.products(first:)".to_string(),
+                None,
+            ),
+            (
+                ChangeKind::ArgumentRemoved,
+                "Query.products(limit:)".to_string(),
+                None,
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_errors_are_reported() {
+        let err = SchemaDiff::new(SUPERGRAPH, "type Query {").unwrap_err();
+        assert_that!(err.to_string()).starts_with("Failed to parse the new schema:");
+    }
+}
@@ -0,0 +1,866 @@
+//! Masks a GraphQL schema so it can be shared outside the team that owns it.
+//!
+//! Types and fields are renamed according to a [`MaskConfig`], and every reference to a renamed
+//! element is updated to match, including the field sets of federation's `@key`, `@requires` and
+//! `@provides`. Descriptions and internal-only directives can be stripped along the way. The
+//! schema is edited in place, so everything that isn't masked keeps its original formatting.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Range;
+
+use apollo_parser::{cst, cst::CstNode, Parser, SyntaxNode};
+use itertools::Itertools;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Root operation types, which patterns never rename so that schemas without a `schema`
+/// definition keep working
+const ROOT_OPERATION_TYPES: [&str; 3] = ["Query", "Mutation", "Subscription"];
+/// Directives whose `fields` argument is a field set that refers to fields by name
+const FIELD_SET_DIRECTIVES: [&str; 3] = ["key", "requires", "provides"];
+
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+pub enum SchemaMaskError {
+    #[error("Failed to parse the mask config: {0}")]
+    InvalidConfig(String),
+    #[error("Failed to parse the schema:\n{}", .0.iter().join("\n"))]
+    InvalidSchema(Vec<String>),
+    #[error("`{pattern}` is not a valid pattern: {reason}")]
+    InvalidPattern { pattern: String, reason: String },
+    #[error("The mask config renames type `{0}`, which isn't in the schema")]
+    UnknownType(String),
+    #[error("The mask config renames field `{0}`, which isn't in the schema")]
+    UnknownField(String),
+    #[error("`{0}` is not a field coordinate. Fields are written as `Type.field`")]
+    InvalidFieldCoordinate(String),
+    #[error("Masking would rename `{coordinate}` to `{name}`, which is not a valid GraphQL name")]
+    InvalidName { coordinate: String, name: String },
+    #[error("Masking would give both `{first}` and `{second}` the name `{name}`")]
+    Conflict {
+        first: String,
+        second: String,
+        name: String,
+    },
+    #[error("`{field}` has to be renamed to `{expected}` to keep implementing `{interface}`, but the mask config renames it to `{name}`")]
+    InterfaceMismatch {
+        field: String,
+        interface: String,
+        expected: String,
+        name: String,
+    },
+}
+
+/// Which names to change and what to remove when masking a schema
+///
+/// ```yaml
+/// types:
+///   AcmeInternalUser: User
+/// fields:
+///   User.ssnHash: taxIdHash
+/// patterns:
+///   - match: ^Acme
+///     replace: ""
+/// strip_descriptions: true
+/// strip_directives:
+///   - internal
+/// ```
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MaskConfig {
+    /// New names for types, keyed by their current name
+    #[serde(default)]
+    pub types: BTreeMap<String, String>,
+    /// New names for fields and input fields, keyed by their `Type.field` coordinate
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+    /// Regular expressions applied to every type and field name that `types` and `fields` don't
+    /// rename. The first pattern that matches a name renames it
+    #[serde(default)]
+    pub patterns: Vec<RenamePattern>,
+    /// Whether to remove every description from the schema
+    #[serde(default = "default_strip_descriptions")]
+    pub strip_descriptions: bool,
+    /// Directives to remove, along with every place they're applied
+    #[serde(default)]
+    pub strip_directives: BTreeSet<String>,
+}
+
+fn default_strip_descriptions() -> bool {
+    true
+}
+
+impl Default for MaskConfig {
+    fn default() -> Self {
+        Self {
+            types: BTreeMap::new(),
+            fields: BTreeMap::new(),
+            patterns: Vec::new(),
+            strip_descriptions: default_strip_descriptions(),
+            strip_directives: BTreeSet::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RenamePattern {
+    /// The regular expression to look for in a name
+    #[serde(rename = "match")]
+    pub pattern: String,
+    /// What to replace each match with. `$1` and so on refer to capture groups
+    pub replace: String,
+}
+
+impl MaskConfig {
+    pub fn parse(contents: &str) -> Result<Self, SchemaMaskError> {
+        serde_yaml::from_str(contents)
+            .map_err(|err| SchemaMaskError::InvalidConfig(err.to_string()))
+    }
+}
+
+/// A schema that's safe to share, along with a summary of what was changed
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct MaskedSchema {
+    pub sdl: String,
+    pub renamed_types: usize,
+    pub renamed_fields: usize,
+}
+
+/// The types a schema defines, and the fields of each
+#[derive(Debug, Default)]
+struct TypeInfo {
+    /// The named type of each field or input field
+    fields: BTreeMap<String, String>,
+    implements: BTreeSet<String>,
+}
+
+/// A replacement of part of the schema's source
+#[derive(Debug)]
+struct Edit {
+    range: Range<usize>,
+    text: String,
+}
+
+struct Masker<'a> {
+    sdl: &'a str,
+    config: &'a MaskConfig,
+    types: BTreeMap<String, TypeInfo>,
+    type_renames: HashMap<String, String>,
+    field_renames: HashMap<(String, String), String>,
+    edits: Vec<Edit>,
+}
+
+/// Masks `sdl` according to `config`
+pub fn mask_schema(sdl: &str, config: &MaskConfig) -> Result<MaskedSchema, SchemaMaskError> {
+    let parsed = Parser::new(sdl).parse();
+    check_errors(parsed.errors())?;
+    let document = parsed.document();
+
+    let mut masker = Masker {
+        sdl,
+        config,
+        types: collect_types(&document),
+        type_renames: HashMap::new(),
+        field_renames: HashMap::new(),
+        edits: Vec::new(),
+    };
+    masker.plan_renames()?;
+    masker.collect_edits(&document);
+
+    let masked = masker.apply_edits();
+    check_errors(Parser::new(&masked).parse().errors())?;
+    Ok(MaskedSchema {
+        sdl: masked,
+        renamed_types: masker.type_renames.len(),
+        renamed_fields: masker.field_renames.len(),
+    })
+}
+
+fn check_errors<'a>(
+    errors: impl ExactSizeIterator<Item = &'a apollo_parser::Error>,
+) -> Result<(), SchemaMaskError> {
+    if errors.len() == 0 {
+        Ok(())
+    } else {
+        Err(SchemaMaskError::InvalidSchema(
+            errors.map(|err| err.message().to_string()).collect(),
+        ))
+    }
+}
+
+fn collect_types(document: &cst::Document) -> BTreeMap<String, TypeInfo> {
+    let mut types: BTreeMap<String, TypeInfo> = BTreeMap::new();
+    for definition in document.definitions() {
+        use cst::Definition::*;
+        let (name, fields, implements) = match definition {
+            ObjectTypeDefinition(def) => (
+                def.name(),
+                fields(def.fields_definition()),
+                implements(def.implements_interfaces()),
+            ),
+            ObjectTypeExtension(def) => (
+                def.name(),
+                fields(def.fields_definition()),
+                implements(def.implements_interfaces()),
+            ),
+            InterfaceTypeDefinition(def) => (
+                def.name(),
+                fields(def.fields_definition()),
+                implements(def.implements_interfaces()),
+            ),
+            InterfaceTypeExtension(def) => (
+                def.name(),
+                fields(def.fields_definition()),
+                implements(def.implements_interfaces()),
+            ),
+            InputObjectTypeDefinition(def) => (
+                def.name(),
+                input_fields(def.input_fields_definition()),
+                BTreeSet::new(),
+            ),
+            InputObjectTypeExtension(def) => (
+                def.name(),
+                input_fields(def.input_fields_definition()),
+                BTreeSet::new(),
+            ),
+            EnumTypeDefinition(def) => (def.name(), Vec::new(), BTreeSet::new()),
+            EnumTypeExtension(def) => (def.name(), Vec::new(), BTreeSet::new()),
+            UnionTypeDefinition(def) => (def.name(), Vec::new(), BTreeSet::new()),
+            UnionTypeExtension(def) => (def.name(), Vec::new(), BTreeSet::new()),
+            ScalarTypeDefinition(def) => (def.name(), Vec::new(), BTreeSet::new()),
+            ScalarTypeExtension(def) => (def.name(), Vec::new(), BTreeSet::new()),
+            _ => continue,
+        };
+        let Some(name) = name else {
+            continue;
+        };
+        let info = types.entry(name.text().to_string()).or_default();
+        info.fields.extend(fields);
+        info.implements.extend(implements);
+    }
+    types
+}
+
+fn fields(definition: Option<cst::FieldsDefinition>) -> Vec<(String, String)> {
+    definition
+        .iter()
+        .flat_map(|fields| fields.field_definitions())
+        .filter_map(|field| Some((field.name()?.text().to_string(), named_type(field.ty()?)?)))
+        .collect()
+}
+
+fn input_fields(definition: Option<cst::InputFieldsDefinition>) -> Vec<(String, String)> {
+    definition
+        .iter()
+        .flat_map(|fields| fields.input_value_definitions())
+        .filter_map(|field| Some((field.name()?.text().to_string(), named_type(field.ty()?)?)))
+        .collect()
+}
+
+fn implements(interfaces: Option<cst::ImplementsInterfaces>) -> BTreeSet<String> {
+    interfaces
+        .iter()
+        .flat_map(|interfaces| interfaces.named_types())
+        .filter_map(|ty| Some(ty.name()?.text().to_string()))
+        .collect()
+}
+
+/// The name of the type at the bottom of any lists and non-null wrappers
+fn named_type(ty: cst::Type) -> Option<String> {
+    match ty {
+        cst::Type::NamedType(ty) => Some(ty.name()?.text().to_string()),
+        cst::Type::ListType(ty) => named_type(ty.ty()?),
+        cst::Type::NonNullType(ty) => match (ty.named_type(), ty.list_type()) {
+            (Some(ty), _) => Some(ty.name()?.text().to_string()),
+            (None, Some(ty)) => named_type(ty.ty()?),
+            (None, None) => None,
+        },
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first == '_' || first.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+/// Whether patterns can rename a type or field. Names that start with `_` belong to GraphQL
+/// introspection or to federation
+fn is_maskable(name: &str) -> bool {
+    !name.starts_with('_') && !name.contains("__")
+}
+
+fn text_range(node: &SyntaxNode) -> Range<usize> {
+    let range = node.text_range();
+    usize::from(range.start())..usize::from(range.end())
+}
+
+impl Masker<'_> {
+    fn plan_renames(&mut self) -> Result<(), SchemaMaskError> {
+        let patterns = self
+            .config
+            .patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(&pattern.pattern)
+                    .map(|regex| (regex, pattern.replace.as_str()))
+                    .map_err(|err| SchemaMaskError::InvalidPattern {
+                        pattern: pattern.pattern.clone(),
+                        reason: err.to_string(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let apply_patterns = |name: &str| {
+            patterns
+                .iter()
+                .find(|(regex, _)| regex.is_match(name))
+                .map(|(regex, replace)| regex.replace_all(name, *replace).to_string())
+                .filter(|renamed| renamed != name)
+        };
+
+        for (name, renamed) in &self.config.types {
+            if !self.types.contains_key(name) {
+                return Err(SchemaMaskError::UnknownType(name.clone()));
+            }
+            self.type_renames.insert(name.clone(), renamed.clone());
+        }
+        let mut explicit_fields = BTreeSet::new();
+        for (coordinate, renamed) in &self.config.fields {
+            let (type_name, field_name) = coordinate
+                .split_once('.')
+                .ok_or_else(|| SchemaMaskError::InvalidFieldCoordinate(coordinate.clone()))?;
+            if !self
+                .types
+                .get(type_name)
+                .is_some_and(|info| info.fields.contains_key(field_name))
+            {
+                return Err(SchemaMaskError::UnknownField(coordinate.clone()));
+            }
+            let key = (type_name.to_string(), field_name.to_string());
+            explicit_fields.insert(key.clone());
+            self.field_renames.insert(key, renamed.clone());
+        }
+
+        for (type_name, info) in &self.types {
+            if is_maskable(type_name)
+                && !ROOT_OPERATION_TYPES.contains(&type_name.as_str())
+                && !self.type_renames.contains_key(type_name)
+            {
+                if let Some(renamed) = apply_patterns(type_name) {
+                    self.type_renames.insert(type_name.clone(), renamed);
+                }
+            }
+            for field_name in info.fields.keys().filter(|name| is_maskable(name)) {
+                let key = (type_name.clone(), field_name.clone());
+                if !explicit_fields.contains(&key) {
+                    if let Some(renamed) = apply_patterns(field_name) {
+                        self.field_renames.insert(key, renamed);
+                    }
+                }
+            }
+        }
+
+        // a field that implements an interface's field has to keep the same name as it
+        for (type_name, info) in &self.types {
+            for interface in &info.implements {
+                for field_name in info.fields.keys() {
+                    let Some(expected) = self
+                        .field_renames
+                        .get(&(interface.clone(), field_name.clone()))
+                        .cloned()
+                    else {
+                        continue;
+                    };
+                    let key = (type_name.clone(), field_name.clone());
+                    match self.field_renames.get(&key) {
+                        Some(name) if explicit_fields.contains(&key) && *name != expected => {
+                            return Err(SchemaMaskError::InterfaceMismatch {
+                                field: format!("{type_name}.{field_name}"),
+                                interface: interface.clone(),
+                                expected,
+                                name: name.clone(),
+                            })
+                        }
+                        _ => {
+                            self.field_renames.insert(key, expected);
+                        }
+                    }
+                }
+            }
+        }
+
+        self.check_renames()
+    }
+
+    /// Makes sure every new name is valid and that no two types, or two fields of the same type,
+    /// end up with the same name
+    fn check_renames(&self) -> Result<(), SchemaMaskError> {
+        let mut type_names = HashMap::new();
+        for type_name in self.types.keys() {
+            let renamed = self.type_name(type_name);
+            if !is_valid_name(renamed) {
+                return Err(SchemaMaskError::InvalidName {
+                    coordinate: type_name.clone(),
+                    name: renamed.to_string(),
+                });
+            }
+            if let Some(first) = type_names.insert(renamed, type_name) {
+                return Err(SchemaMaskError::Conflict {
+                    first: first.clone(),
+                    second: type_name.clone(),
+                    name: renamed.to_string(),
+                });
+            }
+        }
+        for (type_name, info) in &self.types {
+            let mut field_names = HashMap::new();
+            for field_name in info.fields.keys() {
+                let renamed = self.field_name(type_name, field_name);
+                let coordinate = format!("{type_name}.{field_name}");
+                if !is_valid_name(renamed) {
+                    return Err(SchemaMaskError::InvalidName {
+                        coordinate,
+                        name: renamed.to_string(),
+                    });
+                }
+                if let Some(first) = field_names.insert(renamed, coordinate.clone()) {
+                    return Err(SchemaMaskError::Conflict {
+                        first,
+                        second: coordinate,
+                        name: renamed.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn type_name<'n>(&'n self, name: &'n str) -> &'n str {
+        self.type_renames.get(name).map_or(name, String::as_str)
+    }
+
+    fn field_name<'n>(&'n self, type_name: &str, field_name: &'n str) -> &'n str {
+        self.field_renames
+            .get(&(type_name.to_string(), field_name.to_string()))
+            .map_or(field_name, String::as_str)
+    }
+
+    fn rename(&mut self, name: Option<cst::Name>, renamed: Option<String>) {
+        if let (Some(name), Some(renamed)) = (name, renamed) {
+            self.edits.push(Edit {
+                range: text_range(name.syntax()),
+                text: renamed,
+            });
+        }
+    }
+
+    /// Removes a node, along with the whitespace after it
+    fn remove(&mut self, node: &SyntaxNode) {
+        let range = text_range(node);
+        let trailing = self.sdl[range.end..].len() - self.sdl[range.end..].trim_start().len();
+        self.edits.push(Edit {
+            range: range.start..range.end + trailing,
+            text: String::new(),
+        });
+    }
+
+    fn collect_edits(&mut self, document: &cst::Document) {
+        for definition in document.definitions() {
+            use cst::Definition::*;
+            let (name, directives, fields, input_fields) = match definition {
+                DirectiveDefinition(def) => {
+                    if def
+                        .name()
+                        .is_some_and(|name| self.config.strip_directives.contains(&*name.text()))
+                    {
+                        self.remove(def.syntax());
+                    }
+                    continue;
+                }
+                ObjectTypeDefinition(def) => {
+                    (def.name(), def.directives(), def.fields_definition(), None)
+                }
+                ObjectTypeExtension(def) => {
+                    (def.name(), def.directives(), def.fields_definition(), None)
+                }
+                InterfaceTypeDefinition(def) => {
+                    (def.name(), def.directives(), def.fields_definition(), None)
+                }
+                InterfaceTypeExtension(def) => {
+                    (def.name(), def.directives(), def.fields_definition(), None)
+                }
+                InputObjectTypeDefinition(def) => (
+                    def.name(),
+                    def.directives(),
+                    None,
+                    def.input_fields_definition(),
+                ),
+                InputObjectTypeExtension(def) => (
+                    def.name(),
+                    def.directives(),
+                    None,
+                    def.input_fields_definition(),
+                ),
+                EnumTypeDefinition(def) => (def.name(), None, None, None),
+                EnumTypeExtension(def) => (def.name(), None, None, None),
+                UnionTypeDefinition(def) => (def.name(), None, None, None),
+                UnionTypeExtension(def) => (def.name(), None, None, None),
+                ScalarTypeDefinition(def) => (def.name(), None, None, None),
+                ScalarTypeExtension(def) => (def.name(), None, None, None),
+                _ => continue,
+            };
+            let Some(name) = name else {
+                continue;
+            };
+            let type_name = name.text().to_string();
+            self.rename(Some(name), self.type_renames.get(&type_name).cloned());
+            self.rename_field_sets(directives, &type_name, &type_name);
+
+            for field in fields.iter().flat_map(|fields| fields.field_definitions()) {
+                let Some(field_name) = field.name() else {
+                    continue;
+                };
+                let key = (type_name.clone(), field_name.text().to_string());
+                let renamed = self.field_renames.get(&key).cloned();
+                let return_type = self.types[&type_name].fields.get(&key.1).cloned();
+                self.rename(Some(field_name), renamed);
+                self.rename_field_sets(
+                    field.directives(),
+                    &type_name,
+                    return_type.as_deref().unwrap_or_default(),
+                );
+            }
+            for field in input_fields
+                .iter()
+                .flat_map(|fields| fields.input_value_definitions())
+            {
+                let Some(field_name) = field.name() else {
+                    continue;
+                };
+                let key = (type_name.clone(), field_name.text().to_string());
+                self.rename(Some(field_name), self.field_renames.get(&key).cloned());
+            }
+        }
+
+        for node in document.syntax().descendants() {
+            if let Some(ty) = cst::NamedType::cast(node.clone()) {
+                if let Some(name) = ty.name() {
+                    let renamed = self.type_renames.get(&*name.text()).cloned();
+                    self.rename(Some(name), renamed);
+                }
+            } else if let Some(description) = cst::Description::cast(node.clone()) {
+                if self.config.strip_descriptions {
+                    self.remove(description.syntax());
+                }
+            } else if let Some(directive) = cst::Directive::cast(node) {
+                if directive
+                    .name()
+                    .is_some_and(|name| self.config.strip_directives.contains(&*name.text()))
+                {
+                    // directives are removed along with the whitespace before them, so that the
+                    // element they're applied to keeps its formatting
+                    let range = text_range(directive.syntax());
+                    let leading = self.sdl[..range.start].len()
+                        - self.sdl[..range.start].trim_end_matches([' ', '\t']).len();
+                    self.edits.push(Edit {
+                        range: range.start - leading..range.end,
+                        text: String::new(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Renames the fields referred to by `@key(fields:)` and `@requires(fields:)`, which select
+    /// from `parent_type`, and by `@provides(fields:)`, which selects from `return_type`
+    fn rename_field_sets(
+        &mut self,
+        directives: Option<cst::Directives>,
+        parent_type: &str,
+        return_type: &str,
+    ) {
+        for directive in directives.iter().flat_map(|d| d.directives()) {
+            let Some(name) = directive.name().map(|name| name.text().to_string()) else {
+                continue;
+            };
+            if !FIELD_SET_DIRECTIVES.contains(&name.as_str())
+                || self.config.strip_directives.contains(&name)
+            {
+                continue;
+            }
+            let selected_type = if name == "provides" {
+                return_type
+            } else {
+                parent_type
+            };
+            for argument in directive
+                .arguments()
+                .iter()
+                .flat_map(|arguments| arguments.arguments())
+            {
+                if argument.name().is_some_and(|name| name.text() != "fields") {
+                    continue;
+                }
+                let Some(cst::Value::StringValue(value)) = argument.value() else {
+                    continue;
+                };
+                let field_set = String::from(&value);
+                if let Some(renamed) = self.rename_field_set(&field_set, selected_type) {
+                    self.edits.push(Edit {
+                        range: text_range(value.syntax()),
+                        text: serde_json::Value::String(renamed).to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn rename_field_set(&self, field_set: &str, selected_type: &str) -> Option<String> {
+        let parsed = Parser::new(field_set).parse_selection_set();
+        if parsed.errors().len() > 0 {
+            return None;
+        }
+        let mut edits = Vec::new();
+        self.rename_selections(parsed.field_set(), selected_type, &mut edits);
+        if edits.is_empty() {
+            return None;
+        }
+        edits.sort_by_key(|edit| edit.range.start);
+        let mut renamed = field_set.to_string();
+        for edit in edits.into_iter().rev() {
+            renamed.replace_range(edit.range, &edit.text);
+        }
+        Some(renamed)
+    }
+
+    fn rename_selections(
+        &self,
+        selection_set: cst::SelectionSet,
+        selected_type: &str,
+        edits: &mut Vec<Edit>,
+    ) {
+        for selection in selection_set.selections() {
+            match selection {
+                cst::Selection::Field(field) => {
+                    let Some(name) = field.name() else {
+                        continue;
+                    };
+                    let field_name = name.text().to_string();
+                    let renamed = self.field_name(selected_type, &field_name);
+                    if renamed != field_name {
+                        edits.push(Edit {
+                            range: text_range(name.syntax()),
+                            text: renamed.to_string(),
+                        });
+                    }
+                    let field_type = self
+                        .types
+                        .get(selected_type)
+                        .and_then(|info| info.fields.get(&field_name));
+                    if let (Some(selection_set), Some(field_type)) =
+                        (field.selection_set(), field_type)
+                    {
+                        self.rename_selections(selection_set, field_type, edits);
+                    }
+                }
+                cst::Selection::InlineFragment(fragment) => {
+                    let condition = fragment
+                        .type_condition()
+                        .and_then(|condition| condition.named_type())
+                        .and_then(|ty| ty.name());
+                    let fragment_type = match condition {
+                        Some(name) => {
+                            let type_name = name.text().to_string();
+                            let renamed = self.type_name(&type_name);
+                            if renamed != type_name {
+                                edits.push(Edit {
+                                    range: text_range(name.syntax()),
+                                    text: renamed.to_string(),
+                                });
+                            }
+                            type_name
+                        }
+                        None => selected_type.to_string(),
+                    };
+                    if let Some(selection_set) = fragment.selection_set() {
+                        self.rename_selections(selection_set, &fragment_type, edits);
+                    }
+                }
+                cst::Selection::FragmentSpread(_) => {}
+            }
+        }
+    }
+
+    /// Applies every edit to the schema. An edit that falls inside a removed node, like a
+    /// description of a stripped directive definition, is dropped along with the node
+    fn apply_edits(&mut self) -> String {
+        self.edits.sort_by(|a, b| {
+            a.range
+                .start
+                .cmp(&b.range.start)
+                .then(b.range.end.cmp(&a.range.end))
+        });
+        let mut masked = String::with_capacity(self.sdl.len());
+        let mut position = 0;
+        for edit in &self.edits {
+            if edit.range.start < position {
+                continue;
+            }
+            masked.push_str(&self.sdl[position..edit.range.start]);
+            masked.push_str(&edit.text);
+            position = edit.range.end;
+        }
+        masked.push_str(&self.sdl[position..]);
+        masked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn test_renames_types_and_fields_with_their_references() {
+        let sdl = indoc! {r#"
+            type Query {
+              """The signed-in user"""
+              me: AcmeUser
+              users(filter: AcmeUserFilter): [AcmeUser!]!
+            }
+
+            interface Node {
+              id: ID!
+            }
+
+            type AcmeUser implements Node @key(fields: "id ssnHash") {
+              id: ID!
+              ssnHash: String @internal
+              team: AcmeTeam
+            }
+
+            type AcmeTeam {
+              members: [AcmeUser] @provides(fields: "ssnHash")
+            }
+
+            input AcmeUserFilter {
+              ssnHash: String
+            }
+
+            directive @internal on FIELD_DEFINITION
+        "#};
+        let config = MaskConfig::parse(indoc! {r#"
+            fields:
+              AcmeUser.ssnHash: taxId
+              AcmeUserFilter.ssnHash: taxId
+            patterns:
+              - match: ^Acme
+                replace: ""
+            strip_directives:
+              - internal
+        "#})
+        .unwrap();
+
+        let masked = mask_schema(sdl, &config).unwrap();
+
+        assert_eq!(
+            masked.sdl,
+            indoc! {r#"
+                type Query {
+                  me: User
+                  users(filter: UserFilter): [User!]!
+                }
+
+                interface Node {
+                  id: ID!
+                }
+
+                type User implements Node @key(fields: "id taxId") {
+                  id: ID!
+                  taxId: String
+                  team: Team
+                }
+
+                type Team {
+                  members: [User] @provides(fields: "taxId")
+                }
+
+                input UserFilter {
+                  taxId: String
+                }
+
+            "#}
+        );
+        assert_eq!(masked.renamed_types, 3);
+        assert_eq!(masked.renamed_fields, 2);
+    }
+
+    #[test]
+    fn test_renames_implementations_of_renamed_interface_fields() {
+        let sdl = indoc! {r#"
+            interface Account {
+              internalScore: Int
+            }
+
+            type User implements Account {
+              internalScore: Int
+            }
+        "#};
+        let config = MaskConfig {
+            fields: BTreeMap::from([("Account.internalScore".to_string(), "score".to_string())]),
+            ..Default::default()
+        };
+
+        let masked = mask_schema(sdl, &config).unwrap();
+
+        assert_eq!(
+            masked.sdl,
+            indoc! {r#"
+                interface Account {
+                  score: Int
+                }
+
+                type User implements Account {
+                  score: Int
+                }
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_keeps_descriptions_when_asked() {
+        let sdl = "\"A product\"\ntype Product {\n  name: String\n}\n";
+        let config = MaskConfig::parse("strip_descriptions: false").unwrap();
+
+        assert_eq!(mask_schema(sdl, &config).unwrap().sdl, sdl);
+    }
+
+    #[rstest]
+    #[case::conflicting_types(
+        "types:\n  User: Account\n",
+        SchemaMaskError::Conflict {
+            first: "Account".to_string(),
+            second: "User".to_string(),
+            name: "Account".to_string(),
+        }
+    )]
+    #[case::unknown_type("types:\n  Missing: Other\n", SchemaMaskError::UnknownType("Missing".to_string()))]
+    #[case::unknown_field("fields:\n  User.missing: other\n", SchemaMaskError::UnknownField("User.missing".to_string()))]
+    #[case::invalid_name(
+        "fields:\n  User.name: full-name\n",
+        SchemaMaskError::InvalidName {
+            coordinate: "User.name".to_string(),
+            name: "full-name".to_string(),
+        }
+    )]
+    fn test_rejects_invalid_renames(#[case] config: &str, #[case] expected: SchemaMaskError) {
+        let sdl = "type Account { id: ID }\ntype User { name: String }\n";
+        let config = MaskConfig::parse(config).unwrap();
+
+        assert_eq!(mask_schema(sdl, &config).unwrap_err(), expected);
+    }
+}
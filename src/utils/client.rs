@@ -1,15 +1,27 @@
 use core::fmt;
-use std::{io, str::FromStr, time::Duration};
+use std::{io, str::FromStr, sync::Arc, time::Duration};
 
-use crate::{options::ProfileOpt, PKG_NAME, PKG_VERSION};
-use anyhow::Result;
+use crate::{
+    options::ProfileOpt,
+    utils::{
+        credentials::CredentialReport,
+        dns::{CachingResolver, DnsCacheStats},
+        response_cache::ResponseCache,
+        runtime_mode::{NetworkDisabled, RuntimeMode},
+        sso,
+    },
+    PKG_NAME, PKG_VERSION,
+};
+use anyhow::{Context, Result};
 
+use camino::Utf8PathBuf;
 use derive_getters::Getters;
 use houston as config;
-use reqwest::Client;
+use reqwest::{Client, Identity};
 use rover_client::blocking::StudioClient;
 
 use rover_http::{HttpService, ReqwestService};
+use rover_std::timing;
 use rover_studio::HttpStudioServiceLayer;
 use serde::Serialize;
 use tower::{ServiceBuilder, ServiceExt};
@@ -18,11 +30,14 @@ use url::Url;
 /// the Apollo graph registry's production API endpoint
 const STUDIO_PROD_API_ENDPOINT: &str = "https://api.apollographql.com/graphql";
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ClientBuilder {
     accept_invalid_certs: bool,
     accept_invalid_hostnames: bool,
     timeout: Option<std::time::Duration>,
+    client_identity: Option<ClientIdentity>,
+    follow_redirects: bool,
+    dns_resolver: Option<CachingResolver>,
 }
 
 impl Default for ClientBuilder {
@@ -37,6 +52,9 @@ impl ClientBuilder {
             accept_invalid_certs: false,
             accept_invalid_hostnames: false,
             timeout: None,
+            client_identity: None,
+            follow_redirects: true,
+            dns_resolver: None,
         }
     }
 
@@ -61,7 +79,30 @@ impl ClientBuilder {
         }
     }
 
-    pub(crate) fn build(self) -> Result<Client> {
+    pub fn with_client_identity(self, client_identity: Option<ClientIdentity>) -> Self {
+        Self {
+            client_identity,
+            ..self
+        }
+    }
+
+    pub fn follow_redirects(self, value: bool) -> Self {
+        Self {
+            follow_redirects: value,
+            ..self
+        }
+    }
+
+    /// Resolves hostnames with `dns_resolver`, sharing its cache with every other client built
+    /// from this builder or its clones
+    pub fn with_dns_resolver(self, dns_resolver: CachingResolver) -> Self {
+        Self {
+            dns_resolver: Some(dns_resolver),
+            ..self
+        }
+    }
+
+    pub(crate) fn build(&self) -> Result<Client> {
         let mut builder = Client::builder()
             .gzip(true)
             .brotli(true)
@@ -72,6 +113,18 @@ impl ClientBuilder {
             builder = builder.timeout(timeout);
         }
 
+        if let Some(client_identity) = &self.client_identity {
+            builder = builder.identity(client_identity.load()?);
+        }
+
+        if !self.follow_redirects {
+            builder = builder.redirect(reqwest::redirect::Policy::none());
+        }
+
+        if let Some(dns_resolver) = &self.dns_resolver {
+            builder = builder.dns_resolver(Arc::new(dns_resolver.clone()));
+        }
+
         let client = builder
             .user_agent(format!("{}/{}", PKG_NAME, PKG_VERSION))
             .build()?;
@@ -80,6 +133,33 @@ impl ClientBuilder {
     }
 }
 
+/// A PEM-encoded certificate and PKCS #8 private key that Rover presents to servers requiring
+/// mutual TLS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIdentity {
+    cert: Utf8PathBuf,
+    key: Utf8PathBuf,
+}
+
+impl ClientIdentity {
+    pub fn new(cert: Utf8PathBuf, key: Utf8PathBuf) -> Self {
+        Self { cert, key }
+    }
+
+    fn load(&self) -> Result<Identity> {
+        let cert = std::fs::read(&self.cert)
+            .with_context(|| format!("Could not read client certificate at {}", self.cert))?;
+        let key = std::fs::read(&self.key)
+            .with_context(|| format!("Could not read client key at {}", self.key))?;
+        Identity::from_pkcs8_pem(&cert, &key).with_context(|| {
+            format!(
+                "Could not use {} and {} as a client certificate and key",
+                self.cert, self.key
+            )
+        })
+    }
+}
+
 #[derive(Debug, Copy, Clone, Serialize)]
 pub(crate) struct ClientTimeout {
     duration: Duration,
@@ -129,6 +209,12 @@ pub struct StudioClientConfig {
     client: Option<Client>,
     #[getter(skip)]
     pub(crate) retry_period: Option<Duration>,
+    #[getter(skip)]
+    runtime_mode: RuntimeMode,
+    #[getter(skip)]
+    dry_run: bool,
+    #[getter(skip)]
+    allow_stale: bool,
 }
 
 impl StudioClientConfig {
@@ -153,9 +239,53 @@ impl StudioClientConfig {
             is_sudo,
             client: None,
             retry_period,
+            runtime_mode: RuntimeMode::default(),
+            dry_run: false,
+            allow_stale: false,
+        }
+    }
+
+    /// Describes the mutations clients would send to Apollo Studio instead of sending them
+    pub fn with_dry_run(self, dry_run: bool) -> Self {
+        Self { dry_run, ..self }
+    }
+
+    /// Whether mutations are described instead of sent, because `--dry-run` was passed
+    pub(crate) fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Serves cached responses to read-only queries when Apollo Studio can't be reached
+    pub fn with_allow_stale(self, allow_stale: bool) -> Self {
+        Self {
+            allow_stale,
+            ..self
         }
     }
 
+    /// Responses to read-only queries, cached for `--allow-stale`
+    pub(crate) fn response_cache(&self) -> ResponseCache {
+        ResponseCache::new(&self.config.home, self.allow_stale)
+    }
+
+    /// Refuses to authenticate with Apollo Studio, or to do anything else that needs the network,
+    /// when `runtime_mode` is offline
+    pub fn with_runtime_mode(self, runtime_mode: RuntimeMode) -> Self {
+        Self {
+            runtime_mode,
+            ..self
+        }
+    }
+
+    pub(crate) fn is_offline(&self) -> bool {
+        self.runtime_mode.is_offline()
+    }
+
+    /// Fails when Rover is running offline, for an `action` that needs the network
+    pub(crate) fn require_network(&self, action: &str) -> Result<(), NetworkDisabled> {
+        self.runtime_mode.require_network(action)
+    }
+
     pub(crate) fn get_reqwest_client(&self) -> Result<Client> {
         if let Some(client) = &self.client {
             Ok(client.clone())
@@ -165,13 +295,29 @@ impl StudioClientConfig {
         }
     }
 
+    /// A client with the same settings as [`Self::get_reqwest_client`] that returns redirect
+    /// responses as they are, for requests that need to read a redirect's headers
+    pub(crate) fn get_reqwest_client_without_redirects(&self) -> Result<Client> {
+        self.client_builder.clone().follow_redirects(false).build()
+    }
+
+    /// How often hostnames were resolved from the DNS cache shared by this config's clients
+    pub(crate) fn dns_cache_stats(&self) -> Option<DnsCacheStats> {
+        self.client_builder
+            .dns_resolver
+            .as_ref()
+            .map(CachingResolver::stats)
+    }
+
     #[cfg(feature = "composition-js")]
     pub(crate) fn get_builder(&self) -> ClientBuilder {
-        self.client_builder
+        self.client_builder.clone()
     }
 
     pub fn get_authenticated_client(&self, profile_opt: &ProfileOpt) -> Result<StudioClient> {
-        let credential = config::Profile::get_credential(&profile_opt.profile_name, &self.config)?;
+        let _timing = timing::time_phase("auth");
+        self.require_network("Talking to Apollo Studio")?;
+        let credential = self.get_credential(profile_opt)?;
         Ok(StudioClient::new(
             credential,
             &self.uri,
@@ -179,12 +325,15 @@ impl StudioClientConfig {
             self.is_sudo,
             self.get_reqwest_client()?,
             self.retry_period,
-        ))
+        )
+        .with_dry_run(self.dry_run))
     }
 
     pub fn authenticated_service(&self, profile_opt: &ProfileOpt) -> Result<HttpService> {
+        let _timing = timing::time_phase("auth");
+        self.require_network("Talking to Apollo Studio")?;
         let client = self.get_reqwest_client()?;
-        let credential = config::Profile::get_credential(&profile_opt.profile_name, &self.config)?;
+        let credential = self.get_credential(profile_opt)?;
         let service = ServiceBuilder::new()
             .layer(HttpStudioServiceLayer::new(
                 Url::from_str(&self.uri)?,
@@ -196,4 +345,68 @@ impl StudioClientConfig {
             .boxed_clone();
         Ok(service)
     }
+
+    /// The credential commands run with `profile_opt` authenticate with, along with a report of
+    /// where it was found, refreshed first when it's a short-lived token from an SSO login that's
+    /// about to expire. Sources are checked in [`CredentialSource::resolution_order`].
+    ///
+    /// [`CredentialSource::resolution_order`]: crate::utils::credentials::CredentialSource::resolution_order
+    pub(crate) fn resolve_credential(
+        &self,
+        profile_opt: &ProfileOpt,
+    ) -> Result<(config::Credential, CredentialReport)> {
+        let profile_name = &profile_opt.profile_name;
+        if let Some(session) = config::Profile::get_sso_session(profile_name, &self.config) {
+            if session.expires_within(sso::REFRESH_MARGIN) {
+                sso::refresh(profile_name, &self.config, &self.client_builder, &session)?;
+            }
+        }
+        let credential = config::Profile::get_credential(profile_name, &self.config)?;
+        let report = CredentialReport::new(&credential, profile_name, &self.config);
+        Ok((credential, report))
+    }
+
+    fn get_credential(&self, profile_opt: &ProfileOpt) -> Result<config::Credential> {
+        let (credential, report) = self.resolve_credential(profile_opt)?;
+        tracing::info!("authenticating with the {report}");
+        Ok(credential)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::{Method::HEAD, MockServer};
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::ClientBuilder;
+
+    #[rstest]
+    #[case::following(true, 200)]
+    #[case::not_following(false, 302)]
+    #[tokio::test]
+    async fn test_follow_redirects(#[case] follow_redirects: bool, #[case] expected_status: u16) {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(HEAD).path("/tar/supergraph/latest-2");
+            then.status(302)
+                .header("x-version", "v2.9.3")
+                .header("location", server.url("/download/supergraph-v2.9.3.tar.gz"));
+        });
+        server.mock(|when, then| {
+            when.method(HEAD).path("/download/supergraph-v2.9.3.tar.gz");
+            then.status(200);
+        });
+
+        let client = ClientBuilder::new()
+            .follow_redirects(follow_redirects)
+            .build()
+            .unwrap();
+        let response = client
+            .head(server.url("/tar/supergraph/latest-2"))
+            .send()
+            .await
+            .unwrap();
+        assert_that!(response.status().as_u16()).is_equal_to(expected_status);
+    }
 }
@@ -0,0 +1,251 @@
+//! Describes the API key a command authenticates with, and the order the places it could come
+//! from are checked in, without revealing the key itself
+
+use std::{borrow::Cow, fmt};
+
+use houston::{mask_key, Config, Credential, CredentialOrigin, CredentialStore, Profile};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::Serialize;
+
+use crate::utils::env::RoverEnvKey;
+
+lazy_static! {
+    // personal keys look like `user:gh.<user-id>:<secret>`, and graph keys like
+    // `service:<graph-id>:<secret>`
+    static ref API_KEY: Regex = Regex::new(r"\b(?:user|service):[\w.\-]+:[\w\-]{8,}").unwrap();
+}
+
+/// Masks anything that looks like an Apollo API key in `text`, for messages that might include
+/// one, like errors echoing a request
+pub fn mask_api_keys(text: &str) -> Cow<'_, str> {
+    API_KEY.replace_all(text, |captures: &Captures| mask_key(&captures[0]))
+}
+
+/// A place an API key can be read from
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CredentialSource {
+    /// The `APOLLO_KEY` env var
+    EnvVar,
+
+    /// The API key saved to a profile
+    Profile(String),
+}
+
+impl CredentialSource {
+    /// The places an API key is read from for commands run with `profile_name`, in the order
+    /// they're checked. The first one with a key is used.
+    pub fn resolution_order(profile_name: &str) -> [CredentialSource; 2] {
+        [
+            CredentialSource::EnvVar,
+            CredentialSource::Profile(profile_name.to_string()),
+        ]
+    }
+}
+
+impl fmt::Display for CredentialSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialSource::EnvVar => write!(f, "${}", RoverEnvKey::Key),
+            CredentialSource::Profile(name) => write!(f, "--profile {name}"),
+        }
+    }
+}
+
+impl From<&CredentialOrigin> for CredentialSource {
+    fn from(origin: &CredentialOrigin) -> Self {
+        match origin {
+            CredentialOrigin::EnvVar => CredentialSource::EnvVar,
+            CredentialOrigin::ConfigFile(name) => CredentialSource::Profile(name.clone()),
+        }
+    }
+}
+
+/// Whether a place in the resolution order had the API key that was used
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionOutcome {
+    /// The API key was read from here
+    Used,
+
+    /// There's no API key here
+    NotSet,
+
+    /// There might be an API key here, but one earlier in the order was used instead
+    Skipped,
+}
+
+/// A place in the resolution order, and whether its API key was used
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ResolutionStep {
+    pub source: String,
+    pub outcome: ResolutionOutcome,
+}
+
+/// The kind of an API key, as told by its prefix
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum KeyKind {
+    /// A personal API key, which can access every graph its user can
+    Personal,
+
+    /// A graph API key, which can only access the graph it was created for
+    Graph { graph_id: String },
+
+    /// A key without a known prefix, like a short-lived token from an SSO login
+    Unknown,
+}
+
+impl KeyKind {
+    pub fn of(api_key: &str) -> Self {
+        let mut parts = api_key.splitn(3, ':');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some("user"), Some(_), Some(_)) => KeyKind::Personal,
+            (Some("service"), Some(graph_id), Some(_)) if !graph_id.is_empty() => KeyKind::Graph {
+                graph_id: graph_id.to_string(),
+            },
+            _ => KeyKind::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for KeyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyKind::Personal => write!(f, "personal"),
+            KeyKind::Graph { graph_id } => write!(f, "graph (scoped to {graph_id})"),
+            KeyKind::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Which API key a command authenticates with and where it came from, with the key masked
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct CredentialReport {
+    pub source: String,
+    /// Where a profile's key is kept, `file` or `keychain`
+    pub store: Option<CredentialStore>,
+    /// Whether the key is a short-lived token from an SSO login
+    pub sso: bool,
+    pub key: KeyKind,
+    pub masked_key: String,
+    pub resolution: Vec<ResolutionStep>,
+}
+
+impl CredentialReport {
+    /// Describes `credential`, which was resolved for commands run with `profile_name`
+    pub fn new(credential: &Credential, profile_name: &str, config: &Config) -> Self {
+        let used = CredentialSource::from(&credential.origin);
+        let resolution = CredentialSource::resolution_order(profile_name)
+            .into_iter()
+            .scan(false, |found, source| {
+                let outcome = if *found {
+                    ResolutionOutcome::Skipped
+                } else if source == used {
+                    *found = true;
+                    ResolutionOutcome::Used
+                } else {
+                    ResolutionOutcome::NotSet
+                };
+                Some(ResolutionStep {
+                    source: source.to_string(),
+                    outcome,
+                })
+            })
+            .collect();
+        let (store, sso) = match &used {
+            CredentialSource::EnvVar => (None, false),
+            CredentialSource::Profile(name) => (
+                Profile::get_store(name, config).ok(),
+                Profile::get_sso_session(name, config).is_some(),
+            ),
+        };
+        Self {
+            source: used.to_string(),
+            store,
+            sso,
+            key: KeyKind::of(&credential.api_key),
+            masked_key: mask_key(&credential.api_key),
+            resolution,
+        }
+    }
+}
+
+impl fmt::Display for CredentialReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} API key {} from {}",
+            self.key, self.masked_key, self.source
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use houston::{Config, Credential, CredentialOrigin, CredentialStore, Profile};
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::{mask_api_keys, CredentialReport, KeyKind, ResolutionOutcome};
+
+    #[rstest]
+    #[case::personal("user:gh.foo:djru4788dhsg3657fhLOLO", KeyKind::Personal)]
+    #[case::graph(
+        "service:my-graph:djru4788dhsg3657fhLOLO",
+        KeyKind::Graph { graph_id: "my-graph".to_string() }
+    )]
+    #[case::token("eyJhbGciOiJSUzI1NiJ9", KeyKind::Unknown)]
+    fn test_key_kind(#[case] api_key: &str, #[case] expected: KeyKind) {
+        assert_that!(KeyKind::of(api_key)).is_equal_to(expected);
+    }
+
+    #[test]
+    fn test_mask_api_keys() {
+        let message = "invalid header value for service:my-graph:djru4788dhsg3657fhLOLO, try again";
+        assert_that!(mask_api_keys(message).to_string()).is_equal_to(
+            "invalid header value for serv*******************************LOLO, try again"
+                .to_string(),
+        );
+        assert_that!(mask_api_keys("user:gh.foo is not a key").to_string())
+            .is_equal_to("user:gh.foo is not a key".to_string());
+    }
+
+    #[test]
+    fn test_reports_the_resolution_order() {
+        let home = TempDir::new().unwrap();
+        let home = Utf8PathBuf::try_from(home.path().to_path_buf()).unwrap();
+        let config = Config::new(Some(&home), None).unwrap();
+        Profile::set_api_key("work", &config, "user:gh.foo:djru4788dhsg3657fhLOLO").unwrap();
+        let credential = Profile::get_credential("work", &config).unwrap();
+
+        let report = CredentialReport::new(&credential, "work", &config);
+        assert_that!(report.source).is_equal_to("--profile work".to_string());
+        assert_that!(report.store).is_equal_to(Some(CredentialStore::File));
+        assert_that!(report.key).is_equal_to(KeyKind::Personal);
+        assert_that!(report
+            .resolution
+            .iter()
+            .map(|step| step.outcome.clone())
+            .collect::<Vec<_>>())
+        .is_equal_to(vec![ResolutionOutcome::NotSet, ResolutionOutcome::Used]);
+
+        let credential = Credential {
+            api_key: "service:my-graph:djru4788dhsg3657fhLOLO".to_string(),
+            origin: CredentialOrigin::EnvVar,
+        };
+        let report = CredentialReport::new(&credential, "work", &config);
+        assert_that!(report.to_string()).is_equal_to(
+            "graph (scoped to my-graph) API key serv*******************************LOLO from $APOLLO_KEY"
+                .to_string(),
+        );
+        assert_that!(report
+            .resolution
+            .iter()
+            .map(|step| step.outcome.clone())
+            .collect::<Vec<_>>())
+        .is_equal_to(vec![ResolutionOutcome::Used, ResolutionOutcome::Skipped]);
+    }
+}
@@ -0,0 +1,254 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use hickory_resolver::{
+    config::{NameServerConfigGroup, ResolverConfig, ResolverOpts},
+    TokioAsyncResolver,
+};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde::Serialize;
+use tokio::sync::OnceCell;
+
+/// How long addresses resolved by the system resolver are cached for. The system resolver doesn't
+/// tell us the TTL of the records it resolved, unlike DNS servers passed with `--dns-server`
+const SYSTEM_LOOKUP_TTL: Duration = Duration::from_secs(30);
+
+/// A DNS server to resolve hostnames with instead of the system resolver, as `IP[:PORT]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DnsServer(SocketAddr);
+
+impl FromStr for DnsServer {
+    type Err = std::net::AddrParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match SocketAddr::from_str(s) {
+            Ok(addr) => Ok(DnsServer(addr)),
+            Err(_) => Ok(DnsServer(SocketAddr::new(IpAddr::from_str(s)?, 53))),
+        }
+    }
+}
+
+impl fmt::Display for DnsServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// How often lookups were answered from the cache of a [`CachingResolver`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DnsCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+impl fmt::Display for DnsCacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses, {} cached hosts",
+            self.hits, self.misses, self.entries
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedLookup {
+    addrs: Vec<IpAddr>,
+    expires_at: Instant,
+}
+
+#[derive(Default)]
+struct CachingResolverState {
+    dns_server: Option<DnsServer>,
+    system_lookup_ttl: Duration,
+    dns_server_resolver: OnceCell<TokioAsyncResolver>,
+    cache: Mutex<HashMap<String, CachedLookup>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Resolves hostnames for Rover's HTTP clients, caching the addresses so that clients polling the
+/// same hosts, like the introspection watchers and healthchecks of `rover dev`, don't wait on DNS
+/// for every request
+///
+/// Clones share their cache, so every client built from the same [`ClientBuilder`] shares it too
+///
+/// [`ClientBuilder`]: crate::utils::client::ClientBuilder
+#[derive(Clone)]
+pub struct CachingResolver {
+    state: Arc<CachingResolverState>,
+}
+
+impl CachingResolver {
+    /// Creates a resolver that asks `dns_server`, or the system resolver when there isn't one
+    pub fn new(dns_server: Option<DnsServer>) -> Self {
+        Self::with_system_lookup_ttl(dns_server, SYSTEM_LOOKUP_TTL)
+    }
+
+    fn with_system_lookup_ttl(dns_server: Option<DnsServer>, system_lookup_ttl: Duration) -> Self {
+        Self {
+            state: Arc::new(CachingResolverState {
+                dns_server,
+                system_lookup_ttl,
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn stats(&self) -> DnsCacheStats {
+        DnsCacheStats {
+            hits: self.state.hits.load(Ordering::Relaxed),
+            misses: self.state.misses.load(Ordering::Relaxed),
+            entries: self.state.cache.lock().unwrap().len(),
+        }
+    }
+
+    /// Resolves `host`, from the cache while its addresses are still fresh
+    pub async fn lookup(
+        &self,
+        host: &str,
+    ) -> Result<Vec<IpAddr>, Box<dyn std::error::Error + Send + Sync>> {
+        let cached = self
+            .state
+            .cache
+            .lock()
+            .unwrap()
+            .get(host)
+            .filter(|cached| cached.expires_at > Instant::now())
+            .map(|cached| cached.addrs.clone());
+        if let Some(addrs) = cached {
+            self.state.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(addrs);
+        }
+        self.state.misses.fetch_add(1, Ordering::Relaxed);
+
+        let (addrs, expires_at) = match self.state.dns_server {
+            Some(DnsServer(dns_server)) => {
+                let resolver = self
+                    .state
+                    .dns_server_resolver
+                    .get_or_init(|| async {
+                        TokioAsyncResolver::tokio(
+                            ResolverConfig::from_parts(
+                                None,
+                                Vec::new(),
+                                NameServerConfigGroup::from_ips_clear(
+                                    &[dns_server.ip()],
+                                    dns_server.port(),
+                                    true,
+                                ),
+                            ),
+                            ResolverOpts::default(),
+                        )
+                    })
+                    .await;
+                let lookup = resolver.lookup_ip(host).await?;
+                (lookup.iter().collect::<Vec<_>>(), lookup.valid_until())
+            }
+            None => (
+                tokio::net::lookup_host((host, 0))
+                    .await?
+                    .map(|addr| addr.ip())
+                    .collect(),
+                Instant::now() + self.state.system_lookup_ttl,
+            ),
+        };
+        tracing::debug!("resolved {host} to {addrs:?}");
+        self.state.cache.lock().unwrap().insert(
+            host.to_string(),
+            CachedLookup {
+                addrs: addrs.clone(),
+                expires_at,
+            },
+        );
+        Ok(addrs)
+    }
+}
+
+impl Resolve for CachingResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let addrs = resolver.lookup(name.as_str()).await?;
+            // reqwest replaces the port with the one in the request's URL
+            let addrs: Addrs = Box::new(addrs.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok(addrs)
+        })
+    }
+}
+
+impl fmt::Debug for CachingResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachingResolver")
+            .field("dns_server", &self.state.dns_server)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl PartialEq for CachingResolver {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, time::Duration};
+
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::{CachingResolver, DnsCacheStats, DnsServer};
+
+    #[rstest]
+    #[case::ip("10.0.0.2", "10.0.0.2:53")]
+    #[case::ip_and_port("10.0.0.2:5353", "10.0.0.2:5353")]
+    #[case::ipv6_and_port("[::1]:5353", "[::1]:5353")]
+    fn test_parse_dns_server(#[case] dns_server: &str, #[case] expected: &str) {
+        let dns_server = DnsServer::from_str(dns_server).unwrap();
+        assert_that!(dns_server.to_string()).is_equal_to(expected.to_string());
+    }
+
+    #[test]
+    fn test_parse_dns_server_rejects_hostnames() {
+        assert_that!(DnsServer::from_str("dns.example.com")).is_err();
+    }
+
+    #[tokio::test]
+    async fn test_lookup_caches_addresses() {
+        let resolver = CachingResolver::new(None);
+        let first = resolver.lookup("localhost").await.unwrap();
+        let second = resolver.lookup("localhost").await.unwrap();
+
+        assert_that!(first).is_equal_to(second);
+        assert_that!(resolver.stats()).is_equal_to(DnsCacheStats {
+            hits: 1,
+            misses: 1,
+            entries: 1,
+        });
+        // clones share the cache
+        resolver.clone().lookup("localhost").await.unwrap();
+        assert_that!(resolver.stats().hits).is_equal_to(2);
+    }
+
+    #[tokio::test]
+    async fn test_lookup_resolves_expired_addresses_again() {
+        let resolver = CachingResolver::with_system_lookup_ttl(None, Duration::ZERO);
+        resolver.lookup("localhost").await.unwrap();
+        resolver.lookup("localhost").await.unwrap();
+
+        assert_that!(resolver.stats().hits).is_equal_to(0);
+        assert_that!(resolver.stats().misses).is_equal_to(2);
+    }
+}
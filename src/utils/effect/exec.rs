@@ -8,7 +8,10 @@ use async_trait::async_trait;
 use buildstructor::Builder;
 use camino::Utf8PathBuf;
 use futures::Future;
-use tokio::process::{Child, Command};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    process::{Child, Command},
+};
 use tower::Service;
 
 #[derive(Builder, Debug)]
@@ -18,6 +21,8 @@ pub struct ExecCommandConfig {
     args: Option<Vec<String>>,
     env: Option<HashMap<String, String>>,
     output: Option<ExecCommandOutput>,
+    /// The most bytes the command may write to stdout before it's killed
+    max_stdout_bytes: Option<u64>,
 }
 
 #[derive(Builder, Default, Debug)]
@@ -45,13 +50,101 @@ pub struct TokioCommand {}
 #[async_trait]
 impl ExecCommand for TokioCommand {
     type Error = std::io::Error;
+    /// Runs the command in a process group of its own, so that if this future is dropped, like
+    /// when it's cancelled or times out, the command and anything it started are killed along with
+    /// it instead of being left running
     async fn exec_command<'a>(&self, config: ExecCommandConfig) -> Result<Output, Self::Error> {
+        let max_stdout_bytes = config.max_stdout_bytes;
         let mut command = Command::new(config.exe.clone());
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(unix)]
+        command.process_group(0);
         let command = build_command(&mut command, config);
-        command.output().await
+
+        let mut child = command.spawn()?;
+        let process_group = ProcessGroupGuard::new(child.id());
+        let output = wait_with_limited_output(&mut child, max_stdout_bytes).await;
+        if output.is_ok() {
+            process_group.disarm();
+        }
+        output
+    }
+}
+
+/// Kills the process group led by a process when dropped, unless it's been disarmed first
+struct ProcessGroupGuard {
+    pgid: Option<u32>,
+}
+
+impl ProcessGroupGuard {
+    fn new(pgid: Option<u32>) -> Self {
+        Self { pgid }
+    }
+
+    fn disarm(mut self) {
+        self.pgid = None;
     }
 }
 
+impl Drop for ProcessGroupGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(pgid) = self.pgid {
+            tracing::debug!("killing process group {}", pgid);
+            // SAFETY: sending a signal has no memory safety requirements
+            unsafe {
+                libc::killpg(pgid as libc::pid_t, libc::SIGKILL);
+            }
+        }
+    }
+}
+
+/// Like [`Child::wait_with_output`], but fails as soon as stdout is over `max_stdout_bytes`
+async fn wait_with_limited_output(
+    child: &mut Child,
+    max_stdout_bytes: Option<u64>,
+) -> std::io::Result<Output> {
+    let (stdout, stderr) = tokio::try_join!(
+        read_to_end(child.stdout.take(), max_stdout_bytes),
+        read_to_end(child.stderr.take(), None)
+    )?;
+    let status = child.wait().await?;
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+async fn read_to_end<R: AsyncRead + Unpin>(
+    reader: Option<R>,
+    max_bytes: Option<u64>,
+) -> std::io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    let Some(mut reader) = reader else {
+        return Ok(buf);
+    };
+    match max_bytes {
+        Some(max_bytes) => {
+            reader.take(max_bytes + 1).read_to_end(&mut buf).await?;
+            if buf.len() as u64 > max_bytes {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("output was larger than the {} bytes allowed", max_bytes),
+                ));
+            }
+        }
+        None => {
+            reader.read_to_end(&mut buf).await?;
+        }
+    }
+    Ok(buf)
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
 pub struct TokioSpawn {}
 
@@ -98,3 +191,81 @@ fn build_command(command: &mut Command, config: ExecCommandConfig) -> &mut Comma
     };
     command
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{process::Command as StdCommand, thread, time::Duration};
+
+    use assert_fs::{prelude::*, TempDir};
+    use camino::Utf8PathBuf;
+    use speculoos::prelude::*;
+
+    use super::{ExecCommand, ExecCommandConfig, TokioCommand};
+
+    fn sh(script: &str) -> ExecCommandConfig {
+        ExecCommandConfig::builder()
+            .exe(Utf8PathBuf::from("sh"))
+            .args(vec!["-c".to_string(), script.to_string()])
+            .build()
+    }
+
+    /// Whether the process is still running, counting zombies that nothing has reaped as gone
+    fn is_running(pid: &str) -> bool {
+        let output = StdCommand::new("ps")
+            .args(["-o", "stat=", "-p", pid])
+            .output()
+            .unwrap();
+        let stat = String::from_utf8_lossy(&output.stdout);
+        !stat.trim().is_empty() && !stat.trim().starts_with('Z')
+    }
+
+    #[tokio::test]
+    async fn test_exec_command_collects_output() {
+        let output = TokioCommand::default()
+            .exec_command(sh("echo out; echo err >&2"))
+            .await
+            .unwrap();
+        assert_that!(output.status.success()).is_true();
+        assert_that!(output.stdout).is_equal_to(b"out\n".to_vec());
+        assert_that!(output.stderr).is_equal_to(b"err\n".to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_exec_command_stops_at_max_stdout_bytes() {
+        let config = ExecCommandConfig::builder()
+            .exe(Utf8PathBuf::from("sh"))
+            .args(vec!["-c".to_string(), "yes | head -c 10000".to_string()])
+            .max_stdout_bytes(100)
+            .build();
+        let err = TokioCommand::default()
+            .exec_command(config)
+            .await
+            .unwrap_err();
+        assert_that!(err.to_string()).contains("larger than the 100 bytes allowed");
+    }
+
+    #[tokio::test]
+    async fn test_exec_command_kills_process_group_when_dropped() {
+        let temp_dir = TempDir::new().unwrap();
+        let pid_file = temp_dir.child("pid");
+        let script = format!("sleep 30 & echo $! > {}; wait", pid_file.path().display());
+
+        let exec_command = TokioCommand::default();
+        let result = tokio::time::timeout(
+            Duration::from_millis(500),
+            exec_command.exec_command(sh(&script)),
+        )
+        .await;
+        assert_that!(result.is_err()).is_true();
+
+        let pid = std::fs::read_to_string(pid_file.path()).unwrap();
+        let pid = pid.trim();
+        for _ in 0..20 {
+            if !is_running(pid) {
+                return;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        panic!("the background `sleep` with pid {pid} outlived the command that started it");
+    }
+}
@@ -1,15 +1,80 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use async_trait::async_trait;
-use rover_client::{blocking::GraphQLClient, operations::subgraph::introspect, RoverClientError};
+use rover_client::{
+    blocking::{GraphQLClient, GraphQLTransport},
+    operations::subgraph::introspect,
+    RoverClientError,
+};
+use serde::Deserialize;
 use url::Url;
 
-use crate::{utils::client::StudioClientConfig, RoverError};
+use crate::{
+    composition::supergraph::config::extended::SchemaUrl,
+    utils::{
+        client::{ClientIdentity, StudioClientConfig},
+        graphql_ws::{self, NegotiateSubscriptionsError, SubscriptionSupport},
+        introspection_auth::{IntrospectionAuthError, IntrospectionAuthenticator},
+        runtime_mode::NetworkDisabled,
+    },
+    RoverError,
+};
+
+/// How the introspection query is sent to a subgraph. Some gateways in front of subgraphs only
+/// let through GET requests or persisted queries
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IntrospectionMode {
+    /// A POST request with the query in a JSON body
+    #[default]
+    Post,
+    /// A GET request with the query encoded in the query string
+    Get,
+    /// An automatic persisted query handshake: the query's hash first, then the full query if
+    /// the subgraph doesn't recognize it
+    Apq,
+}
+
+impl From<IntrospectionMode> for GraphQLTransport {
+    fn from(mode: IntrospectionMode) -> Self {
+        match mode {
+            IntrospectionMode::Post => GraphQLTransport::Post,
+            IntrospectionMode::Get => GraphQLTransport::Get,
+            IntrospectionMode::Apq => GraphQLTransport::PersistedQuery,
+        }
+    }
+}
+
+/// How long to wait on each attempt to introspect a subgraph, how many attempts to make, how to
+/// send each one, and how to authenticate it
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IntrospectionPolicy {
+    /// How long to wait for an introspection response, instead of the client's default timeout
+    pub timeout: Option<Duration>,
+    /// How many times to retry after an attempt times out or gets an HTTP error
+    pub retries: u32,
+    /// How to send the introspection query
+    pub mode: IntrospectionMode,
+    /// Credentials to add to the headers of every attempt, fetched again when they expire
+    pub auth: Option<IntrospectionAuthenticator>,
+}
+
+impl IntrospectionPolicy {
+    const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+    const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+    /// How long to wait before the given retry, counting from zero
+    pub fn backoff(&self, retry: u32) -> Duration {
+        Self::INITIAL_BACKOFF
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(Self::MAX_BACKOFF)
+    }
+}
 
 #[cfg_attr(test, derive(thiserror::Error, Debug))]
 #[cfg_attr(test, error("{}", .0))]
 #[cfg(test)]
-pub struct MockIntrospectSubgraphError(String);
+pub struct MockIntrospectSubgraphError(pub String);
 
 #[cfg_attr(test, mockall::automock(type Error = MockIntrospectSubgraphError;))]
 #[async_trait]
@@ -19,6 +84,26 @@ pub trait IntrospectSubgraph {
         &self,
         endpoint: Url,
         headers: HashMap<String, String>,
+        client_identity: Option<ClientIdentity>,
+        policy: IntrospectionPolicy,
+    ) -> Result<String, Self::Error>;
+
+    /// Finds out whether the subgraph serves subscriptions over a `graphql-transport-ws`
+    /// websocket at the same endpoint it's introspected at
+    async fn negotiate_subscriptions(
+        &self,
+        endpoint: Url,
+        headers: HashMap<String, String>,
+        client_identity: Option<ClientIdentity>,
+    ) -> Result<Option<SubscriptionSupport>, Self::Error>;
+
+    /// Downloads a schema that's served as raw SDL rather than introspected, like the schema of a
+    /// `url` schema source
+    async fn download_schema(
+        &self,
+        schema_url: SchemaUrl,
+        client_identity: Option<ClientIdentity>,
+        timeout: Option<Duration>,
     ) -> Result<String, Self::Error>;
 }
 
@@ -26,8 +111,100 @@ pub trait IntrospectSubgraph {
 pub enum RoverIntrospectSubgraphError {
     #[error("Failed to build the reuest client")]
     Build(RoverError),
-    #[error("Failed to introspect the graphql endpoint")]
-    IntrospectionError(#[from] RoverClientError),
+    #[error("Timed out waiting for an introspection response from {endpoint}")]
+    Timeout {
+        endpoint: Url,
+        source: Box<RoverClientError>,
+    },
+    #[error("Failed to send an introspection request to {endpoint}")]
+    Http {
+        endpoint: Url,
+        source: Box<RoverClientError>,
+    },
+    #[error("Failed to introspect {endpoint} because it returned an invalid introspection result")]
+    InvalidResult {
+        endpoint: Url,
+        source: Box<RoverClientError>,
+    },
+    #[error("Failed to authenticate the introspection request to {endpoint}")]
+    Auth {
+        endpoint: Url,
+        source: Box<IntrospectionAuthError>,
+    },
+    #[error("Failed to negotiate subscriptions with the graphql endpoint")]
+    Subscriptions(#[from] NegotiateSubscriptionsError),
+    #[error("Failed to download the schema from {url}")]
+    Download {
+        url: String,
+        source: Box<reqwest::Error>,
+    },
+    #[error(transparent)]
+    Offline(#[from] NetworkDisabled),
+}
+
+impl RoverIntrospectSubgraphError {
+    /// Sorts an error from introspecting `endpoint` by whether the request timed out, failed to
+    /// get a response, or got a response that isn't a valid introspection result
+    fn from_client_error(endpoint: &Url, error: RoverClientError) -> Self {
+        let endpoint = endpoint.clone();
+        match error {
+            RoverClientError::SendRequest { ref source, .. } if source.is_timeout() => {
+                Self::Timeout {
+                    endpoint,
+                    source: Box::new(error),
+                }
+            }
+            RoverClientError::SendRequest { .. } => Self::Http {
+                endpoint,
+                source: Box::new(error),
+            },
+            RoverClientError::InvalidHeaderName(_) | RoverClientError::InvalidHeaderValue(_) => {
+                Self::Build(RoverError::from(error))
+            }
+            error => Self::InvalidResult {
+                endpoint,
+                source: Box::new(error),
+            },
+        }
+    }
+
+    /// Whether trying again might succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Timeout { .. } | Self::Http { .. } => true,
+            // a schema that isn't there, or that the request isn't allowed to read, won't turn up
+            // for asking again
+            Self::Download { source, .. } => source
+                .status()
+                .map_or(true, |status| !status.is_client_error()),
+            _ => false,
+        }
+    }
+}
+
+impl StudioClientConfig {
+    fn introspection_client(
+        &self,
+        client_identity: Option<ClientIdentity>,
+        timeout: Option<Duration>,
+    ) -> Result<reqwest::Client, RoverIntrospectSubgraphError> {
+        let client = match (client_identity, timeout) {
+            (None, None) => self.get_reqwest_client(),
+            (client_identity, timeout) => {
+                let mut client_builder = self.client_builder().clone();
+                if let Some(client_identity) = client_identity {
+                    client_builder = client_builder.with_client_identity(Some(client_identity));
+                }
+                if let Some(timeout) = timeout {
+                    client_builder = client_builder.with_timeout(timeout);
+                }
+                client_builder.build()
+            }
+        };
+        client
+            .map_err(RoverError::from)
+            .map_err(RoverIntrospectSubgraphError::Build)
+    }
 }
 
 #[async_trait]
@@ -37,25 +214,89 @@ impl IntrospectSubgraph for StudioClientConfig {
         &self,
         endpoint: Url,
         headers: HashMap<String, String>,
+        client_identity: Option<ClientIdentity>,
+        policy: IntrospectionPolicy,
     ) -> Result<String, Self::Error> {
-        let client = self
-            .get_reqwest_client()
-            .map_err(RoverError::from)
-            .map_err(RoverIntrospectSubgraphError::Build)?;
-        let client = GraphQLClient::new(endpoint.as_ref(), client, self.retry_period);
-        let response = introspect::run(
-            introspect::SubgraphIntrospectInput { headers },
-            &client,
-            false,
-        )
-        .await?;
-        Ok(response.result.to_string())
+        let reqwest_client = self.introspection_client(client_identity, policy.timeout)?;
+        let client =
+            GraphQLClient::new(endpoint.as_ref(), reqwest_client.clone(), self.retry_period);
+        let mut retry = 0;
+        loop {
+            let mut headers = headers.clone();
+            if let Some(auth) = &policy.auth {
+                headers.extend(auth.headers(&reqwest_client).await.map_err(|source| {
+                    RoverIntrospectSubgraphError::Auth {
+                        endpoint: endpoint.clone(),
+                        source: Box::new(source),
+                    }
+                })?);
+            }
+            let result = introspect::run(
+                introspect::SubgraphIntrospectInput {
+                    headers,
+                    transport: policy.mode.into(),
+                },
+                &client,
+                false,
+            )
+            .await
+            .map_err(|err| RoverIntrospectSubgraphError::from_client_error(&endpoint, err));
+            match result {
+                Ok(response) => return Ok(response.result.to_string()),
+                Err(err) if err.is_retryable() && retry < policy.retries => {
+                    tracing::debug!(
+                        "retrying introspection of {} after error: {}",
+                        endpoint,
+                        err
+                    );
+                    // the subgraph may have rejected a token that was revoked before it expired
+                    if let Some(auth) = &policy.auth {
+                        auth.invalidate().await;
+                    }
+                    tokio::time::sleep(policy.backoff(retry)).await;
+                    retry += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    async fn negotiate_subscriptions(
+        &self,
+        endpoint: Url,
+        headers: HashMap<String, String>,
+        client_identity: Option<ClientIdentity>,
+    ) -> Result<Option<SubscriptionSupport>, Self::Error> {
+        let client = self.introspection_client(client_identity, None)?;
+        Ok(graphql_ws::negotiate_subscriptions(&client, endpoint, &headers).await?)
+    }
+
+    async fn download_schema(
+        &self,
+        schema_url: SchemaUrl,
+        client_identity: Option<ClientIdentity>,
+        timeout: Option<Duration>,
+    ) -> Result<String, Self::Error> {
+        self.require_network(&format!("Downloading the schema from {}", schema_url.url))?;
+        let client = self.introspection_client(client_identity, timeout)?;
+        schema_url
+            .download(&client, None)
+            .await
+            .map(|download| download.map(|(_, sdl)| sdl).unwrap_or_default())
+            .map_err(|source| RoverIntrospectSubgraphError::Download {
+                url: schema_url.url.clone(),
+                source: Box::new(source),
+            })
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::{collections::HashMap, str::FromStr, time::Duration};
+    use std::{
+        collections::{BTreeMap, HashMap},
+        str::FromStr,
+        time::Duration,
+    };
 
     use anyhow::Result;
     use assert_fs::TempDir;
@@ -66,12 +307,20 @@ mod test {
     use serde_json::json;
     use speculoos::prelude::*;
 
-    use crate::utils::{
-        client::{ClientBuilder, StudioClientConfig},
-        effect::test::SUBGRAPH_INTROSPECTION_QUERY,
+    use crate::{
+        composition::supergraph::config::extended::SchemaUrl,
+        utils::{
+            client::{ClientBuilder, StudioClientConfig},
+            effect::test::SUBGRAPH_INTROSPECTION_QUERY,
+            env::RoverEnv,
+            introspection_auth::{IntrospectionAuth, IntrospectionAuthenticator},
+            runtime_mode::RuntimeMode,
+        },
     };
 
-    use super::IntrospectSubgraph;
+    use super::{
+        IntrospectSubgraph, IntrospectionMode, IntrospectionPolicy, RoverIntrospectSubgraphError,
+    };
 
     #[fixture]
     #[once]
@@ -118,11 +367,236 @@ mod test {
             StudioClientConfig::new(None, config, false, ClientBuilder::default(), None);
         let headers = HashMap::from_iter([("x-test-name".to_string(), "x-test-value".to_string())]);
         let result = studio_client_config
-            .introspect_subgraph(endpoint, headers)
+            .introspect_subgraph(endpoint, headers, None, IntrospectionPolicy::default())
             .await;
         assert_that!(result)
             .is_ok()
             .is_equal_to("type Query { test: String! }".to_string());
         Ok(())
     }
+
+    fn studio_client_config(home: &TempDir) -> StudioClientConfig {
+        let config = Config {
+            home: Utf8PathBuf::from_path_buf(home.path().to_path_buf()).unwrap(),
+            override_api_key: None,
+        };
+        StudioClientConfig::new(None, config, false, ClientBuilder::default(), None)
+    }
+
+    #[rstest]
+    #[timeout(Duration::from_secs(5))]
+    #[tokio::test]
+    async fn test_introspect_subgraph_retries_http_errors() -> Result<()> {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.path("/graphql").method(httpmock::Method::POST);
+            then.status(503);
+        });
+        let endpoint = url::Url::from_str(&server.url("/graphql"))?;
+        let home = TempDir::new()?;
+        let result = studio_client_config(&home)
+            .introspect_subgraph(
+                endpoint,
+                HashMap::new(),
+                None,
+                IntrospectionPolicy {
+                    timeout: None,
+                    retries: 2,
+                    ..Default::default()
+                },
+            )
+            .await;
+        mock.assert_hits(3);
+        assert_that!(matches!(
+            result,
+            Err(RoverIntrospectSubgraphError::Http { .. })
+        ))
+        .is_true();
+        Ok(())
+    }
+
+    #[rstest]
+    #[timeout(Duration::from_secs(5))]
+    #[tokio::test]
+    async fn test_introspect_subgraph_does_not_retry_invalid_results() -> Result<()> {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.path("/graphql").method(httpmock::Method::POST);
+            then.status(200)
+                .json_body(json!({ "data": { "_service": null } }));
+        });
+        let endpoint = url::Url::from_str(&server.url("/graphql"))?;
+        let home = TempDir::new()?;
+        let result = studio_client_config(&home)
+            .introspect_subgraph(
+                endpoint,
+                HashMap::new(),
+                None,
+                IntrospectionPolicy {
+                    timeout: None,
+                    retries: 2,
+                    ..Default::default()
+                },
+            )
+            .await;
+        mock.assert_hits(1);
+        assert_that!(matches!(
+            result,
+            Err(RoverIntrospectSubgraphError::InvalidResult { .. })
+        ))
+        .is_true();
+        Ok(())
+    }
+
+    #[rstest]
+    #[timeout(Duration::from_secs(5))]
+    #[tokio::test]
+    async fn test_introspect_subgraph_with_get_mode() -> Result<()> {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.path("/graphql")
+                .method(httpmock::Method::GET)
+                .query_param_exists("query");
+            then.status(200).json_body(
+                json!({ "data": { "_service": { "sdl": "type Query { name: String }" } } }),
+            );
+        });
+        let endpoint = url::Url::from_str(&server.url("/graphql"))?;
+        let home = TempDir::new()?;
+        let result = studio_client_config(&home)
+            .introspect_subgraph(
+                endpoint,
+                HashMap::new(),
+                None,
+                IntrospectionPolicy {
+                    mode: IntrospectionMode::Get,
+                    ..Default::default()
+                },
+            )
+            .await;
+        mock.assert_hits(1);
+        assert_that!(result.map_err(|err| err.to_string()))
+            .is_ok()
+            .is_equal_to("type Query { name: String }".to_string());
+        Ok(())
+    }
+
+    #[rstest]
+    #[timeout(Duration::from_secs(5))]
+    #[tokio::test]
+    async fn test_introspect_subgraph_with_auth() -> Result<()> {
+        let server = MockServer::start_async().await;
+        let token = server.mock(|when, then| {
+            when.path("/oauth/token").method(httpmock::Method::POST);
+            then.status(200)
+                .json_body(json!({ "access_token": "abc123", "expires_in": 3600 }));
+        });
+        let mock = server.mock(|when, then| {
+            when.path("/graphql")
+                .method(httpmock::Method::POST)
+                .header("Authorization", "Bearer abc123");
+            then.status(200).json_body(
+                json!({ "data": { "_service": { "sdl": "type Query { name: String }" } } }),
+            );
+        });
+        let endpoint = url::Url::from_str(&server.url("/graphql"))?;
+        let home = TempDir::new()?;
+        let policy = IntrospectionPolicy {
+            auth: Some(IntrospectionAuthenticator::new(
+                IntrospectionAuth::OauthClientCredentials {
+                    token_url: url::Url::from_str(&server.url("/oauth/token"))?,
+                    client_id: "rover".to_string(),
+                    client_secret: "hunter2".to_string(),
+                    scope: None,
+                    audience: None,
+                    header: None,
+                },
+            )),
+            ..Default::default()
+        };
+        let client_config = studio_client_config(&home);
+        for _ in 0..2 {
+            let result = client_config
+                .introspect_subgraph(endpoint.clone(), HashMap::new(), None, policy.clone())
+                .await;
+            assert_that!(result.map_err(|err| err.to_string())).is_ok();
+        }
+        mock.assert_hits(2);
+        token.assert_hits(1);
+        Ok(())
+    }
+
+    #[rstest]
+    #[timeout(Duration::from_secs(5))]
+    #[tokio::test]
+    async fn test_introspect_subgraph_times_out() -> Result<()> {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.path("/graphql").method(httpmock::Method::POST);
+            then.status(200).delay(Duration::from_secs(2));
+        });
+        let endpoint = url::Url::from_str(&server.url("/graphql"))?;
+        let home = TempDir::new()?;
+        let result = studio_client_config(&home)
+            .introspect_subgraph(
+                endpoint,
+                HashMap::new(),
+                None,
+                IntrospectionPolicy {
+                    timeout: Some(Duration::from_millis(100)),
+                    retries: 0,
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert_that!(matches!(
+            result,
+            Err(RoverIntrospectSubgraphError::Timeout { .. })
+        ))
+        .is_true();
+        Ok(())
+    }
+
+    #[rstest]
+    #[case::online(false, true)]
+    #[case::offline(true, false)]
+    #[timeout(Duration::from_secs(5))]
+    #[tokio::test]
+    async fn test_download_schema(#[case] offline: bool, #[case] downloaded: bool) -> Result<()> {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.path("/products.graphql")
+                .method(httpmock::Method::GET)
+                .header("authorization", "Bearer token");
+            then.status(200).body("type Query { products: [String] }");
+        });
+        let home = TempDir::new()?;
+        let result = studio_client_config(&home)
+            .with_runtime_mode(RuntimeMode::new(offline, false, &RoverEnv::new()?))
+            .download_schema(
+                SchemaUrl {
+                    url: server.url("/products.graphql"),
+                    headers: Some(BTreeMap::from([(
+                        "Authorization".to_string(),
+                        "Bearer token".to_string(),
+                    )])),
+                },
+                None,
+                None,
+            )
+            .await;
+        if downloaded {
+            assert_that!(result)
+                .is_ok()
+                .is_equal_to("type Query { products: [String] }".to_string());
+        } else {
+            assert_that!(matches!(
+                result,
+                Err(RoverIntrospectSubgraphError::Offline(_))
+            ))
+            .is_true();
+        }
+        mock.assert_hits(usize::from(downloaded));
+        Ok(())
+    }
 }
@@ -100,6 +100,7 @@ pub enum RoverEnvKey {
     RegistryUrl,
     TelemetryUrl,
     TelemetryDisabled,
+    Offline,
     VcsRemoteUrl,
     VcsBranch,
     VcsCommit,
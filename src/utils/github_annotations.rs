@@ -0,0 +1,366 @@
+use std::fmt::{self, Display};
+
+use apollo_federation_types::build_plugin::BuildMessageLocation;
+use apollo_federation_types::rover::{BuildErrors, BuildHint};
+use rover_client::shared::{ChangeSeverity, CheckWorkflowResponse, LintResponse};
+
+/// How prominently GitHub shows an annotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationLevel {
+    Error,
+    Warning,
+    Notice,
+}
+
+impl AnnotationLevel {
+    /// Maps the `ERROR`/`WARNING`/`IGNORED` levels Studio gives lint and custom check results
+    fn from_studio(level: &str) -> Self {
+        match level {
+            "ERROR" => AnnotationLevel::Error,
+            "WARNING" => AnnotationLevel::Warning,
+            _ => AnnotationLevel::Notice,
+        }
+    }
+}
+
+impl Display for AnnotationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnnotationLevel::Error => write!(f, "error"),
+            AnnotationLevel::Warning => write!(f, "warning"),
+            AnnotationLevel::Notice => write!(f, "notice"),
+        }
+    }
+}
+
+/// A GitHub Actions workflow command that annotates a file, or the whole run when there's no
+/// file, like `::error file=schema.graphql,line=3,col=5,title=E031::message`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GithubAnnotation {
+    pub level: AnnotationLevel,
+    pub message: String,
+    pub title: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl GithubAnnotation {
+    pub fn new(level: AnnotationLevel, message: impl Into<String>) -> Self {
+        Self {
+            level,
+            message: message.into(),
+            title: None,
+            file: None,
+            line: None,
+            column: None,
+        }
+    }
+
+    pub fn with_title(self, title: impl Into<String>) -> Self {
+        Self {
+            title: Some(title.into()),
+            ..self
+        }
+    }
+
+    pub fn with_file(self, file: Option<String>) -> Self {
+        Self { file, ..self }
+    }
+
+    pub fn with_position(self, line: Option<usize>, column: Option<usize>) -> Self {
+        Self {
+            // GitHub ignores a column without a line
+            line,
+            column: line.and(column),
+            ..self
+        }
+    }
+}
+
+impl Display for GithubAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let properties = [
+            ("file", self.file.clone()),
+            ("line", self.line.map(|line| line.to_string())),
+            ("col", self.column.map(|column| column.to_string())),
+            ("title", self.title.clone()),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| {
+            value.map(|value| format!("{name}={}", escape_property(&value)))
+        })
+        .collect::<Vec<_>>();
+        if properties.is_empty() {
+            write!(f, "::{}::{}", self.level, escape_data(&self.message))
+        } else {
+            write!(
+                f,
+                "::{} {}::{}",
+                self.level,
+                properties.join(","),
+                escape_data(&self.message)
+            )
+        }
+    }
+}
+
+/// Escapes a message the way `@actions/core` does, so multi-line messages stay one command
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a property value, which additionally can't contain the `:` and `,` separators
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// One annotation per composition error, at the first place in a subgraph it points to
+pub fn from_build_errors(build_errors: &BuildErrors) -> Vec<GithubAnnotation> {
+    build_errors
+        .iter()
+        .map(|error| {
+            let annotation = GithubAnnotation::new(
+                AnnotationLevel::Error,
+                error
+                    .get_message()
+                    .unwrap_or_else(|| "Composition failed".to_string()),
+            );
+            located(
+                annotation,
+                error.get_code(),
+                error.get_nodes().unwrap_or_default().first(),
+            )
+        })
+        .collect()
+}
+
+/// One warning per composition hint
+pub fn from_build_hints(hints: &[BuildHint]) -> Vec<GithubAnnotation> {
+    hints
+        .iter()
+        .map(|hint| {
+            let annotation = GithubAnnotation::new(AnnotationLevel::Warning, &hint.message);
+            located(
+                annotation,
+                hint.code.clone(),
+                hint.nodes.as_ref().and_then(|nodes| nodes.first()),
+            )
+        })
+        .collect()
+}
+
+/// Composition only knows subgraphs by name, so the subgraph goes in the title instead of `file`
+fn located(
+    annotation: GithubAnnotation,
+    code: Option<String>,
+    location: Option<&BuildMessageLocation>,
+) -> GithubAnnotation {
+    let subgraph = location.and_then(|location| location.subgraph.clone());
+    let title = match (code, subgraph) {
+        (Some(code), Some(subgraph)) => Some(format!("{code} in subgraph {subgraph}")),
+        (Some(code), None) => Some(code),
+        (None, Some(subgraph)) => Some(format!("subgraph {subgraph}")),
+        (None, None) => None,
+    };
+    let start = location.and_then(|location| location.start.as_ref());
+    let annotation = annotation.with_position(
+        start.and_then(|start| start.line),
+        start.and_then(|start| start.column),
+    );
+    match title {
+        Some(title) => annotation.with_title(title),
+        None => annotation,
+    }
+}
+
+/// One annotation per lint violation, pointing into the linted file
+pub fn from_lint_response(lint_response: &LintResponse) -> Vec<GithubAnnotation> {
+    lint_response
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let (line, column) =
+                position(&lint_response.proposed_schema, diagnostic.start_byte_offset);
+            GithubAnnotation::new(
+                AnnotationLevel::from_studio(&diagnostic.level),
+                &diagnostic.message,
+            )
+            .with_title(&diagnostic.rule)
+            .with_file(Some(lint_response.file_name.clone()))
+            .with_position(Some(line), Some(column))
+        })
+        .collect()
+}
+
+/// The 1-based line and column of a byte offset in `source`
+fn position(source: &str, byte_offset: usize) -> (usize, usize) {
+    let before = source.get(..byte_offset).unwrap_or(source);
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |line| line.chars().count())
+        + 1;
+    (line, column)
+}
+
+/// Annotations for every check task that failed or warned. Studio doesn't say which file a
+/// schema came from, so these annotate the run, prefixed by `subgraph` when there is one
+pub fn from_check_response(
+    check_response: &CheckWorkflowResponse,
+    subgraph: Option<&str>,
+) -> Vec<GithubAnnotation> {
+    let title = |check: &str| match subgraph {
+        Some(subgraph) => format!("{check} check for subgraph {subgraph}"),
+        None => format!("{check} check"),
+    };
+    let mut annotations = Vec::new();
+    if let Some(operations) = &check_response.maybe_operations_response {
+        annotations.extend(
+            operations
+                .changes()
+                .iter()
+                .filter(|change| matches!(change.severity, ChangeSeverity::FAIL))
+                .map(|change| {
+                    GithubAnnotation::new(
+                        AnnotationLevel::Error,
+                        format!("{}: {}", change.code, change.description),
+                    )
+                    .with_title(title("Operation"))
+                }),
+        );
+    }
+    if let Some(lint) = &check_response.maybe_lint_response {
+        annotations.extend(lint.diagnostics.iter().map(|diagnostic| {
+            let line = usize::try_from(diagnostic.start_line)
+                .ok()
+                .filter(|&line| line > 0);
+            GithubAnnotation::new(
+                AnnotationLevel::from_studio(&diagnostic.level),
+                format!("{} ({})", diagnostic.message, diagnostic.rule),
+            )
+            .with_title(title("Linter"))
+            .with_position(line, None)
+        }));
+    }
+    if let Some(custom) = &check_response.maybe_custom_response {
+        annotations.extend(custom.violations.iter().map(|violation| {
+            let line = violation
+                .start_line
+                .and_then(|line| usize::try_from(line).ok())
+                .filter(|&line| line > 0);
+            GithubAnnotation::new(
+                AnnotationLevel::from_studio(&violation.level),
+                format!("{} ({})", violation.message, violation.rule),
+            )
+            .with_title(title("Custom"))
+            .with_position(line, None)
+        }));
+    }
+    if let Some(proposals) = &check_response.maybe_proposals_response {
+        if proposals.has_unapproved_changes() {
+            annotations.push(
+                GithubAnnotation::new(AnnotationLevel::Warning, proposals.get_msg())
+                    .with_title(title("Proposals")),
+            );
+        }
+    }
+    if let Some(downstream) = &check_response.maybe_downstream_response {
+        if !downstream.failed_variants().is_empty() {
+            annotations.push(
+                GithubAnnotation::new(AnnotationLevel::Error, downstream.get_msg())
+                    .with_title(title("Downstream")),
+            );
+        }
+    }
+    annotations
+}
+
+#[cfg(test)]
+mod tests {
+    use apollo_federation_types::build_plugin::BuildMessagePoint;
+    use apollo_federation_types::rover::BuildError;
+    use rover_client::shared::Diagnostic;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_annotation_escapes_message_and_properties() {
+        let annotation = GithubAnnotation::new(AnnotationLevel::Error, "100% broken\nsee below")
+            .with_title("E031: timeout, again")
+            .with_file(Some("a.graphql".to_string()))
+            .with_position(Some(3), Some(5));
+        assert_that!(annotation.to_string()).is_equal_to(
+            "::error file=a.graphql,line=3,col=5,title=E031%3A timeout%2C again::100%25 broken%0Asee below"
+                .to_string(),
+        );
+        assert_that!(GithubAnnotation::new(AnnotationLevel::Notice, "hi").to_string())
+            .is_equal_to("::notice::hi".to_string());
+    }
+
+    #[test]
+    fn test_build_errors_are_titled_by_subgraph() {
+        let mut location = BuildMessageLocation {
+            subgraph: Some("products".to_string()),
+            source: None,
+            start: Some(BuildMessagePoint {
+                start: None,
+                end: None,
+                line: Some(4),
+                column: Some(2),
+            }),
+            end: None,
+            other: Default::default(),
+        };
+        let mut build_errors = BuildErrors::new();
+        build_errors.push(BuildError::composition_error(
+            Some("FIELD_TYPE_MISMATCH".to_string()),
+            Some("Type mismatch".to_string()),
+            Some(vec![location.clone()]),
+            None,
+        ));
+        location.subgraph = None;
+        build_errors.push(BuildError::composition_error(
+            None,
+            Some("Something else".to_string()),
+            Some(vec![location]),
+            None,
+        ));
+        let annotations = from_build_errors(&build_errors)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_that!(annotations).is_equal_to(vec![
+            "::error line=4,col=2,title=FIELD_TYPE_MISMATCH in subgraph products::Type mismatch"
+                .to_string(),
+            "::error line=4,col=2::Something else".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_lint_violations_point_into_the_file() {
+        let proposed_schema = "type Query {\n  Hello: String\n}\n".to_string();
+        let lint_response = LintResponse {
+            diagnostics: vec![Diagnostic {
+                level: "WARNING".to_string(),
+                message: "Field names should use camelCase style.".to_string(),
+                coordinate: "Query.Hello".to_string(),
+                start_line: 2,
+                start_byte_offset: proposed_schema.find("Hello").unwrap(),
+                end_byte_offset: 0,
+                rule: "FIELD_NAMES_SHOULD_BE_CAMEL_CASE".to_string(),
+            }],
+            file_name: "schema.graphql".to_string(),
+            proposed_schema,
+        };
+        assert_that!(from_lint_response(&lint_response)[0].to_string()).is_equal_to(
+            "::warning file=schema.graphql,line=2,col=3,title=FIELD_NAMES_SHOULD_BE_CAMEL_CASE::Field names should use camelCase style."
+                .to_string(),
+        );
+    }
+}
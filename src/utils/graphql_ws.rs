@@ -0,0 +1,325 @@
+//! Just enough of the websocket protocol and its `graphql-transport-ws` subprotocol to find out
+//! whether a GraphQL endpoint serves subscriptions. Rover never runs subscriptions itself, so this
+//! only opens a connection, waits for the server to acknowledge it, and closes it again
+
+use std::{collections::HashMap, io, time::Duration};
+
+use apollo_parser::{cst, Parser};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use reqwest::{
+    header::{
+        CONNECTION, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_PROTOCOL, SEC_WEBSOCKET_VERSION, UPGRADE,
+    },
+    Client, StatusCode,
+};
+use serde::Serialize;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use url::Url;
+use uuid::Uuid;
+
+/// The websocket subprotocol spoken by the `graphql-ws` library and by the router
+pub const GRAPHQL_TRANSPORT_WS: &str = "graphql-transport-ws";
+
+/// How long the server has to acknowledge the connection once it's been upgraded
+const ACKNOWLEDGEMENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The largest frame we'll read, which is far more than a `connection_ack` ever needs
+const MAX_FRAME_BYTES: u64 = 64 * 1024;
+
+const FIN: u8 = 0x80;
+const MASKED: u8 = 0x80;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xa;
+
+/// Close code for a connection that did what it was opened for
+const NORMAL_CLOSURE: u16 = 1000;
+
+/// How a subgraph serves subscriptions, as found by negotiating a websocket connection with it
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct SubscriptionSupport {
+    /// The endpoint that accepted the websocket connection
+    pub url: Url,
+    /// The websocket subprotocol the endpoint accepted
+    pub protocol: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum NegotiateSubscriptionsError {
+    #[error("Failed to request a websocket connection. {}", .0)]
+    Request(#[from] reqwest::Error),
+    #[error("The websocket connection failed. {}", .0)]
+    Io(#[from] io::Error),
+}
+
+/// Asks `endpoint` to upgrade to a `graphql-transport-ws` websocket connection, and returns how
+/// it serves subscriptions if it accepts and acknowledges a `connection_init` message. Endpoints
+/// that refuse the upgrade, speak another subprotocol, or never acknowledge the connection don't
+/// serve subscriptions in a way the router can use, so they're reported as `None`
+pub async fn negotiate_subscriptions(
+    client: &Client,
+    endpoint: Url,
+    headers: &HashMap<String, String>,
+) -> Result<Option<SubscriptionSupport>, NegotiateSubscriptionsError> {
+    let mut request = client
+        .get(endpoint.clone())
+        .header(CONNECTION, "Upgrade")
+        .header(UPGRADE, "websocket")
+        .header(SEC_WEBSOCKET_VERSION, "13")
+        .header(SEC_WEBSOCKET_KEY, BASE64_STANDARD.encode(Uuid::new_v4()))
+        .header(SEC_WEBSOCKET_PROTOCOL, GRAPHQL_TRANSPORT_WS);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    let response = request.send().await?;
+    if response.status() != StatusCode::SWITCHING_PROTOCOLS {
+        tracing::debug!(
+            "{} answered a websocket upgrade with {}",
+            endpoint,
+            response.status()
+        );
+        return Ok(None);
+    }
+    let accepted_protocol = response
+        .headers()
+        .get(SEC_WEBSOCKET_PROTOCOL)
+        .is_some_and(|protocol| protocol == GRAPHQL_TRANSPORT_WS);
+    if !accepted_protocol {
+        tracing::debug!("{} did not accept {}", endpoint, GRAPHQL_TRANSPORT_WS);
+        return Ok(None);
+    }
+    let mut stream = response.upgrade().await?;
+    match tokio::time::timeout(ACKNOWLEDGEMENT_TIMEOUT, acknowledge(&mut stream)).await {
+        Ok(Ok(true)) => Ok(Some(SubscriptionSupport {
+            url: endpoint,
+            protocol: GRAPHQL_TRANSPORT_WS.to_string(),
+        })),
+        Ok(Ok(false)) => Ok(None),
+        Ok(Err(err)) => Err(err.into()),
+        Err(_) => {
+            tracing::debug!("{} never acknowledged the websocket connection", endpoint);
+            Ok(None)
+        }
+    }
+}
+
+/// Sends `connection_init` and waits for `connection_ack`, answering pings along the way. Servers
+/// send `connection_ack` in a single frame, so fragmented messages are skipped rather than joined
+async fn acknowledge<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut S) -> io::Result<bool> {
+    write_frame(stream, OPCODE_TEXT, br#"{"type":"connection_init"}"#).await?;
+    loop {
+        let (opcode, payload) = read_frame(stream).await?;
+        match opcode {
+            OPCODE_TEXT => {
+                let message: serde_json::Value =
+                    serde_json::from_slice(&payload).unwrap_or_default();
+                match message.get("type").and_then(|kind| kind.as_str()) {
+                    Some("connection_ack") => {
+                        // the answer is in, so there's no need to wait for the server to agree
+                        let _ =
+                            write_frame(stream, OPCODE_CLOSE, &NORMAL_CLOSURE.to_be_bytes()).await;
+                        return Ok(true);
+                    }
+                    Some("ping") => {
+                        write_frame(stream, OPCODE_TEXT, br#"{"type":"pong"}"#).await?;
+                    }
+                    _ => {}
+                }
+            }
+            OPCODE_PING => write_frame(stream, OPCODE_PONG, &payload).await?,
+            OPCODE_CLOSE => return Ok(false),
+            _ => {}
+        }
+    }
+}
+
+async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<(u8, Vec<u8>)> {
+    let mut header = [0; 2];
+    reader.read_exact(&mut header).await?;
+    let opcode = header[0] & 0x0f;
+    let len = match header[1] & 0x7f {
+        126 => u64::from(reader.read_u16().await?),
+        127 => reader.read_u64().await?,
+        len => u64::from(len),
+    };
+    if len > MAX_FRAME_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("received a {len} byte websocket frame, more than the {MAX_FRAME_BYTES} bytes allowed"),
+        ));
+    }
+    let mask = if header[1] & MASKED != 0 {
+        let mut mask = [0; 4];
+        reader.read_exact(&mut mask).await?;
+        Some(mask)
+    } else {
+        None
+    };
+    let mut payload = vec![0; len as usize];
+    reader.read_exact(&mut payload).await?;
+    if let Some(mask) = mask {
+        apply_mask(&mut payload, mask);
+    }
+    Ok((opcode, payload))
+}
+
+/// Writes `payload` as a single frame, masked like every frame a client sends has to be
+async fn write_frame<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    opcode: u8,
+    payload: &[u8],
+) -> io::Result<()> {
+    let mut frame = vec![FIN | opcode];
+    match payload.len() {
+        len @ 0..=125 => frame.push(MASKED | len as u8),
+        len @ 126..=0xffff => {
+            frame.push(MASKED | 126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(MASKED | 127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    let random = Uuid::new_v4().into_bytes();
+    let mask = [random[0], random[1], random[2], random[3]];
+    frame.extend_from_slice(&mask);
+    let payload_start = frame.len();
+    frame.extend_from_slice(payload);
+    apply_mask(&mut frame[payload_start..], mask);
+    writer.write_all(&frame).await?;
+    writer.flush().await
+}
+
+fn apply_mask(payload: &mut [u8], mask: [u8; 4]) {
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+}
+
+/// Whether `sdl` defines or extends a subscription root type
+pub fn schema_has_subscriptions(sdl: &str) -> bool {
+    let document = Parser::new(sdl).parse().document();
+    let is_subscription_type = |name: Option<cst::Name>| {
+        name.map(|name| name.text() == "Subscription")
+            .unwrap_or_default()
+    };
+    document.definitions().any(|definition| match definition {
+        cst::Definition::ObjectTypeDefinition(def) => is_subscription_type(def.name()),
+        cst::Definition::ObjectTypeExtension(ext) => is_subscription_type(ext.name()),
+        cst::Definition::SchemaDefinition(def) => {
+            def.root_operation_type_definitions().any(|root| {
+                root.operation_type()
+                    .and_then(|op| op.subscription_token())
+                    .is_some()
+            })
+        }
+        cst::Definition::SchemaExtension(ext) => {
+            ext.root_operation_type_definitions().any(|root| {
+                root.operation_type()
+                    .and_then(|op| op.subscription_token())
+                    .is_some()
+            })
+        }
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, str::FromStr, time::Duration};
+
+    use httpmock::MockServer;
+    use indoc::indoc;
+    use reqwest::Client;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+    use url::Url;
+
+    use super::{
+        negotiate_subscriptions, read_frame, schema_has_subscriptions, GRAPHQL_TRANSPORT_WS,
+    };
+
+    #[rstest]
+    #[timeout(Duration::from_secs(5))]
+    #[tokio::test]
+    async fn test_negotiate_subscriptions_acknowledged() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = Url::from_str(&format!(
+            "http://{}/graphql",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(socket.read_u8().await.unwrap());
+            }
+            let request = String::from_utf8(request).unwrap().to_lowercase();
+            assert_that!(request).contains("upgrade: websocket");
+            assert_that!(request).contains("sec-websocket-protocol: graphql-transport-ws");
+            assert_that!(request).contains("authorization: bearer token");
+            socket
+                .write_all(
+                    indoc! {"
+                        HTTP/1.1 101 Switching Protocols\r
+                        connection: upgrade\r
+                        upgrade: websocket\r
+                        sec-websocket-protocol: graphql-transport-ws\r
+                        \r
+                    "}
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
+            let (_, init) = read_frame(&mut socket).await.unwrap();
+            let ack = br#"{"type":"connection_ack"}"#;
+            socket.write_all(&[0x81, ack.len() as u8]).await.unwrap();
+            socket.write_all(ack).await.unwrap();
+            String::from_utf8(init).unwrap()
+        });
+
+        let headers =
+            HashMap::from_iter([("authorization".to_string(), "bearer token".to_string())]);
+        let support = negotiate_subscriptions(&Client::new(), endpoint.clone(), &headers)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_that!(support.url).is_equal_to(endpoint);
+        assert_that!(support.protocol.as_str()).is_equal_to(GRAPHQL_TRANSPORT_WS);
+        assert_that!(server.await.unwrap().as_str()).is_equal_to(r#"{"type":"connection_init"}"#);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_negotiate_subscriptions_refused() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.path("/graphql");
+            then.status(400).body("websockets are not supported");
+        });
+        let endpoint = Url::from_str(&server.url("/graphql")).unwrap();
+        let support = negotiate_subscriptions(&Client::new(), endpoint, &HashMap::new())
+            .await
+            .unwrap();
+        assert_that!(support).is_none();
+    }
+
+    #[rstest]
+    #[case::subscription_type("type Query { a: Int } type Subscription { a: Int }", true)]
+    #[case::extension("extend type Subscription { a: Int }", true)]
+    #[case::schema_definition(
+        "schema { query: Query subscription: Changes } type Query { a: Int }",
+        true
+    )]
+    #[case::none("type Query { a: Int } type Mutation { a: Int }", false)]
+    fn test_schema_has_subscriptions(#[case] sdl: &str, #[case] expected: bool) {
+        assert_that!(schema_has_subscriptions(sdl)).is_equal_to(expected);
+    }
+}
@@ -0,0 +1,419 @@
+//! Credentials that are fetched or computed before each introspection request, for subgraphs
+//! that require a token that expires, like one from an OAuth client credentials grant. Tokens are
+//! cached, and refreshed shortly before they expire, so that long-running `rover dev` sessions
+//! keep introspecting after the first token runs out
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+use tokio::{process::Command, sync::Mutex};
+use url::Url;
+
+/// How to authenticate introspection requests to a subgraph
+///
+/// ```yaml
+/// introspection:
+///   auth:
+///     type: oauth_client_credentials
+///     token_url: https://auth.example.com/oauth/token
+///     client_id: rover
+///     client_secret: ${env.PRODUCTS_CLIENT_SECRET}
+///     scope: introspection
+/// ```
+#[derive(Clone, Deserialize, Eq, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum IntrospectionAuth {
+    /// A header with a fixed value
+    Header { name: String, value: String },
+    /// A bearer token from an OAuth 2.0 client credentials grant, fetched again shortly before it
+    /// expires
+    OauthClientCredentials {
+        token_url: Url,
+        client_id: String,
+        client_secret: String,
+        scope: Option<String>,
+        audience: Option<String>,
+        /// The header to send the token in, `Authorization` by default
+        header: Option<String>,
+    },
+    /// A bearer token printed to stdout by a command, run again once `refresh_seconds` have
+    /// passed
+    Exec {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        /// How long a token stays valid, in seconds. 300 by default
+        refresh_seconds: Option<u64>,
+        /// The header to send the token in, `Authorization` by default
+        header: Option<String>,
+    },
+}
+
+impl fmt::Debug for IntrospectionAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Header { name, .. } => f
+                .debug_struct("Header")
+                .field("name", name)
+                .field("value", &"***")
+                .finish(),
+            Self::OauthClientCredentials {
+                token_url,
+                client_id,
+                scope,
+                ..
+            } => f
+                .debug_struct("OauthClientCredentials")
+                .field("token_url", token_url)
+                .field("client_id", client_id)
+                .field("client_secret", &"***")
+                .field("scope", scope)
+                .finish_non_exhaustive(),
+            Self::Exec { command, args, .. } => f
+                .debug_struct("Exec")
+                .field("command", command)
+                .field("args", args)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IntrospectionAuthError {
+    #[error("Failed to request a token from {token_url}")]
+    TokenRequest {
+        token_url: Url,
+        source: reqwest::Error,
+    },
+    #[error("The token response from {token_url} doesn't include an access_token")]
+    InvalidTokenResponse {
+        token_url: Url,
+        source: reqwest::Error,
+    },
+    #[error("Failed to run `{command}` for an introspection token")]
+    Exec {
+        command: String,
+        source: std::io::Error,
+    },
+    #[error("`{command}` exited with {status} instead of printing an introspection token")]
+    ExecFailed { command: String, status: String },
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+#[derive(Clone)]
+struct CachedToken {
+    token: String,
+    /// When to fetch a new token, or never
+    refresh_at: Option<Instant>,
+}
+
+impl CachedToken {
+    fn is_fresh(&self) -> bool {
+        self.refresh_at
+            .map_or(true, |refresh_at| Instant::now() < refresh_at)
+    }
+}
+
+/// An [`IntrospectionAuth`] along with the token it last fetched. Clones share the token, so a
+/// subgraph that's introspected on startup and then polled only fetches it once
+#[derive(Clone, Deserialize)]
+#[serde(from = "IntrospectionAuth")]
+pub struct IntrospectionAuthenticator {
+    auth: IntrospectionAuth,
+    token: Arc<Mutex<Option<CachedToken>>>,
+}
+
+impl IntrospectionAuthenticator {
+    /// How long before a token expires to fetch a new one, so that it doesn't expire in flight
+    const REFRESH_MARGIN: Duration = Duration::from_secs(30);
+    const DEFAULT_EXEC_REFRESH: Duration = Duration::from_secs(300);
+    const AUTHORIZATION: &'static str = "Authorization";
+
+    pub fn new(auth: IntrospectionAuth) -> Self {
+        Self {
+            auth,
+            token: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn auth(&self) -> &IntrospectionAuth {
+        &self.auth
+    }
+
+    /// The headers to send with the next introspection request, fetching a new token with
+    /// `client` first when there isn't one or it's about to expire
+    pub async fn headers(
+        &self,
+        client: &reqwest::Client,
+    ) -> Result<HashMap<String, String>, IntrospectionAuthError> {
+        let (header, token) = match &self.auth {
+            IntrospectionAuth::Header { name, value } => (name.clone(), value.clone()),
+            IntrospectionAuth::OauthClientCredentials { header, .. }
+            | IntrospectionAuth::Exec { header, .. } => (
+                header
+                    .clone()
+                    .unwrap_or_else(|| Self::AUTHORIZATION.to_string()),
+                format!("Bearer {}", self.token(client).await?),
+            ),
+        };
+        Ok(HashMap::from([(header, token)]))
+    }
+
+    /// Forgets the cached token, so the next request fetches a new one. For when a subgraph
+    /// rejects a token before it was due to expire
+    pub async fn invalidate(&self) {
+        self.token.lock().await.take();
+    }
+
+    async fn token(&self, client: &reqwest::Client) -> Result<String, IntrospectionAuthError> {
+        // holding the lock while fetching keeps concurrent requests from fetching a token each
+        let mut cached = self.token.lock().await;
+        if let Some(cached) = cached.as_ref().filter(|cached| cached.is_fresh()) {
+            return Ok(cached.token.clone());
+        }
+        let fetched = match &self.auth {
+            IntrospectionAuth::Header { value, .. } => CachedToken {
+                token: value.clone(),
+                refresh_at: None,
+            },
+            IntrospectionAuth::OauthClientCredentials {
+                token_url,
+                client_id,
+                client_secret,
+                scope,
+                audience,
+                ..
+            } => {
+                tracing::debug!("fetching an introspection token from {}", token_url);
+                let mut form = vec![
+                    ("grant_type", "client_credentials"),
+                    ("client_id", client_id.as_str()),
+                    ("client_secret", client_secret.as_str()),
+                ];
+                if let Some(scope) = scope {
+                    form.push(("scope", scope));
+                }
+                if let Some(audience) = audience {
+                    form.push(("audience", audience));
+                }
+                let response = client
+                    .post(token_url.clone())
+                    .form(&form)
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status())
+                    .map_err(|source| IntrospectionAuthError::TokenRequest {
+                        token_url: token_url.clone(),
+                        source,
+                    })?
+                    .json::<TokenResponse>()
+                    .await
+                    .map_err(|source| IntrospectionAuthError::InvalidTokenResponse {
+                        token_url: token_url.clone(),
+                        source,
+                    })?;
+                CachedToken {
+                    token: response.access_token,
+                    refresh_at: response.expires_in.map(|expires_in| {
+                        Instant::now()
+                            + Duration::from_secs(expires_in).saturating_sub(Self::REFRESH_MARGIN)
+                    }),
+                }
+            }
+            IntrospectionAuth::Exec {
+                command,
+                args,
+                refresh_seconds,
+                ..
+            } => {
+                tracing::debug!("running `{}` for an introspection token", command);
+                let output = Command::new(command)
+                    .args(args)
+                    .kill_on_drop(true)
+                    .output()
+                    .await
+                    .map_err(|source| IntrospectionAuthError::Exec {
+                        command: command.clone(),
+                        source,
+                    })?;
+                if !output.status.success() {
+                    return Err(IntrospectionAuthError::ExecFailed {
+                        command: command.clone(),
+                        status: output.status.to_string(),
+                    });
+                }
+                CachedToken {
+                    token: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+                    refresh_at: Some(
+                        Instant::now()
+                            + refresh_seconds
+                                .map(Duration::from_secs)
+                                .unwrap_or(Self::DEFAULT_EXEC_REFRESH),
+                    ),
+                }
+            }
+        };
+        let token = fetched.token.clone();
+        *cached = Some(fetched);
+        Ok(token)
+    }
+}
+
+impl From<IntrospectionAuth> for IntrospectionAuthenticator {
+    fn from(auth: IntrospectionAuth) -> Self {
+        Self::new(auth)
+    }
+}
+
+impl fmt::Debug for IntrospectionAuthenticator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.auth.fmt(f)
+    }
+}
+
+/// Authenticators are the same when they're configured the same, whatever token they hold
+impl PartialEq for IntrospectionAuthenticator {
+    fn eq(&self, other: &Self) -> bool {
+        self.auth == other.auth
+    }
+}
+
+impl Eq for IntrospectionAuthenticator {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use httpmock::MockServer;
+    use rstest::rstest;
+    use serde_json::json;
+    use speculoos::prelude::*;
+    use url::Url;
+
+    use super::{IntrospectionAuth, IntrospectionAuthenticator};
+
+    fn oauth(server: &MockServer) -> IntrospectionAuthenticator {
+        IntrospectionAuthenticator::new(IntrospectionAuth::OauthClientCredentials {
+            token_url: Url::parse(&server.url("/oauth/token")).unwrap(),
+            client_id: "rover".to_string(),
+            client_secret: "hunter2".to_string(),
+            scope: Some("introspection".to_string()),
+            audience: None,
+            header: None,
+        })
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_header_auth() {
+        let authenticator = IntrospectionAuthenticator::new(IntrospectionAuth::Header {
+            name: "x-api-key".to_string(),
+            value: "secret".to_string(),
+        });
+        let headers = authenticator
+            .headers(&reqwest::Client::new())
+            .await
+            .unwrap();
+        assert_that!(headers.get("x-api-key"))
+            .is_some()
+            .is_equal_to(&"secret".to_string());
+    }
+
+    #[rstest]
+    #[timeout(Duration::from_secs(5))]
+    #[tokio::test]
+    async fn test_oauth_token_is_cached_until_it_expires() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.path("/oauth/token")
+                .method(httpmock::Method::POST)
+                .body_contains("grant_type=client_credentials")
+                .body_contains("scope=introspection");
+            then.status(200)
+                .json_body(json!({ "access_token": "abc123", "expires_in": 3600 }));
+        });
+        let authenticator = oauth(&server);
+        let client = reqwest::Client::new();
+        for _ in 0..2 {
+            let headers = authenticator.clone().headers(&client).await.unwrap();
+            assert_that!(headers.get("Authorization"))
+                .is_some()
+                .is_equal_to(&"Bearer abc123".to_string());
+        }
+        mock.assert_hits(1);
+
+        authenticator.invalidate().await;
+        authenticator.headers(&client).await.unwrap();
+        mock.assert_hits(2);
+    }
+
+    #[rstest]
+    #[timeout(Duration::from_secs(5))]
+    #[tokio::test]
+    async fn test_oauth_token_is_refreshed_before_it_expires() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.path("/oauth/token").method(httpmock::Method::POST);
+            // shorter than the refresh margin, so it's never fresh
+            then.status(200)
+                .json_body(json!({ "access_token": "abc123", "expires_in": 10 }));
+        });
+        let authenticator = oauth(&server);
+        let client = reqwest::Client::new();
+        authenticator.headers(&client).await.unwrap();
+        authenticator.headers(&client).await.unwrap();
+        mock.assert_hits(2);
+    }
+
+    #[rstest]
+    #[timeout(Duration::from_secs(5))]
+    #[tokio::test]
+    async fn test_oauth_token_request_fails() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.path("/oauth/token");
+            then.status(401);
+        });
+        let result = oauth(&server).headers(&reqwest::Client::new()).await;
+        assert_that!(result.map_err(|err| err.to_string()))
+            .is_err()
+            .matches(|err| err.starts_with("Failed to request a token from"));
+    }
+
+    #[cfg(unix)]
+    #[rstest]
+    #[tokio::test]
+    async fn test_exec_auth() {
+        let authenticator = IntrospectionAuthenticator::new(IntrospectionAuth::Exec {
+            command: "echo".to_string(),
+            args: vec!["token-from-exec".to_string()],
+            refresh_seconds: None,
+            header: Some("x-token".to_string()),
+        });
+        let headers = authenticator
+            .headers(&reqwest::Client::new())
+            .await
+            .unwrap();
+        assert_that!(headers.get("x-token"))
+            .is_some()
+            .is_equal_to(&"Bearer token-from-exec".to_string());
+    }
+
+    #[test]
+    fn test_debug_output_hides_secrets() {
+        let auth: IntrospectionAuth =
+            serde_yaml::from_str("type: header\nname: Authorization\nvalue: Bearer hunter2")
+                .unwrap();
+        assert_that!(format!("{auth:?}")).does_not_contain("hunter2");
+    }
+}
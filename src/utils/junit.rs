@@ -0,0 +1,330 @@
+use std::{
+    fmt::{self, Display},
+    time::Duration,
+};
+
+use rover_client::shared::{ChangeSeverity, CheckTaskStatus, CheckWorkflowResponse};
+
+/// What happened to a single check task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JunitOutcome {
+    Passed,
+    Failed {
+        message: String,
+        details: String,
+    },
+    /// The task didn't run, because it was blocked by another task or hasn't finished
+    Skipped {
+        message: String,
+    },
+}
+
+/// A `<testcase>`, one per check task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JunitCase {
+    pub name: String,
+    pub outcome: JunitOutcome,
+}
+
+/// A `<testsuite>`, one per check workflow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JunitSuite {
+    pub name: String,
+    pub cases: Vec<JunitCase>,
+}
+
+impl JunitSuite {
+    fn count(&self, outcome: fn(&JunitOutcome) -> bool) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| outcome(&case.outcome))
+            .count()
+    }
+}
+
+/// A JUnit XML report, for CI systems that show check tasks as test results
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JunitReport {
+    pub suites: Vec<JunitSuite>,
+    /// How long the command took. Studio doesn't report how long each task took, so only the
+    /// report as a whole is timed
+    pub time: Option<Duration>,
+}
+
+impl JunitReport {
+    pub fn new(suites: Vec<JunitSuite>) -> Self {
+        Self { suites, time: None }
+    }
+
+    pub fn with_time(self, time: Option<Duration>) -> Self {
+        Self { time, ..self }
+    }
+}
+
+impl Display for JunitReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = |outcome: fn(&JunitOutcome) -> bool| -> usize {
+            self.suites.iter().map(|suite| suite.count(outcome)).sum()
+        };
+        let time = self
+            .time
+            .map(|time| format!(r#" time="{:.3}""#, time.as_secs_f64()))
+            .unwrap_or_default();
+        writeln!(f, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            f,
+            r#"<testsuites name="rover" tests="{}" failures="{}" skipped="{}"{}>"#,
+            total(|_| true),
+            total(|outcome| matches!(outcome, JunitOutcome::Failed { .. })),
+            total(|outcome| matches!(outcome, JunitOutcome::Skipped { .. })),
+            time
+        )?;
+        for suite in &self.suites {
+            writeln!(
+                f,
+                r#"  <testsuite name="{}" tests="{}" failures="{}" skipped="{}">"#,
+                escape(&suite.name),
+                suite.cases.len(),
+                suite.count(|outcome| matches!(outcome, JunitOutcome::Failed { .. })),
+                suite.count(|outcome| matches!(outcome, JunitOutcome::Skipped { .. })),
+            )?;
+            for case in &suite.cases {
+                let open = format!(
+                    r#"    <testcase name="{}" classname="{}""#,
+                    escape(&case.name),
+                    escape(&suite.name)
+                );
+                match &case.outcome {
+                    JunitOutcome::Passed => writeln!(f, "{open}/>")?,
+                    JunitOutcome::Failed { message, details } => {
+                        writeln!(f, "{open}>")?;
+                        writeln!(
+                            f,
+                            r#"      <failure message="{}">{}</failure>"#,
+                            escape(message),
+                            escape(details)
+                        )?;
+                        writeln!(f, "    </testcase>")?;
+                    }
+                    JunitOutcome::Skipped { message } => {
+                        writeln!(f, "{open}>")?;
+                        writeln!(f, r#"      <skipped message="{}"/>"#, escape(message))?;
+                        writeln!(f, "    </testcase>")?;
+                    }
+                }
+            }
+            writeln!(f, "  </testsuite>")?;
+        }
+        write!(f, "</testsuites>")
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// One test case per task of a check workflow, with what made failed tasks fail as the details
+pub fn from_check_response(check_response: &CheckWorkflowResponse, name: &str) -> JunitSuite {
+    let studio_link = format!(
+        "View the check in Studio: {}",
+        check_response.default_target_url
+    );
+    let case = |task: &str, status: &CheckTaskStatus, message: String, details: Vec<String>| {
+        let outcome = match status {
+            CheckTaskStatus::PASSED => JunitOutcome::Passed,
+            CheckTaskStatus::FAILED => {
+                let mut details = details;
+                details.push(studio_link.clone());
+                JunitOutcome::Failed {
+                    message,
+                    details: details.join("\n"),
+                }
+            }
+            CheckTaskStatus::BLOCKED => JunitOutcome::Skipped {
+                message: "blocked by another task that failed".to_string(),
+            },
+            CheckTaskStatus::PENDING => JunitOutcome::Skipped {
+                message: "still running".to_string(),
+            },
+        };
+        JunitCase {
+            name: task.to_string(),
+            outcome,
+        }
+    };
+
+    let mut cases = Vec::new();
+    if let Some(operations) = &check_response.maybe_operations_response {
+        let failures = operations
+            .changes()
+            .iter()
+            .filter(|change| matches!(change.severity, ChangeSeverity::FAIL))
+            .map(|change| format!("{}: {}", change.code, change.description))
+            .collect::<Vec<_>>();
+        cases.push(case(
+            "Operation check",
+            &operations.task_status,
+            format!("{} schema changes would break operations", failures.len()),
+            failures,
+        ));
+    }
+    if let Some(lint) = &check_response.maybe_lint_response {
+        cases.push(case(
+            "Linter check",
+            &lint.task_status,
+            format!(
+                "{} errors and {} warnings",
+                lint.errors_count, lint.warnings_count
+            ),
+            lint.diagnostics
+                .iter()
+                .map(|diagnostic| {
+                    format!(
+                        "{} {} at line {}: {}",
+                        diagnostic.level,
+                        diagnostic.rule,
+                        diagnostic.start_line,
+                        diagnostic.message
+                    )
+                })
+                .collect(),
+        ));
+    }
+    if let Some(proposals) = &check_response.maybe_proposals_response {
+        cases.push(case(
+            "Proposals check",
+            &proposals.task_status,
+            proposals.get_msg(),
+            proposals
+                .related_proposals
+                .iter()
+                .map(|proposal| format!("{}: {}", proposal.status, proposal.display_name))
+                .collect(),
+        ));
+    }
+    if let Some(custom) = &check_response.maybe_custom_response {
+        cases.push(case(
+            "Custom check",
+            &custom.task_status,
+            format!("{} violations", custom.violations.len()),
+            custom
+                .violations
+                .iter()
+                .map(|violation| match violation.start_line {
+                    Some(line) => format!(
+                        "{} {} at line {}: {}",
+                        violation.level, violation.rule, line, violation.message
+                    ),
+                    None => format!(
+                        "{} {}: {}",
+                        violation.level, violation.rule, violation.message
+                    ),
+                })
+                .collect(),
+        ));
+    }
+    if let Some(downstream) = &check_response.maybe_downstream_response {
+        cases.push(case(
+            "Downstream check",
+            &downstream.task_status,
+            format!(
+                "the checks of blocking downstream variants failed: {}",
+                downstream.blocking_variants.join(", ")
+            ),
+            downstream
+                .failed_variants()
+                .iter()
+                .map(|variant| format!("failed: {}", variant.variant_name))
+                .collect(),
+        ));
+    }
+
+    JunitSuite {
+        name: name.to_string(),
+        cases,
+    }
+}
+
+/// A suite with a single failed test case, for errors that aren't the result of a check
+pub fn from_error(name: &str, code: Option<String>, message: String) -> JunitSuite {
+    JunitSuite {
+        name: name.to_string(),
+        cases: vec![JunitCase {
+            name: code.unwrap_or_else(|| "rover".to_string()),
+            outcome: JunitOutcome::Failed {
+                details: message.clone(),
+                message,
+            },
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rover_client::shared::{LintCheckResponse, OperationCheckResponse, SchemaChange};
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_check_tasks_become_test_cases() {
+        let check_response = CheckWorkflowResponse {
+            default_target_url: "https://studio.apollographql.com/checks/1".to_string(),
+            maybe_core_schema_modified: Some(true),
+            maybe_operations_response: Some(OperationCheckResponse::try_new(
+                CheckTaskStatus::FAILED,
+                None,
+                10,
+                vec![SchemaChange {
+                    code: "FIELD_REMOVED".to_string(),
+                    description: "type `Query`: field `me` removed".to_string(),
+                    severity: ChangeSeverity::FAIL,
+                }],
+            )),
+            maybe_lint_response: Some(LintCheckResponse {
+                task_status: CheckTaskStatus::BLOCKED,
+                target_url: None,
+                diagnostics: Vec::new(),
+                errors_count: 0,
+                warnings_count: 0,
+            }),
+            maybe_proposals_response: None,
+            maybe_custom_response: None,
+            maybe_downstream_response: None,
+        };
+        let report = JunitReport::new(vec![from_check_response(&check_response, "checks")])
+            .with_time(Some(Duration::from_millis(1500)));
+
+        assert_that!(report.to_string()).is_equal_to(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="rover" tests="2" failures="1" skipped="1" time="1.500">
+  <testsuite name="checks" tests="2" failures="1" skipped="1">
+    <testcase name="Operation check" classname="checks">
+      <failure message="1 schema changes would break operations">FIELD_REMOVED: type `Query`: field `me` removed
+View the check in Studio: https://studio.apollographql.com/checks/1</failure>
+    </testcase>
+    <testcase name="Linter check" classname="checks">
+      <skipped message="blocked by another task that failed"/>
+    </testcase>
+  </testsuite>
+</testsuites>"#
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn test_errors_are_escaped() {
+        let report = JunitReport::new(vec![from_error(
+            "checks",
+            Some("E029".to_string()),
+            "<schema> is \"invalid\" & broken".to_string(),
+        )]);
+        assert_that!(report.to_string())
+            .contains(r#"<failure message="&lt;schema&gt; is &quot;invalid&quot; &amp; broken">"#);
+    }
+}
@@ -0,0 +1,259 @@
+//! A registry of the changes between Rover versions that can break existing scripts, along with
+//! what to do about each of them. It backs `rover meta migrations`, and is consulted on every run
+//! to warn about usage that a change has made obsolete.
+
+use rover_std::warnln;
+use semver::Version;
+use serde::Serialize;
+
+/// A change in Rover's behavior that existing usage may need to be migrated for
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+pub struct Migration {
+    /// The version of Rover that introduced the change
+    pub version: &'static str,
+    pub kind: MigrationKind,
+    pub summary: &'static str,
+    /// What to change in existing usage
+    pub migration: &'static str,
+    /// Usage that the change made obsolete, if it can be recognized
+    #[serde(skip_serializing)]
+    pub triggers: &'static [Trigger],
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MigrationKind {
+    RenamedCommand,
+    RemovedCommand,
+    RemovedFlag,
+    ChangedFlag,
+    ChangedDefault,
+    ChangedJson,
+    RenamedEnvVar,
+    ChangedBehavior,
+}
+
+/// Usage that a [`Migration`] made obsolete
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Trigger {
+    /// Consecutive command line arguments
+    Args(&'static [&'static str]),
+    /// An environment variable that is set
+    EnvVar(&'static str),
+}
+
+/// Every known migration, oldest first
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: "0.0.3",
+        kind: MigrationKind::RemovedCommand,
+        summary: "`rover config show` was folded into `rover config whoami`.",
+        migration: "Run `rover config whoami` instead.",
+        triggers: &[Trigger::Args(&["config", "show"])],
+    },
+    Migration {
+        version: "0.0.4",
+        kind: MigrationKind::RenamedCommand,
+        summary: "The `push` commands were renamed to `publish`.",
+        migration: "Run `rover graph publish` or `rover subgraph publish` instead.",
+        triggers: &[
+            Trigger::Args(&["graph", "push"]),
+            Trigger::Args(&["subgraph", "push"]),
+        ],
+    },
+    Migration {
+        version: "0.0.4",
+        kind: MigrationKind::RenamedEnvVar,
+        summary: "`APOLLO_VCS_COMMITTER` was renamed to `APOLLO_VCS_AUTHOR`.",
+        migration: "Set `APOLLO_VCS_AUTHOR` instead.",
+        triggers: &[Trigger::EnvVar("APOLLO_VCS_COMMITTER")],
+    },
+    Migration {
+        version: "0.0.5",
+        kind: MigrationKind::RenamedCommand,
+        summary: "`rover core build` was renamed to `rover supergraph compose`.",
+        migration: "Run `rover supergraph compose` instead.",
+        triggers: &[Trigger::Args(&["core", "build"])],
+    },
+    Migration {
+        version: "0.1.0",
+        kind: MigrationKind::RemovedFlag,
+        summary: "Subcommands no longer accept `-V` or `--version`.",
+        migration: "Run `rover --version` instead.",
+        triggers: &[],
+    },
+    Migration {
+        version: "0.5.0",
+        kind: MigrationKind::RemovedCommand,
+        summary: "`rover fed2 supergraph compose` was removed.",
+        migration: "Set `federation_version: 2` in your supergraph config and run `rover supergraph compose`.",
+        triggers: &[Trigger::Args(&["fed2", "supergraph"])],
+    },
+    Migration {
+        version: "0.6.0",
+        kind: MigrationKind::ChangedBehavior,
+        summary: "Rover talks to `api.apollographql.com` instead of `graphql.api.apollographql.com`.",
+        migration: "Allow the new hostname through your firewall.",
+        triggers: &[],
+    },
+    Migration {
+        version: "0.10.0",
+        kind: MigrationKind::ChangedFlag,
+        summary: "`--header` accepts a single header per use.",
+        migration: "Pass `--header` once for every header.",
+        triggers: &[],
+    },
+    Migration {
+        version: "0.14.0",
+        kind: MigrationKind::ChangedBehavior,
+        summary: "`rover config whoami` prints to stdout instead of stderr.",
+        migration: "Read its output from stdout, or pass `--format json` for structured output.",
+        triggers: &[],
+    },
+    Migration {
+        version: "0.16.0",
+        kind: MigrationKind::ChangedJson,
+        summary: "The JSON output of `rover graph check` and `rover subgraph check` includes lint results.",
+        migration: "Update scripts that parse the output of check commands with `--format json`.",
+        triggers: &[],
+    },
+    Migration {
+        version: "0.16.0",
+        kind: MigrationKind::ChangedBehavior,
+        summary: "Every string in `supergraph.yaml` and `router.yaml` is expanded with `${}` syntax.",
+        migration: "Escape literal `${` in your config files.",
+        triggers: &[],
+    },
+    Migration {
+        version: "0.17.1",
+        kind: MigrationKind::ChangedDefault,
+        summary: "`rover dev` serves the router on port 4000 instead of 3000 by default.",
+        migration: "Pass `--supergraph-port 3000` to keep using the old port.",
+        triggers: &[],
+    },
+    Migration {
+        version: "0.18.0",
+        kind: MigrationKind::ChangedBehavior,
+        summary: "`rover subgraph publish` rejects routing URLs that can't be parsed.",
+        migration: "Pass a valid `--routing-url`, or `--allow-invalid-routing-url` if you're sure.",
+        triggers: &[],
+    },
+    Migration {
+        version: "0.24.0",
+        kind: MigrationKind::RemovedFlag,
+        summary: "`--output plain` and `--output json` were removed, and `--output` only takes a file to write to.",
+        migration: "Pass `--format plain` or `--format json` instead.",
+        triggers: &[
+            Trigger::Args(&["--output", "json"]),
+            Trigger::Args(&["--output", "plain"]),
+            Trigger::Args(&["-o", "json"]),
+            Trigger::Args(&["-o", "plain"]),
+        ],
+    },
+    Migration {
+        version: "0.26.0",
+        kind: MigrationKind::ChangedFlag,
+        summary: "`--client-timeout` is the period over which failed requests are retried.",
+        migration: "Raise `--client-timeout` if long-running requests now time out.",
+        triggers: &[],
+    },
+    Migration {
+        version: "0.27.0",
+        kind: MigrationKind::ChangedBehavior,
+        summary: "Rover exits with 3 for an invalid API key, 4 for a permission error, 5 when a graph isn't found, and 6 when rate limited, instead of 1.",
+        migration: "Treat any non-zero exit status as a failure in scripts that check for 1.",
+        triggers: &[],
+    },
+];
+
+/// The migrations introduced after `from` and up to `to`, oldest first
+pub fn migrations_between(from: Option<&Version>, to: &Version) -> Vec<&'static Migration> {
+    MIGRATIONS
+        .iter()
+        .filter(|migration| {
+            let version = Version::parse(migration.version).expect("migration versions are valid");
+            from.map_or(true, |from| &version > from) && &version <= to
+        })
+        .collect()
+}
+
+/// The migrations that made part of `args` or the environment obsolete
+pub fn triggered_migrations(
+    args: &[String],
+    env_var: impl Fn(&str) -> Option<String>,
+) -> Vec<&'static Migration> {
+    MIGRATIONS
+        .iter()
+        .filter(|migration| {
+            migration.triggers.iter().any(|trigger| match trigger {
+                Trigger::Args(trigger_args) => args.windows(trigger_args.len()).any(|window| {
+                    window
+                        .iter()
+                        .map(String::as_str)
+                        .eq(trigger_args.iter().copied())
+                }),
+                Trigger::EnvVar(key) => env_var(key).is_some(),
+            })
+        })
+        .collect()
+}
+
+/// Warns about every migration that made part of `args` or the environment obsolete
+pub fn warn_about_obsolete_usage(args: &[String], env_var: impl Fn(&str) -> Option<String>) {
+    for migration in triggered_migrations(args, env_var) {
+        warnln!(
+            "{} (changed in v{}) {}",
+            migration.summary,
+            migration.version,
+            migration.migration
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_migrations_are_ordered_by_version() {
+        let versions: Vec<Version> = MIGRATIONS
+            .iter()
+            .map(|migration| Version::parse(migration.version).unwrap())
+            .collect();
+        assert_that!(versions.windows(2).all(|pair| pair[0] <= pair[1])).is_true();
+    }
+
+    #[test]
+    fn test_migrations_between() {
+        let migrations = migrations_between(Some(&Version::new(0, 16, 0)), &Version::new(0, 18, 0));
+        assert_that!(migrations
+            .iter()
+            .map(|migration| migration.version)
+            .collect::<Vec<_>>())
+        .is_equal_to(vec!["0.17.1", "0.18.0"]);
+    }
+
+    #[rstest]
+    #[case::renamed_command(&["rover", "subgraph", "push", "my-graph"], None, vec!["0.0.4"])]
+    #[case::removed_output_format(&["rover", "graph", "fetch", "-o", "json"], None, vec!["0.24.0"])]
+    #[case::renamed_env_var(&["rover", "graph", "check"], Some("APOLLO_VCS_COMMITTER"), vec!["0.0.4"])]
+    #[case::current_usage(&["rover", "graph", "fetch", "--output", "schema.graphql"], None, vec![])]
+    fn test_triggered_migrations(
+        #[case] args: &[&str],
+        #[case] set_env_var: Option<&str>,
+        #[case] expected_versions: Vec<&str>,
+    ) {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let migrations = triggered_migrations(&args, |key| {
+            (Some(key) == set_env_var).then(|| "value".to_string())
+        });
+        assert_that!(migrations
+            .iter()
+            .map(|migration| migration.version)
+            .collect::<Vec<_>>())
+        .is_equal_to(expected_versions);
+    }
+}
@@ -1,14 +1,26 @@
 pub mod client;
+pub mod credentials;
+pub mod dns;
 pub mod effect;
 pub mod env;
+pub mod github_annotations;
+pub mod graphql_ws;
+pub mod introspection_auth;
+pub mod junit;
+pub mod migrations;
 pub mod parsers;
 pub mod pkg;
+pub(crate) mod project;
+pub(crate) mod response_cache;
+pub mod runtime_mode;
+pub(crate) mod sso;
 pub mod stringify;
 #[cfg(feature = "composition-js")]
 pub mod supergraph_config;
 pub mod table;
 pub mod telemetry;
 pub mod version;
+pub(crate) mod yaml_lines;
 
 #[cfg(feature = "composition-js")]
 pub(crate) mod expansion;
@@ -0,0 +1,733 @@
+use std::{env, str::FromStr};
+
+use anyhow::anyhow;
+use apollo_federation_types::config::FederationVersion;
+use buildstructor::Builder;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::{builder::ArgPredicate, Command};
+use houston::{Config, Profile};
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+
+use rover_std::Fs;
+
+use crate::command::install::find_project_config;
+use crate::utils::yaml_lines::{is_trivia, key_at};
+use crate::{RoverError, RoverResult};
+
+/// The ids of the arguments that take a supergraph config, across commands
+const SUPERGRAPH_CONFIG_ARGS: [&str; 2] = ["supergraph_yaml", "supergraph_config_path"];
+
+/// The ids of the arguments that choose subgraphs without a supergraph config. A project's
+/// supergraph config isn't used when any of these is passed
+const SUBGRAPH_SOURCE_ARGS: [&str; 4] = [
+    "graph_ref",
+    "subgraph_name",
+    "subgraph_url",
+    "subgraph_schema_path",
+];
+
+/// The defaults a project sets for Rover's commands at the top level of its
+/// `.apollo/config.yaml`, which look like this:
+///
+/// ```yaml
+/// graph_ref: my-graph@current
+/// profile: my-profile
+/// federation_version: =2.9.3
+/// supergraph_config: supergraph.yaml
+/// router_config: router.yaml
+/// url_map:
+///   - https://*.internal.example.com=>http://localhost:4000
+/// ```
+///
+/// Paths are relative to the project's root, the directory that contains `.apollo`. Options passed
+/// on the command line always take precedence, and the project's defaults take precedence over
+/// those of the configuration profile the command runs with.
+#[derive(Builder, Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub(crate) struct ProjectDefaults {
+    graph_ref: Option<String>,
+    profile: Option<String>,
+    federation_version: Option<FederationVersion>,
+    supergraph_config: Option<Utf8PathBuf>,
+    router_config: Option<Utf8PathBuf>,
+    /// Rules that rewrite the routing URLs of subgraphs in `rover dev`, formatted like `--url-map`
+    url_map: Vec<String>,
+    /// The format to print output in, which only a profile's defaults set
+    #[serde(skip)]
+    output_format: Option<String>,
+    /// What to log, which only a profile's defaults set
+    #[serde(skip)]
+    log: Option<String>,
+}
+
+impl ProjectDefaults {
+    /// Loads the defaults of the project the current directory is in, if any
+    pub(crate) fn for_current_dir() -> RoverResult<Self> {
+        let current_dir = Utf8PathBuf::try_from(env::current_dir()?)?;
+        match find_project_config(&current_dir) {
+            Some(config_path) => Self::load(&config_path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub(crate) fn load(config_path: &Utf8Path) -> RoverResult<Self> {
+        let contents = Fs::read_file(config_path)?;
+        let defaults: Option<Self> = serde_yaml::from_str(&contents)
+            .map_err(|e| RoverError::new(anyhow!("Could not parse {}: {}", config_path, e)))?;
+        let mut defaults = defaults.unwrap_or_default();
+        if let Some(root) = config_path.parent().and_then(Utf8Path::parent) {
+            for path in [&mut defaults.supergraph_config, &mut defaults.router_config]
+                .into_iter()
+                .flatten()
+            {
+                *path = root.join(&*path);
+            }
+        }
+        Ok(defaults)
+    }
+
+    /// Fills in the defaults the project leaves out with those of the configuration profile that
+    /// `args` run with, which is the one passed with `--profile`, or else the project's
+    pub(crate) fn with_profile_defaults(
+        mut self,
+        args: &[String],
+        config: &Config,
+    ) -> RoverResult<Self> {
+        let profile = Profile::get_defaults(self.profile_for(args), config)?;
+        if self.graph_ref.is_none() {
+            self.graph_ref = profile.graph_ref;
+        }
+        if self.federation_version.is_none() {
+            self.federation_version = profile
+                .federation_version
+                .as_deref()
+                .map(FederationVersion::from_str)
+                .transpose()?;
+        }
+        if self.output_format.is_none() {
+            self.output_format = profile.output_format;
+        }
+        if self.log.is_none() {
+            self.log = profile.log;
+        }
+        Ok(self)
+    }
+
+    fn profile_for<'a>(&'a self, args: &'a [String]) -> &'a str {
+        let mut args = args.iter().map(String::as_str);
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                break;
+            } else if arg == "--profile" {
+                if let Some(profile) = args.next() {
+                    return profile;
+                }
+            } else if let Some(profile) = arg.strip_prefix("--profile=") {
+                return profile;
+            }
+        }
+        self.profile.as_deref().unwrap_or("default")
+    }
+
+    /// Writes the defaults that are set to the project config at `config_path`, creating the file
+    /// if it doesn't exist yet. Anything else in the file is kept, as described in
+    /// [`update_project_config`]
+    pub(crate) fn write(&self, config_path: &Utf8Path) -> RoverResult<()> {
+        let defaults = [
+            ("graph_ref", self.graph_ref.clone()),
+            ("profile", self.profile.clone()),
+            (
+                "federation_version",
+                self.federation_version.as_ref().map(ToString::to_string),
+            ),
+            (
+                "supergraph_config",
+                self.supergraph_config.as_ref().map(ToString::to_string),
+            ),
+            (
+                "router_config",
+                self.router_config.as_ref().map(ToString::to_string),
+            ),
+        ];
+        update_project_config(config_path, |config| {
+            for (key, value) in defaults {
+                if let Some(value) = value {
+                    config.insert(Value::from(key), Value::from(value));
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Sets the defaults on `command` and each of its subcommands that take the matching options,
+    /// so they're only used when the options aren't passed
+    pub(crate) fn apply(&self, mut command: Command) -> Command {
+        if let Some(profile) = &self.profile {
+            command = set_default(command, "profile_name", profile, &[]);
+        }
+        if let Some(federation_version) = &self.federation_version {
+            command = set_default(
+                command,
+                "federation_version",
+                &federation_version.to_string(),
+                &[],
+            );
+        }
+        if let Some(output_format) = &self.output_format {
+            command = set_default(command, "format_kind", output_format, &[]);
+        }
+        if let Some(log) = &self.log {
+            command = set_default(command, "log_level", log, &[]);
+        }
+        if let Some(router_config) = &self.router_config {
+            command = set_default(command, "router_config_path", router_config.as_str(), &[]);
+        }
+        if !self.url_map.is_empty() {
+            command = set_default_values(command, "url_map", &self.url_map, &[]);
+        }
+        let composes = SUPERGRAPH_CONFIG_ARGS
+            .iter()
+            .any(|id| has_arg(&command, id));
+        if let Some(graph_ref) = &self.graph_ref {
+            // commands that compose would otherwise merge the graph's subgraphs into the
+            // project's supergraph config
+            if !composes || self.supergraph_config.is_none() {
+                command = set_default(command, "graph_ref", graph_ref, &SUPERGRAPH_CONFIG_ARGS);
+            }
+        }
+        if let Some(supergraph_config) = &self.supergraph_config {
+            for id in SUPERGRAPH_CONFIG_ARGS {
+                command = set_default(
+                    command,
+                    id,
+                    supergraph_config.as_str(),
+                    &SUBGRAPH_SOURCE_ARGS,
+                );
+            }
+        }
+
+        let subcommands: Vec<String> = command
+            .get_subcommands()
+            .map(|subcommand| subcommand.get_name().to_string())
+            .collect();
+        for name in subcommands {
+            command = command.mut_subcommand(name, |subcommand| self.apply(subcommand));
+        }
+        command
+    }
+}
+
+/// Changes the project config at `config_path` with `update`, creating the file if it doesn't
+/// exist yet.
+///
+/// Top-level keys that `update` doesn't change are written back exactly as they were, comments
+/// included, so only the keys that did change lose the comments inside them. When the file is
+/// written in a way that can't be edited key by key, like a flow mapping, the whole file is
+/// rewritten instead. Either way, the new contents have to parse back to what `update` produced,
+/// and they replace the file atomically so that a failed write never leaves it half-written
+pub(crate) fn update_project_config(
+    config_path: &Utf8Path,
+    update: impl FnOnce(&mut Mapping) -> RoverResult<()>,
+) -> RoverResult<()> {
+    let original = if config_path.exists() {
+        Fs::read_file(config_path)?
+    } else {
+        String::new()
+    };
+    let before = match serde_yaml::from_str(&original) {
+        Ok(Value::Mapping(config)) => config,
+        Ok(Value::Null) => Mapping::new(),
+        _ => {
+            return Err(RoverError::new(anyhow!(
+                "Could not update {} because it is not a YAML mapping.",
+                config_path
+            )))
+        }
+    };
+    let mut after = before.clone();
+    update(&mut after)?;
+    if after == before && config_path.exists() {
+        return Ok(());
+    }
+
+    let parses_back = |contents: &str| {
+        serde_yaml::from_str::<Value>(contents).ok() == Some(Value::Mapping(after.clone()))
+    };
+    let contents = match edit_top_level_keys(&original, &before, &after) {
+        Some(contents) if parses_back(&contents) => contents,
+        _ => {
+            let contents = serde_yaml::to_string(&after)?;
+            if !parses_back(&contents) {
+                return Err(RoverError::new(anyhow!(
+                    "Could not update {} because the new contents don't read back the same.",
+                    config_path
+                )));
+            }
+            contents
+        }
+    };
+    Fs::write_file_atomically(config_path, contents)?;
+    Ok(())
+}
+
+/// Rewrites only the top-level keys of `original` whose values differ between `before` and
+/// `after`, removing keys that are gone and appending new ones. Comments and blank lines directly
+/// above a key stay with it. Returns `None` when the keys found in the text don't match the ones
+/// that were parsed from it
+fn edit_top_level_keys(original: &str, before: &Mapping, after: &Mapping) -> Option<String> {
+    let lines: Vec<&str> = original.lines().collect();
+    let starts: Vec<(usize, String)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            key_at(line)
+                .filter(|(indent, _, _)| *indent == 0)
+                .map(|(_, key, _)| (index, key))
+        })
+        .collect();
+    let mut keys: Vec<&str> = starts.iter().map(|(_, key)| key.as_str()).collect();
+    keys.sort_unstable();
+    let mut parsed_keys = before
+        .keys()
+        .map(Value::as_str)
+        .collect::<Option<Vec<_>>>()?;
+    parsed_keys.sort_unstable();
+    if keys != parsed_keys {
+        return None;
+    }
+
+    let render = |key: &str, value: &Value| -> Option<String> {
+        let mut entry = Mapping::new();
+        entry.insert(Value::from(key), value.clone());
+        serde_yaml::to_string(&entry)
+            .ok()
+            .map(|entry| entry.trim_end().to_string())
+    };
+    let mut edited: Vec<String> = Vec::new();
+    let mut cursor = 0;
+    for (position, (start, key)) in starts.iter().enumerate() {
+        let mut end = starts
+            .get(position + 1)
+            .map_or(lines.len(), |(next, _)| *next);
+        if position + 1 < starts.len() {
+            while end > start + 1 && is_trivia(lines[end - 1]) {
+                end -= 1;
+            }
+        }
+        edited.extend(lines[cursor..*start].iter().map(ToString::to_string));
+        let old = before.get(key.as_str());
+        match after.get(key.as_str()) {
+            Some(new) if Some(new) == old => {
+                edited.extend(lines[*start..end].iter().map(ToString::to_string))
+            }
+            Some(new) => edited.push(render(key, new)?),
+            None => {}
+        }
+        cursor = end;
+    }
+    edited.extend(lines[cursor..].iter().map(ToString::to_string));
+    for (key, value) in after {
+        if !before.contains_key(key) {
+            edited.push(render(key.as_str()?, value)?);
+        }
+    }
+
+    let mut contents = edited.join("\n");
+    contents.push('\n');
+    Some(contents)
+}
+
+fn has_arg(command: &Command, id: &str) -> bool {
+    command.get_arguments().any(|arg| arg.get_id() == id)
+}
+
+/// Defaults the argument `id` of `command` to `value`, unless any of the `unless_present`
+/// arguments are passed. Groups that require one of their arguments are satisfied by the default
+fn set_default(command: Command, id: &str, value: &str, unless_present: &[&str]) -> Command {
+    set_default_values(command, id, &[value.to_string()], unless_present)
+}
+
+/// Like [`set_default`], for an argument that takes more than one value
+fn set_default_values(
+    mut command: Command,
+    id: &str,
+    values: &[String],
+    unless_present: &[&str],
+) -> Command {
+    if !has_arg(&command, id) {
+        return command;
+    }
+    let groups: Vec<String> = command
+        .get_groups()
+        .filter(|group| group.is_required_set() && group.get_args().any(|arg| arg == id))
+        .map(|group| group.get_id().to_string())
+        .collect();
+    for group in groups {
+        command = command.mut_group(group, |group| group.required(false));
+    }
+    let unless_present: Vec<String> = unless_present
+        .iter()
+        .filter(|other| has_arg(&command, other))
+        .map(|other| other.to_string())
+        .collect();
+    let values = values.to_vec();
+    command.mut_arg(id, move |arg| {
+        arg.default_value_ifs(
+            unless_present
+                .into_iter()
+                .map(|other| (other, ArgPredicate::IsPresent, None::<&str>)),
+        )
+        .default_values(values)
+        .required(false)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use clap::{ArgMatches, CommandFactory};
+    use houston::{Config, Profile, ProfileDefaults};
+    use indoc::indoc;
+    use rover_client::shared::GraphRef;
+    use rstest::{fixture, rstest};
+    use speculoos::prelude::*;
+    use timber::LogFilter;
+
+    use super::{update_project_config, ProjectDefaults};
+    use crate::cli::{Rover, RoverOutputFormatKind};
+    use crate::command::install::PROJECT_CONFIG_PATH;
+    use crate::utils::parsers::FileDescriptorType;
+
+    struct Project {
+        _temp_dir: TempDir,
+        root: Utf8PathBuf,
+        defaults: ProjectDefaults,
+    }
+
+    #[fixture]
+    fn project() -> Project {
+        let temp_dir = TempDir::new().unwrap();
+        let root = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf()).unwrap();
+        let config_path = root.join(PROJECT_CONFIG_PATH);
+        std::fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &config_path,
+            indoc! {r#"
+                graph_ref: my-graph@current
+                profile: my-profile
+                federation_version: 2
+                supergraph_config: supergraph.yaml
+                router_config: /etc/router.yaml
+                url_map:
+                  - https://*.internal.example.com=>http://localhost:4000
+                plugins:
+                  router: 1.57.1
+            "#},
+        )
+        .unwrap();
+        let defaults = ProjectDefaults::load(&config_path).unwrap();
+        Project {
+            _temp_dir: temp_dir,
+            root,
+            defaults,
+        }
+    }
+
+    /// The matches of the innermost subcommand `args` run
+    fn leaf_matches(defaults: &ProjectDefaults, args: &[&str]) -> ArgMatches {
+        let mut matches = defaults
+            .apply(Rover::command())
+            .try_get_matches_from(args)
+            .unwrap();
+        while let Some((_, subcommand)) = matches.subcommand() {
+            matches = subcommand.clone();
+        }
+        matches
+    }
+
+    #[rstest]
+    fn test_load_resolves_paths_from_the_project_root(project: Project) {
+        assert_that!(project.defaults.supergraph_config)
+            .is_equal_to(Some(project.root.join("supergraph.yaml")));
+        assert_that!(project.defaults.router_config)
+            .is_equal_to(Some(Utf8PathBuf::from("/etc/router.yaml")));
+    }
+
+    #[rstest]
+    fn test_write_keeps_other_keys(project: Project) {
+        let config_path = project.root.join(PROJECT_CONFIG_PATH);
+        ProjectDefaults::builder()
+            .graph_ref("other-graph@dev")
+            .supergraph_config("graph/supergraph.yaml")
+            .build()
+            .write(&config_path)
+            .unwrap();
+
+        let written = std::fs::read_to_string(&config_path).unwrap();
+        assert_that!(written).contains("router: 1.57.1");
+        let defaults = ProjectDefaults::load(&config_path).unwrap();
+        assert_that!(defaults.graph_ref).is_equal_to(Some("other-graph@dev".to_string()));
+        assert_that!(defaults.profile).is_equal_to(Some("my-profile".to_string()));
+        assert_that!(defaults.supergraph_config)
+            .is_equal_to(Some(project.root.join("graph/supergraph.yaml")));
+    }
+
+    #[rstest]
+    fn test_apply_keeps_the_command_valid(project: Project) {
+        project.defaults.apply(Rover::command()).debug_assert();
+    }
+
+    #[rstest]
+    #[case::defaulted(&["rover", "subgraph", "publish", "--name", "products", "--schema", "products.graphql"], "my-graph@current", "my-profile")]
+    #[case::overridden(
+        &["rover", "subgraph", "publish", "other-graph@dev", "--name", "products", "--schema", "products.graphql", "--profile", "other"],
+        "other-graph@dev",
+        "other"
+    )]
+    fn test_apply_defaults_graph_ref_and_profile(
+        project: Project,
+        #[case] args: &[&str],
+        #[case] graph_ref: &str,
+        #[case] profile: &str,
+    ) {
+        let matches = leaf_matches(&project.defaults, args);
+        assert_that!(matches
+            .get_one::<GraphRef>("graph_ref")
+            .map(ToString::to_string))
+        .is_equal_to(Some(graph_ref.to_string()));
+        assert_that!(matches.get_one::<String>("profile_name"))
+            .is_equal_to(Some(&profile.to_string()));
+    }
+
+    #[rstest]
+    fn test_apply_prefers_the_supergraph_config_when_composing(project: Project) {
+        let matches = leaf_matches(&project.defaults, &["rover", "supergraph", "compose"]);
+        assert_that!(matches.get_one::<FileDescriptorType>("supergraph_yaml")).is_equal_to(Some(
+            &FileDescriptorType::File(project.root.join("supergraph.yaml")),
+        ));
+        assert_that!(matches.get_one::<GraphRef>("graph_ref")).is_none();
+
+        let matches = leaf_matches(
+            &project.defaults,
+            &[
+                "rover",
+                "supergraph",
+                "compose",
+                "--graph-ref",
+                "other-graph@dev",
+            ],
+        );
+        assert_that!(matches.get_one::<FileDescriptorType>("supergraph_yaml")).is_none();
+    }
+
+    #[rstest]
+    #[case::defaulted(&["rover", "dev"], vec!["https://*.internal.example.com=>http://localhost:4000"])]
+    #[case::overridden(
+        &["rover", "dev", "--url-map", "http://*=>http://localhost:5000"],
+        vec!["http://*=>http://localhost:5000"]
+    )]
+    fn test_apply_defaults_url_map(
+        project: Project,
+        #[case] args: &[&str],
+        #[case] expected: Vec<&str>,
+    ) {
+        let matches = leaf_matches(&project.defaults, args);
+        let url_map: Vec<String> = matches
+            .get_raw("url_map")
+            .into_iter()
+            .flatten()
+            .map(|rule| rule.to_string_lossy().to_string())
+            .collect();
+        assert_that!(url_map).is_equal_to(
+            expected
+                .into_iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[rstest]
+    #[case::project_profile(&["rover", "subgraph", "fetch", "--name", "products"], RoverOutputFormatKind::Json)]
+    #[case::passed_profile(&["rover", "subgraph", "fetch", "--name", "products", "--profile", "personal"], RoverOutputFormatKind::Plain)]
+    #[case::passed_profile_with_equals(&["rover", "subgraph", "fetch", "--name", "products", "--profile=personal"], RoverOutputFormatKind::Plain)]
+    fn test_with_profile_defaults_fills_in_what_the_project_leaves_out(
+        project: Project,
+        #[case] args: &[&str],
+        #[case] format: RoverOutputFormatKind,
+    ) {
+        let config = Config::new(Some(&project.root.join("home")), None).unwrap();
+        for (profile, output_format) in [("my-profile", Some("json")), ("personal", None)] {
+            Profile::set_api_key(profile, &config, "key").unwrap();
+            let defaults = ProfileDefaults {
+                graph_ref: Some("profile-graph@current".to_string()),
+                federation_version: Some("=2.9.3".to_string()),
+                output_format: output_format.map(ToString::to_string),
+                log: None,
+            };
+            Profile::set_defaults(profile, &config, &defaults).unwrap();
+        }
+        let owned_args: Vec<String> = args.iter().map(ToString::to_string).collect();
+
+        let defaults = project
+            .defaults
+            .clone()
+            .with_profile_defaults(&owned_args, &config)
+            .unwrap();
+
+        // the project's graph ref and federation version take precedence over the profile's
+        assert_that!(defaults.federation_version)
+            .is_equal_to(project.defaults.federation_version.clone());
+        let matches = defaults
+            .apply(Rover::command())
+            .try_get_matches_from(args)
+            .unwrap();
+        assert_that!(matches.get_one::<RoverOutputFormatKind>("format_kind"))
+            .is_equal_to(Some(&format));
+        assert_that!(leaf_matches(&defaults, args)
+            .get_one::<GraphRef>("graph_ref")
+            .map(ToString::to_string))
+        .is_equal_to(Some("my-graph@current".to_string()));
+    }
+
+    #[test]
+    fn test_with_profile_defaults_without_a_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let home = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf()).unwrap();
+        let config = Config::new(Some(&home), None).unwrap();
+        Profile::set_api_key("default", &config, "key").unwrap();
+        let profile_defaults = ProfileDefaults {
+            graph_ref: Some("profile-graph@current".to_string()),
+            ..Default::default()
+        };
+        Profile::set_defaults("default", &config, &profile_defaults).unwrap();
+
+        let defaults = ProjectDefaults::default()
+            .with_profile_defaults(&["rover".to_string()], &config)
+            .unwrap();
+        assert_that!(defaults.graph_ref).is_equal_to(Some("profile-graph@current".to_string()));
+    }
+
+    #[rstest]
+    #[case::from_profile(&["rover", "config", "list"], "info,composition=trace")]
+    #[case::passed(&["rover", "config", "list", "--log", "warn"], "warn")]
+    fn test_apply_sets_the_profiles_log_filter(#[case] args: &[&str], #[case] expected: &str) {
+        let temp_dir = TempDir::new().unwrap();
+        let home = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf()).unwrap();
+        let config = Config::new(Some(&home), None).unwrap();
+        Profile::set_api_key("default", &config, "key").unwrap();
+        let profile_defaults = ProfileDefaults {
+            log: Some("info,composition=trace".to_string()),
+            ..Default::default()
+        };
+        Profile::set_defaults("default", &config, &profile_defaults).unwrap();
+
+        let defaults = ProjectDefaults::default()
+            .with_profile_defaults(&["rover".to_string()], &config)
+            .unwrap();
+        let matches = defaults
+            .apply(Rover::command())
+            .try_get_matches_from(args)
+            .unwrap();
+        assert_that!(matches
+            .get_one::<LogFilter>("log_level")
+            .map(ToString::to_string))
+        .is_equal_to(Some(expected.to_string()));
+    }
+
+    #[rstest]
+    fn test_apply_skips_the_supergraph_config_for_a_single_subgraph(project: Project) {
+        let matches = leaf_matches(
+            &project.defaults,
+            &[
+                "rover",
+                "dev",
+                "--name",
+                "products",
+                "--url",
+                "http://localhost:4001",
+            ],
+        );
+        assert_that!(matches.get_one::<FileDescriptorType>("supergraph_config_path")).is_none();
+        assert_that!(matches.get_one::<Utf8PathBuf>("router_config_path"))
+            .is_equal_to(Some(&Utf8PathBuf::from("/etc/router.yaml")));
+    }
+
+    #[rstest]
+    #[case::changed_key(
+        indoc! {r#"
+            # the graph this project publishes to
+            graph_ref: my-graph@current
+            # the profile to authenticate with
+            profile: my-profile
+            plugins:
+              # keep in sync with CI
+              router: 1.57.1
+        "#},
+        None,
+        indoc! {r#"
+            # the graph this project publishes to
+            graph_ref: other-graph@dev
+            # the profile to authenticate with
+            profile: my-profile
+            plugins:
+              # keep in sync with CI
+              router: 1.57.1
+        "#}
+    )]
+    #[case::new_key(
+        indoc! {r#"
+            # the graph this project publishes to
+            graph_ref: other-graph@dev
+        "#},
+        Some("supergraph.yaml"),
+        indoc! {r#"
+            # the graph this project publishes to
+            graph_ref: other-graph@dev
+            supergraph_config: supergraph.yaml
+        "#}
+    )]
+    #[case::flow_mapping(
+        "{ graph_ref: my-graph@current, profile: my-profile } # not kept\n",
+        Some("supergraph.yaml"),
+        "graph_ref: other-graph@dev\nprofile: my-profile\nsupergraph_config: supergraph.yaml\n"
+    )]
+    fn test_write_keeps_unchanged_keys_as_written(
+        #[case] original: &str,
+        #[case] supergraph_config: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path =
+            Utf8PathBuf::from_path_buf(temp_dir.path().join(PROJECT_CONFIG_PATH)).unwrap();
+        std::fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+        std::fs::write(&config_path, original).unwrap();
+
+        ProjectDefaults::builder()
+            .graph_ref("other-graph@dev")
+            .and_supergraph_config(supergraph_config.map(Utf8PathBuf::from))
+            .build()
+            .write(&config_path)
+            .unwrap();
+
+        assert_that!(std::fs::read_to_string(&config_path).unwrap())
+            .is_equal_to(expected.to_string());
+        let leftovers: Vec<_> = std::fs::read_dir(config_path.parent().unwrap())
+            .unwrap()
+            .collect();
+        assert_that!(leftovers).has_length(1);
+    }
+
+    #[rstest]
+    fn test_update_project_config_rejects_non_mappings() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = Utf8PathBuf::from_path_buf(temp_dir.path().join("config.yaml")).unwrap();
+        std::fs::write(&config_path, "- not\n- a mapping\n").unwrap();
+
+        let err = update_project_config(&config_path, |_| Ok(())).unwrap_err();
+        assert_that!(err.to_string()).contains("is not a YAML mapping");
+        assert_that!(std::fs::read_to_string(&config_path).unwrap())
+            .is_equal_to("- not\n- a mapping\n".to_string());
+    }
+}
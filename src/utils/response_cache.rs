@@ -0,0 +1,226 @@
+//! Successful responses of read-only queries to Apollo Studio, kept on disk so that read commands
+//! run with `--allow-stale` can fall back to them when Studio can't be reached
+
+use std::future::Future;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, Duration, Utc};
+use rover_std::{Fs, Style};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{RoverErrorSuggestion, RoverResult};
+
+/// A cached response, along with when it was fetched
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry<T> {
+    cached_at: DateTime<Utc>,
+    value: T,
+}
+
+/// Responses are cached in `$APOLLO_CONFIG_HOME/cache/responses`, a file per query and set of
+/// inputs
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseCache {
+    dir: Utf8PathBuf,
+    allow_stale: bool,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(config_home: &Utf8Path, allow_stale: bool) -> Self {
+        Self {
+            dir: config_home.join("cache").join("responses"),
+            allow_stale,
+        }
+    }
+
+    /// Runs `fetch`, caching what it returns under `query` and `inputs`, such as the graph ref
+    /// and profile it ran with. When Studio can't be reached and `--allow-stale` was passed, a
+    /// cached response younger than `ttl` is returned instead, with a warning saying how old it is.
+    pub(crate) async fn fetch<T, F>(
+        &self,
+        query: &str,
+        inputs: &[&str],
+        ttl: Duration,
+        fetch: F,
+    ) -> RoverResult<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: Future<Output = RoverResult<T>>,
+    {
+        let path = self.path(query, inputs);
+        match fetch.await {
+            Ok(value) => {
+                self.store(&path, &value);
+                Ok(value)
+            }
+            Err(error) if error.is_studio_unreachable() => {
+                if !self.allow_stale {
+                    let mut error = error;
+                    if self.load::<T>(&path, ttl).is_some() {
+                        error.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                            "A cached response from an earlier run is available. Pass {} to use it.",
+                            Style::Command.paint("`--allow-stale`")
+                        )));
+                    }
+                    return Err(error);
+                }
+                match self.load::<T>(&path, ttl) {
+                    Some(entry) => {
+                        // printed even with `--quiet`, since the output may be out of date
+                        eprintln!(
+                            "{} Apollo Studio couldn't be reached, so this is a cached response that is stale as of {}.",
+                            Style::WarningPrefix.paint("warning:"),
+                            entry.cached_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+                        );
+                        Ok(entry.value)
+                    }
+                    None => Err(error),
+                }
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    fn path(&self, query: &str, inputs: &[&str]) -> Utf8PathBuf {
+        let mut hasher = Sha256::new();
+        for input in inputs {
+            hasher.update(input.as_bytes());
+            // keeps ["ab", "c"] and ["a", "bc"] apart
+            hasher.update([0]);
+        }
+        let digest = hasher.finalize();
+        let mut name = query.to_string();
+        name.push('-');
+        for byte in digest.iter().take(12) {
+            name.push_str(&format!("{:02x}", byte));
+        }
+        self.dir.join(format!("{}.json", name))
+    }
+
+    fn store<T: Serialize>(&self, path: &Utf8Path, value: &T) {
+        let entry = CacheEntry {
+            cached_at: Utc::now(),
+            value,
+        };
+        // a response that can't be cached is still a response
+        let stored = serde_json::to_string(&entry)
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| Ok(Fs::write_file(path, contents)?));
+        if let Err(error) = stored {
+            tracing::debug!("could not cache the response at {}: {}", path, error);
+        }
+    }
+
+    fn load<T: DeserializeOwned>(&self, path: &Utf8Path, ttl: Duration) -> Option<CacheEntry<T>> {
+        let contents = Fs::read_file(path).ok()?;
+        let entry: CacheEntry<T> = serde_json::from_str(&contents).ok()?;
+        if Utc::now() - entry.cached_at > ttl {
+            let _ = std::fs::remove_file(path);
+            return None;
+        }
+        Some(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use assert_fs::TempDir;
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use rover_client::RoverClientError;
+
+    use super::*;
+    use crate::utils::runtime_mode::NetworkDisabled;
+    use crate::RoverError;
+
+    fn cache(dir: &TempDir, allow_stale: bool) -> ResponseCache {
+        let home = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+        ResponseCache::new(&home, allow_stale)
+    }
+
+    fn offline() -> RoverResult<String> {
+        Err(RoverError::new(NetworkDisabled {
+            action: "Talking to Apollo Studio".to_string(),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_stale_responses_are_served_only_when_allowed() {
+        let dir = TempDir::new().unwrap();
+        let inputs = ["default", "my-graph@current"];
+        let ttl = Duration::days(1);
+        let fetched = cache(&dir, false)
+            .fetch("graph-fetch", &inputs, ttl, async {
+                Ok("type Query { a: Int }".to_string())
+            })
+            .await;
+        assert_that!(fetched).is_ok();
+
+        let error = cache(&dir, false)
+            .fetch("graph-fetch", &inputs, ttl, async { offline() })
+            .await
+            .unwrap_err();
+        assert_that!(error
+            .suggestions()
+            .iter()
+            .any(|suggestion| suggestion.to_string().contains("--allow-stale")))
+        .is_true();
+
+        let stale = cache(&dir, true)
+            .fetch("graph-fetch", &inputs, ttl, async { offline() })
+            .await;
+        assert_that!(stale)
+            .is_ok()
+            .is_equal_to("type Query { a: Int }".to_string());
+
+        // other inputs have nothing cached
+        let other = cache(&dir, true)
+            .fetch("graph-fetch", &["default", "other@current"], ttl, async {
+                offline()
+            })
+            .await;
+        assert_that!(other).is_err();
+    }
+
+    #[tokio::test]
+    async fn test_expired_and_unrelated_errors_are_not_served() {
+        let dir = TempDir::new().unwrap();
+        let cache = cache(&dir, true);
+        cache
+            .fetch("subgraph-list", &["a"], Duration::days(1), async {
+                Ok("cached".to_string())
+            })
+            .await
+            .unwrap();
+
+        let expired = cache
+            .fetch(
+                "subgraph-list",
+                &["a"],
+                Duration::zero() - Duration::seconds(1),
+                async { offline() },
+            )
+            .await;
+        assert_that!(expired).is_err();
+
+        let not_found = cache
+            .fetch("subgraph-list", &["a"], Duration::days(1), async {
+                Err::<String, _>(RoverError::new(anyhow!("graph not found")))
+            })
+            .await;
+        assert_that!(not_found).is_err();
+    }
+
+    #[rstest]
+    #[case::server_error("502 Bad Gateway", true)]
+    #[case::client_error("403 Forbidden", false)]
+    fn test_server_errors_mean_studio_is_unavailable(#[case] msg: &str, #[case] expected: bool) {
+        let error = RoverError::new(RoverClientError::ClientError {
+            msg: msg.to_string(),
+        });
+        assert_that!(error.is_studio_unreachable()).is_equal_to(expected);
+    }
+}
@@ -0,0 +1,110 @@
+//! Whether Rover may reach the network and report telemetry, decided once from the global
+//! `--offline` and `--no-telemetry` flags and their env vars, so that every subsystem agrees
+
+use thiserror::Error;
+
+use crate::utils::env::{RoverEnv, RoverEnvKey};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RuntimeMode {
+    offline: bool,
+    no_telemetry: bool,
+}
+
+impl RuntimeMode {
+    /// The mode set by the `--offline` and `--no-telemetry` flags, or by `$APOLLO_OFFLINE` and
+    /// `$APOLLO_TELEMETRY_DISABLED` when they aren't passed
+    pub fn new(offline: bool, no_telemetry: bool, env: &RoverEnv) -> Self {
+        let offline = offline
+            || env
+                .get(RoverEnvKey::Offline)
+                .is_some_and(|value| matches!(value.to_lowercase().as_str(), "true" | "1"));
+        // any value disables telemetry, as it always has
+        let no_telemetry = no_telemetry || env.get(RoverEnvKey::TelemetryDisabled).is_some();
+        Self {
+            offline,
+            no_telemetry,
+        }
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Telemetry is never reported offline
+    pub fn is_telemetry_enabled(&self) -> bool {
+        !self.offline && !self.no_telemetry
+    }
+
+    /// Whether to check for newer versions of Rover in the background
+    pub fn is_update_check_enabled(&self) -> bool {
+        !self.offline
+    }
+
+    /// Fails when offline, for an `action` that can't be done without the network, like
+    /// `fetching a schema from Apollo Studio`
+    pub fn require_network(&self, action: &str) -> Result<(), NetworkDisabled> {
+        if self.offline {
+            Err(NetworkDisabled {
+                action: action.to_string(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// An action that needs the network was attempted while Rover runs offline
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("{action} needs network access, but Rover is running offline.")]
+pub struct NetworkDisabled {
+    pub action: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::RuntimeMode;
+    use crate::utils::env::{RoverEnv, RoverEnvKey};
+
+    #[rstest]
+    #[case::default(false, false, &[], false, true)]
+    #[case::offline_flag(true, false, &[], true, false)]
+    #[case::offline_env(false, false, &[(RoverEnvKey::Offline, "true")], true, false)]
+    #[case::offline_env_false(false, false, &[(RoverEnvKey::Offline, "false")], false, true)]
+    #[case::no_telemetry_flag(false, true, &[], false, false)]
+    #[case::telemetry_disabled_env(false, false, &[(RoverEnvKey::TelemetryDisabled, "1")], false, false)]
+    fn test_runtime_mode(
+        #[case] offline: bool,
+        #[case] no_telemetry: bool,
+        #[case] vars: &[(RoverEnvKey, &str)],
+        #[case] expect_offline: bool,
+        #[case] expect_telemetry: bool,
+    ) {
+        let mut env = RoverEnv::new().unwrap();
+        for (key, value) in vars {
+            env.insert(*key, value);
+        }
+        let mode = RuntimeMode::new(offline, no_telemetry, &env);
+        assert_that!(mode.is_offline()).is_equal_to(expect_offline);
+        assert_that!(mode.is_update_check_enabled()).is_equal_to(!expect_offline);
+        assert_that!(mode.is_telemetry_enabled()).is_equal_to(expect_telemetry);
+        assert_that!(mode.require_network("fetching a schema").is_err())
+            .is_equal_to(expect_offline);
+    }
+
+    #[test]
+    fn test_network_disabled_message() {
+        let mode = RuntimeMode::new(true, false, &RoverEnv::new().unwrap());
+        assert_that!(mode
+            .require_network("Fetching a schema from Apollo Studio")
+            .unwrap_err()
+            .to_string())
+        .is_equal_to(
+            "Fetching a schema from Apollo Studio needs network access, but Rover is running offline."
+                .to_string(),
+        );
+    }
+}
@@ -0,0 +1,357 @@
+//! Logging in through an organization's SSO with the OAuth 2.0 device authorization grant
+//! ([RFC 8628](https://www.rfc-editor.org/rfc/rfc8628)), for organizations that don't allow
+//! long-lived personal API keys. The login exchanges for a short-lived Studio token, which Rover
+//! refreshes as it expires.
+
+use std::{
+    future::Future,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Result};
+use houston::{Config, HoustonProblem, Profile, SsoSession};
+use reqwest::Response;
+use rover_std::Style;
+use serde::Deserialize;
+
+use crate::{utils::client::ClientBuilder, RoverError, RoverErrorSuggestion, RoverResult};
+
+/// How long before a short-lived token expires it's refreshed, so it doesn't expire mid-request
+pub(crate) const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+const CLIENT_ID: &str = "rover";
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// What the SSO service returns when a login starts
+#[derive(Debug, Deserialize)]
+struct DeviceAuthorization {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    verification_uri_complete: Option<String>,
+    expires_in: u64,
+    #[serde(default = "default_interval")]
+    interval: u64,
+}
+
+fn default_interval() -> u64 {
+    5
+}
+
+/// A short-lived token, as returned for both logins and refreshes
+#[derive(Debug, Deserialize)]
+struct Token {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: u64,
+}
+
+impl Token {
+    /// The session to refresh the token with, which keeps using `previous_refresh_token` when the
+    /// SSO service doesn't rotate it
+    fn into_session(
+        self,
+        token_url: &str,
+        previous_refresh_token: Option<&str>,
+    ) -> Result<(String, SsoSession)> {
+        let refresh_token = self
+            .refresh_token
+            .or_else(|| previous_refresh_token.map(str::to_string))
+            .ok_or_else(|| anyhow!("the SSO service didn't return a refresh token"))?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let session = SsoSession {
+            refresh_token,
+            token_url: token_url.to_string(),
+            expires_at: now + self.expires_in,
+        };
+        Ok((self.access_token, session))
+    }
+}
+
+/// An OAuth error response
+#[derive(Debug, Deserialize)]
+struct TokenError {
+    error: String,
+    error_description: Option<String>,
+}
+
+impl std::fmt::Display for TokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.error_description {
+            Some(description) => write!(f, "{} ({})", description, self.error),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
+/// Logs in through the SSO service at `sso_url`, printing the link and code to confirm the login
+/// with, and waits for it to be confirmed. Returns the short-lived token along with the session to
+/// refresh it with. Requests are sent with a client from `client_builder`, so they honour the same
+/// TLS, timeout and DNS settings as requests to Apollo Studio.
+pub(crate) fn log_in(
+    sso_url: &str,
+    client_builder: &ClientBuilder,
+) -> RoverResult<(String, SsoSession)> {
+    let sso_url = sso_url.trim_end_matches('/');
+    off_runtime(device_flow(sso_url, client_builder)).map_err(|e| {
+        let mut err = RoverError::new(anyhow!("Could not log in with SSO: {e}."));
+        err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+            "Run {} again, or authenticate with an API key by running it without {}.",
+            Style::Command.paint("`rover config auth --sso`"),
+            Style::Command.paint("`--sso`")
+        )));
+        err
+    })
+}
+
+/// Exchanges the refresh token of a profile's SSO session for a new short-lived token, and saves
+/// both to the profile.
+pub(crate) fn refresh(
+    profile_name: &str,
+    config: &Config,
+    client_builder: &ClientBuilder,
+    session: &SsoSession,
+) -> Result<String, HoustonProblem> {
+    let failed = |e: anyhow::Error| {
+        HoustonProblem::SsoRefreshFailed(profile_name.to_string(), e.to_string())
+    };
+    let token = off_runtime(async {
+        let response = client_builder
+            .build()?
+            .post(&session.token_url)
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", &session.refresh_token),
+                ("client_id", CLIENT_ID),
+            ])
+            .send()
+            .await?;
+        token_response(response).await?.map_err(|e| anyhow!("{e}"))
+    })
+    .map_err(failed)?;
+    let (token, session) = token
+        .into_session(&session.token_url, Some(&session.refresh_token))
+        .map_err(failed)?;
+    Profile::set_sso_token(profile_name, config, &token, session)?;
+    tracing::debug!(profile_name, "refreshed SSO token");
+    Ok(token)
+}
+
+async fn device_flow(
+    sso_url: &str,
+    client_builder: &ClientBuilder,
+) -> Result<(String, SsoSession)> {
+    let client = client_builder.build()?;
+    let token_url = format!("{sso_url}/token");
+    let authorization: DeviceAuthorization = client
+        .post(format!("{sso_url}/device/code"))
+        .form(&[("client_id", CLIENT_ID)])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let link = authorization
+        .verification_uri_complete
+        .as_deref()
+        .unwrap_or(&authorization.verification_uri);
+    eprintln!(
+        "Go to {} and confirm the code {} to log in.",
+        Style::Link.paint(link),
+        Style::Command.paint(&authorization.user_code)
+    );
+    if console::Term::stderr().is_term() {
+        if let Err(e) = opener::open(link) {
+            tracing::debug!("could not open {link} in a browser: {e}");
+        }
+    }
+    eprintln!("Waiting for the login to be confirmed...");
+
+    let deadline = Instant::now() + Duration::from_secs(authorization.expires_in);
+    let mut interval = Duration::from_secs(authorization.interval);
+    loop {
+        if Instant::now() >= deadline {
+            return Err(anyhow!(
+                "the login wasn't confirmed before its code expired"
+            ));
+        }
+        tokio::time::sleep(interval).await;
+        let response = client
+            .post(&token_url)
+            .form(&[
+                ("grant_type", DEVICE_CODE_GRANT),
+                ("device_code", &authorization.device_code),
+                ("client_id", CLIENT_ID),
+            ])
+            .send()
+            .await?;
+        match token_response(response).await? {
+            Ok(token) => return token.into_session(&token_url, None),
+            Err(e) if e.error == "authorization_pending" => {}
+            Err(e) if e.error == "slow_down" => interval += Duration::from_secs(5),
+            Err(e) => return Err(anyhow!("{e}")),
+        }
+    }
+}
+
+/// Reads a token, or the OAuth error the SSO service responded with instead
+async fn token_response(response: Response) -> Result<std::result::Result<Token, TokenError>> {
+    let status = response.status();
+    let body = response.text().await?;
+    if status.is_success() {
+        return Ok(Ok(serde_json::from_str(&body)?));
+    }
+    match serde_json::from_str::<TokenError>(&body) {
+        Ok(error) if status.is_client_error() => Ok(Err(error)),
+        _ => Err(anyhow!("the SSO service responded with {status}")),
+    }
+}
+
+/// Runs `future` on its own thread and runtime, so SSO requests can be made from synchronous code
+/// whether or not it's already running within the async runtime
+fn off_runtime<T: Send>(future: impl Future<Output = Result<T>> + Send) -> Result<T> {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?
+                    .block_on(future)
+            })
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use houston::{Config, HoustonProblem, Profile, SsoSession};
+    use httpmock::{Method::POST, MockServer};
+    use serde_json::json;
+    use speculoos::prelude::*;
+
+    use super::{log_in, refresh};
+    use crate::utils::client::ClientBuilder;
+
+    fn config(home: &TempDir) -> Config {
+        let home = Utf8PathBuf::try_from(home.path().to_path_buf()).unwrap();
+        Config::new(Some(&home), None).unwrap()
+    }
+
+    #[test]
+    fn test_log_in_exchanges_the_device_code_for_a_token() {
+        let server = MockServer::start();
+        let device_code = server.mock(|when, then| {
+            when.method(POST)
+                .path("/device/code")
+                .body_contains("client_id=rover");
+            then.status(200).json_body(json!({
+                "device_code": "device",
+                "user_code": "ABCD-EFGH",
+                "verification_uri": server.url("/activate"),
+                "expires_in": 600,
+                "interval": 0
+            }));
+        });
+        let token = server.mock(|when, then| {
+            when.method(POST)
+                .path("/token")
+                .body_contains("device_code=device");
+            then.status(200).json_body(json!({
+                "access_token": "short-lived",
+                "refresh_token": "refresh",
+                "expires_in": 3600
+            }));
+        });
+
+        let (access_token, session) = log_in(&server.url("/"), &ClientBuilder::default()).unwrap();
+        device_code.assert();
+        token.assert();
+        assert_that!(access_token).is_equal_to("short-lived".to_string());
+        assert_that!(session.refresh_token).is_equal_to("refresh".to_string());
+        assert_that!(session.token_url).is_equal_to(server.url("/token"));
+    }
+
+    #[test]
+    fn test_log_in_reports_denied_logins() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/device/code");
+            then.status(200).json_body(json!({
+                "device_code": "device",
+                "user_code": "ABCD-EFGH",
+                "verification_uri": server.url("/activate"),
+                "expires_in": 600,
+                "interval": 0
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/token");
+            then.status(400)
+                .json_body(json!({ "error": "access_denied" }));
+        });
+
+        let err = log_in(&server.url(""), &ClientBuilder::default()).unwrap_err();
+        assert_that!(err.to_string()).contains("access_denied");
+    }
+
+    #[test]
+    fn test_refresh_saves_the_new_token() {
+        let home = TempDir::new().unwrap();
+        let config = config(&home);
+        let server = MockServer::start();
+        let refreshed = server.mock(|when, then| {
+            when.method(POST)
+                .path("/token")
+                .body_contains("grant_type=refresh_token")
+                .body_contains("refresh_token=refresh");
+            then.status(200).json_body(json!({
+                "access_token": "new-token",
+                "expires_in": 3600
+            }));
+        });
+        let session = SsoSession {
+            refresh_token: "refresh".to_string(),
+            token_url: server.url("/token"),
+            expires_at: 0,
+        };
+        Profile::set_sso_token("work", &config, "expired", session.clone()).unwrap();
+
+        assert_that!(refresh("work", &config, &ClientBuilder::default(), &session).unwrap())
+            .is_equal_to("new-token".to_string());
+        refreshed.assert();
+        assert_that!(Profile::get_credential("work", &config).unwrap().api_key)
+            .is_equal_to("new-token".to_string());
+        let saved = Profile::get_sso_session("work", &config).unwrap();
+        assert_that!(saved.refresh_token).is_equal_to("refresh".to_string());
+        assert_that!(saved.expires_at).is_greater_than(0);
+    }
+
+    #[test]
+    fn test_refresh_reports_expired_sessions() {
+        let home = TempDir::new().unwrap();
+        let config = config(&home);
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/token");
+            then.status(400).json_body(json!({
+                "error": "invalid_grant",
+                "error_description": "the session has expired"
+            }));
+        });
+        let session = SsoSession {
+            refresh_token: "refresh".to_string(),
+            token_url: server.url("/token"),
+            expires_at: 0,
+        };
+
+        assert!(matches!(
+            refresh("work", &config, &ClientBuilder::default(), &session),
+            Err(HoustonProblem::SsoRefreshFailed(profile_name, reason))
+                if profile_name == "work" && reason.contains("the session has expired")
+        ));
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 use std::env::current_dir;
 use std::path;
 use std::str::FromStr;
+use std::time::Duration;
 
 use anyhow::anyhow;
 use apollo_federation_types::config::{
@@ -9,22 +10,32 @@ use apollo_federation_types::config::{
 };
 use apollo_federation_types::rover::{BuildError, BuildErrors};
 use apollo_parser::{cst, Parser};
-use camino::Utf8PathBuf;
-use futures::future::join_all;
-use rover_client::blocking::{GraphQLClient, StudioClient};
+use camino::{Utf8Path, Utf8PathBuf};
+use futures::future::{join_all, try_join_all};
+use rover_client::blocking::StudioClient;
 use rover_client::operations::subgraph;
+use rover_client::operations::subgraph::fetch;
 use rover_client::operations::subgraph::fetch::SubgraphFetchInput;
 use rover_client::operations::subgraph::fetch_all::{
     SubgraphFetchAllInput, SubgraphFetchAllResponse,
 };
-use rover_client::operations::subgraph::introspect::SubgraphIntrospectInput;
-use rover_client::operations::subgraph::{fetch, introspect};
 use rover_client::shared::GraphRef;
 use rover_client::RoverClientError;
-use rover_std::{Fs, Style};
+use rover_std::{timing, Fs, Style};
 
+use crate::composition::supergraph::config::extended::{ExtendedSupergraphConfig, SchemaUrl};
+use crate::composition::supergraph::config::introspection::SubgraphIntrospectionConfig;
+use crate::composition::supergraph::config::resolver::LoadSupergraphConfigError;
+use crate::composition::supergraph::config::source_map::{
+    SubgraphConfigKey, SupergraphConfigSourceMap,
+};
+use crate::composition::supergraph::config::unresolved::IncludedSupergraphConfigs;
+use crate::composition::supergraph::config::validation::{
+    validate_supergraph_config, SupergraphConfigValidation,
+};
 use crate::options::ProfileOpt;
 use crate::utils::client::StudioClientConfig;
+use crate::utils::effect::introspect::{IntrospectSubgraph, IntrospectionPolicy};
 use crate::utils::expansion::expand;
 use crate::utils::parsers::FileDescriptorType;
 use crate::RoverErrorSuggestion::InvalidSupergraphYamlSubgraphSchemaPath;
@@ -66,6 +77,7 @@ impl RemoteSubgraphs {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn get_supergraph_config(
     graph_ref: &Option<GraphRef>,
     supergraph_config_path: &Option<FileDescriptorType>,
@@ -73,9 +85,24 @@ pub async fn get_supergraph_config(
     client_config: StudioClientConfig,
     profile_opt: &ProfileOpt,
     create_static_config: bool,
+    environment: Option<&str>,
+    introspection_policy: IntrospectionPolicy,
 ) -> Result<Option<SupergraphConfig>, RoverError> {
+    let _timing = timing::time_phase("resolution");
+    let offline = client_config.is_offline();
     // Read in Remote subgraphs
     let remote_subgraphs = match graph_ref {
+        Some(graph_ref) if offline => {
+            let mut err = RoverError::new(anyhow!(
+                "Can't load the subgraphs of {} from GraphOS while offline",
+                graph_ref
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Describe every subgraph in a supergraph config instead, or run without `--offline`."
+                    .to_string(),
+            ));
+            return Err(err);
+        }
         Some(graph_ref) => {
             let studio_client = client_config.get_authenticated_client(profile_opt)?;
             let remote_subgraphs = Some(RemoteSubgraphs::fetch(&studio_client, graph_ref).await?);
@@ -100,6 +127,8 @@ pub async fn get_supergraph_config(
                             .as_ref()
                             .and_then(|it| it.inner().get_federation_version())
                             .is_none(),
+                    environment,
+                    introspection_policy,
                 )
                 .await?,
             )
@@ -108,9 +137,13 @@ pub async fn get_supergraph_config(
             // set up watchers on the subgraph sources. This branch is what `rover dev` uses.
             // So we run the `expand` function only to hydrate the YAML into a series of objects,
             // but we don't need to completely resolve all of those objects.
-            let mut config = file_descriptor
-                .read_file_descriptor("supergraph config", &mut std::io::stdin())
-                .and_then(|contents| expand_supergraph_yaml(&contents))?;
+            let contents =
+                file_descriptor.read_file_descriptor("supergraph config", &mut std::io::stdin())?;
+            let origin_path = file_descriptor.to_path_buf().ok();
+            let (config, introspection_configs) =
+                load_supergraph_yaml(&contents, origin_path, environment)?;
+            let mut config =
+                download_url_schemas(config, &introspection_configs, &client_config).await?;
             // Once we have expanded the supergraph.yaml we need to make some changes to the paths
             // to ensure we maintain correct semantics
             config = match file_descriptor {
@@ -122,6 +155,15 @@ pub async fn get_supergraph_config(
                     correctly_resolve_paths(config, &file_path.parent().unwrap().to_path_buf())?
                 }
             };
+            if offline {
+                if let Some(err) = config
+                    .clone()
+                    .into_iter()
+                    .find_map(|(name, subgraph)| offline_schema_error(&name, &subgraph.schema))
+                {
+                    return Err(err);
+                }
+            }
             Some(config)
         }
     } else {
@@ -142,7 +184,9 @@ fn correctly_resolve_paths(
     supergraph_config: SupergraphConfig,
     root_to_resolve_from: &Utf8PathBuf,
 ) -> Result<SupergraphConfig, RoverError> {
-    supergraph_config
+    // Collecting the subgraphs back into a config would otherwise drop the federation version
+    let federation_version = supergraph_config.get_federation_version();
+    let mut resolved_supergraph_config: SupergraphConfig = supergraph_config
         .into_iter()
         .map(
             |(subgraph_name, subgraph_config)| match subgraph_config.schema {
@@ -178,11 +222,16 @@ fn correctly_resolve_paths(
                 _ => Ok((subgraph_name, subgraph_config)),
             },
         )
-        .collect()
+        .collect::<Result<_, RoverError>>()?;
+    if let Some(federation_version) = federation_version {
+        resolved_supergraph_config.set_federation_version(federation_version);
+    }
+    Ok(resolved_supergraph_config)
 }
 
 #[cfg(test)]
 mod test_get_supergraph_config {
+    use std::collections::BTreeMap;
     use std::fs;
     use std::fs::File;
     use std::io::Write;
@@ -191,7 +240,9 @@ mod test_get_supergraph_config {
     use std::time::Duration;
 
     use anyhow::Result;
-    use apollo_federation_types::config::{FederationVersion, SchemaSource, SupergraphConfig};
+    use apollo_federation_types::config::{
+        FederationVersion, SchemaSource, SubgraphConfig, SupergraphConfig,
+    };
     use camino::Utf8PathBuf;
     use houston::Config;
     use httpmock::MockServer;
@@ -201,12 +252,15 @@ mod test_get_supergraph_config {
     use semver::Version;
     use serde_json::{json, Value};
     use speculoos::assert_that;
-    use speculoos::prelude::OptionAssertions;
+    use speculoos::prelude::{OptionAssertions, ResultAssertions};
     use tempfile::{NamedTempFile, TempDir};
 
     use crate::options::ProfileOpt;
     use crate::utils::client::{ClientBuilder, StudioClientConfig};
+    use crate::utils::effect::introspect::IntrospectionPolicy;
+    use crate::utils::env::RoverEnv;
     use crate::utils::parsers::FileDescriptorType;
+    use crate::utils::runtime_mode::RuntimeMode;
     use crate::utils::supergraph_config::{get_supergraph_config, resolve_federation_version};
 
     #[fixture]
@@ -372,6 +426,8 @@ mod test_get_supergraph_config {
                 studio_client_config,
                 &profile_opt,
                 true,
+                None,
+                IntrospectionPolicy::default(),
             )
             .await
             .expect("Could not construct SupergraphConfig")
@@ -383,6 +439,8 @@ mod test_get_supergraph_config {
                 studio_client_config,
                 &profile_opt,
                 true,
+                None,
+                IntrospectionPolicy::default(),
             )
             .await
             .expect("Could not construct SupergraphConfig")
@@ -508,6 +566,8 @@ mod test_get_supergraph_config {
             studio_client_config,
             &profile_opt,
             false,
+            None,
+            IntrospectionPolicy::default(),
         )
         .await
         .expect("Could not create Supergraph Config")
@@ -525,6 +585,181 @@ mod test_get_supergraph_config {
             }
         }
     }
+
+    #[rstest]
+    #[case::static_config(true)]
+    #[case::dynamic_config(false)]
+    #[tokio::test]
+    async fn test_offline_refuses_introspected_subgraphs(
+        config: Config,
+        profile_opt: ProfileOpt,
+        #[case] create_static_config: bool,
+    ) {
+        let supergraph_config = indoc! {
+            r#"
+            subgraphs:
+              products:
+                schema:
+                  subgraph_url: http://localhost:4001
+            "#
+        };
+        let supergraph_config_dir = TempDir::new().expect("Can't create test folder");
+        let supergraph_config_path = supergraph_config_dir.path().join("supergraph.yaml");
+        fs::write(supergraph_config_path.clone(), supergraph_config)
+            .expect("Could not write supergraph.yaml");
+
+        let studio_client_config = StudioClientConfig::new(
+            None,
+            config,
+            false,
+            ClientBuilder::default(),
+            Some(Duration::from_secs(3)),
+        )
+        .with_runtime_mode(RuntimeMode::new(true, false, &RoverEnv::new().unwrap()));
+
+        let result = get_supergraph_config(
+            &None,
+            &Some(FileDescriptorType::File(
+                Utf8PathBuf::from_path_buf(supergraph_config_path).unwrap(),
+            )),
+            None,
+            studio_client_config,
+            &profile_opt,
+            create_static_config,
+            None,
+            IntrospectionPolicy::default(),
+        )
+        .await;
+
+        assert_that!(result)
+            .is_err()
+            .matches(|err| err.to_string().contains("while offline"));
+    }
+
+    #[rstest]
+    #[case::static_config(true)]
+    #[case::dynamic_config(false)]
+    #[tokio::test]
+    async fn test_merges_included_supergraph_configs(
+        config: Config,
+        profile_opt: ProfileOpt,
+        #[case] create_static_config: bool,
+    ) {
+        let supergraph_config_dir = TempDir::new().expect("Can't create test folder");
+        let shared_dir = supergraph_config_dir.path().join("shared");
+        fs::create_dir(&shared_dir).expect("Could not create shared folder");
+        fs::write(
+            shared_dir.join("base.yaml"),
+            indoc! {r#"
+                federation_version: =2.8.0
+                subgraphs:
+                  products:
+                    routing_url: https://products.example.com
+                    schema:
+                      file: ./products.graphql
+                  reviews:
+                    routing_url: https://reviews.example.com
+                    schema:
+                      file: ./products.graphql
+            "#},
+        )
+        .expect("Could not write base.yaml");
+        fs::write(shared_dir.join("products.graphql"), "type Query { a: Int }")
+            .expect("Could not write products.graphql");
+        let supergraph_config_path = supergraph_config_dir.path().join("supergraph.yaml");
+        fs::write(
+            &supergraph_config_path,
+            indoc! {r#"
+                extends: ./shared/base.yaml
+                subgraphs:
+                  reviews:
+                    routing_url: http://localhost:4002
+                    schema:
+                      sdl: "type Query { b: Int }"
+            "#},
+        )
+        .expect("Could not write supergraph.yaml");
+
+        let studio_client_config = StudioClientConfig::new(
+            None,
+            config,
+            false,
+            ClientBuilder::default(),
+            Some(Duration::from_secs(3)),
+        );
+        let supergraph_config = get_supergraph_config(
+            &None,
+            &Some(FileDescriptorType::File(
+                Utf8PathBuf::from_path_buf(supergraph_config_path).unwrap(),
+            )),
+            None,
+            studio_client_config,
+            &profile_opt,
+            create_static_config,
+            None,
+            IntrospectionPolicy::default(),
+        )
+        .await
+        .expect("Could not create Supergraph Config")
+        .expect("SuperGraph Config was None which was unexpected");
+
+        assert_that!(supergraph_config.get_federation_version())
+            .is_equal_to(Some(FederationVersion::from_str("=2.8.0").unwrap()));
+        let subgraphs: BTreeMap<String, SubgraphConfig> = supergraph_config.into_iter().collect();
+        assert_that!(subgraphs["products"].routing_url)
+            .is_equal_to(Some("https://products.example.com".to_string()));
+        assert_that!(subgraphs["reviews"].routing_url)
+            .is_equal_to(Some("http://localhost:4002".to_string()));
+    }
+
+    #[rstest]
+    #[case::static_config(true)]
+    #[case::dynamic_config(false)]
+    #[tokio::test]
+    async fn test_rejects_unknown_keys(
+        config: Config,
+        profile_opt: ProfileOpt,
+        #[case] create_static_config: bool,
+    ) {
+        let supergraph_config = indoc! {
+            r#"
+            subgraphs:
+              products:
+                routing_ur: http://localhost:4001
+                schema:
+                  sdl: "type Query { a: Int }"
+            "#
+        };
+        let supergraph_config_dir = TempDir::new().expect("Can't create test folder");
+        let supergraph_config_path = supergraph_config_dir.path().join("supergraph.yaml");
+        fs::write(supergraph_config_path.clone(), supergraph_config)
+            .expect("Could not write supergraph.yaml");
+
+        let studio_client_config = StudioClientConfig::new(
+            None,
+            config,
+            false,
+            ClientBuilder::default(),
+            Some(Duration::from_secs(3)),
+        );
+        let result = get_supergraph_config(
+            &None,
+            &Some(FileDescriptorType::File(
+                Utf8PathBuf::from_path_buf(supergraph_config_path).unwrap(),
+            )),
+            None,
+            studio_client_config,
+            &profile_opt,
+            create_static_config,
+            None,
+            IntrospectionPolicy::default(),
+        )
+        .await;
+
+        assert_that!(result)
+            .is_err()
+            .matches(|err| err.to_string().contains("Unknown key `routing_ur`"));
+    }
 }
 
 /// Merge local and remote supergraphs, making sure that the federation version is correct: eg, when
@@ -778,16 +1013,27 @@ pub(crate) async fn resolve_supergraph_yaml(
     client_config: StudioClientConfig,
     profile_opt: &ProfileOpt,
     must_determine_federation_version: bool,
+    environment: Option<&str>,
+    introspection_policy: IntrospectionPolicy,
 ) -> RoverResult<SupergraphConfig> {
+    let offline = client_config.is_offline();
     let err_invalid_graph_ref = || {
         let err = anyhow!("Invalid graph ref.");
         let mut err = RoverError::new(err);
         err.set_suggestion(RoverErrorSuggestion::CheckGraphNameAndAuth);
         err
     };
-    let supergraph_config = unresolved_supergraph_yaml
-        .read_file_descriptor("supergraph config", &mut std::io::stdin())
-        .and_then(|contents| expand_supergraph_yaml(&contents))?;
+    let contents = unresolved_supergraph_yaml
+        .read_file_descriptor("supergraph config", &mut std::io::stdin())?;
+    let origin_path = unresolved_supergraph_yaml.to_path_buf().ok();
+    let (supergraph_config, introspection_configs) =
+        load_supergraph_yaml(&contents, origin_path, environment)?;
+    let supergraph_config =
+        download_url_schemas(supergraph_config, &introspection_configs, &client_config).await?;
+    let source_map = SupergraphConfigSourceMap::new(
+        &contents,
+        unresolved_supergraph_yaml.to_path_buf().ok().cloned(),
+    );
     let maybe_specified_federation_version = supergraph_config.get_federation_version();
     let supergraph_config = supergraph_config
         .into_iter()
@@ -797,6 +1043,12 @@ pub(crate) async fn resolve_supergraph_yaml(
         .iter()
         .map(|(subgraph_name, subgraph_data)| async {
             let cloned_subgraph_name = subgraph_name.to_string();
+            if let Some(err) = offline
+                .then(|| offline_schema_error(subgraph_name, &subgraph_data.schema))
+                .flatten()
+            {
+                return Ok((cloned_subgraph_name, Err(err)));
+            }
             let result = match &subgraph_data.schema {
                 SchemaSource::File { file } => {
                     let relative_schema_path = match unresolved_supergraph_yaml {
@@ -823,45 +1075,34 @@ pub(crate) async fn resolve_supergraph_yaml(
                     subgraph_url,
                     introspection_headers,
                 } => {
-                    let client = client_config
-                        .get_reqwest_client()
-                        .map_err(RoverError::from)?;
-                    let client = GraphQLClient::new(
-                        subgraph_url.as_ref(),
-                        client,
-                        client_config.retry_period,
-                    );
-
                     // given a federated introspection URL, use subgraph introspect to
                     // obtain SDL and add it to subgraph_definition.
-                    introspect::run(
-                        SubgraphIntrospectInput {
-                            headers: introspection_headers.clone().unwrap_or_default(),
-                        },
-                        &client,
-                        false,
-                    )
-                    .await
-                    .map(|introspection_response| {
-                        let schema = introspection_response.result;
-
-                        (
-                            // We don't require a routing_url in config for
-                            // this variant of a schema, if one isn't
-                            // provided, just use the URL they passed for
-                            // introspection. (This does mean there's no way
-                            // when combining `--graph-ref` and a config
-                            // file to say "fetch the schema from
-                            // introspection but use the routing URL from
-                            // the graph" at the moment.)
-                            subgraph_data
-                                .routing_url
-                                .clone()
-                                .or_else(|| Some(subgraph_url.to_string())),
-                            schema,
+                    client_config
+                        .introspect_subgraph(
+                            subgraph_url.clone(),
+                            introspection_headers.clone().unwrap_or_default(),
+                            None,
+                            introspection_policy.clone(),
                         )
-                    })
-                    .map_err(RoverError::from)
+                        .await
+                        .map(|schema| {
+                            (
+                                // We don't require a routing_url in config for
+                                // this variant of a schema, if one isn't
+                                // provided, just use the URL they passed for
+                                // introspection. (This does mean there's no way
+                                // when combining `--graph-ref` and a config
+                                // file to say "fetch the schema from
+                                // introspection but use the routing URL from
+                                // the graph" at the moment.)
+                                subgraph_data
+                                    .routing_url
+                                    .clone()
+                                    .or_else(|| Some(subgraph_url.to_string())),
+                                schema,
+                            )
+                        })
+                        .map_err(RoverError::from)
                 }
                 SchemaSource::Subgraph {
                     graphref: graph_ref,
@@ -976,6 +1217,13 @@ pub(crate) async fn resolve_supergraph_yaml(
                         "{} while resolving the schema for the '{}' subgraph",
                         message, subgraph_name
                     );
+                    let config_key = supergraph_config
+                        .iter()
+                        .find(|(name, _)| name == subgraph_name)
+                        .map(|(_, subgraph_config)| schema_source_key(&subgraph_config.schema));
+                    if let Some(location) = source_map.locate_subgraph(subgraph_name, config_key) {
+                        message = format!("{} (at {})", message, location);
+                    }
                     for suggestion in error.suggestions() {
                         message = format!("{}\n        {}", message, suggestion)
                     }
@@ -1130,7 +1378,7 @@ mod test_resolve_supergraph_yaml {
             subgraphs:
 "#
         };
-        let config = expand_supergraph_yaml(yaml).unwrap();
+        let config = expand_supergraph_yaml(yaml, None, None).unwrap();
         assert_eq!(
             config.get_federation_version(),
             Some(FederationVersion::LatestFedOne)
@@ -1160,7 +1408,9 @@ mod test_resolve_supergraph_yaml {
             &FileDescriptorType::File(config_path),
             client_config,
             &profile_opt,
-            true
+            true,
+            None,
+            IntrospectionPolicy::default()
         )
         .await
         .is_err())
@@ -1200,7 +1450,9 @@ subgraphs:
             &FileDescriptorType::File(config_path),
             client_config,
             &profile_opt,
-            true
+            true,
+            None,
+            IntrospectionPolicy::default()
         )
         .await
         .is_ok())
@@ -1244,6 +1496,8 @@ subgraphs:
             client_config,
             &profile_opt,
             true,
+            None,
+            IntrospectionPolicy::default(),
         )
         .await
         .unwrap()
@@ -1298,6 +1552,8 @@ subgraphs:
             client_config,
             &profile_opt,
             false,
+            None,
+            IntrospectionPolicy::default(),
         )
         .await
         .unwrap()
@@ -1383,6 +1639,8 @@ type _Service {\n  sdl: String\n}"#;
             client_config,
             &profile_opt,
             true,
+            None,
+            IntrospectionPolicy::default(),
         )
         .await;
 
@@ -1456,6 +1714,8 @@ type _Service {\n  sdl: String\n}"#;
             client_config,
             &profile_opt,
             true,
+            None,
+            IntrospectionPolicy::default(),
         )
         .await;
 
@@ -1560,6 +1820,8 @@ type _Service {\n  sdl: String\n}"#;
             studio_client_config,
             &profile_opt,
             true,
+            None,
+            IntrospectionPolicy::default(),
         )
         .await;
 
@@ -1620,6 +1882,8 @@ type _Service {\n  sdl: String\n}"#;
             client_config,
             &profile_opt,
             true,
+            None,
+            IntrospectionPolicy::default(),
         )
         .await;
 
@@ -1678,6 +1942,8 @@ type _Service {\n  sdl: String\n}"#;
             client_config,
             &profile_opt,
             must_determine_federation_version,
+            None,
+            IntrospectionPolicy::default(),
         )
         .await;
 
@@ -1696,9 +1962,140 @@ type _Service {\n  sdl: String\n}"#;
     }
 }
 
-pub fn expand_supergraph_yaml(content: &str) -> RoverResult<SupergraphConfig> {
+/// The key of a subgraph's `schema` entry that resolution errors for that source originate from
+fn schema_source_key(schema: &SchemaSource) -> SubgraphConfigKey {
+    match schema {
+        SchemaSource::File { .. } => SubgraphConfigKey::SchemaFile,
+        SchemaSource::SubgraphIntrospection { .. } => SubgraphConfigKey::SchemaSubgraphUrl,
+        SchemaSource::Subgraph { .. } => SubgraphConfigKey::SchemaGraphRef,
+        SchemaSource::Sdl { .. } => SubgraphConfigKey::Schema,
+    }
+}
+
+/// The error for a subgraph whose schema can only be resolved over the network, when resolving
+/// offline
+fn offline_schema_error(subgraph_name: &str, schema: &SchemaSource) -> Option<RoverError> {
+    let message = match schema {
+        SchemaSource::SubgraphIntrospection { .. } => {
+            format!(
+                "Can't introspect the subgraph `{}` while offline",
+                subgraph_name
+            )
+        }
+        SchemaSource::Subgraph { .. } => format!(
+            "Can't fetch the schema for the subgraph `{}` from GraphOS while offline",
+            subgraph_name
+        ),
+        SchemaSource::File { .. } | SchemaSource::Sdl { .. } => return None,
+    };
+    let mut err = RoverError::new(anyhow!(message));
+    err.set_suggestion(RoverErrorSuggestion::Adhoc(
+        "Point the subgraph's `schema` at a local `file` or `sdl`, or run without `--offline`."
+            .to_string(),
+    ));
+    Some(err)
+}
+
+/// Expands any environment variables in a supergraph config read from `origin_path`, and
+/// down-converts it to the upstream [`SupergraphConfig`], resolving any `glob` schema sources
+/// against the directory the config is in and picking routing URLs for `environment`
+pub fn expand_supergraph_yaml(
+    content: &str,
+    origin_path: Option<&Utf8Path>,
+    environment: Option<&str>,
+) -> RoverResult<SupergraphConfig> {
+    let base_dir = origin_path
+        .and_then(Utf8Path::parent)
+        .filter(|parent| !parent.as_str().is_empty())
+        .unwrap_or_else(|| Utf8Path::new("."));
     serde_yaml::from_str(content)
         .map_err(RoverError::from)
         .and_then(expand)
-        .and_then(|v| serde_yaml::from_value(v).map_err(RoverError::from))
+        .and_then(|v| {
+            serde_yaml::from_value::<ExtendedSupergraphConfig>(v).map_err(RoverError::from)
+        })
+        .and_then(|config| {
+            config
+                .into_supergraph_config(base_dir, environment)
+                .map_err(RoverError::new)
+        })
+}
+
+/// Validates and expands a supergraph config like [`expand_supergraph_yaml`] and merges it on top
+/// of the supergraph configs it `extends` or `include`s, along with the introspection settings of
+/// every subgraph in the result
+fn load_supergraph_yaml(
+    contents: &str,
+    origin_path: Option<&Utf8PathBuf>,
+    environment: Option<&str>,
+) -> RoverResult<(
+    SupergraphConfig,
+    BTreeMap<String, SubgraphIntrospectionConfig>,
+)> {
+    let included = IncludedSupergraphConfigs::load(contents, origin_path, environment)
+        .map_err(RoverError::new)?;
+    let source_map = SupergraphConfigSourceMap::new(contents, origin_path.cloned());
+    validate_supergraph_config(
+        contents,
+        &source_map,
+        included.subgraphs(),
+        SupergraphConfigValidation::default(),
+    )
+    .map_err(|errors| RoverError::new(LoadSupergraphConfigError::Invalid(errors)))?;
+    let supergraph_config =
+        expand_supergraph_yaml(contents, origin_path.map(Utf8PathBuf::as_path), environment)?;
+    let introspection_configs = included.merge_introspection_configs(
+        &supergraph_config,
+        SubgraphIntrospectionConfig::load_all(contents, origin_path, environment)?,
+    );
+    Ok((included.merge(supergraph_config), introspection_configs))
+}
+
+/// Downloads the schemas of subgraphs whose `schema` is a `url` into inline SDL. Until then they
+/// stand in as the URL they're downloaded from, which [`SubgraphIntrospectionConfig::load_all`]
+/// tells apart from subgraphs that are introspected
+async fn download_url_schemas(
+    supergraph_config: SupergraphConfig,
+    introspection_configs: &BTreeMap<String, SubgraphIntrospectionConfig>,
+    client_config: &StudioClientConfig,
+) -> RoverResult<SupergraphConfig> {
+    let federation_version = supergraph_config.get_federation_version();
+    let subgraphs = try_join_all(supergraph_config.into_iter().map(
+        |(name, mut subgraph_config)| {
+            let introspection = introspection_configs
+                .get(&name)
+                .filter(|introspection| introspection.schema_url.is_some());
+            async move {
+                if let (
+                    Some(introspection),
+                    SchemaSource::SubgraphIntrospection {
+                        subgraph_url,
+                        introspection_headers,
+                    },
+                ) = (introspection, &subgraph_config.schema)
+                {
+                    let schema_url = SchemaUrl {
+                        url: subgraph_url.to_string(),
+                        headers: introspection_headers
+                            .clone()
+                            .map(|headers| headers.into_iter().collect()),
+                    };
+                    let sdl = client_config
+                        .download_schema(
+                            schema_url,
+                            introspection.client_identity(),
+                            introspection.timeout.map(Duration::from_secs),
+                        )
+                        .await?;
+                    subgraph_config.schema = SchemaSource::Sdl { sdl };
+                }
+                Ok::<_, RoverError>((name, subgraph_config))
+            }
+        },
+    ))
+    .await?;
+    Ok(SupergraphConfig::new(
+        subgraphs.into_iter().collect(),
+        federation_version,
+    ))
 }
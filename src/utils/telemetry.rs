@@ -81,8 +81,7 @@ impl Report for Rover {
     }
 
     fn is_telemetry_enabled(&self) -> Result<bool, SputnikError> {
-        let value = self.get_env_var(RoverEnvKey::TelemetryDisabled)?;
-        let is_telemetry_disabled = value.is_some();
+        let is_telemetry_disabled = !self.get_runtime_mode()?.is_telemetry_enabled();
         if is_telemetry_disabled {
             tracing::info!("Telemetry has been disabled.");
         } else {
@@ -202,6 +201,15 @@ mod tests {
         assert_eq!(is_telemetry_enabled, expect_enabled);
     }
 
+    #[test]
+    fn it_is_disabled_by_flags() {
+        for flag in ["--no-telemetry", "--offline"] {
+            let args = vec![PKG_NAME, flag, "config", "list"];
+            let rover = Rover::parse_from(args);
+            assert!(!rover.is_telemetry_enabled().unwrap());
+        }
+    }
+
     #[test]
     fn it_is_enabled_by_default() {
         let args = vec![PKG_NAME, "config", "list"];
@@ -0,0 +1,123 @@
+//! Helpers for reading YAML one line at a time, for the places that need to know where something
+//! is in a file rather than only what it parses to: locating keys in a supergraph config,
+//! editing a supergraph config in place, and updating a project's `.apollo/config.yaml` without
+//! losing its comments.
+//!
+//! `serde_yaml` doesn't keep track of where values come from, so these work on the text instead.
+//! They only understand block mappings written one key per line, which is how Rover's config files
+//! are written. A line that isn't a plain or quoted mapping key, like a sequence entry, a flow
+//! collection, or a document marker, is never mistaken for one. Callers handle anything else by
+//! not finding the key, or by falling back to rewriting the whole file.
+
+/// Splits a line with its indentation already removed into a mapping key and the byte offset at
+/// which its value begins. Returns `None` when the line isn't a mapping key
+pub(crate) fn split_key(trimmed: &str) -> Option<(String, usize)> {
+    if trimmed.starts_with('-') {
+        return None;
+    }
+    if let Some(quote) = trimmed.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let end = trimmed[1..].find(quote)? + 1;
+        let after_key = &trimmed[end + 1..];
+        let colon = after_key.find(':')?;
+        if !after_key[..colon].trim().is_empty() {
+            return None;
+        }
+        return Some((trimmed[1..end].to_string(), end + 1 + colon + 1));
+    }
+    let bytes = trimmed.as_bytes();
+    let colon = (0..bytes.len()).find(|idx| {
+        bytes[*idx] == b':'
+            && bytes
+                .get(idx + 1)
+                .map_or(true, |next| next.is_ascii_whitespace())
+    })?;
+    let key = trimmed[..colon].trim_end();
+    if key.is_empty() || key.contains(['{', '}', '[', ']', ',', '#']) {
+        return None;
+    }
+    Some((key.to_string(), colon + 1))
+}
+
+/// The indentation, key, and offset of the value after the key (relative to the indentation) of
+/// a line holding a mapping key
+pub(crate) fn key_at(line: &str) -> Option<(usize, String, usize)> {
+    if is_trivia(line) {
+        return None;
+    }
+    split_key(line.trim_start()).map(|(key, value_offset)| (indent_of(line), key, value_offset))
+}
+
+pub(crate) fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Whether a line is blank or only holds a comment
+pub(crate) fn is_trivia(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+/// Everything before a ` #` comment, trimmed
+pub(crate) fn strip_comment(value: &str) -> &str {
+    match comment_start(value) {
+        Some(start) => value[..start].trim(),
+        None => value.trim(),
+    }
+}
+
+/// A comment at the end of a value, along with the whitespace before it
+pub(crate) fn trailing_comment(value: &str) -> Option<&str> {
+    comment_start(value).map(|start| {
+        let before = value[..start].trim_end().len();
+        &value[before..]
+    })
+}
+
+fn comment_start(value: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (idx, c) in value.char_indices() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' && previous.is_whitespace() => return Some(idx),
+            None => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use speculoos::prelude::*;
+
+    use super::{key_at, strip_comment, trailing_comment};
+
+    #[rstest]
+    #[case::plain("routing_url: http://localhost:4001", Some((0, "routing_url", 12)))]
+    #[case::indented("  products:", Some((2, "products", 9)))]
+    #[case::quoted("  \"reviews\" : x", Some((2, "reviews", 11)))]
+    #[case::url_value("a: http://b:1", Some((0, "a", 2)))]
+    #[case::comment("# a: b", None)]
+    #[case::sequence_entry("- a: b", None)]
+    #[case::flow_mapping("{ a: b }", None)]
+    #[case::document_marker("---", None)]
+    #[case::scalar("http://localhost", None)]
+    fn test_key_at(#[case] line: &str, #[case] expected: Option<(usize, &str, usize)>) {
+        let expected = expected.map(|(indent, key, offset)| (indent, key.to_string(), offset));
+        assert_that!(key_at(line)).is_equal_to(expected);
+    }
+
+    #[rstest]
+    #[case::none(" http://a", "http://a", None)]
+    #[case::comment(" http://a  # local", "http://a", Some("  # local"))]
+    #[case::hash_in_quotes(" \"a # b\" # c", "\"a # b\"", Some(" # c"))]
+    #[case::hash_in_value(" a#b", "a#b", None)]
+    fn test_comments(#[case] value: &str, #[case] stripped: &str, #[case] comment: Option<&str>) {
+        assert_that!(strip_comment(value)).is_equal_to(stripped);
+        assert_that!(trailing_comment(value)).is_equal_to(comment);
+    }
+}
@@ -0,0 +1,18 @@
+use assert_cmd::Command;
+use predicates::prelude::predicate;
+use rstest::rstest;
+
+#[rstest]
+#[case::config(vec!["config", "list"])]
+#[case::fetch(vec!["subgraph", "fetch", "my-graph@current", "--name", "products"])]
+#[case::compose(vec!["supergraph", "compose", "--config", "supergraph.yaml"])]
+fn it_rejects_junit_for_commands_without_checks(#[case] args: Vec<&str>) {
+    let mut cmd = Command::cargo_bin("rover").unwrap();
+    cmd.args(args)
+        .args(["--format", "junit"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "`--format junit` is only supported by `graph check`, `subgraph check`, and `supergraph check`",
+        ));
+}
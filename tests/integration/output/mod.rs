@@ -1 +1,2 @@
+mod junit;
 mod to_file;
@@ -9,3 +9,18 @@ fn it_has_a_graph_fetch_command() {
         .assert()
         .success();
 }
+
+#[test]
+fn it_rejects_an_unknown_schema_kind() {
+    let mut cmd = Command::cargo_bin("rover").unwrap();
+    let assert = cmd
+        .arg("graph")
+        .arg("fetch")
+        .arg("my-graph@current")
+        .arg("--schema-kind=core")
+        .assert()
+        .failure();
+    assert.stderr(predicates::str::starts_with(
+        "error: invalid value 'core' for '--schema-kind <SCHEMA_KIND>'",
+    ));
+}